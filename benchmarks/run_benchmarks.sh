@@ -10,12 +10,17 @@ trap 'rm -rf "$TMP_DIR"' EXIT
 
 # Parse arguments
 JSON_OUTPUT=""
+GC_BACKEND="marksweep"
 while [[ $# -gt 0 ]]; do
     case "$1" in
         --json-output)
             JSON_OUTPUT="$2"
             shift 2
             ;;
+        --gc)
+            GC_BACKEND="$2"
+            shift 2
+            ;;
         *)
             echo "Unknown option: $1" >&2
             exit 1
@@ -26,7 +31,7 @@ done
 # JSON results accumulator (comma-separated entries, joined into array at the end)
 JSON_ENTRIES=""
 
-echo "=== Pluto Runtime Benchmarks ==="
+echo "=== Pluto Runtime Benchmarks (gc: $GC_BACKEND) ==="
 echo ""
 
 # Build compiler in release mode
@@ -86,7 +91,7 @@ for bench in "${BENCHMARKS[@]}"; do
     cp "$src" "$bench_dir/"
 
     # Detect stdlib usage and build compile command
-    COMPILE_FLAGS=()
+    COMPILE_FLAGS=(--gc "$GC_BACKEND")
     if grep -q "^import std\." "$src" 2>/dev/null; then
         COMPILE_FLAGS+=(--stdlib "$STDLIB_DIR")
     fi
@@ -446,6 +446,13 @@ pub fn compile_error_to_diagnostic(err: &pluto::diagnostics::CompileError, sourc
             span: None,
             path: None,
         },
+        pluto::diagnostics::CompileError::Internal(msg) => DiagnosticInfo {
+            severity: "error".to_string(),
+            kind: "internal".to_string(),
+            message: format!("internal compiler error: {msg}"),
+            span: None,
+            path: None,
+        },
     }
 }
 
@@ -585,6 +592,8 @@ fn pretty_print_function(func: &Function) -> String {
         classes: vec![],
         traits: vec![],
         enums: vec![],
+        consts: vec![],
+        globals: vec![],
         app: None,
         stages: vec![],
         system: None,
@@ -592,6 +601,7 @@ fn pretty_print_function(func: &Function) -> String {
         test_info: vec![],
         tests: None,
         fallible_extern_fns: vec![],
+        comments: vec![],
     };
     pluto::pretty::pretty_print(&program, false)
 }
@@ -604,6 +614,8 @@ fn pretty_print_class(cls: &ClassDecl) -> String {
         classes: vec![pluto::span::Spanned::new(cls.clone(), pluto::span::Span::dummy())],
         traits: vec![],
         enums: vec![],
+        consts: vec![],
+        globals: vec![],
         app: None,
         stages: vec![],
         system: None,
@@ -611,6 +623,7 @@ fn pretty_print_class(cls: &ClassDecl) -> String {
         test_info: vec![],
         tests: None,
         fallible_extern_fns: vec![],
+        comments: vec![],
     };
     pluto::pretty::pretty_print(&program, false)
 }
@@ -623,6 +636,8 @@ fn pretty_print_enum(en: &EnumDecl) -> String {
         classes: vec![],
         traits: vec![],
         enums: vec![pluto::span::Spanned::new(en.clone(), pluto::span::Span::dummy())],
+        consts: vec![],
+        globals: vec![],
         app: None,
         stages: vec![],
         system: None,
@@ -630,6 +645,7 @@ fn pretty_print_enum(en: &EnumDecl) -> String {
         test_info: vec![],
         tests: None,
         fallible_extern_fns: vec![],
+        comments: vec![],
     };
     pluto::pretty::pretty_print(&program, false)
 }
@@ -642,6 +658,8 @@ fn pretty_print_trait(tr: &TraitDecl) -> String {
         classes: vec![],
         traits: vec![pluto::span::Spanned::new(tr.clone(), pluto::span::Span::dummy())],
         enums: vec![],
+        consts: vec![],
+        globals: vec![],
         app: None,
         stages: vec![],
         system: None,
@@ -649,6 +667,7 @@ fn pretty_print_trait(tr: &TraitDecl) -> String {
         test_info: vec![],
         tests: None,
         fallible_extern_fns: vec![],
+        comments: vec![],
     };
     pluto::pretty::pretty_print(&program, false)
 }
@@ -661,6 +680,8 @@ fn pretty_print_error_decl(err: &ErrorDecl) -> String {
         classes: vec![],
         traits: vec![],
         enums: vec![],
+        consts: vec![],
+        globals: vec![],
         app: None,
         stages: vec![],
         system: None,
@@ -668,6 +689,7 @@ fn pretty_print_error_decl(err: &ErrorDecl) -> String {
         test_info: vec![],
         tests: None,
         fallible_extern_fns: vec![],
+        comments: vec![],
     };
     pluto::pretty::pretty_print(&program, false)
 }
@@ -680,6 +702,8 @@ fn pretty_print_app(app: &AppDecl) -> String {
         classes: vec![],
         traits: vec![],
         enums: vec![],
+        consts: vec![],
+        globals: vec![],
         app: Some(pluto::span::Spanned::new(app.clone(), pluto::span::Span::dummy())),
         stages: vec![],
         system: None,
@@ -687,6 +711,7 @@ fn pretty_print_app(app: &AppDecl) -> String {
         test_info: vec![],
         tests: None,
         fallible_extern_fns: vec![],
+        comments: vec![],
     };
     pluto::pretty::pretty_print(&program, false)
 }
@@ -923,3 +948,92 @@ pub struct ReloadResult {
     pub reloaded: bool,
     pub message: String,
 }
+
+// --- Refactoring tool outputs ---
+
+#[derive(Serialize)]
+pub struct DanglingRefInfo {
+    pub kind: String, // "call", "struct_lit", "enum_usage", "raise", "match_arm", "type_ref"
+    pub name: String,
+    pub span: SpanInfo,
+}
+
+#[derive(Serialize)]
+pub struct RenameResult {
+    pub uuid: String,
+    pub old_name: String,
+    pub new_name: String,
+    /// Reference sites rewritten by the rename, located in the original source.
+    pub updated_sites: Vec<SpanInfo>,
+    pub dangling: Vec<DanglingRefInfo>,
+    /// The full updated source. Nothing is written to disk.
+    pub source: String,
+    pub diff: String,
+}
+
+#[derive(Serialize)]
+pub struct ExtractFunctionResult {
+    pub name: String,
+    pub params: Vec<ParamInfo>,
+    pub return_type: Option<String>,
+    /// Whether the call needs `!` because the extracted code can raise.
+    pub fallible: bool,
+    /// The statement that replaced the selection.
+    pub call: String,
+    /// The full updated source. Nothing is written to disk.
+    pub source: String,
+    pub diff: String,
+}
+
+// --- Analysis tool outputs ---
+
+#[derive(Serialize)]
+pub struct ModuleCallGraphResult {
+    pub functions: Vec<ModuleCallGraphEntry>,
+    /// Local functions with callees before callers; members of a cycle are adjacent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<Vec<CallEdge>>,
+    /// Groups of mutually recursive functions, including directly recursive ones.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cycles: Option<Vec<Vec<CallEdge>>>,
+}
+
+#[derive(Serialize)]
+pub struct ModuleCallGraphEntry {
+    pub uuid: String,
+    pub name: String,
+    pub calls: Vec<CallEdge>,
+    pub method_calls: Vec<CallEdge>,
+    pub constructs: Vec<CallEdge>,
+    /// Calls with no declaration behind them: builtins, builtin methods
+    /// (as `.name`), and closures held in variables.
+    pub builtins: Vec<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct CallEdge {
+    /// Absent for a method call whose receiver type can't be told from its name alone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    pub name: String,
+}
+
+pub fn dangling_ref_kind_to_string(kind: pluto_sdk::editor::DanglingRefKind) -> &'static str {
+    use pluto_sdk::editor::DanglingRefKind;
+    match kind {
+        DanglingRefKind::Call => "call",
+        DanglingRefKind::StructLit => "struct_lit",
+        DanglingRefKind::EnumUsage => "enum_usage",
+        DanglingRefKind::Raise => "raise",
+        DanglingRefKind::MatchArm => "match_arm",
+        DanglingRefKind::TypeRef => "type_ref",
+    }
+}
+
+/// Unified diff between two versions of a file, labelled `a/<label>` and `b/<label>`.
+pub fn unified_diff(old: &str, new: &str, label: &str) -> String {
+    similar::TextDiff::from_lines(old, new)
+        .unified_diff()
+        .header(&format!("a/{label}"), &format!("b/{label}"))
+        .to_string()
+}
@@ -1151,6 +1151,79 @@ impl PlutoMcp {
         let text = pluto::pretty::pretty_print(module.program(), include_uuid_hints);
         Ok(CallToolResult::success(vec![Content::text(text)]))
     }
+
+    // --- Tool 24: rename_declaration ---
+    #[tool(description = "Rename a top-level function, class, enum, trait, or error and update every reference to it. Takes a source/PLTO file path or module bytes, the target by UUID or name, and the new name. Returns the updated source, the rewritten reference sites, any references left dangling, and a unified diff. Nothing is written to disk.")]
+    async fn rename_declaration(
+        &self,
+        Parameters(input): Parameters<RenameDeclarationInput>,
+    ) -> Result<CallToolResult, McpError> {
+        let (module, label) = self.edit_target(input.path.as_deref(), input.bytes).await?;
+
+        let id = if let Some(uuid_str) = &input.uuid {
+            uuid_str
+                .parse::<Uuid>()
+                .map_err(|_| mcp_err(format!("Invalid UUID: {uuid_str}")))?
+        } else if let Some(name) = &input.name {
+            let matches = module.find(name);
+            match matches.len() {
+                0 => return Err(mcp_err(format!("No declaration found with name '{name}'"))),
+                1 => matches[0].id(),
+                _ => {
+                    let candidates: Vec<serialize::DisambiguationEntry> = matches
+                        .iter()
+                        .map(|d| serialize::DisambiguationEntry {
+                            uuid: d.id().to_string(),
+                            name: d.name().to_string(),
+                            kind: serialize::decl_kind_to_string(d.kind()).to_string(),
+                        })
+                        .collect();
+                    let json = serde_json::to_string_pretty(&serde_json::json!({
+                        "ambiguous": true,
+                        "message": format!("Name '{name}' matches {} declarations. Specify a UUID to disambiguate.", matches.len()),
+                        "candidates": candidates,
+                    }))
+                    .map_err(|e| mcp_internal(format!("JSON serialization failed: {e}")))?;
+                    return Ok(CallToolResult::success(vec![Content::text(json)]));
+                }
+            }
+        } else {
+            return Err(mcp_err("Either 'uuid' or 'name' must be provided"));
+        };
+
+        let result = crate::tools::rename_declaration(module, id, &input.new_name, &label).map_err(mcp_err)?;
+        let json = serde_json::to_string_pretty(&result)
+            .map_err(|e| mcp_internal(format!("JSON serialization failed: {e}")))?;
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    // --- Tool 25: extract_function ---
+    #[tool(description = "Extract the statements in a byte range of a function body into a new function and replace them with a call. Parameters are inferred from variables the range uses but that are defined outside it; a value the range produces for later code becomes the return value. The selection must cover whole statements of one block. Returns the new signature, the call, the updated source, and a unified diff. Nothing is written to disk.")]
+    async fn extract_function(
+        &self,
+        Parameters(input): Parameters<ExtractFunctionInput>,
+    ) -> Result<CallToolResult, McpError> {
+        let (module, label) = self.edit_target(input.path.as_deref(), input.bytes).await?;
+        let result = crate::tools::extract_function(&module, input.start, input.end, &input.name, &label)
+            .map_err(mcp_err)?;
+        let json = serde_json::to_string_pretty(&result)
+            .map_err(|e| mcp_internal(format!("JSON serialization failed: {e}")))?;
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    // --- Tool 26: module_call_graph ---
+    #[tool(description = "Whole-module call graph of a loaded module. For every function, class method, and app method, lists the functions it calls, the methods it calls, and the classes it constructs, by UUID and name. Calls with no declaration behind them (builtins, closures in variables) are grouped under builtins. Set include_order for a callees-first ordering and the cycles (mutual recursion) among the functions.")]
+    async fn module_call_graph(
+        &self,
+        Parameters(input): Parameters<ModuleCallGraphInput>,
+    ) -> Result<CallToolResult, McpError> {
+        let modules = self.modules.read().await;
+        let metadata = self.find_module(&modules, &input.path)?;
+        let result = crate::tools::module_call_graph(&metadata.module, input.include_order.unwrap_or(false));
+        let json = serde_json::to_string_pretty(&result)
+            .map_err(|e| mcp_internal(format!("JSON serialization failed: {e}")))?;
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
 }
 
 /// Recursively discover .pluto and .pt files in a directory, skipping hidden dirs and .git.
@@ -1320,6 +1393,28 @@ fn has_cycle_dfs<'a>(
 
 // --- Helper methods ---
 impl PlutoMcp {
+    /// Load the module a refactoring tool edits, from a file or from bytes.
+    /// Returns it with the label its diff is headed with.
+    async fn edit_target(&self, path: Option<&str>, bytes: Option<Vec<u8>>) -> Result<(Module, String), McpError> {
+        let (bytes, label) = match (path, bytes) {
+            (Some(path), None) => {
+                let canonical = canon(path);
+                ensure_within_project(&canonical, &*self.project_root.read().await)?;
+                let bytes = std::fs::read(&canonical)
+                    .map_err(|e| mcp_err(format!("Cannot read file: {e}")))?;
+                let label = Path::new(&canonical)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or(canonical);
+                (bytes, label)
+            }
+            (None, Some(bytes)) => (bytes, "module".to_string()),
+            _ => return Err(mcp_err("Exactly one of 'path' or 'bytes' must be provided")),
+        };
+        let module = editable_module(&bytes).map_err(mcp_err)?;
+        Ok((module, label))
+    }
+
     fn find_module<'a>(
         &self,
         modules: &'a HashMap<String, ModuleMetadata>,
@@ -1505,7 +1600,7 @@ impl ServerHandler for PlutoMcp {
                 website_url: None,
             },
             instructions: Some(
-                "Pluto language MCP server (read-only). Load a .pluto or .pt source file with load_module, or scan a project directory with load_project. Then query declarations, types, error sets, and cross-references. Use check to type-check, compile to build, run to execute, and test to run tests. Use docs to get language reference documentation and stdlib_docs to explore available stdlib modules and functions.".to_string()
+                "Pluto language MCP server (read-only). Load a .pluto or .pt source file with load_module, or scan a project directory with load_project. Then query declarations, types, error sets, and cross-references, or get a whole-module call graph with module_call_graph. Use check to type-check, compile to build, run to execute, and test to run tests. Use docs to get language reference documentation and stdlib_docs to explore available stdlib modules and functions. Use rename_declaration and extract_function to preview refactorings as updated source plus a diff; nothing is written to disk.".to_string()
             ),
         }
     }
@@ -1,5 +1,16 @@
 use rmcp::schemars;
 use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+use uuid::Uuid;
+
+use pluto::lexer::token::Token;
+use pluto::parser::ast::*;
+use pluto::span::{Span, Spanned};
+use pluto::visit::{walk_block, walk_expr, walk_stmt, Visitor};
+use pluto_sdk::Module;
+
+use crate::serialize;
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct LoadModuleInput {
@@ -182,3 +193,1142 @@ pub struct PrettyPrintInput {
     #[schemars(description = "Include UUID hints in output (default: false)")]
     pub include_uuid_hints: Option<bool>,
 }
+
+// --- Refactoring tool inputs ---
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RenameDeclarationInput {
+    #[schemars(description = "Path to a .pluto/.pt source file or PLTO binary file (omit when passing bytes)")]
+    pub path: Option<String>,
+    #[schemars(description = "Module contents as an array of bytes, either PLTO binary or source text (alternative to path)")]
+    pub bytes: Option<Vec<u8>>,
+    #[schemars(description = "UUID of the declaration to rename")]
+    pub uuid: Option<String>,
+    #[schemars(description = "Current name of the declaration to rename (may be ambiguous)")]
+    pub name: Option<String>,
+    #[schemars(description = "New name for the declaration")]
+    pub new_name: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ExtractFunctionInput {
+    #[schemars(description = "Path to a .pluto/.pt source file or PLTO binary file (omit when passing bytes)")]
+    pub path: Option<String>,
+    #[schemars(description = "Module contents as an array of bytes, either PLTO binary or source text (alternative to path)")]
+    pub bytes: Option<Vec<u8>>,
+    #[schemars(description = "Start byte offset of the selection; it must cover whole statements of one block")]
+    pub start: usize,
+    #[schemars(description = "End byte offset of the selection (exclusive)")]
+    pub end: usize,
+    #[schemars(description = "Name of the new function")]
+    pub name: String,
+}
+
+// --- Analysis tool inputs ---
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ModuleCallGraphInput {
+    #[schemars(description = "Path of the loaded module")]
+    pub path: String,
+    #[schemars(description = "Also return a callees-first ordering of the functions and the cycles among them (default: false)")]
+    pub include_order: Option<bool>,
+}
+
+// --- Refactoring ---
+
+/// Build an edit-friendly module from PLTO or source bytes. A PLTO module is
+/// edited through the source it embeds, so both produce source text back.
+pub fn editable_module(bytes: &[u8]) -> Result<Module, String> {
+    let source = if bytes.starts_with(b"PLTO") {
+        Module::from_bytes(bytes)
+            .map_err(|e| format!("Failed to load binary: {e}"))?
+            .source()
+            .to_string()
+    } else {
+        String::from_utf8(bytes.to_vec()).map_err(|_| "Source is not valid UTF-8".to_string())?
+    };
+    Module::from_source(&source).map_err(|e| format!("Failed to parse source: {e}"))
+}
+
+/// Rename declaration `id` and return the edit for review. `label` names the
+/// module in the diff header.
+pub fn rename_declaration(
+    module: Module,
+    id: Uuid,
+    new_name: &str,
+    label: &str,
+) -> Result<serialize::RenameResult, String> {
+    if !is_identifier(new_name) {
+        return Err(format!("'{new_name}' is not a valid identifier"));
+    }
+    if let Some(existing) = module.find(new_name).iter().find(|d| d.id() != id) {
+        return Err(format!(
+            "A {} named '{new_name}' already exists",
+            serialize::decl_kind_to_string(existing.kind())
+        ));
+    }
+
+    let old_source = module.source().to_string();
+    let mut editor = module.edit();
+    let renamed = editor.rename(id, new_name).map_err(|e| e.to_string())?;
+    let module = editor.commit();
+
+    Ok(serialize::RenameResult {
+        uuid: id.to_string(),
+        old_name: renamed.old_name,
+        new_name: new_name.to_string(),
+        updated_sites: renamed
+            .updated
+            .iter()
+            .map(|span| serialize::span_to_info_with_source(*span, &old_source))
+            .collect(),
+        dangling: renamed
+            .dangling
+            .iter()
+            .map(|d| serialize::DanglingRefInfo {
+                kind: serialize::dangling_ref_kind_to_string(d.kind).to_string(),
+                name: d.name.clone(),
+                span: serialize::span_to_info_with_source(d.span, &old_source),
+            })
+            .collect(),
+        diff: serialize::unified_diff(&old_source, module.source(), label),
+        source: module.source().to_string(),
+    })
+}
+
+/// Move the statements selected by `start..end` into a new function `name`
+/// and replace them with a call. Variables the statements read from the
+/// enclosing function become parameters; a value they produce for the code
+/// after them becomes the return value. The new function is placed right
+/// after the declaration the selection came from.
+pub fn extract_function(
+    module: &Module,
+    start: usize,
+    end: usize,
+    name: &str,
+    label: &str,
+) -> Result<serialize::ExtractFunctionResult, String> {
+    if !is_identifier(name) {
+        return Err(format!("'{name}' is not a valid identifier"));
+    }
+    if !module.find(name).is_empty() {
+        return Err(format!("A declaration named '{name}' already exists"));
+    }
+    let source = module.source();
+    if start >= end || end > source.len() || !source.is_char_boundary(start) || !source.is_char_boundary(end) {
+        return Err(format!("Byte range [{start}..{end}) is not a valid selection"));
+    }
+
+    let program = module.program();
+    let enclosing = enclosing_function(program, start, end)
+        .ok_or_else(|| "Selection is not inside a function body".to_string())?;
+    let stmts = selected_run(&enclosing.func.body, start, end)?
+        .ok_or_else(|| "Selection contains no statements".to_string())?;
+    let sel = Span::new(stmts[0].span.start, stmts[stmts.len() - 1].span.end);
+
+    let mut scan = SelectionScan::default();
+    for stmt in &stmts {
+        scan.visit_stmt(stmt);
+    }
+    if let Some(err) = scan.error {
+        return Err(err);
+    }
+
+    let mut bindings = BindingCollector { program, blocks: Vec::new(), bindings: Vec::new() };
+    for p in &enclosing.func.params {
+        bindings.bind(&p.name.node, Some(p.ty.node.clone()), enclosing.func.body.span, enclosing.func.body.span.start, p.is_mut);
+    }
+    bindings.visit_block(&enclosing.func.body);
+    let bindings = bindings.bindings;
+    let mut uses = UseCollector::default();
+    uses.visit_block(&enclosing.func.body);
+
+    // Bindings from outside the selection that it reads or writes
+    let inside = |pos: usize| sel.start <= pos && pos < sel.end;
+    let mut params: Vec<usize> = Vec::new();
+    let mut assigned = HashSet::new();
+    for u in uses.uses.iter().filter(|u| inside(u.pos)) {
+        let Some(b) = resolve(&bindings, &u.name, u.pos) else { continue };
+        if bindings[b].from < sel.start {
+            if !params.contains(&b) {
+                params.push(b);
+            }
+            if u.assign {
+                assigned.insert(b);
+            }
+        }
+    }
+
+    // Bindings the code outside the selection needs back from it
+    let mut outputs: Vec<usize> = Vec::new();
+    for u in uses.uses.iter().filter(|u| !inside(u.pos)) {
+        if let Some(b) = resolve(&bindings, &u.name, u.pos) {
+            let declared_inside = sel.start < bindings[b].from && bindings[b].from <= sel.end;
+            if ((declared_inside && u.pos >= sel.end) || assigned.contains(&b)) && !outputs.contains(&b) {
+                outputs.push(b);
+            }
+        }
+    }
+    if outputs.len() > 1 {
+        let names: Vec<_> = outputs.iter().map(|&b| bindings[b].name.as_str()).collect();
+        return Err(format!(
+            "Selection produces {} values used after it ({}); extract a smaller range",
+            outputs.len(),
+            names.join(", ")
+        ));
+    }
+    if params.iter().any(|&b| bindings[b].name == "self") {
+        return Err("Selection uses 'self'; only code that does not touch 'self' can be extracted".to_string());
+    }
+
+    let type_of = |b: usize| {
+        bindings[b].ty.clone().ok_or_else(|| {
+            format!("Cannot infer the type of '{}'; add a type annotation to its declaration", bindings[b].name)
+        })
+    };
+    let mut param_infos = Vec::new();
+    for &b in &params {
+        param_infos.push((bindings[b].name.clone(), type_of(b)?));
+    }
+    let output = outputs.first().map(|&b| type_of(b).map(|ty| (b, ty))).transpose()?;
+    let generic = param_infos.iter().map(|(_, ty)| ty).chain(output.as_ref().map(|(_, ty)| ty))
+        .find_map(|ty| enclosing.type_params.iter().find(|tp| mentions_type(ty, tp)));
+    if let Some(tp) = generic {
+        return Err(format!("Selection depends on type parameter '{tp}' of the enclosing declaration"));
+    }
+
+    // New function: the selected text, re-indented one level, plus the return
+    let line_start = source[..sel.start].rfind('\n').map_or(0, |i| i + 1);
+    let indent = &source[line_start..sel.start];
+    let mut body = String::new();
+    for (i, line) in source[sel.start..sel.end].split('\n').enumerate() {
+        let line = if i == 0 { line } else { line.strip_prefix(indent).unwrap_or(line) };
+        if !line.trim().is_empty() {
+            body.push_str("    ");
+            body.push_str(line);
+        }
+        body.push('\n');
+    }
+    let return_type = output.as_ref().map(|(_, ty)| serialize::type_expr_to_string(ty));
+    if let Some((b, _)) = &output {
+        body.push_str(&format!("    return {}\n", bindings[*b].name));
+    }
+    let param_list: Vec<String> = param_infos
+        .iter()
+        .map(|(n, ty)| format!("{n}: {}", serialize::type_expr_to_string(ty)))
+        .collect();
+    let signature_ret = return_type.as_ref().map(|t| format!(" {t}")).unwrap_or_default();
+    let function = format!("fn {name}({}){signature_ret} {{\n{body}}}", param_list.join(", "));
+
+    let args: Vec<&str> = params.iter().map(|&b| bindings[b].name.as_str()).collect();
+    let mut call = format!("{name}({})", args.join(", "));
+    if scan.fallible {
+        call.push('!');
+    }
+    if let Some((b, _)) = &output {
+        let binding = &bindings[*b];
+        call = if assigned.contains(b) {
+            format!("{} = {call}", binding.name)
+        } else if binding.is_mut {
+            format!("let mut {} = {call}", binding.name)
+        } else {
+            format!("let {} = {call}", binding.name)
+        };
+    }
+
+    let decl_end = enclosing.decl_span.end;
+    let new_source = format!(
+        "{}{call}{}\n\n{function}{}",
+        &source[..sel.start],
+        &source[sel.end..decl_end],
+        &source[decl_end..]
+    );
+    Module::from_source(&new_source).map_err(|e| format!("Extraction produced source that does not parse: {e}"))?;
+
+    Ok(serialize::ExtractFunctionResult {
+        name: name.to_string(),
+        params: param_infos
+            .iter()
+            .map(|(n, ty)| serialize::ParamInfo {
+                name: n.clone(),
+                type_str: serialize::type_expr_to_string(ty),
+                is_mut: false,
+            })
+            .collect(),
+        return_type,
+        fallible: scan.fallible,
+        call,
+        diff: serialize::unified_diff(source, &new_source, label),
+        source: new_source,
+    })
+}
+
+/// The function whose body holds a selection, with what extraction needs from
+/// the declaration around it.
+struct Enclosing<'a> {
+    func: &'a Function,
+    /// Span of the top-level declaration the new function goes after.
+    decl_span: Span,
+    /// Type parameters in scope: the function's and its class's.
+    type_params: Vec<&'a str>,
+}
+
+fn enclosing_function(program: &Program, start: usize, end: usize) -> Option<Enclosing<'_>> {
+    let holds = |f: &Spanned<Function>| f.node.body.span.start < start && end < f.node.body.span.end;
+    fn type_params(f: &Function) -> Vec<&str> {
+        f.type_params.iter().map(|t| t.node.as_str()).collect()
+    }
+
+    if let Some(f) = program.functions.iter().find(|f| holds(f)) {
+        return Some(Enclosing { func: &f.node, decl_span: f.span, type_params: type_params(&f.node) });
+    }
+    for c in &program.classes {
+        if let Some(m) = c.node.methods.iter().find(|m| holds(m)) {
+            let mut tps = type_params(&m.node);
+            tps.extend(c.node.type_params.iter().map(|t| t.node.as_str()));
+            return Some(Enclosing { func: &m.node, decl_span: c.span, type_params: tps });
+        }
+    }
+    let app = program.app.as_ref()?;
+    let m = app.node.methods.iter().find(|m| holds(m))?;
+    Some(Enclosing { func: &m.node, decl_span: app.span, type_params: type_params(&m.node) })
+}
+
+/// The statements of the innermost block that the selection covers. Errors
+/// when the selection covers part of a statement.
+fn selected_run(block: &Spanned<Block>, start: usize, end: usize) -> Result<Option<Vec<Spanned<Stmt>>>, String> {
+    let stmts = &block.node.stmts;
+    let overlapping: Vec<usize> = (0..stmts.len())
+        .filter(|&i| stmts[i].span.start < end && start < stmts[i].span.end)
+        .collect();
+    let (Some(&first), Some(&last)) = (overlapping.first(), overlapping.last()) else {
+        return Ok(None);
+    };
+    let covered = |i: usize| start <= stmts[i].span.start && stmts[i].span.end <= end;
+
+    if first == last && !covered(first) {
+        // Inside a single statement: look for a nested block holding the selection
+        let mut nested = NestedBlocks::default();
+        walk_stmt(&mut nested, &stmts[first]);
+        return match nested.blocks.iter().find(|b| b.span.start <= start && end <= b.span.end) {
+            Some(inner) => selected_run(inner, start, end),
+            None => Err("Selection must cover whole statements".to_string()),
+        };
+    }
+    if !covered(first) || !covered(last) {
+        return Err("Selection crosses a statement or block boundary; select whole statements of one block".to_string());
+    }
+    Ok(Some(stmts[first..=last].to_vec()))
+}
+
+/// Blocks directly nested in a statement, not those inside them.
+#[derive(Default)]
+struct NestedBlocks {
+    blocks: Vec<Spanned<Block>>,
+}
+
+impl Visitor for NestedBlocks {
+    fn visit_block(&mut self, block: &Spanned<Block>) {
+        self.blocks.push(block.clone());
+    }
+}
+
+/// Control flow in the selection that a call cannot reproduce, and whether it
+/// can raise out of the enclosing function.
+#[derive(Default)]
+struct SelectionScan {
+    loop_depth: usize,
+    try_depth: usize,
+    closure_depth: usize,
+    fallible: bool,
+    error: Option<String>,
+}
+
+impl SelectionScan {
+    fn fail(&mut self, msg: &str) {
+        self.error.get_or_insert_with(|| msg.to_string());
+    }
+}
+
+impl Visitor for SelectionScan {
+    fn visit_stmt(&mut self, stmt: &Spanned<Stmt>) {
+        let escapes = self.closure_depth == 0;
+        match &stmt.node {
+            Stmt::Return(_) if escapes => self.fail("Selection contains a return statement"),
+            Stmt::Break | Stmt::Continue if escapes && self.loop_depth == 0 => {
+                self.fail("Selection contains break or continue for a loop outside it")
+            }
+            Stmt::Yield { .. } if escapes => self.fail("Selection contains a yield"),
+            Stmt::Raise { .. } if escapes && self.try_depth == 0 => self.fallible = true,
+            Stmt::While { .. } | Stmt::For { .. } => {
+                self.loop_depth += 1;
+                walk_stmt(self, stmt);
+                self.loop_depth -= 1;
+                return;
+            }
+            Stmt::Try { body, handler, finally, .. } => {
+                self.try_depth += 1;
+                self.visit_block(body);
+                self.try_depth -= 1;
+                self.visit_block(handler);
+                if let Some(finally) = finally {
+                    self.visit_block(finally);
+                }
+                return;
+            }
+            _ => {}
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+        match &expr.node {
+            Expr::Propagate { .. } | Expr::PropagateMap { .. } if self.closure_depth == 0 && self.try_depth == 0 => {
+                self.fallible = true;
+            }
+            Expr::Closure { .. } => {
+                // A closure body is its own function: its returns and loops stay inside it
+                let loop_depth = std::mem::take(&mut self.loop_depth);
+                self.closure_depth += 1;
+                walk_expr(self, expr);
+                self.closure_depth -= 1;
+                self.loop_depth = loop_depth;
+                return;
+            }
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
+
+/// A local variable and where it is visible.
+struct Binding {
+    name: String,
+    ty: Option<TypeExpr>,
+    /// The block (or arm) the binding lives in.
+    scope: Span,
+    /// Offset from which the binding is visible inside `scope`.
+    from: usize,
+    is_mut: bool,
+}
+
+/// The binding `name` refers to at `pos`: the latest one visible there.
+fn resolve(bindings: &[Binding], name: &str, pos: usize) -> Option<usize> {
+    (0..bindings.len())
+        .filter(|&i| {
+            let b = &bindings[i];
+            b.name == name && b.scope.start <= pos && pos < b.scope.end && b.from <= pos
+        })
+        .max_by_key(|&i| bindings[i].from)
+}
+
+/// Records every local a function body binds, with its type when the
+/// declaration or initializer shows it.
+struct BindingCollector<'p> {
+    program: &'p Program,
+    blocks: Vec<Span>,
+    bindings: Vec<Binding>,
+}
+
+impl BindingCollector<'_> {
+    fn bind(&mut self, name: &str, ty: Option<TypeExpr>, scope: Span, from: usize, is_mut: bool) {
+        self.bindings.push(Binding { name: name.to_string(), ty, scope, from, is_mut });
+    }
+
+    fn current_block(&self) -> Span {
+        self.blocks.last().copied().unwrap_or_else(Span::dummy)
+    }
+
+    fn infer(&self, expr: &Spanned<Expr>) -> Option<TypeExpr> {
+        let named = |n: &str| Some(TypeExpr::Named(n.to_string()));
+        let with_args = |name: &str, type_args: &[Spanned<TypeExpr>]| {
+            if type_args.is_empty() {
+                TypeExpr::Named(name.to_string())
+            } else {
+                TypeExpr::Generic { name: name.to_string(), type_args: type_args.to_vec() }
+            }
+        };
+        match &expr.node {
+            Expr::IntLit(_) => named("int"),
+            Expr::FloatLit(_) => named("float"),
+            Expr::BoolLit(_) => named("bool"),
+            Expr::StringLit(_) | Expr::StringInterp { .. } => named("string"),
+            Expr::Ident(name) => resolve(&self.bindings, name, expr.span.start).and_then(|b| self.bindings[b].ty.clone()),
+            Expr::StructLit { name, type_args, .. } => Some(with_args(&name.node, type_args)),
+            Expr::EnumUnit { enum_name, type_args, .. } | Expr::EnumData { enum_name, type_args, .. } => {
+                Some(with_args(&enum_name.node, type_args))
+            }
+            Expr::Call { name, type_args, .. } if type_args.is_empty() => self
+                .program
+                .functions
+                .iter()
+                .find(|f| f.node.name.node == name.node && f.node.type_params.is_empty())
+                .and_then(|f| f.node.return_type.as_ref().map(|t| t.node.clone())),
+            Expr::Cast { target_type, .. } => Some(target_type.node.clone()),
+            Expr::ArrayLit { elements } => elements
+                .first()
+                .and_then(|e| self.infer(e))
+                .map(|t| TypeExpr::Array(Box::new(Spanned::dummy(t)))),
+            Expr::MapLit { key_type, value_type, .. } => {
+                Some(TypeExpr::Generic { name: "Map".to_string(), type_args: vec![key_type.clone(), value_type.clone()] })
+            }
+            Expr::SetLit { elem_type, .. } => {
+                Some(TypeExpr::Generic { name: "Set".to_string(), type_args: vec![elem_type.clone()] })
+            }
+            Expr::BinOp { op, lhs, rhs } => match op {
+                BinOp::Eq | BinOp::Neq | BinOp::Lt | BinOp::Gt | BinOp::LtEq | BinOp::GtEq | BinOp::And | BinOp::Or => {
+                    named("bool")
+                }
+                _ => self.infer(lhs).or_else(|| self.infer(rhs)),
+            },
+            Expr::UnaryOp { op: UnaryOp::Not, .. } => named("bool"),
+            Expr::UnaryOp { operand, .. } => self.infer(operand),
+            Expr::Propagate { expr: inner } | Expr::Catch { expr: inner, .. } => self.infer(inner),
+            Expr::Index { object, .. } => match self.infer(object)? {
+                TypeExpr::Array(elem) => Some(elem.node),
+                TypeExpr::Named(n) if n == "string" => named("string"),
+                _ => None,
+            },
+            Expr::FieldAccess { object, field } => {
+                let TypeExpr::Named(class) = self.infer(object)? else { return None };
+                self.field_type(&class, &field.node)
+            }
+            _ => None,
+        }
+    }
+
+    fn field_type(&self, class: &str, field: &str) -> Option<TypeExpr> {
+        let class = self.program.classes.iter().find(|c| c.node.name.node == class)?;
+        class.node.fields.iter().find(|f| f.name.node == field).map(|f| f.ty.node.clone())
+    }
+}
+
+impl Visitor for BindingCollector<'_> {
+    fn visit_block(&mut self, block: &Spanned<Block>) {
+        self.blocks.push(block.span);
+        walk_block(self, block);
+        self.blocks.pop();
+    }
+
+    fn visit_stmt(&mut self, stmt: &Spanned<Stmt>) {
+        walk_stmt(self, stmt);
+        let block = self.current_block();
+        match &stmt.node {
+            Stmt::Let { name, ty, value, is_mut } => {
+                let ty = ty.as_ref().map(|t| t.node.clone()).or_else(|| self.infer(value));
+                self.bind(&name.node, ty, block, stmt.span.end, *is_mut);
+            }
+            Stmt::LetStruct { ty, fields, is_mut, .. } => {
+                for (field, local) in fields {
+                    let field_ty = match &ty.node {
+                        TypeExpr::Named(class) => self.field_type(class, &field.node),
+                        _ => None,
+                    };
+                    let name = local.as_ref().unwrap_or(field);
+                    self.bind(&name.node, field_ty, block, stmt.span.end, *is_mut);
+                }
+            }
+            Stmt::LetChan { sender, receiver, elem_type, .. } => {
+                let chan = |name: &str| TypeExpr::Generic { name: name.to_string(), type_args: vec![elem_type.clone()] };
+                self.bind(&sender.node, Some(chan("Sender")), block, stmt.span.end, false);
+                self.bind(&receiver.node, Some(chan("Receiver")), block, stmt.span.end, false);
+            }
+            Stmt::For { var, iterable, body } => {
+                let ty = match &iterable.node {
+                    Expr::Range { .. } => Some(TypeExpr::Named("int".to_string())),
+                    _ => match self.infer(iterable) {
+                        Some(TypeExpr::Array(elem)) => Some(elem.node),
+                        _ => None,
+                    },
+                };
+                self.bind(&var.node, ty, body.span, body.span.start, false);
+            }
+            Stmt::IfLet { binding, value, then_block, .. } => {
+                let ty = match self.infer(value) {
+                    Some(TypeExpr::Nullable(inner)) => Some(inner.node),
+                    _ => None,
+                };
+                self.bind(&binding.node, ty, then_block.span, then_block.span.start, false);
+            }
+            Stmt::Match { arms, .. } => {
+                for arm in arms {
+                    for (field, local) in &arm.bindings {
+                        let name = local.as_ref().unwrap_or(field);
+                        self.bind(&name.node, None, arm.body.span, arm.body.span.start, false);
+                    }
+                }
+            }
+            Stmt::Select { arms, .. } => {
+                for arm in arms {
+                    if let SelectOp::Recv { binding, .. } = &arm.op {
+                        self.bind(&binding.node, None, arm.body.span, arm.body.span.start, false);
+                    }
+                }
+            }
+            Stmt::Scope { bindings, body, .. } => {
+                for b in bindings {
+                    self.bind(&b.name.node, Some(b.ty.node.clone()), body.span, body.span.start, false);
+                }
+            }
+            Stmt::Try { var, handler, .. } => {
+                self.bind(&var.node, None, handler.span, handler.span.start, false);
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+        match &expr.node {
+            Expr::Closure { params, body, .. } => {
+                for p in params {
+                    self.bind(&p.name.node, Some(p.ty.node.clone()), body.span, body.span.start, p.is_mut);
+                }
+            }
+            Expr::Catch { handlers, .. } => {
+                for handler in handlers {
+                    match handler {
+                        CatchHandler::Wildcard { var, body } => self.bind(&var.node, None, body.span, body.span.start, false),
+                        CatchHandler::Typed { var, error_type, body } => {
+                            let ty = Some(TypeExpr::Named(error_type.node.clone()));
+                            self.bind(&var.node, ty, body.span, body.span.start, false);
+                        }
+                        CatchHandler::Shorthand(_) => {}
+                    }
+                }
+            }
+            Expr::Match { arms, .. } => {
+                for arm in arms {
+                    for (field, local) in &arm.bindings {
+                        let name = local.as_ref().unwrap_or(field);
+                        self.bind(&name.node, None, arm.value.span, arm.value.span.start, false);
+                    }
+                }
+            }
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
+
+/// A read or write of a name in a function body.
+struct Use {
+    name: String,
+    pos: usize,
+    assign: bool,
+}
+
+#[derive(Default)]
+struct UseCollector {
+    uses: Vec<Use>,
+}
+
+impl UseCollector {
+    fn record(&mut self, name: &str, pos: usize, assign: bool) {
+        self.uses.push(Use { name: name.to_string(), pos, assign });
+    }
+}
+
+impl Visitor for UseCollector {
+    fn visit_stmt(&mut self, stmt: &Spanned<Stmt>) {
+        if let Stmt::Assign { target, .. } = &stmt.node {
+            self.record(&target.node, target.span.start, true);
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+        match &expr.node {
+            Expr::Ident(name) => self.record(name, expr.span.start, false),
+            // A call by name may call a closure held in a local
+            Expr::Call { name, .. } => self.record(&name.node, name.span.start, false),
+            Expr::QualifiedAccess { segments } => {
+                if let Some(first) = segments.first() {
+                    self.record(&first.node, first.span.start, false);
+                }
+            }
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
+
+// --- Module call graph ---
+
+/// Adjacency lists for every function, class method, and app method defined in
+/// the module, built from the call targets xref resolved. Calls inside closures
+/// count as calls of the function that creates the closure. Generic functions
+/// and classes appear as their monomorphized instances (`id$$int`).
+pub fn module_call_graph(module: &Module, include_order: bool) -> serialize::ModuleCallGraphResult {
+    let program = module.program();
+
+    let mut nodes: Vec<(String, Option<&str>, &Function)> = Vec::new();
+    for f in &program.functions {
+        let name = &f.node.name.node;
+        if !f.span.is_synthetic() && !name.starts_with("__closure_") && !name.contains('.') {
+            nodes.push((name.clone(), None, &f.node));
+        }
+    }
+    let owners = program.classes.iter()
+        .filter(|c| !c.node.name.node.contains('.'))
+        .map(|c| (&c.node.name.node, &c.node.methods))
+        .chain(program.app.iter().map(|a| (&a.node.name.node, &a.node.methods)));
+    for (owner, methods) in owners {
+        for m in methods {
+            nodes.push((format!("{owner}.{}", m.node.name.node), Some(owner.as_str()), &m.node));
+        }
+    }
+
+    let mut names: HashMap<Uuid, String> = HashMap::new();
+    for f in &program.functions {
+        names.insert(f.node.id, f.node.name.node.clone());
+    }
+    for c in &program.classes {
+        names.insert(c.node.id, c.node.name.node.clone());
+    }
+    let mut methods: HashMap<&str, Vec<(&str, Uuid)>> = HashMap::new();
+    for (name, owner, func) in &nodes {
+        names.insert(func.id, name.clone());
+        if let Some(owner) = owner {
+            methods.entry(func.name.node.as_str()).or_default().push((owner, func.id));
+        }
+    }
+    let closures: HashMap<&str, &Function> = program.functions.iter()
+        .filter(|f| f.node.name.node.starts_with("__closure_"))
+        .map(|f| (f.node.name.node.as_str(), &f.node))
+        .collect();
+
+    let mut functions = Vec::new();
+    for (name, owner, func) in &nodes {
+        let mut edges = CallEdges {
+            names: &names,
+            methods: &methods,
+            closures: &closures,
+            owner: *owner,
+            entered: HashSet::new(),
+            calls: Vec::new(),
+            method_calls: Vec::new(),
+            constructs: Vec::new(),
+            builtins: Vec::new(),
+        };
+        walk_block(&mut edges, &func.body);
+        functions.push(serialize::ModuleCallGraphEntry {
+            uuid: func.id.to_string(),
+            name: name.clone(),
+            calls: edges.calls,
+            method_calls: edges.method_calls,
+            constructs: edges.constructs,
+            builtins: edges.builtins,
+        });
+    }
+
+    let (order, cycles) = if include_order {
+        let (order, cycles) = callees_first(&functions);
+        (Some(order), Some(cycles))
+    } else {
+        (None, None)
+    };
+    serialize::ModuleCallGraphResult { functions, order, cycles }
+}
+
+struct CallEdges<'a> {
+    names: &'a HashMap<Uuid, String>,
+    methods: &'a HashMap<&'a str, Vec<(&'a str, Uuid)>>,
+    closures: &'a HashMap<&'a str, &'a Function>,
+    owner: Option<&'a str>,
+    entered: HashSet<String>,
+    calls: Vec<serialize::CallEdge>,
+    method_calls: Vec<serialize::CallEdge>,
+    constructs: Vec<serialize::CallEdge>,
+    builtins: Vec<String>,
+}
+
+impl CallEdges<'_> {
+    fn edge(&self, id: Uuid, fallback: &str) -> serialize::CallEdge {
+        serialize::CallEdge {
+            uuid: Some(id.to_string()),
+            name: self.names.get(&id).cloned().unwrap_or_else(|| fallback.to_string()),
+        }
+    }
+
+    fn builtin(&mut self, name: String) {
+        if !self.builtins.contains(&name) {
+            self.builtins.push(name);
+        }
+    }
+
+    /// Resolve a method call by name: a method of the receiver's own class when
+    /// called on `self`, otherwise the only method in the module with that name.
+    fn method(&self, object: &Expr, method: &str) -> Option<serialize::CallEdge> {
+        let candidates = self.methods.get(method)?;
+        let on_self = matches!(object, Expr::Ident(n) if n == "self");
+        let target = match (on_self, self.owner) {
+            (true, Some(owner)) => candidates.iter().find(|(o, _)| *o == owner),
+            _ if candidates.len() == 1 => candidates.first(),
+            _ => None,
+        };
+        Some(match target {
+            Some((_, id)) => self.edge(*id, method),
+            None => serialize::CallEdge { uuid: None, name: method.to_string() },
+        })
+    }
+}
+
+fn push_edge(edges: &mut Vec<serialize::CallEdge>, edge: serialize::CallEdge) {
+    if !edges.iter().any(|e| e.uuid == edge.uuid && e.name == edge.name) {
+        edges.push(edge);
+    }
+}
+
+impl Visitor for CallEdges<'_> {
+    fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+        match &expr.node {
+            Expr::Call { name, target_id: Some(id), .. } => {
+                let edge = self.edge(*id, &name.node);
+                push_edge(&mut self.calls, edge);
+            }
+            Expr::Call { name, target_id: None, .. } => self.builtin(name.node.clone()),
+            Expr::MethodCall { object, method, .. } => match self.method(&object.node, &method.node) {
+                Some(edge) => push_edge(&mut self.method_calls, edge),
+                None => self.builtin(format!(".{}", method.node)),
+            },
+            Expr::StructLit { name, target_id: Some(id), .. } => {
+                let edge = self.edge(*id, &name.node);
+                push_edge(&mut self.constructs, edge);
+            }
+            Expr::ClosureCreate { fn_name, .. } if self.entered.insert(fn_name.clone()) => {
+                if let Some(closure) = self.closures.get(fn_name.as_str()) {
+                    walk_block(self, &closure.body);
+                }
+            }
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
+
+/// Order the graph's functions callees-first with Tarjan's algorithm, which
+/// emits strongly connected components in reverse topological order. Returns
+/// the order and the components that form cycles.
+fn callees_first(
+    functions: &[serialize::ModuleCallGraphEntry],
+) -> (Vec<serialize::CallEdge>, Vec<Vec<serialize::CallEdge>>) {
+    let index_of: HashMap<&str, usize> = functions.iter().enumerate().map(|(i, f)| (f.uuid.as_str(), i)).collect();
+    let successors: Vec<Vec<usize>> = functions
+        .iter()
+        .map(|f| {
+            f.calls.iter().chain(&f.method_calls)
+                .filter_map(|e| index_of.get(e.uuid.as_deref()?).copied())
+                .collect()
+        })
+        .collect();
+
+    struct Tarjan<'a> {
+        successors: &'a [Vec<usize>],
+        index: Vec<Option<usize>>,
+        lowlink: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        next: usize,
+        components: Vec<Vec<usize>>,
+    }
+
+    impl Tarjan<'_> {
+        fn connect(&mut self, v: usize) {
+            self.index[v] = Some(self.next);
+            self.lowlink[v] = self.next;
+            self.next += 1;
+            self.stack.push(v);
+            self.on_stack[v] = true;
+            for &w in &self.successors[v] {
+                match self.index[w] {
+                    None => {
+                        self.connect(w);
+                        self.lowlink[v] = self.lowlink[v].min(self.lowlink[w]);
+                    }
+                    Some(iw) if self.on_stack[w] => self.lowlink[v] = self.lowlink[v].min(iw),
+                    Some(_) => {}
+                }
+            }
+            if Some(self.lowlink[v]) == self.index[v] {
+                let mut component = Vec::new();
+                while let Some(w) = self.stack.pop() {
+                    self.on_stack[w] = false;
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                component.reverse();
+                self.components.push(component);
+            }
+        }
+    }
+
+    let n = functions.len();
+    let mut tarjan = Tarjan {
+        successors: &successors,
+        index: vec![None; n],
+        lowlink: vec![0; n],
+        on_stack: vec![false; n],
+        stack: Vec::new(),
+        next: 0,
+        components: Vec::new(),
+    };
+    for v in 0..n {
+        if tarjan.index[v].is_none() {
+            tarjan.connect(v);
+        }
+    }
+
+    let edge = |i: usize| serialize::CallEdge { uuid: Some(functions[i].uuid.clone()), name: functions[i].name.clone() };
+    let order = tarjan.components.iter().flatten().map(|&i| edge(i)).collect();
+    let cycles = tarjan
+        .components
+        .iter()
+        .filter(|c| c.len() > 1 || successors[c[0]].contains(&c[0]))
+        .map(|c| c.iter().map(|&i| edge(i)).collect())
+        .collect();
+    (order, cycles)
+}
+
+/// Whether `ty` names the type parameter `param` anywhere.
+fn mentions_type(ty: &TypeExpr, param: &str) -> bool {
+    match ty {
+        TypeExpr::Named(n) => n == param,
+        TypeExpr::Qualified { .. } => false,
+        TypeExpr::Array(inner) | TypeExpr::Nullable(inner) | TypeExpr::Stream(inner) => mentions_type(&inner.node, param),
+        TypeExpr::Generic { name, type_args } => name == param || type_args.iter().any(|a| mentions_type(&a.node, param)),
+        TypeExpr::Fn { params, return_type } => {
+            params.iter().any(|p| mentions_type(&p.node, param)) || mentions_type(&return_type.node, param)
+        }
+    }
+}
+
+/// Whether `name` lexes as a single identifier (so not a keyword).
+fn is_identifier(name: &str) -> bool {
+    matches!(pluto::lexer::lex(name).as_deref(), Ok([tok]) if tok.node == Token::Ident && tok.span.end == name.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "fn greet(n: int) int {\n    return n\n}\n\nfn main() {\n    let x = greet(1)\n    print(greet(x))\n}\n";
+
+    fn greet_id(module: &Module) -> Uuid {
+        module.find("greet")[0].id()
+    }
+
+    #[test]
+    fn rename_returns_source_sites_and_diff() {
+        let module = editable_module(SOURCE.as_bytes()).unwrap();
+        let id = greet_id(&module);
+        let result = rename_declaration(module, id, "welcome", "main.pt").unwrap();
+
+        assert_eq!(result.old_name, "greet");
+        assert!(result.source.contains("fn welcome(n: int) int"));
+        assert!(result.source.contains("print(welcome(x))"));
+        assert!(!result.source.contains("greet"));
+        assert!(result.dangling.is_empty());
+
+        let lines: Vec<_> = result.updated_sites.iter().map(|s| s.start_line.unwrap()).collect();
+        assert_eq!(lines, vec![6, 7]);
+
+        assert!(result.diff.starts_with("--- a/main.pt\n+++ b/main.pt\n"), "{}", result.diff);
+        assert!(result.diff.contains("-    print(greet(x))\n+    let x = welcome(1)\n"), "{}", result.diff);
+    }
+
+    #[test]
+    fn rename_rejects_bad_names() {
+        let module = editable_module(SOURCE.as_bytes()).unwrap();
+        let id = greet_id(&module);
+        assert!(rename_declaration(module, id, "while", "main.pt").err().unwrap().contains("not a valid identifier"));
+
+        let module = editable_module(SOURCE.as_bytes()).unwrap();
+        let id = greet_id(&module);
+        assert!(rename_declaration(module, id, "main", "main.pt").err().unwrap().contains("already exists"));
+    }
+
+    #[test]
+    fn editable_module_reads_plto_bytes() {
+        let program = pluto::parse_for_editing(SOURCE).unwrap();
+        let bytes = pluto::binary::serialize_program(&program, SOURCE, &Default::default()).unwrap();
+        let module = editable_module(&bytes).unwrap();
+        assert_eq!(module.source(), SOURCE);
+        assert_eq!(module.find("greet").len(), 1);
+    }
+
+    /// Extract the first occurrence of `selected` in `source`.
+    fn extract(source: &str, selected: &str) -> Result<serialize::ExtractFunctionResult, String> {
+        let start = source.find(selected).expect("selection not in source");
+        let module = editable_module(source.as_bytes()).unwrap();
+        extract_function(&module, start, start + selected.len(), "helper", "main.pt")
+    }
+
+    /// Type-check extracted source, the way the compiler would see it.
+    fn assert_checks(source: &str) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.pt");
+        std::fs::write(&path, source).unwrap();
+        if let Err(e) = pluto::analyze_file_standalone(&path, None) {
+            panic!("extracted source does not check: {e}\n{source}");
+        }
+    }
+
+    #[test]
+    fn extract_infers_params_and_return() {
+        let source = "fn main() {\n    let base = 10\n    let scale: int = 3\n    let total = base * scale\n    let doubled = total * 2\n    print(doubled)\n}\n";
+        let result = extract(source, "let total = base * scale\n    let doubled = total * 2").unwrap();
+
+        let params: Vec<_> = result.params.iter().map(|p| format!("{}: {}", p.name, p.type_str)).collect();
+        assert_eq!(params, vec!["base: int", "scale: int"]);
+        assert_eq!(result.return_type.as_deref(), Some("int"));
+        assert_eq!(result.call, "let doubled = helper(base, scale)");
+        assert_eq!(
+            result.source,
+            "fn main() {\n    let base = 10\n    let scale: int = 3\n    let doubled = helper(base, scale)\n    print(doubled)\n}\n\n\
+             fn helper(base: int, scale: int) int {\n    let total = base * scale\n    let doubled = total * 2\n    return doubled\n}\n"
+        );
+        assert!(result.diff.contains("+fn helper(base: int, scale: int) int {\n"), "{}", result.diff);
+        assert_checks(&result.source);
+    }
+
+    #[test]
+    fn extract_returns_reassigned_outer_variable() {
+        let source = "fn main() {\n    let mut total = 0\n    for i in 0..5 {\n        total = total + i\n    }\n    print(total)\n}\n";
+        let result = extract(source, "for i in 0..5 {\n        total = total + i\n    }").unwrap();
+        assert_eq!(result.call, "total = helper(total)");
+        assert!(result.source.contains("fn helper(total: int) int {\n    for i in 0..5 {\n        total = total + i\n    }\n    return total\n}"), "{}", result.source);
+        assert_checks(&result.source);
+    }
+
+    #[test]
+    fn extract_from_nested_block_and_propagates_errors() {
+        let source = "error TooBig {\n    n: int\n}\n\nfn check(n: int) {\n    if n > 0 {\n        let limit = 3\n        if n > limit {\n            raise TooBig { n: n }\n        }\n    }\n}\n\nfn main() {\n    check(1) catch err { print(\"too big\") }\n}\n";
+        let result = extract(source, "if n > limit {\n            raise TooBig { n: n }\n        }").unwrap();
+        assert!(result.fallible);
+        assert_eq!(result.call, "helper(n, limit)!");
+        assert!(result.source.contains("        let limit = 3\n        helper(n, limit)!\n    }\n}\n\nfn helper(n: int, limit: int) {\n"), "{}", result.source);
+        assert_checks(&result.source);
+    }
+
+    #[test]
+    fn extract_rejects_incoherent_selections() {
+        let source = "fn main() {\n    let a = 1\n    if a > 0 {\n        print(a)\n    }\n    let b = a + 1\n    print(b)\n}\n";
+        let err = extract(source, "print(a)\n    }\n    let b").err().unwrap();
+        assert!(err.contains("crosses a statement or block boundary"), "{err}");
+        let err = extract(source, "a + 1").err().unwrap();
+        assert!(err.contains("whole statements"), "{err}");
+
+        let source = "fn f(x: int) int {\n    let y = x + 1\n    return y\n}\n";
+        let err = extract(source, "let y = x + 1\n    return y").err().unwrap();
+        assert!(err.contains("return statement"), "{err}");
+    }
+
+    #[test]
+    fn extract_rejects_two_outputs_and_unknown_types() {
+        let source = "fn main() {\n    let a = 1\n    let b = 2\n    print(a + b)\n}\n";
+        let err = extract(source, "let a = 1\n    let b = 2").err().unwrap();
+        assert!(err.contains("2 values used after it (a, b)"), "{err}");
+
+        let source = "class Box {\n    v: int\n\n    fn get(self) int {\n        return self.v\n    }\n}\n\nfn main() {\n    let b = Box { v: 1 }\n    let v = b.get()\n    print(v)\n}\n";
+        let err = extract(source, "print(v)").err().unwrap();
+        assert!(err.contains("Cannot infer the type of 'v'"), "{err}");
+    }
+
+    const GRAPH_SOURCE: &str = "class Counter {
+    n: int
+
+    fn bump(mut self) {
+        self.n = self.n + 1
+        self.report()
+    }
+
+    fn report(self) {
+        print(self.n)
+    }
+}
+
+fn is_even(n: int) bool {
+    if n == 0 {
+        return true
+    }
+    return is_odd(n - 1)
+}
+
+fn is_odd(n: int) bool {
+    if n == 0 {
+        return false
+    }
+    return is_even(n - 1)
+}
+
+fn fact(n: int) int {
+    if n <= 1 {
+        return 1
+    }
+    return n * fact(n - 1)
+}
+
+fn main() {
+    let mut c = Counter { n: 0 }
+    c.bump()
+    let f = (x: int) => fact(x)
+    print(f(3))
+    print(is_even(4))
+    let xs = [1, 2]
+    print(xs.len())
+}
+";
+
+    fn call_graph(include_order: bool) -> serialize::ModuleCallGraphResult {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.pt");
+        std::fs::write(&path, GRAPH_SOURCE).unwrap();
+        let module = Module::from_source_file_standalone(&path, None).unwrap();
+        module_call_graph(&module, include_order)
+    }
+
+    fn names(edges: &[serialize::CallEdge]) -> Vec<&str> {
+        edges.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn call_graph_lists_edges_by_kind() {
+        let graph = call_graph(false);
+        let nodes: Vec<_> = graph.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(nodes, vec!["is_even", "is_odd", "fact", "main", "Counter.bump", "Counter.report"]);
+        assert!(graph.order.is_none() && graph.cycles.is_none());
+
+        let main = &graph.functions[3];
+        assert_eq!(names(&main.calls), vec!["fact", "is_even"]);
+        assert_eq!(main.calls[1].uuid, Some(graph.functions[0].uuid.clone()));
+        assert_eq!(names(&main.method_calls), vec!["Counter.bump"]);
+        assert_eq!(main.method_calls[0].uuid, Some(graph.functions[4].uuid.clone()));
+        assert_eq!(names(&main.constructs), vec!["Counter"]);
+        assert_eq!(main.builtins, vec!["print", "f", ".len"]);
+
+        let bump = &graph.functions[4];
+        assert_eq!(names(&bump.method_calls), vec!["Counter.report"]);
+        assert!(bump.calls.is_empty());
+    }
+
+    #[test]
+    fn call_graph_orders_callees_first_and_flags_cycles() {
+        let graph = call_graph(true);
+        let order = names(graph.order.as_ref().unwrap());
+        let pos = |n: &str| order.iter().position(|o| *o == n).unwrap();
+        assert_eq!(order.len(), 6);
+        assert!(pos("fact") < pos("main"));
+        assert!(pos("is_even") < pos("main") && pos("is_odd") < pos("main"));
+        assert!(pos("Counter.report") < pos("Counter.bump") && pos("Counter.bump") < pos("main"));
+
+        let mut cycles: Vec<Vec<&str>> = graph.cycles.as_ref().unwrap().iter().map(|c| {
+            let mut c = names(c);
+            c.sort();
+            c
+        }).collect();
+        cycles.sort();
+        assert_eq!(cycles, vec![vec!["fact"], vec!["is_even", "is_odd"]]);
+    }
+}
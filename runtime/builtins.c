@@ -15,6 +15,7 @@
 // - Math builtins (trigonometry, rounding)
 // - Test framework (expect assertions)
 // - Error handling (TLS error state)
+// - Debug stack traces (frame stack for --debug builds)
 // - Contract enforcement (__pluto_invariant_violation)
 // - RPC response parsing (JSON extraction)
 //──────────────────────────────────────────────────────────────────────────────
@@ -333,9 +334,177 @@ long __pluto_array_index_of(void *handle, long value, long type_tag) {
     return -1;
 }
 
+// Comparison modes for __pluto_array_sort. The first four order slots
+// directly; the rest call back into compiled Pluto code.
+#define SORT_INT           0  // signed 64-bit
+#define SORT_FLOAT         1  // f64 bits
+#define SORT_STRING        2  // byte-wise, shorter prefix first
+#define SORT_UNSIGNED      3  // byte, bool
+#define SORT_CLOSURE       4  // fn(T, T) int closure, T passed as a 64-bit word
+#define SORT_CLOSURE_FLOAT 5  // fn(float, float) int closure
+#define SORT_CLOSURE_SMALL 6  // fn(T, T) int closure, T passed as an 8-bit value
+#define SORT_ORD           7  // T.compare(other) Ordering method
+
+static long sort_compare(long a, long b, long cmp_fn, long ctx, long mode) {
+    switch (mode) {
+    case SORT_INT:
+        return a < b ? -1 : a > b;
+    case SORT_FLOAT: {
+        double da, db;
+        memcpy(&da, &a, sizeof(double));
+        memcpy(&db, &b, sizeof(double));
+        return da < db ? -1 : da > db;
+    }
+    case SORT_STRING: {
+        const char *data_a, *data_b;
+        long len_a, len_b;
+        __pluto_string_data((void *)a, &data_a, &len_a);
+        __pluto_string_data((void *)b, &data_b, &len_b);
+        int c = memcmp(data_a, data_b, len_a < len_b ? len_a : len_b);
+        if (c != 0) return c < 0 ? -1 : 1;
+        return len_a < len_b ? -1 : len_a > len_b;
+    }
+    case SORT_UNSIGNED:
+        return (unsigned long)a < (unsigned long)b ? -1 : (unsigned long)a > (unsigned long)b;
+    case SORT_CLOSURE:
+        return ((long (*)(long, long, long))cmp_fn)(ctx, a, b);
+    case SORT_CLOSURE_FLOAT: {
+        double da, db;
+        memcpy(&da, &a, sizeof(double));
+        memcpy(&db, &b, sizeof(double));
+        return ((long (*)(long, double, double))cmp_fn)(ctx, da, db);
+    }
+    case SORT_CLOSURE_SMALL:
+        return ((long (*)(long, unsigned char, unsigned char))cmp_fn)(ctx, (unsigned char)a, (unsigned char)b);
+    default: {
+        // Ordering is an enum pointer whose tag is Less (0), Equal (1), Greater (2)
+        long *ordering = (long *)((long (*)(long, long))cmp_fn)(a, b);
+        return ordering[0] - 1;
+    }
+    }
+}
+
+// Stable bottom-up merge sort. A comparator may allocate and trigger a
+// collection, so the runs are merged between two array handles the GC can
+// see rather than a malloc'd scratch buffer.
+void __pluto_array_sort(void *handle, long cmp_fn, long ctx, long mode) {
+    long *h = (long *)handle;
+    long len = h[0];
+    if (len < 2) return;
+    long *src_h = (long *)__pluto_array_new(len);
+    long *dst_h = (long *)__pluto_array_new(len);
+    long *src = (long *)src_h[2];
+    long *dst = (long *)dst_h[2];
+    memcpy(src, (long *)h[2], len * sizeof(long));
+    memcpy(dst, src, len * sizeof(long));
+    src_h[0] = len;
+    dst_h[0] = len;
+    for (long width = 1; width < len; width *= 2) {
+        for (long lo = 0; lo < len; lo += 2 * width) {
+            long mid = lo + width < len ? lo + width : len;
+            long hi = lo + 2 * width < len ? lo + 2 * width : len;
+            long i = lo, j = mid, k = lo;
+            while (i < mid && j < hi) {
+                // Take from the right run only when strictly smaller, so equal elements keep their order
+                if (sort_compare(src[j], src[i], cmp_fn, ctx, mode) < 0) dst[k++] = src[j++];
+                else dst[k++] = src[i++];
+            }
+            while (i < mid) dst[k++] = src[i++];
+            while (j < hi) dst[k++] = src[j++];
+        }
+        long *tmp = src; src = dst; dst = tmp;
+        long *tmp_h = src_h; src_h = dst_h; dst_h = tmp_h;
+    }
+    // The comparator may have shrunk the array; write back only what fits
+    long n = h[0] < len ? h[0] : len;
+    memcpy((long *)h[2], src, n * sizeof(long));
+}
+
+void *__pluto_array_join(void *handle, void *sep) {
+    long *h = (long *)handle;
+    long len = h[0];
+    long *data = (long *)h[2];
+    const char *sep_data, *elem_data;
+    long sep_len, elem_len;
+    __pluto_string_data(sep, &sep_data, &sep_len);
+    long total = len > 0 ? (len - 1) * sep_len : 0;
+    for (long i = 0; i < len; i++) {
+        __pluto_string_data((void *)data[i], &elem_data, &elem_len);
+        total += elem_len;
+    }
+    void *header = gc_alloc(8 + total + 1, GC_TAG_STRING, 0);
+    *(long *)header = total;
+    char *out = (char *)header + 8;
+    for (long i = 0; i < len; i++) {
+        if (i > 0) {
+            memcpy(out, sep_data, sep_len);
+            out += sep_len;
+        }
+        __pluto_string_data((void *)data[i], &elem_data, &elem_len);
+        memcpy(out, elem_data, elem_len);
+        out += elem_len;
+    }
+    *out = '\0';
+    return header;
+}
+
+// Format every element the way string interpolation would. `type_tag` uses
+// the key tags of the contains/index_of functions: 0 int or byte, 1 float,
+// 2 bool, 3 string.
+void *__pluto_array_map_to_string(void *handle, long type_tag) {
+    long *h = (long *)handle;
+    long len = h[0];
+    void *result = __pluto_array_new(len > 0 ? len : 1);
+    for (long i = 0; i < len; i++) {
+        long value = ((long *)h[2])[i];
+        void *str;
+        switch (type_tag) {
+        case 1: {
+            double d;
+            memcpy(&d, &value, sizeof(double));
+            str = __pluto_float_to_string(d);
+            break;
+        }
+        case 2:
+            str = __pluto_bool_to_string(value != 0);
+            break;
+        case 3:
+            str = (void *)value;
+            break;
+        default:
+            str = __pluto_int_to_string(value);
+            break;
+        }
+        __pluto_array_push(result, (long)str);
+    }
+    return result;
+}
+
 // ── Bytes runtime functions ───────────────────────────────────────────────────
 // Handle layout (24 bytes): [len: long] [cap: long] [data_ptr: unsigned char*]
 
+// Grow a byte buffer so it can hold at least `needed` bytes. Capacity doubles,
+// so a run of appends costs amortized O(1) per byte. Shared by every path that
+// appends to a bytes handle (push, push_str).
+static unsigned char *bytes_reserve(long *h, long needed) {
+    long cap = h[1];
+    unsigned char *data = (unsigned char *)h[2];
+    if (needed <= cap) return data;
+    if (cap == 0) cap = 16;
+    while (cap < needed) {
+        if (cap > LONG_MAX / 2) {
+            fprintf(stderr, "pluto: bytes capacity overflow\n");
+            exit(1);
+        }
+        cap = cap * 2;
+    }
+    data = (unsigned char *)realloc(data, cap);
+    if (!data) { fprintf(stderr, "pluto: out of memory\n"); exit(1); }
+    h[1] = cap;
+    h[2] = (long)data;
+    return data;
+}
+
 long __pluto_bytes_new(void) {
     long *handle = (long *)gc_alloc(24, GC_TAG_BYTES, 3);
     handle[0] = 0;   // len
@@ -349,24 +518,23 @@ long __pluto_bytes_new(void) {
 void __pluto_bytes_push(long handle, long value) {
     long *h = (long *)handle;
     long len = h[0];
-    long cap = h[1];
-    unsigned char *data = (unsigned char *)h[2];
-    if (len == cap) {
-        if (cap > LONG_MAX / 2) {
-            fprintf(stderr, "pluto: bytes capacity overflow\n");
-            exit(1);
-        }
-        cap = cap * 2;
-        if (cap == 0) cap = 16;
-        data = (unsigned char *)realloc(data, cap);
-        if (!data) { fprintf(stderr, "pluto: out of memory\n"); exit(1); }
-        h[1] = cap;
-        h[2] = (long)data;
-    }
+    unsigned char *data = bytes_reserve(h, len + 1);
     data[len] = (unsigned char)(value & 0xFF);
     h[0] = len + 1;
 }
 
+void __pluto_bytes_push_str(long handle, long str_handle) {
+    long *h = (long *)handle;
+    const char *str_data;
+    long str_len;
+    __pluto_string_data((void *)str_handle, &str_data, &str_len);
+    if (str_len == 0) return;
+    long len = h[0];
+    unsigned char *data = bytes_reserve(h, len + str_len);
+    memcpy(data + len, str_data, str_len);
+    h[0] = len + str_len;
+}
+
 long __pluto_bytes_get(long handle, long index) {
     long *h = (long *)handle;
     long len = h[0];
@@ -434,6 +602,16 @@ void *__pluto_string_substring(void *s, long start, long len) {
     return __pluto_string_slice_new(s, start, len);
 }
 
+// Bounds check for s[start..end] and arr[start..end] (end exclusive). Unlike
+// substring/slice, which clamp, a range outside 0..len or running backwards traps.
+void __pluto_slice_check(long start, long end, long len, long is_string) {
+    if (start < 0 || end > len || start > end) {
+        fprintf(stderr, "pluto: %s slice out of bounds: range %ld..%ld, length %ld\n",
+                is_string ? "string" : "array", start, end, len);
+        exit(1);
+    }
+}
+
 long __pluto_string_contains(void *haystack, void *needle) {
     const char *hdata, *ndata;
     long hlen, nlen;
@@ -604,6 +782,61 @@ void *__pluto_string_split(void *s, void *delim) {
     return arr;
 }
 
+// Length of the UTF-8 sequence starting with lead byte `c`. Invalid lead
+// bytes count as a single byte so malformed input still makes progress.
+static long utf8_seq_len(unsigned char c) {
+    if (c < 0x80) return 1;
+    if ((c & 0xE0) == 0xC0) return 2;
+    if ((c & 0xF0) == 0xE0) return 3;
+    if ((c & 0xF8) == 0xF0) return 4;
+    return 1;
+}
+
+void *__pluto_string_chars(void *s) {
+    const char *data;
+    long slen;
+    __pluto_string_data(s, &data, &slen);
+    void *arr = __pluto_array_new(slen > 0 ? slen : 1);
+    long i = 0;
+    while (i < slen) {
+        long n = utf8_seq_len((unsigned char)data[i]);
+        if (n > slen - i) n = slen - i;
+        __pluto_array_push(arr, (long)__pluto_string_slice_new(s, i, n));
+        i += n;
+    }
+    return arr;
+}
+
+// Split on '\n', dropping a '\r' that precedes it. A trailing newline does
+// not produce an empty final line, and the empty string has no lines.
+void *__pluto_string_lines(void *s) {
+    const char *data;
+    long slen;
+    __pluto_string_data(s, &data, &slen);
+    void *arr = __pluto_array_new(4);
+    long start = 0;
+    while (start < slen) {
+        const char *nl = (const char *)memchr(data + start, '\n', slen - start);
+        long end = nl ? nl - data : slen;
+        long next = nl ? end + 1 : slen;
+        if (nl && end > start && data[end - 1] == '\r') end--;
+        __pluto_array_push(arr, (long)__pluto_string_slice_new(s, start, end - start));
+        start = next;
+    }
+    return arr;
+}
+
+void *__pluto_string_bytes(void *s) {
+    const char *data;
+    long slen;
+    __pluto_string_data(s, &data, &slen);
+    void *arr = __pluto_array_new(slen > 0 ? slen : 1);
+    for (long i = 0; i < slen; i++) {
+        __pluto_array_push(arr, (long)(unsigned char)data[i]);
+    }
+    return arr;
+}
+
 void *__pluto_string_char_at(void *s, long index) {
     const char *data;
     long slen;
@@ -619,6 +852,38 @@ void *__pluto_string_char_at(void *s, long index) {
     return header;
 }
 
+long __pluto_string_char_count(void *s) {
+    const char *data;
+    long slen;
+    __pluto_string_data(s, &data, &slen);
+    long count = 0;
+    for (long i = 0; i < slen; i += utf8_seq_len((unsigned char)data[i])) {
+        count++;
+    }
+    return count;
+}
+
+// Like __pluto_string_char_at, but `index` counts UTF-8 code points and the
+// result holds the whole (possibly multi-byte) character.
+void *__pluto_string_char_at_utf8(void *s, long index) {
+    const char *data;
+    long slen;
+    __pluto_string_data(s, &data, &slen);
+    long i = 0;
+    long n = 0;
+    while (i < slen) {
+        long len = utf8_seq_len((unsigned char)data[i]);
+        if (len > slen - i) len = slen - i;
+        if (n == index) {
+            return __pluto_string_slice_new(s, i, len);
+        }
+        i += len;
+        n++;
+    }
+    fprintf(stderr, "pluto: string index out of bounds: index %ld, char count %ld\n", index, n);
+    exit(1);
+}
+
 long __pluto_string_byte_at(void *s, long index) {
     const char *data;
     long slen;
@@ -931,8 +1196,11 @@ const char *__pluto_string_to_cstr(void *s) {
 
 // ── Error handling runtime ────────────────────────────────────────────────────
 
+static void __pluto_snapshot_frames(void);
+
 void __pluto_raise_error(void *error_obj) {
     __pluto_current_error = error_obj;
+    __pluto_snapshot_frames();
 }
 
 long __pluto_has_error() {
@@ -959,24 +1227,116 @@ void *__pluto_error_type() {
     return __pluto_current_error_type ? __pluto_current_error_type : __pluto_string_new("", 0);
 }
 
+// Put back an error that was set aside while `finally` blocks ran. Unlike
+// __pluto_raise_error this keeps the frames recorded when it was raised.
+void __pluto_restore_error(void *error_obj, void *type_str) {
+    __pluto_current_error = error_obj;
+    __pluto_current_error_type = type_str;
+}
+
+// ── Debug stack traces ────────────────────────────────────────────────────────
+//
+// Only used by binaries compiled with `--debug`: codegen pushes the function
+// name on entry and pops it on every return. `__pluto_raise_error` snapshots
+// the live frames so the trace survives the unwinding done by propagation.
+// Frames beyond PLUTO_MAX_FRAMES are counted but not recorded.
+
+#define PLUTO_MAX_FRAMES 256
+
+static __thread const char *__pluto_frames[PLUTO_MAX_FRAMES];
+static __thread long __pluto_frame_depth = 0;
+static __thread const char *__pluto_error_frames[PLUTO_MAX_FRAMES];
+static __thread long __pluto_error_frame_depth = 0;
+
+void __pluto_push_frame(const char *name) {
+    if (__pluto_frame_depth < PLUTO_MAX_FRAMES) {
+        __pluto_frames[__pluto_frame_depth] = name;
+    }
+    __pluto_frame_depth++;
+}
+
+void __pluto_pop_frame(void) {
+    if (__pluto_frame_depth > 0) {
+        __pluto_frame_depth--;
+    }
+}
+
+static void __pluto_snapshot_frames(void) {
+    long recorded = __pluto_frame_depth < PLUTO_MAX_FRAMES ? __pluto_frame_depth : PLUTO_MAX_FRAMES;
+    memcpy(__pluto_error_frames, __pluto_frames, recorded * sizeof(const char *));
+    __pluto_error_frame_depth = __pluto_frame_depth;
+}
+
+// Called on the way out of `main` in debug builds. If an error escaped, print
+// its type and the frames that were live when it was raised, then exit(1).
+void __pluto_report_uncaught_error(void) {
+    if (!__pluto_current_error) return;
+    fflush(stdout);
+    if (__pluto_current_error_type) {
+        const char *data;
+        long len;
+        __pluto_string_data(__pluto_current_error_type, &data, &len);
+        fprintf(stderr, "error: uncaught error %.*s\n", (int)len, data);
+    } else {
+        fprintf(stderr, "error: uncaught error\n");
+    }
+    fprintf(stderr, "stack trace (most recent call first):\n");
+    long recorded = __pluto_error_frame_depth < PLUTO_MAX_FRAMES ? __pluto_error_frame_depth : PLUTO_MAX_FRAMES;
+    if (__pluto_error_frame_depth > recorded) {
+        fprintf(stderr, "  ... %ld deeper frames not recorded\n", __pluto_error_frame_depth - recorded);
+    }
+    for (long i = recorded - 1; i >= 0; i--) {
+        fprintf(stderr, "  at %s\n", __pluto_error_frames[i]);
+    }
+    exit(1);
+}
+
 // Time
+#ifdef PLUTO_TEST_MODE
+// Test mode: sleeping advances a virtual clock instead of blocking, so one
+// fiber's sleep doesn't stall the scheduler. Both clocks include it.
+static long __pluto_time_virtual_ns = 0;
+#define TIME_VIRTUAL_NS __pluto_time_virtual_ns
+#else
+#define TIME_VIRTUAL_NS 0L
+#endif
+
 long __pluto_time_ns(void) {
     struct timespec ts;
     clock_gettime(CLOCK_MONOTONIC, &ts);
-    return (long)ts.tv_sec * 1000000000L + (long)ts.tv_nsec;
+    return (long)ts.tv_sec * 1000000000L + (long)ts.tv_nsec + TIME_VIRTUAL_NS;
 }
 
 long __pluto_time_wall_ns(void) {
     struct timespec ts;
     clock_gettime(CLOCK_REALTIME, &ts);
-    return (long)ts.tv_sec * 1000000000L + (long)ts.tv_nsec;
+    return (long)ts.tv_sec * 1000000000L + (long)ts.tv_nsec + TIME_VIRTUAL_NS;
 }
 
 void __pluto_time_sleep_ns(long ns) {
+#ifdef PLUTO_TEST_MODE
+    if (ns > 0) __pluto_time_virtual_ns += ns;
+    __pluto_task_yield();
+#else
+    // Only this thread blocks, and it gives up its run slot meanwhile
     struct timespec req;
     req.tv_sec = ns / 1000000000L;
     req.tv_nsec = ns % 1000000000L;
+    __pluto_task_block();
     nanosleep(&req, NULL);
+    __pluto_task_unblock();
+#endif
+}
+
+// Format a wall-clock time in milliseconds since the epoch with a strftime
+// layout, in UTC. An empty string if the result doesn't fit in 256 bytes.
+void *__pluto_time_format(long ms, void *layout_str) {
+    time_t secs = (time_t)(ms >= 0 ? ms / 1000 : (ms - 999) / 1000);
+    struct tm tm;
+    gmtime_r(&secs, &tm);
+    char buf[256];
+    size_t len = strftime(buf, sizeof(buf), __pluto_string_to_cstr(layout_str), &tm);
+    return __pluto_string_new(buf, (long)len);
 }
 
 // Random — xorshift64*
@@ -1018,6 +1378,122 @@ long __pluto_gc_heap_size(void) {
     return (long)__pluto_gc_bytes_allocated();
 }
 
+// A GcStats (stdlib/prelude.pt); fields in declaration order.
+void *__pluto_gc_stats(void) {
+    long *stats = (long *)__pluto_alloc(4 * sizeof(long));
+    stats[0] = (long)__pluto_gc_total_allocated();
+    stats[1] = (long)__pluto_gc_bytes_allocated();
+    stats[2] = __pluto_gc_collections();
+    stats[3] = __pluto_gc_pause_ns();
+    return stats;
+}
+
+static void __pluto_gc_print_stats(void) {
+    long pause_ns = __pluto_gc_pause_ns();
+    fprintf(stderr,
+        "gc: %zu bytes allocated, %zu bytes live, %ld collections, %ld.%03ld ms paused\n",
+        __pluto_gc_total_allocated(), __pluto_gc_bytes_allocated(),
+        __pluto_gc_collections(), pause_ns / 1000000, (pause_ns / 1000) % 1000);
+}
+
+// `pluto run --gc-stats` sets PLUTO_GC_STATS; any binary run with it set
+// prints a summary to stderr at exit.
+__attribute__((constructor))
+static void __pluto_gc_stats_at_exit(void) {
+    const char *flag = getenv("PLUTO_GC_STATS");
+    if (flag && *flag && strcmp(flag, "0") != 0) {
+        atexit(__pluto_gc_print_stats);
+    }
+}
+
+// Program arguments — glibc and the macOS loader pass (argc, argv, envp) to
+// constructors, so argv is captured here instead of threading it through the
+// generated main.
+static int __pluto_argc = 0;
+static char **__pluto_argv = NULL;
+
+__attribute__((constructor))
+static void __pluto_capture_args(int argc, char **argv, char **envp) {
+    (void)envp;
+    __pluto_argc = argc;
+    __pluto_argv = argv;
+}
+
+// The program's arguments, excluding the program name.
+void *__pluto_args(void) {
+    void *arr = __pluto_array_new(__pluto_argc > 1 ? __pluto_argc - 1 : 1);
+    for (int i = 1; i < __pluto_argc; i++) {
+        __pluto_array_push(arr, (long)__pluto_string_new(__pluto_argv[i], (long)strlen(__pluto_argv[i])));
+    }
+    return arr;
+}
+
+// ── Standard input ───────────────────────────────────────────────────────────
+
+// Raise an IOError carrying strerror(errno), then reset stdin's error flag so
+// a later read can be retried.
+static void __pluto_raise_stdin_error(void) {
+    const char *msg = strerror(errno);
+    void *msg_str = __pluto_string_new(msg, (long)strlen(msg));
+    void *err_obj = __pluto_alloc(8);  // 1 field: message
+    *(long *)err_obj = (long)msg_str;
+    __pluto_raise_error(err_obj);
+    __pluto_set_error_type(__pluto_string_new("IOError", 7));
+    clearerr(stdin);
+}
+
+// Next line of stdin without its line terminator, or none (0) at EOF.
+void *__pluto_read_line(void) {
+    char *buf = NULL;
+    size_t cap = 0;
+    errno = 0;
+    ssize_t len = getline(&buf, &cap, stdin);
+    if (len < 0) {
+        free(buf);
+        if (ferror(stdin)) __pluto_raise_stdin_error();
+        return NULL;
+    }
+    if (len > 0 && buf[len - 1] == '\n') len--;
+    if (len > 0 && buf[len - 1] == '\r') len--;
+    void *result = __pluto_string_new(buf, len);
+    free(buf);
+    return result;
+}
+
+// Everything left on stdin, up to EOF.
+void *__pluto_read_all(void) {
+    size_t cap = 4096, len = 0;
+    char *buf = (char *)malloc(cap);
+    if (!buf) {
+        fprintf(stderr, "pluto: out of memory reading stdin\n");
+        exit(1);
+    }
+    errno = 0;
+    while (1) {
+        if (len == cap) {
+            cap *= 2;
+            char *grown = (char *)realloc(buf, cap);
+            if (!grown) {
+                free(buf);
+                fprintf(stderr, "pluto: out of memory reading stdin\n");
+                exit(1);
+            }
+            buf = grown;
+        }
+        size_t n = fread(buf + len, 1, cap - len, stdin);
+        len += n;
+        if (n == 0) break;
+    }
+    if (ferror(stdin)) {
+        free(buf);
+        __pluto_raise_stdin_error();
+        return __pluto_string_new("", 0);
+    }
+    void *result = __pluto_string_new(buf, (long)len);
+    free(buf);
+    return result;
+}
+
 // ── Socket runtime — POSIX sockets for networking ─────────────────────────────
 
 __attribute__((constructor))
@@ -1338,7 +1814,23 @@ void *__pluto_request_field(void *s, long index) {
 #define MAP_LOAD_FACTOR_NUM 3
 #define MAP_LOAD_FACTOR_DEN 4
 
+// Hash seed — fixed (0) unless PLUTO_HASH_SEED is set, so iteration order and
+// anything derived from it is identical from run to run. Read once, on first use;
+// racing threads all compute the same value.
+static unsigned long ht_seed_value = 0;
+static int ht_seed_loaded = 0;
+
+static unsigned long ht_seed(void) {
+    if (!ht_seed_loaded) {
+        const char *env = getenv("PLUTO_HASH_SEED");
+        ht_seed_value = env ? (unsigned long)strtoull(env, NULL, 0) : 0;
+        ht_seed_loaded = 1;
+    }
+    return ht_seed_value;
+}
+
 static unsigned long ht_hash(long key, long key_type) {
+    unsigned long seed = ht_seed();
     unsigned long h;
     switch (key_type) {
     case 1: { // float — bitcast
@@ -1346,7 +1838,7 @@ static unsigned long ht_hash(long key, long key_type) {
         memcpy(&d, &key, sizeof(double));
         unsigned long bits;
         memcpy(&bits, &d, sizeof(unsigned long));
-        h = bits * 0x9e3779b97f4a7c15ULL;
+        h = (bits ^ seed) * 0x9e3779b97f4a7c15ULL;
         break;
     }
     case 3: { // string — FNV-1a
@@ -1355,7 +1847,7 @@ static unsigned long ht_hash(long key, long key_type) {
         long slen;
         __pluto_string_data(s, &str_data, &slen);
         const unsigned char *data = (const unsigned char *)str_data;
-        h = 0xcbf29ce484222325ULL;
+        h = 0xcbf29ce484222325ULL ^ seed;
         for (long i = 0; i < slen; i++) {
             h ^= data[i];
             h *= 0x100000001b3ULL;
@@ -1363,7 +1855,7 @@ static unsigned long ht_hash(long key, long key_type) {
         break;
     }
     default: // int(0), bool(2), enum(4)
-        h = (unsigned long)key * 0x9e3779b97f4a7c15ULL;
+        h = ((unsigned long)key ^ seed) * 0x9e3779b97f4a7c15ULL;
         break;
     }
     return h;
@@ -33,6 +33,7 @@
 #include <stdio.h>
 #include <stdlib.h>
 #include <string.h>
+#include <strings.h>
 #include <ctype.h>
 #include <setjmp.h>
 #include <time.h>
@@ -48,9 +49,9 @@
 #include <fcntl.h>
 #include <limits.h>
 #include <math.h>
+#include <stdatomic.h>
 #ifndef PLUTO_TEST_MODE
 #include <pthread.h>
-#include <stdatomic.h>
 #endif
 #ifdef PLUTO_TEST_MODE
 #include <ucontext.h>
@@ -70,6 +71,9 @@
 #define GC_TAG_BYTES   8   // [len][cap][data_ptr]; 1 byte per element
 #define GC_TAG_CHANNEL 9   // [sync_ptr][buf_ptr][capacity][count][head][tail][closed]
 #define GC_TAG_STRING_SLICE 10 // [backing_ptr][offset][len]; lightweight view into owned string
+#define GC_TAG_WAITGROUP 11  // [sync_ptr][count]
+#define GC_TAG_ATOMIC 12     // [value]; shared by reference across spawns
+#define GC_TAG_MUTEX 13      // [value][sync_ptr][locked]; trace value only
 
 // ── Thread-Local Storage ─────────────────────────────────────────────────────
 
@@ -90,7 +94,7 @@ typedef struct GCHeader {
     uint16_t field_count;     // 2B: number of 8-byte slots to scan
 } GCHeader;
 
-// ── Channel Sync (Production Mode Only) ──────────────────────────────────────
+// ── Channel / WaitGroup Sync (Production Mode Only) ──────────────────────────
 
 #ifndef PLUTO_TEST_MODE
 typedef struct {
@@ -98,18 +102,33 @@ typedef struct {
     pthread_cond_t not_empty;
     pthread_cond_t not_full;
 } ChannelSync;
+
+typedef struct {
+    pthread_mutex_t mutex;
+    pthread_cond_t zero;
+} WaitGroupSync;
 #endif
 
 // ── GC Public API (implemented in gc.c) ──────────────────────────────────────
 
 void __pluto_gc_init(void *stack_bottom);
 void __pluto_gc_collect(void);
+void __pluto_gc_force_collect(void);
 void *__pluto_alloc(long size);
 void __pluto_safepoint(void);
+extern atomic_int __pluto_safepoint_requested;
+// Why threads should stop at their next safepoint (bits of __pluto_safepoint_requested)
+#define PLUTO_SAFEPOINT_GC 1      // A collection is waiting for threads to stop
+#define PLUTO_SAFEPOINT_CANCEL 2  // A running task has been cancelled
+#define PLUTO_SAFEPOINT_YIELD 4   // Threads are waiting for a run slot (PLUTO_THREADS)
 
 // Internal GC allocation API (used by runtime, not by generated code)
 void *gc_alloc(size_t user_size, uint8_t type_tag, uint16_t field_count);
 size_t __pluto_gc_bytes_allocated(void);
+// Counters since startup, for gc_stats() and PLUTO_GC_STATS
+size_t __pluto_gc_total_allocated(void);
+long __pluto_gc_collections(void);
+long __pluto_gc_pause_ns(void);
 
 #ifdef PLUTO_TEST_MODE
 // Fiber stack API for scheduler (test mode only)
@@ -138,10 +157,16 @@ GCHeader *__pluto_gc_get_head(void);
 
 // Error handling
 void __pluto_raise_error(void *error_obj);
+void __pluto_set_error_type(void *type_str);
 
 // String functions (needed by threading for error messages)
 void *__pluto_string_new(const char *src, long len);
 
+// Number formatting (needed by array map_to_string)
+void *__pluto_int_to_string(long value);
+void *__pluto_float_to_string(double value);
+void *__pluto_bool_to_string(int value);
+
 // String slice functions (needed by codegen for escape materialization)
 void *__pluto_string_slice_new(void *backing, long offset, long len);
 void *__pluto_string_slice_to_owned(void *s);
@@ -160,4 +185,11 @@ void __pluto_array_push(void *handle, long value);
 // Time functions (needed by threading for select randomization)
 long __pluto_time_ns(void);
 
+// Task cancellation and run slots (needed by the GC's safepoint and sleep)
+void __pluto_task_poll_cancel(void);
+void __pluto_task_poll_yield(void);
+void __pluto_task_block(void);
+void __pluto_task_unblock(void);
+void __pluto_task_yield(void);
+
 #endif // PLUTO_BUILTINS_H
@@ -0,0 +1,118 @@
+//──────────────────────────────────────────────────────────────────────────────
+// Pluto Runtime: Bump-Allocating Garbage Collector
+//
+// The mark-sweep collector (marksweep.c, appended to this file when the
+// runtime is built) with objects bump-allocated out of 64 KB arena blocks
+// instead of one malloc call each. Allocation is a pointer increment.
+//
+// Collection still marks conservatively, so objects never move. A sweep
+// doesn't free dead objects one by one: it counts them off their block, and a
+// block whose objects are all dead is reset wholesale for reuse. A block
+// with any reachable object is kept as is, so reachable objects survive every
+// reset. The cost is that a dead object's space is only reclaimed once the
+// rest of its block dies too, which suits workloads whose garbage is mostly
+// short-lived.
+//
+// Collections run at the same points as mark-sweep: when an allocation
+// crosses the threshold, after which other threads stop at their next
+// `__pluto_safepoint`. PLUTO_GC_INITIAL_HEAP and PLUTO_GC_GROWTH_FACTOR apply.
+//──────────────────────────────────────────────────────────────────────────────
+
+#include "builtins.h"
+
+#define PLUTO_GC_BUMP
+
+// Blocks are aligned to their size, so an object's block is its address with
+// the low bits cleared. Objects too big for a block get a block of their own,
+// still starting within the first BUMP_BLOCK_SIZE bytes.
+#define BUMP_BLOCK_SIZE ((size_t)64 * 1024)
+// Empty blocks kept for reuse after a sweep; the rest go back to the system
+#define BUMP_SPARE_BLOCKS 8
+
+typedef struct BumpBlock {
+    struct BumpBlock *next;
+    char *cursor;       // Next free byte
+    char *end;
+    size_t live;        // Objects allocated here and not yet swept
+} BumpBlock;
+
+static BumpBlock *bump_blocks = NULL;   // Blocks holding objects
+static BumpBlock *bump_current = NULL;  // Block being allocated from
+static BumpBlock *bump_spare = NULL;    // Empty blocks ready for reuse
+static size_t bump_spare_count = 0;
+
+static void bump_reset(BumpBlock *b) {
+    b->cursor = (char *)(b + 1);
+    b->live = 0;
+}
+
+static BumpBlock *bump_block_new(size_t size) {
+    void *mem = NULL;
+    if (posix_memalign(&mem, BUMP_BLOCK_SIZE, size) != 0) {
+        fprintf(stderr, "pluto: out of memory\n");
+        exit(1);
+    }
+    BumpBlock *b = (BumpBlock *)mem;
+    b->end = (char *)b + size;
+    bump_reset(b);
+    return b;
+}
+
+static GCHeader *gc_store_alloc(size_t total) {
+    size_t need = (total + 15) & ~(size_t)15;
+    BumpBlock *b = bump_current;
+    if (!b || b->cursor + need > b->end) {
+        size_t size = sizeof(BumpBlock) + need;
+        if (size > BUMP_BLOCK_SIZE) {
+            // Large object: a block of its own, never allocated from again
+            size = (size + BUMP_BLOCK_SIZE - 1) & ~(BUMP_BLOCK_SIZE - 1);
+            b = bump_block_new(size);
+        } else {
+            if (bump_spare) {
+                b = bump_spare;
+                bump_spare = b->next;
+                bump_spare_count--;
+            } else {
+                b = bump_block_new(BUMP_BLOCK_SIZE);
+            }
+            bump_current = b;
+        }
+        b->next = bump_blocks;
+        bump_blocks = b;
+    }
+    GCHeader *h = (GCHeader *)b->cursor;
+    b->cursor += need;
+    b->live++;
+    memset(h, 0, need);
+    return h;
+}
+
+static void gc_store_free(GCHeader *h) {
+    BumpBlock *b = (BumpBlock *)((size_t)h & ~(BUMP_BLOCK_SIZE - 1));
+    b->live--;
+}
+
+// Reset every block the sweep emptied
+static void gc_store_after_sweep(void) {
+    BumpBlock **pp = &bump_blocks;
+    while (*pp) {
+        BumpBlock *b = *pp;
+        if (b->live > 0) {
+            pp = &b->next;
+        } else if (b == bump_current) {
+            bump_reset(b);
+            pp = &b->next;
+        } else {
+            *pp = b->next;
+            if ((size_t)(b->end - (char *)b) == BUMP_BLOCK_SIZE && bump_spare_count < BUMP_SPARE_BLOCKS) {
+                bump_reset(b);
+                b->next = bump_spare;
+                bump_spare = b;
+                bump_spare_count++;
+            } else {
+                free(b);
+            }
+        }
+    }
+}
+
@@ -9,10 +9,27 @@
 // - Stop-the-world via safepoint polling (production mode)
 // - Single-threaded sequential collection (test mode)
 // - Supports concurrent task execution with thread stack scanning
+//
+// The bump backend (bump.c) is this collector with a different object store:
+// it defines PLUTO_GC_BUMP and its own gc_store_* hooks, then this file is
+// appended to it.
 //──────────────────────────────────────────────────────────────────────────────
 
 #include "builtins.h"
 
+#ifndef PLUTO_GC_BUMP
+// Object store: one zeroed malloc block per object
+static GCHeader *gc_store_alloc(size_t total) {
+    return (GCHeader *)calloc(1, total);
+}
+
+static void gc_store_free(GCHeader *h) {
+    free(h);
+}
+
+static void gc_store_after_sweep(void) {}
+#endif
+
 // ── GC Infrastructure ─────────────────────────────────────────────────────────
 
 // Interval for binary-search pointer lookup
@@ -23,7 +40,21 @@ typedef struct { void *start; void *end; void *array_handle; } GCDataInterval;
 // Global GC state
 static GCHeader *gc_head = NULL;
 static size_t gc_bytes_allocated = 0;
-static size_t gc_threshold = 256 * 1024;  // 256KB initial
+// Collection policy. The compiler passes the configured values as -D defines
+// (see GcConfig in src/lib.rs): the first collection runs once the heap reaches
+// the initial size, and each collection sets the next threshold to the surviving
+// bytes times the growth factor, never below the initial size.
+#ifndef PLUTO_GC_INITIAL_HEAP
+#define PLUTO_GC_INITIAL_HEAP (256 * 1024)
+#endif
+#ifndef PLUTO_GC_GROWTH_FACTOR
+#define PLUTO_GC_GROWTH_FACTOR 2.0
+#endif
+static size_t gc_threshold = PLUTO_GC_INITIAL_HEAP;
+// Statistics: bytes ever allocated, collections run, and time spent collecting
+static size_t gc_total_allocated = 0;
+static long gc_collections = 0;
+static long gc_pause_ns = 0;
 static void *gc_stack_bottom = NULL;
 #ifdef PLUTO_TEST_MODE
 static int gc_collecting = 0;
@@ -92,6 +123,12 @@ void __pluto_gc_disable_fiber_scanning(void) {
 }
 #endif
 
+// PLUTO_SAFEPOINT_* bits: set while a collection waits for threads to stop, a
+// running task has been cancelled or threads are waiting for a run slot. Generated code polls it inline and only
+// calls __pluto_safepoint when it is nonzero. Never set in test mode, where
+// collection doesn't need other threads to stop.
+atomic_int __pluto_safepoint_requested = 0;
+
 // GC thread safety (production mode only)
 #ifndef PLUTO_TEST_MODE
 static pthread_mutex_t gc_mutex = PTHREAD_MUTEX_INITIALIZER;
@@ -110,20 +147,17 @@ static GCThreadStack gc_thread_stacks[GC_MAX_THREAD_STACKS];
 static int gc_thread_stack_count = 0;
 
 // Safepoint-based stop-the-world state.
-// GC sets gc_safepoint_requested; threads check this flag periodically and yield.
-// When yielding, threads increment gc_stw_stopped and spin on gc_stw_resume.
-static atomic_int gc_safepoint_requested = 0;
+// GC sets __pluto_safepoint_requested; threads check this flag periodically and yield.
+// When yielding, threads increment gc_stw_stopped and spin until gc_stw_epoch
+// moves on. An epoch rather than a resume flag, so a thread still spinning when
+// the next collection starts isn't held by it.
 static volatile int gc_stw_stopped = 0;
-static volatile int gc_stw_resume = 0;
+static atomic_int gc_stw_epoch = 0;
 
-// Safepoint check - called by threads at regular intervals (loop back-edges, allocations).
-// If GC has requested a safepoint, the thread yields here until GC completes.
-void __pluto_safepoint(void) {
-    if (atomic_load(&gc_safepoint_requested) == 0) {
-        return;  // Fast path - no GC pending
-    }
+// Yield to a collection until it completes
+static void gc_safepoint_stop(void) {
+    int epoch = atomic_load(&gc_stw_epoch);
 
-    // GC is running - yield at this safepoint
     // Flush registers to stack so GC can scan them
     jmp_buf regs;
     setjmp(regs);
@@ -133,11 +167,28 @@ void __pluto_safepoint(void) {
     __sync_fetch_and_add(&gc_stw_stopped, 1);
 
     // Spin-wait until GC is done (memory barrier to see the update)
-    while (!gc_stw_resume) {
+    while (atomic_load(&gc_stw_epoch) == epoch) {
         __sync_synchronize();
     }
 }
 
+// Safepoint check - called by threads at regular intervals (loop back-edges, function entry).
+// If GC has requested a safepoint, the thread yields here until GC completes.
+// A cancelled task then has TaskCancelled raised, which generated code unwinds from,
+// and a thread that has used up its time slice passes its run slot on.
+void __pluto_safepoint(void) {
+    int requested = atomic_load(&__pluto_safepoint_requested);
+    if (requested & PLUTO_SAFEPOINT_GC) {
+        gc_safepoint_stop();
+    }
+    if (requested & PLUTO_SAFEPOINT_CANCEL) {
+        __pluto_task_poll_cancel();
+    }
+    if (requested & PLUTO_SAFEPOINT_YIELD) {
+        __pluto_task_poll_yield();
+    }
+}
+
 // Thread registration API for spawned tasks
 void __pluto_gc_register_thread_stack(void *stack_lo, void *stack_hi) {
     pthread_mutex_lock(&gc_mutex);
@@ -153,7 +204,11 @@ void __pluto_gc_register_thread_stack(void *stack_lo, void *stack_hi) {
 
 void __pluto_gc_deregister_thread_stack(void) {
     pthread_t self = pthread_self();
-    pthread_mutex_lock(&gc_mutex);
+    // Still registered, so a collection holding the mutex waits for this
+    // thread to stop; keep answering safepoints until the lock is free
+    while (pthread_mutex_trylock(&gc_mutex) != 0) {
+        __pluto_safepoint();
+    }
     for (int i = 0; i < gc_thread_stack_count; i++) {
         if (pthread_equal(gc_thread_stacks[i].thread, self)) {
             gc_thread_stacks[i].active = 0;
@@ -196,7 +251,7 @@ void *gc_alloc(size_t user_size, uint8_t type_tag, uint16_t field_count) {
         __pluto_gc_collect();
     }
     size_t total = sizeof(GCHeader) + user_size;
-    GCHeader *h = (GCHeader *)calloc(1, total);
+    GCHeader *h = gc_store_alloc(total);
     if (!h) { fprintf(stderr, "pluto: out of memory\n"); exit(1); }
     h->next = gc_head;
     gc_head = h;
@@ -205,6 +260,7 @@ void *gc_alloc(size_t user_size, uint8_t type_tag, uint16_t field_count) {
     h->field_count = field_count;
     h->mark = 0;
     gc_bytes_allocated += total;
+    gc_total_allocated += total;
     return (char *)h + sizeof(GCHeader);
 }
 #else
@@ -214,7 +270,6 @@ void *gc_alloc(size_t user_size, uint8_t type_tag, uint16_t field_count) {
 static int gc_stw_stop_threads(void) {
     int count = 0;
     gc_stw_stopped = 0;
-    gc_stw_resume = 0;
     __sync_synchronize();  // memory barrier
 
     // Count active threads (excluding self)
@@ -227,7 +282,7 @@ static int gc_stw_stop_threads(void) {
 
     if (count > 0) {
         // Request all threads to stop at their next safepoint
-        atomic_store(&gc_safepoint_requested, 1);
+        atomic_fetch_or(&__pluto_safepoint_requested, PLUTO_SAFEPOINT_GC);
         __sync_synchronize();
 
         // Wait for all threads to acknowledge (NO TIMEOUT - they WILL hit a safepoint)
@@ -240,9 +295,9 @@ static int gc_stw_stop_threads(void) {
 }
 
 static void gc_stw_resume_threads(void) {
-    gc_stw_resume = 1;
-    __sync_synchronize();  // ensure visibility
-    atomic_store(&gc_safepoint_requested, 0);  // Clear safepoint request
+    // Clear the request before releasing threads, so none re-stops for this epoch
+    atomic_fetch_and(&__pluto_safepoint_requested, ~PLUTO_SAFEPOINT_GC);
+    atomic_fetch_add(&gc_stw_epoch, 1);
     __sync_synchronize();
 }
 
@@ -270,7 +325,7 @@ void *gc_alloc(size_t user_size, uint8_t type_tag, uint16_t field_count) {
         }
     }
     size_t total = sizeof(GCHeader) + user_size;
-    GCHeader *h = (GCHeader *)calloc(1, total);
+    GCHeader *h = gc_store_alloc(total);
     if (!h) { pthread_mutex_unlock(&gc_mutex); fprintf(stderr, "pluto: out of memory\n"); exit(1); }
     h->next = gc_head;
     gc_head = h;
@@ -279,11 +334,32 @@ void *gc_alloc(size_t user_size, uint8_t type_tag, uint16_t field_count) {
     h->field_count = field_count;
     h->mark = 0;
     gc_bytes_allocated += total;
+    gc_total_allocated += total;
     pthread_mutex_unlock(&gc_mutex);
     return (char *)h + sizeof(GCHeader);
 }
 #endif
 
+// Collect now, regardless of the threshold (the `gc_collect()` builtin).
+#ifdef PLUTO_TEST_MODE
+void __pluto_gc_force_collect(void) {
+    if (gc_stack_bottom && !gc_collecting) {
+        __pluto_gc_collect();
+    }
+}
+#else
+void __pluto_gc_force_collect(void) {
+    pthread_mutex_lock(&gc_mutex);
+    int expected = 0;
+    if (gc_stack_bottom && atomic_compare_exchange_strong(&gc_collecting, &expected, 1)) {
+        int stopped = gc_stw_stop_threads();
+        __pluto_gc_collect();
+        if (stopped > 0) gc_stw_resume_threads();
+    }
+    pthread_mutex_unlock(&gc_mutex);
+}
+#endif
+
 // Public allocation API
 void *__pluto_alloc(long size) {
     if (size == 0) size = 8;
@@ -581,6 +657,8 @@ static void gc_mark_candidate(void *candidate) {
 
 void __pluto_gc_collect(void) {
     gc_collecting = 1;
+    struct timespec pause_start;
+    clock_gettime(CLOCK_MONOTONIC, &pause_start);
 
     // Build interval tables
     gc_build_intervals();
@@ -751,7 +829,31 @@ void __pluto_gc_collect(void) {
                 }
                 if (buf) free(buf);
             }
-            free(h);
+            // Free wait group sync
+            if (h->type_tag == GC_TAG_WAITGROUP && h->size >= 16) {
+                long *wg = (long *)((char *)h + sizeof(GCHeader));
+                void *sync = (void *)wg[0];
+                if (sync) {
+#ifndef PLUTO_TEST_MODE
+                    WaitGroupSync *ws = (WaitGroupSync *)sync;
+                    pthread_mutex_destroy(&ws->mutex);
+                    pthread_cond_destroy(&ws->zero);
+#endif
+                    free(sync);
+                }
+            }
+            // Free mutex sync
+            if (h->type_tag == GC_TAG_MUTEX && h->size >= 24) {
+                long *m = (long *)((char *)h + sizeof(GCHeader));
+                void *sync = (void *)m[1];
+                if (sync) {
+#ifndef PLUTO_TEST_MODE
+                    pthread_mutex_destroy((pthread_mutex_t *)sync);
+#endif
+                    free(sync);
+                }
+            }
+            gc_store_free(h);
             freed_bytes += total;
         } else {
             h->mark = 0;  // Clear for next cycle
@@ -760,9 +862,10 @@ void __pluto_gc_collect(void) {
     }
 
     gc_bytes_allocated -= freed_bytes;
+    gc_store_after_sweep();
     size_t surviving = gc_bytes_allocated;
-    gc_threshold = surviving * 2;
-    if (gc_threshold < 256 * 1024) gc_threshold = 256 * 1024;
+    gc_threshold = (size_t)((double)surviving * PLUTO_GC_GROWTH_FACTOR);
+    if (gc_threshold < PLUTO_GC_INITIAL_HEAP) gc_threshold = PLUTO_GC_INITIAL_HEAP;
 
     // Free interval tables and worklist
     free(gc_intervals);
@@ -776,6 +879,12 @@ void __pluto_gc_collect(void) {
     gc_worklist_count = 0;
     gc_worklist_cap = 0;
 
+    struct timespec pause_end;
+    clock_gettime(CLOCK_MONOTONIC, &pause_end);
+    gc_pause_ns += (pause_end.tv_sec - pause_start.tv_sec) * 1000000000L
+        + (pause_end.tv_nsec - pause_start.tv_nsec);
+    gc_collections++;
+
     gc_collecting = 0;
 }
 
@@ -829,7 +938,7 @@ size_t __pluto_gc_bytes_allocated(void) {
 #else
 // Production mode helpers
 int __pluto_gc_check_safepoint(void) {
-    return atomic_load(&gc_safepoint_requested);
+    return atomic_load(&__pluto_safepoint_requested) & PLUTO_SAFEPOINT_GC;
 }
 
 void __pluto_gc_maybe_collect(void) {
@@ -844,3 +953,15 @@ size_t __pluto_gc_bytes_allocated(void) {
     return gc_bytes_allocated;
 }
 #endif
+
+size_t __pluto_gc_total_allocated(void) {
+    return gc_total_allocated;
+}
+
+long __pluto_gc_collections(void) {
+    return gc_collections;
+}
+
+long __pluto_gc_pause_ns(void) {
+    return gc_pause_ns;
+}
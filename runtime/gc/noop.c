@@ -17,6 +17,7 @@
 
 static GCHeader *gc_head = NULL;
 static size_t gc_bytes_allocated = 0;
+static size_t gc_total_allocated = 0;
 
 // TLS variables used by threading.c and builtins.c — must be defined by the GC module
 __thread void *__pluto_current_error = NULL;
@@ -34,8 +35,23 @@ void __pluto_gc_collect(void) {
     // No-op: never collect
 }
 
+void __pluto_gc_force_collect(void) {
+    // No-op: never collect
+}
+
+// Polled by generated code. Only PLUTO_SAFEPOINT_CANCEL is ever set, since
+// nothing stops the world.
+atomic_int __pluto_safepoint_requested = 0;
+
 void __pluto_safepoint(void) {
-    // No-op: no STW coordination needed
+    // No STW coordination needed, only cancellation and run slots
+    int requested = atomic_load(&__pluto_safepoint_requested);
+    if (requested & PLUTO_SAFEPOINT_CANCEL) {
+        __pluto_task_poll_cancel();
+    }
+    if (requested & PLUTO_SAFEPOINT_YIELD) {
+        __pluto_task_poll_yield();
+    }
 }
 
 void *__pluto_alloc(long size) {
@@ -55,6 +71,7 @@ void *gc_alloc(size_t user_size, uint8_t type_tag, uint16_t field_count) {
     header->next = gc_head;
     gc_head = header;
     gc_bytes_allocated += user_size + sizeof(GCHeader);
+    gc_total_allocated += user_size + sizeof(GCHeader);
     void *user_data = (void *)(header + 1);
     memset(user_data, 0, user_size);
     return user_data;
@@ -64,6 +81,18 @@ size_t __pluto_gc_bytes_allocated(void) {
     return gc_bytes_allocated;
 }
 
+size_t __pluto_gc_total_allocated(void) {
+    return gc_total_allocated;
+}
+
+long __pluto_gc_collections(void) {
+    return 0;  // Never collects
+}
+
+long __pluto_gc_pause_ns(void) {
+    return 0;
+}
+
 void __pluto_gc_maybe_collect(void) {
     // No-op
 }
@@ -5,13 +5,21 @@
 //
 // Design:
 // - Test mode: Cooperative fiber scheduler with exhaustive DPOR state exploration
-// - Production mode: Pthread-based tasks with mutex-protected channels
+// - Production mode: Pthread-based tasks with mutex-protected channels; PLUTO_THREADS
+//   caps how many run at once
 // - Deep copy semantics for spawn arguments (value isolation between tasks)
 // - Rwlock synchronization for contract enforcement on shared objects
 //
 // API:
-// - Tasks: __pluto_task_spawn, __pluto_task_get, __pluto_task_detach, __pluto_task_cancel
-// - Channels: __pluto_chan_create, __pluto_chan_send, __pluto_chan_recv, __pluto_chan_close
+// - Tasks: __pluto_task_spawn, __pluto_task_get, __pluto_task_get_infallible, __pluto_task_detach, __pluto_task_cancel
+// - Cancellation: __pluto_task_poll_cancel (from __pluto_safepoint), __pluto_task_unwinding
+// - Scheduling: __pluto_num_cpus, __pluto_task_poll_yield (from __pluto_safepoint),
+//               __pluto_task_block, __pluto_task_unblock, __pluto_task_yield (PLUTO_THREADS run slots)
+// - Channels: __pluto_chan_create, __pluto_chan_send, __pluto_chan_recv, __pluto_chan_recv_timeout, __pluto_chan_close,
+//             __pluto_chan_len, __pluto_chan_is_full
+// - Wait groups: __pluto_waitgroup_new, __pluto_waitgroup_add, __pluto_waitgroup_done, __pluto_waitgroup_wait
+// - Atomics: __pluto_atomic_new, __pluto_atomic_load, __pluto_atomic_store, __pluto_atomic_add,
+//            __pluto_atomic_compare_and_swap
 // - Select: __pluto_select_init, __pluto_select_add_recv, __pluto_select_add_send, __pluto_select_wait
 // - Sync: __pluto_rwlock_* (for contract invariants on concurrent objects)
 //──────────────────────────────────────────────────────────────────────────────
@@ -27,7 +35,8 @@
 //   [3] done      (i64)
 //   [4] sync_ptr  (i64, raw malloc — NULL in test mode)
 //   [5] detached  (i64, 0 or 1)
-//   [6] cancelled (i64, 0 or 1)
+//   [6] cancelled (i64: 0; 1 if cancelled while running; 2 once a safepoint
+//       has raised the cancellation in the task)
 
 static void task_raise_cancelled(void) {
     const char *msg = "task cancelled";
@@ -35,6 +44,31 @@ static void task_raise_cancelled(void) {
     void *err_obj = __pluto_alloc(8);  // 1 field: message
     *(long *)err_obj = (long)msg_str;
     __pluto_raise_error(err_obj);
+    __pluto_set_error_type(__pluto_string_new("TaskCancelled", 13));
+}
+
+// Called from __pluto_safepoint while PLUTO_SAFEPOINT_CANCEL is set: raise
+// TaskCancelled if the current task is the one that was cancelled. Only once,
+// so the `finally` blocks run while unwinding aren't interrupted in turn.
+void __pluto_task_poll_cancel(void) {
+    if (__pluto_current_task && __pluto_current_task[6] == 1 && !__pluto_current_error) {
+        __pluto_current_task[6] = 2;
+        task_raise_cancelled();
+    }
+}
+
+// Whether the current task is unwinding: it has been cancelled and an error is
+// pending. Generated code checks this after safepoints and calls, and leaves
+// the function through its `finally` blocks and guard releases. A cancelled
+// task's errors can no longer be caught.
+long __pluto_task_unwinding(void) {
+    return __pluto_current_task && __pluto_current_task[6] && __pluto_current_error ? 1 : 0;
+}
+
+// Online CPUs; the default for PLUTO_THREADS.
+long __pluto_num_cpus(void) {
+    long n = sysconf(_SC_NPROCESSORS_ONLN);
+    return n > 0 ? n : 1;
 }
 
 #ifdef PLUTO_TEST_MODE
@@ -49,7 +83,7 @@ typedef enum {
     FIBER_READY=0, FIBER_RUNNING=1,
     FIBER_BLOCKED_TASK=2, FIBER_BLOCKED_CHAN_SEND=3,
     FIBER_BLOCKED_CHAN_RECV=4, FIBER_BLOCKED_SELECT=5,
-    FIBER_COMPLETED=6
+    FIBER_BLOCKED_WAITGROUP=6, FIBER_BLOCKED_MUTEX=7, FIBER_COMPLETED=8
 } FiberState;
 
 typedef struct {
@@ -58,7 +92,7 @@ typedef struct {
     FiberState state;
     long *task;              // associated task handle (NULL for fiber 0 / main test fiber)
     long closure_ptr;        // closure to execute (for spawned fibers)
-    void *blocked_on;        // task handle or channel handle we're waiting on
+    void *blocked_on;        // task, channel, or wait group handle we're waiting on
     long blocked_value;      // value for pending send
     int id;
     // Per-fiber saved TLS state (restored on context switch)
@@ -123,6 +157,19 @@ static void scheduler_run(void);
 static void fiber_yield_to_scheduler(void);
 static void test_main_fiber_entry(void);
 
+// ── Sequential-mode deadlock reporting ──────────────────────────────────────
+
+// Sequential mode runs each spawned task to completion at its spawn, so an
+// operation that would block can never be unblocked: report it and exit.
+static void seq_deadlock(const char *op) {
+    fprintf(stderr, "pluto: deadlock detected — %s in sequential test mode\n", op);
+    if (__pluto_current_task) {
+        fprintf(stderr, "  note: the Sequential scheduler runs spawned tasks to completion at spawn; "
+                        "use tests[scheduler: RoundRobin] to let a task wait for a later send\n");
+    }
+    exit(1);
+}
+
 // ── Fiber helper functions ──────────────────────────────────────────────────
 
 static void wake_fibers_blocked_on_task(long *task_ptr) {
@@ -162,6 +209,28 @@ static void wake_select_fibers_for_chan(long *ch_ptr) {
     }
 }
 
+static void wake_fibers_blocked_on_waitgroup(long *wg_ptr) {
+    if (!g_scheduler) return;
+    for (int i = 0; i < g_scheduler->fiber_count; i++) {
+        Fiber *f = &g_scheduler->fibers[i];
+        if (f->state == FIBER_BLOCKED_WAITGROUP && f->blocked_on == (void *)wg_ptr) {
+            f->state = FIBER_READY;
+            f->blocked_on = NULL;
+        }
+    }
+}
+
+static void wake_fibers_blocked_on_mutex(long *mutex_ptr) {
+    if (!g_scheduler) return;
+    for (int i = 0; i < g_scheduler->fiber_count; i++) {
+        Fiber *f = &g_scheduler->fibers[i];
+        if (f->state == FIBER_BLOCKED_MUTEX && f->blocked_on == (void *)mutex_ptr) {
+            f->state = FIBER_READY;
+            f->blocked_on = NULL;
+        }
+    }
+}
+
 static uint64_t lcg_next(uint64_t *seed) {
     *seed = (*seed) * 6364136223846793005ULL + 1442695040888963407ULL;
     return *seed;
@@ -370,13 +439,15 @@ static void scheduler_run(void) {
             fprintf(stderr, "pluto: deadlock detected in test\n");
             for (int i = 0; i < g_scheduler->fiber_count; i++) {
                 Fiber *f = &g_scheduler->fibers[i];
-                if (f->state >= FIBER_BLOCKED_TASK && f->state <= FIBER_BLOCKED_SELECT) {
+                if (f->state >= FIBER_BLOCKED_TASK && f->state <= FIBER_BLOCKED_MUTEX) {
                     const char *reason = "unknown";
                     switch (f->state) {
                         case FIBER_BLOCKED_TASK:      reason = "task.get()"; break;
                         case FIBER_BLOCKED_CHAN_SEND:  reason = "chan.send()"; break;
                         case FIBER_BLOCKED_CHAN_RECV:  reason = "chan.recv()"; break;
                         case FIBER_BLOCKED_SELECT:     reason = "select"; break;
+                        case FIBER_BLOCKED_WAITGROUP:  reason = "WaitGroup.wait()"; break;
+                        case FIBER_BLOCKED_MUTEX:      reason = "Mutex.lock()"; break;
                         default: break;
                     }
                     fprintf(stderr, "  Fiber %d: blocked on %s\n", i, reason);
@@ -675,7 +746,8 @@ long __pluto_task_get(long task_ptr) {
     }
     // Task is done (either was already done, or we waited)
     if (task[2]) {
-        __pluto_current_error = (void *)task[2];
+        // Re-raise in the waiting thread so debug traces point at the .get()
+        __pluto_raise_error((void *)task[2]);
         return 0;
     }
     return task[1];
@@ -697,7 +769,21 @@ void __pluto_task_detach(long task_ptr) {
 
 void __pluto_task_cancel(long task_ptr) {
     long *task = (long *)task_ptr;
-    task[6] = 1;
+    if (!task[3]) task[6] = 1;  // a finished task has nothing to interrupt
+}
+
+// Fibers take turns already; there are no run slots to hand over.
+void __pluto_task_poll_yield(void) {}
+void __pluto_task_block(void) {}
+void __pluto_task_unblock(void) {}
+
+// Let other fibers run; the current one stays runnable. Sequential mode has
+// no one to yield to.
+void __pluto_task_yield(void) {
+    if (g_scheduler && g_scheduler->strategy != STRATEGY_SEQUENTIAL) {
+        g_scheduler->fibers[g_scheduler->current_fiber].state = FIBER_READY;
+        fiber_yield_to_scheduler();
+    }
 }
 
 #else
@@ -709,6 +795,136 @@ typedef struct {
     pthread_cond_t cond;
 } TaskSync;
 
+// Cancelled tasks that haven't stopped yet. While there are any,
+// PLUTO_SAFEPOINT_CANCEL sends every thread's safepoints to the slow path.
+static atomic_int task_cancels_pending = 0;
+
+static void task_cancel_finished(void) {
+    if (atomic_fetch_sub(&task_cancels_pending, 1) == 1) {
+        atomic_fetch_and(&__pluto_safepoint_requested, ~PLUTO_SAFEPOINT_CANCEL);
+        // A task cancelled meanwhile may have set the bit before it was cleared
+        if (atomic_load(&task_cancels_pending) > 0) {
+            atomic_fetch_or(&__pluto_safepoint_requested, PLUTO_SAFEPOINT_CANCEL);
+        }
+    }
+}
+
+// ── Run slots ──
+// At most PLUTO_THREADS threads (default: one per CPU) run Pluto code at once,
+// the main thread included. Every task still gets its own pthread, but a task
+// thread takes a run slot before starting, gives it up while it blocks (get,
+// channel ops, wait groups, mutexes, select, sleep) and takes one back before
+// carrying on. Slots are handed out first come, first served, and a thread that
+// has run for RUN_SLICE_NS while others are waiting passes its slot on at its
+// next safepoint, so with PLUTO_THREADS=1 tasks take turns in a steady order.
+
+#define RUN_SLICE_NS 10000000L  // 10ms
+
+static pthread_mutex_t run_mutex = PTHREAD_MUTEX_INITIALIZER;
+static pthread_cond_t run_cond = PTHREAD_COND_INITIALIZER;
+static int run_limit = 1;
+static int run_active = 1;          // the main thread starts with a slot
+static long run_next_ticket = 0;
+static long run_now_serving = 0;
+static __thread int run_held = 0;   // whether this thread holds a slot
+static __thread long run_since_ns;  // when it took the slot
+
+__attribute__((constructor))
+static void run_slots_init(void) {
+    run_limit = (int)__pluto_num_cpus();
+    const char *env = getenv("PLUTO_THREADS");
+    if (env && *env) {
+        char *end;
+        long n = strtol(env, &end, 10);
+        if (*end || n < 1 || n > INT_MAX) {
+            fprintf(stderr, "pluto: PLUTO_THREADS must be a positive integer, got '%s'\n", env);
+            exit(1);
+        }
+        run_limit = (int)n;
+    }
+    // Constructors run on the main thread
+    run_held = 1;
+    run_since_ns = __pluto_time_ns();
+}
+
+static void run_slot_acquire(void) {
+    pthread_mutex_lock(&run_mutex);
+    long ticket = run_next_ticket++;
+    if (ticket != run_now_serving || run_active >= run_limit) {
+        // Ask running threads to pass their slots on once their slice is up
+        atomic_fetch_or(&__pluto_safepoint_requested, PLUTO_SAFEPOINT_YIELD);
+        while (ticket != run_now_serving || run_active >= run_limit) {
+            struct timespec ts;
+            clock_gettime(CLOCK_REALTIME, &ts);
+            ts.tv_nsec += 10000000;  // 10ms timeout, for safepoint checks
+            if (ts.tv_nsec >= 1000000000) {
+                ts.tv_sec += 1;
+                ts.tv_nsec -= 1000000000;
+            }
+            pthread_cond_timedwait(&run_cond, &run_mutex, &ts);
+            if (__pluto_gc_check_safepoint()) {
+                pthread_mutex_unlock(&run_mutex);
+                __pluto_safepoint();
+                pthread_mutex_lock(&run_mutex);
+            }
+        }
+    }
+    run_now_serving++;
+    run_active++;
+    if (run_now_serving == run_next_ticket) {
+        atomic_fetch_and(&__pluto_safepoint_requested, ~PLUTO_SAFEPOINT_YIELD);
+    }
+    // The next ticket may fit in a slot too
+    pthread_cond_broadcast(&run_cond);
+    pthread_mutex_unlock(&run_mutex);
+    run_held = 1;
+    run_since_ns = __pluto_time_ns();
+}
+
+static void run_slot_release(void) {
+    run_held = 0;
+    pthread_mutex_lock(&run_mutex);
+    run_active--;
+    pthread_cond_broadcast(&run_cond);
+    pthread_mutex_unlock(&run_mutex);
+}
+
+// pthread_cond_wait without a run slot. The slot is taken back with `mutex`
+// unlocked, so the thread can't hold up whoever has a slot and needs `mutex`;
+// callers re-check their condition after waking anyway.
+static int run_cond_wait(pthread_cond_t *cond, pthread_mutex_t *mutex, const struct timespec *deadline) {
+    run_slot_release();
+    int rc = deadline ? pthread_cond_timedwait(cond, mutex, deadline) : pthread_cond_wait(cond, mutex);
+    pthread_mutex_unlock(mutex);
+    run_slot_acquire();
+    pthread_mutex_lock(mutex);
+    return rc;
+}
+
+// Called from __pluto_safepoint while PLUTO_SAFEPOINT_YIELD is set: threads
+// are waiting for a slot, so pass this one on if its slice is up.
+void __pluto_task_poll_yield(void) {
+    if (run_held && __pluto_time_ns() - run_since_ns >= RUN_SLICE_NS) {
+        run_slot_release();
+        run_slot_acquire();
+    }
+}
+
+// For blocking calls outside this file (sleep)
+void __pluto_task_block(void) {
+    run_slot_release();
+}
+
+void __pluto_task_unblock(void) {
+    run_slot_acquire();
+}
+
+// Let other threads waiting for a slot run first
+void __pluto_task_yield(void) {
+    run_slot_release();
+    run_slot_acquire();
+}
+
 static void *__pluto_spawn_trampoline(void *arg) {
     long *task = (long *)arg;
     long closure_ptr = task[0];
@@ -736,17 +952,20 @@ static void *__pluto_spawn_trampoline(void *arg) {
         __pluto_gc_register_thread_stack(stack_lo, stack_hi);
     }
 
+    run_slot_acquire();
     long fn_ptr = *(long *)closure_ptr;
     long result = ((long(*)(long))fn_ptr)(closure_ptr);
 
     TaskSync *sync = (TaskSync *)task[4];
     pthread_mutex_lock(&sync->mutex);
     if (__pluto_current_error) {
-        task[2] = (long)__pluto_current_error;
+        // A cancelled task unwound with its cancellation; get() raises TaskCancelled
+        if (!task[6]) task[2] = (long)__pluto_current_error;
         __pluto_current_error = NULL;
     } else {
         task[1] = result;
     }
+    if (task[6]) task_cancel_finished();
     task[3] = 1;  // done
     // If detached and errored, print to stderr
     if (task[5] && task[2]) {
@@ -761,11 +980,13 @@ static void *__pluto_spawn_trampoline(void *arg) {
     }
     pthread_cond_signal(&sync->cond);
     pthread_mutex_unlock(&sync->mutex);
+    run_slot_release();
 
-    // Deregister thread stack from GC
+    // Deregister thread stack from GC. The task is over, so safepoints taken
+    // while waiting to deregister don't raise its cancellation.
+    __pluto_current_task = NULL;
     __pluto_gc_deregister_thread_stack();
 
-    __pluto_current_task = NULL;
     __pluto_gc_task_end();
     return NULL;
 }
@@ -800,6 +1021,7 @@ long __pluto_task_get(long task_ptr) {
     long *task = (long *)task_ptr;
     TaskSync *sync = (TaskSync *)task[4];
 
+    run_slot_release();
     pthread_mutex_lock(&sync->mutex);
     while (!task[3]) {
         // Use timed wait with short timeout to allow safepoint checks
@@ -818,8 +1040,17 @@ long __pluto_task_get(long task_ptr) {
             __pluto_safepoint();
             pthread_mutex_lock(&sync->mutex);
         }
+
+        // A cancelled waiter stops waiting, like a blocked channel op
+        if (__pluto_current_task && __pluto_current_task[6]) {
+            pthread_mutex_unlock(&sync->mutex);
+            run_slot_acquire();
+            task_raise_cancelled();
+            return 0;
+        }
     }
     pthread_mutex_unlock(&sync->mutex);
+    run_slot_acquire();
 
     // If cancelled and no result, raise TaskCancelled
     if (task[6] && !task[1] && !task[2]) {
@@ -828,7 +1059,8 @@ long __pluto_task_get(long task_ptr) {
     }
 
     if (task[2]) {
-        __pluto_current_error = (void *)task[2];
+        // Re-raise in the waiting thread so debug traces point at the .get()
+        __pluto_raise_error((void *)task[2]);
         return 0;
     }
     return task[1];
@@ -855,16 +1087,34 @@ void __pluto_task_detach(long task_ptr) {
 
 void __pluto_task_cancel(long task_ptr) {
     long *task = (long *)task_ptr;
-    task[6] = 1;  // set cancelled flag
-    // Wake the task thread if it's blocked on its own sync (for .get() waiters)
     TaskSync *sync = (TaskSync *)task[4];
     pthread_mutex_lock(&sync->mutex);
+    // A finished task has nothing to interrupt. A running one stops at its next
+    // safepoint (see __pluto_task_poll_cancel).
+    if (!task[3] && !task[6]) {
+        task[6] = 1;  // set cancelled flag
+        atomic_fetch_add(&task_cancels_pending, 1);
+        atomic_fetch_or(&__pluto_safepoint_requested, PLUTO_SAFEPOINT_CANCEL);
+    }
+    // Wake the task thread if it's blocked on its own sync (for .get() waiters)
     pthread_cond_broadcast(&sync->cond);
     pthread_mutex_unlock(&sync->mutex);
 }
 
 #endif
 
+// Task.get() where the task's function can't raise. The caller doesn't check
+// for errors, so a cancelled task's TaskCancelled can't be handled and ends the program.
+long __pluto_task_get_infallible(long task_ptr) {
+    long result = __pluto_task_get(task_ptr);
+    if (__pluto_current_error && !__pluto_task_unwinding()) {
+        fflush(stdout);
+        fprintf(stderr, "pluto: get() on a cancelled task: uncaught TaskCancelled\n");
+        exit(1);
+    }
+    return result;
+}
+
 // ── Deep Copy (for spawn isolation) ──────────────────────────────────────────
 
 // Visited table for cycle detection during deep copy
@@ -945,7 +1195,10 @@ static long dc_deep_copy_impl(long ptr, DeepCopyVisited *visited) {
 
     case GC_TAG_TASK:
     case GC_TAG_CHANNEL:
-        // Tasks and channels are shared by reference
+    case GC_TAG_WAITGROUP:
+    case GC_TAG_ATOMIC:
+    case GC_TAG_MUTEX:
+        // Tasks, channels, wait groups, atomics and mutexes are shared by reference
         return ptr;
 
     case GC_TAG_OBJECT: {
@@ -1095,11 +1348,14 @@ long __pluto_deep_copy(long ptr) {
 //   [5] tail       (int, write position)
 //   [6] closed     (int, 0 or 1)
 
-static void chan_raise_error(const char *msg) {
+// Raise a builtin channel error. The type name is recorded so typed catches
+// (`catch err: ChannelClosed`) can tell the channel errors apart.
+static void chan_raise_error(const char *type_name, const char *msg) {
     void *msg_str = __pluto_string_new((char *)msg, (long)strlen(msg));
     void *err_obj = __pluto_alloc(8);  // 1 field: message
     *(long *)err_obj = (long)msg_str;
     __pluto_raise_error(err_obj);
+    __pluto_set_error_type(__pluto_string_new((char *)type_name, (long)strlen(type_name)));
 }
 
 #ifdef PLUTO_TEST_MODE
@@ -1131,7 +1387,7 @@ long __pluto_chan_send(long handle, long value) {
         // Fiber mode: yield when buffer is full
         while (1) {
             if (ch[6]) {
-                chan_raise_error("channel closed");
+                chan_raise_error("ChannelClosed", "channel closed");
                 return 0;
             }
             if (ch[3] < ch[2]) {
@@ -1157,12 +1413,11 @@ long __pluto_chan_send(long handle, long value) {
 
     // Sequential mode
     if (ch[6]) {
-        chan_raise_error("channel closed");
+        chan_raise_error("ChannelClosed", "channel closed");
         return 0;
     }
     if (ch[3] == ch[2]) {
-        fprintf(stderr, "pluto: deadlock detected — channel send on full buffer in sequential test mode\n");
-        exit(1);
+        seq_deadlock("channel send on full buffer");
     }
     long *buf = (long *)ch[1];
     buf[ch[5]] = value;
@@ -1192,7 +1447,7 @@ long __pluto_chan_recv(long handle) {
                 return val;
             }
             if (ch[6]) {
-                chan_raise_error("channel closed");
+                chan_raise_error("ChannelClosed", "channel closed");
                 return 0;
             }
             // Buffer empty — yield
@@ -1206,12 +1461,11 @@ long __pluto_chan_recv(long handle) {
 
     // Sequential mode
     if (ch[3] == 0 && ch[6]) {
-        chan_raise_error("channel closed");
+        chan_raise_error("ChannelClosed", "channel closed");
         return 0;
     }
     if (ch[3] == 0) {
-        fprintf(stderr, "pluto: deadlock detected — channel recv on empty buffer in sequential test mode\n");
-        exit(1);
+        seq_deadlock("channel recv on empty buffer");
     }
     long *buf = (long *)ch[1];
     long val = buf[ch[4]];
@@ -1223,11 +1477,11 @@ long __pluto_chan_recv(long handle) {
 long __pluto_chan_try_send(long handle, long value) {
     long *ch = (long *)handle;
     if (ch[6]) {
-        chan_raise_error("channel closed");
+        chan_raise_error("ChannelClosed", "channel closed");
         return 0;
     }
     if (ch[3] == ch[2]) {
-        chan_raise_error("channel full");
+        chan_raise_error("ChannelFull", "channel full");
         return 0;
     }
     long *buf = (long *)ch[1];
@@ -1244,11 +1498,11 @@ long __pluto_chan_try_send(long handle, long value) {
 long __pluto_chan_try_recv(long handle) {
     long *ch = (long *)handle;
     if (ch[3] == 0 && ch[6]) {
-        chan_raise_error("channel closed");
+        chan_raise_error("ChannelClosed", "channel closed");
         return 0;
     }
     if (ch[3] == 0) {
-        chan_raise_error("channel empty");
+        chan_raise_error("ChannelEmpty", "channel empty");
         return 0;
     }
     long *buf = (long *)ch[1];
@@ -1262,6 +1516,64 @@ long __pluto_chan_try_recv(long handle) {
     return val;
 }
 
+long __pluto_chan_recv_timeout(long handle, long timeout_ms) {
+    long *ch = (long *)handle;
+
+    if (g_scheduler && g_scheduler->strategy != STRATEGY_SEQUENTIAL) {
+        exhaustive_record_channel(g_scheduler->current_fiber, (void *)ch);
+
+        // Fiber mode: stay runnable and yield until data, close, or the deadline
+        long deadline = __pluto_time_ns() + (timeout_ms > 0 ? timeout_ms : 0) * 1000000L;
+        while (1) {
+            if (ch[3] > 0) {
+                long *buf = (long *)ch[1];
+                long val = buf[ch[4]];
+                ch[4] = (ch[4] + 1) % ch[2];
+                ch[3]--;
+                wake_fibers_blocked_on_chan(ch);
+                wake_select_fibers_for_chan(ch);
+                return val;
+            }
+            if (ch[6]) {
+                chan_raise_error("ChannelClosed", "channel closed");
+                return 0;
+            }
+            if (__pluto_time_ns() >= deadline) {
+                chan_raise_error("ChannelTimeout", "channel receive timed out");
+                return 0;
+            }
+            g_scheduler->fibers[g_scheduler->current_fiber].state = FIBER_READY;
+            fiber_yield_to_scheduler();
+        }
+    }
+
+    // Sequential mode: nothing else can send, so an empty channel times out at once
+    if (ch[3] == 0 && ch[6]) {
+        chan_raise_error("ChannelClosed", "channel closed");
+        return 0;
+    }
+    if (ch[3] == 0) {
+        chan_raise_error("ChannelTimeout", "channel receive timed out");
+        return 0;
+    }
+    long *buf = (long *)ch[1];
+    long val = buf[ch[4]];
+    ch[4] = (ch[4] + 1) % ch[2];
+    ch[3]--;
+    return val;
+}
+
+// Single-threaded scheduler: the counters can be read without locking
+long __pluto_chan_len(long handle) {
+    long *ch = (long *)handle;
+    return ch[3];
+}
+
+long __pluto_chan_is_full(long handle) {
+    long *ch = (long *)handle;
+    return ch[3] == ch[2] ? 1 : 0;
+}
+
 void __pluto_chan_close(long handle) {
     long *ch = (long *)handle;
     ch[6] = 1;
@@ -1325,7 +1637,7 @@ long __pluto_chan_send(long handle, long value) {
 
     pthread_mutex_lock(&sync->mutex);
     while (ch[3] == ch[2] && !ch[6]) {
-        pthread_cond_wait(&sync->not_full, &sync->mutex);
+        run_cond_wait(&sync->not_full, &sync->mutex, NULL);
         // Check for task cancellation after waking from condvar
         if (__pluto_current_task && __pluto_current_task[6]) {
             pthread_mutex_unlock(&sync->mutex);
@@ -1335,7 +1647,7 @@ long __pluto_chan_send(long handle, long value) {
     }
     if (ch[6]) {
         pthread_mutex_unlock(&sync->mutex);
-        chan_raise_error("channel closed");
+        chan_raise_error("ChannelClosed", "channel closed");
         return 0;
     }
     long *buf = (long *)ch[1];
@@ -1353,7 +1665,7 @@ long __pluto_chan_recv(long handle) {
 
     pthread_mutex_lock(&sync->mutex);
     while (ch[3] == 0 && !ch[6]) {
-        pthread_cond_wait(&sync->not_empty, &sync->mutex);
+        run_cond_wait(&sync->not_empty, &sync->mutex, NULL);
         // Check for task cancellation after waking from condvar
         if (__pluto_current_task && __pluto_current_task[6]) {
             pthread_mutex_unlock(&sync->mutex);
@@ -1363,7 +1675,7 @@ long __pluto_chan_recv(long handle) {
     }
     if (ch[3] == 0 && ch[6]) {
         pthread_mutex_unlock(&sync->mutex);
-        chan_raise_error("channel closed");
+        chan_raise_error("ChannelClosed", "channel closed");
         return 0;
     }
     long *buf = (long *)ch[1];
@@ -1382,12 +1694,12 @@ long __pluto_chan_try_send(long handle, long value) {
     pthread_mutex_lock(&sync->mutex);
     if (ch[6]) {
         pthread_mutex_unlock(&sync->mutex);
-        chan_raise_error("channel closed");
+        chan_raise_error("ChannelClosed", "channel closed");
         return 0;
     }
     if (ch[3] == ch[2]) {
         pthread_mutex_unlock(&sync->mutex);
-        chan_raise_error("channel full");
+        chan_raise_error("ChannelFull", "channel full");
         return 0;
     }
     long *buf = (long *)ch[1];
@@ -1406,12 +1718,58 @@ long __pluto_chan_try_recv(long handle) {
     pthread_mutex_lock(&sync->mutex);
     if (ch[3] == 0 && ch[6]) {
         pthread_mutex_unlock(&sync->mutex);
-        chan_raise_error("channel closed");
+        chan_raise_error("ChannelClosed", "channel closed");
+        return 0;
+    }
+    if (ch[3] == 0) {
+        pthread_mutex_unlock(&sync->mutex);
+        chan_raise_error("ChannelEmpty", "channel empty");
         return 0;
     }
+    long *buf = (long *)ch[1];
+    long val = buf[ch[4]];
+    ch[4] = (ch[4] + 1) % ch[2];
+    ch[3]--;
+    pthread_cond_signal(&sync->not_full);
+    pthread_mutex_unlock(&sync->mutex);
+    return val;
+}
+
+long __pluto_chan_recv_timeout(long handle, long timeout_ms) {
+    long *ch = (long *)handle;
+    ChannelSync *sync = (ChannelSync *)ch[0];
+
+    // Condition variables wait on CLOCK_REALTIME by default
+    struct timespec deadline;
+    clock_gettime(CLOCK_REALTIME, &deadline);
+    if (timeout_ms > 0) {
+        deadline.tv_sec += timeout_ms / 1000;
+        deadline.tv_nsec += (timeout_ms % 1000) * 1000000L;
+        if (deadline.tv_nsec >= 1000000000L) {
+            deadline.tv_sec++;
+            deadline.tv_nsec -= 1000000000L;
+        }
+    }
+
+    pthread_mutex_lock(&sync->mutex);
+    int rc = 0;
+    while (ch[3] == 0 && !ch[6] && rc != ETIMEDOUT) {
+        rc = run_cond_wait(&sync->not_empty, &sync->mutex, &deadline);
+        if (__pluto_current_task && __pluto_current_task[6]) {
+            pthread_mutex_unlock(&sync->mutex);
+            task_raise_cancelled();
+            return 0;
+        }
+    }
+    // Buffered values are still delivered after a close; the close only
+    // wins over the timeout when the channel is empty
     if (ch[3] == 0) {
         pthread_mutex_unlock(&sync->mutex);
-        chan_raise_error("channel empty");
+        if (ch[6]) {
+            chan_raise_error("ChannelClosed", "channel closed");
+        } else {
+            chan_raise_error("ChannelTimeout", "channel receive timed out");
+        }
         return 0;
     }
     long *buf = (long *)ch[1];
@@ -1423,6 +1781,27 @@ long __pluto_chan_try_recv(long handle) {
     return val;
 }
 
+// Snapshots only: another thread may send or receive as soon as the lock drops
+long __pluto_chan_len(long handle) {
+    long *ch = (long *)handle;
+    ChannelSync *sync = (ChannelSync *)ch[0];
+
+    pthread_mutex_lock(&sync->mutex);
+    long count = ch[3];
+    pthread_mutex_unlock(&sync->mutex);
+    return count;
+}
+
+long __pluto_chan_is_full(long handle) {
+    long *ch = (long *)handle;
+    ChannelSync *sync = (ChannelSync *)ch[0];
+
+    pthread_mutex_lock(&sync->mutex);
+    long full = ch[3] == ch[2] ? 1 : 0;
+    pthread_mutex_unlock(&sync->mutex);
+    return full;
+}
+
 void __pluto_chan_close(long handle) {
     long *ch = (long *)handle;
     ChannelSync *sync = (ChannelSync *)ch[0];
@@ -1456,6 +1835,210 @@ void __pluto_chan_sender_dec(long handle) {
 
 #endif
 
+// ── Wait groups ─────────────────────────────────────────────────────────────
+
+// Wait group handle layout (16 bytes, 2 slots):
+//   [0] sync_ptr   (raw malloc'd WaitGroupSync; 0 in test mode)
+//   [1] count      (int, outstanding done() calls)
+
+static void waitgroup_negative(void) {
+    fprintf(stderr, "pluto: WaitGroup counter went negative\n");
+    exit(1);
+}
+
+#ifdef PLUTO_TEST_MODE
+
+long __pluto_waitgroup_new(void) {
+    long *wg = (long *)gc_alloc(16, GC_TAG_WAITGROUP, 0);
+    wg[0] = 0;  // no sync needed in test mode
+    wg[1] = 0;
+    return (long)wg;
+}
+
+void __pluto_waitgroup_add(long handle, long delta) {
+    long *wg = (long *)handle;
+    if (g_scheduler) exhaustive_record_channel(g_scheduler->current_fiber, (void *)wg);
+    wg[1] += delta;
+    if (wg[1] < 0) waitgroup_negative();
+    if (wg[1] == 0) wake_fibers_blocked_on_waitgroup(wg);
+}
+
+void __pluto_waitgroup_done(long handle) {
+    __pluto_waitgroup_add(handle, -1);
+}
+
+void __pluto_waitgroup_wait(long handle) {
+    long *wg = (long *)handle;
+    if (g_scheduler && g_scheduler->strategy != STRATEGY_SEQUENTIAL) {
+        exhaustive_record_channel(g_scheduler->current_fiber, (void *)wg);
+        while (wg[1] > 0) {
+            Fiber *cur = &g_scheduler->fibers[g_scheduler->current_fiber];
+            cur->state = FIBER_BLOCKED_WAITGROUP;
+            cur->blocked_on = (void *)wg;
+            fiber_yield_to_scheduler();
+        }
+        return;
+    }
+    // Sequential mode: spawned tasks have already run to completion, so a
+    // nonzero count can never drop
+    if (wg[1] > 0) {
+        char op[64];
+        snprintf(op, sizeof(op), "WaitGroup.wait() with %ld outstanding", wg[1]);
+        seq_deadlock(op);
+    }
+}
+
+#else
+
+long __pluto_waitgroup_new(void) {
+    // field_count=0: slot 0 is a raw malloc ptr, slot 1 is an int
+    long *wg = (long *)gc_alloc(16, GC_TAG_WAITGROUP, 0);
+    WaitGroupSync *sync = (WaitGroupSync *)calloc(1, sizeof(WaitGroupSync));
+    pthread_mutex_init(&sync->mutex, NULL);
+    pthread_cond_init(&sync->zero, NULL);
+    wg[0] = (long)sync;
+    wg[1] = 0;
+    return (long)wg;
+}
+
+void __pluto_waitgroup_add(long handle, long delta) {
+    long *wg = (long *)handle;
+    WaitGroupSync *sync = (WaitGroupSync *)wg[0];
+
+    pthread_mutex_lock(&sync->mutex);
+    wg[1] += delta;
+    if (wg[1] < 0) {
+        pthread_mutex_unlock(&sync->mutex);
+        waitgroup_negative();
+    }
+    if (wg[1] == 0) pthread_cond_broadcast(&sync->zero);
+    pthread_mutex_unlock(&sync->mutex);
+}
+
+void __pluto_waitgroup_done(long handle) {
+    __pluto_waitgroup_add(handle, -1);
+}
+
+void __pluto_waitgroup_wait(long handle) {
+    long *wg = (long *)handle;
+    WaitGroupSync *sync = (WaitGroupSync *)wg[0];
+
+    pthread_mutex_lock(&sync->mutex);
+    while (wg[1] > 0) {
+        run_cond_wait(&sync->zero, &sync->mutex, NULL);
+    }
+    pthread_mutex_unlock(&sync->mutex);
+}
+
+#endif
+
+// ── Atomics ─────────────────────────────────────────────────────────────────
+
+// Atomic handle layout (8 bytes, 1 slot):
+//   [0] value  (int, only accessed through C11 atomics)
+// The same code serves both modes: fibers never preempt mid-operation, and
+// sequentially consistent atomics are correct across pthreads.
+
+long __pluto_atomic_new(long value) {
+    long *a = (long *)gc_alloc(8, GC_TAG_ATOMIC, 0);
+    atomic_init((_Atomic long *)a, value);
+    return (long)a;
+}
+
+long __pluto_atomic_load(long handle) {
+    return atomic_load((_Atomic long *)handle);
+}
+
+void __pluto_atomic_store(long handle, long value) {
+    atomic_store((_Atomic long *)handle, value);
+}
+
+long __pluto_atomic_add(long handle, long delta) {
+    // Returns the updated value
+    return atomic_fetch_add((_Atomic long *)handle, delta) + delta;
+}
+
+long __pluto_atomic_compare_and_swap(long handle, long expected, long desired) {
+    return atomic_compare_exchange_strong((_Atomic long *)handle, &expected, desired) ? 1 : 0;
+}
+
+// ── Mutexes ─────────────────────────────────────────────────────────────────
+
+// Mutex handle layout (24 bytes, 3 slots):
+//   [0] value     (the protected value; the only slot the GC traces)
+//   [1] sync_ptr  (raw malloc'd pthread_mutex_t; 0 in test mode)
+//   [2] locked    (test mode only: 1 while a guard is held)
+// lock() returns the handle itself as the guard, so guard.get()/set() read and
+// write slot 0 directly. Codegen calls unlock when the guard's block exits.
+
+#ifdef PLUTO_TEST_MODE
+
+long __pluto_mutex_new(long value) {
+    long *m = (long *)gc_alloc(24, GC_TAG_MUTEX, 1);
+    m[0] = value;
+    m[1] = 0;  // no sync needed in test mode
+    m[2] = 0;
+    return (long)m;
+}
+
+long __pluto_mutex_lock(long handle) {
+    long *m = (long *)handle;
+    if (g_scheduler && g_scheduler->strategy != STRATEGY_SEQUENTIAL) {
+        exhaustive_record_channel(g_scheduler->current_fiber, (void *)m);
+        while (m[2]) {
+            Fiber *cur = &g_scheduler->fibers[g_scheduler->current_fiber];
+            cur->state = FIBER_BLOCKED_MUTEX;
+            cur->blocked_on = (void *)m;
+            fiber_yield_to_scheduler();
+        }
+    } else if (m[2]) {
+        // Sequential mode: the holder is suspended below us and cannot resume
+        seq_deadlock("Mutex.lock() on a mutex that is already held");
+    }
+    m[2] = 1;
+    return handle;
+}
+
+void __pluto_mutex_unlock(long handle) {
+    long *m = (long *)handle;
+    if (!m) return;  // guard variable never bound on this path
+    if (g_scheduler) exhaustive_record_channel(g_scheduler->current_fiber, (void *)m);
+    m[2] = 0;
+    wake_fibers_blocked_on_mutex(m);
+}
+
+#else
+
+long __pluto_mutex_new(long value) {
+    // field_count=1: trace the value; slot 1 is a raw malloc ptr
+    long *m = (long *)gc_alloc(24, GC_TAG_MUTEX, 1);
+    pthread_mutex_t *sync = (pthread_mutex_t *)malloc(sizeof(pthread_mutex_t));
+    pthread_mutex_init(sync, NULL);
+    m[0] = value;
+    m[1] = (long)sync;
+    m[2] = 0;
+    return (long)m;
+}
+
+long __pluto_mutex_lock(long handle) {
+    long *m = (long *)handle;
+    pthread_mutex_t *sync = (pthread_mutex_t *)m[1];
+    if (pthread_mutex_trylock(sync) != 0) {
+        run_slot_release();
+        pthread_mutex_lock(sync);
+        run_slot_acquire();
+    }
+    return handle;
+}
+
+void __pluto_mutex_unlock(long handle) {
+    long *m = (long *)handle;
+    if (!m) return;  // guard variable never bound on this path
+    pthread_mutex_unlock((pthread_mutex_t *)m[1]);
+}
+
+#endif
+
 // ── Select (channel multiplexing) ──────────────────────────
 
 /*
@@ -1545,7 +2128,7 @@ long __pluto_select(long buffer_ptr, long count, long has_default) {
             if (result >= 0) return result;
             if (has_default) return -1;
             if (result == -2) {
-                chan_raise_error("channel closed");
+                chan_raise_error("ChannelClosed", "channel closed");
                 return -2;
             }
             // Block and yield
@@ -1562,11 +2145,11 @@ long __pluto_select(long buffer_ptr, long count, long has_default) {
     if (result >= 0) return result;
     if (has_default) return -1;
     if (result == -2) {
-        chan_raise_error("channel closed");
+        chan_raise_error("ChannelClosed", "channel closed");
         return -2;
     }
-    fprintf(stderr, "pluto: deadlock detected — select with no ready channels in sequential test mode\n");
-    exit(1);
+    seq_deadlock("select with no ready channels");
+    return -1;
 }
 
 #else
@@ -1647,12 +2230,14 @@ long __pluto_select(long buffer_ptr, long count, long has_default) {
 
         if (all_closed) {
             /* Raise ChannelClosed error */
-            chan_raise_error("channel closed");
+            chan_raise_error("ChannelClosed", "channel closed");
             return -2;
         }
 
         /* Adaptive sleep: 100us -> 200us -> ... -> 1ms max */
+        run_slot_release();
         usleep((useconds_t)spin_us);
+        run_slot_acquire();
         if (spin_us < 1000) spin_us = spin_us * 2;
         if (spin_us > 1000) spin_us = 1000;
     }
@@ -1700,6 +2285,15 @@ void __pluto_assert_failure(long assert_desc) {
     exit(1);
 }
 
+void __pluto_unreachable(void *message) {
+    // message may be a string slice, so read it through __pluto_string_data
+    const char *data;
+    long len;
+    __pluto_string_data(message, &data, &len);
+    fprintf(stderr, "entered unreachable code: %.*s\n", (int)len, data);
+    exit(1);
+}
+
 // ── Rwlock synchronization ─────────────────────────────────────────────────
 
 #ifndef PLUTO_TEST_MODE
@@ -1710,11 +2304,21 @@ long __pluto_rwlock_init(void) {
 }
 
 void __pluto_rwlock_rdlock(long lock_ptr) {
-    pthread_rwlock_rdlock((pthread_rwlock_t *)lock_ptr);
+    pthread_rwlock_t *lock = (pthread_rwlock_t *)lock_ptr;
+    if (pthread_rwlock_tryrdlock(lock) != 0) {
+        run_slot_release();
+        pthread_rwlock_rdlock(lock);
+        run_slot_acquire();
+    }
 }
 
 void __pluto_rwlock_wrlock(long lock_ptr) {
-    pthread_rwlock_wrlock((pthread_rwlock_t *)lock_ptr);
+    pthread_rwlock_t *lock = (pthread_rwlock_t *)lock_ptr;
+    if (pthread_rwlock_trywrlock(lock) != 0) {
+        run_slot_release();
+        pthread_rwlock_wrlock(lock);
+        run_slot_acquire();
+    }
 }
 
 void __pluto_rwlock_unlock(long lock_ptr) {
@@ -1724,8 +2328,25 @@ void __pluto_rwlock_unlock(long lock_ptr) {
 
 // ── Logging ────────────────────────────────────────────────────────────────
 
+// Levels match std.log's Level: 0 debug, 1 info, 2 warn, 3 error
 static int __pluto_global_log_level = 1;  // Default to INFO (1)
 
+// PLUTO_LOG sets the starting level; read once, before main runs
+__attribute__((constructor))
+static void __pluto_log_level_from_env(void) {
+    static const char *names[] = { "debug", "info", "warn", "error" };
+    const char *env = getenv("PLUTO_LOG");
+    if (!env || !*env) return;
+    for (int i = 0; i < 4; i++) {
+        if (strcasecmp(env, names[i]) == 0) {
+            __pluto_global_log_level = i;
+            return;
+        }
+    }
+    fprintf(stderr, "pluto: PLUTO_LOG must be debug, info, warn or error, got '%s'\n", env);
+    exit(1);
+}
+
 long __pluto_log_get_level(void) {
     return __pluto_global_log_level;
 }
@@ -1734,17 +2355,33 @@ void __pluto_log_set_level(long level) {
     __pluto_global_log_level = (int)level;
 }
 
+// UTC to the millisecond, e.g. 2026-01-02T03:04:05.678Z
+static void log_format_time(long timestamp_ms, char *buf, size_t size) {
+    time_t secs = (time_t)(timestamp_ms / 1000);
+    struct tm tm;
+    gmtime_r(&secs, &tm);
+    size_t n = strftime(buf, size, "%Y-%m-%dT%H:%M:%S", &tm);
+    snprintf(buf + n, size - n, ".%03ldZ", timestamp_ms % 1000);
+}
+
+// `<time> [LEVEL] message`, in one fprintf so lines from different threads
+// don't interleave
 void __pluto_log_write(void *level_str, long timestamp, void *message) {
     const char *level = __pluto_string_to_cstr(level_str);
     const char *msg = __pluto_string_to_cstr(message);
-    fprintf(stderr, "[%s] %ld %s\n", level, timestamp, msg);
+    char when[40];
+    log_format_time(timestamp, when, sizeof(when));
+    fprintf(stderr, "%s [%s] %s\n", when, level, msg);
     fflush(stderr);
 }
 
 void __pluto_log_write_structured(void *level_str, long timestamp, void *message, long fields_ptr) {
     const char *level = __pluto_string_to_cstr(level_str);
     const char *msg = __pluto_string_to_cstr(message);
-    fprintf(stderr, "[%s] %ld %s", level, timestamp, msg);
+    char when[40];
+    log_format_time(timestamp, when, sizeof(when));
+    flockfile(stderr);  // the line takes several writes
+    fprintf(stderr, "%s [%s] %s", when, level, msg);
 
     long *arr_header = (long *)fields_ptr;
     long len = arr_header[0];
@@ -1760,6 +2397,7 @@ void __pluto_log_write_structured(void *level_str, long timestamp, void *message
     }
     fprintf(stderr, "\n");
     fflush(stderr);
+    funlockfile(stderr);
 }
 
 // ── Environment Variables ──────────────────────────────────────────────────
@@ -17,11 +17,13 @@ pub enum DeclKind {
 }
 
 /// Borrowed handle to a declaration in a Module.
+#[derive(Clone, Copy)]
 pub struct DeclRef<'a> {
     kind: DeclKind,
     inner: DeclInner<'a>,
 }
 
+#[derive(Clone, Copy)]
 enum DeclInner<'a> {
     Function(&'a Function),
     Class(&'a ClassDecl),
@@ -85,6 +87,24 @@ impl<'a> DeclRef<'a> {
         self.kind
     }
 
+    /// The declaration's own visibility (`pub`, `pub(module)`, or private).
+    /// `None` for declarations without a visibility modifier: enum variants,
+    /// trait methods, fields, params, and the app.
+    pub fn visibility(&self) -> Option<Visibility> {
+        match &self.inner {
+            DeclInner::Function(f) => Some(f.visibility),
+            DeclInner::Class(c) => Some(c.visibility),
+            DeclInner::Enum(e) => Some(e.visibility),
+            DeclInner::Trait(t) => Some(t.visibility),
+            DeclInner::Error(e) => Some(e.visibility),
+            DeclInner::EnumVariant(_)
+            | DeclInner::TraitMethod(_)
+            | DeclInner::App(_)
+            | DeclInner::Field(_)
+            | DeclInner::Param(_) => None,
+        }
+    }
+
     pub fn as_function(&self) -> Option<&'a Function> {
         if let DeclInner::Function(f) = &self.inner { Some(f) } else { None }
     }
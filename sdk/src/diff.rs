@@ -0,0 +1,94 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use pluto::parser::ast::Program;
+use pluto::pretty::*;
+use uuid::Uuid;
+
+use crate::decl::DeclKind;
+
+/// A top-level declaration that differs between two module versions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeclChange {
+    pub id: Uuid,
+    pub kind: DeclKind,
+    /// Name in the newer module, or in the older one for removed declarations.
+    pub name: String,
+}
+
+/// Summary of what changed between two modules, keyed by declaration UUID.
+/// Same shape as `pluto::sync::SyncResult`, computed without touching disk.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleDiff {
+    pub added: Vec<DeclChange>,
+    pub removed: Vec<DeclChange>,
+    pub modified: Vec<DeclChange>,
+    pub unchanged: usize,
+}
+
+impl ModuleDiff {
+    /// True when no declaration was added, removed, or modified.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Compare the top-level declarations of `old` and `new` by UUID, treating a
+/// declaration as modified when its content hash differs (renames included).
+pub(crate) fn diff_programs(old: &Program, new: &Program) -> ModuleDiff {
+    let old_decls = content_hashes(old);
+    let old_by_id: HashMap<Uuid, &str> = old_decls.iter().map(|(c, h)| (c.id, h.as_str())).collect();
+    let new_decls = content_hashes(new);
+    let new_ids: HashSet<Uuid> = new_decls.iter().map(|(c, _)| c.id).collect();
+
+    let mut diff = ModuleDiff::default();
+    for (change, hash) in new_decls {
+        match old_by_id.get(&change.id) {
+            None => diff.added.push(change),
+            Some(old_hash) if *old_hash != hash => diff.modified.push(change),
+            Some(_) => diff.unchanged += 1,
+        }
+    }
+    diff.removed = old_decls.into_iter()
+        .filter(|(c, _)| !new_ids.contains(&c.id))
+        .map(|(c, _)| c)
+        .collect();
+    diff
+}
+
+/// Hash every local top-level declaration in source order. Like the test
+/// dependency hashes in `DerivedInfo`, this hashes a textual form of the
+/// declaration; the pretty-printed form is used so spans don't affect it.
+fn content_hashes(program: &Program) -> Vec<(DeclChange, String)> {
+    let mut out = Vec::new();
+    let mut push = |id: Uuid, kind: DeclKind, name: &str, text: String| {
+        // Imported and compiler-generated declarations aren't part of the module's own text
+        if name.contains('.') || name.starts_with("__closure_") {
+            return;
+        }
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        out.push((DeclChange { id, kind, name: name.to_string() }, format!("{:x}", hasher.finish())));
+    };
+
+    for f in program.functions.iter().filter(|f| !f.span.is_synthetic()) {
+        push(f.node.id, DeclKind::Function, &f.node.name.node, pretty_print_function(&f.node, false));
+    }
+    for c in &program.classes {
+        push(c.node.id, DeclKind::Class, &c.node.name.node, pretty_print_class(&c.node, false));
+    }
+    for e in &program.enums {
+        push(e.node.id, DeclKind::Enum, &e.node.name.node, pretty_print_enum(&e.node, false));
+    }
+    for t in &program.traits {
+        push(t.node.id, DeclKind::Trait, &t.node.name.node, pretty_print_trait(&t.node, false));
+    }
+    for e in &program.errors {
+        push(e.node.id, DeclKind::Error, &e.node.name.node, pretty_print_error(&e.node, false));
+    }
+    if let Some(app) = &program.app {
+        push(app.node.id, DeclKind::App, &app.node.name.node, pretty_print_app(&app.node, false));
+    }
+    out
+}
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use uuid::Uuid;
 
@@ -17,7 +17,41 @@ pub struct DeleteResult {
     pub dangling: Vec<DanglingRef>,
 }
 
-/// A reference that dangles after a deletion.
+/// Result of renaming a declaration.
+pub struct RenameResult {
+    /// The declaration's name before the rename.
+    pub old_name: String,
+    /// Reference sites rewritten to the new name, as spans into the pre-edit source.
+    pub updated: Vec<Span>,
+    /// References still bound to the declaration under another name; they will
+    /// not resolve once the edit is committed.
+    pub dangling: Vec<DanglingRef>,
+}
+
+/// One parameter of the signature passed to `ModuleEditor::change_signature`.
+pub enum ParamChange {
+    /// Keep the parameter at this index of the current signature.
+    Keep(usize),
+    /// Add a parameter. Call sites pass `placeholder`, an expression, for it.
+    Add { name: String, ty: String, placeholder: String },
+}
+
+/// Result of changing a function's signature.
+pub struct ChangeSignatureResult {
+    /// Call sites whose arguments were rewritten, as spans into the pre-edit source.
+    pub updated: Vec<Span>,
+    /// Sites that still use the old signature and need a manual fix.
+    pub skipped: Vec<SkippedSite>,
+}
+
+/// A use of a function that `change_signature` could not rewrite.
+#[derive(Debug)]
+pub struct SkippedSite {
+    pub span: Span,
+    pub reason: String,
+}
+
+/// A reference that dangles after a deletion or rename.
 #[derive(Debug)]
 pub struct DanglingRef {
     /// What kind of reference this is.
@@ -43,7 +77,8 @@ pub enum DanglingRefKind {
 ///
 /// Created via `Module::edit()`. Mutations accumulate on the AST in memory.
 /// Call `commit()` to pretty-print, re-resolve xrefs, rebuild the index,
-/// and produce a new `Module`.
+/// and produce a new `Module`. Declarations the edits left alone keep their
+/// original text byte-for-byte; only changed ones are reformatted.
 pub struct ModuleEditor {
     program: Program,
     source: String,
@@ -273,7 +308,8 @@ impl ModuleEditor {
     }
 
     /// Rename a top-level declaration and update all reference sites in the AST.
-    pub fn rename(&mut self, id: Uuid, new_name: &str) -> Result<(), SdkError> {
+    /// Returns the rewritten reference sites and any references the rename missed.
+    pub fn rename(&mut self, id: Uuid, new_name: &str) -> Result<RenameResult, SdkError> {
         let (kind, idx) = self.find_top_level(id)?;
 
         let old_name = match kind {
@@ -310,6 +346,110 @@ impl ModuleEditor {
         // Walk entire AST to update reference sites
         rename_references(&mut self.program, id, kind, &old_name, new_name);
 
+        // Any site still bound to `id` that kept another name was missed
+        let (updated, dangling): (Vec<_>, Vec<_>) = collect_dangling_refs(&self.program, id)
+            .into_iter()
+            .partition(|r| r.name == new_name);
+
+        Ok(RenameResult {
+            old_name,
+            updated: updated.into_iter().map(|r| r.span).collect(),
+            dangling,
+        })
+    }
+
+    /// Replace the parameter list of a top-level function and rewrite the
+    /// arguments of every call to it to match: kept arguments are permuted,
+    /// dropped ones removed, and added ones filled with their placeholder.
+    /// Calls with the wrong number of arguments and uses of the function as a
+    /// value are left alone and reported as skipped.
+    pub fn change_signature(&mut self, id: Uuid, params: Vec<ParamChange>) -> Result<ChangeSignatureResult, SdkError> {
+        let (kind, idx) = self.find_top_level(id)?;
+        if kind != DeclKindSimple::Function {
+            return Err(SdkError::Edit("change_signature is only supported for functions".to_string()));
+        }
+        let old_params = &self.program.functions[idx].node.params;
+        let fn_name = self.program.functions[idx].node.name.node.clone();
+
+        let mut new_params = Vec::with_capacity(params.len());
+        let mut args = Vec::with_capacity(params.len());
+        let mut kept = HashSet::new();
+        for change in params {
+            match change {
+                ParamChange::Keep(i) => {
+                    let param = old_params.get(i).ok_or_else(|| {
+                        SdkError::Edit(format!("'{fn_name}' has no parameter at index {i}"))
+                    })?;
+                    if !kept.insert(i) {
+                        return Err(SdkError::Edit(format!("parameter '{}' is kept more than once", param.name.node)));
+                    }
+                    new_params.push(param.clone());
+                    args.push(NewArg::Old(i));
+                }
+                ParamChange::Add { name, ty, placeholder } => {
+                    new_params.push(Param {
+                        id: Uuid::new_v4(),
+                        name: Spanned::new(name, Span::dummy()),
+                        ty: Spanned::new(parse_type_expr(&ty)?, Span::dummy()),
+                        is_mut: false,
+                    });
+                    args.push(NewArg::Placeholder(Box::new(parse_expr(&placeholder)?)));
+                }
+            }
+        }
+        let mut names = HashSet::new();
+        if let Some(dup) = new_params.iter().find(|p| !names.insert(p.name.node.as_str())) {
+            return Err(SdkError::Edit(format!("duplicate parameter '{}'", dup.name.node)));
+        }
+
+        let old_len = old_params.len();
+        self.program.functions[idx].node.params = new_params;
+
+        let mut rewriter = CallRewriter {
+            target: id,
+            fn_name,
+            old_len,
+            args,
+            updated: Vec::new(),
+            skipped: Vec::new(),
+        };
+        pluto::visit::walk_program_mut(&mut rewriter, &mut self.program);
+
+        Ok(ChangeSignatureResult {
+            updated: rewriter.updated,
+            skipped: rewriter.skipped,
+        })
+    }
+
+    /// Move a top-level declaration to just before `before`, or to the end of
+    /// its group when `before` is `None`. Source lists declarations grouped by
+    /// kind, so `before` must be of the same kind. UUIDs are untouched, so
+    /// references to the moved declaration still resolve after `commit`.
+    pub fn move_decl(&mut self, id: Uuid, before: Option<Uuid>) -> Result<(), SdkError> {
+        let (kind, from) = self.find_top_level(id)?;
+        let to = match before {
+            Some(before_id) => {
+                let (before_kind, to) = self.find_top_level(before_id)?;
+                if before_kind != kind {
+                    return Err(SdkError::Edit(
+                        "cannot move a declaration before one of a different kind; declarations are grouped by kind".to_string(),
+                    ));
+                }
+                Some(to)
+            }
+            None => None,
+        };
+
+        match kind {
+            DeclKindSimple::Function => move_item(&mut self.program.functions, from, to),
+            DeclKindSimple::Class => move_item(&mut self.program.classes, from, to),
+            DeclKindSimple::Enum => move_item(&mut self.program.enums, from, to),
+            DeclKindSimple::Trait => move_item(&mut self.program.traits, from, to),
+            DeclKindSimple::Error => move_item(&mut self.program.errors, from, to),
+            DeclKindSimple::App => {
+                return Err(SdkError::Edit("move is not supported for app declarations".to_string()));
+            }
+        }
         Ok(())
     }
 
@@ -346,8 +486,10 @@ impl ModuleEditor {
     /// Pretty-print the modified AST, re-resolve cross-references, rebuild the index,
     /// and return a new `Module`.
     pub fn commit(mut self) -> Module {
-        // Pretty-print produces fresh source
-        let source = pluto::pretty::pretty_print(&self.program, false);
+        // Pretty-print produces fresh source, reusing the original text of
+        // untouched declarations to keep diffs minimal
+        let verbatim = self.unchanged_sources();
+        let source = pluto::pretty::pretty_print_with_verbatim(&self.program, verbatim);
 
         // Re-resolve cross-references
         pluto::xref::resolve_cross_refs(&mut self.program);
@@ -365,6 +507,51 @@ impl ModuleEditor {
 
     // --- Internal helpers ---
 
+    /// Original source text of every top-level declaration that is unchanged,
+    /// keyed by UUID. A declaration counts as unchanged when its text in
+    /// `self.source` still parses to something that prints identically to the
+    /// current AST. This catches direct edits, reference sites rewritten by a
+    /// rename, and spans that no longer point into `self.source` (e.g. after an
+    /// earlier commit reformatted the declaration).
+    fn unchanged_sources(&self) -> HashMap<Uuid, String> {
+        use pluto::pretty::*;
+
+        let mut out = HashMap::new();
+        let mut keep = |id: Uuid, span: Span, current: String, reprint: &dyn Fn(&Program) -> Option<String>| {
+            let Some(text) = declaration_text(&self.source, span) else { return };
+            let Ok(parsed) = parse_single_program(text, &self.program) else { return };
+            if reprint(&parsed).is_some_and(|p| p == current) {
+                out.insert(id, text.to_string());
+            }
+        };
+
+        for e in &self.program.errors {
+            keep(e.node.id, e.span, pretty_print_error(&e.node, false),
+                &|p| p.errors.first().map(|d| pretty_print_error(&d.node, false)));
+        }
+        for t in &self.program.traits {
+            keep(t.node.id, t.span, pretty_print_trait(&t.node, false),
+                &|p| p.traits.first().map(|d| pretty_print_trait(&d.node, false)));
+        }
+        for e in &self.program.enums {
+            keep(e.node.id, e.span, pretty_print_enum(&e.node, false),
+                &|p| p.enums.first().map(|d| pretty_print_enum(&d.node, false)));
+        }
+        for c in &self.program.classes {
+            keep(c.node.id, c.span, pretty_print_class(&c.node, false),
+                &|p| p.classes.first().map(|d| pretty_print_class(&d.node, false)));
+        }
+        for f in &self.program.functions {
+            keep(f.node.id, f.span, pretty_print_function(&f.node, false),
+                &|p| p.functions.first().map(|d| pretty_print_function(&d.node, false)));
+        }
+        if let Some(app) = &self.program.app {
+            keep(app.node.id, app.span, pretty_print_app(&app.node, false),
+                &|p| p.app.as_ref().map(|d| pretty_print_app(&d.node, false)));
+        }
+        out
+    }
+
     /// Find a top-level declaration by UUID, returning its simple kind and vec index.
     fn find_top_level(&self, id: Uuid) -> Result<(DeclKindSimple, usize), SdkError> {
         for (i, f) in self.program.functions.iter().enumerate() {
@@ -423,6 +610,64 @@ enum DeclKindSimple {
     App,
 }
 
+// --- Call-site rewriting ---
+
+/// Where an argument of a rewritten call comes from.
+enum NewArg {
+    /// The argument at this index of the old call.
+    Old(usize),
+    Placeholder(Box<Spanned<Expr>>),
+}
+
+struct CallRewriter {
+    target: Uuid,
+    fn_name: String,
+    old_len: usize,
+    args: Vec<NewArg>,
+    updated: Vec<Span>,
+    skipped: Vec<SkippedSite>,
+}
+
+impl pluto::visit::VisitMut for CallRewriter {
+    fn visit_expr_mut(&mut self, expr: &mut Spanned<Expr>) {
+        pluto::visit::walk_expr_mut(self, expr);
+        match &mut expr.node {
+            Expr::Call { args, target_id: Some(target), .. } if *target == self.target => {
+                if args.len() != self.old_len {
+                    self.skipped.push(SkippedSite {
+                        span: expr.span,
+                        reason: format!("call passes {} arguments, expected {}", args.len(), self.old_len),
+                    });
+                    return;
+                }
+                *args = self.args.iter().map(|arg| match arg {
+                    NewArg::Old(i) => args[*i].clone(),
+                    NewArg::Placeholder(e) => (**e).clone(),
+                }).collect();
+                self.updated.push(expr.span);
+            }
+            Expr::Ident(name) if *name == self.fn_name => {
+                self.skipped.push(SkippedSite {
+                    span: expr.span,
+                    reason: format!("'{name}' is used as a value"),
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Move `items[from]` to just before `items[before]`, or to the end.
+fn move_item<T>(items: &mut Vec<T>, from: usize, before: Option<usize>) {
+    let item = items.remove(from);
+    let to = match before {
+        Some(i) if i > from => i - 1,
+        Some(i) => i,
+        None => items.len(),
+    };
+    items.insert(to, item);
+}
+
 // --- Parsing helpers ---
 
 /// Merge imports from `new` into `existing`, skipping duplicates.
@@ -452,6 +697,24 @@ fn collect_enum_names(program: &Program) -> HashSet<String> {
 }
 
 /// Parse source as a program with enum context from the current state.
+/// The source text of a top-level declaration: its span, extended back over
+/// any `pub`/`pub(module)`/lifecycle modifiers on the same line (declaration
+/// spans start at the keyword). `None` if the span does not fit `source`.
+fn declaration_text(source: &str, span: Span) -> Option<&str> {
+    let body = source.get(span.start..span.end)?;
+    if body.is_empty() {
+        return None;
+    }
+    let line_start = source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let prefix = &source[line_start..span.start];
+    let is_modifier = |w: &str| matches!(w, "pub" | "pub(module)" | "scoped" | "transient");
+    if prefix.split_whitespace().all(is_modifier) {
+        source.get(line_start + (prefix.len() - prefix.trim_start().len())..span.end)
+    } else {
+        Some(body)
+    }
+}
+
 fn parse_single_program(source: &str, context: &Program) -> Result<Program, SdkError> {
     let tokens = pluto::lexer::lex(source)?;
     let enum_names = collect_enum_names(context);
@@ -497,6 +760,20 @@ fn parse_type_expr(ty: &str) -> Result<TypeExpr, SdkError> {
     Ok(program.functions.remove(0).node.params.remove(0).ty.node)
 }
 
+/// Parse an expression by wrapping it in a dummy `let`.
+fn parse_expr(expr: &str) -> Result<Spanned<Expr>, SdkError> {
+    let dummy = format!("fn __tmp() {{\n    let __x = {}\n}}", expr);
+    let tokens = pluto::lexer::lex(&dummy)?;
+    let mut parser = pluto::parser::Parser::new(&tokens, &dummy);
+    let mut program = parser.parse_program()?;
+
+    let stmts = program.functions.pop().map(|f| f.node.body.node.stmts).unwrap_or_default();
+    match <[_; 1]>::try_from(stmts) {
+        Ok([Spanned { node: Stmt::Let { value, .. }, .. }]) => Ok(value),
+        _ => Err(SdkError::Edit(format!("failed to parse expression: {}", expr))),
+    }
+}
+
 // --- UUID transplanting ---
 
 /// Match new params to old params by name, preserving UUIDs for matching names.
@@ -593,7 +870,7 @@ fn collect_dangling_in_block(block: &Block, target: Uuid, out: &mut Vec<Dangling
 
 fn collect_dangling_in_stmt(stmt: &Stmt, span: Span, target: Uuid, out: &mut Vec<DanglingRef>) {
     match stmt {
-        Stmt::Let { value, .. } => collect_dangling_in_expr(&value.node, value.span, target, out),
+        Stmt::Let { value, .. } | Stmt::LetStruct { value, .. } => collect_dangling_in_expr(&value.node, value.span, target, out),
         Stmt::Return(Some(e)) => collect_dangling_in_expr(&e.node, e.span, target, out),
         Stmt::Return(None) => {}
         Stmt::Assign { value, .. } => collect_dangling_in_expr(&value.node, value.span, target, out),
@@ -601,7 +878,8 @@ fn collect_dangling_in_stmt(stmt: &Stmt, span: Span, target: Uuid, out: &mut Vec
             collect_dangling_in_expr(&object.node, object.span, target, out);
             collect_dangling_in_expr(&value.node, value.span, target, out);
         }
-        Stmt::If { condition, then_block, else_block } => {
+        Stmt::If { condition, then_block, else_block }
+        | Stmt::IfLet { value: condition, then_block, else_block, .. } => {
             collect_dangling_in_expr(&condition.node, condition.span, target, out);
             collect_dangling_in_block(&then_block.node, target, out);
             if let Some(eb) = else_block {
@@ -634,6 +912,21 @@ fn collect_dangling_in_stmt(stmt: &Stmt, span: Span, target: Uuid, out: &mut Vec
                 collect_dangling_in_block(&arm.body.node, target, out);
             }
         }
+        Stmt::IntMatch { expr, arms } => {
+            collect_dangling_in_expr(&expr.node, expr.span, target, out);
+            for arm in arms {
+                collect_dangling_in_block(&arm.body.node, target, out);
+            }
+        }
+        Stmt::NullableMatch { expr, some_arm, none_arm } => {
+            collect_dangling_in_expr(&expr.node, expr.span, target, out);
+            if let Some(arm) = some_arm {
+                collect_dangling_in_block(&arm.body.node, target, out);
+            }
+            if let Some(nb) = none_arm {
+                collect_dangling_in_block(&nb.node, target, out);
+            }
+        }
         Stmt::Raise { error_id, error_name, fields, .. } => {
             if *error_id == Some(target) {
                 out.push(DanglingRef {
@@ -676,6 +969,13 @@ fn collect_dangling_in_stmt(stmt: &Stmt, span: Span, target: Uuid, out: &mut Vec
             }
             collect_dangling_in_block(&body.node, target, out);
         }
+        Stmt::Try { body, handler, finally, .. } => {
+            collect_dangling_in_block(&body.node, target, out);
+            collect_dangling_in_block(&handler.node, target, out);
+            if let Some(finally) = finally {
+                collect_dangling_in_block(&finally.node, target, out);
+            }
+        }
         Stmt::Yield { value } => {
             collect_dangling_in_expr(&value.node, value.span, target, out);
         }
@@ -724,6 +1024,16 @@ fn collect_dangling_in_expr(expr: &Expr, span: Span, target: Uuid, out: &mut Vec
                 });
             }
         }
+        Expr::Is { expr, enum_name, enum_id, .. } => {
+            if *enum_id == Some(target) {
+                out.push(DanglingRef {
+                    kind: DanglingRefKind::EnumUsage,
+                    name: enum_name.node.clone(),
+                    span,
+                });
+            }
+            collect_dangling_in_expr(&expr.node, expr.span, target, out);
+        }
         Expr::EnumData { enum_name, fields, enum_id, .. } => {
             if *enum_id == Some(target) {
                 out.push(DanglingRef {
@@ -780,6 +1090,10 @@ fn collect_dangling_in_expr(expr: &Expr, span: Span, target: Uuid, out: &mut Vec
         Expr::Propagate { expr } | Expr::Cast { expr, .. } | Expr::Spawn { call: expr } | Expr::NullPropagate { expr } => {
             collect_dangling_in_expr(&expr.node, expr.span, target, out);
         }
+        Expr::PropagateMap { expr, mapper } => {
+            collect_dangling_in_expr(&expr.node, expr.span, target, out);
+            collect_dangling_in_expr(&mapper.node, mapper.span, target, out);
+        }
         Expr::Catch { expr: inner, handlers } => {
             collect_dangling_in_expr(&inner.node, inner.span, target, out);
             for handler in handlers {
@@ -928,6 +1242,10 @@ fn rename_in_stmt(stmt: &mut Stmt, id: Uuid, kind: DeclKindSimple, old_name: &st
             }
             rename_in_expr(&mut value.node, id, kind, old_name, new_name);
         }
+        Stmt::LetStruct { ty, value, .. } => {
+            rename_in_type_expr(&mut ty.node, kind, old_name, new_name);
+            rename_in_expr(&mut value.node, id, kind, old_name, new_name);
+        }
         Stmt::Return(Some(e)) => {
             rename_in_expr(&mut e.node, id, kind, old_name, new_name);
         }
@@ -939,7 +1257,8 @@ fn rename_in_stmt(stmt: &mut Stmt, id: Uuid, kind: DeclKindSimple, old_name: &st
             rename_in_expr(&mut object.node, id, kind, old_name, new_name);
             rename_in_expr(&mut value.node, id, kind, old_name, new_name);
         }
-        Stmt::If { condition, then_block, else_block } => {
+        Stmt::If { condition, then_block, else_block }
+        | Stmt::IfLet { value: condition, then_block, else_block, .. } => {
             rename_in_expr(&mut condition.node, id, kind, old_name, new_name);
             rename_in_block(&mut then_block.node, id, kind, old_name, new_name);
             if let Some(eb) = else_block {
@@ -970,6 +1289,21 @@ fn rename_in_stmt(stmt: &mut Stmt, id: Uuid, kind: DeclKindSimple, old_name: &st
                 rename_in_block(&mut arm.body.node, id, kind, old_name, new_name);
             }
         }
+        Stmt::IntMatch { expr, arms } => {
+            rename_in_expr(&mut expr.node, id, kind, old_name, new_name);
+            for arm in arms {
+                rename_in_block(&mut arm.body.node, id, kind, old_name, new_name);
+            }
+        }
+        Stmt::NullableMatch { expr, some_arm, none_arm } => {
+            rename_in_expr(&mut expr.node, id, kind, old_name, new_name);
+            if let Some(arm) = some_arm {
+                rename_in_block(&mut arm.body.node, id, kind, old_name, new_name);
+            }
+            if let Some(nb) = none_arm {
+                rename_in_block(&mut nb.node, id, kind, old_name, new_name);
+            }
+        }
         Stmt::Raise { error_name, fields, error_id } => {
             if kind == DeclKindSimple::Error && *error_id == Some(id) {
                 error_name.node = new_name.to_string();
@@ -1014,6 +1348,13 @@ fn rename_in_stmt(stmt: &mut Stmt, id: Uuid, kind: DeclKindSimple, old_name: &st
             }
             rename_in_block(&mut body.node, id, kind, old_name, new_name);
         }
+        Stmt::Try { body, handler, finally, .. } => {
+            rename_in_block(&mut body.node, id, kind, old_name, new_name);
+            rename_in_block(&mut handler.node, id, kind, old_name, new_name);
+            if let Some(finally) = finally {
+                rename_in_block(&mut finally.node, id, kind, old_name, new_name);
+            }
+        }
         Stmt::Yield { value } => {
             rename_in_expr(&mut value.node, id, kind, old_name, new_name);
         }
@@ -1050,6 +1391,17 @@ fn rename_in_expr(expr: &mut Expr, id: Uuid, kind: DeclKindSimple, old_name: &st
                 enum_name.node = new_name.to_string();
             }
         }
+        Expr::Is { expr, enum_name, enum_id, .. } => {
+            if kind == DeclKindSimple::Enum && *enum_id == Some(id) {
+                enum_name.node = new_name.to_string();
+            }
+            rename_in_expr(&mut expr.node, id, kind, old_name, new_name);
+        }
+        Expr::MethodRef { type_name, .. } => {
+            if type_name.node == old_name && matches!(kind, DeclKindSimple::Class | DeclKindSimple::Trait) {
+                type_name.node = new_name.to_string();
+            }
+        }
         Expr::EnumData { enum_name, fields, enum_id, .. } => {
             if kind == DeclKindSimple::Enum && *enum_id == Some(id) {
                 enum_name.node = new_name.to_string();
@@ -1106,6 +1458,10 @@ fn rename_in_expr(expr: &mut Expr, id: Uuid, kind: DeclKindSimple, old_name: &st
         Expr::Propagate { expr } | Expr::Cast { expr, .. } | Expr::Spawn { call: expr } | Expr::NullPropagate { expr } => {
             rename_in_expr(&mut expr.node, id, kind, old_name, new_name);
         }
+        Expr::PropagateMap { expr, mapper } => {
+            rename_in_expr(&mut expr.node, id, kind, old_name, new_name);
+            rename_in_expr(&mut mapper.node, id, kind, old_name, new_name);
+        }
         Expr::Catch { expr: inner, handlers } => {
             rename_in_expr(&mut inner.node, id, kind, old_name, new_name);
             for handler in handlers {
@@ -212,7 +212,7 @@ fn collect_stmt_xrefs(
     raise_sites: &mut HashMap<Uuid, Vec<RaiseSiteInfo>>,
 ) {
     match stmt {
-        Stmt::Let { value, .. } => {
+        Stmt::Let { value, .. } | Stmt::LetStruct { value, .. } => {
             collect_expr_xrefs(&value.node, value.span, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
         }
         Stmt::Return(Some(expr)) => {
@@ -226,7 +226,8 @@ fn collect_stmt_xrefs(
             collect_expr_xrefs(&object.node, object.span, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
             collect_expr_xrefs(&value.node, value.span, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
         }
-        Stmt::If { condition, then_block, else_block } => {
+        Stmt::If { condition, then_block, else_block }
+        | Stmt::IfLet { value: condition, then_block, else_block, .. } => {
             collect_expr_xrefs(&condition.node, condition.span, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
             collect_block_xrefs(&then_block.node, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
             if let Some(eb) = else_block {
@@ -252,6 +253,21 @@ fn collect_stmt_xrefs(
                 collect_block_xrefs(&arm.body.node, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
             }
         }
+        Stmt::IntMatch { expr, arms } => {
+            collect_expr_xrefs(&expr.node, expr.span, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
+            for arm in arms {
+                collect_block_xrefs(&arm.body.node, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
+            }
+        }
+        Stmt::NullableMatch { expr, some_arm, none_arm } => {
+            collect_expr_xrefs(&expr.node, expr.span, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
+            if let Some(arm) = some_arm {
+                collect_block_xrefs(&arm.body.node, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
+            }
+            if let Some(nb) = none_arm {
+                collect_block_xrefs(&nb.node, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
+            }
+        }
         Stmt::Raise { error_id, error_name, fields, .. } => {
             if let Some(eid) = error_id {
                 raise_sites.entry(*eid).or_default().push(RaiseSiteInfo {
@@ -298,6 +314,13 @@ fn collect_stmt_xrefs(
             }
             collect_block_xrefs(&body.node, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
         }
+        Stmt::Try { body, handler, finally, .. } => {
+            collect_block_xrefs(&body.node, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
+            collect_block_xrefs(&handler.node, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
+            if let Some(finally) = finally {
+                collect_block_xrefs(&finally.node, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
+            }
+        }
         Stmt::Yield { value } => {
             collect_expr_xrefs(&value.node, value.span, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
         }
@@ -368,6 +391,17 @@ fn collect_expr_xrefs(
                 variant_id: *vid,
             });
         }
+        Expr::Is { expr: inner, enum_id, variant_id, .. } => {
+            if let (Some(eid), Some(vid)) = (enum_id, variant_id) {
+                enum_usages.entry(*eid).or_default().push(EnumUsageSiteInfo {
+                    fn_name: fn_name.to_string(),
+                    span: expr_span,
+                    enum_id: *eid,
+                    variant_id: *vid,
+                });
+            }
+            collect_expr_xrefs(&inner.node, inner.span, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
+        }
         Expr::EnumData { enum_id: Some(eid), variant_id: Some(vid), fields, .. } => {
             enum_usages.entry(*eid).or_default().push(EnumUsageSiteInfo {
                 fn_name: fn_name.to_string(),
@@ -435,6 +469,10 @@ fn collect_expr_xrefs(
         Expr::Propagate { expr } => {
             collect_expr_xrefs(&expr.node, expr.span, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
         }
+        Expr::PropagateMap { expr, mapper } => {
+            collect_expr_xrefs(&expr.node, expr.span, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
+            collect_expr_xrefs(&mapper.node, mapper.span, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
+        }
         Expr::Catch { expr: inner, handlers } => {
             collect_expr_xrefs(&inner.node, inner.span, caller_id, fn_name, callers, callees, constructors, enum_usages, raise_sites);
             for handler in handlers {
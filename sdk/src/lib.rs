@@ -1,4 +1,5 @@
 pub mod decl;
+pub mod diff;
 pub mod editor;
 pub mod error;
 pub mod index;
@@ -6,7 +7,8 @@ pub mod module;
 pub mod xref;
 
 pub use decl::{DeclKind, DeclRef};
-pub use editor::{ModuleEditor, DeleteResult, DanglingRef};
+pub use diff::{DeclChange, ModuleDiff};
+pub use editor::{ModuleEditor, DeleteResult, DanglingRef, RenameResult, ParamChange, ChangeSignatureResult};
 pub use error::SdkError;
 pub use module::Module;
 
@@ -16,7 +18,7 @@ pub use pluto::derived::{
     ResolvedClassInfo, ResolvedTraitInfo, ResolvedEnumInfo,
     ResolvedErrorInfo, ResolvedFieldInfo, ResolvedVariantInfo,
 };
-pub use pluto::parser::ast::Program;
+pub use pluto::parser::ast::{Program, Visibility};
 pub use pluto::span::{Span, Spanned};
 pub use pluto::typeck::types::PlutoType;
 
@@ -49,9 +51,11 @@ mod tests {
             return_type: None,
             contracts: vec![],
             body: empty_block(),
-            is_pub: false,
+            visibility: Visibility::Private,
             is_override: false,
             is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
         })
     }
 
@@ -63,6 +67,8 @@ mod tests {
             classes: vec![],
             traits: vec![],
             enums: vec![],
+            consts: vec![],
+            globals: vec![],
             app: None,
             stages: vec![],
             system: None,
@@ -70,6 +76,7 @@ mod tests {
             test_info: vec![],
             tests: None,
             fallible_extern_fns: vec![],
+            comments: vec![],
         }
     }
 
@@ -96,6 +103,18 @@ mod tests {
         assert_eq!(module.functions()[0].name(), "main");
     }
 
+    #[test]
+    fn from_bytes_reads_compressed_modules() {
+        use pluto::binary::{serialize_program_with, Compression};
+
+        let source = "fn greet() {\n    print(\"hello\")\n}\n\nfn main() {\n    greet()\n}\n";
+        let program = parse(source);
+        let bytes = serialize_program_with(&program, source, &empty_derived(), Compression::Deflate).unwrap();
+        let module = Module::from_bytes(&bytes).unwrap();
+        assert_eq!(module.source(), source);
+        assert_eq!(module.find("greet")[0].id(), program.functions[0].node.id);
+    }
+
     #[test]
     fn get_by_uuid() {
         let source = "fn foo() {\n}\n\nfn main() {\n}\n";
@@ -257,8 +276,9 @@ fn main() {
             methods: vec![],
             invariants: vec![],
             impl_traits: vec![],
+            where_bounds: std::collections::HashMap::new(),
             uses: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             lifecycle: Lifecycle::Singleton,
         }));
 
@@ -300,7 +320,9 @@ fn main() {
                 name: sp("Red".to_string()),
                 fields: vec![],
             }],
-            is_pub: false,
+            methods: vec![],
+            impl_traits: vec![],
+            visibility: Visibility::Private,
         }));
 
         let usage_span = PlutoSpan::new(300, 310);
@@ -335,7 +357,7 @@ fn main() {
             id: err_id,
             name: sp("NotFound".to_string()),
             fields: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
         }));
 
         let raise_span = PlutoSpan::new(400, 420);
@@ -613,6 +635,116 @@ fn main() {
         assert!(!module.source().contains("greet"));
     }
 
+    #[test]
+    fn move_decl_reorders_and_keeps_xrefs() {
+        let source = "fn greet() {\n    print(\"hello\")\n}\n\n// entry point\nfn main() {\n    greet()\n}\n\nfn helper() {\n}\n";
+        let module = Module::from_source(source).unwrap();
+        let greet_id = module.find("greet")[0].id();
+        let main_id = module.find("main")[0].id();
+        let helper_id = module.find("helper")[0].id();
+        let mut editor = module.edit();
+
+        editor.move_decl(main_id, Some(greet_id)).unwrap();
+        editor.move_decl(greet_id, None).unwrap();
+        let module = editor.commit();
+
+        let names: Vec<_> = module.functions().iter().map(|f| f.name().to_string()).collect();
+        assert_eq!(names, vec!["main", "helper", "greet"]);
+        assert_eq!(
+            module.source(),
+            "// entry point\nfn main() {\n    greet()\n}\n\nfn helper() {\n}\n\nfn greet() {\n    print(\"hello\")\n}\n"
+        );
+        assert_eq!(module.find("main")[0].id(), main_id);
+        assert_eq!(module.find("helper")[0].id(), helper_id);
+        let callers = module.callers_of(greet_id);
+        assert_eq!(callers.len(), 1);
+        assert_eq!(callers[0].caller.id, main_id);
+    }
+
+    #[test]
+    fn move_decl_rejects_unknown_and_cross_kind_targets() {
+        let source = "class Point {\n    x: int\n}\n\nfn main() {\n}\n";
+        let module = Module::from_source(source).unwrap();
+        let point_id = module.classes()[0].id();
+        let main_id = module.find("main")[0].id();
+        let mut editor = module.edit();
+
+        assert!(editor.move_decl(Uuid::new_v4(), None).is_err());
+        assert!(editor.move_decl(main_id, Some(Uuid::new_v4())).is_err());
+        assert!(editor.move_decl(main_id, Some(point_id)).is_err());
+    }
+
+    #[test]
+    fn change_signature_rewrites_call_sites() {
+        use crate::editor::ParamChange;
+
+        let source = "fn area(w: int, h: int, unused: int) int {\n    return w * h\n}\n\nfn main() {\n    print(area(area(1, 2, 0), 3, 4))\n}\n";
+        let module = Module::from_source(source).unwrap();
+        let area_id = module.find("area")[0].id();
+        let w_id = module.find("area")[0].as_function().unwrap().params[0].id;
+        let mut editor = module.edit();
+
+        let result = editor.change_signature(area_id, vec![
+            ParamChange::Keep(1),
+            ParamChange::Keep(0),
+            ParamChange::Add { name: "depth".to_string(), ty: "int".to_string(), placeholder: "1".to_string() },
+        ]).unwrap();
+        assert!(result.skipped.is_empty());
+        let updated: Vec<_> = result.updated.iter().map(|s| &source[s.start..s.end]).collect();
+        assert_eq!(updated, vec!["area(1, 2, 0)", "area(area(1, 2, 0), 3, 4)"]);
+
+        let module = editor.commit();
+        assert!(module.source().contains("fn area(h: int, w: int, depth: int) int {"), "{}", module.source());
+        assert!(module.source().contains("print(area(3, area(2, 1, 1), 1))"), "{}", module.source());
+        assert_eq!(module.find("area")[0].as_function().unwrap().params[1].id, w_id);
+        assert_eq!(module.callers_of(area_id).len(), 2);
+    }
+
+    #[test]
+    fn change_signature_rejects_bad_changes() {
+        use crate::editor::ParamChange;
+
+        let source = "class Point {\n    x: int\n}\n\nfn f(a: int, b: int) {\n}\n\nfn main() {\n}\n";
+        let module = Module::from_source(source).unwrap();
+        let f_id = module.find("f")[0].id();
+        let point_id = module.classes()[0].id();
+        let mut editor = module.edit();
+
+        assert!(editor.change_signature(f_id, vec![ParamChange::Keep(2)]).is_err());
+        assert!(editor.change_signature(f_id, vec![ParamChange::Keep(0), ParamChange::Keep(0)]).is_err());
+        let add_a = ParamChange::Add { name: "a".to_string(), ty: "int".to_string(), placeholder: "0".to_string() };
+        assert!(editor.change_signature(f_id, vec![ParamChange::Keep(0), add_a]).is_err());
+        assert!(editor.change_signature(point_id, vec![]).is_err());
+        assert_eq!(editor.program().functions[0].node.params.len(), 2);
+    }
+
+    #[test]
+    fn diff_reports_changes_by_uuid() {
+        use crate::{DeclChange, DeclKind};
+
+        let source = "class Point {\n    x: int\n}\n\nfn greet() {\n    print(\"hello\")\n}\n\nfn old() {\n}\n\nfn main() {\n    greet()\n}\n";
+        let bytes = serialize_program(&parse(source), source, &empty_derived()).unwrap();
+        let saved = Module::from_bytes(&bytes).unwrap();
+        let edited = Module::from_bytes(&bytes).unwrap();
+        assert!(saved.diff(&edited).is_empty());
+        assert_eq!(saved.diff(&edited).unchanged, 4);
+
+        let greet_id = edited.find("greet")[0].id();
+        let old_id = edited.find("old")[0].id();
+        let mut editor = edited.edit();
+        editor.rename(greet_id, "salute").unwrap();
+        editor.delete(old_id).unwrap();
+        let added_id = editor.add_from_source("fn helper() {\n}\n").unwrap();
+        let edited = editor.commit();
+
+        let diff = saved.diff(&edited);
+        let change = |id, kind, name: &str| DeclChange { id, kind, name: name.to_string() };
+        assert_eq!(diff.added, vec![change(added_id, DeclKind::Function, "helper")]);
+        assert_eq!(diff.removed, vec![change(old_id, DeclKind::Function, "old")]);
+        assert_eq!(diff.modified, vec![change(greet_id, DeclKind::Function, "salute")]);
+        assert_eq!(diff.unchanged, 2);
+    }
+
     #[test]
     fn delete_reports_dangling_refs() {
         let source = "fn greet() {\n    print(\"hello\")\n}\n\nfn main() {\n    greet()\n}\n";
@@ -649,6 +781,53 @@ fn main() {
         assert!(!module.source().contains("greet"));
     }
 
+    #[test]
+    fn rename_reports_updated_sites() {
+        let source = "fn greet() {\n    print(\"hello\")\n}\n\nfn main() {\n    greet()\n    greet()\n}\n";
+        let module = Module::from_source(source).unwrap();
+        let greet_id = module.find("greet")[0].id();
+        let mut editor = module.edit();
+
+        let result = editor.rename(greet_id, "hello").unwrap();
+        assert_eq!(result.old_name, "greet");
+        assert!(result.dangling.is_empty());
+        assert_eq!(result.updated.len(), 2);
+        for span in &result.updated {
+            assert_eq!(&source[span.start..span.end], "greet()");
+        }
+    }
+
+    #[test]
+    fn find_references_spans_all_kinds() {
+        use crate::xref::RefKind;
+
+        let source = "trait Shape {\n    fn area(self) int\n}\n\nclass Square impl Shape {\n    side: int\n\n    fn area(self) int {\n        return self.side * self.side\n    }\n}\n\nclass Scene {\n    main: Square\n}\n\nfn make(side: int) Square {\n    return Square { side: side }\n}\n\nfn main() {\n    let s: Square = make(2)\n    let all: [Square] = [s]\n    let scene = Scene { main: all[0] }\n    print(scene.main.area())\n}\n";
+        let module = Module::from_source(source).unwrap();
+
+        let square = module.find("Square")[0].id();
+        let sites: Vec<_> = module.find_references(square).iter()
+            .map(|s| (s.kind, s.context.name().to_string(), &source[s.span.start..s.span.end]))
+            .collect();
+        assert_eq!(sites, vec![
+            (RefKind::TypeAnnotation, "Scene".to_string(), "Square"),
+            (RefKind::TypeAnnotation, "make".to_string(), "Square"),
+            (RefKind::Construct, "make".to_string(), "Square { side: side }"),
+            (RefKind::TypeAnnotation, "main".to_string(), "Square"),
+            (RefKind::TypeAnnotation, "main".to_string(), "Square"),
+        ]);
+
+        let shape = module.find("Shape")[0].id();
+        let sites = module.find_references(shape);
+        assert_eq!(sites.len(), 1);
+        assert_eq!(sites[0].kind, RefKind::ImplTrait);
+        assert_eq!(sites[0].context.name(), "Square");
+
+        let make = module.find("make")[0].id();
+        let sites = module.find_references(make);
+        assert_eq!(sites.len(), 1);
+        assert_eq!((sites[0].kind, sites[0].context.name()), (RefKind::Call, "main"));
+    }
+
     #[test]
     fn rename_class_updates_struct_lits_and_types() {
         let source = "class Point {\n    x: int\n    y: int\n}\n\nfn make() Point {\n    return Point { x: 1, y: 2 }\n}\n\nfn main() {\n}\n";
@@ -742,6 +921,50 @@ fn main() {
         assert_eq!(module.find("helper").len(), 1);
     }
 
+    #[test]
+    fn commit_keeps_untouched_declarations_verbatim() {
+        let untouched_fn = "fn  add(a: int,  b: int) int {\n    // keep this comment\n    return a + b\n\n}";
+        let untouched_cls = "pub scoped class Counter {\n    n: int   // count\n}";
+        let source = format!(
+            "{untouched_cls}\n\n{untouched_fn}\n\nfn greet() {{\n    print(\"hello\")\n}}\n\nfn main() {{\n    greet()\n}}\n"
+        );
+        let module = Module::from_source(&source).unwrap();
+        let greet_id = module.find("greet")[0].id();
+        let mut editor = module.edit();
+        editor.replace_from_source(greet_id, "fn greet() {\n  print(\"goodbye\")\n}\n").unwrap();
+        let module = editor.commit();
+
+        let src = module.source();
+        assert!(src.contains(untouched_fn), "untouched function reformatted:\n{src}");
+        assert!(src.contains(untouched_cls), "untouched class reformatted:\n{src}");
+        // The edited function is reformatted
+        assert!(src.contains("fn greet() {\n    print(\"goodbye\")\n}"), "{src}");
+
+        // Spans of a committed module may be stale; a later session must not
+        // copy mismatched text
+        let mut editor = module.edit();
+        editor.add_from_source("fn extra() {\n}\n").unwrap();
+        let module = editor.commit();
+        let reparsed = Module::from_source(module.source()).unwrap();
+        assert_eq!(reparsed.find("add").len(), 1);
+        assert_eq!(reparsed.find("greet").len(), 1);
+        assert_eq!(reparsed.find("extra").len(), 1);
+    }
+
+    #[test]
+    fn commit_reformats_rename_reference_sites() {
+        let source = "fn greet() {\n    print(\"hello\")\n}\n\nfn main() {\n    greet()  // call it\n}\n";
+        let module = Module::from_source(source).unwrap();
+        let greet_id = module.find("greet")[0].id();
+        let mut editor = module.edit();
+        editor.rename(greet_id, "hello").unwrap();
+        let module = editor.commit();
+
+        // `main` changed through its call site, so it is reprinted
+        assert!(module.source().contains("hello()"), "{}", module.source());
+        assert!(!module.source().contains("greet"), "{}", module.source());
+    }
+
     #[test]
     fn parse_error_returns_sdk_error() {
         let source = "fn main() {\n}\n";
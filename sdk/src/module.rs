@@ -6,13 +6,15 @@ use pluto::derived::{
     ResolvedSignature, ResolvedTraitInfo,
 };
 use pluto::parser::ast::Program;
-use pluto::span::Span;
+use pluto::span::{Span, Spanned};
+use pluto::visit::{walk_type_expr, Visitor};
 
 use crate::decl::{DeclKind, DeclRef};
+use crate::diff::ModuleDiff;
 use crate::editor::ModuleEditor;
 use crate::error::SdkError;
 use crate::index::ModuleIndex;
-use crate::xref::{CallSite, ConstructSite, EnumUsageSite, RaiseSite};
+use crate::xref::{CallSite, ConstructSite, EnumUsageSite, RaiseSite, RefKind, RefSite};
 
 /// Primary entry point for querying a Pluto program.
 /// Owns the deserialized Program and source text, with pre-built indexes and derived type data.
@@ -266,6 +268,112 @@ impl Module {
         }).collect()
     }
 
+    /// Get every site that refers to the given declaration, whatever its kind:
+    /// calls of a function, constructions of a class, enum usages, raises of an
+    /// error, `impl` lists naming a trait, and type annotations naming a class,
+    /// enum, or trait. Sites are in source order.
+    pub fn find_references(&self, id: Uuid) -> Vec<RefSite<'_>> {
+        let Some(decl) = self.get(id) else {
+            return vec![];
+        };
+        let mut sites = Vec::new();
+        match decl.kind() {
+            DeclKind::Function => {
+                sites.extend(self.callers_of(id).into_iter().map(|c| RefSite {
+                    kind: RefKind::Call,
+                    context: DeclRef::function(c.caller),
+                    span: c.span,
+                }));
+            }
+            DeclKind::Class => {
+                sites.extend(self.constructors_of(id).into_iter().map(|c| RefSite {
+                    kind: RefKind::Construct,
+                    context: DeclRef::function(c.function),
+                    span: c.span,
+                }));
+            }
+            DeclKind::Enum => {
+                sites.extend(self.enum_usages_of(id).into_iter().map(|u| RefSite {
+                    kind: RefKind::EnumUsage,
+                    context: DeclRef::function(u.function),
+                    span: u.span,
+                }));
+            }
+            DeclKind::Error => {
+                sites.extend(self.raise_sites_of(id).into_iter().map(|r| RefSite {
+                    kind: RefKind::Raise,
+                    context: DeclRef::function(r.function),
+                    span: r.span,
+                }));
+            }
+            DeclKind::Trait => {
+                for c in &self.program.classes {
+                    for t in c.node.impl_traits.iter().filter(|t| t.node == decl.name()) {
+                        sites.push(RefSite { kind: RefKind::ImplTrait, context: DeclRef::class(&c.node), span: t.span });
+                    }
+                }
+            }
+            _ => return vec![],
+        }
+        if matches!(decl.kind(), DeclKind::Class | DeclKind::Enum | DeclKind::Trait) {
+            self.collect_type_refs(decl.name(), &mut sites);
+        }
+        sites.sort_by_key(|s| (s.span.file_id, s.span.start));
+        sites
+    }
+
+    /// Collect type annotations naming `name`, attributed to the declaration that holds them.
+    fn collect_type_refs<'a>(&'a self, name: &str, sites: &mut Vec<RefSite<'a>>) {
+        let mut push = |context: DeclRef<'a>, refs: TypeRefs| {
+            sites.extend(refs.spans.into_iter().map(|span| RefSite { kind: RefKind::TypeAnnotation, context, span }));
+        };
+        let in_function = |f: &Spanned<Function>| {
+            let mut refs = TypeRefs::new(name);
+            refs.visit_function(f);
+            refs
+        };
+        let in_fields = |fields: &[Field]| {
+            let mut refs = TypeRefs::new(name);
+            for field in fields {
+                refs.visit_type_expr(&field.ty);
+            }
+            refs
+        };
+
+        for f in &self.program.functions {
+            push(DeclRef::function(&f.node), in_function(f));
+        }
+        for c in &self.program.classes {
+            push(DeclRef::class(&c.node), in_fields(&c.node.fields));
+            for m in &c.node.methods {
+                push(DeclRef::function(&m.node), in_function(m));
+            }
+        }
+        for t in &self.program.traits {
+            let mut refs = TypeRefs::new(name);
+            refs.visit_trait(t);
+            push(DeclRef::trait_decl(&t.node), refs);
+        }
+        for e in &self.program.errors {
+            push(DeclRef::error_decl(&e.node), in_fields(&e.node.fields));
+        }
+        if let Some(app) = &self.program.app {
+            push(DeclRef::app(&app.node), in_fields(&app.node.inject_fields));
+            for m in &app.node.methods {
+                push(DeclRef::function(&m.node), in_function(m));
+            }
+        }
+    }
+
+    // --- Comparison ---
+
+    /// Compare this module with a newer version of it. Top-level declarations are
+    /// matched by UUID and compared by content hash, so a rename counts as a
+    /// modification rather than an add and a remove.
+    pub fn diff(&self, other: &Module) -> ModuleDiff {
+        crate::diff::diff_programs(&self.program, &other.program)
+    }
+
     // --- Source access ---
 
     pub fn source(&self) -> &str {
@@ -439,6 +547,34 @@ impl Module {
     }
 }
 
+/// Spans of the type expressions that name a given declaration.
+struct TypeRefs<'n> {
+    name: &'n str,
+    spans: Vec<Span>,
+}
+
+impl<'n> TypeRefs<'n> {
+    fn new(name: &'n str) -> Self {
+        Self { name, spans: Vec::new() }
+    }
+}
+
+impl Visitor for TypeRefs<'_> {
+    fn visit_type_expr(&mut self, te: &Spanned<TypeExpr>) {
+        let named = match &te.node {
+            TypeExpr::Named(n) | TypeExpr::Generic { name: n, .. } => n == self.name,
+            TypeExpr::Qualified { module, name } => {
+                self.name.strip_prefix(module.as_str()).and_then(|rest| rest.strip_prefix('.')) == Some(name)
+            }
+            _ => false,
+        };
+        if named {
+            self.spans.push(te.span);
+        }
+        walk_type_expr(self, te);
+    }
+}
+
 // --- AST search helpers for finding nodes at a specific span ---
 
 use pluto::parser::ast::*;
@@ -513,7 +649,7 @@ fn find_stmt_in_block<'a>(block: &'a Block, target: Span) -> Option<&'a Stmt> {
 
 fn find_stmt_nested<'a>(stmt: &'a Stmt, target: Span) -> Option<&'a Stmt> {
     match stmt {
-        Stmt::If { then_block, else_block, .. } => {
+        Stmt::If { then_block, else_block, .. } | Stmt::IfLet { then_block, else_block, .. } => {
             if let Some(s) = find_stmt_in_block(&then_block.node, target) {
                 return Some(s);
             }
@@ -535,6 +671,13 @@ fn find_stmt_nested<'a>(stmt: &'a Stmt, target: Span) -> Option<&'a Stmt> {
                 }
             }
         }
+        Stmt::IntMatch { arms, .. } => {
+            for arm in arms {
+                if let Some(s) = find_stmt_in_block(&arm.body.node, target) {
+                    return Some(s);
+                }
+            }
+        }
         Stmt::Select { arms, default } => {
             for arm in arms {
                 if let Some(s) = find_stmt_in_block(&arm.body.node, target) {
@@ -554,7 +697,7 @@ fn find_stmt_nested<'a>(stmt: &'a Stmt, target: Span) -> Option<&'a Stmt> {
 
 fn find_expr_in_stmt<'a>(stmt: &'a Stmt, target: Span) -> Option<&'a Expr> {
     match stmt {
-        Stmt::Let { value, .. } => find_expr_recursive(&value.node, value.span, target),
+        Stmt::Let { value, .. } | Stmt::LetStruct { value, .. } => find_expr_recursive(&value.node, value.span, target),
         Stmt::Return(Some(e)) => find_expr_recursive(&e.node, e.span, target),
         Stmt::Return(None) => None,
         Stmt::Assign { value, .. } => find_expr_recursive(&value.node, value.span, target),
@@ -562,7 +705,8 @@ fn find_expr_in_stmt<'a>(stmt: &'a Stmt, target: Span) -> Option<&'a Expr> {
             find_expr_recursive(&object.node, object.span, target)
                 .or_else(|| find_expr_recursive(&value.node, value.span, target))
         }
-        Stmt::If { condition, then_block, else_block } => {
+        Stmt::If { condition, then_block, else_block }
+        | Stmt::IfLet { value: condition, then_block, else_block, .. } => {
             find_expr_recursive(&condition.node, condition.span, target)
                 .or_else(|| find_expr_in_block(&then_block.node, target))
                 .or_else(|| else_block.as_ref().and_then(|eb| find_expr_in_block(&eb.node, target)))
@@ -591,6 +735,15 @@ fn find_expr_in_stmt<'a>(stmt: &'a Stmt, target: Span) -> Option<&'a Expr> {
                     None
                 })
         }
+        Stmt::IntMatch { expr, arms } => {
+            find_expr_recursive(&expr.node, expr.span, target)
+                .or_else(|| arms.iter().find_map(|arm| find_expr_in_block(&arm.body.node, target)))
+        }
+        Stmt::NullableMatch { expr, some_arm, none_arm } => {
+            find_expr_recursive(&expr.node, expr.span, target)
+                .or_else(|| some_arm.as_ref().and_then(|arm| find_expr_in_block(&arm.body.node, target)))
+                .or_else(|| none_arm.as_ref().and_then(|nb| find_expr_in_block(&nb.node, target)))
+        }
         Stmt::Raise { fields, .. } => {
             for (_, e) in fields {
                 if let Some(found) = find_expr_recursive(&e.node, e.span, target) {
@@ -638,6 +791,11 @@ fn find_expr_in_stmt<'a>(stmt: &'a Stmt, target: Span) -> Option<&'a Expr> {
             }
             find_expr_in_block(&body.node, target)
         }
+        Stmt::Try { body, handler, finally, .. } => {
+            find_expr_in_block(&body.node, target)
+                .or_else(|| find_expr_in_block(&handler.node, target))
+                .or_else(|| finally.as_ref().and_then(|f| find_expr_in_block(&f.node, target)))
+        }
         Stmt::Yield { value } => {
             find_expr_recursive(&value.node, value.span, target)
         }
@@ -737,8 +895,12 @@ fn find_expr_recursive<'a>(expr: &'a Expr, span: Span, target: Span) -> Option<&
             }
             None
         }
-        Expr::Propagate { expr: inner } | Expr::Cast { expr: inner, .. } => {
+        Expr::Propagate { expr: inner } | Expr::Cast { expr: inner, .. } | Expr::Is { expr: inner, .. } => {
+            find_expr_recursive(&inner.node, inner.span, target)
+        }
+        Expr::PropagateMap { expr: inner, mapper } => {
             find_expr_recursive(&inner.node, inner.span, target)
+                .or_else(|| find_expr_recursive(&mapper.node, mapper.span, target))
         }
         Expr::Catch { expr: inner, handlers } => {
             find_expr_recursive(&inner.node, inner.span, target)
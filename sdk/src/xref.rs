@@ -2,6 +2,8 @@ use pluto::parser::ast::{Expr, Function, Stmt};
 use pluto::span::Span;
 use uuid::Uuid;
 
+use crate::decl::DeclRef;
+
 /// A call site where a function is invoked.
 pub struct CallSite<'a> {
     pub caller: &'a Function,
@@ -35,6 +37,28 @@ pub struct RaiseSite<'a> {
     pub span: Span,
 }
 
+/// What a `RefSite` refers to its declaration through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefKind {
+    Call,
+    Construct,
+    /// A type annotation: param, return, field, `let`, cast, or type argument.
+    TypeAnnotation,
+    EnumUsage,
+    Raise,
+    /// A trait named in a class's `impl` list.
+    ImplTrait,
+}
+
+/// Any site that refers to a declaration, as returned by `Module::find_references`.
+pub struct RefSite<'a> {
+    pub kind: RefKind,
+    /// The declaration the site appears in: the enclosing function, or the
+    /// class, trait, error, or app whose fields or `impl` list hold it.
+    pub context: DeclRef<'a>,
+    pub span: Span,
+}
+
 // Owned (non-borrowing) variants for the index — store enough info to reconstruct
 // the borrowed versions when queried through Module.
 
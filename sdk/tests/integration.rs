@@ -1,4 +1,4 @@
-use pluto_sdk::{DeclKind, Module};
+use pluto_sdk::{DeclKind, Module, Visibility};
 use std::path::PathBuf;
 
 fn examples_dir() -> PathBuf {
@@ -57,6 +57,19 @@ fn load_binary_ast_example_from_source() {
     assert!(!main_fns.is_empty());
 }
 
+#[test]
+fn decl_visibility() {
+    let module = Module::from_source(
+        "pub fn shown() int {\n    return 1\n}\n\npub(module) class Internal {\n    x: int\n}\n\nenum Hidden {\n    A\n}\n\nfn main() {\n}\n",
+    ).unwrap();
+    let visibility = |name: &str| module.find(name)[0].visibility();
+    assert_eq!(visibility("shown"), Some(Visibility::Public));
+    assert_eq!(visibility("Internal"), Some(Visibility::Module));
+    assert_eq!(visibility("Hidden"), Some(Visibility::Private));
+    let variant = module.find("Hidden")[0].as_enum().unwrap().variants[0].id;
+    assert_eq!(module.get(variant).unwrap().visibility(), None);
+}
+
 #[test]
 fn binary_round_trip() {
     let path = examples_dir().join("binary-ast").join("main.pt");
@@ -309,6 +309,30 @@ impl VisitMut for AmbientRewriter<'_> {
                 inner_rewriter.visit_block_mut(body);
                 return;
             }
+            Stmt::IfLet { binding, value, then_block, else_block } => {
+                self.visit_expr_mut(value);
+                let mut inner = self.active.clone();
+                inner.remove(&binding.node);
+                let mut inner_rewriter = AmbientRewriter { active: &inner };
+                inner_rewriter.visit_block_mut(then_block);
+                if let Some(eb) = else_block {
+                    self.visit_block_mut(eb);
+                }
+                return;
+            }
+            Stmt::NullableMatch { expr, some_arm, none_arm } => {
+                self.visit_expr_mut(expr);
+                if let Some(arm) = some_arm {
+                    let mut inner = self.active.clone();
+                    inner.remove(&arm.binding.node);
+                    let mut inner_rewriter = AmbientRewriter { active: &inner };
+                    inner_rewriter.visit_block_mut(&mut arm.body);
+                }
+                if let Some(nb) = none_arm {
+                    self.visit_block_mut(nb);
+                }
+                return;
+            }
             Stmt::Match { expr, arms } => {
                 self.visit_expr_mut(expr);
                 for arm in arms {
@@ -334,6 +358,17 @@ impl VisitMut for AmbientRewriter<'_> {
                 inner_rewriter.visit_block_mut(body);
                 return;
             }
+            Stmt::Try { body, var, handler, finally } => {
+                self.visit_block_mut(body);
+                let mut inner = self.active.clone();
+                inner.remove(&var.node);
+                let mut inner_rewriter = AmbientRewriter { active: &inner };
+                inner_rewriter.visit_block_mut(handler);
+                if let Some(finally) = finally {
+                    self.visit_block_mut(finally);
+                }
+                return;
+            }
             _ => {}
         }
 
@@ -351,6 +386,11 @@ impl VisitMut for AmbientRewriter<'_> {
             if let Stmt::Let { name, .. } = &stmt.node {
                 active.remove(&name.node);
             }
+            if let Stmt::LetStruct { fields, .. } = &stmt.node {
+                for (field, rename) in fields {
+                    active.remove(&rename.as_ref().unwrap_or(field).node);
+                }
+            }
             if let Stmt::LetChan { sender, receiver, .. } = &stmt.node {
                 active.remove(&sender.node);
                 active.remove(&receiver.node);
@@ -1,13 +1,24 @@
-//! Binary container format for serialized Pluto ASTs (v3).
+//! Binary container format for serialized Pluto ASTs (v5).
 //!
-//! Container layout (20-byte header + three length-prefixed sections):
+//! Container layout (24-byte header + three length-prefixed sections):
 //!
 //! ```text
-//! [4B magic "PLTO"] [4B schema version u32 LE] [4B source offset u32 LE] [4B AST offset u32 LE] [4B derived offset u32 LE]
+//! [4B magic "PLTO"] [4B schema version u32 LE] [4B flags u32 LE] [4B source offset u32 LE] [4B AST offset u32 LE] [4B derived offset u32 LE]
 //! [Source section: 4B length u32 LE + UTF-8 bytes]
 //! [AST section: 4B length u32 LE + bincode bytes]
 //! [Derived section: 4B length u32 LE + bincode bytes]
 //! ```
+//!
+//! With the deflate flag set, everything after the header is a single deflate
+//! stream of the sections; the offsets still locate them in the inflated
+//! container. v2–v4 headers have no flags word (20 bytes) and are never compressed.
+//!
+//! Readers accept every version from `FormatVersion::OLDEST_SUPPORTED` up to
+//! `FormatVersion::CURRENT`, migrating older AST sections as they decode, and
+//! report a newer version as `BinaryError::NewerVersion` instead of misparsing it.
+
+use std::borrow::Cow;
+use std::io::{Read, Write};
 
 use crate::derived::DerivedInfo;
 use crate::parser::ast::Program;
@@ -15,34 +26,115 @@ use crate::parser::ast::Program;
 /// Magic bytes identifying a binary Pluto file.
 const MAGIC: &[u8; 4] = b"PLTO";
 
-/// Current schema version.
-const SCHEMA_VERSION: u32 = 3;
+/// Header size in bytes: magic (4) + version (4) + flags (4) + source_offset (4) + ast_offset (4) + derived_offset (4).
+const HEADER_SIZE: usize = 24;
+
+/// Header size of v2–v4 containers, which have no flags word.
+const LEGACY_HEADER_SIZE: usize = 20;
 
-/// Header size in bytes: magic (4) + version (4) + source_offset (4) + ast_offset (4) + derived_offset (4).
-const HEADER_SIZE: usize = 20;
+/// Header flag: the sections are deflate-compressed.
+const FLAG_DEFLATE: u32 = 1;
+
+/// Schema version of a binary container, stored right after the magic bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FormatVersion(pub u32);
+
+impl FormatVersion {
+    /// The version `serialize_program` writes.
+    pub const CURRENT: FormatVersion = FormatVersion(5);
+    /// The oldest version `deserialize_program` can still upgrade.
+    pub const OLDEST_SUPPORTED: FormatVersion = FormatVersion(2);
+
+    pub fn is_current(self) -> bool {
+        self == Self::CURRENT
+    }
+}
+
+impl std::fmt::Display for FormatVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+/// How the sections of a container are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    #[default]
+    None,
+    Deflate,
+}
+
+/// Rewrites an AST section encoded at one schema version into the next one's layout.
+type AstMigration = fn(&mut Vec<u8>);
+
+/// `AST_MIGRATIONS[i]` upgrades an AST section from `OLDEST_SUPPORTED + i` to
+/// the version after it. Bumping `CURRENT` means appending a migration here.
+const AST_MIGRATIONS: &[AstMigration] = &[
+    // v3 only added derived metadata, which decodes through serde defaults
+    |_ast| {},
+    // v4 appended `Program::comments`; older ASTs get it as an empty list
+    |ast| ast.push(0),
+    // v5 only added the header flags word
+    |_ast| {},
+];
 
 /// Errors that can occur during binary serialization/deserialization.
 #[derive(Debug, thiserror::Error)]
 pub enum BinaryError {
     #[error("invalid magic number: expected PLTO")]
     InvalidMagic,
-    #[error("unsupported schema version {0} (expected {SCHEMA_VERSION})")]
+    #[error("unsupported schema version {0} (oldest readable is {oldest})", oldest = FormatVersion::OLDEST_SUPPORTED.0)]
     UnsupportedVersion(u32),
+    #[error("file uses schema version {0}, newer than this build reads ({current}); upgrade pluto to open it", current = FormatVersion::CURRENT.0)]
+    NewerVersion(u32),
+    #[error("unknown header flags {0:#x}")]
+    UnknownFlags(u32),
     #[error("truncated file: expected at least {expected} bytes, got {got}")]
     Truncated { expected: usize, got: usize },
     #[error("bincode encode error: {0}")]
     Encode(String),
     #[error("bincode decode error: {0}")]
     Decode(String),
+    #[error("corrupt compressed sections: {0}")]
+    Decompress(String),
     #[error("invalid UTF-8 in source section: {0}")]
     InvalidUtf8(#[from] std::string::FromUtf8Error),
 }
 
+/// A parsed container header.
+struct Header {
+    version: FormatVersion,
+    flags: u32,
+    source_offset: usize,
+    ast_offset: usize,
+    derived_offset: usize,
+}
+
+impl Header {
+    fn size(&self) -> usize {
+        if self.version.0 >= 5 { HEADER_SIZE } else { LEGACY_HEADER_SIZE }
+    }
+
+    fn compression(&self) -> Compression {
+        if self.flags & FLAG_DEFLATE != 0 { Compression::Deflate } else { Compression::None }
+    }
+}
+
 /// Serialize a parsed `Program`, its source text, and derived analysis data into the binary container format.
 pub fn serialize_program(
     program: &Program,
     source: &str,
     derived: &DerivedInfo,
+) -> Result<Vec<u8>, BinaryError> {
+    serialize_program_with(program, source, derived, Compression::None)
+}
+
+/// Like `serialize_program`, storing the sections with the given compression.
+pub fn serialize_program_with(
+    program: &Program,
+    source: &str,
+    derived: &DerivedInfo,
+    compression: Compression,
 ) -> Result<Vec<u8>, BinaryError> {
     let config = bincode::config::standard();
     let ast_bytes = bincode::serde::encode_to_vec(program, config)
@@ -58,13 +150,18 @@ pub fn serialize_program(
     let source_offset = HEADER_SIZE as u32;
     let ast_offset = (HEADER_SIZE + source_section_size) as u32;
     let derived_offset = (HEADER_SIZE + source_section_size + ast_section_size) as u32;
+    let flags = match compression {
+        Compression::None => 0,
+        Compression::Deflate => FLAG_DEFLATE,
+    };
 
     let total_size = HEADER_SIZE + source_section_size + ast_section_size + derived_section_size;
     let mut buf = Vec::with_capacity(total_size);
 
     // Header
     buf.extend_from_slice(MAGIC);
-    buf.extend_from_slice(&SCHEMA_VERSION.to_le_bytes());
+    buf.extend_from_slice(&FormatVersion::CURRENT.0.to_le_bytes());
+    buf.extend_from_slice(&flags.to_le_bytes());
     buf.extend_from_slice(&source_offset.to_le_bytes());
     buf.extend_from_slice(&ast_offset.to_le_bytes());
     buf.extend_from_slice(&derived_offset.to_le_bytes());
@@ -81,139 +178,166 @@ pub fn serialize_program(
     buf.extend_from_slice(&(derived_bytes.len() as u32).to_le_bytes());
     buf.extend_from_slice(&derived_bytes);
 
-    Ok(buf)
+    if compression == Compression::Deflate {
+        let mut encoder = flate2::write::DeflateEncoder::new(buf[..HEADER_SIZE].to_vec(), flate2::Compression::default());
+        encoder
+            .write_all(&buf[HEADER_SIZE..])
+            .and_then(|_| encoder.finish())
+            .map_err(|e| BinaryError::Encode(e.to_string()))
+    } else {
+        Ok(buf)
+    }
 }
 
 /// Deserialize a binary container back into a `Program`, its source text, and derived analysis data.
+/// Containers written at an older schema version are migrated on the way in,
+/// and compressed ones are inflated.
 pub fn deserialize_program(data: &[u8]) -> Result<(Program, String, DerivedInfo), BinaryError> {
-    let _version = validate_header(data)?;
-
-    let source = read_source_section(data)?;
-    let program = read_ast_section(data)?;
-    let derived = read_derived_section(data)?;
+    let header = read_header(data)?;
+    let data = inflate(data, &header)?;
 
-    // Note: v2 files don't have metadata (meta = None), which is handled
-    // automatically by serde's #[serde(default)] attribute
+    let source = read_source_section(&data, &header)?;
+    let program = read_ast_section(&data, &header)?;
+    let derived = read_derived_section(&data, &header)?;
 
     Ok((program, source, derived))
 }
 
-/// Check whether a byte slice looks like a valid Pluto binary container.
-/// Checks magic bytes, minimum header size, and schema version.
+/// Check whether a byte slice is a Pluto binary container: PLTO magic and a
+/// full header. The version is not checked here, so a container from a newer
+/// build is still routed to `deserialize_program` and reported as such.
 pub fn is_binary_format(data: &[u8]) -> bool {
-    if data.len() < HEADER_SIZE || &data[..4] != MAGIC {
-        return false;
-    }
-    let version = u32::from_le_bytes(data[4..8].try_into().unwrap());
-    version == 2 || version == 3
+    data.len() >= LEGACY_HEADER_SIZE && &data[..4] == MAGIC
+}
+
+/// Read the schema version of a binary container, rejecting ones this build can't read.
+pub fn format_version(data: &[u8]) -> Result<FormatVersion, BinaryError> {
+    read_header(data).map(|h| h.version)
+}
+
+/// Read how a binary container's sections are stored.
+pub fn compression_of(data: &[u8]) -> Result<Compression, BinaryError> {
+    read_header(data).map(|h| h.compression())
+}
+
+/// Rewrite a binary container at the current schema version, keeping its compression.
+pub fn upgrade(data: &[u8]) -> Result<Vec<u8>, BinaryError> {
+    let compression = compression_of(data)?;
+    let (program, source, derived) = deserialize_program(data)?;
+    serialize_program_with(&program, &source, &derived, compression)
 }
 
 /// Read only the source text from a binary container, without deserializing the AST.
 pub fn read_source_only(data: &[u8]) -> Result<String, BinaryError> {
-    let _version = validate_header(data)?;
-    read_source_section(data)
+    let header = read_header(data)?;
+    read_source_section(&inflate(data, &header)?, &header)
 }
 
 // --- internal helpers ---
 
-fn validate_header(data: &[u8]) -> Result<u32, BinaryError> {
-    if data.len() < HEADER_SIZE {
+fn read_header(data: &[u8]) -> Result<Header, BinaryError> {
+    if data.len() < LEGACY_HEADER_SIZE {
         return Err(BinaryError::Truncated {
-            expected: HEADER_SIZE,
+            expected: LEGACY_HEADER_SIZE,
             got: data.len(),
         });
     }
     if &data[..4] != MAGIC {
         return Err(BinaryError::InvalidMagic);
     }
-    let version = u32::from_le_bytes(data[4..8].try_into().unwrap());
+    let word = |at: usize| u32::from_le_bytes(data[at..at + 4].try_into().unwrap());
+    let version = word(4);
 
-    // Accept v2 and v3
-    if version != 2 && version != 3 {
+    if version > FormatVersion::CURRENT.0 {
+        return Err(BinaryError::NewerVersion(version));
+    }
+    if version < FormatVersion::OLDEST_SUPPORTED.0 {
         return Err(BinaryError::UnsupportedVersion(version));
     }
 
-    Ok(version)
+    // v5 inserted the flags word before the section offsets
+    let (flags, offsets) = if version >= 5 {
+        if data.len() < HEADER_SIZE {
+            return Err(BinaryError::Truncated {
+                expected: HEADER_SIZE,
+                got: data.len(),
+            });
+        }
+        (word(8), 12)
+    } else {
+        (0, 8)
+    };
+    if flags & !FLAG_DEFLATE != 0 {
+        return Err(BinaryError::UnknownFlags(flags));
+    }
+
+    Ok(Header {
+        version: FormatVersion(version),
+        flags,
+        source_offset: word(offsets) as usize,
+        ast_offset: word(offsets + 4) as usize,
+        derived_offset: word(offsets + 8) as usize,
+    })
 }
 
-fn read_source_section(data: &[u8]) -> Result<String, BinaryError> {
-    let source_offset = u32::from_le_bytes(data[8..12].try_into().unwrap()) as usize;
+/// The container with its sections inflated, so section offsets index into it.
+fn inflate<'a>(data: &'a [u8], header: &Header) -> Result<Cow<'a, [u8]>, BinaryError> {
+    if header.compression() == Compression::None {
+        return Ok(Cow::Borrowed(data));
+    }
+    let mut inflated = data[..header.size()].to_vec();
+    flate2::read::DeflateDecoder::new(&data[header.size()..])
+        .read_to_end(&mut inflated)
+        .map_err(|e| BinaryError::Decompress(e.to_string()))?;
+    Ok(Cow::Owned(inflated))
+}
 
-    if data.len() < source_offset + 4 {
+/// The bytes of the length-prefixed section at `offset`.
+fn section(data: &[u8], offset: usize) -> Result<&[u8], BinaryError> {
+    if data.len() < offset + 4 {
         return Err(BinaryError::Truncated {
-            expected: source_offset + 4,
+            expected: offset + 4,
             got: data.len(),
         });
     }
 
-    let source_len =
-        u32::from_le_bytes(data[source_offset..source_offset + 4].try_into().unwrap()) as usize;
+    let len = u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()) as usize;
 
-    let source_end = source_offset + 4 + source_len;
-    if data.len() < source_end {
+    let end = offset + 4 + len;
+    if data.len() < end {
         return Err(BinaryError::Truncated {
-            expected: source_end,
+            expected: end,
             got: data.len(),
         });
     }
 
-    let source = String::from_utf8(data[source_offset + 4..source_end].to_vec())?;
-    Ok(source)
+    Ok(&data[offset + 4..end])
 }
 
-fn read_ast_section(data: &[u8]) -> Result<Program, BinaryError> {
-    let ast_offset = u32::from_le_bytes(data[12..16].try_into().unwrap()) as usize;
-
-    if data.len() < ast_offset + 4 {
-        return Err(BinaryError::Truncated {
-            expected: ast_offset + 4,
-            got: data.len(),
-        });
-    }
-
-    let ast_len =
-        u32::from_le_bytes(data[ast_offset..ast_offset + 4].try_into().unwrap()) as usize;
+fn read_source_section(data: &[u8], header: &Header) -> Result<String, BinaryError> {
+    let source = String::from_utf8(section(data, header.source_offset)?.to_vec())?;
+    Ok(source)
+}
 
-    let ast_end = ast_offset + 4 + ast_len;
-    if data.len() < ast_end {
-        return Err(BinaryError::Truncated {
-            expected: ast_end,
-            got: data.len(),
-        });
+fn read_ast_section(data: &[u8], header: &Header) -> Result<Program, BinaryError> {
+    let mut ast_bytes = Cow::Borrowed(section(data, header.ast_offset)?);
+    let pending = (header.version.0 - FormatVersion::OLDEST_SUPPORTED.0) as usize;
+    for migrate in &AST_MIGRATIONS[pending..] {
+        migrate(ast_bytes.to_mut());
     }
 
     let config = bincode::config::standard();
     let (program, _bytes_read): (Program, usize) =
-        bincode::serde::decode_from_slice(&data[ast_offset + 4..ast_end], config)
+        bincode::serde::decode_from_slice(&ast_bytes, config)
             .map_err(|e| BinaryError::Decode(e.to_string()))?;
 
     Ok(program)
 }
 
-fn read_derived_section(data: &[u8]) -> Result<DerivedInfo, BinaryError> {
-    let derived_offset = u32::from_le_bytes(data[16..20].try_into().unwrap()) as usize;
-
-    if data.len() < derived_offset + 4 {
-        return Err(BinaryError::Truncated {
-            expected: derived_offset + 4,
-            got: data.len(),
-        });
-    }
-
-    let derived_len =
-        u32::from_le_bytes(data[derived_offset..derived_offset + 4].try_into().unwrap()) as usize;
-
-    let derived_end = derived_offset + 4 + derived_len;
-    if data.len() < derived_end {
-        return Err(BinaryError::Truncated {
-            expected: derived_end,
-            got: data.len(),
-        });
-    }
-
+fn read_derived_section(data: &[u8], header: &Header) -> Result<DerivedInfo, BinaryError> {
     let config = bincode::config::standard();
     let (derived, _bytes_read): (DerivedInfo, usize) =
-        bincode::serde::decode_from_slice(&data[derived_offset + 4..derived_end], config)
+        bincode::serde::decode_from_slice(section(data, header.derived_offset)?, config)
             .map_err(|e| BinaryError::Decode(e.to_string()))?;
 
     Ok(derived)
@@ -400,8 +524,23 @@ fn main() {
         let mut data = vec![0u8; 32];
         data[..4].copy_from_slice(MAGIC);
         data[4..8].copy_from_slice(&99u32.to_le_bytes()); // future version
-        let result = deserialize_program(&data);
-        assert!(matches!(result, Err(BinaryError::UnsupportedVersion(99))));
+        assert!(is_binary_format(&data));
+        let err = deserialize_program(&data).unwrap_err();
+        assert!(matches!(err, BinaryError::NewerVersion(99)));
+        assert!(err.to_string().contains("newer than this build reads (5)"), "{err}");
+
+        data[4..8].copy_from_slice(&1u32.to_le_bytes()); // before the oldest migration
+        assert!(matches!(deserialize_program(&data), Err(BinaryError::UnsupportedVersion(1))));
+        assert!(matches!(format_version(&data), Err(BinaryError::UnsupportedVersion(1))));
+    }
+
+    #[test]
+    fn truncation_anywhere_is_an_error() {
+        let source = "class Point {\n    x: int\n}\n\nfn main() {\n    let p = Point { x: 1 }\n}\n";
+        let bytes = serialize_program(&parse(source), source, &empty_derived()).unwrap();
+        for len in 0..bytes.len() {
+            assert!(deserialize_program(&bytes[..len]).is_err(), "prefix of {len} bytes decoded");
+        }
     }
 
     #[test]
@@ -430,7 +569,7 @@ fn main() {
         // Valid header but truncated source section
         let mut data = vec![0u8; 20];
         data[..4].copy_from_slice(MAGIC);
-        data[4..8].copy_from_slice(&SCHEMA_VERSION.to_le_bytes());
+        data[4..8].copy_from_slice(&FormatVersion::CURRENT.0.to_le_bytes());
         data[8..12].copy_from_slice(&20u32.to_le_bytes()); // source at offset 20
         data[12..16].copy_from_slice(&24u32.to_le_bytes()); // ast at offset 24
         data[16..20].copy_from_slice(&28u32.to_le_bytes()); // derived at offset 28
@@ -465,6 +604,7 @@ fn main() {
                 param_types: vec![PlutoType::String],
                 return_type: PlutoType::Int,
                 is_fallible: true,
+                declared_errors: None,
             },
         );
 
@@ -506,10 +646,10 @@ fn main() {
         derived.source_hash = DerivedInfo::compute_source_hash(source);
         let v3_bytes = serialize_program(&program, source, &derived).unwrap();
 
-        // Check that version is 3
+        // Check that version is current
         assert_eq!(&v3_bytes[..4], b"PLTO");
         let version = u32::from_le_bytes([v3_bytes[4], v3_bytes[5], v3_bytes[6], v3_bytes[7]]);
-        assert_eq!(version, 3);
+        assert_eq!(version, FormatVersion::CURRENT.0);
 
         // Read v3
         let (_prog, src, deriv) = deserialize_program(&v3_bytes).unwrap();
@@ -521,6 +661,106 @@ fn main() {
         assert!(deriv.is_stale("fn main() { let x = 1 }"));
     }
 
+    /// Rebuild a current container as v3: the AST section loses its trailing
+    /// empty `comments` list.
+    fn as_v3(bytes: &[u8]) -> Vec<u8> {
+        // v3 headers also lack the flags word, which shifts every section back 4 bytes
+        let word = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap()) as usize;
+        let (source_offset, ast_offset, derived_offset) = (word(12), word(16), word(20));
+        let mut v3 = Vec::new();
+        v3.extend_from_slice(MAGIC);
+        v3.extend_from_slice(&3u32.to_le_bytes());
+        for offset in [source_offset - 4, ast_offset - 4, derived_offset - 5] {
+            v3.extend_from_slice(&(offset as u32).to_le_bytes());
+        }
+        v3.extend_from_slice(&bytes[source_offset..ast_offset]);
+        v3.extend_from_slice(&((derived_offset - ast_offset - 5) as u32).to_le_bytes());
+        v3.extend_from_slice(&bytes[ast_offset + 4..derived_offset - 1]);
+        v3.extend_from_slice(&bytes[derived_offset..]);
+        v3
+    }
+
+    #[test]
+    fn v3_without_comments_still_decodes() {
+        let source = "fn main() {}";
+        let program = parse(source);
+        let bytes = serialize_program(&program, source, &empty_derived()).unwrap();
+        let v3 = as_v3(&bytes);
+
+        let (decoded, src, _) = deserialize_program(&v3).unwrap();
+        assert_eq!(src, source);
+        assert_eq!(decoded.functions.len(), 1);
+        assert!(decoded.comments.is_empty());
+    }
+
+    #[test]
+    fn upgrade_rewrites_at_current_version() {
+        let source = "fn main() {}";
+        let bytes = serialize_program(&parse(source), source, &empty_derived()).unwrap();
+        let v3 = as_v3(&bytes);
+        assert_eq!(format_version(&v3).unwrap(), FormatVersion(3));
+        assert!(!format_version(&v3).unwrap().is_current());
+
+        let upgraded = upgrade(&v3).unwrap();
+        assert!(format_version(&upgraded).unwrap().is_current());
+        assert_eq!(upgraded, bytes);
+    }
+
+    /// A source big enough for compression to matter.
+    fn large_source() -> String {
+        let mut source = String::new();
+        for i in 0..200 {
+            source.push_str(&format!("// Adds {i} to the running total of the accumulator.\nfn add_{i}(total: int) int {{\n    return total + {i}\n}}\n\n"));
+        }
+        source.push_str("fn main() {\n}\n");
+        source
+    }
+
+    #[test]
+    fn deflate_round_trip_shrinks_the_file() {
+        let source = large_source();
+        let program = parse(&source);
+        let derived = empty_derived();
+        let plain = serialize_program(&program, &source, &derived).unwrap();
+        let packed = serialize_program_with(&program, &source, &derived, Compression::Deflate).unwrap();
+
+        assert!(packed.len() * 2 < plain.len(), "{} vs {} bytes", packed.len(), plain.len());
+        assert!(is_binary_format(&packed));
+        assert_eq!(compression_of(&packed).unwrap(), Compression::Deflate);
+        assert_eq!(compression_of(&plain).unwrap(), Compression::None);
+        assert_eq!(read_source_only(&packed).unwrap(), source);
+
+        let (program2, source2, _) = deserialize_program(&packed).unwrap();
+        assert_eq!(source2, source);
+        assert_eq!(serialize_program(&program2, &source2, &derived).unwrap(), plain);
+    }
+
+    #[test]
+    fn corrupt_compressed_sections_are_errors() {
+        let source = large_source();
+        let packed = serialize_program_with(&parse(&source), &source, &empty_derived(), Compression::Deflate).unwrap();
+        for len in (0..packed.len()).step_by(97) {
+            assert!(deserialize_program(&packed[..len]).is_err(), "prefix of {len} bytes decoded");
+        }
+
+        let mut garbled = packed.clone();
+        garbled[HEADER_SIZE..HEADER_SIZE + 8].copy_from_slice(&[0xff; 8]);
+        assert!(matches!(deserialize_program(&garbled), Err(BinaryError::Decompress(_))));
+
+        let mut flagged = packed;
+        flagged[8..12].copy_from_slice(&0x80u32.to_le_bytes());
+        assert!(matches!(deserialize_program(&flagged), Err(BinaryError::UnknownFlags(0x80))));
+    }
+
+    #[test]
+    fn upgrade_keeps_compression() {
+        let source = "fn main() {}";
+        let packed = serialize_program_with(&parse(source), source, &empty_derived(), Compression::Deflate).unwrap();
+        let upgraded = upgrade(&packed).unwrap();
+        assert_eq!(compression_of(&upgraded).unwrap(), Compression::Deflate);
+        assert_eq!(read_source_only(&upgraded).unwrap(), source);
+    }
+
     #[test]
     fn test_v2_compatibility() {
         let source = "fn main() {}";
@@ -3,7 +3,7 @@ use uuid::Uuid;
 use crate::diagnostics::CompileError;
 use crate::parser::ast::*;
 use crate::span::{Span, Spanned};
-use crate::typeck::env::{FuncSig, TypeEnv};
+use crate::typeck::env::{mangle_method, FuncSig, MethodRefInfo, TypeEnv};
 use crate::typeck::types::{PlutoType, pluto_type_to_type_expr};
 use crate::visit::{walk_expr_mut, walk_stmt_mut, VisitMut};
 
@@ -15,6 +15,9 @@ use crate::visit::{walk_expr_mut, walk_stmt_mut, VisitMut};
 /// 3. Replaces the `Expr::Closure` with `Expr::ClosureCreate { fn_name, captures }`
 /// 4. Registers the lifted function in `env.functions` and `env.closure_fns`
 ///
+/// Method references (`obj.method`, `Type::method`) are lifted the same way,
+/// into a function that forwards its arguments to the method.
+///
 /// Returns the list of newly created functions to append to `program.functions`.
 pub fn lift_closures(program: &mut Program, env: &mut TypeEnv) -> Result<(), CompileError> {
     let mut counter = 0usize;
@@ -22,27 +25,31 @@ pub fn lift_closures(program: &mut Program, env: &mut TypeEnv) -> Result<(), Com
 
     // Lift from top-level function bodies
     for func in &mut program.functions {
-        lift_in_block(&mut func.node.body.node, env, &mut counter, &mut new_fns)?;
+        let owner = func.node.name.node.clone();
+        lift_in_block(&mut func.node.body.node, owner, env, &mut counter, &mut new_fns)?;
     }
 
     // Lift from class method bodies
     for class in &mut program.classes {
         for method in &mut class.node.methods {
-            lift_in_block(&mut method.node.body.node, env, &mut counter, &mut new_fns)?;
+            let owner = mangle_method(&class.node.name.node, &method.node.name.node);
+            lift_in_block(&mut method.node.body.node, owner, env, &mut counter, &mut new_fns)?;
         }
     }
 
     // Lift from app method bodies
     if let Some(app) = &mut program.app {
         for method in &mut app.node.methods {
-            lift_in_block(&mut method.node.body.node, env, &mut counter, &mut new_fns)?;
+            let owner = mangle_method(&app.node.name.node, &method.node.name.node);
+            lift_in_block(&mut method.node.body.node, owner, env, &mut counter, &mut new_fns)?;
         }
     }
 
     // Lift from stage method bodies
     for stage in &mut program.stages {
         for method in &mut stage.node.methods {
-            lift_in_block(&mut method.node.body.node, env, &mut counter, &mut new_fns)?;
+            let owner = mangle_method(&stage.node.name.node, &method.node.name.node);
+            lift_in_block(&mut method.node.body.node, owner, env, &mut counter, &mut new_fns)?;
         }
     }
 
@@ -55,15 +62,118 @@ pub fn lift_closures(program: &mut Program, env: &mut TypeEnv) -> Result<(), Com
 }
 
 struct ClosureLifter<'a> {
+    /// Function whose body is being lifted from; typeck recorded its side tables under this name
+    owner: String,
     env: &'a mut TypeEnv,
     counter: &'a mut usize,
     new_fns: &'a mut Vec<Spanned<Function>>,
 }
 
+impl ClosureLifter<'_> {
+    /// Replace a method reference with a closure whose lifted body calls the
+    /// method. A bound reference captures the variable its receiver path is
+    /// rooted at; `Type::method` takes the receiver as its first argument.
+    fn lift_method_ref(&mut self, expr: &mut Spanned<Expr>, info: MethodRefInfo) {
+        let span = expr.span;
+        let fn_name = format!("__closure_{}", *self.counter);
+        *self.counter += 1;
+
+        let mut param_types = Vec::new();
+        let receiver = match std::mem::replace(&mut expr.node, Expr::IntLit(0)) {
+            Expr::FieldAccess { object, .. } => *object,
+            _ => {
+                param_types.push(info.receiver.clone());
+                Spanned::dummy(Expr::Ident("__arg0".to_string()))
+            }
+        };
+        let first_arg = param_types.len();
+        param_types.extend(info.params.iter().cloned());
+
+        let env_param = Param {
+            id: Uuid::new_v4(),
+            name: Spanned::dummy("__env".to_string()),
+            ty: Spanned::dummy(TypeExpr::Named("int".to_string())),
+            is_mut: false,
+        };
+        let mut params = vec![env_param];
+        for (i, ty) in param_types.iter().enumerate() {
+            params.push(Param {
+                id: Uuid::new_v4(),
+                name: Spanned::dummy(format!("__arg{i}")),
+                ty: Spanned::dummy(pluto_type_to_type_expr(ty)),
+                is_mut: false,
+            });
+        }
+
+        let args = (first_arg..param_types.len())
+            .map(|i| Spanned::dummy(Expr::Ident(format!("__arg{i}"))))
+            .collect();
+        let call = Spanned::new(
+            Expr::MethodCall {
+                object: Box::new(receiver),
+                method: Spanned::dummy(info.method.clone()),
+                args,
+            },
+            span,
+        );
+        let stmt = if info.return_type == PlutoType::Void {
+            Stmt::Expr(call)
+        } else {
+            Stmt::Return(Some(call))
+        };
+
+        let mut sig_params = vec![PlutoType::Int]; // __env is I64
+        sig_params.extend(param_types);
+        self.env.functions.insert(fn_name.clone(), FuncSig {
+            params: sig_params,
+            return_type: info.return_type.clone(),
+        });
+        let captures: Vec<(String, PlutoType)> = info.capture.into_iter().collect();
+        let capture_names = captures.iter().map(|(n, _)| n.clone()).collect();
+        self.env.closure_fns.insert(fn_name.clone(), captures);
+        self.env.closure_owners.insert(fn_name.clone(), self.owner.clone());
+
+        let lifted = Function {
+            id: Uuid::new_v4(),
+            name: Spanned::dummy(fn_name.clone()),
+            type_params: vec![],
+            type_param_bounds: std::collections::HashMap::new(),
+            params,
+            return_type: if info.return_type == PlutoType::Void {
+                None
+            } else {
+                Some(Spanned::dummy(pluto_type_to_type_expr(&info.return_type)))
+            },
+            contracts: vec![],
+            body: Spanned::new(Block { stmts: vec![Spanned::new(stmt, span)] }, span),
+            visibility: Visibility::Private,
+            is_override: false,
+            is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
+        };
+        self.new_fns.push(Spanned::new(lifted, span));
+
+        expr.node = Expr::ClosureCreate {
+            fn_name,
+            captures: capture_names,
+            target_id: None,
+        };
+    }
+}
+
 impl VisitMut for ClosureLifter<'_> {
     fn visit_expr_mut(&mut self, expr: &mut Spanned<Expr>) {
         let span = expr.span;
 
+        // Handle method references — typeck recorded them by owner and span
+        if matches!(expr.node, Expr::FieldAccess { .. } | Expr::MethodRef { .. })
+            && let Some(info) = self.env.method_refs.get(&(self.owner.clone(), span)).cloned()
+        {
+            self.lift_method_ref(expr, info);
+            return;
+        }
+
         // Handle Closure — the main transformation
         if let Expr::Closure { .. } = &expr.node {
             // This is the main case — lift the closure
@@ -120,6 +230,7 @@ impl VisitMut for ClosureLifter<'_> {
 
                 // Register captures in env.closure_fns
                 self.env.closure_fns.insert(fn_name.clone(), captures);
+        self.env.closure_owners.insert(fn_name.clone(), self.owner.clone());
 
                 // Build the return type annotation (None → codegen will use env.functions)
                 let ret_type_expr = pluto_type_to_type_expr(&ret_type);
@@ -145,9 +256,11 @@ impl VisitMut for ClosureLifter<'_> {
                     },
                     contracts: vec![],
                     body: lifted_body,
-                    is_pub: false,
+                    visibility: Visibility::Private,
                     is_override: false,
                     is_generator: false,
+                    declared_errors: None,
+                    no_coverage: false,
                 };
 
                 self.new_fns.push(Spanned::new(lifted, span));
@@ -192,11 +305,13 @@ impl VisitMut for ClosureLifter<'_> {
 
 fn lift_in_block(
     block: &mut Block,
+    owner: String,
     env: &mut TypeEnv,
     counter: &mut usize,
     new_fns: &mut Vec<Spanned<Function>>,
 ) -> Result<(), CompileError> {
     let mut lifter = ClosureLifter {
+        owner,
         env,
         counter,
         new_fns,
@@ -216,6 +331,7 @@ fn lift_in_stmt(
     new_fns: &mut Vec<Spanned<Function>>,
 ) -> Result<(), CompileError> {
     let mut lifter = ClosureLifter {
+        owner: String::new(),
         env,
         counter,
         new_fns,
@@ -235,6 +351,7 @@ fn lift_in_expr(
     new_fns: &mut Vec<Spanned<Function>>,
 ) -> Result<(), CompileError> {
     let mut lifter = ClosureLifter {
+        owner: String::new(),
         env,
         counter,
         new_fns,
@@ -256,6 +373,8 @@ fn resolve_type_for_lift(ty: &TypeExpr) -> PlutoType {
             "void" => PlutoType::Void,
             "byte" => PlutoType::Byte,
             "bytes" => PlutoType::Bytes,
+            "WaitGroup" => PlutoType::WaitGroup,
+            "Atomic" => PlutoType::Atomic,
             _ => PlutoType::Class(name.clone()),
         },
         TypeExpr::Array(inner) => PlutoType::Array(Box::new(resolve_type_for_lift(&inner.node))),
@@ -282,6 +401,9 @@ fn resolve_type_for_lift(ty: &TypeExpr) -> PlutoType {
             } else if name == "Receiver" && type_args.len() == 1 {
                 let t = resolve_type_for_lift(&type_args[0].node);
                 PlutoType::Receiver(Box::new(t))
+            } else if name == "Mutex" && type_args.len() == 1 {
+                let t = resolve_type_for_lift(&type_args[0].node);
+                PlutoType::Mutex(Box::new(t))
             } else {
                 PlutoType::Class(name.clone())
             }
@@ -316,6 +438,7 @@ fn infer_type_from_expr(expr: &Expr) -> PlutoType {
             }
         }
         Expr::Cast { target_type, .. } => resolve_type_for_lift(&target_type.node),
+        Expr::Is { .. } => PlutoType::Bool,
         Expr::UnaryOp { op, operand } => match op {
             UnaryOp::Not => PlutoType::Bool,
             UnaryOp::BitNot => PlutoType::Int,
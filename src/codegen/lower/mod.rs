@@ -10,7 +10,7 @@ use crate::diagnostics::CompileError;
 use crate::parser::ast::*;
 use crate::typeck::env::{mangle_method, TypeEnv};
 use crate::typeck::types::PlutoType;
-use crate::visit::{walk_stmt, Visitor};
+use crate::visit::{walk_expr, walk_stmt, Visitor};
 
 use super::runtime::RuntimeRegistry;
 
@@ -22,6 +22,16 @@ pub struct FnContracts {
     pub requires: Vec<(Expr, String)>,  // (expr, description)
 }
 
+/// The `finally` block of an enclosing `try` statement, with the depths of the
+/// loop, try and guard stacks at that statement. Every path that leaves the
+/// statement lowers its own copy of the block.
+struct FinallyScope {
+    block: Block,
+    loop_depth: usize,
+    try_depth: usize,
+    guard_depth: usize,
+}
+
 struct LowerContext<'a> {
     builder: FunctionBuilder<'a>,
     module: &'a mut dyn Module,
@@ -39,6 +49,8 @@ struct LowerContext<'a> {
     singleton_globals: &'a HashMap<String, DataId>,
     /// Module-level globals holding rwlock pointers for synchronized singletons.
     rwlock_globals: &'a HashMap<String, DataId>,
+    /// Module-level globals backing `global mut` variables.
+    user_globals: &'a HashMap<String, DataId>,
     /// Coverage: (file_id, byte_offset, branch_id) → point_id. Empty when coverage is disabled.
     coverage_lookup: &'a HashMap<(u32, usize, u32), u32>,
     // Per-function mutable state
@@ -46,16 +58,28 @@ struct LowerContext<'a> {
     var_types: HashMap<String, PlutoType>,
     next_var: u32,
     expected_return_type: Option<PlutoType>,
-    /// Stack of (continue_target, break_target) blocks for break/continue
-    loop_stack: Vec<(cranelift_codegen::ir::Block, cranelift_codegen::ir::Block)>,
+    /// Stack of (continue_target, break_target, guard_depth) for break/continue.
+    /// Guards in scopes at or above `guard_depth` are released before the jump.
+    loop_stack: Vec<(cranelift_codegen::ir::Block, cranelift_codegen::ir::Block, usize)>,
+    /// Stack of enclosing `try` handler blocks and their guard depths; errors
+    /// jump to the innermost one
+    try_stack: Vec<(cranelift_codegen::ir::Block, usize)>,
+    /// `finally` blocks of the enclosing `try` statements, innermost last
+    finally_stack: Vec<FinallyScope>,
     /// Variables holding Sender handles that need sender_dec on function exit
     sender_cleanup_vars: Vec<Variable>,
+    /// Mutex guard variables declared in each enclosing block, innermost last
+    guard_scopes: Vec<Vec<Variable>>,
+    /// Every mutex guard variable in the function; the exit block unlocks any still held
+    guard_vars: Vec<Variable>,
     /// If non-None, all returns jump here for sender cleanup before actual return
     exit_block: Option<cranelift_codegen::ir::Block>,
     /// Display name for the current function (for contract violation messages)
     fn_display_name: String,
     /// Whether this function is a spawn closure (return values must be I64-encoded)
     is_spawn_closure: bool,
+    /// Whether this function is the program's `main` (returns become the exit code)
+    is_main: bool,
 }
 
 impl<'a> LowerContext<'a> {
@@ -63,6 +87,12 @@ impl<'a> LowerContext<'a> {
         self.builder.finalize();
     }
 
+    /// Key into typeck's per-body side tables (see [`TypeEnv::body_key`]) for a
+    /// node of the function being lowered.
+    fn body_key(&self, span: crate::span::Span) -> (String, crate::span::Span) {
+        (self.env.body_owner(&self.fn_display_name).to_string(), span)
+    }
+
     /// Call a runtime function that returns a value.
     fn call_runtime(&mut self, name: &str, args: &[Value]) -> Value {
         let func_ref = self.module.declare_func_in_func(self.runtime.get(name), self.builder.func);
@@ -90,6 +120,48 @@ impl<'a> LowerContext<'a> {
         self.builder.ins().call(func_ref, args);
     }
 
+    /// Stop here if a collection is waiting for this thread, and unwind if its
+    /// task has been cancelled. The flag is checked inline, so the common case
+    /// costs a load and a branch.
+    fn emit_safepoint(&mut self) -> Result<(), CompileError> {
+        self.emit_safepoint_poll(true)
+    }
+
+    /// After a call into Pluto code: keep unwinding if the callee unwound
+    /// because the task was cancelled. Checked only while the safepoint flag
+    /// is set, since cancellation sets it.
+    fn emit_cancel_check(&mut self) -> Result<(), CompileError> {
+        self.emit_safepoint_poll(false)
+    }
+
+    fn emit_safepoint_poll(&mut self, stop: bool) -> Result<(), CompileError> {
+        let flag_gv = self.module.declare_data_in_func(self.runtime.safepoint_flag(), self.builder.func);
+        let flag_addr = self.builder.ins().global_value(types::I64, flag_gv);
+        let requested = self.builder.ins().atomic_load(types::I32, MemFlags::trusted(), flag_addr);
+        let slow_bb = self.builder.create_block();
+        let unwind_bb = self.builder.create_block();
+        let cont_bb = self.builder.create_block();
+        self.builder.set_cold_block(slow_bb);
+        self.builder.set_cold_block(unwind_bb);
+        self.builder.ins().brif(requested, slow_bb, &[], cont_bb, &[]);
+
+        self.builder.switch_to_block(slow_bb);
+        self.builder.seal_block(slow_bb);
+        if stop {
+            self.call_runtime_void("__pluto_safepoint", &[]);
+        }
+        let unwinding = self.call_runtime("__pluto_task_unwinding", &[]);
+        self.builder.ins().brif(unwinding, unwind_bb, &[], cont_bb, &[]);
+
+        self.builder.switch_to_block(unwind_bb);
+        self.builder.seal_block(unwind_bb);
+        self.emit_cancel_exit()?;
+
+        self.builder.switch_to_block(cont_bb);
+        self.builder.seal_block(cont_bb);
+        Ok(())
+    }
+
     /// Materialize a string slice to an owned string at escape boundaries.
     /// No-op for non-string types.
     fn emit_string_escape(&mut self, val: Value, ty: &PlutoType) -> Value {
@@ -109,6 +181,30 @@ impl<'a> LowerContext<'a> {
         }
     }
 
+    /// Emit a coverage hit for `if_true` or `if_false` depending on `cond`, if coverage is enabled.
+    fn emit_coverage_branch(&mut self, cond: Value, file_id: u32, byte_offset: usize, if_true: u32, if_false: u32) {
+        if !self.coverage_lookup.contains_key(&(file_id, byte_offset, if_true)) {
+            return;
+        }
+        let true_bb = self.builder.create_block();
+        let false_bb = self.builder.create_block();
+        let merge_bb = self.builder.create_block();
+        self.builder.ins().brif(cond, true_bb, &[], false_bb, &[]);
+
+        self.builder.switch_to_block(true_bb);
+        self.builder.seal_block(true_bb);
+        self.emit_coverage_hit(file_id, byte_offset, if_true);
+        self.builder.ins().jump(merge_bb, &[]);
+
+        self.builder.switch_to_block(false_bb);
+        self.builder.seal_block(false_bb);
+        self.emit_coverage_hit(file_id, byte_offset, if_false);
+        self.builder.ins().jump(merge_bb, &[]);
+
+        self.builder.switch_to_block(merge_bb);
+        self.builder.seal_block(merge_bb);
+    }
+
     /// Lower a statement with coverage instrumentation: emit a coverage hit before lowering.
     fn lower_stmt_covered(
         &mut self,
@@ -122,6 +218,46 @@ impl<'a> LowerContext<'a> {
         self.lower_stmt(&stmt.node, terminated)
     }
 
+    /// Lower a block's statements in a fresh guard scope. Mutex guards declared
+    /// in the block are released when control falls off its end.
+    fn lower_block(
+        &mut self,
+        stmts: &[crate::span::Spanned<Stmt>],
+        terminated: &mut bool,
+    ) -> Result<(), CompileError> {
+        self.guard_scopes.push(Vec::new());
+        for s in stmts {
+            self.lower_stmt_covered(s, terminated)?;
+        }
+        self.pop_guard_scope(*terminated);
+        Ok(())
+    }
+
+    /// Pop the innermost guard scope, unlocking its guards unless the current
+    /// path already left the block.
+    fn pop_guard_scope(&mut self, terminated: bool) {
+        let depth = self.guard_scopes.len() - 1;
+        if !terminated {
+            self.release_guards_from(depth);
+        }
+        self.guard_scopes.truncate(depth);
+    }
+
+    /// Unlock the guards of every scope at or above `depth`, innermost first.
+    /// Each guard variable is cleared on this path so the exit block's
+    /// unlock of it becomes a no-op.
+    fn release_guards_from(&mut self, depth: usize) {
+        let guards: Vec<Variable> = self.guard_scopes[depth..].iter().rev()
+            .flat_map(|scope| scope.iter().rev().copied())
+            .collect();
+        for var in guards {
+            let guard = self.builder.use_var(var);
+            self.call_runtime_void("__pluto_mutex_unlock", &[guard]);
+            let null = self.builder.ins().iconst(types::I64, 0);
+            self.builder.def_var(var, null);
+        }
+    }
+
     /// Wrap a class pointer as a trait handle by calling __pluto_trait_wrap.
     fn wrap_class_as_trait(
         &mut self,
@@ -190,6 +326,13 @@ impl<'a> LowerContext<'a> {
         Ok(self.builder.ins().load(types::I64, MemFlags::new(), addr, Offset32::new(0)))
     }
 
+    /// Address of the module-level global backing a `global mut`, if `name` is one.
+    fn user_global_addr(&mut self, name: &str) -> Option<Value> {
+        let data_id = *self.user_globals.get(name)?;
+        let gv = self.module.declare_data_in_func(data_id, self.builder.func);
+        Some(self.builder.ins().global_value(types::I64, gv))
+    }
+
     /// Check if a class is a stage (for RPC routing).
     fn is_stage(&self, class_name: &str) -> bool {
         self.env.stages.iter().any(|(name, _)| name == class_name)
@@ -202,6 +345,105 @@ impl<'a> LowerContext<'a> {
         Ok(self.call_runtime("__pluto_string_new", &[raw_ptr, len_val]))
     }
 
+    /// Leave the current path with the error still set in TLS: jump to the
+    /// innermost enclosing `try` handler, or return the default value if there is none.
+    /// `finally` blocks passed on the way run first, with the error set aside so
+    /// their own calls don't see it; an error they raise replaces it.
+    fn emit_error_exit(&mut self) -> Result<(), CompileError> {
+        let try_depth = self.try_stack.len();
+        let from = self.finally_stack.iter().position(|s| s.try_depth >= try_depth);
+        if let Some(from) = from {
+            let err = self.call_runtime("__pluto_get_error", &[]);
+            let err_type = self.call_runtime("__pluto_error_type", &[]);
+            self.call_runtime_void("__pluto_clear_error", &[]);
+            if self.emit_finally_from(from)? {
+                return Ok(());
+            }
+            self.call_runtime_void("__pluto_restore_error", &[err, err_type]);
+        }
+        if let Some(&(handler_bb, guard_depth)) = self.try_stack.last() {
+            self.release_guards_from(guard_depth);
+            self.builder.ins().jump(handler_bb, &[]);
+        } else {
+            self.emit_default_return();
+        }
+        Ok(())
+    }
+
+    /// After a bare fallible call in a `try` body (named by `name_span`), enter
+    /// the handler if the call left an error, just as `!` would.
+    fn emit_implicit_propagation(&mut self, name_span: crate::span::Span) -> Result<(), CompileError> {
+        if !self.env.implicit_propagations.contains(&self.body_key(name_span)) {
+            return Ok(());
+        }
+        let has_err = self.call_runtime("__pluto_has_error", &[]);
+        let zero = self.builder.ins().iconst(types::I64, 0);
+        let is_error = self.builder.ins().icmp(IntCC::NotEqual, has_err, zero);
+        let propagate_bb = self.builder.create_block();
+        let continue_bb = self.builder.create_block();
+        self.builder.ins().brif(is_error, propagate_bb, &[], continue_bb, &[]);
+
+        self.builder.switch_to_block(propagate_bb);
+        self.builder.seal_block(propagate_bb);
+        self.emit_error_exit()?;
+
+        self.builder.switch_to_block(continue_bb);
+        self.builder.seal_block(continue_bb);
+        Ok(())
+    }
+
+    /// Leave the function because its task was cancelled. Like
+    /// `emit_error_exit`, but no `try` handler catches it: every enclosing
+    /// `finally` block runs, then the default value is returned.
+    fn emit_cancel_exit(&mut self) -> Result<(), CompileError> {
+        if !self.finally_stack.is_empty() {
+            let err = self.call_runtime("__pluto_get_error", &[]);
+            let err_type = self.call_runtime("__pluto_error_type", &[]);
+            self.call_runtime_void("__pluto_clear_error", &[]);
+            if self.emit_finally_from(0)? {
+                return Ok(());
+            }
+            self.call_runtime_void("__pluto_restore_error", &[err, err_type]);
+        }
+        self.emit_default_return();
+        Ok(())
+    }
+
+    /// Run the `finally` blocks that a `break` or `continue` of the innermost
+    /// loop leaves. Returns true if one of them ended the path itself.
+    fn emit_loop_exit_finally(&mut self) -> Result<bool, CompileError> {
+        let loop_depth = self.loop_stack.len();
+        match self.finally_stack.iter().position(|s| s.loop_depth >= loop_depth) {
+            Some(from) => self.emit_finally_from(from),
+            None => Ok(false),
+        }
+    }
+
+    /// Lower copies of the `finally` blocks in `finally_stack[from..]`, innermost
+    /// first, on a path that leaves their `try` statements. Guards taken inside
+    /// each statement are released before its block runs, and the block sees the
+    /// loops and handlers that enclose the statement, not the ones inside it.
+    /// Returns true if a block ended the path itself, e.g. with a `return`.
+    fn emit_finally_from(&mut self, from: usize) -> Result<bool, CompileError> {
+        for i in (from..self.finally_stack.len()).rev() {
+            let inner = self.finally_stack.split_off(i);
+            let scope = &inner[0];
+            self.release_guards_from(scope.guard_depth);
+            let loops = self.loop_stack.split_off(scope.loop_depth.min(self.loop_stack.len()));
+            let tries = self.try_stack.split_off(scope.try_depth.min(self.try_stack.len()));
+            let mut terminated = false;
+            let result = self.lower_block(&scope.block.stmts, &mut terminated);
+            self.loop_stack.extend(loops);
+            self.try_stack.extend(tries);
+            self.finally_stack.extend(inner);
+            result?;
+            if terminated {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
     /// Emit a return with the default value for the current function's return type.
     /// Used by raise and propagation to exit the function when an error occurs.
     fn emit_default_return(&mut self) {
@@ -288,6 +530,52 @@ impl<'a> LowerContext<'a> {
         }
     }
 
+    /// Turn a value returned from `main` into the process exit code: an int is
+    /// used as-is, an `int?` exits with its value (0 for none), and any other
+    /// nullable main exits with 0.
+    fn emit_main_exit_code(&mut self, val: Value, val_type: &PlutoType) -> Value {
+        match val_type {
+            PlutoType::Int => val,
+            PlutoType::Nullable(inner) if **inner == PlutoType::Int => {
+                let zero = self.builder.ins().iconst(types::I64, 0);
+                let is_none = self.builder.ins().icmp_imm(IntCC::Equal, val, 0);
+                let some_bb = self.builder.create_block();
+                let done_bb = self.builder.create_block();
+                self.builder.append_block_param(done_bb, types::I64);
+                self.builder.ins().brif(is_none, done_bb, &[zero], some_bb, &[]);
+                self.builder.switch_to_block(some_bb);
+                self.builder.seal_block(some_bb);
+                let code = self.emit_nullable_unwrap(val, inner);
+                self.builder.ins().jump(done_bb, &[code]);
+                self.builder.switch_to_block(done_bb);
+                self.builder.seal_block(done_bb);
+                self.builder.block_params(done_bb)[0]
+            }
+            _ => self.builder.ins().iconst(types::I64, 0),
+        }
+    }
+
+    /// Inverse of `emit_nullable_wrap`: turn a non-none `T?` value back into a `T`.
+    fn emit_nullable_unwrap(&mut self, val: Value, inner_type: &PlutoType) -> Value {
+        match inner_type {
+            PlutoType::Int => {
+                self.builder.ins().load(types::I64, MemFlags::new(), val, Offset32::new(0))
+            }
+            PlutoType::Float => {
+                let raw = self.builder.ins().load(types::I64, MemFlags::new(), val, Offset32::new(0));
+                self.builder.ins().bitcast(types::F64, MemFlags::new(), raw)
+            }
+            PlutoType::Bool | PlutoType::Byte => {
+                let raw = self.builder.ins().load(types::I64, MemFlags::new(), val, Offset32::new(0));
+                self.builder.ins().ireduce(types::I8, raw)
+            }
+            _ => {
+                // Heap types (string, class, array, etc.) — pointer IS the value
+                val
+            }
+        }
+    }
+
     /// Create a null-terminated string in the data section and return its pointer as a Value.
     fn create_data_str(&mut self, s: &str) -> Result<Value, CompileError> {
         let mut data_desc = DataDescription::new();
@@ -430,24 +718,28 @@ impl<'a> LowerContext<'a> {
                 Ok(())
             }
             Stmt::LetChan { sender, receiver, elem_type, capacity } => self.lower_let_chan(sender, receiver, elem_type, capacity),
+            Stmt::LetStruct { ty, fields, value, .. } => self.lower_let_struct(ty, fields, value),
             Stmt::Return(value) => {
                 let target_block = self.exit_block;
                 match value {
                     Some(expr) => {
                         let val = self.lower_expr(&expr.node)?;
-                        let val_type = infer_type_for_expr(&expr.node, self.env, &self.var_types);
+                        let val_type = infer_type_for_expr(&expr.node, self.env, &self.var_types, &self.fn_display_name);
                         // Materialize string slices at function return boundary
                         let val = self.emit_string_escape(val, &val_type);
 
                         // If returning a void expression (e.g., spawn closure wrapping a void function),
                         // lower the expr for side effects but emit default return
                         if val_type == PlutoType::Void {
-                            self.emit_default_return();
+                            if !self.emit_finally_from(0)? {
+                                self.emit_default_return();
+                            }
                         } else {
                             // If returning a class where a trait is expected, wrap it
                             // If returning T where T? is expected, box value types
                             let expected = self.expected_return_type.clone();
                             let final_val = match (&val_type, &expected) {
+                                _ if self.is_main => self.emit_main_exit_code(val, &val_type),
                                 (PlutoType::Class(cn), Some(PlutoType::Trait(tn))) => {
                                     self.wrap_class_as_trait(val, cn, tn)?
                                 }
@@ -462,7 +754,9 @@ impl<'a> LowerContext<'a> {
                             } else {
                                 final_val
                             };
-                            if let Some(bb) = target_block {
+                            if self.emit_finally_from(0)? {
+                                // A finally block returned first
+                            } else if let Some(bb) = target_block {
                                 self.builder.ins().jump(bb, &[final_val]);
                             } else {
                                 self.builder.ins().return_(&[final_val]);
@@ -470,7 +764,9 @@ impl<'a> LowerContext<'a> {
                         }
                     }
                     None => {
-                        self.emit_default_return();
+                        if !self.emit_finally_from(0)? {
+                            self.emit_default_return();
+                        }
                     }
                 }
                 *terminated = true;
@@ -478,7 +774,7 @@ impl<'a> LowerContext<'a> {
             }
             Stmt::Assign { target, value } => {
                 let val = self.lower_expr(&value.node)?;
-                let val_type = infer_type_for_expr(&value.node, self.env, &self.var_types);
+                let val_type = infer_type_for_expr(&value.node, self.env, &self.var_types, &self.fn_display_name);
                 let target_type = self.var_types.get(&target.node).cloned();
 
                 // If assigning a class to a trait-typed variable, wrap it
@@ -489,6 +785,12 @@ impl<'a> LowerContext<'a> {
                     _ => val,
                 };
 
+                if !self.variables.contains_key(&target.node)
+                    && let Some(addr) = self.user_global_addr(&target.node)
+                {
+                    self.builder.ins().store(MemFlags::new(), final_val, addr, Offset32::new(0));
+                    return Ok(());
+                }
                 let var = self.variables.get(&target.node).ok_or_else(|| {
                     CompileError::codegen(format!("undefined variable '{}'", target.node))
                 })?;
@@ -498,16 +800,20 @@ impl<'a> LowerContext<'a> {
             Stmt::FieldAssign { object, field, value } => {
                 let ptr = self.lower_expr(&object.node)?;
                 let val = self.lower_expr(&value.node)?;
-                let val_type = infer_type_for_expr(&value.node, self.env, &self.var_types);
+                let val_type = infer_type_for_expr(&value.node, self.env, &self.var_types, &self.fn_display_name);
                 // Materialize string slices before storing into struct fields
                 let val = self.emit_string_escape(val, &val_type);
-                let obj_type = infer_type_for_expr(&object.node, self.env, &self.var_types);
+                let obj_type = infer_type_for_expr(&object.node, self.env, &self.var_types, &self.fn_display_name);
                 if let PlutoType::Class(class_name) = &obj_type
                     && let Some(class_info) = self.env.classes.get(class_name)
                 {
-                    let offset = class_info.fields.iter()
+                    let field_idx = class_info.fields.iter()
                         .position(|(n, _, _)| *n == field.node)
-                        .ok_or_else(|| CompileError::codegen(format!("unknown field '{}' on class '{class_name}'", field.node)))? as i32 * POINTER_SIZE;
+                        .ok_or_else(|| CompileError::codegen(format!("unknown field '{}' on class '{class_name}'", field.node)))?;
+                    let field_type = class_info.fields[field_idx].1.clone();
+                    // Class → trait wraps in a trait handle; T → T? boxes value types
+                    let val = self.coerce_to_expected_type(val, &val_type, &field_type)?;
+                    let offset = field_idx as i32 * POINTER_SIZE;
                     self.builder.ins().store(MemFlags::new(), val, ptr, Offset32::new(offset));
                 }
 
@@ -517,7 +823,7 @@ impl<'a> LowerContext<'a> {
                 let handle = self.lower_expr(&object.node)?;
                 let idx = self.lower_expr(&index.node)?;
                 let val = self.lower_expr(&value.node)?;
-                let obj_type = infer_type_for_expr(&object.node, self.env, &self.var_types);
+                let obj_type = infer_type_for_expr(&object.node, self.env, &self.var_types, &self.fn_display_name);
                 if let PlutoType::Array(elem) = &obj_type {
                     let val = self.emit_string_escape(val, elem);
                     let slot = to_array_slot(val, elem, &mut self.builder);
@@ -538,28 +844,47 @@ impl<'a> LowerContext<'a> {
             Stmt::If { condition, then_block, else_block } => {
                 self.lower_if(condition, then_block, else_block, terminated)
             }
+            Stmt::IfLet { binding, value, then_block, else_block } => {
+                self.lower_if_let(binding, value, then_block, else_block.as_ref(), terminated)
+            }
             Stmt::While { condition, body } => self.lower_while(condition, body),
             Stmt::For { var, iterable, body } => self.lower_for(var, iterable, body),
             Stmt::Match { expr, arms } => self.lower_match_stmt(expr, arms, terminated),
+            Stmt::IntMatch { expr, arms } => self.lower_int_match_stmt(expr, arms, terminated),
+            Stmt::NullableMatch { expr, some_arm: Some(some_arm), none_arm: Some(none_arm) } => {
+                self.lower_if_let(&some_arm.binding, expr, &some_arm.body, Some(none_arm), terminated)
+            }
+            Stmt::NullableMatch { .. } => {
+                Err(CompileError::codegen("non-exhaustive match on nullable value".to_string()))
+            }
+            Stmt::Try { body, var, handler, finally } => self.lower_try(body, var, handler, finally.as_ref(), terminated),
             Stmt::Raise { error_name, fields, .. } => {
                 self.lower_raise(error_name, fields)?;
                 *terminated = true;
                 Ok(())
             }
             Stmt::Break => {
-                let (_, break_bb) = self.loop_stack.last().ok_or_else(|| {
+                let &(_, break_bb, guard_depth) = self.loop_stack.last().ok_or_else(|| {
                     CompileError::codegen("break outside of loop".to_string())
                 })?;
-                self.builder.ins().jump(*break_bb, &[]);
                 *terminated = true;
+                if self.emit_loop_exit_finally()? {
+                    return Ok(());
+                }
+                self.release_guards_from(guard_depth);
+                self.builder.ins().jump(break_bb, &[]);
                 Ok(())
             }
             Stmt::Continue => {
-                let (continue_bb, _) = self.loop_stack.last().ok_or_else(|| {
+                let &(continue_bb, _, guard_depth) = self.loop_stack.last().ok_or_else(|| {
                     CompileError::codegen("continue outside of loop".to_string())
                 })?;
-                self.builder.ins().jump(*continue_bb, &[]);
                 *terminated = true;
+                if self.emit_loop_exit_finally()? {
+                    return Ok(());
+                }
+                self.release_guards_from(guard_depth);
+                self.builder.ins().jump(continue_bb, &[]);
                 Ok(())
             }
             Stmt::Select { arms, default } => self.lower_select(arms, default, terminated),
@@ -594,6 +919,13 @@ impl<'a> LowerContext<'a> {
                 unreachable!("Stmt::Yield should only appear in generator next function codegen")
             }
             Stmt::Expr(expr) => {
+                if let Expr::Call { args, .. } = &expr.node
+                    && crate::typeck::is_unreachable_call(&expr.node)
+                {
+                    self.emit_unreachable(args)?;
+                    *terminated = true;
+                    return Ok(());
+                }
                 self.lower_expr(&expr.node)?;
                 Ok(())
             }
@@ -664,7 +996,7 @@ impl<'a> LowerContext<'a> {
         service: &crate::span::Spanned<Expr>,
         port: &crate::span::Spanned<Expr>,
     ) -> Result<(), CompileError> {
-        let svc_type = infer_type_for_expr(&service.node, self.env, &self.var_types);
+        let svc_type = infer_type_for_expr(&service.node, self.env, &self.var_types, &self.fn_display_name);
         let class_name = match svc_type {
             PlutoType::Class(n) => n,
             other => return Err(CompileError::codegen(format!(
@@ -897,7 +1229,11 @@ impl<'a> LowerContext<'a> {
         value: &crate::span::Spanned<Expr>,
     ) -> Result<(), CompileError> {
         let val = self.lower_expr(&value.node)?;
-        let val_type = infer_type_for_expr(&value.node, self.env, &self.var_types);
+        // `let _` only evaluates the value for its side effects
+        if name.node == "_" {
+            return Ok(());
+        }
+        let val_type = infer_type_for_expr(&value.node, self.env, &self.var_types, &self.fn_display_name);
 
         // Resolve declared type if present
         let declared_type = ty.as_ref().map(|t| resolve_type_expr_to_pluto(&t.node, self.env));
@@ -926,10 +1262,59 @@ impl<'a> LowerContext<'a> {
         self.builder.declare_var(var, cl_type);
         self.builder.def_var(var, final_val);
         self.variables.insert(name.node.clone(), var);
+        if matches!(store_type, PlutoType::MutexGuard(_)) {
+            if let Some(scope) = self.guard_scopes.last_mut() {
+                scope.push(var);
+            }
+            self.guard_vars.push(var);
+        }
         self.var_types.insert(name.node.clone(), store_type);
         Ok(())
     }
 
+    /// Load a class field from the object at `ptr`, returning its value and type.
+    fn load_class_field(&mut self, ptr: Value, class_name: &str, field: &str) -> Result<(Value, PlutoType), CompileError> {
+        let class_info = self.env.classes.get(class_name).ok_or_else(|| {
+            CompileError::codegen(format!("unknown class '{class_name}'"))
+        })?;
+        let (field_idx, (_, field_type, _)) = class_info.fields.iter()
+            .enumerate()
+            .find(|(_, (n, _, _))| *n == field)
+            .ok_or_else(|| {
+                CompileError::codegen(format!("unknown field '{field}'"))
+            })?;
+        let field_type = field_type.clone();
+        let offset = (field_idx as i32) * POINTER_SIZE;
+        let cl_type = pluto_to_cranelift(&field_type);
+        Ok((self.builder.ins().load(cl_type, MemFlags::new(), ptr, Offset32::new(offset)), field_type))
+    }
+
+    fn lower_let_struct(
+        &mut self,
+        ty: &crate::span::Spanned<TypeExpr>,
+        fields: &[(crate::span::Spanned<String>, Option<crate::span::Spanned<String>>)],
+        value: &crate::span::Spanned<Expr>,
+    ) -> Result<(), CompileError> {
+        let ptr = self.lower_expr(&value.node)?;
+        let PlutoType::Class(class_name) = resolve_type_expr_to_pluto(&ty.node, self.env) else {
+            return Err(CompileError::codegen("destructuring a non-class value".to_string()));
+        };
+        for (field, rename) in fields {
+            let local = rename.as_ref().unwrap_or(field);
+            if local.node == "_" {
+                continue;
+            }
+            let (val, field_type) = self.load_class_field(ptr, &class_name, &field.node)?;
+            let var = Variable::from_u32(self.next_var);
+            self.next_var += 1;
+            self.builder.declare_var(var, pluto_to_cranelift(&field_type));
+            self.builder.def_var(var, val);
+            self.variables.insert(local.node.clone(), var);
+            self.var_types.insert(local.node.clone(), field_type);
+        }
+        Ok(())
+    }
+
     fn lower_let_chan(
         &mut self,
         sender: &crate::span::Spanned<String>,
@@ -969,6 +1354,84 @@ impl<'a> LowerContext<'a> {
         Ok(())
     }
 
+    /// Lower `try { body } catch var { handler }`. While the body is lowered,
+    /// its handler block sits on `try_stack`, so every error exit inside the
+    /// body jumps there instead of returning from the function.
+    ///
+    /// A `finally` block sits on `finally_stack` while both body and handler are
+    /// lowered, so returns, break/continue and error exits out of them run a copy
+    /// of it; the paths that fall through share the copy lowered at the merge.
+    fn lower_try(
+        &mut self,
+        body: &crate::span::Spanned<Block>,
+        var: &crate::span::Spanned<String>,
+        handler: &crate::span::Spanned<Block>,
+        finally: Option<&crate::span::Spanned<Block>>,
+        terminated: &mut bool,
+    ) -> Result<(), CompileError> {
+        let handler_bb = self.builder.create_block();
+        let merge_bb = self.builder.create_block();
+
+        if let Some(finally) = finally {
+            self.finally_stack.push(FinallyScope {
+                block: finally.node.clone(),
+                loop_depth: self.loop_stack.len(),
+                try_depth: self.try_stack.len(),
+                guard_depth: self.guard_scopes.len(),
+            });
+        }
+
+        self.try_stack.push((handler_bb, self.guard_scopes.len()));
+        let mut body_terminated = false;
+        self.lower_block(&body.node.stmts, &mut body_terminated)?;
+        self.try_stack.pop();
+        if !body_terminated {
+            self.builder.ins().jump(merge_bb, &[]);
+        }
+
+        // All error exits from the body are known now, so the handler can be sealed.
+        self.builder.switch_to_block(handler_bb);
+        self.builder.seal_block(handler_bb);
+        let err_obj = self.call_runtime("__pluto_get_error", &[]);
+        self.call_runtime_void("__pluto_clear_error", &[]);
+        let err_var = Variable::from_u32(self.next_var);
+        self.next_var += 1;
+        self.builder.declare_var(err_var, types::I64);
+        self.builder.def_var(err_var, err_obj);
+        let prev_var = self.variables.insert(var.node.clone(), err_var);
+        let prev_type = self.var_types.insert(var.node.clone(), PlutoType::Error);
+
+        let mut handler_terminated = false;
+        self.lower_block(&handler.node.stmts, &mut handler_terminated)?;
+        if !handler_terminated {
+            self.builder.ins().jump(merge_bb, &[]);
+        }
+
+        match prev_var {
+            Some(pv) => { self.variables.insert(var.node.clone(), pv); }
+            None => { self.variables.remove(&var.node); }
+        }
+        match prev_type {
+            Some(pt) => { self.var_types.insert(var.node.clone(), pt); }
+            None => { self.var_types.remove(&var.node); }
+        }
+
+        if finally.is_some() {
+            self.finally_stack.pop();
+        }
+
+        if body_terminated && handler_terminated {
+            *terminated = true;
+        } else {
+            self.builder.switch_to_block(merge_bb);
+            self.builder.seal_block(merge_bb);
+            if let Some(finally) = finally {
+                self.lower_block(&finally.node.stmts, terminated)?;
+            }
+        }
+        Ok(())
+    }
+
     fn lower_if(
         &mut self,
         condition: &crate::span::Spanned<Expr>,
@@ -990,9 +1453,7 @@ impl<'a> LowerContext<'a> {
             // Branch coverage: then path taken
             self.emit_coverage_hit(then_block.span.file_id, then_block.span.start, 1);
             let mut then_terminated = false;
-            for s in &then_block.node.stmts {
-                self.lower_stmt_covered(s, &mut then_terminated)?;
-            }
+            self.lower_block(&then_block.node.stmts, &mut then_terminated)?;
             if !then_terminated {
                 self.builder.ins().jump(merge_bb, &[]);
             }
@@ -1002,9 +1463,7 @@ impl<'a> LowerContext<'a> {
             // Branch coverage: else path taken
             self.emit_coverage_hit(else_blk.span.file_id, else_blk.span.start, 1);
             let mut else_terminated = false;
-            for s in &else_blk.node.stmts {
-                self.lower_stmt_covered(s, &mut else_terminated)?;
-            }
+            self.lower_block(&else_blk.node.stmts, &mut else_terminated)?;
             if !else_terminated {
                 self.builder.ins().jump(merge_bb, &[]);
             }
@@ -1023,9 +1482,7 @@ impl<'a> LowerContext<'a> {
             // Branch coverage: then path taken (no else)
             self.emit_coverage_hit(then_block.span.file_id, then_block.span.start, 1);
             let mut then_terminated = false;
-            for s in &then_block.node.stmts {
-                self.lower_stmt_covered(s, &mut then_terminated)?;
-            }
+            self.lower_block(&then_block.node.stmts, &mut then_terminated)?;
             if !then_terminated {
                 self.builder.ins().jump(merge_bb, &[]);
             }
@@ -1044,6 +1501,82 @@ impl<'a> LowerContext<'a> {
         Ok(())
     }
 
+    /// `if let x = maybe { ... } else { ... }`: branch on the none sentinel (0),
+    /// binding the unboxed payload in the then-block.
+    fn lower_if_let(
+        &mut self,
+        binding: &crate::span::Spanned<String>,
+        value: &crate::span::Spanned<Expr>,
+        then_block: &crate::span::Spanned<Block>,
+        else_block: Option<&crate::span::Spanned<Block>>,
+        terminated: &mut bool,
+    ) -> Result<(), CompileError> {
+        let val = self.lower_expr(&value.node)?;
+        let inner_type = match infer_type_for_expr(&value.node, self.env, &self.var_types, &self.fn_display_name) {
+            PlutoType::Nullable(inner) => *inner,
+            other => {
+                return Err(CompileError::codegen(format!("if let on non-nullable type {other}")));
+            }
+        };
+
+        let zero = self.builder.ins().iconst(types::I64, 0);
+        let is_present = self.builder.ins().icmp(IntCC::NotEqual, val, zero);
+
+        let then_bb = self.builder.create_block();
+        let else_bb = self.builder.create_block();
+        let merge_bb = self.builder.create_block();
+        self.builder.ins().brif(is_present, then_bb, &[], else_bb, &[]);
+
+        // Present: bind the unwrapped value for the duration of the then-block
+        self.builder.switch_to_block(then_bb);
+        self.builder.seal_block(then_bb);
+        self.emit_coverage_hit(then_block.span.file_id, then_block.span.start, 1);
+        let unwrapped = self.emit_nullable_unwrap(val, &inner_type);
+        let var = Variable::from_u32(self.next_var);
+        self.next_var += 1;
+        self.builder.declare_var(var, pluto_to_cranelift(&inner_type));
+        self.builder.def_var(var, unwrapped);
+        let prev_var = self.variables.insert(binding.node.clone(), var);
+        let prev_type = self.var_types.insert(binding.node.clone(), inner_type);
+
+        let mut then_terminated = false;
+        self.lower_block(&then_block.node.stmts, &mut then_terminated)?;
+
+        match prev_var {
+            Some(pv) => { self.variables.insert(binding.node.clone(), pv); }
+            None => { self.variables.remove(&binding.node); }
+        }
+        match prev_type {
+            Some(pt) => { self.var_types.insert(binding.node.clone(), pt); }
+            None => { self.var_types.remove(&binding.node); }
+        }
+        if !then_terminated {
+            self.builder.ins().jump(merge_bb, &[]);
+        }
+
+        // None
+        self.builder.switch_to_block(else_bb);
+        self.builder.seal_block(else_bb);
+        let mut else_terminated = false;
+        if let Some(else_blk) = else_block {
+            self.emit_coverage_hit(else_blk.span.file_id, else_blk.span.start, 1);
+            self.lower_block(&else_blk.node.stmts, &mut else_terminated)?;
+        } else {
+            self.emit_coverage_hit(value.span.file_id, value.span.start, 2);
+        }
+        if !else_terminated {
+            self.builder.ins().jump(merge_bb, &[]);
+        }
+
+        if then_terminated && else_terminated {
+            *terminated = true;
+        } else {
+            self.builder.switch_to_block(merge_bb);
+            self.builder.seal_block(merge_bb);
+        }
+        Ok(())
+    }
+
     fn lower_while(
         &mut self,
         condition: &crate::span::Spanned<Expr>,
@@ -1063,15 +1596,13 @@ impl<'a> LowerContext<'a> {
         self.builder.seal_block(body_bb);
         // Branch coverage: loop body entered
         self.emit_coverage_hit(body.span.file_id, body.span.start, 1);
-        self.loop_stack.push((header_bb, exit_bb));
+        self.loop_stack.push((header_bb, exit_bb, self.guard_scopes.len()));
         let mut body_terminated = false;
-        for s in &body.node.stmts {
-            self.lower_stmt_covered(s, &mut body_terminated)?;
-        }
+        self.lower_block(&body.node.stmts, &mut body_terminated)?;
         self.loop_stack.pop();
         if !body_terminated {
             // Safepoint check before loop back-edge
-            self.call_runtime_void("__pluto_safepoint", &[]);
+            self.emit_safepoint()?;
             self.builder.ins().jump(header_bb, &[]);
         }
 
@@ -1087,7 +1618,7 @@ impl<'a> LowerContext<'a> {
         iterable: &crate::span::Spanned<Expr>,
         body: &crate::span::Spanned<Block>,
     ) -> Result<(), CompileError> {
-        let iter_type = infer_type_for_expr(&iterable.node, self.env, &self.var_types);
+        let iter_type = infer_type_for_expr(&iterable.node, self.env, &self.var_types, &self.fn_display_name);
         match &iter_type {
             PlutoType::Range => self.lower_for_range(var, iterable, body),
             PlutoType::Array(_) => self.lower_for_array(var, iterable, body),
@@ -1156,11 +1687,9 @@ impl<'a> LowerContext<'a> {
         self.var_types.insert(var.node.clone(), PlutoType::Int);
 
         // Push loop stack: continue goes to increment, break goes to exit
-        self.loop_stack.push((increment_bb, exit_bb));
+        self.loop_stack.push((increment_bb, exit_bb, self.guard_scopes.len()));
         let mut body_terminated = false;
-        for s in &body.node.stmts {
-            self.lower_stmt_covered(s, &mut body_terminated)?;
-        }
+        self.lower_block(&body.node.stmts, &mut body_terminated)?;
         self.loop_stack.pop();
 
         if !body_terminated {
@@ -1175,7 +1704,7 @@ impl<'a> LowerContext<'a> {
         let new_counter = self.builder.ins().iadd(counter_inc, one);
         self.builder.def_var(counter_var, new_counter);
         // Safepoint check before loop back-edge
-        self.call_runtime_void("__pluto_safepoint", &[]);
+        self.emit_safepoint()?;
         self.builder.ins().jump(header_bb, &[]);
 
         self.builder.seal_block(header_bb);
@@ -1206,7 +1735,7 @@ impl<'a> LowerContext<'a> {
         let handle = self.lower_expr(&iterable.node)?;
 
         // Get element type from iterable
-        let iter_type = infer_type_for_expr(&iterable.node, self.env, &self.var_types);
+        let iter_type = infer_type_for_expr(&iterable.node, self.env, &self.var_types, &self.fn_display_name);
         let elem_type = match &iter_type {
             PlutoType::Array(elem) => *elem.clone(),
             other => return Err(CompileError::codegen(
@@ -1262,11 +1791,9 @@ impl<'a> LowerContext<'a> {
         self.var_types.insert(var.node.clone(), elem_type);
 
         // Push loop stack: continue goes to increment, break goes to exit
-        self.loop_stack.push((increment_bb, exit_bb));
+        self.loop_stack.push((increment_bb, exit_bb, self.guard_scopes.len()));
         let mut body_terminated = false;
-        for s in &body.node.stmts {
-            self.lower_stmt_covered(s, &mut body_terminated)?;
-        }
+        self.lower_block(&body.node.stmts, &mut body_terminated)?;
         self.loop_stack.pop();
 
         // Restore prior variable binding if shadowed
@@ -1293,7 +1820,7 @@ impl<'a> LowerContext<'a> {
         let new_counter = self.builder.ins().iadd(counter_inc, one);
         self.builder.def_var(counter_var, new_counter);
         // Safepoint check before loop back-edge
-        self.call_runtime_void("__pluto_safepoint", &[]);
+        self.emit_safepoint()?;
         self.builder.ins().jump(header_bb, &[]);
 
         self.builder.seal_block(header_bb);
@@ -1349,11 +1876,9 @@ impl<'a> LowerContext<'a> {
         self.variables.insert(var.node.clone(), loop_var);
         self.var_types.insert(var.node.clone(), PlutoType::Byte);
 
-        self.loop_stack.push((increment_bb, exit_bb));
+        self.loop_stack.push((increment_bb, exit_bb, self.guard_scopes.len()));
         let mut body_terminated = false;
-        for s in &body.node.stmts {
-            self.lower_stmt_covered(s, &mut body_terminated)?;
-        }
+        self.lower_block(&body.node.stmts, &mut body_terminated)?;
         self.loop_stack.pop();
 
         if let Some(pv) = prev_var {
@@ -1378,7 +1903,7 @@ impl<'a> LowerContext<'a> {
         let new_counter = self.builder.ins().iadd(counter_inc, one);
         self.builder.def_var(counter_var, new_counter);
         // Safepoint check before loop back-edge
-        self.call_runtime_void("__pluto_safepoint", &[]);
+        self.emit_safepoint()?;
         self.builder.ins().jump(header_bb, &[]);
 
         self.builder.seal_block(header_bb);
@@ -1439,11 +1964,9 @@ impl<'a> LowerContext<'a> {
         self.var_types.insert(var.node.clone(), PlutoType::String);
 
         // Push loop stack: continue goes to increment, break goes to exit
-        self.loop_stack.push((increment_bb, exit_bb));
+        self.loop_stack.push((increment_bb, exit_bb, self.guard_scopes.len()));
         let mut body_terminated = false;
-        for s in &body.node.stmts {
-            self.lower_stmt_covered(s, &mut body_terminated)?;
-        }
+        self.lower_block(&body.node.stmts, &mut body_terminated)?;
         self.loop_stack.pop();
 
         // Restore prior variable binding if shadowed
@@ -1470,7 +1993,7 @@ impl<'a> LowerContext<'a> {
         let new_counter = self.builder.ins().iadd(counter_inc, one);
         self.builder.def_var(counter_var, new_counter);
         // Safepoint check before loop back-edge
-        self.call_runtime_void("__pluto_safepoint", &[]);
+        self.emit_safepoint()?;
         self.builder.ins().jump(header_bb, &[]);
 
         self.builder.seal_block(header_bb);
@@ -1487,7 +2010,7 @@ impl<'a> LowerContext<'a> {
     ) -> Result<(), CompileError> {
         let handle = self.lower_expr(&iterable.node)?;
 
-        let iter_type = infer_type_for_expr(&iterable.node, self.env, &self.var_types);
+        let iter_type = infer_type_for_expr(&iterable.node, self.env, &self.var_types, &self.fn_display_name);
         let elem_type = match &iter_type {
             PlutoType::Receiver(elem) => *elem.clone(),
             other => return Err(CompileError::codegen(
@@ -1536,11 +2059,9 @@ impl<'a> LowerContext<'a> {
         self.var_types.insert(var.node.clone(), elem_type);
 
         // Push loop stack: continue goes to header (re-recv), break goes to exit
-        self.loop_stack.push((header_bb, exit_bb));
+        self.loop_stack.push((header_bb, exit_bb, self.guard_scopes.len()));
         let mut body_terminated = false;
-        for s in &body.node.stmts {
-            self.lower_stmt_covered(s, &mut body_terminated)?;
-        }
+        self.lower_block(&body.node.stmts, &mut body_terminated)?;
         self.loop_stack.pop();
 
         // Restore prior variable binding
@@ -1557,7 +2078,7 @@ impl<'a> LowerContext<'a> {
 
         if !body_terminated {
             // Safepoint check before loop back-edge
-            self.call_runtime_void("__pluto_safepoint", &[]);
+            self.emit_safepoint()?;
             self.builder.ins().jump(header_bb, &[]);
         }
 
@@ -1575,7 +2096,7 @@ impl<'a> LowerContext<'a> {
     ) -> Result<(), CompileError> {
         let gen_ptr = self.lower_expr(&iterable.node)?;
 
-        let iter_type = infer_type_for_expr(&iterable.node, self.env, &self.var_types);
+        let iter_type = infer_type_for_expr(&iterable.node, self.env, &self.var_types, &self.fn_display_name);
         let elem_type = match &iter_type {
             PlutoType::Stream(elem) => *elem.clone(),
             other => return Err(CompileError::codegen(
@@ -1603,6 +2124,7 @@ impl<'a> LowerContext<'a> {
 
         // Call next function indirectly
         self.builder.ins().call_indirect(next_sig_ref, next_fn_ptr, &[gen_ptr]);
+        self.emit_cancel_check()?;
 
         // Check done flag at offset 16
         let done = self.builder.ins().load(types::I64, MemFlags::new(), gen_ptr, Offset32::new(16));
@@ -1630,11 +2152,9 @@ impl<'a> LowerContext<'a> {
         self.var_types.insert(var.node.clone(), elem_type);
 
         // Push loop stack: continue goes to header (re-call next), break goes to exit
-        self.loop_stack.push((header_bb, exit_bb));
+        self.loop_stack.push((header_bb, exit_bb, self.guard_scopes.len()));
         let mut body_terminated = false;
-        for s in &body.node.stmts {
-            self.lower_stmt_covered(s, &mut body_terminated)?;
-        }
+        self.lower_block(&body.node.stmts, &mut body_terminated)?;
         self.loop_stack.pop();
 
         // Restore prior variable binding
@@ -1651,7 +2171,7 @@ impl<'a> LowerContext<'a> {
 
         if !body_terminated {
             // Safepoint check before loop back-edge
-            self.call_runtime_void("__pluto_safepoint", &[]);
+            self.emit_safepoint()?;
             self.builder.ins().jump(header_bb, &[]);
         }
 
@@ -1670,7 +2190,7 @@ impl<'a> LowerContext<'a> {
         let ptr = self.lower_expr(&expr.node)?;
         let tag = self.builder.ins().load(types::I64, MemFlags::new(), ptr, Offset32::new(0));
 
-        let enum_name = match infer_type_for_expr(&expr.node, self.env, &self.var_types) {
+        let enum_name = match infer_type_for_expr(&expr.node, self.env, &self.var_types, &self.fn_display_name) {
             PlutoType::Enum(name) => name,
             other_type => return Err(CompileError::codegen(
                 format!("match requires enum type, found {}", other_type)
@@ -1752,9 +2272,7 @@ impl<'a> LowerContext<'a> {
             }
 
             let mut arm_terminated = false;
-            for s in &arm.body.node.stmts {
-                self.lower_stmt_covered(s, &mut arm_terminated)?;
-            }
+            self.lower_block(&arm.body.node.stmts, &mut arm_terminated)?;
 
             // Restore previous variable bindings
             for (name, prev_var, prev_type) in prev_vars {
@@ -1793,11 +2311,113 @@ impl<'a> LowerContext<'a> {
         Ok(())
     }
 
+    /// `match n { ... }` over int patterns: test each arm's patterns in order,
+    /// running the first arm that matches.
+    fn lower_int_match_stmt(
+        &mut self,
+        expr: &crate::span::Spanned<Expr>,
+        arms: &[IntMatchArm],
+        terminated: &mut bool,
+    ) -> Result<(), CompileError> {
+        let val = self.lower_expr(&expr.node)?;
+        let merge_bb = self.builder.create_block();
+        let mut all_terminated = true;
+
+        for (i, arm) in arms.iter().enumerate() {
+            let mut matched: Option<Value> = None;
+            for pat in &arm.patterns {
+                let cond = match &pat.node {
+                    IntPattern::Wildcard => self.builder.ins().iconst(types::I8, 1),
+                    IntPattern::Value(e) => {
+                        let v = self.int_pattern_bound(e)?;
+                        self.builder.ins().icmp_imm(IntCC::Equal, val, v)
+                    }
+                    IntPattern::Range { start, end, inclusive } => {
+                        let mut cond = self.builder.ins().iconst(types::I8, 1);
+                        if let Some(start) = start {
+                            let lo = self.int_pattern_bound(start)?;
+                            let ge = self.builder.ins().icmp_imm(IntCC::SignedGreaterThanOrEqual, val, lo);
+                            cond = self.builder.ins().band(cond, ge);
+                        }
+                        if let Some(end) = end {
+                            let hi = self.int_pattern_bound(end)?;
+                            let cc = if *inclusive { IntCC::SignedLessThanOrEqual } else { IntCC::SignedLessThan };
+                            let lt = self.builder.ins().icmp_imm(cc, val, hi);
+                            cond = self.builder.ins().band(cond, lt);
+                        }
+                        cond
+                    }
+                };
+                matched = Some(match matched {
+                    Some(prev) => self.builder.ins().bor(prev, cond),
+                    None => cond,
+                });
+            }
+            let matched = matched.expect("int match arm should have at least one pattern");
+
+            let body_bb = self.builder.create_block();
+            // Last arm: exhaustiveness guaranteed, so fallthrough to merge
+            let next_bb = if i + 1 < arms.len() { self.builder.create_block() } else { merge_bb };
+            self.builder.ins().brif(matched, body_bb, &[], next_bb, &[]);
+
+            self.builder.switch_to_block(body_bb);
+            self.builder.seal_block(body_bb);
+            // Branch coverage: match arm taken
+            self.emit_coverage_hit(arm.body.span.file_id, arm.body.span.start, 1);
+            let mut arm_terminated = false;
+            self.lower_block(&arm.body.node.stmts, &mut arm_terminated)?;
+            if !arm_terminated {
+                self.builder.ins().jump(merge_bb, &[]);
+                all_terminated = false;
+            }
+
+            if next_bb != merge_bb {
+                self.builder.switch_to_block(next_bb);
+                self.builder.seal_block(next_bb);
+            }
+        }
+
+        if all_terminated {
+            *terminated = true;
+        }
+        self.builder.switch_to_block(merge_bb);
+        self.builder.seal_block(merge_bb);
+        if *terminated {
+            // All arms returned; merge block is unreachable but needs a terminator.
+            self.builder.ins().trap(cranelift_codegen::ir::TrapCode::user(1).unwrap());
+        }
+        Ok(())
+    }
+
+    /// An int pattern bound: a literal, or a const inlined by value.
+    fn int_pattern_bound(&self, e: &crate::span::Spanned<Expr>) -> Result<i64, CompileError> {
+        crate::typeck::eval_const_int(&e.node, self.env)
+            .ok_or_else(|| CompileError::codegen("int pattern bound is not a constant".to_string()))
+    }
+
     fn lower_raise(
         &mut self,
         error_name: &crate::span::Spanned<String>,
         fields: &[(crate::span::Spanned<String>, crate::span::Spanned<Expr>)],
     ) -> Result<(), CompileError> {
+        let ptr = self.lower_error_lit(error_name, fields)?;
+
+        // Set TLS error pointer and its type name (for typed catch).
+        self.call_runtime_void("__pluto_raise_error", &[ptr]);
+        let type_str = self.make_string_literal(&error_name.node)?;
+        self.call_runtime_void("__pluto_set_error_type", &[type_str]);
+
+        // Return default value (caller checks TLS), or enter the enclosing try handler
+        self.emit_error_exit()?;
+        Ok(())
+    }
+
+    /// Allocate an error object and store its fields (without raising it).
+    fn lower_error_lit(
+        &mut self,
+        error_name: &crate::span::Spanned<String>,
+        fields: &[(crate::span::Spanned<String>, crate::span::Spanned<Expr>)],
+    ) -> Result<Value, CompileError> {
         let error_info = self.env.errors.get(&error_name.node).ok_or_else(|| {
             CompileError::codegen(format!("unknown error '{}'", error_name.node))
         })?.clone();
@@ -1817,15 +2437,7 @@ impl<'a> LowerContext<'a> {
                 .unwrap_or(0) as i32 * POINTER_SIZE;
             self.builder.ins().store(MemFlags::new(), val, ptr, Offset32::new(offset));
         }
-
-        // Set TLS error pointer and its type name (for typed catch).
-        self.call_runtime_void("__pluto_raise_error", &[ptr]);
-        let type_str = self.make_string_literal(&error_name.node)?;
-        self.call_runtime_void("__pluto_set_error_type", &[type_str]);
-
-        // Return default value (caller checks TLS)
-        self.emit_default_return();
-        Ok(())
+        Ok(ptr)
     }
 
     fn lower_scope(
@@ -1965,9 +2577,7 @@ impl<'a> LowerContext<'a> {
 
         // 4. Lower body
         let mut body_terminated = false;
-        for s in &body.node.stmts {
-            self.lower_stmt_covered(s, &mut body_terminated)?;
-        }
+        self.lower_block(&body.node.stmts, &mut body_terminated)?;
 
         // 5. Restore previous variable bindings
         for (name, prev_var, prev_type) in saved_vars {
@@ -2021,7 +2631,7 @@ impl<'a> LowerContext<'a> {
                     let op_val = self.builder.ins().iconst(types::I64, 1); // 1 = send
                     self.builder.ins().store(MemFlags::new(), op_val, buffer, Offset32::new(op_offset));
                     let send_val = self.lower_expr(&value.node)?;
-                    let slot = to_array_slot(send_val, &infer_type_for_expr(&value.node, self.env, &self.var_types), &mut self.builder);
+                    let slot = to_array_slot(send_val, &infer_type_for_expr(&value.node, self.env, &self.var_types, &self.fn_display_name), &mut self.builder);
                     self.builder.ins().store(MemFlags::new(), slot, buffer, Offset32::new(val_offset));
                 }
             }
@@ -2045,7 +2655,7 @@ impl<'a> LowerContext<'a> {
             // Error block: propagate (error is already in TLS)
             self.builder.switch_to_block(err_bb);
             self.builder.seal_block(err_bb);
-            self.emit_default_return();
+            self.emit_error_exit()?;
 
             // Continue to dispatch
             self.builder.switch_to_block(dispatch_bb);
@@ -2064,10 +2674,10 @@ impl<'a> LowerContext<'a> {
             // Default block
             self.builder.switch_to_block(default_bb);
             self.builder.seal_block(default_bb);
+            // Branch coverage: select default taken
+            self.emit_coverage_hit(def.span.file_id, def.span.start, 1);
             let mut default_terminated = false;
-            for s in &def.node.stmts {
-                self.lower_stmt_covered(s, &mut default_terminated)?;
-            }
+            self.lower_block(&def.node.stmts, &mut default_terminated)?;
             if !default_terminated {
                 self.builder.ins().jump(merge_bb, &[]);
             }
@@ -2098,11 +2708,13 @@ impl<'a> LowerContext<'a> {
             // Body block
             self.builder.switch_to_block(body_bb);
             self.builder.seal_block(body_bb);
+            // Branch coverage: select arm taken
+            self.emit_coverage_hit(arm.body.span.file_id, arm.body.span.start, 1);
 
             // For recv arms, bind the received value
             let mut prev_vars: Vec<(String, Option<Variable>, Option<PlutoType>)> = Vec::new();
             if let SelectOp::Recv { binding, channel } = &arm.op {
-                let chan_type = infer_type_for_expr(&channel.node, self.env, &self.var_types);
+                let chan_type = infer_type_for_expr(&channel.node, self.env, &self.var_types, &self.fn_display_name);
                 if let PlutoType::Receiver(elem_type) = &chan_type {
                     let val_offset = (2 * count as i32 + i as i32) * POINTER_SIZE;
                     let raw = self.builder.ins().load(types::I64, MemFlags::new(), buffer, Offset32::new(val_offset));
@@ -2125,9 +2737,7 @@ impl<'a> LowerContext<'a> {
             }
 
             let mut arm_terminated = false;
-            for s in &arm.body.node.stmts {
-                self.lower_stmt_covered(s, &mut arm_terminated)?;
-            }
+            self.lower_block(&arm.body.node.stmts, &mut arm_terminated)?;
 
             // Restore previous variable bindings
             for (name, prev_var, prev_type) in prev_vars {
@@ -2178,7 +2788,7 @@ impl<'a> LowerContext<'a> {
             Expr::NullPropagate { expr: inner } => {
                 // Lower the inner expression (must be Nullable(T))
                 let val = self.lower_expr(&inner.node)?;
-                let inner_type = infer_type_for_expr(&inner.node, self.env, &self.var_types);
+                let inner_type = infer_type_for_expr(&inner.node, self.env, &self.var_types, &self.fn_display_name);
 
                 // Compare with 0 (none)
                 let zero = self.builder.ins().iconst(types::I64, 0);
@@ -2194,7 +2804,9 @@ impl<'a> LowerContext<'a> {
                 // Branch coverage: null propagation — value was null
                 self.emit_coverage_hit(inner.span.file_id, inner.span.start, 1);
                 let is_void_return = matches!(&self.expected_return_type, Some(PlutoType::Void) | None);
-                if is_void_return {
+                if self.emit_finally_from(0)? {
+                    // A finally block returned first
+                } else if is_void_return {
                     if let Some(exit_bb) = self.exit_block {
                         self.builder.ins().jump(exit_bb, &[]);
                     } else {
@@ -2213,31 +2825,11 @@ impl<'a> LowerContext<'a> {
                 self.builder.switch_to_block(continue_bb);
                 self.builder.seal_block(continue_bb);
                 // Branch coverage: null propagation — value was non-null
-                self.emit_coverage_hit(inner.span.file_id, inner.span.start, 2);
-
-                // Unbox value types (int, float, bool stored as boxed pointer)
-                if let PlutoType::Nullable(unwrapped) = &inner_type {
-                    match unwrapped.as_ref() {
-                        PlutoType::Int => {
-                            Ok(self.builder.ins().load(types::I64, MemFlags::new(), val, Offset32::new(0)))
-                        }
-                        PlutoType::Float => {
-                            let raw = self.builder.ins().load(types::I64, MemFlags::new(), val, Offset32::new(0));
-                            Ok(self.builder.ins().bitcast(types::F64, MemFlags::new(), raw))
-                        }
-                        PlutoType::Bool => {
-                            let raw = self.builder.ins().load(types::I64, MemFlags::new(), val, Offset32::new(0));
-                            Ok(self.builder.ins().ireduce(types::I8, raw))
-                        }
-                        PlutoType::Byte => {
-                            let raw = self.builder.ins().load(types::I64, MemFlags::new(), val, Offset32::new(0));
-                            Ok(self.builder.ins().ireduce(types::I8, raw))
-                        }
-                        _ => {
-                            // Heap types (string, class, array, etc.) — pointer IS the value
-                            Ok(val)
-                        }
-                    }
+                self.emit_coverage_hit(inner.span.file_id, inner.span.start, 2);
+
+                // Unbox value types (int, float, bool stored as boxed pointer)
+                if let PlutoType::Nullable(unwrapped) = &inner_type {
+                    Ok(self.emit_nullable_unwrap(val, unwrapped))
                 } else {
                     // Shouldn't happen — typeck ensures ? is only on nullable types
                     Ok(val)
@@ -2250,6 +2842,18 @@ impl<'a> LowerContext<'a> {
             }
             Expr::StringInterp { parts } => self.lower_string_interp(parts),
             Expr::Ident(name) => {
+                if !self.variables.contains_key(name)
+                    && let Some(c) = self.env.consts.get(name)
+                {
+                    // Constants are inlined at each use
+                    return self.lower_expr(&c.value.clone());
+                }
+                if !self.variables.contains_key(name)
+                    && let Some(addr) = self.user_global_addr(name)
+                {
+                    let ty = pluto_to_cranelift(&self.env.globals[name].ty);
+                    return Ok(self.builder.ins().load(ty, MemFlags::new(), addr, Offset32::new(0)));
+                }
                 let var = self.variables.get(name).ok_or_else(|| {
                     CompileError::codegen(format!("undefined variable '{name}'"))
                 })?;
@@ -2258,7 +2862,7 @@ impl<'a> LowerContext<'a> {
             Expr::BinOp { op, lhs, rhs } => self.lower_binop(op, lhs, rhs),
             Expr::UnaryOp { op, operand } => {
                 let val = self.lower_expr(&operand.node)?;
-                let operand_type = infer_type_for_expr(&operand.node, self.env, &self.var_types);
+                let operand_type = infer_type_for_expr(&operand.node, self.env, &self.var_types, &self.fn_display_name);
                 match op {
                     UnaryOp::Neg if operand_type == PlutoType::Float => Ok(self.builder.ins().fneg(val)),
                     UnaryOp::Neg => Ok(self.builder.ins().ineg(val)),
@@ -2269,9 +2873,24 @@ impl<'a> LowerContext<'a> {
                     UnaryOp::BitNot => Ok(self.builder.ins().bnot(val)),
                 }
             }
+            Expr::Is { expr: inner, variant, .. } => {
+                // Enum values carry their variant index as the tag at offset 0
+                let ptr = self.lower_expr(&inner.node)?;
+                let enum_name = match infer_type_for_expr(&inner.node, self.env, &self.var_types, &self.fn_display_name) {
+                    PlutoType::Enum(name) => name,
+                    other => return Err(CompileError::codegen(format!("'is' on non-enum type {other}"))),
+                };
+                let enum_info = self.env.enums.get(&enum_name).ok_or_else(|| {
+                    CompileError::codegen(format!("unknown enum '{enum_name}'"))
+                })?;
+                let variant_idx = enum_info.variants.iter().position(|(n, _)| *n == variant.node)
+                    .expect("variant should exist after typeck validation");
+                let tag = self.builder.ins().load(types::I64, MemFlags::new(), ptr, Offset32::new(0));
+                Ok(self.builder.ins().icmp_imm(IntCC::Equal, tag, variant_idx as i64))
+            }
             Expr::Cast { expr: inner, target_type } => {
                 let val = self.lower_expr(&inner.node)?;
-                let source_type = infer_type_for_expr(&inner.node, self.env, &self.var_types);
+                let source_type = infer_type_for_expr(&inner.node, self.env, &self.var_types, &self.fn_display_name);
                 let target_type = resolve_type_expr_to_pluto(&target_type.node, self.env);
                 match (source_type.clone(), target_type.clone()) {
                     (PlutoType::Int, PlutoType::Float) => Ok(self.builder.ins().fcvt_from_sint(types::F64, val)),
@@ -2288,7 +2907,16 @@ impl<'a> LowerContext<'a> {
                     )),
                 }
             }
-            Expr::Call { name, args, .. } => self.lower_call(name, args),
+            Expr::Call { name, args, .. } => {
+                let val = self.lower_call(name, args)?;
+                self.emit_implicit_propagation(name.span)?;
+                Ok(val)
+            }
+            Expr::StructLit { name, fields, .. }
+                if !self.env.classes.contains_key(&name.node) && self.env.errors.contains_key(&name.node) =>
+            {
+                self.lower_error_lit(name, fields)
+            }
             Expr::StructLit { name, fields, .. } => self.lower_struct_lit(name, fields),
             Expr::ArrayLit { elements } => {
                 let n = elements.len() as i64;
@@ -2296,7 +2924,7 @@ impl<'a> LowerContext<'a> {
                 let handle = self.call_runtime("__pluto_array_new", &[cap_val]);
 
                 if !elements.is_empty() {
-                    let elem_type = infer_type_for_expr(&elements[0].node, self.env, &self.var_types);
+                    let elem_type = infer_type_for_expr(&elements[0].node, self.env, &self.var_types, &self.fn_display_name);
                     // Hoist func_ref before loop to avoid repeated HashMap lookups
                     let func_ref_push = self.module.declare_func_in_func(self.runtime.get("__pluto_array_push"), self.builder.func);
                     for elem in elements {
@@ -2339,8 +2967,11 @@ impl<'a> LowerContext<'a> {
             }
             Expr::Index { object, index } => {
                 let handle = self.lower_expr(&object.node)?;
+                let obj_type = infer_type_for_expr(&object.node, self.env, &self.var_types, &self.fn_display_name);
+                if let Expr::Range { start, end, inclusive } = &index.node {
+                    return self.lower_range_slice(handle, &obj_type, start, end, *inclusive);
+                }
                 let idx = self.lower_expr(&index.node)?;
-                let obj_type = infer_type_for_expr(&object.node, self.env, &self.var_types);
                 if let PlutoType::Array(elem) = &obj_type {
                     let raw = self.call_runtime("__pluto_array_get", &[handle, idx]);
                     Ok(from_array_slot(raw, elem, &mut self.builder))
@@ -2380,7 +3011,7 @@ impl<'a> LowerContext<'a> {
             }
             Expr::FieldAccess { object, field } => {
                 let ptr = self.lower_expr(&object.node)?;
-                let obj_type = infer_type_for_expr(&object.node, self.env, &self.var_types);
+                let obj_type = infer_type_for_expr(&object.node, self.env, &self.var_types, &self.fn_display_name);
                 // Typed-catch var: Class(error_name). The error object lays out
                 // its fields in declaration order, same as a class.
                 if let PlutoType::Class(name) = &obj_type
@@ -2396,19 +3027,26 @@ impl<'a> LowerContext<'a> {
                     let cl_type = pluto_to_cranelift(field_type);
                     return Ok(self.builder.ins().load(cl_type, MemFlags::new(), ptr, Offset32::new(offset)));
                 }
-                if let PlutoType::Class(class_name) = &obj_type {
-                    let class_info = self.env.classes.get(class_name).ok_or_else(|| {
-                        CompileError::codegen(format!("unknown class '{class_name}'"))
+                // Enum payload read under an `is` guard: typeck recorded the variant.
+                if let PlutoType::Enum(enum_name) = &obj_type {
+                    let variant = self.env.variant_field_accesses.get(&self.body_key(field.span))
+                        .ok_or_else(|| CompileError::codegen(format!(
+                            "field access '{}' on enum '{enum_name}' outside an 'is' guard", field.node)))?;
+                    let enum_info = self.env.enums.get(enum_name).ok_or_else(|| {
+                        CompileError::codegen(format!("unknown enum '{enum_name}'"))
                     })?;
-                    let (field_idx, (_, field_type, _)) = class_info.fields.iter()
-                        .enumerate()
-                        .find(|(_, (n, _, _))| *n == field.node)
-                        .ok_or_else(|| {
-                            CompileError::codegen(format!("unknown field '{}'", field.node))
-                        })?;
-                    let offset = (field_idx as i32) * POINTER_SIZE;
-                    let cl_type = pluto_to_cranelift(field_type);
-                    Ok(self.builder.ins().load(cl_type, MemFlags::new(), ptr, Offset32::new(offset)))
+                    let variant_fields = &enum_info.variants.iter()
+                        .find(|(n, _)| n == variant)
+                        .expect("variant should exist after typeck validation").1;
+                    let field_idx = variant_fields.iter().position(|(n, _)| *n == field.node)
+                        .expect("field should exist after typeck validation");
+                    let field_type = variant_fields[field_idx].1.clone();
+                    let offset = ((1 + field_idx) as i32) * POINTER_SIZE;
+                    let slot = self.builder.ins().load(types::I64, MemFlags::new(), ptr, Offset32::new(offset));
+                    return Ok(from_array_slot(slot, &field_type, &mut self.builder));
+                }
+                if let PlutoType::Class(class_name) = &obj_type {
+                    Ok(self.load_class_field(ptr, class_name, &field.node)?.0)
                 } else if obj_type == PlutoType::Error && field.node == "message" {
                     Ok(self.builder.ins().load(types::I64, MemFlags::new(), ptr, Offset32::new(0)))
                 } else {
@@ -2433,7 +3071,7 @@ impl<'a> LowerContext<'a> {
                 self.builder.seal_block(propagate_bb);
                 // Branch coverage: error propagation — error occurred
                 self.emit_coverage_hit(inner.span.file_id, inner.span.start, 1);
-                self.emit_default_return();
+                self.emit_error_exit()?;
 
                 // Continue block: no error, use the call result
                 self.builder.switch_to_block(continue_bb);
@@ -2442,13 +3080,19 @@ impl<'a> LowerContext<'a> {
                 self.emit_coverage_hit(inner.span.file_id, inner.span.start, 2);
                 Ok(val)
             }
+            Expr::PropagateMap { expr: inner, mapper } => self.lower_propagate_map(inner, mapper),
             Expr::Catch { expr: inner, handlers } => self.lower_catch(inner, handlers),
             Expr::MethodCall { object, method, args } => {
-                self.lower_method_call(object, method, args)
+                let val = self.lower_method_call(object, method, args)?;
+                self.emit_implicit_propagation(method.span)?;
+                Ok(val)
             }
             Expr::Closure { .. } => {
                 Err(CompileError::codegen("closures should be lifted before codegen"))
             }
+            Expr::MethodRef { .. } => {
+                Err(CompileError::codegen("method references should be lifted before codegen"))
+            }
             Expr::ClosureCreate { fn_name, captures, .. } => {
                 self.lower_closure_create(fn_name, captures)
             }
@@ -2526,7 +3170,7 @@ impl<'a> LowerContext<'a> {
                 }
                 StringInterpPart::Expr(e) => {
                     let val = self.lower_expr(&e.node)?;
-                    let t = infer_type_for_expr(&e.node, self.env, &self.var_types);
+                    let t = infer_type_for_expr(&e.node, self.env, &self.var_types, &self.fn_display_name);
                     let str_val = match t {
                         PlutoType::String => val,
                         PlutoType::Int => self.call_runtime("__pluto_int_to_string", &[val]),
@@ -2539,7 +3183,8 @@ impl<'a> LowerContext<'a> {
                             let widened = self.builder.ins().uextend(types::I64, val);
                             self.call_runtime("__pluto_int_to_string", &[widened])
                         }
-                        _ => return Err(CompileError::codegen(format!("cannot interpolate {t}"))),
+                        // Typeck rejects every other type with the expression's span
+                        _ => return Err(CompileError::codegen(format!("invalid interpolated type {t} in lowered AST"))),
                     };
                     string_vals.push(str_val);
                 }
@@ -2555,6 +3200,37 @@ impl<'a> LowerContext<'a> {
         Ok(result)
     }
 
+    /// Lower `obj[start..end]` / `obj[start..=end]` on a string or array. The range is
+    /// bounds-checked at runtime, then the copy is made by substring/array_slice.
+    fn lower_range_slice(
+        &mut self,
+        handle: Value,
+        obj_type: &PlutoType,
+        start: &crate::span::Spanned<Expr>,
+        end: &crate::span::Spanned<Expr>,
+        inclusive: bool,
+    ) -> Result<Value, CompileError> {
+        let start_val = self.lower_expr(&start.node)?;
+        let mut end_val = self.lower_expr(&end.node)?;
+        if inclusive {
+            end_val = self.builder.ins().iadd_imm(end_val, 1);
+        }
+        let (len_fn, is_string) = match obj_type {
+            PlutoType::String => ("__pluto_string_len", 1),
+            PlutoType::Array(_) => ("__pluto_array_len", 0),
+            _ => return Err(CompileError::codegen(format!("cannot slice type {obj_type}"))),
+        };
+        let len = self.call_runtime(len_fn, &[handle]);
+        let is_string_val = self.builder.ins().iconst(types::I64, is_string);
+        self.call_runtime_void("__pluto_slice_check", &[start_val, end_val, len, is_string_val]);
+        if is_string == 1 {
+            let count = self.builder.ins().isub(end_val, start_val);
+            Ok(self.call_runtime("__pluto_string_substring", &[handle, start_val, count]))
+        } else {
+            Ok(self.call_runtime("__pluto_array_slice", &[handle, start_val, end_val]))
+        }
+    }
+
     fn lower_binop(
         &mut self,
         op: &BinOp,
@@ -2564,11 +3240,23 @@ impl<'a> LowerContext<'a> {
         let l = self.lower_expr(&lhs.node)?;
         let r = self.lower_expr(&rhs.node)?;
 
-        let lhs_type = infer_type_for_expr(&lhs.node, self.env, &self.var_types);
+        let lhs_type = infer_type_for_expr(&lhs.node, self.env, &self.var_types, &self.fn_display_name);
         let is_float = lhs_type == PlutoType::Float;
         let is_string = lhs_type == PlutoType::String;
         let is_byte = lhs_type == PlutoType::Byte;
 
+        if let Some(result) = self.lower_operator_trait_call(op, &lhs_type, l, r)? {
+            return Ok(result);
+        }
+
+        // Branch coverage: whether the left operand alone decided `&&`/`||` (branch_id 3)
+        // or the right one did (branch_id 4)
+        match op {
+            BinOp::And => self.emit_coverage_branch(l, rhs.span.file_id, rhs.span.start, 4, 3),
+            BinOp::Or => self.emit_coverage_branch(l, rhs.span.file_id, rhs.span.start, 3, 4),
+            _ => {}
+        }
+
         let result = match op {
             BinOp::Add if is_string => self.call_runtime("__pluto_string_concat", &[l, r]),
             BinOp::Add if is_float => self.builder.ins().fadd(l, r),
@@ -2611,12 +3299,108 @@ impl<'a> LowerContext<'a> {
             BinOp::BitAnd => self.builder.ins().band(l, r),
             BinOp::BitOr => self.builder.ins().bor(l, r),
             BinOp::BitXor => self.builder.ins().bxor(l, r),
+            // Shift amounts are taken modulo the operand width (64 for int, 8 for byte)
             BinOp::Shl => self.builder.ins().ishl(l, r),
+            // Bytes are unsigned, so their right shift is logical
+            BinOp::Shr if is_byte => self.builder.ins().ushr(l, r),
             BinOp::Shr => self.builder.ins().sshr(l, r),
         };
         Ok(result)
     }
 
+    /// Lower `a op b` on a class or enum implementing the operator's trait as
+    /// a call to its method. `Ord` comparisons test the returned `Ordering` tag.
+    /// Typeck rejects operator methods that can raise, so the call has no error
+    /// to check.
+    fn lower_operator_trait_call(
+        &mut self,
+        op: &BinOp,
+        lhs_type: &PlutoType,
+        l: Value,
+        r: Value,
+    ) -> Result<Option<Value>, CompileError> {
+        let type_name = match lhs_type {
+            PlutoType::Class(name) | PlutoType::Enum(name) => name,
+            _ => return Ok(None),
+        };
+        let Some((trait_name, method)) = crate::typeck::env::operator_trait(op) else {
+            return Ok(None);
+        };
+        if !self.env.implements_operator_trait(lhs_type, trait_name) {
+            return Ok(None);
+        }
+        let mangled = mangle_method(type_name, method);
+        if self.env.is_fn_fallible(&mangled) {
+            return Err(CompileError::codegen(format!("operator method '{mangled}' can raise an error")));
+        }
+        let func_id = self.func_ids.get(&mangled).ok_or_else(|| {
+            CompileError::codegen(format!("undefined operator method '{mangled}'"))
+        })?;
+        let func_ref = self.module.declare_func_in_func(*func_id, self.builder.func);
+        let call = self.builder.ins().call(func_ref, &[l, r]);
+        let result = self.builder.inst_results(call)[0];
+        self.emit_cancel_check()?;
+
+        let value = match op {
+            BinOp::Neq => {
+                let one = self.builder.ins().iconst(types::I8, 1);
+                self.builder.ins().bxor(result, one)
+            }
+            // Ordering variants are Less (0), Equal (1), Greater (2)
+            BinOp::Lt | BinOp::Gt | BinOp::LtEq | BinOp::GtEq => {
+                let tag = self.builder.ins().load(types::I64, MemFlags::new(), result, Offset32::new(0));
+                let (cc, variant) = match op {
+                    BinOp::Lt => (IntCC::Equal, 0),
+                    BinOp::LtEq => (IntCC::NotEqual, 2),
+                    BinOp::Gt => (IntCC::Equal, 2),
+                    _ => (IntCC::NotEqual, 0),
+                };
+                self.builder.ins().icmp_imm(cc, tag, variant)
+            }
+            _ => result,
+        };
+        Ok(Some(value))
+    }
+
+    /// `unreachable(msg?)`: report the message and abort. The value it yields
+    /// is never used, but the surrounding code still needs one of the type
+    /// typeck picked, so it is produced in a fresh block with no predecessors.
+    fn lower_unreachable(
+        &mut self,
+        name: &crate::span::Spanned<String>,
+        args: &[crate::span::Spanned<Expr>],
+    ) -> Result<Value, CompileError> {
+        self.emit_unreachable(args)?;
+
+        let dead_bb = self.builder.create_block();
+        self.builder.switch_to_block(dead_bb);
+        self.builder.seal_block(dead_bb);
+        let ty = self.env.unreachable_types.get(&self.body_key(name.span))
+            .cloned()
+            .unwrap_or(PlutoType::Void);
+        Ok(match ty {
+            PlutoType::Float => self.builder.ins().f64const(0.0),
+            PlutoType::Bool | PlutoType::Byte => self.builder.ins().iconst(types::I8, 0),
+            _ => self.builder.ins().iconst(types::I64, 0),
+        })
+    }
+
+    /// Print the `unreachable()` message and trap, ending the current block.
+    fn emit_unreachable(&mut self, args: &[crate::span::Spanned<Expr>]) -> Result<(), CompileError> {
+        let msg = match args.first() {
+            Some(arg) => self.lower_expr(&arg.node)?,
+            None => {
+                let text = "unreachable() was called";
+                let raw = self.create_data_str(text)?;
+                let len = self.builder.ins().iconst(types::I64, text.len() as i64);
+                self.call_runtime("__pluto_string_new", &[raw, len])
+            }
+        };
+        self.call_runtime_void("__pluto_unreachable", &[msg]);
+        self.builder.ins().trap(cranelift_codegen::ir::TrapCode::unwrap_user(1));
+        Ok(())
+    }
+
     fn lower_call(
         &mut self,
         name: &crate::span::Spanned<String>,
@@ -2629,15 +3413,39 @@ impl<'a> LowerContext<'a> {
         if name.node == "print" {
             return self.lower_print(args);
         }
+        if name.node == "unreachable" {
+            return self.lower_unreachable(name, args);
+        }
+        if name.node == "gc_collect" {
+            self.call_runtime_void("__pluto_gc_force_collect", &[]);
+            return Ok(self.builder.ins().iconst(types::I64, 0));
+        }
         // Table-driven zero-arg builtins
         const ZERO_ARG_BUILTINS: &[(&str, &str)] = &[
             ("time_ns", "__pluto_time_ns"),
+            ("num_cpus", "__pluto_num_cpus"),
             ("gc_heap_size", "__pluto_gc_heap_size"),
+            ("gc_stats", "__pluto_gc_stats"),
             ("bytes_new", "__pluto_bytes_new"),
+            ("args", "__pluto_args"),
+            ("read_line", "__pluto_read_line"),
+            ("read_all", "__pluto_read_all"),
+            ("wait_group", "__pluto_waitgroup_new"),
         ];
         if let Some((_, rt_fn)) = ZERO_ARG_BUILTINS.iter().find(|(n, _)| *n == name.node.as_str()) {
             return Ok(self.call_runtime(rt_fn, &[]));
         }
+        if name.node == "atomic" {
+            let initial = self.lower_expr(&args[0].node)?;
+            return Ok(self.call_runtime("__pluto_atomic_new", &[initial]));
+        }
+        if name.node == "mutex" {
+            let inner = infer_type_for_expr(&args[0].node, self.env, &self.var_types, &self.fn_display_name);
+            let initial = self.lower_expr(&args[0].node)?;
+            let initial = self.emit_string_escape(initial, &inner);
+            let slot = to_array_slot(initial, &inner, &mut self.builder);
+            return Ok(self.call_runtime("__pluto_mutex_new", &[slot]));
+        }
 
         // Table-driven type-dispatched unary builtins (int/float)
         const TYPED_UNARY: &[(&str, &str, &str)] = &[
@@ -2645,7 +3453,7 @@ impl<'a> LowerContext<'a> {
         ];
         if let Some((_, int_fn, float_fn)) = TYPED_UNARY.iter().find(|(n, _, _)| *n == name.node.as_str()) {
             let arg = self.lower_expr(&args[0].node)?;
-            let arg_ty = infer_type_for_expr(&args[0].node, self.env, &self.var_types);
+            let arg_ty = infer_type_for_expr(&args[0].node, self.env, &self.var_types, &self.fn_display_name);
             return Ok(match arg_ty {
                 PlutoType::Int => self.call_runtime(int_fn, &[arg]),
                 PlutoType::Float => self.call_runtime(float_fn, &[arg]),
@@ -2662,7 +3470,7 @@ impl<'a> LowerContext<'a> {
         if let Some((_, int_fn, float_fn)) = TYPED_BINARY.iter().find(|(n, _, _)| *n == name.node.as_str()) {
             let a = self.lower_expr(&args[0].node)?;
             let b = self.lower_expr(&args[1].node)?;
-            let arg_ty = infer_type_for_expr(&args[0].node, self.env, &self.var_types);
+            let arg_ty = infer_type_for_expr(&args[0].node, self.env, &self.var_types, &self.fn_display_name);
             return Ok(match arg_ty {
                 PlutoType::Int => self.call_runtime(int_fn, &[a, b]),
                 PlutoType::Float => self.call_runtime(float_fn, &[a, b]),
@@ -2708,11 +3516,13 @@ impl<'a> LowerContext<'a> {
 
             let call = self.builder.ins().call_indirect(sig_ref, fn_ptr, &call_args);
             let results = self.builder.inst_results(call);
-            return Ok(if results.is_empty() {
+            let result = if results.is_empty() {
                 self.builder.ins().iconst(types::I64, 0)
             } else {
                 results[0]
-            });
+            };
+            self.emit_cancel_check()?;
+            return Ok(result);
         }
 
         let func_id = self.func_ids.get(&name.node).ok_or_else(|| {
@@ -2728,7 +3538,7 @@ impl<'a> LowerContext<'a> {
         let mut arg_values = Vec::new();
         for (i, arg) in args.iter().enumerate() {
             let val = self.lower_expr(&arg.node)?;
-            let arg_actual_type = infer_type_for_expr(&arg.node, self.env, &self.var_types);
+            let arg_actual_type = infer_type_for_expr(&arg.node, self.env, &self.var_types, &self.fn_display_name);
             let param_expected = param_types.get(i);
 
             if let (PlutoType::Class(cn), Some(PlutoType::Trait(tn))) = (&arg_actual_type, param_expected) {
@@ -2742,11 +3552,13 @@ impl<'a> LowerContext<'a> {
 
         let call = self.builder.ins().call(func_ref, &arg_values);
         let results = self.builder.inst_results(call);
-        if results.is_empty() {
-            Ok(self.builder.ins().iconst(types::I64, 0))
+        let result = if results.is_empty() {
+            self.builder.ins().iconst(types::I64, 0)
         } else {
-            Ok(results[0])
-        }
+            results[0]
+        };
+        self.emit_cancel_check()?;
+        Ok(result)
     }
 
     fn lower_static_trait_call(
@@ -2790,11 +3602,13 @@ impl<'a> LowerContext<'a> {
         // Make the call
         let call = self.builder.ins().call(func_ref, &arg_values);
         let results = self.builder.inst_results(call);
-        if results.is_empty() {
-            Ok(self.builder.ins().iconst(types::I64, 0))
+        let result = if results.is_empty() {
+            self.builder.ins().iconst(types::I64, 0)
         } else {
-            Ok(results[0])
-        }
+            results[0]
+        };
+        self.emit_cancel_check()?;
+        Ok(result)
     }
 
     /// Helper to mangle a type expression into a string for function name mangling
@@ -2833,7 +3647,7 @@ impl<'a> LowerContext<'a> {
 
         for (lit_name, lit_val) in fields {
             let val = self.lower_expr(&lit_val.node)?;
-            let val_type = infer_type_for_expr(&lit_val.node, self.env, &self.var_types);
+            let val_type = infer_type_for_expr(&lit_val.node, self.env, &self.var_types, &self.fn_display_name);
             // Materialize string slices before storing into struct fields
             let val = self.emit_string_escape(val, &val_type);
 
@@ -2877,7 +3691,7 @@ impl<'a> LowerContext<'a> {
 
         for (lit_name, lit_val) in fields {
             let val = self.lower_expr(&lit_val.node)?;
-            let val_type = infer_type_for_expr(&lit_val.node, self.env, &self.var_types);
+            let val_type = infer_type_for_expr(&lit_val.node, self.env, &self.var_types, &self.fn_display_name);
             // Materialize string slices before storing into enum variant fields
             let val = self.emit_string_escape(val, &val_type);
 
@@ -2894,13 +3708,66 @@ impl<'a> LowerContext<'a> {
         Ok(ptr)
     }
 
+    /// `call()! map_err(mapper)`: on error, pass the current error to the mapper
+    /// closure and raise the error it returns in place of the original. The
+    /// mapper is only evaluated on the error path.
+    fn lower_propagate_map(
+        &mut self,
+        inner: &crate::span::Spanned<Expr>,
+        mapper: &crate::span::Spanned<Expr>,
+    ) -> Result<Value, CompileError> {
+        let val = self.lower_expr(&inner.node)?;
+
+        let has_err = self.call_runtime("__pluto_has_error", &[]);
+        let zero = self.builder.ins().iconst(types::I64, 0);
+        let is_error = self.builder.ins().icmp(IntCC::NotEqual, has_err, zero);
+
+        let propagate_bb = self.builder.create_block();
+        let continue_bb = self.builder.create_block();
+        self.builder.ins().brif(is_error, propagate_bb, &[], continue_bb, &[]);
+
+        // Propagate block: map the error, raise the result, return default
+        self.builder.switch_to_block(propagate_bb);
+        self.builder.seal_block(propagate_bb);
+        self.emit_coverage_hit(inner.span.file_id, inner.span.start, 1);
+        let mapper_type = infer_type_for_expr(&mapper.node, self.env, &self.var_types, &self.fn_display_name);
+        let error_name = match &mapper_type {
+            PlutoType::Fn(_, ret) => match ret.as_ref() {
+                PlutoType::Class(name) => name.clone(),
+                other => return Err(CompileError::codegen(format!("map_err mapper must return an error, found {other}"))),
+            },
+            other => return Err(CompileError::codegen(format!("map_err mapper must be a closure, found {other}"))),
+        };
+        let err_obj = self.call_runtime("__pluto_get_error", &[]);
+        self.call_runtime_void("__pluto_clear_error", &[]);
+        let closure_ptr = self.lower_expr(&mapper.node)?;
+        let fn_ptr = self.builder.ins().load(types::I64, MemFlags::new(), closure_ptr, Offset32::new(0));
+        let mut sig = self.module.make_signature();
+        sig.params.push(AbiParam::new(types::I64)); // __env
+        sig.params.push(AbiParam::new(types::I64)); // error
+        sig.returns.push(AbiParam::new(types::I64));
+        let sig_ref = self.builder.func.import_signature(sig);
+        let call = self.builder.ins().call_indirect(sig_ref, fn_ptr, &[closure_ptr, err_obj]);
+        let new_err = self.builder.inst_results(call)[0];
+        self.call_runtime_void("__pluto_raise_error", &[new_err]);
+        let type_str = self.make_string_literal(&error_name)?;
+        self.call_runtime_void("__pluto_set_error_type", &[type_str]);
+        self.emit_error_exit()?;
+
+        // Continue block: no error, use the call result
+        self.builder.switch_to_block(continue_bb);
+        self.builder.seal_block(continue_bb);
+        self.emit_coverage_hit(inner.span.file_id, inner.span.start, 2);
+        Ok(val)
+    }
+
     fn lower_catch(
         &mut self,
         inner: &crate::span::Spanned<Expr>,
         handlers: &[CatchHandler],
     ) -> Result<Value, CompileError> {
         let val = self.lower_expr(&inner.node)?;
-        let val_type = infer_type_for_expr(&inner.node, self.env, &self.var_types);
+        let val_type = infer_type_for_expr(&inner.node, self.env, &self.var_types, &self.fn_display_name);
         let cl_type = pluto_to_cranelift(&val_type);
 
         let has_err = self.call_runtime("__pluto_has_error", &[]);
@@ -2957,7 +3824,7 @@ impl<'a> LowerContext<'a> {
         // All handlers were typed and none matched: re-propagate (the coverage
         // check guarantees this is unreachable for errors the call can raise).
         if !had_catch_all {
-            self.emit_default_return();
+            self.emit_error_exit()?;
         }
 
         self.builder.switch_to_block(merge_bb);
@@ -2988,6 +3855,7 @@ impl<'a> LowerContext<'a> {
 
         let stmts = &body.node.stmts;
         let mut term = false;
+        self.guard_scopes.push(Vec::new());
         for stmt in stmts.iter().take(stmts.len().saturating_sub(1)) {
             self.lower_stmt_covered(stmt, &mut term)?;
         }
@@ -3005,6 +3873,7 @@ impl<'a> LowerContext<'a> {
         } else {
             (Some(self.builder.ins().iconst(types::I64, 0)), false)
         };
+        self.pop_guard_scope(did_term);
         if let Some(pv) = prev_var { self.variables.insert(var.node.clone(), pv); }
         else { self.variables.remove(&var.node); }
         if let Some(pt) = prev_type { self.var_types.insert(var.node.clone(), pt); }
@@ -3025,7 +3894,7 @@ impl<'a> LowerContext<'a> {
         let cond_val = self.lower_expr(&condition.node)?;
 
         // Infer the type of the if-expression
-        let if_type = infer_type_for_expr_if(then_block, else_block, self.env, &self.var_types);
+        let if_type = infer_type_for_expr_if(then_block, else_block, self.env, &self.var_types, &self.fn_display_name);
         let cl_type = pluto_to_cranelift(&if_type);
 
         // Create blocks
@@ -3075,7 +3944,7 @@ impl<'a> LowerContext<'a> {
         let ptr = self.lower_expr(scrutinee)?;
 
         // Infer result type
-        let match_type = infer_type_for_expr_match(arms, scrutinee, self.env, &self.var_types);
+        let match_type = infer_type_for_expr_match(arms, scrutinee, self.env, &self.var_types, &self.fn_display_name);
         let cl_type = pluto_to_cranelift(&match_type);
 
         // Load tag (discriminant) from offset 0
@@ -3097,7 +3966,7 @@ impl<'a> LowerContext<'a> {
         self.builder.ins().jump(check_blocks[0], &[]);
 
         // Get enum info for variant indices
-        let scrutinee_type = infer_type_for_expr(scrutinee, self.env, &self.var_types);
+        let scrutinee_type = infer_type_for_expr(scrutinee, self.env, &self.var_types, &self.fn_display_name);
         let enum_name = match &scrutinee_type {
             PlutoType::Enum(name) => name.clone(),
             _ => return Err(CompileError::codegen("match scrutinee must be enum".to_string())),
@@ -3209,6 +4078,21 @@ impl<'a> LowerContext<'a> {
     fn lower_block_value(
         &mut self,
         block: &crate::parser::ast::Block
+    ) -> Result<Value, CompileError> {
+        self.guard_scopes.push(Vec::new());
+        let val = self.lower_block_value_stmts(block)?;
+        // A nested `return` may already have closed the current block
+        let terminated = self.builder.current_block()
+            .and_then(|bb| self.builder.func.layout.last_inst(bb))
+            .is_some_and(|inst| self.builder.func.dfg.insts[inst].opcode().is_terminator());
+        self.pop_guard_scope(terminated);
+        Ok(val)
+    }
+
+    /// Lower a value block's statements; the last one produces the value.
+    fn lower_block_value_stmts(
+        &mut self,
+        block: &crate::parser::ast::Block
     ) -> Result<Value, CompileError> {
         use crate::parser::ast::Stmt;
 
@@ -3237,7 +4121,7 @@ impl<'a> LowerContext<'a> {
                 let cond_val = self.lower_expr(&condition.node)?;
 
                 // Infer the type of the if branches
-                let if_type = infer_type_for_expr_if(then_block, else_block, self.env, &self.var_types);
+                let if_type = infer_type_for_expr_if(then_block, else_block, self.env, &self.var_types, &self.fn_display_name);
                 let cl_type = pluto_to_cranelift(&if_type);
 
                 // Create blocks
@@ -3288,7 +4172,7 @@ impl<'a> LowerContext<'a> {
             && name.node == "expect" && expect_args.len() == 1
         {
             let actual_val = self.lower_expr(&expect_args[0].node)?;
-            let inner_type = infer_type_for_expr(&expect_args[0].node, self.env, &self.var_types);
+            let inner_type = infer_type_for_expr(&expect_args[0].node, self.env, &self.var_types, &self.fn_display_name);
             let line_no = byte_to_line(self.source, object.span.start);
             let line_val = self.builder.ins().iconst(types::I64, line_no as i64);
 
@@ -3334,7 +4218,7 @@ impl<'a> LowerContext<'a> {
         // line-framed (`<method>\n<arg1>\n<arg2>...`). Complex types over the
         // wire (via the marshalers) are a follow-up.
         {
-            let pre_type = infer_type_for_expr(&object.node, self.env, &self.var_types);
+            let pre_type = infer_type_for_expr(&object.node, self.env, &self.var_types, &self.fn_display_name);
             if let PlutoType::Class(cname) = &pre_type
                 && self.env.remote_types.contains(cname)
             {
@@ -3348,7 +4232,7 @@ impl<'a> LowerContext<'a> {
                 let mut payload = self.make_string_literal("")?;
                 let nl = self.make_string_literal("\n")?;
                 for (i, arg) in args.iter().enumerate() {
-                    let aty = infer_type_for_expr(&arg.node, self.env, &self.var_types);
+                    let aty = infer_type_for_expr(&arg.node, self.env, &self.var_types, &self.fn_display_name);
                     let av = self.lower_expr(&arg.node)?;
                     let s = match aty {
                         PlutoType::Int => self.call_runtime("__pluto_int_to_string", &[av]),
@@ -3485,13 +4369,21 @@ impl<'a> LowerContext<'a> {
         }
 
         let obj_ptr = self.lower_expr(&object.node)?;
-        let obj_type = infer_type_for_expr(&object.node, self.env, &self.var_types);
+        let obj_type = infer_type_for_expr(&object.node, self.env, &self.var_types, &self.fn_display_name);
 
         // Task methods
         if let PlutoType::Task(inner) = &obj_type {
             match method.node.as_str() {
                 "get" => {
-                    let raw = self.call_runtime("__pluto_task_get", &[obj_ptr]);
+                    // Nothing checks for errors after an infallible get, so the
+                    // runtime reports a cancelled task itself
+                    let fallible = self.env
+                        .resolve_method_fallibility(&self.fn_display_name, method.span.start)
+                        .unwrap_or(true);
+                    let get_fn = if fallible { "__pluto_task_get" } else { "__pluto_task_get_infallible" };
+                    let raw = self.call_runtime(get_fn, &[obj_ptr]);
+                    // A cancelled waiter stops waiting and unwinds
+                    self.emit_cancel_check()?;
                     return Ok(from_array_slot(raw, inner, &mut self.builder));
                 }
                 "detach" => {
@@ -3527,6 +4419,10 @@ impl<'a> LowerContext<'a> {
                     self.call_runtime_void("__pluto_chan_sender_dec", &[obj_ptr]);
                     return Ok(self.builder.ins().iconst(types::I64, 0));
                 }
+                "is_full" => {
+                    let result = self.call_runtime("__pluto_chan_is_full", &[obj_ptr]);
+                    return Ok(self.builder.ins().ireduce(types::I8, result));
+                }
                 _ => return Err(CompileError::codegen(format!("Sender has no method '{}'", method.node)))
             }
         }
@@ -3542,10 +4438,78 @@ impl<'a> LowerContext<'a> {
                     let raw = self.call_runtime("__pluto_chan_try_recv", &[obj_ptr]);
                     return Ok(from_array_slot(raw, inner, &mut self.builder));
                 }
+                "recv_timeout" => {
+                    let inner = inner.clone();
+                    let timeout_ms = self.lower_expr(&args[0].node)?;
+                    let raw = self.call_runtime("__pluto_chan_recv_timeout", &[obj_ptr, timeout_ms]);
+                    return Ok(from_array_slot(raw, &inner, &mut self.builder));
+                }
+                "len" => {
+                    return Ok(self.call_runtime("__pluto_chan_len", &[obj_ptr]));
+                }
                 _ => return Err(CompileError::codegen(format!("Receiver has no method '{}'", method.node)))
             }
         }
 
+        // WaitGroup methods
+        if obj_type == PlutoType::WaitGroup {
+            match method.node.as_str() {
+                "add" => {
+                    let delta = self.lower_expr(&args[0].node)?;
+                    self.call_runtime_void("__pluto_waitgroup_add", &[obj_ptr, delta]);
+                }
+                "done" => self.call_runtime_void("__pluto_waitgroup_done", &[obj_ptr]),
+                "wait" => self.call_runtime_void("__pluto_waitgroup_wait", &[obj_ptr]),
+                _ => return Err(CompileError::codegen(format!("WaitGroup has no method '{}'", method.node))),
+            }
+            return Ok(self.builder.ins().iconst(types::I64, 0));
+        }
+
+        // Atomic methods
+        if obj_type == PlutoType::Atomic {
+            return match method.node.as_str() {
+                "load" => Ok(self.call_runtime("__pluto_atomic_load", &[obj_ptr])),
+                "store" => {
+                    let value = self.lower_expr(&args[0].node)?;
+                    self.call_runtime_void("__pluto_atomic_store", &[obj_ptr, value]);
+                    Ok(self.builder.ins().iconst(types::I64, 0))
+                }
+                "add" => {
+                    let delta = self.lower_expr(&args[0].node)?;
+                    Ok(self.call_runtime("__pluto_atomic_add", &[obj_ptr, delta]))
+                }
+                "compare_and_swap" => {
+                    let expected = self.lower_expr(&args[0].node)?;
+                    let desired = self.lower_expr(&args[1].node)?;
+                    let result = self.call_runtime("__pluto_atomic_compare_and_swap", &[obj_ptr, expected, desired]);
+                    Ok(self.builder.ins().ireduce(types::I8, result))
+                }
+                _ => Err(CompileError::codegen(format!("Atomic has no method '{}'", method.node))),
+            };
+        }
+
+        // Mutex methods: the guard is the mutex pointer itself, with the value in slot 0
+        if let PlutoType::Mutex(_) = &obj_type {
+            return Ok(self.call_runtime("__pluto_mutex_lock", &[obj_ptr]));
+        }
+        if let PlutoType::MutexGuard(inner) = &obj_type {
+            let inner = (**inner).clone();
+            return match method.node.as_str() {
+                "get" => {
+                    let raw = self.builder.ins().load(types::I64, MemFlags::new(), obj_ptr, 0);
+                    Ok(from_array_slot(raw, &inner, &mut self.builder))
+                }
+                "set" => {
+                    let value = self.lower_expr(&args[0].node)?;
+                    let value = self.emit_string_escape(value, &inner);
+                    let slot = to_array_slot(value, &inner, &mut self.builder);
+                    self.builder.ins().store(MemFlags::new(), slot, obj_ptr, 0);
+                    Ok(self.builder.ins().iconst(types::I64, 0))
+                }
+                _ => Err(CompileError::codegen(format!("MutexGuard has no method '{}'", method.node))),
+            };
+        }
+
         // Array methods
         if let PlutoType::Array(elem) = &obj_type {
             match method.node.as_str() {
@@ -3623,6 +4587,48 @@ impl<'a> LowerContext<'a> {
                     let tag = self.builder.ins().iconst(types::I64, key_type_tag(&elem));
                     return Ok(self.call_runtime("__pluto_array_index_of", &[obj_ptr, slot, tag]));
                 }
+                "sort" => {
+                    // Comparison modes match the SORT_* constants in builtins.c
+                    let (cmp_fn, mode) = match &**elem {
+                        PlutoType::Int => (self.builder.ins().iconst(types::I64, 0), 0),
+                        PlutoType::Float => (self.builder.ins().iconst(types::I64, 0), 1),
+                        PlutoType::String => (self.builder.ins().iconst(types::I64, 0), 2),
+                        PlutoType::Byte | PlutoType::Bool => (self.builder.ins().iconst(types::I64, 0), 3),
+                        PlutoType::Class(name) | PlutoType::Enum(name) => {
+                            let mangled = mangle_method(name, "compare");
+                            let func_id = self.func_ids.get(&mangled).ok_or_else(|| {
+                                CompileError::codegen(format!("undefined method 'compare' on '{name}'"))
+                            })?;
+                            let func_ref = self.module.declare_func_in_func(*func_id, self.builder.func);
+                            (self.builder.ins().func_addr(types::I64, func_ref), 7)
+                        }
+                        other => return Err(CompileError::codegen(format!("cannot sort array of {other}"))),
+                    };
+                    let ctx = self.builder.ins().iconst(types::I64, 0);
+                    let mode = self.builder.ins().iconst(types::I64, mode);
+                    self.call_runtime_void("__pluto_array_sort", &[obj_ptr, cmp_fn, ctx, mode]);
+                    return Ok(self.builder.ins().iconst(types::I64, 0));
+                }
+                "sort_by" => {
+                    let closure_ptr = self.lower_expr(&args[0].node)?;
+                    let cmp_fn = self.builder.ins().load(types::I64, MemFlags::new(), closure_ptr, Offset32::new(0));
+                    let mode = match pluto_to_cranelift(elem) {
+                        types::F64 => 5,
+                        types::I8 => 6,
+                        _ => 4,
+                    };
+                    let mode = self.builder.ins().iconst(types::I64, mode);
+                    self.call_runtime_void("__pluto_array_sort", &[obj_ptr, cmp_fn, closure_ptr, mode]);
+                    return Ok(self.builder.ins().iconst(types::I64, 0));
+                }
+                "join" => {
+                    let sep = self.lower_expr(&args[0].node)?;
+                    return Ok(self.call_runtime("__pluto_array_join", &[obj_ptr, sep]));
+                }
+                "map_to_string" => {
+                    let tag = self.builder.ins().iconst(types::I64, key_type_tag(elem));
+                    return Ok(self.call_runtime("__pluto_array_map_to_string", &[obj_ptr, tag]));
+                }
                 _ => {
                     return Err(CompileError::codegen(format!("array has no method '{}'", method.node)));
                 }
@@ -3701,6 +4707,11 @@ impl<'a> LowerContext<'a> {
                     self.call_runtime_void("__pluto_bytes_push", &[obj_ptr, widened]);
                     Ok(self.builder.ins().iconst(types::I64, 0))
                 }
+                "push_str" => {
+                    let arg_val = self.lower_expr(&args[0].node)?;
+                    self.call_runtime_void("__pluto_bytes_push_str", &[obj_ptr, arg_val]);
+                    Ok(self.builder.ins().iconst(types::I64, 0))
+                }
                 "to_string" => Ok(self.call_runtime("__pluto_bytes_to_string", &[obj_ptr])),
                 _ => Err(CompileError::codegen(format!("bytes has no method '{}'", method.node))),
             };
@@ -3710,6 +4721,7 @@ impl<'a> LowerContext<'a> {
         if obj_type == PlutoType::String {
             return match method.node.as_str() {
                 "len" => Ok(self.call_runtime("__pluto_string_len", &[obj_ptr])),
+                "char_count" => Ok(self.call_runtime("__pluto_string_char_count", &[obj_ptr])),
                 "contains" => {
                     let arg = self.lower_expr(&args[0].node)?;
                     let result = self.call_runtime("__pluto_string_contains", &[obj_ptr, arg]);
@@ -3748,12 +4760,15 @@ impl<'a> LowerContext<'a> {
                 }
                 "char_at" => {
                     let idx = self.lower_expr(&args[0].node)?;
-                    Ok(self.call_runtime("__pluto_string_char_at", &[obj_ptr, idx]))
+                    Ok(self.call_runtime("__pluto_string_char_at_utf8", &[obj_ptr, idx]))
                 }
                 "byte_at" => {
                     let idx = self.lower_expr(&args[0].node)?;
                     Ok(self.call_runtime("__pluto_string_byte_at", &[obj_ptr, idx]))
                 }
+                "chars" => Ok(self.call_runtime("__pluto_string_chars", &[obj_ptr])),
+                "lines" => Ok(self.call_runtime("__pluto_string_lines", &[obj_ptr])),
+                "bytes" => Ok(self.call_runtime("__pluto_string_bytes", &[obj_ptr])),
                 "to_bytes" => Ok(self.call_runtime("__pluto_string_to_bytes", &[obj_ptr])),
                 "to_int" => Ok(self.call_runtime("__pluto_string_to_int", &[obj_ptr])),
                 "to_float" => Ok(self.call_runtime("__pluto_string_to_float", &[obj_ptr])),
@@ -3821,7 +4836,7 @@ impl<'a> LowerContext<'a> {
             let mut call_args = vec![data_ptr]; // data_ptr as self
             for (i, arg) in args.iter().enumerate() {
                 let val = self.lower_expr(&arg.node)?;
-                let arg_type = infer_type_for_expr(&arg.node, self.env, &self.var_types);
+                let arg_type = infer_type_for_expr(&arg.node, self.env, &self.var_types, &self.fn_display_name);
                 let param_expected = method_sig.params.get(i + 1); // +1 to skip self
                 if let (PlutoType::Class(cn), Some(PlutoType::Trait(tn))) = (&arg_type, param_expected) {
                     let wrapped = self.wrap_class_as_trait(val, cn, tn)?;
@@ -3833,11 +4848,13 @@ impl<'a> LowerContext<'a> {
 
             let call = self.builder.ins().call_indirect(sig_ref, fn_ptr, &call_args);
             let results = self.builder.inst_results(call);
-            if results.is_empty() {
-                Ok(self.builder.ins().iconst(types::I64, 0))
+            let result = if results.is_empty() {
+                self.builder.ins().iconst(types::I64, 0)
             } else {
-                Ok(results[0])
-            }
+                results[0]
+            };
+            self.emit_cancel_check()?;
+            Ok(result)
         } else if let PlutoType::Class(class_name) = &obj_type {
             let class_name = class_name.clone();
 
@@ -3862,7 +4879,7 @@ impl<'a> LowerContext<'a> {
             let mut arg_values = vec![obj_ptr];
             for (i, arg) in args.iter().enumerate() {
                 let val = self.lower_expr(&arg.node)?;
-                let arg_type = infer_type_for_expr(&arg.node, self.env, &self.var_types);
+                let arg_type = infer_type_for_expr(&arg.node, self.env, &self.var_types, &self.fn_display_name);
 
                 // Check if we're passing a class instance to a trait parameter
                 let param_expected = method_sig.as_ref().and_then(|sig| sig.params.get(i + 1)); // +1 to skip self
@@ -3912,6 +4929,39 @@ impl<'a> LowerContext<'a> {
                 self.call_runtime_void("__pluto_rwlock_unlock", &[lock_ptr]);
             }
 
+            self.emit_cancel_check()?;
+            Ok(result)
+        } else if let PlutoType::Enum(enum_name) = &obj_type {
+            // Enum methods were lowered to `Enum$method` functions taking the
+            // enum pointer as self; the body dispatches on the tag itself.
+            let mangled = mangle_method(enum_name, &method.node);
+            let func_id = self.func_ids.get(&mangled).ok_or_else(|| {
+                CompileError::codegen(format!("undefined method '{}' on enum '{enum_name}'", method.node))
+            })?;
+            let func_ref = self.module.declare_func_in_func(*func_id, self.builder.func);
+            let method_sig = self.env.functions.get(&mangled).cloned();
+
+            let mut arg_values = vec![obj_ptr];
+            for (i, arg) in args.iter().enumerate() {
+                let val = self.lower_expr(&arg.node)?;
+                let arg_type = infer_type_for_expr(&arg.node, self.env, &self.var_types, &self.fn_display_name);
+                let param_expected = method_sig.as_ref().and_then(|sig| sig.params.get(i + 1)); // +1 to skip self
+                if let (PlutoType::Class(cn), Some(PlutoType::Trait(tn))) = (&arg_type, param_expected) {
+                    let wrapped = self.wrap_class_as_trait(val, cn, tn)?;
+                    arg_values.push(wrapped);
+                } else {
+                    arg_values.push(val);
+                }
+            }
+
+            let call = self.builder.ins().call(func_ref, &arg_values);
+            let results = self.builder.inst_results(call);
+            let result = if results.is_empty() {
+                self.builder.ins().iconst(types::I64, 0)
+            } else {
+                results[0]
+            };
+            self.emit_cancel_check()?;
             Ok(result)
         } else {
             Err(CompileError::codegen(format!("method call on non-class type {obj_type}")))
@@ -3943,7 +4993,7 @@ impl<'a> LowerContext<'a> {
             }
 
             let arg_val = self.lower_expr(&arg.node)?;
-            let arg_type = infer_type_for_expr(&arg.node, self.env, &self.var_types);
+            let arg_type = infer_type_for_expr(&arg.node, self.env, &self.var_types, &self.fn_display_name);
 
             let json_val = match arg_type {
                 PlutoType::Int => self.call_runtime("__pluto_int_to_string", &[arg_val]),
@@ -4069,7 +5119,7 @@ impl<'a> LowerContext<'a> {
         args: &[crate::span::Spanned<Expr>],
     ) -> Result<Value, CompileError> {
         let arg = &args[0];
-        let arg_type = infer_type_for_expr(&arg.node, self.env, &self.var_types);
+        let arg_type = infer_type_for_expr(&arg.node, self.env, &self.var_types, &self.fn_display_name);
         let arg_val = self.lower_expr(&arg.node)?;
 
         match arg_type {
@@ -4092,7 +5142,7 @@ impl<'a> LowerContext<'a> {
                 let widened = self.builder.ins().uextend(types::I64, arg_val);
                 self.call_runtime_void("__pluto_print_int", &[widened]);
             }
-            PlutoType::Void | PlutoType::Class(_) | PlutoType::Array(_) | PlutoType::Trait(_) | PlutoType::Enum(_) | PlutoType::Fn(_, _) | PlutoType::Map(_, _) | PlutoType::Set(_) | PlutoType::Task(_) | PlutoType::Sender(_) | PlutoType::Receiver(_) | PlutoType::Range | PlutoType::Error | PlutoType::TypeParam(_) | PlutoType::Bytes | PlutoType::GenericInstance(_, _, _) | PlutoType::Nullable(_) | PlutoType::Stream(_) => {
+            PlutoType::Void | PlutoType::Class(_) | PlutoType::Array(_) | PlutoType::Trait(_) | PlutoType::Enum(_) | PlutoType::Fn(_, _) | PlutoType::Map(_, _) | PlutoType::Set(_) | PlutoType::Task(_) | PlutoType::Sender(_) | PlutoType::Receiver(_) | PlutoType::Range | PlutoType::Error | PlutoType::TypeParam(_) | PlutoType::Bytes | PlutoType::GenericInstance(_, _, _) | PlutoType::Nullable(_) | PlutoType::Stream(_) | PlutoType::WaitGroup | PlutoType::Atomic | PlutoType::Mutex(_) | PlutoType::MutexGuard(_) => {
                 return Err(CompileError::codegen(format!("cannot print {arg_type}")));
             }
         }
@@ -4149,7 +5199,9 @@ pub fn lower_function(
     fn_contracts: &HashMap<String, FnContracts>,
     singleton_globals: &HashMap<String, DataId>,
     rwlock_globals: &HashMap<String, DataId>,
+    user_globals: &HashMap<String, DataId>,
     coverage_lookup: &HashMap<(u32, usize, u32), u32>,
+    debug: bool,
 ) -> Result<(), CompileError> {
     let entry_block = builder.create_block();
     builder.append_block_params_for_function_params(entry_block);
@@ -4167,7 +5219,8 @@ pub fn lower_function(
             if let Some(cn) = class_name {
                 PlutoType::Class(cn.to_string())
             } else {
-                PlutoType::Void
+                // Lowered enum method: `self` is declared with the enum type
+                resolve_param_type(param, env)
             }
         } else {
             resolve_param_type(param, env)
@@ -4227,7 +5280,8 @@ pub fn lower_function(
     }
 
     // Compute expected return type for class→trait wrapping in return statements
-    let expected_return_type = if func.name.node == "main" && class_name.is_none() {
+    let is_main = func.name.node == "main" && class_name.is_none();
+    let expected_return_type = if is_main {
         Some(PlutoType::Int)
     } else {
         let lookup_name = if let Some(cn) = class_name {
@@ -4240,19 +5294,16 @@ pub fn lower_function(
 
     let is_spawn_closure = spawn_closure_fns.contains(&func.name.node);
 
-    // Create exit block if we have sender cleanup vars
-    let exit_block = if !sender_cleanup_vars.is_empty() {
+    // Create exit block if we have sender cleanup vars or mutex guards to
+    // release, or in debug builds so every return path pops the stack-trace frame
+    let binds_guard = binds_mutex_guard(&func.body.node.stmts);
+    let exit_block = if !sender_cleanup_vars.is_empty() || binds_guard || debug {
         let exit_bb = builder.create_block();
-        // Add return value as block param if function returns non-void
-        let is_void_return = matches!(&expected_return_type, Some(PlutoType::Void) | None);
-        if !is_void_return {
-            // Spawn closures return I64 regardless of actual type
-            let ret_cl_type = if is_spawn_closure {
-                types::I64
-            } else {
-                pluto_to_cranelift(expected_return_type.as_ref()
-                    .expect("non-void return type should be set"))
-            };
+        // Add return value as block param if function returns non-void. Take the
+        // type from the signature: spawn closures return I64 regardless of their
+        // Pluto type, and synthesized functions may have no typeck entry.
+        if let Some(ret) = builder.func.signature.returns.first() {
+            let ret_cl_type = ret.value_type;
             builder.append_block_param(exit_bb, ret_cl_type);
         }
         Some(exit_bb)
@@ -4271,7 +5322,6 @@ pub fn lower_function(
     let fn_display_name = fn_lookup.clone();
 
     // Build context and lower body
-    let is_main = func.name.node == "main" && class_name.is_none();
     let mut ctx = LowerContext {
         builder,
         module,
@@ -4284,16 +5334,22 @@ pub fn lower_function(
         fn_contracts,
         singleton_globals,
         rwlock_globals,
+        user_globals,
         coverage_lookup,
         variables,
         var_types,
         next_var,
         expected_return_type,
         loop_stack: Vec::new(),
+        try_stack: Vec::new(),
+        finally_stack: Vec::new(),
         sender_cleanup_vars,
+        guard_scopes: Vec::new(),
+        guard_vars: Vec::new(),
         exit_block,
         fn_display_name,
         is_spawn_closure,
+        is_main,
     };
 
     // Initialize GC at start of non-app main
@@ -4320,6 +5376,21 @@ pub fn lower_function(
         }
     }
 
+    if debug {
+        let name_ptr = ctx.create_data_str(&ctx.fn_display_name.clone())?;
+        ctx.call_runtime_void("__pluto_push_frame", &[name_ptr]);
+    }
+
+    // Safepoint at entry, so recursion without loops still stops for GC. A
+    // function that calls nothing returns without recursing, and its loops
+    // have their own safepoints, so it skips the check.
+    if makes_calls(&func.body.node.stmts) {
+        ctx.emit_safepoint()?;
+    }
+
+    // Count the call (the scanner keys function entry points on the name span)
+    ctx.emit_coverage_hit(func.name.span.file_id, func.name.span.start, 0);
+
     // Emit requires checks at function entry
     if let Some(contracts) = fn_contracts.get(&fn_lookup) {
         if !contracts.requires.is_empty() {
@@ -4329,6 +5400,8 @@ pub fn lower_function(
 
     }
 
+    // Guards bound at the top level of the body are released by the exit block
+    ctx.guard_scopes.push(Vec::new());
     let mut terminated = false;
     for stmt in &func.body.node.stmts {
         if terminated {
@@ -4361,25 +5434,40 @@ pub fn lower_function(
         }
     }
 
-    // Emit exit block: sender cleanup + actual return
-    let is_void_return = matches!(&ctx.expected_return_type, Some(PlutoType::Void) | None);
+    // Emit exit block: sender cleanup, debug frame pop + actual return
     if let Some(exit_bb) = ctx.exit_block {
         ctx.builder.switch_to_block(exit_bb);
         ctx.builder.seal_block(exit_bb);
 
+        // Unlock any mutex guard still held on the path that got here
+        for var in ctx.guard_vars.clone() {
+            let guard = ctx.builder.use_var(var);
+            ctx.call_runtime_void("__pluto_mutex_unlock", &[guard]);
+        }
+
         // Call sender_dec for each cleanup variable
-        let dec_ref = ctx.module.declare_func_in_func(ctx.runtime.get("__pluto_chan_sender_dec"), ctx.builder.func);
-        for &var in &ctx.sender_cleanup_vars {
-            let val = ctx.builder.use_var(var);
-            ctx.builder.ins().call(dec_ref, &[val]);
+        if !ctx.sender_cleanup_vars.is_empty() {
+            let dec_ref = ctx.module.declare_func_in_func(ctx.runtime.get("__pluto_chan_sender_dec"), ctx.builder.func);
+            for &var in &ctx.sender_cleanup_vars {
+                let val = ctx.builder.use_var(var);
+                ctx.builder.ins().call(dec_ref, &[val]);
+            }
+        }
+
+        if debug {
+            ctx.call_runtime_void("__pluto_pop_frame", &[]);
+            if is_main {
+                ctx.call_runtime_void("__pluto_report_uncaught_error", &[]);
+            }
         }
 
-        // Emit actual return
-        if !is_void_return {
+        // Emit actual return (SSA construction may append further params for
+        // variables used above, so only the first one is the return value)
+        if ctx.builder.func.signature.returns.is_empty() {
+            ctx.builder.ins().return_(&[]);
+        } else {
             let ret_val = ctx.builder.block_params(exit_bb)[0];
             ctx.builder.ins().return_(&[ret_val]);
-        } else {
-            ctx.builder.ins().return_(&[]);
         }
     }
 
@@ -4387,6 +5475,55 @@ pub fn lower_function(
     Ok(())
 }
 
+/// Whether a function body calls anything: functions, methods, closures or
+/// spawned tasks. Operators on classes dispatch to methods without a call
+/// expression, so they don't count.
+fn makes_calls(stmts: &[crate::span::Spanned<Stmt>]) -> bool {
+    struct CallFinder {
+        found: bool,
+    }
+    impl Visitor for CallFinder {
+        fn visit_expr(&mut self, expr: &crate::span::Spanned<Expr>) {
+            if matches!(
+                expr.node,
+                Expr::Call { .. } | Expr::MethodCall { .. } | Expr::StaticTraitCall { .. } | Expr::Spawn { .. }
+            ) {
+                self.found = true;
+                return;
+            }
+            walk_expr(self, expr);
+        }
+    }
+    let mut finder = CallFinder { found: false };
+    for stmt in stmts {
+        finder.visit_stmt(stmt);
+    }
+    finder.found
+}
+
+/// Whether a function body binds a mutex guard (`let g = m.lock()`) anywhere,
+/// in which case returns must go through the exit block to unlock it.
+fn binds_mutex_guard(stmts: &[crate::span::Spanned<Stmt>]) -> bool {
+    struct GuardFinder {
+        found: bool,
+    }
+    impl Visitor for GuardFinder {
+        fn visit_stmt(&mut self, stmt: &crate::span::Spanned<Stmt>) {
+            if let Stmt::Let { value, .. } = &stmt.node
+                && matches!(&value.node, Expr::MethodCall { method, args, .. } if method.node == "lock" && args.is_empty())
+            {
+                self.found = true;
+            }
+            walk_stmt(self, stmt);
+        }
+    }
+    let mut finder = GuardFinder { found: false };
+    for stmt in stmts {
+        finder.visit_stmt(stmt);
+    }
+    finder.found
+}
+
 // ── Generator codegen ────────────────────────────────────────────────────
 
 /// Count yield points in a block (recursively enters if/while/for/match bodies).
@@ -4415,6 +5552,7 @@ fn count_yields_in_block(stmts: &[crate::span::Spanned<Stmt>]) -> u32 {
 /// Returns (name, type) pairs. Walks into if/while/for/match bodies.
 struct LocalDeclCollector<'a> {
     env: &'a TypeEnv,
+    current_fn: &'a str,
     locals: &'a mut Vec<(String, PlutoType)>,
     seen: &'a mut HashSet<String>,
 }
@@ -4427,14 +5565,29 @@ impl Visitor for LocalDeclCollector<'_> {
                     let pty = if let Some(t) = ty {
                         resolve_type_expr_to_pluto(&t.node, self.env)
                     } else {
-                        infer_type_for_expr(&value.node, self.env, &HashMap::new())
+                        infer_type_for_expr(&value.node, self.env, &HashMap::new(), self.current_fn)
                     };
                     self.locals.push((name.node.clone(), pty));
                 }
             }
+            Stmt::LetStruct { ty, fields, .. } => {
+                if let PlutoType::Class(class_name) = resolve_type_expr_to_pluto(&ty.node, self.env)
+                    && let Some(class_info) = self.env.classes.get(&class_name)
+                {
+                    for (field, rename) in fields {
+                        let local = rename.as_ref().unwrap_or(field);
+                        if local.node == "_" || !self.seen.insert(local.node.clone()) {
+                            continue;
+                        }
+                        if let Some((_, field_type, _)) = class_info.fields.iter().find(|(n, _, _)| *n == field.node) {
+                            self.locals.push((local.node.clone(), field_type.clone()));
+                        }
+                    }
+                }
+            }
             Stmt::For { var, iterable, body, .. } => {
                 if self.seen.insert(var.node.clone()) {
-                    let iter_type = infer_type_for_expr(&iterable.node, self.env, &HashMap::new());
+                    let iter_type = infer_type_for_expr(&iterable.node, self.env, &HashMap::new(), self.current_fn);
                     let elem_type = match iter_type {
                         PlutoType::Array(e) => *e,
                         PlutoType::Range => PlutoType::Int,
@@ -4458,11 +5611,12 @@ impl Visitor for LocalDeclCollector<'_> {
     }
 }
 
-fn collect_local_decls(stmts: &[crate::span::Spanned<Stmt>], env: &TypeEnv) -> Vec<(String, PlutoType)> {
+fn collect_local_decls(stmts: &[crate::span::Spanned<Stmt>], env: &TypeEnv, current_fn: &str) -> Vec<(String, PlutoType)> {
     let mut locals = Vec::new();
     let mut seen = HashSet::new();
     let mut collector = LocalDeclCollector {
         env,
+        current_fn,
         locals: &mut locals,
         seen: &mut seen,
     };
@@ -4490,7 +5644,7 @@ pub fn lower_generator_creator(
     builder.seal_block(entry_block);
 
     let num_params = func.params.len();
-    let local_decls = collect_local_decls(&func.body.node.stmts, env);
+    let local_decls = collect_local_decls(&func.body.node.stmts, env, &func.name.node);
     let num_locals = local_decls.len();
 
     // Layout: [next_fn_ptr(0) | state(8) | done(16) | result(24) | params(32..) | locals(32+P*8..)]
@@ -4559,6 +5713,7 @@ pub fn lower_generator_next(
     fn_contracts: &HashMap<String, FnContracts>,
     singleton_globals: &HashMap<String, DataId>,
     rwlock_globals: &HashMap<String, DataId>,
+    user_globals: &HashMap<String, DataId>,
     coverage_lookup: &HashMap<(u32, usize, u32), u32>,
 ) -> Result<(), CompileError> {
     let entry_block = builder.create_block();
@@ -4566,7 +5721,7 @@ pub fn lower_generator_next(
     builder.switch_to_block(entry_block);
 
     let num_params = func.params.len();
-    let local_decls = collect_local_decls(&func.body.node.stmts, env);
+    let local_decls = collect_local_decls(&func.body.node.stmts, env, &func.name.node);
     let _num_locals = local_decls.len();
     let num_yields = count_yields_in_block(&func.body.node.stmts);
 
@@ -4682,16 +5837,22 @@ pub fn lower_generator_next(
         fn_contracts,
         singleton_globals,
         rwlock_globals,
+        user_globals,
         coverage_lookup,
         variables,
         var_types,
         next_var: next_var_id,
         expected_return_type: Some(PlutoType::Void),
         loop_stack: Vec::new(),
+        try_stack: Vec::new(),
+        finally_stack: Vec::new(),
         sender_cleanup_vars: Vec::new(),
+        guard_scopes: Vec::new(),
+        guard_vars: Vec::new(),
         exit_block: None,
         fn_display_name: func.name.node.clone(),
         is_spawn_closure: false,
+        is_main: false,
     };
 
     // Generator-specific state
@@ -4756,7 +5917,7 @@ fn lower_generator_block(
             Stmt::Yield { value } => {
                 // 1. Lower the yield value expression
                 let val = ctx.lower_expr(&value.node)?;
-                let val_type = infer_type_for_expr(&value.node, ctx.env, &ctx.var_types);
+                let val_type = infer_type_for_expr(&value.node, ctx.env, &ctx.var_types, &ctx.fn_display_name);
 
                 // 2. Store result at gen_ptr[24]
                 let slot_val = to_array_slot(val, &val_type, &mut ctx.builder);
@@ -4828,7 +5989,7 @@ fn lower_generator_block(
                 // which would shadow the pre-declared one used for save/restore across yields.
                 // Instead, evaluate the value and def_var on the existing pre-declared variable.
                 let val = ctx.lower_expr(&value.node)?;
-                let val_type = infer_type_for_expr(&value.node, ctx.env, &ctx.var_types);
+                let val_type = infer_type_for_expr(&value.node, ctx.env, &ctx.var_types, &ctx.fn_display_name);
 
                 // Handle type coercions (trait wrapping, nullable boxing)
                 let declared_type = ty.as_ref().map(|t| resolve_type_expr_to_pluto(&t.node, ctx.env));
@@ -4848,6 +6009,24 @@ fn lower_generator_block(
                 })?;
                 ctx.builder.def_var(*var, final_val);
             }
+            Stmt::LetStruct { ty, fields, value, .. } => {
+                // Same as Let: bind into the pre-declared slots, not fresh variables
+                let ptr = ctx.lower_expr(&value.node)?;
+                let PlutoType::Class(class_name) = resolve_type_expr_to_pluto(&ty.node, ctx.env) else {
+                    return Err(CompileError::codegen("destructuring a non-class value".to_string()));
+                };
+                for (field, rename) in fields {
+                    let local = rename.as_ref().unwrap_or(field);
+                    if local.node == "_" {
+                        continue;
+                    }
+                    let (val, _) = ctx.load_class_field(ptr, &class_name, &field.node)?;
+                    let var = ctx.variables.get(&local.node).ok_or_else(|| {
+                        CompileError::codegen(format!("generator local variable '{}' not found in pre-declared slots", local.node))
+                    })?;
+                    ctx.builder.def_var(*var, val);
+                }
+            }
             Stmt::If { condition, then_block, else_block } => {
                 lower_generator_if(ctx, condition, then_block, else_block.as_ref(), terminated, yield_counter, resume_blocks, param_slots, local_slots, num_params, gen_ptr_var, done_bb)?;
             }
@@ -4883,7 +6062,7 @@ fn lower_generator_if(
     done_bb: cranelift_codegen::ir::Block,
 ) -> Result<(), CompileError> {
     let cond_val = ctx.lower_expr(&condition.node)?;
-    let cond_type = infer_type_for_expr(&condition.node, ctx.env, &ctx.var_types);
+    let cond_type = infer_type_for_expr(&condition.node, ctx.env, &ctx.var_types, &ctx.fn_display_name);
     let cond_i8 = if cond_type == PlutoType::Bool {
         cond_val
     } else {
@@ -4959,7 +6138,7 @@ fn lower_generator_while(
     // Header: evaluate condition
     ctx.builder.switch_to_block(header_bb);
     let cond_val = ctx.lower_expr(&condition.node)?;
-    let cond_type = infer_type_for_expr(&condition.node, ctx.env, &ctx.var_types);
+    let cond_type = infer_type_for_expr(&condition.node, ctx.env, &ctx.var_types, &ctx.fn_display_name);
     let cond_i8 = if cond_type == PlutoType::Bool {
         cond_val
     } else {
@@ -4972,7 +6151,7 @@ fn lower_generator_while(
     ctx.builder.switch_to_block(body_bb);
     ctx.builder.seal_block(body_bb);
 
-    ctx.loop_stack.push((header_bb, exit_bb));
+    ctx.loop_stack.push((header_bb, exit_bb, ctx.guard_scopes.len()));
     let mut body_terminated = false;
     lower_generator_block(
         &body.node.stmts, ctx, &mut body_terminated, yield_counter,
@@ -5011,7 +6190,7 @@ fn lower_generator_for(
     // For simplicity in Phase 1, we only support range-based for loops in generators
     // with yields. The for-loop variable is already pre-declared as a local slot.
 
-    let iter_type = infer_type_for_expr(&iterable.node, ctx.env, &ctx.var_types);
+    let iter_type = infer_type_for_expr(&iterable.node, ctx.env, &ctx.var_types, &ctx.fn_display_name);
 
     match &iter_type {
         PlutoType::Range => {
@@ -5044,7 +6223,7 @@ fn lower_generator_for(
             ctx.builder.switch_to_block(body_bb);
             ctx.builder.seal_block(body_bb);
 
-            ctx.loop_stack.push((header_bb, exit_bb));
+            ctx.loop_stack.push((header_bb, exit_bb, ctx.guard_scopes.len()));
             let mut body_terminated = false;
             lower_generator_block(
                 &body.node.stmts, ctx, &mut body_terminated, yield_counter,
@@ -5114,7 +6293,7 @@ fn lower_generator_for(
             let elem_val = from_array_slot(raw_elem, &elem_type, &mut ctx.builder);
             ctx.builder.def_var(loop_var, elem_val);
 
-            ctx.loop_stack.push((header_bb, exit_bb));
+            ctx.loop_stack.push((header_bb, exit_bb, ctx.guard_scopes.len()));
             let mut body_terminated = false;
             lower_generator_block(
                 &body.node.stmts, ctx, &mut body_terminated, yield_counter,
@@ -5156,6 +6335,9 @@ pub fn resolve_type_expr_to_pluto(ty: &TypeExpr, env: &TypeEnv) -> PlutoType {
             "string" => PlutoType::String,
             "byte" => PlutoType::Byte,
             "bytes" => PlutoType::Bytes,
+            "WaitGroup" => PlutoType::WaitGroup,
+            "Atomic" => PlutoType::Atomic,
+            "error" => PlutoType::Error,
             _ => {
                 if env.classes.contains_key(name) {
                     PlutoType::Class(name.clone())
@@ -5163,6 +6345,8 @@ pub fn resolve_type_expr_to_pluto(ty: &TypeExpr, env: &TypeEnv) -> PlutoType {
                     PlutoType::Trait(name.clone())
                 } else if env.enums.contains_key(name) {
                     PlutoType::Enum(name.clone())
+                } else if env.errors.contains_key(name) {
+                    PlutoType::Class(name.clone())
                 } else {
                     PlutoType::Void
                 }
@@ -5208,6 +6392,9 @@ pub fn resolve_type_expr_to_pluto(ty: &TypeExpr, env: &TypeEnv) -> PlutoType {
             } else if name == "Receiver" && type_args.len() == 1 {
                 let t = resolve_type_expr_to_pluto(&type_args[0].node, env);
                 PlutoType::Receiver(Box::new(t))
+            } else if name == "Mutex" && type_args.len() == 1 {
+                let t = resolve_type_expr_to_pluto(&type_args[0].node, env);
+                PlutoType::Mutex(Box::new(t))
             } else {
                 panic!("Generic TypeExpr should not reach codegen — monomorphize should have resolved it")
             }
@@ -5228,7 +6415,8 @@ fn infer_type_for_expr_if(
     then_block: &crate::span::Spanned<crate::parser::ast::Block>,
     else_block: &crate::span::Spanned<crate::parser::ast::Block>,
     env: &TypeEnv,
-    var_types: &HashMap<String, PlutoType>
+    var_types: &HashMap<String, PlutoType>,
+    current_fn: &str,
 ) -> PlutoType {
     use crate::parser::ast::Stmt;
 
@@ -5236,11 +6424,11 @@ fn infer_type_for_expr_if(
     if let Some(last) = then_block.node.stmts.last() {
         match &last.node {
             Stmt::Expr(expr) => {
-                return infer_type_for_expr(&expr.node, env, var_types);
+                return infer_type_for_expr(&expr.node, env, var_types, current_fn);
             }
             Stmt::If { then_block: inner_then, else_block: Some(inner_else), .. } => {
                 // Nested if-statement with else acts as an expression
-                return infer_type_for_expr_if(inner_then, inner_else, env, var_types);
+                return infer_type_for_expr_if(inner_then, inner_else, env, var_types, current_fn);
             }
             _ => {}
         }
@@ -5250,11 +6438,11 @@ fn infer_type_for_expr_if(
     if let Some(last) = else_block.node.stmts.last() {
         match &last.node {
             Stmt::Expr(expr) => {
-                return infer_type_for_expr(&expr.node, env, var_types);
+                return infer_type_for_expr(&expr.node, env, var_types, current_fn);
             }
             Stmt::If { then_block: inner_then, else_block: Some(inner_else), .. } => {
                 // Nested if-statement with else acts as an expression
-                return infer_type_for_expr_if(inner_then, inner_else, env, var_types);
+                return infer_type_for_expr_if(inner_then, inner_else, env, var_types, current_fn);
             }
             _ => {}
         }
@@ -5269,12 +6457,13 @@ fn infer_type_for_expr_match(
     arms: &[crate::parser::ast::MatchExprArm],
     scrutinee: &Expr,
     env: &TypeEnv,
-    var_types: &HashMap<String, PlutoType>
+    var_types: &HashMap<String, PlutoType>,
+    current_fn: &str,
 ) -> PlutoType {
     // Infer from the first arm's value expression (typeck guarantees all arms have same type)
     if let Some(arm) = arms.first() {
         // Get scrutinee type to find enum info
-        let scrutinee_type = infer_type_for_expr(scrutinee, env, var_types);
+        let scrutinee_type = infer_type_for_expr(scrutinee, env, var_types, current_fn);
         let enum_name = match &scrutinee_type {
             PlutoType::Enum(name) => name,
             _ => return PlutoType::Void,
@@ -5296,7 +6485,7 @@ fn infer_type_for_expr_match(
                         temp_var_types.insert(var_name.clone(), field_type.clone());
                     }
                 }
-                return infer_type_for_expr(&arm.value.node, env, &temp_var_types);
+                return infer_type_for_expr(&arm.value.node, env, &temp_var_types, current_fn);
             }
         }
     }
@@ -5307,12 +6496,13 @@ fn infer_type_for_expr_match(
 
 /// Whether a type needs deep-copying at spawn sites.
 /// Heap-allocated mutable types need copying; primitives, immutable strings,
-/// and shared-by-reference types (tasks, channels) do not.
+/// and shared-by-reference types (tasks, channels, wait groups, atomics, mutexes) do not.
 fn needs_deep_copy(ty: &PlutoType) -> bool {
     match ty {
         PlutoType::Int | PlutoType::Float | PlutoType::Bool | PlutoType::Byte
         | PlutoType::Void | PlutoType::Range | PlutoType::String
-        | PlutoType::Sender(_) | PlutoType::Receiver(_) | PlutoType::Task(_)
+        | PlutoType::Sender(_) | PlutoType::Receiver(_) | PlutoType::Task(_) | PlutoType::WaitGroup | PlutoType::Atomic
+        | PlutoType::Mutex(_) | PlutoType::MutexGuard(_)
         | PlutoType::Error | PlutoType::TypeParam(_) | PlutoType::GenericInstance(..) => false,
         PlutoType::Class(_) | PlutoType::Array(_) | PlutoType::Map(..)
         | PlutoType::Set(_) | PlutoType::Enum(_) | PlutoType::Bytes
@@ -5364,6 +6554,10 @@ pub fn pluto_to_cranelift(ty: &PlutoType) -> types::Type {
         PlutoType::Bytes => types::I64,        // pointer to bytes handle
         PlutoType::Nullable(_) => types::I64,   // pointer (0 = none)
         PlutoType::Stream(_) => types::I64,    // pointer to generator object
+        PlutoType::WaitGroup => types::I64,    // pointer to wait group handle
+        PlutoType::Atomic => types::I64,       // pointer to atomic handle
+        PlutoType::Mutex(_) => types::I64,     // pointer to mutex handle
+        PlutoType::MutexGuard(_) => types::I64, // same pointer as the locked mutex
         PlutoType::GenericInstance(_, name, _) => panic!("ICE: generic instance '{name}' reached codegen unresolved"),
     }
 }
@@ -5383,28 +6577,33 @@ fn key_type_tag(ty: &PlutoType) -> i64 {
 }
 
 /// Quick type inference at codegen time (type checker has already validated).
-fn infer_type_for_expr(expr: &Expr, env: &TypeEnv, var_types: &HashMap<String, PlutoType>) -> PlutoType {
+fn infer_type_for_expr(expr: &Expr, env: &TypeEnv, var_types: &HashMap<String, PlutoType>, current_fn: &str) -> PlutoType {
     match expr {
         Expr::IntLit(_) => PlutoType::Int,
         Expr::FloatLit(_) => PlutoType::Float,
         Expr::BoolLit(_) => PlutoType::Bool,
         Expr::StringLit(_) => PlutoType::String,
         Expr::StringInterp { .. } => PlutoType::String,
-        Expr::Ident(name) => var_types.get(name).cloned().unwrap_or(PlutoType::Void),
+        Expr::Ident(name) => var_types
+            .get(name)
+            .or_else(|| env.consts.get(name).map(|c| &c.ty))
+            .or_else(|| env.globals.get(name).map(|g| &g.ty))
+            .cloned()
+            .unwrap_or(PlutoType::Void),
         Expr::BinOp { op, lhs, .. } => {
             match op {
                 BinOp::Eq | BinOp::Neq | BinOp::Lt | BinOp::Gt | BinOp::LtEq | BinOp::GtEq | BinOp::And | BinOp::Or => PlutoType::Bool,
-                _ => infer_type_for_expr(&lhs.node, env, var_types),
+                _ => infer_type_for_expr(&lhs.node, env, var_types, current_fn),
             }
         }
         Expr::UnaryOp { op, operand } => {
             match op {
                 UnaryOp::Not => PlutoType::Bool,
-                UnaryOp::BitNot => PlutoType::Int,
-                UnaryOp::Neg => infer_type_for_expr(&operand.node, env, var_types),
+                UnaryOp::BitNot | UnaryOp::Neg => infer_type_for_expr(&operand.node, env, var_types, current_fn),
             }
         }
         Expr::Cast { target_type, .. } => resolve_type_expr_to_pluto(&target_type.node, env),
+        Expr::Is { .. } => PlutoType::Bool,
         Expr::Call { name, args, .. } => {
             // Check if calling a closure variable first
             if let Some(PlutoType::Fn(_, ret)) = var_types.get(&name.node) {
@@ -5412,16 +6611,21 @@ fn infer_type_for_expr(expr: &Expr, env: &TypeEnv, var_types: &HashMap<String, P
             }
             // Check builtins
             if name.node == "expect" && !args.is_empty() {
-                return infer_type_for_expr(&args[0].node, env, var_types);
+                return infer_type_for_expr(&args[0].node, env, var_types, current_fn);
             }
             if name.node == "print" {
                 return PlutoType::Void;
             }
-            if name.node == "time_ns" {
+            if name.node == "unreachable" {
+                return env.unreachable_types.get(&(env.body_owner(current_fn).to_string(), name.span))
+                    .cloned()
+                    .unwrap_or(PlutoType::Void);
+            }
+            if name.node == "time_ns" || name.node == "num_cpus" {
                 return PlutoType::Int;
             }
             if name.node == "abs" || name.node == "min" || name.node == "max" || name.node == "pow" {
-                return infer_type_for_expr(&args[0].node, env, var_types);
+                return infer_type_for_expr(&args[0].node, env, var_types, current_fn);
             }
             if matches!(
                 name.node.as_str(),
@@ -5432,14 +6636,39 @@ fn infer_type_for_expr(expr: &Expr, env: &TypeEnv, var_types: &HashMap<String, P
             if name.node == "gc_heap_size" {
                 return PlutoType::Int;
             }
+            if name.node == "gc_collect" {
+                return PlutoType::Void;
+            }
+            if name.node == "gc_stats" {
+                return PlutoType::Class("GcStats".to_string());
+            }
             if name.node == "bytes_new" {
                 return PlutoType::Bytes;
             }
+            if name.node == "args" {
+                return PlutoType::Array(Box::new(PlutoType::String));
+            }
+            if name.node == "read_line" {
+                return PlutoType::Nullable(Box::new(PlutoType::String));
+            }
+            if name.node == "read_all" {
+                return PlutoType::String;
+            }
+            if name.node == "wait_group" {
+                return PlutoType::WaitGroup;
+            }
+            if name.node == "atomic" {
+                return PlutoType::Atomic;
+            }
+            if name.node == "mutex" && args.len() == 1 {
+                let inner = infer_type_for_expr(&args[0].node, env, var_types, current_fn);
+                return PlutoType::Mutex(Box::new(inner));
+            }
             env.functions.get(&name.node).map(|s| s.return_type.clone()).unwrap_or(PlutoType::Void)
         }
         Expr::StructLit { name, .. } => PlutoType::Class(name.node.clone()),
         Expr::FieldAccess { object, field } => {
-            let obj_type = infer_type_for_expr(&object.node, env, var_types);
+            let obj_type = infer_type_for_expr(&object.node, env, var_types, current_fn);
             if let PlutoType::Class(class_name) = &obj_type {
                 if let Some(class_info) = env.classes.get(class_name) {
                     class_info.fields.iter()
@@ -5455,6 +6684,12 @@ fn infer_type_for_expr(expr: &Expr, env: &TypeEnv, var_types: &HashMap<String, P
                 } else {
                     PlutoType::Void
                 }
+            } else if let PlutoType::Enum(enum_name) = &obj_type {
+                env.variant_field_accesses.get(&(env.body_owner(current_fn).to_string(), field.span))
+                    .and_then(|variant| env.enums.get(enum_name)?.variants.iter().find(|(n, _)| n == variant))
+                    .and_then(|(_, fields)| fields.iter().find(|(n, _)| *n == field.node))
+                    .map(|(_, t)| t.clone())
+                    .unwrap_or(PlutoType::Void)
             } else if obj_type == PlutoType::Error && field.node == "message" {
                 PlutoType::String
             } else {
@@ -5466,12 +6701,15 @@ fn infer_type_for_expr(expr: &Expr, env: &TypeEnv, var_types: &HashMap<String, P
                 // Empty array — type comes from context (var_types), default to Void
                 PlutoType::Array(Box::new(PlutoType::Void))
             } else {
-                let first = infer_type_for_expr(&elements[0].node, env, var_types);
+                let first = infer_type_for_expr(&elements[0].node, env, var_types, current_fn);
                 PlutoType::Array(Box::new(first))
             }
         }
-        Expr::Index { object, .. } => {
-            let obj_type = infer_type_for_expr(&object.node, env, var_types);
+        Expr::Index { object, index } => {
+            let obj_type = infer_type_for_expr(&object.node, env, var_types, current_fn);
+            if matches!(index.node, Expr::Range { .. }) {
+                return obj_type;
+            }
             if let PlutoType::Array(elem) = obj_type {
                 *elem
             } else if let PlutoType::Map(_, v) = obj_type {
@@ -5496,13 +6734,13 @@ fn infer_type_for_expr(expr: &Expr, env: &TypeEnv, var_types: &HashMap<String, P
         Expr::EnumUnit { enum_name, .. } | Expr::EnumData { enum_name, .. } => {
             PlutoType::Enum(enum_name.node.clone())
         }
-        Expr::Propagate { expr } => {
+        Expr::Propagate { expr } | Expr::PropagateMap { expr, .. } => {
             // Propagation returns the success type of the inner call
-            infer_type_for_expr(&expr.node, env, var_types)
+            infer_type_for_expr(&expr.node, env, var_types, current_fn)
         }
         Expr::Catch { expr, .. } => {
             // Catch returns the success type (same as the inner call)
-            infer_type_for_expr(&expr.node, env, var_types)
+            infer_type_for_expr(&expr.node, env, var_types, current_fn)
         }
         Expr::MethodCall { object, method, .. } => {
             // expect() intrinsic methods always return Void
@@ -5511,14 +6749,16 @@ fn infer_type_for_expr(expr: &Expr, env: &TypeEnv, var_types: &HashMap<String, P
             {
                 return PlutoType::Void;
             }
-            let obj_type = infer_type_for_expr(&object.node, env, var_types);
+            let obj_type = infer_type_for_expr(&object.node, env, var_types, current_fn);
             if let PlutoType::Array(elem) = &obj_type {
                 return match method.node.as_str() {
                     "len" | "index_of" => PlutoType::Int,
                     "pop" | "last" | "first" | "remove_at" => (**elem).clone(),
                     "is_empty" | "contains" => PlutoType::Bool,
                     "slice" => PlutoType::Array(elem.clone()),
-                    _ => PlutoType::Void, // push, clear, insert_at, reverse
+                    "join" => PlutoType::String,
+                    "map_to_string" => PlutoType::Array(Box::new(PlutoType::String)),
+                    _ => PlutoType::Void, // push, clear, insert_at, reverse, sort, sort_by
                 };
             }
             if let PlutoType::Map(key_ty, val_ty) = &obj_type {
@@ -5548,24 +6788,45 @@ fn infer_type_for_expr(expr: &Expr, env: &TypeEnv, var_types: &HashMap<String, P
                 return match method.node.as_str() {
                     "len" => PlutoType::Int,
                     "to_string" => PlutoType::String,
-                    _ => PlutoType::Void, // push
+                    _ => PlutoType::Void, // push, push_str
                 };
             }
             if let PlutoType::Sender(_) = &obj_type {
-                return PlutoType::Void; // send/try_send/close all return void
+                return match method.node.as_str() {
+                    "is_full" => PlutoType::Bool,
+                    _ => PlutoType::Void, // send/try_send/close
+                };
             }
             if let PlutoType::Receiver(inner) = &obj_type {
                 return match method.node.as_str() {
-                    "recv" | "try_recv" => *inner.clone(),
+                    "recv" | "try_recv" | "recv_timeout" => *inner.clone(),
+                    "len" => PlutoType::Int,
                     _ => PlutoType::Void,
                 };
             }
+            if obj_type == PlutoType::Atomic {
+                return match method.node.as_str() {
+                    "load" | "add" => PlutoType::Int,
+                    "compare_and_swap" => PlutoType::Bool,
+                    _ => PlutoType::Void, // store
+                };
+            }
+            if let PlutoType::Mutex(inner) = &obj_type {
+                return PlutoType::MutexGuard(inner.clone());
+            }
+            if let PlutoType::MutexGuard(inner) = &obj_type {
+                return match method.node.as_str() {
+                    "get" => (**inner).clone(),
+                    _ => PlutoType::Void, // set
+                };
+            }
             if obj_type == PlutoType::String {
                 return match method.node.as_str() {
-                    "len" | "index_of" | "last_index_of" | "count" | "byte_at" => PlutoType::Int,
+                    "len" | "char_count" | "index_of" | "last_index_of" | "count" | "byte_at" => PlutoType::Int,
                     "contains" | "starts_with" | "ends_with" | "is_empty" | "is_whitespace" => PlutoType::Bool,
                     "substring" | "trim" | "to_upper" | "to_lower" | "replace" | "char_at" | "trim_start" | "trim_end" | "repeat" => PlutoType::String,
-                    "split" => PlutoType::Array(Box::new(PlutoType::String)),
+                    "split" | "chars" | "lines" => PlutoType::Array(Box::new(PlutoType::String)),
+                    "bytes" => PlutoType::Array(Box::new(PlutoType::Byte)),
                     "to_bytes" => PlutoType::Bytes,
                     "to_int" => PlutoType::Nullable(Box::new(PlutoType::Int)),
                     "to_float" => PlutoType::Nullable(Box::new(PlutoType::Float)),
@@ -5581,7 +6842,7 @@ fn infer_type_for_expr(expr: &Expr, env: &TypeEnv, var_types: &HashMap<String, P
                 }
                 return PlutoType::Void;
             }
-            if let PlutoType::Class(class_name) = &obj_type {
+            if let PlutoType::Class(class_name) | PlutoType::Enum(class_name) = &obj_type {
                 let mangled = mangle_method(class_name, &method.node);
                 env.functions.get(&mangled).map(|s| s.return_type.clone()).unwrap_or(PlutoType::Void)
             } else {
@@ -5598,6 +6859,8 @@ fn infer_type_for_expr(expr: &Expr, env: &TypeEnv, var_types: &HashMap<String, P
             };
             PlutoType::Fn(param_types, Box::new(ret))
         }
+        // Lifted to ClosureCreate before codegen
+        Expr::MethodRef { .. } => PlutoType::Void,
         Expr::ClosureCreate { fn_name, .. } => {
             if let Some(sig) = env.functions.get(fn_name) {
                 // Return fn type: skip the __env param (first param)
@@ -5608,7 +6871,7 @@ fn infer_type_for_expr(expr: &Expr, env: &TypeEnv, var_types: &HashMap<String, P
             }
         }
         Expr::Spawn { call } => {
-            let closure_type = infer_type_for_expr(&call.node, env, var_types);
+            let closure_type = infer_type_for_expr(&call.node, env, var_types, current_fn);
             match closure_type {
                 PlutoType::Fn(_, ret) => PlutoType::Task(ret),
                 _ => PlutoType::Void,
@@ -5618,7 +6881,7 @@ fn infer_type_for_expr(expr: &Expr, env: &TypeEnv, var_types: &HashMap<String, P
         Expr::NoneLit => PlutoType::Nullable(Box::new(PlutoType::Void)),
         Expr::NullPropagate { expr } => {
             // The result of `expr?` is the inner type if nullable, otherwise same type
-            let inner = infer_type_for_expr(&expr.node, env, var_types);
+            let inner = infer_type_for_expr(&expr.node, env, var_types, current_fn);
             match inner {
                 PlutoType::Nullable(t) => *t,
                 other => other,
@@ -5636,10 +6899,10 @@ fn infer_type_for_expr(expr: &Expr, env: &TypeEnv, var_types: &HashMap<String, P
         }
         Expr::If { then_block, else_block, .. } => {
             // Use the helper function we already defined
-            infer_type_for_expr_if(then_block, else_block, env, var_types)
+            infer_type_for_expr_if(then_block, else_block, env, var_types, current_fn)
         }
         Expr::Match { expr, arms } => {
-            infer_type_for_expr_match(arms, &expr.node, env, var_types)
+            infer_type_for_expr_match(arms, &expr.node, env, var_types, current_fn)
         }
         Expr::QualifiedAccess { segments } => {
             panic!(
@@ -5939,6 +7202,7 @@ mod tests {
         env.enums.insert("Option".to_string(), crate::typeck::env::EnumInfo {
             variants: vec![],
             variant_type_exprs: vec![],
+            impl_traits: vec![],
         });
 
         env
@@ -6081,6 +7345,7 @@ mod tests {
         env.enums.insert("result.Result".to_string(), crate::typeck::env::EnumInfo {
             variants: vec![],
             variant_type_exprs: vec![],
+            impl_traits: vec![],
         });
 
         let result = resolve_type_expr_to_pluto(
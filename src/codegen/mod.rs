@@ -63,6 +63,32 @@ fn declare_global_data<'a>(
     Ok(globals)
 }
 
+/// Declare a writable 8-byte global for each `global mut`, holding its
+/// constant initial value.
+fn declare_user_globals(env: &TypeEnv, module: &mut ObjectModule) -> Result<HashMap<String, DataId>, CompileError> {
+    let mut names: Vec<&String> = env.globals.keys().collect();
+    names.sort();
+    let mut globals = HashMap::new();
+    for name in names {
+        let bytes = match &env.globals[name].value {
+            Expr::IntLit(n) => n.to_le_bytes(),
+            Expr::FloatLit(f) => f.to_bits().to_le_bytes(),
+            Expr::BoolLit(b) => i64::from(*b).to_le_bytes(),
+            _ => return Err(CompileError::codegen(format!("global '{name}' has a non-scalar initializer"))),
+        };
+        let data_id = module
+            .declare_data(&format!("__pluto_global_{name}"), Linkage::Local, true, false)
+            .map_err(|e| CompileError::codegen(format!("declare global '{name}': {e}")))?;
+        let mut data_desc = DataDescription::new();
+        data_desc.define(Box::new(bytes));
+        module
+            .define_data(data_id, &data_desc)
+            .map_err(|e| CompileError::codegen(format!("define global '{name}': {e}")))?;
+        globals.insert(name.clone(), data_id);
+    }
+    Ok(globals)
+}
+
 /// Extract requires contracts from a contract list into a FnContracts, if any exist.
 fn extract_fn_contracts(contracts: &[Spanned<ContractClause>]) -> Option<FnContracts> {
     let requires: Vec<(Expr, String)> = contracts.iter()
@@ -76,7 +102,7 @@ fn extract_fn_contracts(contracts: &[Spanned<ContractClause>]) -> Option<FnContr
     }
 }
 
-pub fn codegen(program: &Program, env: &TypeEnv, source: &str, coverage_map: Option<&CoverageMap>) -> Result<Vec<u8>, CompileError> {
+pub fn codegen(program: &Program, env: &TypeEnv, source: &str, coverage_map: Option<&CoverageMap>, debug: bool) -> Result<Vec<u8>, CompileError> {
     let mut flag_builder = settings::builder();
     flag_builder.set("is_pic", "true").unwrap();
 
@@ -103,6 +129,9 @@ pub fn codegen(program: &Program, env: &TypeEnv, source: &str, coverage_map: Opt
     // Declare module-level globals for rwlock pointers (Phase 4b)
     let rwlock_data_ids = declare_global_data(env.synchronized_singletons.iter(), "__pluto_rwlock_", &mut module)?;
 
+    // Declare module-level globals for `global mut` variables
+    let user_global_ids = declare_user_globals(env, &mut module)?;
+
     // Pre-pass: collect spawn closure function names (needed before declarations)
     let spawn_closure_fns = collect_spawn_closure_names(program);
 
@@ -368,7 +397,7 @@ pub fn codegen(program: &Program, env: &TypeEnv, source: &str, coverage_map: Opt
             let mut next_builder_ctx = FunctionBuilderContext::new();
             {
                 let builder = cranelift_frontend::FunctionBuilder::new(&mut next_ctx.func, &mut next_builder_ctx);
-                lower_generator_next(f, builder, env, &mut module, &func_ids, &runtime, &vtable_ids, source, &class_invariants, &fn_contracts, &singleton_data_ids, &rwlock_data_ids, &coverage_lookup)?;
+                lower_generator_next(f, builder, env, &mut module, &func_ids, &runtime, &vtable_ids, source, &class_invariants, &fn_contracts, &singleton_data_ids, &rwlock_data_ids, &user_global_ids, &coverage_lookup)?;
             }
             module
                 .define_function(next_id, &mut next_ctx)
@@ -388,7 +417,7 @@ pub fn codegen(program: &Program, env: &TypeEnv, source: &str, coverage_map: Opt
             let mut builder_ctx = FunctionBuilderContext::new();
             {
                 let builder = cranelift_frontend::FunctionBuilder::new(&mut fn_ctx.func, &mut builder_ctx);
-                lower_function(f, builder, env, &mut module, &func_ids, &runtime, None, &vtable_ids, source, &spawn_closure_fns, &class_invariants, &fn_contracts, &singleton_data_ids, &rwlock_data_ids, &coverage_lookup)?;
+                lower_function(f, builder, env, &mut module, &func_ids, &runtime, None, &vtable_ids, source, &spawn_closure_fns, &class_invariants, &fn_contracts, &singleton_data_ids, &rwlock_data_ids, &user_global_ids, &coverage_lookup, debug)?;
             }
 
             module
@@ -412,7 +441,7 @@ pub fn codegen(program: &Program, env: &TypeEnv, source: &str, coverage_map: Opt
             let mut builder_ctx = FunctionBuilderContext::new();
             {
                 let builder = cranelift_frontend::FunctionBuilder::new(&mut fn_ctx.func, &mut builder_ctx);
-                lower_function(m, builder, env, &mut module, &func_ids, &runtime, Some(&c.name.node), &vtable_ids, source, &spawn_closure_fns, &class_invariants, &fn_contracts, &singleton_data_ids, &rwlock_data_ids, &coverage_lookup)?;
+                lower_function(m, builder, env, &mut module, &func_ids, &runtime, Some(&c.name.node), &vtable_ids, source, &spawn_closure_fns, &class_invariants, &fn_contracts, &singleton_data_ids, &rwlock_data_ids, &user_global_ids, &coverage_lookup, debug)?;
             }
 
             module
@@ -445,9 +474,11 @@ pub fn codegen(program: &Program, env: &TypeEnv, source: &str, coverage_map: Opt
                                 return_type: trait_method.return_type.clone(),
                                 contracts: trait_method.contracts.clone(),
                                 body: body.clone(),
-                                is_pub: false,
+                                visibility: Visibility::Private,
                                 is_override: false,
                                 is_generator: false,
+                                declared_errors: None,
+                                no_coverage: false,
                             };
 
                             let mangled = mangle_method(class_name, &trait_method.name.node);
@@ -469,7 +500,7 @@ pub fn codegen(program: &Program, env: &TypeEnv, source: &str, coverage_map: Opt
                             let mut builder_ctx = FunctionBuilderContext::new();
                             {
                                 let builder = cranelift_frontend::FunctionBuilder::new(&mut fn_ctx.func, &mut builder_ctx);
-                                lower_function(&tmp_func, builder, env, &mut module, &func_ids, &runtime, Some(class_name), &vtable_ids, source, &spawn_closure_fns, &class_invariants, &fn_contracts, &singleton_data_ids, &rwlock_data_ids, &coverage_lookup)?;
+                                lower_function(&tmp_func, builder, env, &mut module, &func_ids, &runtime, Some(class_name), &vtable_ids, source, &spawn_closure_fns, &class_invariants, &fn_contracts, &singleton_data_ids, &rwlock_data_ids, &user_global_ids, &coverage_lookup, debug)?;
                             }
 
                             module
@@ -528,7 +559,7 @@ pub fn codegen(program: &Program, env: &TypeEnv, source: &str, coverage_map: Opt
             let mut builder_ctx = FunctionBuilderContext::new();
             {
                 let builder = cranelift_frontend::FunctionBuilder::new(&mut fn_ctx.func, &mut builder_ctx);
-                lower_function(m, builder, env, &mut module, &func_ids, &runtime, Some(app_name), &vtable_ids, source, &spawn_closure_fns, &class_invariants, &fn_contracts, &singleton_data_ids, &rwlock_data_ids, &coverage_lookup)?;
+                lower_function(m, builder, env, &mut module, &func_ids, &runtime, Some(app_name), &vtable_ids, source, &spawn_closure_fns, &class_invariants, &fn_contracts, &singleton_data_ids, &rwlock_data_ids, &user_global_ids, &coverage_lookup, debug)?;
             }
 
             module
@@ -553,7 +584,7 @@ pub fn codegen(program: &Program, env: &TypeEnv, source: &str, coverage_map: Opt
             let mut builder_ctx = FunctionBuilderContext::new();
             {
                 let builder = cranelift_frontend::FunctionBuilder::new(&mut fn_ctx.func, &mut builder_ctx);
-                lower_function(m, builder, env, &mut module, &func_ids, &runtime, Some(stage_name), &vtable_ids, source, &spawn_closure_fns, &class_invariants, &fn_contracts, &singleton_data_ids, &rwlock_data_ids, &coverage_lookup)?;
+                lower_function(m, builder, env, &mut module, &func_ids, &runtime, Some(stage_name), &vtable_ids, source, &spawn_closure_fns, &class_invariants, &fn_contracts, &singleton_data_ids, &rwlock_data_ids, &user_global_ids, &coverage_lookup, debug)?;
             }
 
             module
@@ -807,6 +838,11 @@ pub fn codegen(program: &Program, env: &TypeEnv, source: &str, coverage_map: Opt
             let app_main_ref = module.declare_func_in_func(*app_main_id, builder.func);
             builder.ins().call(app_main_ref, &[app_ptr]);
 
+            if debug {
+                let report_ref = module.declare_func_in_func(runtime.get("__pluto_report_uncaught_error"), builder.func);
+                builder.ins().call(report_ref, &[]);
+            }
+
             // Return 0
             let zero = builder.ins().iconst(types::I64, 0);
             builder.ins().return_(&[zero]);
@@ -947,6 +983,11 @@ pub fn codegen(program: &Program, env: &TypeEnv, source: &str, coverage_map: Opt
             let stage_main_ref = module.declare_func_in_func(*stage_main_id, builder.func);
             builder.ins().call(stage_main_ref, &[stage_ptr]);
 
+            if debug {
+                let report_ref = module.declare_func_in_func(runtime.get("__pluto_report_uncaught_error"), builder.func);
+                builder.ins().call(report_ref, &[]);
+            }
+
             // Return 0
             let zero = builder.ins().iconst(types::I64, 0);
             builder.ins().return_(&[zero]);
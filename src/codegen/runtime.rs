@@ -1,21 +1,26 @@
 use std::collections::HashMap;
 
 use cranelift_codegen::ir::{types, AbiParam};
-use cranelift_module::{FuncId, Linkage, Module};
+use cranelift_module::{DataId, FuncId, Linkage, Module};
 
 use crate::diagnostics::CompileError;
 
 /// Registry of runtime (builtins.c) functions declared in the Cranelift module.
 pub struct RuntimeRegistry {
     ids: HashMap<&'static str, FuncId>,
+    safepoint_flag: DataId,
 }
 
 impl RuntimeRegistry {
     /// Declare all runtime functions in the module. Each entry specifies raw Cranelift
     /// types for parameters and returns, preserving exact C ABI compatibility.
     pub fn new(module: &mut dyn Module) -> Result<Self, CompileError> {
+        let safepoint_flag = module
+            .declare_data("__pluto_safepoint_requested", Linkage::Import, true, false)
+            .map_err(|e| CompileError::codegen(format!("declare __pluto_safepoint_requested error: {e}")))?;
         let mut reg = RuntimeRegistry {
             ids: HashMap::new(),
+            safepoint_flag,
         };
 
         // Print functions
@@ -38,12 +43,18 @@ impl RuntimeRegistry {
         reg.declare(module, "__pluto_string_ends_with", &[types::I64, types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_string_index_of", &[types::I64, types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_string_substring", &[types::I64, types::I64, types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_slice_check", &[types::I64, types::I64, types::I64, types::I64], &[])?;
         reg.declare(module, "__pluto_string_trim", &[types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_string_to_upper", &[types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_string_to_lower", &[types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_string_replace", &[types::I64, types::I64, types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_string_split", &[types::I64, types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_string_chars", &[types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_string_lines", &[types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_string_bytes", &[types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_string_char_at", &[types::I64, types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_string_char_at_utf8", &[types::I64, types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_string_char_count", &[types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_string_byte_at", &[types::I64, types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_int_to_string", &[types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_float_to_string", &[types::F64], &[types::I64])?;
@@ -86,6 +97,12 @@ impl RuntimeRegistry {
         reg.declare(module, "__pluto_clear_error", &[], &[])?;
         reg.declare(module, "__pluto_set_error_type", &[types::I64], &[])?;
         reg.declare(module, "__pluto_error_type", &[], &[types::I64])?;
+        reg.declare(module, "__pluto_restore_error", &[types::I64, types::I64], &[])?;
+
+        // Debug stack traces (`--debug`)
+        reg.declare(module, "__pluto_push_frame", &[types::I64], &[])?;
+        reg.declare(module, "__pluto_pop_frame", &[], &[])?;
+        reg.declare(module, "__pluto_report_uncaught_error", &[], &[])?;
 
         // Time
         reg.declare(module, "__pluto_time_ns", &[], &[types::I64])?;
@@ -98,6 +115,9 @@ impl RuntimeRegistry {
         reg.declare(module, "__pluto_random_float", &[], &[types::F64])?;
 
         // Environment variables
+        reg.declare(module, "__pluto_args", &[], &[types::I64])?;
+        reg.declare(module, "__pluto_read_line", &[], &[types::I64])?;
+        reg.declare(module, "__pluto_read_all", &[], &[types::I64])?;
         reg.declare(module, "__pluto_env_get", &[types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_env_get_or", &[types::I64, types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_env_set", &[types::I64, types::I64], &[])?;
@@ -139,10 +159,14 @@ impl RuntimeRegistry {
         reg.declare(module, "__pluto_array_reverse", &[types::I64], &[])?;
         reg.declare(module, "__pluto_array_contains", &[types::I64, types::I64, types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_array_index_of", &[types::I64, types::I64, types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_array_sort", &[types::I64, types::I64, types::I64, types::I64], &[])?;
+        reg.declare(module, "__pluto_array_join", &[types::I64, types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_array_map_to_string", &[types::I64, types::I64], &[types::I64])?;
 
         // Bytes functions
         reg.declare(module, "__pluto_bytes_new", &[], &[types::I64])?;
         reg.declare(module, "__pluto_bytes_push", &[types::I64, types::I64], &[])?;
+        reg.declare(module, "__pluto_bytes_push_str", &[types::I64, types::I64], &[])?;
         reg.declare(module, "__pluto_bytes_get", &[types::I64, types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_bytes_set", &[types::I64, types::I64, types::I64], &[])?;
         reg.declare(module, "__pluto_bytes_len", &[types::I64], &[types::I64])?;
@@ -170,13 +194,18 @@ impl RuntimeRegistry {
         // GC
         reg.declare(module, "__pluto_gc_init", &[], &[])?;
         reg.declare(module, "__pluto_gc_heap_size", &[], &[types::I64])?;
+        reg.declare(module, "__pluto_gc_stats", &[], &[types::I64])?;
+        reg.declare(module, "__pluto_gc_force_collect", &[], &[])?;
         reg.declare(module, "__pluto_safepoint", &[], &[])?;
 
         // Concurrency
         reg.declare(module, "__pluto_task_spawn", &[types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_task_get", &[types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_task_get_infallible", &[types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_task_detach", &[types::I64], &[])?;
         reg.declare(module, "__pluto_task_cancel", &[types::I64], &[])?;
+        reg.declare(module, "__pluto_task_unwinding", &[], &[types::I64])?;
+        reg.declare(module, "__pluto_num_cpus", &[], &[types::I64])?;
         reg.declare(module, "__pluto_deep_copy", &[types::I64], &[types::I64])?;
 
         // Rwlock synchronization
@@ -191,7 +220,22 @@ impl RuntimeRegistry {
         reg.declare(module, "__pluto_chan_recv", &[types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_chan_try_send", &[types::I64, types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_chan_try_recv", &[types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_chan_recv_timeout", &[types::I64, types::I64], &[types::I64])?;
         reg.declare(module, "__pluto_chan_close", &[types::I64], &[])?;
+        reg.declare(module, "__pluto_chan_len", &[types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_chan_is_full", &[types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_waitgroup_new", &[], &[types::I64])?;
+        reg.declare(module, "__pluto_waitgroup_add", &[types::I64, types::I64], &[])?;
+        reg.declare(module, "__pluto_waitgroup_done", &[types::I64], &[])?;
+        reg.declare(module, "__pluto_waitgroup_wait", &[types::I64], &[])?;
+        reg.declare(module, "__pluto_atomic_new", &[types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_atomic_load", &[types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_atomic_store", &[types::I64, types::I64], &[])?;
+        reg.declare(module, "__pluto_atomic_add", &[types::I64, types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_atomic_compare_and_swap", &[types::I64, types::I64, types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_mutex_new", &[types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_mutex_lock", &[types::I64], &[types::I64])?;
+        reg.declare(module, "__pluto_mutex_unlock", &[types::I64], &[])?;
         reg.declare(module, "__pluto_chan_sender_inc", &[types::I64], &[])?;
         reg.declare(module, "__pluto_chan_sender_dec", &[types::I64], &[])?;
         reg.declare(module, "__pluto_select", &[types::I64, types::I64, types::I64], &[types::I64])?;
@@ -200,6 +244,7 @@ impl RuntimeRegistry {
         reg.declare(module, "__pluto_invariant_violation", &[types::I64, types::I64], &[])?;
         reg.declare(module, "__pluto_requires_violation", &[types::I64, types::I64], &[])?;
         reg.declare(module, "__pluto_assert_failure", &[types::I64], &[])?;
+        reg.declare(module, "__pluto_unreachable", &[types::I64], &[])?;
 
         // Test framework
         reg.declare(module, "__pluto_expect_equal_int", &[types::I64, types::I64, types::I64], &[])?;
@@ -231,6 +276,11 @@ impl RuntimeRegistry {
         self.ids[name]
     }
 
+    /// The GC's `__pluto_safepoint_requested` flag (a C `atomic_int`).
+    pub fn safepoint_flag(&self) -> DataId {
+        self.safepoint_flag
+    }
+
     fn declare(
         &mut self,
         module: &mut dyn Module,
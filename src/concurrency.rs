@@ -43,6 +43,7 @@ pub fn infer_synchronization(program: &Program, env: &mut TypeEnv) {
         if !class.node.type_params.is_empty() { continue; }
         let class_name = &class.node.name.node;
         for method in &class.node.methods {
+            if !method.node.type_params.is_empty() { continue; }
             let mangled = mangle_method(class_name, &method.node.name.node);
             let (accesses, edges) = collect_block_accesses(&method.node.body.node, &mangled, env, &di_singletons);
             singleton_accesses.entry(mangled.clone()).or_default().extend(accesses);
@@ -80,6 +80,11 @@ fn validate_decidable_fragment(expr: &Expr, span: Span, kind: ContractKind) -> R
             validate_decidable_fragment(&object.node, object.span, kind)
         }
 
+        // Variant test — allowed (recurse into operand)
+        Expr::Is { expr: inner, .. } => {
+            validate_decidable_fragment(&inner.node, inner.span, kind)
+        }
+
         // Method call — only .len() with no args
         Expr::MethodCall { object, method, args } => {
             if method.node == "len" && args.is_empty() {
@@ -147,6 +152,10 @@ fn validate_decidable_fragment(expr: &Expr, span: Span, kind: ContractKind) -> R
             "closures are not allowed in contract expressions",
             span,
         )),
+        Expr::MethodRef { .. } => Err(CompileError::syntax(
+            "method references are not allowed in contract expressions",
+            span,
+        )),
         Expr::Spawn { .. } => Err(CompileError::syntax(
             "spawn is not allowed in contract expressions",
             span,
@@ -163,7 +172,7 @@ fn validate_decidable_fragment(expr: &Expr, span: Span, kind: ContractKind) -> R
             "range expressions are not allowed in contract expressions",
             span,
         )),
-        Expr::Propagate { .. } => Err(CompileError::syntax(
+        Expr::Propagate { .. } | Expr::PropagateMap { .. } => Err(CompileError::syntax(
             "error propagation is not allowed in contract expressions",
             span,
         )),
@@ -9,7 +9,7 @@
 //! Coverage is keyed by `span.start` byte offset — the scanner and codegen
 //! both use this value to match points, avoiding iteration-order coupling.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
@@ -39,6 +39,12 @@ pub enum CoverageKind {
     // Branch coverage: error propagation (!)
     ErrorPropError,
     ErrorPropSuccess,
+    // Branch coverage: select arms and the default block
+    SelectArm { index: u32 },
+    SelectDefault,
+    // Branch coverage: `&&`/`||` decided by the left operand alone, or by the right
+    ShortCircuitLeft,
+    ShortCircuitRight,
 }
 
 impl CoverageKind {
@@ -71,6 +77,9 @@ pub struct CoveragePoint {
 pub struct CoverageFile {
     pub id: u32,
     pub path: String,
+    /// Lines excluded from instrumentation by `@no_coverage` or `// coverage: ignore`.
+    #[serde(default)]
+    pub excluded_lines: Vec<u32>,
 }
 
 /// The complete coverage map — static metadata produced at compile time.
@@ -187,38 +196,52 @@ impl LineIndex {
 
 /// Scans a Program AST and produces a CoverageMap with one point per statement
 /// and one per function entry. Supports multiple files via SourceMap.
+///
+/// Functions marked `@no_coverage` and statements annotated with a
+/// `// coverage: ignore` comment get no points, so codegen emits no probes for
+/// them and they don't count towards any total. A trailing comment excludes the
+/// statement it ends; a comment on its own line excludes the statement below it.
 pub fn build_coverage_map(
     program: &Program,
     source_map: &SourceMap,
 ) -> CoverageMap {
-    // Build per-file LineIndex and source_len maps
+    // Build per-file LineIndex, source_len, and ignore-directive maps
     let mut line_indexes: HashMap<u32, LineIndex> = HashMap::new();
     let mut source_lens: HashMap<u32, usize> = HashMap::new();
+    let mut ignored_lines: HashMap<u32, HashSet<u32>> = HashMap::new();
     let mut files: Vec<CoverageFile> = Vec::new();
 
     for (file_id, (path, source)) in source_map.files.iter().enumerate() {
         let file_id = file_id as u32;
-        line_indexes.insert(file_id, LineIndex::new(source));
+        let line_index = LineIndex::new(source);
+        ignored_lines.insert(file_id, ignore_directive_lines(source, &line_index));
+        line_indexes.insert(file_id, line_index);
         source_lens.insert(file_id, source.len());
         files.push(CoverageFile {
             id: file_id,
             path: path.display().to_string(),
+            excluded_lines: Vec::new(),
         });
     }
 
     let mut scanner = CoverageScanner {
         points: Vec::new(),
+        excluded: Vec::new(),
         line_indexes: &line_indexes,
         source_lens: &source_lens,
+        ignored_lines: &ignored_lines,
         current_function: String::new(),
         current_file_id: 0,
     };
 
-    // Scan top-level functions from ALL files
+    // Scan top-level functions from ALL files. A lifted closure keeps the span
+    // of the closure expression, which places it inside its enclosing function.
+    let mut function_spans: HashMap<&str, (u32, Span)> = HashMap::new();
     for func in &program.functions {
         scanner.current_file_id = func.span.file_id;
         if !scanner.source_lens.contains_key(&scanner.current_file_id) { continue; }
         scanner.scan_function(&func.node);
+        function_spans.insert(func.node.name.node.as_str(), (func.span.file_id, func.span));
     }
 
     // Scan class methods from ALL files
@@ -252,16 +275,68 @@ pub fn build_coverage_map(
         }
     }
 
-    CoverageMap {
-        points: scanner.points,
-        files,
+    // Closures are lifted into their own functions, so drop any point that
+    // landed inside an excluded region, or belongs to a closure defined in one,
+    // and renumber the rest.
+    let excluded = scanner.excluded;
+    let is_excluded = |file_id: u32, offset: usize| {
+        excluded.iter().any(|(f, span)| *f == file_id && span.start <= offset && offset < span.end)
+    };
+    let mut points: Vec<CoveragePoint> = scanner.points.into_iter()
+        .filter(|p| {
+            !is_excluded(p.file_id, p.byte_offset)
+                && !function_spans.get(p.function_name.as_str()).is_some_and(|(f, span)| is_excluded(*f, span.start))
+        })
+        .collect();
+    for (id, point) in points.iter_mut().enumerate() {
+        point.id = id as u32;
+    }
+
+    for (file_id, span) in &excluded {
+        let (Some(file), Some(line_index)) = (files.get_mut(*file_id as usize), line_indexes.get(file_id)) else {
+            continue;
+        };
+        let (first, _) = line_index.line_col(span.start);
+        let (last, _) = line_index.line_col(span.end);
+        file.excluded_lines.extend(first..=last);
+    }
+    for file in &mut files {
+        file.excluded_lines.sort_unstable();
+        file.excluded_lines.dedup();
+    }
+
+    CoverageMap { points, files }
+}
+
+/// Lines whose statements a `// coverage: ignore` comment excludes: the
+/// comment's own line when it trails code, otherwise the next non-blank line.
+fn ignore_directive_lines(source: &str, line_index: &LineIndex) -> HashSet<u32> {
+    let mut lines = HashSet::new();
+    for comment in crate::lexer::lex_comments(source) {
+        let text = comment.node.trim_start_matches("//").trim();
+        if text != "coverage: ignore" {
+            continue;
+        }
+        let line_start = source[..comment.span.start].rfind('\n').map_or(0, |i| i + 1);
+        let target = if source[line_start..comment.span.start].trim().is_empty() {
+            let rest = &source[comment.span.end..];
+            let next = rest.len() - rest.trim_start().len();
+            comment.span.end + next
+        } else {
+            comment.span.start
+        };
+        lines.insert(line_index.line_col(target).0);
     }
+    lines
 }
 
 struct CoverageScanner<'a> {
     points: Vec<CoveragePoint>,
+    /// Regions (file id, span) left uninstrumented.
+    excluded: Vec<(u32, Span)>,
     line_indexes: &'a HashMap<u32, LineIndex>,
     source_lens: &'a HashMap<u32, usize>,
+    ignored_lines: &'a HashMap<u32, HashSet<u32>>,
     current_function: String,
     current_file_id: u32,
 }
@@ -278,6 +353,14 @@ impl<'a> CoverageScanner<'a> {
     fn scan_function_with_name(&mut self, func: &Function, name: &str) {
         self.current_function = name.to_string();
 
+        if func.no_coverage {
+            let span = Span::new(func.name.span.start, func.body.span.end);
+            if span.start < self.source_len() {
+                self.excluded.push((self.current_file_id, span));
+            }
+            return;
+        }
+
         // Skip monomorphized copies whose spans have been offset beyond the source
         if !func.body.node.stmts.is_empty() {
             let first_span = func.body.node.stmts[0].span;
@@ -308,12 +391,18 @@ impl<'a> CoverageScanner<'a> {
             return;
         }
 
+        if self.is_ignored(stmt.span) {
+            self.excluded.push((self.current_file_id, stmt.span));
+            return;
+        }
+
         // Add a statement coverage point
         self.add_point(stmt.span, CoverageKind::Statement);
 
         // Recurse into nested blocks + add branch coverage points
         match &stmt.node {
-            Stmt::If { condition, then_block, else_block } => {
+            Stmt::If { condition, then_block, else_block }
+            | Stmt::IfLet { value: condition, then_block, else_block, .. } => {
                 // Branch coverage: then path (branch_id 1, keyed by then_block span)
                 if then_block.span.start < self.source_len() {
                     self.add_point_with_branch(then_block.span, CoverageKind::BranchThen, 1);
@@ -352,19 +441,65 @@ impl<'a> CoverageScanner<'a> {
                     self.scan_block(&arm.body.node);
                 }
             }
+            Stmt::NullableMatch { some_arm, none_arm, .. } => {
+                // Lowered like `if let`: the some arm is the then path, none the else path
+                if let Some(arm) = some_arm {
+                    if arm.body.span.start < self.source_len() {
+                        self.add_point_with_branch(arm.body.span, CoverageKind::BranchThen, 1);
+                    }
+                    self.scan_block(&arm.body.node);
+                }
+                if let Some(nb) = none_arm {
+                    if nb.span.start < self.source_len() {
+                        self.add_point_with_branch(nb.span, CoverageKind::BranchElse, 1);
+                    }
+                    self.scan_block(&nb.node);
+                }
+            }
+            Stmt::IntMatch { arms, .. } => {
+                for (i, arm) in arms.iter().enumerate() {
+                    if arm.body.span.start < self.source_len() {
+                        self.add_point_with_branch(
+                            arm.body.span,
+                            CoverageKind::MatchArm { index: i as u32 },
+                            1,
+                        );
+                    }
+                    self.scan_block(&arm.body.node);
+                }
+            }
             Stmt::Select { arms, default, .. } => {
-                for arm in arms {
+                for (i, arm) in arms.iter().enumerate() {
+                    // Branch coverage: select arm (branch_id 1, keyed by arm body span)
+                    if arm.body.span.start < self.source_len() {
+                        self.add_point_with_branch(
+                            arm.body.span,
+                            CoverageKind::SelectArm { index: i as u32 },
+                            1,
+                        );
+                    }
                     self.scan_block(&arm.body.node);
                 }
                 if let Some(def) = default {
+                    if def.span.start < self.source_len() {
+                        self.add_point_with_branch(def.span, CoverageKind::SelectDefault, 1);
+                    }
                     self.scan_block(&def.node);
                 }
             }
             Stmt::Scope { body, .. } => {
                 self.scan_block(&body.node);
             }
+            Stmt::Try { body, handler, finally, .. } => {
+                self.scan_block(&body.node);
+                self.scan_block(&handler.node);
+                if let Some(finally) = finally {
+                    self.scan_block(&finally.node);
+                }
+            }
             // Leaf statements — already counted above
             Stmt::Let { .. }
+            | Stmt::LetStruct { .. }
             | Stmt::LetChan { .. }
             | Stmt::Assign { .. }
             | Stmt::FieldAssign { .. }
@@ -383,6 +518,17 @@ impl<'a> CoverageScanner<'a> {
         self.scan_stmt_exprs(&stmt.node);
     }
 
+    /// Whether a `// coverage: ignore` directive targets the line `span` starts on.
+    fn is_ignored(&self, span: Span) -> bool {
+        let (Some(lines), Some(line_index)) = (
+            self.ignored_lines.get(&self.current_file_id),
+            self.line_indexes.get(&self.current_file_id),
+        ) else {
+            return false;
+        };
+        !lines.is_empty() && lines.contains(&line_index.line_col(span.start).0)
+    }
+
     fn add_point(&mut self, span: Span, kind: CoverageKind) {
         self.add_point_with_branch(span, kind, 0);
     }
@@ -411,7 +557,7 @@ impl<'a> CoverageScanner<'a> {
     /// Scan a statement's expressions for expression-level branch points (?, !).
     fn scan_stmt_exprs(&mut self, stmt: &Stmt) {
         match stmt {
-            Stmt::Let { value, .. } => {
+            Stmt::Let { value, .. } | Stmt::LetStruct { value, .. } => {
                 self.scan_expr(&value.node);
             }
             Stmt::Assign { value, .. } => self.scan_expr(&value.node),
@@ -426,13 +572,15 @@ impl<'a> CoverageScanner<'a> {
             }
             Stmt::Return(Some(expr)) => self.scan_expr(&expr.node),
             Stmt::Expr(expr) => self.scan_expr(&expr.node),
-            Stmt::If { condition, .. } => {
+            Stmt::If { condition, .. } | Stmt::IfLet { value: condition, .. } => {
                 self.scan_expr(&condition.node);
                 // then/else blocks already recursed via scan_block
             }
             Stmt::While { condition, .. } => self.scan_expr(&condition.node),
             Stmt::For { iterable, .. } => self.scan_expr(&iterable.node),
-            Stmt::Match { expr, .. } => self.scan_expr(&expr.node),
+            Stmt::Match { expr, .. } | Stmt::IntMatch { expr, .. } | Stmt::NullableMatch { expr, .. } => {
+                self.scan_expr(&expr.node)
+            }
             Stmt::Raise { fields, .. } => {
                 for (_, val) in fields {
                     self.scan_expr(&val.node);
@@ -449,6 +597,7 @@ impl<'a> CoverageScanner<'a> {
             | Stmt::Continue
             | Stmt::LetChan { .. }
             | Stmt::Scope { .. }
+            | Stmt::Try { .. }
             | Stmt::Select { .. } => {}
         }
     }
@@ -472,6 +621,14 @@ impl<'a> CoverageScanner<'a> {
                 }
                 self.scan_expr(&inner.node);
             }
+            Expr::PropagateMap { expr: inner, mapper } => {
+                if inner.span.start < self.source_len() {
+                    self.add_point_with_branch(inner.span, CoverageKind::ErrorPropError, 1);
+                    self.add_point_with_branch(inner.span, CoverageKind::ErrorPropSuccess, 2);
+                }
+                self.scan_expr(&inner.node);
+                self.scan_expr(&mapper.node);
+            }
             // If-expression: add branch points for then/else paths
             Expr::If { condition, then_block, else_block } => {
                 self.scan_expr(&condition.node);
@@ -502,6 +659,16 @@ impl<'a> CoverageScanner<'a> {
                     self.scan_expr(&arm.value.node);
                 }
             }
+            // Short-circuit booleans: keyed by the rhs span with branch_ids 3 and 4,
+            // so they can't collide with a `?`/`!` point on the same operand
+            Expr::BinOp { op: BinOp::And | BinOp::Or, lhs, rhs } => {
+                if rhs.span.start < self.source_len() {
+                    self.add_point_with_branch(rhs.span, CoverageKind::ShortCircuitLeft, 3);
+                    self.add_point_with_branch(rhs.span, CoverageKind::ShortCircuitRight, 4);
+                }
+                self.scan_expr(&lhs.node);
+                self.scan_expr(&rhs.node);
+            }
             // Recurse into sub-expressions
             Expr::BinOp { lhs, rhs, .. } => {
                 self.scan_expr(&lhs.node);
@@ -531,6 +698,7 @@ impl<'a> CoverageScanner<'a> {
             }
             Expr::Catch { expr: inner, .. } => self.scan_expr(&inner.node),
             Expr::Cast { expr: inner, .. } => self.scan_expr(&inner.node),
+            Expr::Is { expr: inner, .. } => self.scan_expr(&inner.node),
             Expr::Range { start, end, .. } => {
                 self.scan_expr(&start.node);
                 self.scan_expr(&end.node);
@@ -578,6 +746,7 @@ impl<'a> CoverageScanner<'a> {
             | Expr::NoneLit
             | Expr::ClosureCreate { .. }
             | Expr::EnumUnit { .. }
+            | Expr::MethodRef { .. }
             | Expr::QualifiedAccess { .. } => {}
         }
     }
@@ -836,7 +1005,8 @@ pub fn generate_lcov(map: &CoverageMap, data: &CoverageData) -> String {
                 let count = data.counters.get(point.id as usize).copied().unwrap_or(0);
                 branches.push((point.line, branch_block, point.branch_id, count));
                 // Increment block ID when we see a new source location
-                if point.branch_id <= 1 {
+                // (a short-circuit pair shares one block)
+                if point.branch_id <= 1 || point.kind == CoverageKind::ShortCircuitRight {
                     branch_block += 1;
                 }
             }
@@ -889,6 +1059,10 @@ pub struct JsonFileCoverage {
     pub branches: JsonCoverageMetric,
     pub line_details: Vec<JsonLineDetail>,
     pub function_details: Vec<JsonFunctionDetail>,
+    #[serde(default)]
+    pub branch_details: Vec<JsonBranchDetail>,
+    #[serde(default)]
+    pub excluded_lines: Vec<u32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -909,6 +1083,19 @@ pub struct JsonFunctionDetail {
     pub name: String,
     pub line: u32,
     pub hit_count: i64,
+    /// Statements executed inside the function, summed over all calls.
+    /// Until there is a profiler this is the report's measure of where time goes.
+    #[serde(default)]
+    pub statement_hits: i64,
+}
+
+/// One side of a branch point and how often it was taken.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonBranchDetail {
+    pub line: u32,
+    pub column: u32,
+    pub kind: CoverageKind,
+    pub hit_count: i64,
 }
 
 /// Generate a structured JSON coverage report.
@@ -941,20 +1128,40 @@ pub fn generate_json_report(map: &CoverageMap, data: &CoverageData) -> JsonCover
 
         // Function details
         let mut func_hits: HashMap<&str, (u32, i64)> = HashMap::new();
+        let mut func_stmt_hits: HashMap<&str, i64> = HashMap::new();
         for point in points {
-            if point.kind == CoverageKind::FunctionEntry {
-                let count = data.counters.get(point.id as usize).copied().unwrap_or(0);
-                let entry = func_hits.entry(&point.function_name).or_insert((point.line, 0));
-                entry.1 += count;
+            let count = data.counters.get(point.id as usize).copied().unwrap_or(0);
+            match point.kind {
+                CoverageKind::FunctionEntry => {
+                    let entry = func_hits.entry(&point.function_name).or_insert((point.line, 0));
+                    entry.1 += count;
+                }
+                CoverageKind::Statement => {
+                    *func_stmt_hits.entry(&point.function_name).or_insert(0) += count;
+                }
+                _ => {}
             }
         }
         let mut function_details: Vec<_> = func_hits.into_iter()
             .map(|(name, (line, hit_count))| JsonFunctionDetail {
                 name: name.to_string(), line, hit_count,
+                statement_hits: func_stmt_hits.get(name).copied().unwrap_or(0),
             })
             .collect();
         function_details.sort_by_key(|d| d.line);
 
+        // Branch details
+        let mut branch_details: Vec<_> = points.iter()
+            .filter(|p| p.kind.is_branch())
+            .map(|p| JsonBranchDetail {
+                line: p.line,
+                column: p.column,
+                kind: p.kind.clone(),
+                hit_count: data.counters.get(p.id as usize).copied().unwrap_or(0),
+            })
+            .collect();
+        branch_details.sort_by_key(|d| (d.line, d.column));
+
         let (tl, cl, tf, cf, tb, cb) = match file_stat {
             Some(s) => (s.total_lines, s.covered_lines, s.total_functions,
                        s.covered_functions, s.total_branches, s.covered_branches),
@@ -977,6 +1184,8 @@ pub fn generate_json_report(map: &CoverageMap, data: &CoverageData) -> JsonCover
             },
             line_details,
             function_details,
+            branch_details,
+            excluded_lines: file.excluded_lines.clone(),
         });
     }
 
@@ -1002,6 +1211,92 @@ pub fn generate_json_report(map: &CoverageMap, data: &CoverageData) -> JsonCover
     }
 }
 
+// ── Coverage gate ───────────────────────────────────────────────────────────
+
+/// Line coverage thresholds for `pluto coverage check`, in percent.
+#[derive(Debug, Clone, Default)]
+pub struct CoverageThresholds {
+    /// Minimum line coverage of the whole run.
+    pub min: Option<f64>,
+    /// Minimum line coverage of the lines that are new since the baseline.
+    pub min_diff: Option<f64>,
+    /// Also apply `min` to every file on its own.
+    pub per_file: bool,
+}
+
+/// A threshold that was not met.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GateFailure {
+    /// The file that failed, or `None` for the run as a whole.
+    pub path: Option<String>,
+    /// Whether the failing figure covers only lines new since the baseline.
+    pub new_lines: bool,
+    pub covered: u32,
+    pub total: u32,
+    pub percent: f64,
+    pub min: f64,
+}
+
+impl JsonCoverageReport {
+    /// Read a report written by `pluto coverage report --format json`.
+    pub fn read_json(path: &Path) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        serde_json::from_str(&json)
+            .map_err(std::io::Error::other)
+    }
+}
+
+/// Check `report` against `thresholds`, returning every threshold it misses.
+///
+/// A line is new when the baseline has no instrumented line with that number
+/// in the same file; files missing from the baseline are new in full. Diff
+/// failures are reported per file and for all new lines together. Without a
+/// baseline `min_diff` is not checked.
+pub fn check_thresholds(
+    report: &JsonCoverageReport,
+    baseline: Option<&JsonCoverageReport>,
+    thresholds: &CoverageThresholds,
+) -> Vec<GateFailure> {
+    let mut failures = Vec::new();
+    let mut gate = |path: Option<&str>, new_lines: bool, covered: u32, total: u32, min: f64| {
+        let percent = if total > 0 { covered as f64 / total as f64 * 100.0 } else { 100.0 };
+        if percent < min {
+            failures.push(GateFailure { path: path.map(str::to_string), new_lines, covered, total, percent, min });
+        }
+    };
+
+    if let Some(min) = thresholds.min {
+        if thresholds.per_file {
+            for file in &report.files {
+                gate(Some(&file.path), false, file.lines.covered, file.lines.total, min);
+            }
+        }
+        gate(None, false, report.summary.covered_lines, report.summary.total_lines, min);
+    }
+
+    if let (Some(min), Some(baseline)) = (thresholds.min_diff, baseline) {
+        let (mut all_covered, mut all_total) = (0, 0);
+        for file in &report.files {
+            let old_lines: HashSet<u32> = baseline.files.iter()
+                .find(|f| f.path == file.path)
+                .map(|f| f.line_details.iter().map(|d| d.line).collect())
+                .unwrap_or_default();
+            let new: Vec<_> = file.line_details.iter().filter(|d| !old_lines.contains(&d.line)).collect();
+            if new.is_empty() {
+                continue;
+            }
+            let covered = new.iter().filter(|d| d.hit_count > 0).count() as u32;
+            let total = new.len() as u32;
+            gate(Some(&file.path), true, covered, total, min);
+            all_covered += covered;
+            all_total += total;
+        }
+        gate(None, true, all_covered, all_total, min);
+    }
+
+    failures
+}
+
 // ── HTML report output ──────────────────────────────────────────────────────
 
 /// Combined data structure for the HTML template.
@@ -1017,7 +1312,8 @@ struct HtmlCoverageData {
 ///
 /// The report is a single HTML file with embedded CSS, JS, and coverage data.
 /// It includes a treemap visualization, per-file source view with line-level
-/// highlighting, and a sortable function table.
+/// highlighting, and a sortable function table. Call counts and executed-statement
+/// totals are overlaid per function so hot paths stand out next to the coverage.
 pub fn generate_html_report(
     map: &CoverageMap,
     data: &CoverageData,
@@ -1098,6 +1394,7 @@ mod tests {
             files: vec![CoverageFile {
                 id: 0,
                 path: "test.pluto".to_string(),
+                excluded_lines: vec![],
             }],
         };
         let json = serde_json::to_string(&map).unwrap();
@@ -1125,7 +1422,7 @@ mod tests {
                     branch_id: 0,
                 },
             ],
-            files: vec![CoverageFile { id: 0, path: "test.pluto".to_string() }],
+            files: vec![CoverageFile { id: 0, path: "test.pluto".to_string(), excluded_lines: vec![] }],
         };
         let lookup = map.build_span_lookup();
         assert_eq!(lookup.get(&(0, 3, 0)), Some(&0));
@@ -1158,7 +1455,7 @@ mod tests {
                     branch_id: 0,
                 },
             ],
-            files: vec![CoverageFile { id: 0, path: "test.pluto".to_string() }],
+            files: vec![CoverageFile { id: 0, path: "test.pluto".to_string(), excluded_lines: vec![] }],
         };
         let data = CoverageData { counters: vec![1, 5, 0] };
         let stats = generate_terminal_report(&map, &data);
@@ -9,7 +9,7 @@
 use std::collections::{BTreeMap, HashSet};
 use uuid::Uuid;
 
-use crate::parser::ast::{Lifecycle, Program};
+use crate::parser::ast::{Function, Lifecycle, Program, Visibility};
 use crate::span::Spanned;
 use crate::typeck::env::{mangle_method, TypeEnv};
 use crate::typeck::types::PlutoType;
@@ -73,6 +73,10 @@ pub struct ResolvedSignature {
     pub param_types: Vec<PlutoType>,
     pub return_type: PlutoType,
     pub is_fallible: bool,
+    /// Error set written in the signature (`int | ParseError`), if any.
+    /// `None` means the error set is purely inferred.
+    #[serde(default)]
+    pub declared_errors: Option<Vec<String>>,
 }
 
 /// Resolved class info extracted from TypeEnv.
@@ -82,7 +86,7 @@ pub struct ResolvedClassInfo {
     pub methods: Vec<(String, ResolvedSignature)>,
     pub impl_traits: Vec<String>,
     pub lifecycle: Lifecycle,
-    pub is_pub: bool,
+    pub visibility: Visibility,
 }
 
 /// A single resolved field with its concrete type.
@@ -176,7 +180,9 @@ impl Visitor for DependencyCollector<'_> {
                     self.deps.push(class_name.clone());
                 }
             }
-            Expr::EnumUnit { enum_name, .. } | Expr::EnumData { enum_name, .. } => {
+            Expr::EnumUnit { enum_name, .. }
+            | Expr::EnumData { enum_name, .. }
+            | Expr::Is { enum_name, .. } => {
                 // Track enum usage
                 let enum_name_str = &enum_name.node;
                 if !self.visited.contains(enum_name_str) {
@@ -302,7 +308,7 @@ impl DerivedInfo {
 
             let key = typeenv_key(name, None);
             Self::collect_fn_data(
-                f.node.id,
+                &f.node,
                 &key,
                 env,
                 &error_uuid_map,
@@ -317,7 +323,7 @@ impl DerivedInfo {
             for method in &class.node.methods {
                 let key = typeenv_key(&method.node.name.node, Some(class_name));
                 Self::collect_fn_data(
-                    method.node.id,
+                    &method.node,
                     &key,
                     env,
                     &error_uuid_map,
@@ -333,7 +339,7 @@ impl DerivedInfo {
             for method in &app.node.methods {
                 let key = typeenv_key(&method.node.name.node, Some(app_name));
                 Self::collect_fn_data(
-                    method.node.id,
+                    &method.node,
                     &key,
                     env,
                     &error_uuid_map,
@@ -349,7 +355,7 @@ impl DerivedInfo {
             for method in &stage.node.methods {
                 let key = typeenv_key(&method.node.name.node, Some(stage_name));
                 Self::collect_fn_data(
-                    method.node.id,
+                    &method.node,
                     &key,
                     env,
                     &error_uuid_map,
@@ -397,6 +403,9 @@ impl DerivedInfo {
                                     param_types: sig.params.clone(),
                                     return_type: sig.return_type.clone(),
                                     is_fallible,
+                                    declared_errors: class.node.methods.iter()
+                                        .find(|m| m.node.name.node == *method_name)
+                                        .and_then(|m| declared_error_names(&m.node.declared_errors)),
                                 },
                             )
                         })
@@ -410,7 +419,7 @@ impl DerivedInfo {
                         methods,
                         impl_traits: ci.impl_traits.clone(),
                         lifecycle: ci.lifecycle,
-                        is_pub: class.node.is_pub,
+                        visibility: class.node.visibility,
                     },
                 );
             }
@@ -435,6 +444,9 @@ impl DerivedInfo {
                                 param_types: sig.params.clone(),
                                 return_type: sig.return_type.clone(),
                                 is_fallible,
+                                declared_errors: tr.node.methods.iter()
+                                    .find(|m| m.name.node == *name)
+                                    .and_then(|m| declared_error_names(&m.declared_errors)),
                             },
                         )
                     })
@@ -537,7 +549,7 @@ impl DerivedInfo {
     }
 
     fn collect_fn_data(
-        id: Uuid,
+        func: &Function,
         key: &str,
         env: &TypeEnv,
         error_uuid_map: &BTreeMap<&str, Uuid>,
@@ -560,22 +572,28 @@ impl DerivedInfo {
         };
 
         let is_fallible = !error_set.is_empty();
-        fn_error_sets.insert(id, error_set);
+        fn_error_sets.insert(func.id, error_set);
 
         // Resolved signature
         if let Some(sig) = env.functions.get(key) {
             fn_signatures.insert(
-                id,
+                func.id,
                 ResolvedSignature {
                     param_types: sig.params.clone(),
                     return_type: sig.return_type.clone(),
                     is_fallible,
+                    declared_errors: declared_error_names(&func.declared_errors),
                 },
             );
         }
     }
 }
 
+/// Names from a signature's declared error set, as stored in `ResolvedSignature`.
+fn declared_error_names(errors: &Option<Vec<Spanned<String>>>) -> Option<Vec<String>> {
+    errors.as_ref().map(|errs| errs.iter().map(|e| e.node.clone()).collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -34,6 +34,10 @@ pub enum CompileError {
 
     #[error("Version not found: {0}")]
     VersionNotFound(String),
+
+    /// A panic inside the compiler, caught and reported instead of aborting.
+    #[error("internal compiler error: {0}")]
+    Internal(String),
 }
 
 impl CompileError {
@@ -72,6 +76,10 @@ impl CompileError {
     pub fn version_not_found(msg: impl Into<String>) -> Self {
         Self::VersionNotFound(msg.into())
     }
+
+    pub fn internal(msg: impl Into<String>) -> Self {
+        Self::Internal(msg.into())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -84,6 +92,12 @@ pub struct CompileWarning {
 #[derive(Debug, Clone)]
 pub enum WarningKind {
     UnusedVariable,
+    /// The value of a call that can raise was discarded.
+    UnusedResult,
+    /// A statement follows a `return`, `raise`, `break` or `continue`.
+    UnreachableCode,
+    /// A task spawned from a scoped or transient class refers to `self`.
+    SpawnCapturesScopedSelf,
 }
 
 /// Render a CompileWarning with ariadne for nice terminal output (yellow).
@@ -125,6 +139,10 @@ pub fn render_error(source: &str, _filename: &str, err: &CompileError) {
         | CompileError::VersionNotFound(msg) => {
             eprintln!("error: {msg}");
         }
+        CompileError::Internal(msg) => {
+            eprintln!("error: internal compiler error: {msg}");
+            eprintln!("note: this is a bug in the Pluto compiler; please file an issue at https://github.com/Mkerian10/pluto/issues");
+        }
         CompileError::Manifest { msg, path } => {
             eprintln!("error[manifest]: {msg}");
             eprintln!("  --> {}", path.display());
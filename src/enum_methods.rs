@@ -0,0 +1,95 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::diagnostics::CompileError;
+use crate::parser::ast::*;
+use crate::span::Spanned;
+use crate::typeck::env::mangle_method;
+use crate::visit::{walk_expr_mut, VisitMut};
+
+/// Lower methods declared inside `enum` bodies into top-level functions.
+///
+/// Each method `fn area(self) float` on enum `Shape` becomes a top-level
+/// function `Shape$area(self: Shape) float`, the same mangled name class
+/// methods use, so typeck and codegen resolve `shape.area()` through the
+/// ordinary method-call path. The method keeps its UUID.
+///
+/// Associated functions (no `self`) are called as `Shape.unit()`, which the
+/// parser reads as a tuple-variant construction; those `EnumData` nodes are
+/// rewritten into calls to `Shape$unit`.
+pub fn lower_enum_methods(program: &mut Program) -> Result<(), CompileError> {
+    let mut associated: HashMap<String, HashSet<String>> = HashMap::new();
+    let mut lowered = Vec::new();
+
+    for enum_decl in &mut program.enums {
+        let e = &mut enum_decl.node;
+        if e.methods.is_empty() {
+            continue;
+        }
+        if !e.type_params.is_empty() {
+            return Err(CompileError::type_err(
+                format!("generic enum '{}' cannot declare methods", e.name.node),
+                e.methods[0].node.name.span,
+            ));
+        }
+
+        let mut seen = HashSet::new();
+        for method in std::mem::take(&mut e.methods) {
+            let mname = &method.node.name;
+            if e.variants.iter().any(|v| v.name.node == mname.node) {
+                return Err(CompileError::type_err(
+                    format!("method '{}' on enum '{}' has the same name as a variant", mname.node, e.name.node),
+                    mname.span,
+                ));
+            }
+            if !seen.insert(mname.node.clone()) {
+                return Err(CompileError::type_err(
+                    format!("duplicate method '{}' on enum '{}'", mname.node, e.name.node),
+                    mname.span,
+                ));
+            }
+
+            let mut func = method;
+            match func.node.params.first_mut() {
+                Some(p) if p.name.node == "self" => {
+                    if p.is_mut {
+                        return Err(CompileError::type_err(
+                            format!("enum methods cannot take 'mut self'; enum '{}' values are immutable", e.name.node),
+                            p.name.span,
+                        ));
+                    }
+                    p.ty.node = TypeExpr::Named(e.name.node.clone());
+                }
+                _ => {
+                    associated.entry(e.name.node.clone()).or_default().insert(func.node.name.node.clone());
+                }
+            }
+            func.node.name.node = mangle_method(&e.name.node, &func.node.name.node);
+            lowered.push(func);
+        }
+    }
+
+    program.functions.extend(lowered);
+    if !associated.is_empty() {
+        AssociatedCallRewriter { associated: &associated }.visit_program_mut(program);
+    }
+    Ok(())
+}
+
+/// Rewrites `Enum.func(args)` (parsed as `EnumData`) into `Enum$func(args)`.
+struct AssociatedCallRewriter<'a> {
+    associated: &'a HashMap<String, HashSet<String>>,
+}
+
+impl VisitMut for AssociatedCallRewriter<'_> {
+    fn visit_expr_mut(&mut self, expr: &mut Spanned<Expr>) {
+        walk_expr_mut(self, expr);
+
+        if let Expr::EnumData { enum_name, variant, fields, .. } = &mut expr.node
+            && self.associated.get(&enum_name.node).is_some_and(|fns| fns.contains(&variant.node))
+        {
+            let name = Spanned::new(mangle_method(&enum_name.node, &variant.node), variant.span);
+            let args = std::mem::take(fields).into_iter().map(|(_, value)| value).collect();
+            expr.node = Expr::Call { name, args, type_args: vec![], target_id: None };
+        }
+    }
+}
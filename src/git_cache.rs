@@ -9,6 +9,20 @@ pub enum GitRef {
     Rev(String),
     Tag(String),
     Branch(String),
+    /// The highest tag (`1.2.3` or `v1.2.3`) satisfying a semver requirement.
+    Version(semver::VersionReq),
+}
+
+impl std::fmt::Display for GitRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitRef::DefaultBranch => write!(f, "default branch"),
+            GitRef::Rev(rev) => write!(f, "rev {rev}"),
+            GitRef::Tag(tag) => write!(f, "tag {tag}"),
+            GitRef::Branch(branch) => write!(f, "branch {branch}"),
+            GitRef::Version(req) => write!(f, "version {req}"),
+        }
+    }
 }
 
 /// Compute the global cache directory for a git URL.
@@ -33,69 +47,121 @@ pub fn ensure_cached(
     let dir = cache_dir_for_url(url);
 
     if !dir.exists() {
-        // Clone into cache
-        std::fs::create_dir_all(dir.parent().unwrap()).map_err(|e| {
-            CompileError::manifest(
-                format!("failed to create git cache directory: {e}"),
-                manifest_path.to_path_buf(),
-            )
-        })?;
+        clone_into(&dir, url, manifest_path)?;
+    }
+
+    checkout_ref(&dir, url, git_ref, manifest_path)?;
+
+    Ok(dir)
+}
+
+/// Clone (if not already cached) and check out an exact commit, fetching
+/// first when the cached clone doesn't have it yet. Used for locked deps.
+pub fn ensure_commit(
+    url: &str,
+    commit: &str,
+    manifest_path: &Path,
+) -> Result<PathBuf, CompileError> {
+    let dir = cache_dir_for_url(url);
+
+    if !dir.exists() {
+        clone_into(&dir, url, manifest_path)?;
+    }
+
+    let rev = GitRef::Rev(commit.to_string());
+    if checkout_ref(&dir, url, &rev, manifest_path).is_err() {
         run_git(
-            None,
-            &["clone", url, &dir.to_string_lossy()],
+            Some(&dir),
+            &["fetch", "--all", "--tags"],
             url,
             manifest_path,
-            "clone",
+            "fetch",
         )?;
+        checkout_ref(&dir, url, &rev, manifest_path)?;
     }
 
-    checkout_ref(&dir, url, git_ref, manifest_path)?;
-
     Ok(dir)
 }
 
+/// The commit currently checked out in a cached repo.
+pub fn head_commit(dir: &Path, url: &str, manifest_path: &Path) -> Result<String, CompileError> {
+    let sha = run_git(Some(dir), &["rev-parse", "HEAD"], url, manifest_path, "rev-parse")?;
+    Ok(sha.trim().to_string())
+}
+
 /// Fetch latest from remote and reset to the requested ref.
-/// Used by `pluto update`.
+/// Used by `pluto update`. Returns the tag a `Version` ref resolved to.
 pub fn fetch_and_update(
     url: &str,
     git_ref: &GitRef,
     manifest_path: &Path,
-) -> Result<PathBuf, CompileError> {
+) -> Result<Option<String>, CompileError> {
     let dir = cache_dir_for_url(url);
 
-    if !dir.exists() {
+    if dir.exists() {
+        // Fetch latest, including tags new versions may have added
+        run_git(
+            Some(&dir),
+            &["fetch", "--all", "--tags"],
+            url,
+            manifest_path,
+            "fetch",
+        )?;
+    } else {
         // Not cached yet — just do a fresh clone
-        return ensure_cached(url, git_ref, manifest_path);
+        clone_into(&dir, url, manifest_path)?;
     }
 
-    // Fetch latest
+    checkout_ref(&dir, url, git_ref, manifest_path)
+}
+
+/// The highest of `tags` whose version (with an optional leading `v`)
+/// satisfies `req`. Tags that aren't versions are ignored.
+pub fn highest_matching_tag<'a>(
+    tags: impl IntoIterator<Item = &'a str>,
+    req: &semver::VersionReq,
+) -> Option<&'a str> {
+    tags.into_iter()
+        .filter_map(|tag| {
+            let version = semver::Version::parse(tag.strip_prefix('v').unwrap_or(tag)).ok()?;
+            req.matches(&version).then_some((version, tag))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, tag)| tag)
+}
+
+// ---- Internal helpers ----
+
+fn clone_into(dir: &Path, url: &str, manifest_path: &Path) -> Result<(), CompileError> {
+    std::fs::create_dir_all(dir.parent().unwrap()).map_err(|e| {
+        CompileError::manifest(
+            format!("failed to create git cache directory: {e}"),
+            manifest_path.to_path_buf(),
+        )
+    })?;
     run_git(
-        Some(&dir),
-        &["fetch", "--all"],
+        None,
+        &["clone", url, &dir.to_string_lossy()],
         url,
         manifest_path,
-        "fetch",
+        "clone",
     )?;
-
-    checkout_ref(&dir, url, git_ref, manifest_path)?;
-
-    Ok(dir)
+    Ok(())
 }
 
-// ---- Internal helpers ----
-
+/// Check out `git_ref`, returning the tag a `Version` ref resolved to.
 fn checkout_ref(
     dir: &Path,
     url: &str,
     git_ref: &GitRef,
     manifest_path: &Path,
-) -> Result<(), CompileError> {
+) -> Result<Option<String>, CompileError> {
     match git_ref {
         GitRef::DefaultBranch => {
             // Reset to origin's default branch HEAD
             run_git(
                 Some(dir),
-                &["checkout", "HEAD"],
+                &["checkout", "origin/HEAD"],
                 url,
                 manifest_path,
                 "checkout",
@@ -139,18 +205,35 @@ fn checkout_ref(
                 )?;
             }
         }
+        GitRef::Version(req) => {
+            let tags = run_git(Some(dir), &["tag", "--list"], url, manifest_path, "tag")?;
+            let Some(tag) = highest_matching_tag(tags.lines(), req) else {
+                return Err(CompileError::manifest(
+                    format!("no tag of '{url}' satisfies version '{req}'"),
+                    manifest_path.to_path_buf(),
+                ));
+            };
+            run_git(
+                Some(dir),
+                &["checkout", &format!("tags/{tag}")],
+                url,
+                manifest_path,
+                "checkout",
+            )?;
+            return Ok(Some(tag.to_string()));
+        }
     }
-    Ok(())
+    Ok(None)
 }
 
-/// Run a git command, returning a descriptive error on failure.
+/// Run a git command, returning its stdout or a descriptive error on failure.
 fn run_git(
     dir: Option<&Path>,
     args: &[&str],
     url: &str,
     manifest_path: &Path,
     operation: &str,
-) -> Result<(), CompileError> {
+) -> Result<String, CompileError> {
     let mut cmd = std::process::Command::new("git");
     if let Some(d) = dir {
         cmd.current_dir(d);
@@ -178,7 +261,7 @@ fn run_git(
         ));
     }
 
-    Ok(())
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
 
 /// Normalize a URL for hashing: lowercase, strip trailing slashes.
@@ -239,6 +322,16 @@ mod tests {
         assert_ne!(djb2_hex("hello"), djb2_hex("world"));
     }
 
+    #[test]
+    fn highest_matching_tag_picks_newest_compatible() {
+        let tags = ["v1.0.0", "v1.2.0", "1.10.1", "v2.0.0", "latest", "v1.11.0-beta.1"];
+        let req = |r: &str| semver::VersionReq::parse(r).unwrap();
+        assert_eq!(highest_matching_tag(tags, &req("^1.2")), Some("1.10.1"));
+        assert_eq!(highest_matching_tag(tags, &req("~1.2")), Some("v1.2.0"));
+        assert_eq!(highest_matching_tag(tags, &req(">=2")), Some("v2.0.0"));
+        assert_eq!(highest_matching_tag(tags, &req("^3")), None);
+    }
+
     #[test]
     fn cache_dir_for_url_deterministic() {
         let d1 = cache_dir_for_url("https://github.com/user/repo.git");
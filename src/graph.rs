@@ -0,0 +1,429 @@
+//! Dependency graphs for `pluto graph`, rendered as Graphviz DOT.
+//!
+//! The import graph follows the same lookup rules as `modules::resolve_modules`
+//! (package deps, local directories and files, `.deps/`, `std.*`), but records
+//! edges instead of flattening, so it can show import cycles that resolution
+//! rejects. The call graph draws an edge from each function or method to every
+//! function it calls, using the `target_id`s cross-reference resolution fills in.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use crate::diagnostics::CompileError;
+use crate::manifest::{self, DependencyScope, PackageGraph};
+use crate::modules::{self, SourceMap};
+use crate::parser::ast::*;
+use crate::span::Spanned;
+use crate::visit::{walk_expr, walk_function, Visitor};
+
+/// A directed graph of named nodes.
+#[derive(Debug, Default)]
+pub struct Graph {
+    pub nodes: Vec<String>,
+    /// `(from, to)` indices into `nodes`, without duplicates.
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl Graph {
+    fn add_edge(&mut self, from: usize, to: usize) {
+        if !self.edges.contains(&(from, to)) {
+            self.edges.push((from, to));
+        }
+    }
+
+    /// Edges that lie on a cycle: both ends in the same strongly connected
+    /// component, or a node pointing at itself.
+    pub fn cycle_edges(&self) -> HashSet<(usize, usize)> {
+        let component = self.components();
+        self.edges.iter()
+            .filter(|(from, to)| component[*from] == component[*to])
+            .copied()
+            .collect()
+    }
+
+    /// Strongly connected component of every node (Tarjan's algorithm).
+    fn components(&self) -> Vec<usize> {
+        struct Tarjan {
+            succ: Vec<Vec<usize>>,
+            index: Vec<Option<usize>>,
+            low: Vec<usize>,
+            on_stack: Vec<bool>,
+            stack: Vec<usize>,
+            component: Vec<usize>,
+            next_index: usize,
+            next_component: usize,
+        }
+
+        impl Tarjan {
+            fn visit(&mut self, v: usize) {
+                self.index[v] = Some(self.next_index);
+                self.low[v] = self.next_index;
+                self.next_index += 1;
+                self.stack.push(v);
+                self.on_stack[v] = true;
+                for i in 0..self.succ[v].len() {
+                    let w = self.succ[v][i];
+                    match self.index[w] {
+                        None => {
+                            self.visit(w);
+                            self.low[v] = self.low[v].min(self.low[w]);
+                        }
+                        Some(w_index) if self.on_stack[w] => self.low[v] = self.low[v].min(w_index),
+                        Some(_) => {}
+                    }
+                }
+                if Some(self.low[v]) == self.index[v] {
+                    while let Some(w) = self.stack.pop() {
+                        self.on_stack[w] = false;
+                        self.component[w] = self.next_component;
+                        if w == v {
+                            break;
+                        }
+                    }
+                    self.next_component += 1;
+                }
+            }
+        }
+
+        let n = self.nodes.len();
+        let mut succ = vec![Vec::new(); n];
+        for &(from, to) in &self.edges {
+            succ[from].push(to);
+        }
+        let mut tarjan = Tarjan {
+            succ,
+            index: vec![None; n],
+            low: vec![0; n],
+            on_stack: vec![false; n],
+            stack: Vec::new(),
+            component: vec![0; n],
+            next_index: 0,
+            next_component: 0,
+        };
+        for v in 0..n {
+            if tarjan.index[v].is_none() {
+                tarjan.visit(v);
+            }
+        }
+        tarjan.component
+    }
+
+    /// Render as a DOT digraph, with nodes and edges on cycles drawn in red.
+    pub fn to_dot(&self, name: &str) -> String {
+        let cycles = self.cycle_edges();
+        let on_cycle: HashSet<usize> = cycles.iter().flat_map(|&(from, to)| [from, to]).collect();
+
+        let mut out = format!("digraph {name} {{\n    rankdir=LR;\n    node [shape=box];\n");
+        for (i, label) in self.nodes.iter().enumerate() {
+            let color = if on_cycle.contains(&i) { ", color=red" } else { "" };
+            out.push_str(&format!("    n{i} [label=\"{}\"{color}];\n", escape_dot(label)));
+        }
+        for &(from, to) in &self.edges {
+            let color = if cycles.contains(&(from, to)) { " [color=red]" } else { "" };
+            out.push_str(&format!("    n{from} -> n{to}{color};\n"));
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// ---- Import graph ----
+
+/// Build the module import graph of `entry_file`. The entry file and the
+/// sibling files merged into it form the root node.
+pub fn import_graph(entry_file: &Path, stdlib_root: Option<&Path>) -> Result<Graph, CompileError> {
+    let entry_file = entry_file.canonicalize().map_err(|e| {
+        CompileError::codegen(format!("could not resolve path '{}': {e}", entry_file.display()))
+    })?;
+    let entry_dir = entry_file.parent().ok_or_else(|| {
+        CompileError::codegen("entry file has no parent directory")
+    })?.to_path_buf();
+
+    let pkg_graph = manifest::find_and_resolve(&entry_dir)?;
+    let fallback_stdlib = entry_dir.join("stdlib");
+    let stdlib = crate::resolve_stdlib(stdlib_root)
+        .or_else(|| fallback_stdlib.is_dir().then_some(fallback_stdlib));
+
+    let mut walker = ImportWalker {
+        stdlib: stdlib.as_deref(),
+        pkg_graph: &pkg_graph,
+        graph: Graph::default(),
+        ids: HashMap::new(),
+        entry_dir: entry_dir.clone(),
+    };
+
+    // Like resolution, siblings named after an import or a dep are modules, not part of the root
+    let mut source_map = SourceMap::new();
+    let (entry_prog, _) = modules::load_file_auto(&entry_file, &mut source_map)?;
+    let skip: HashSet<String> = entry_prog.imports.iter()
+        .map(|i| i.node.path[0].node.clone())
+        .chain(pkg_graph.root_deps().keys().cloned())
+        .collect();
+    let mut files = vec![entry_file.clone()];
+    for sibling in modules::collect_source_files(&entry_dir)? {
+        let stem = sibling.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        if sibling.canonicalize().unwrap_or(sibling.clone()) != entry_file && !skip.contains(stem) {
+            files.push(sibling);
+        }
+    }
+
+    let label = entry_file.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let (root, _) = walker.node(entry_file, label);
+    walker.walk(root, &files, &entry_dir, pkg_graph.root_deps())?;
+    Ok(walker.graph)
+}
+
+/// A module an import resolved to.
+struct Target {
+    /// Canonical path of the module's file or directory.
+    key: PathBuf,
+    files: Vec<PathBuf>,
+    /// Directory its own imports resolve against.
+    base_dir: PathBuf,
+    deps: DependencyScope,
+}
+
+struct ImportWalker<'a> {
+    stdlib: Option<&'a Path>,
+    pkg_graph: &'a PackageGraph,
+    graph: Graph,
+    ids: HashMap<PathBuf, usize>,
+    entry_dir: PathBuf,
+}
+
+impl ImportWalker<'_> {
+    /// Node for `key`, and whether it was just added.
+    fn node(&mut self, key: PathBuf, label: String) -> (usize, bool) {
+        if let Some(&id) = self.ids.get(&key) {
+            return (id, false);
+        }
+        let id = self.graph.nodes.len();
+        self.graph.nodes.push(label);
+        self.ids.insert(key, id);
+        (id, true)
+    }
+
+    fn walk(&mut self, id: usize, files: &[PathBuf], base_dir: &Path, deps: &DependencyScope) -> Result<(), CompileError> {
+        for file in files {
+            let (program, _) = modules::load_file_auto(file, &mut SourceMap::new())?;
+            for import in &program.imports {
+                for (label, target) in self.resolve(&import.node, import.span, base_dir, deps)? {
+                    let (target_id, added) = self.node(target.key, label);
+                    self.graph.add_edge(id, target_id);
+                    if added {
+                        self.walk(target_id, &target.files, &target.base_dir, &target.deps)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The modules an import binds, labelled by their dotted path. Like resolution,
+    /// a glob import (`import dir.*`) binds each file in the directory as its own
+    /// module, `dir.stem`.
+    fn resolve(&self, import: &ImportDecl, span: crate::span::Span, base_dir: &Path, deps: &DependencyScope) -> Result<Vec<(String, Target)>, CompileError> {
+        let target = self.resolve_target(import, span, base_dir, deps)?;
+        if !import.glob {
+            return Ok(vec![(import.full_path(), target)]);
+        }
+        let dir_path = import.path.iter().map(|s| s.node.as_str()).collect::<Vec<_>>().join(".");
+        Ok(target.files.iter().map(|file| {
+            let stem = file.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+            let module = Target {
+                key: file.canonicalize().unwrap_or_else(|_| file.clone()),
+                files: vec![file.clone()],
+                base_dir: target.base_dir.clone(),
+                deps: target.deps.clone(),
+            };
+            (format!("{dir_path}.{stem}"), module)
+        }).collect())
+    }
+
+    fn resolve_target(&self, import: &ImportDecl, span: crate::span::Span, base_dir: &Path, deps: &DependencyScope) -> Result<Target, CompileError> {
+        let path = &import.path;
+        let first = &path[0].node;
+        let not_found = || CompileError::syntax(
+            format!("cannot find module '{}': no directory or file found", import.full_path()),
+            path[0].span,
+        );
+
+        if let Some(dep_path) = deps.get(first) {
+            let dep_canonical = dep_path.canonicalize().map_err(|e| {
+                CompileError::codegen(format!("cannot resolve dep path '{}': {e}", dep_path.display()))
+            })?;
+            let dep_scope = self.pkg_graph.deps_for(&dep_canonical).clone();
+            return if import.glob {
+                self.glob_directory(&path[1..], &dep_canonical, dep_scope, span)
+            } else if path.len() == 1 {
+                self.directory(&dep_canonical, dep_scope)
+            } else {
+                self.resolve_path(&path[1..], &dep_canonical, dep_scope, span)
+            };
+        }
+
+        if (path.len() > 1 || import.glob) && first == "std" {
+            let Some(root) = self.stdlib else {
+                return Err(CompileError::syntax(
+                    format!("cannot import '{}': no stdlib root found", import.full_path()),
+                    span,
+                ));
+            };
+            return if import.glob {
+                self.glob_directory(&path[1..], root, deps.clone(), span)
+            } else {
+                self.resolve_path(&path[1..], root, deps.clone(), span)
+            };
+        }
+
+        if import.glob {
+            return self.glob_directory(path, base_dir, deps.clone(), span);
+        }
+        if path.len() > 1 {
+            return self.resolve_path(path, base_dir, deps.clone(), span);
+        }
+
+        let vendored = self.entry_dir.join(".deps").join(first);
+        if base_dir.join(first).is_dir() || modules::resolve_module_file(base_dir, first).is_some() {
+            self.resolve_path(path, base_dir, deps.clone(), span)
+        } else if base_dir == self.entry_dir && vendored.is_dir() {
+            self.directory(&vendored, deps.clone())
+        } else {
+            Err(not_found())
+        }
+    }
+
+    fn resolve_path(&self, segments: &[Spanned<String>], base_dir: &Path, deps: DependencyScope, span: crate::span::Span) -> Result<Target, CompileError> {
+        let mut dir = base_dir.to_path_buf();
+        for segment in &segments[..segments.len() - 1] {
+            dir = dir.join(&segment.node);
+            if !dir.is_dir() {
+                return Err(CompileError::syntax(
+                    format!("cannot find module path: '{}' is not a directory", dir.display()),
+                    span,
+                ));
+            }
+        }
+        let last = &segments[segments.len() - 1];
+        if let Some(file) = modules::resolve_module_file(&dir, &last.node) {
+            let key = file.canonicalize().unwrap_or_else(|_| file.clone());
+            return Ok(Target { key, files: vec![file], base_dir: dir, deps });
+        }
+        let module_dir = dir.join(&last.node);
+        if module_dir.is_dir() {
+            return self.directory(&module_dir, deps);
+        }
+        let full: Vec<&str> = segments.iter().map(|s| s.node.as_str()).collect();
+        Err(CompileError::syntax(
+            format!("cannot find module '{}': no directory or file found", full.join(".")),
+            last.span,
+        ))
+    }
+
+    /// The directory a glob import (`import dir.*`) names; `resolve` splits it into its files.
+    fn glob_directory(&self, segments: &[Spanned<String>], base_dir: &Path, deps: DependencyScope, span: crate::span::Span) -> Result<Target, CompileError> {
+        let dir = segments.iter().fold(base_dir.to_path_buf(), |dir, segment| dir.join(&segment.node));
+        if !dir.is_dir() {
+            return Err(CompileError::syntax(
+                format!("cannot find module path: '{}' is not a directory", dir.display()),
+                span,
+            ));
+        }
+        self.directory(&dir, deps)
+    }
+
+    fn directory(&self, dir: &Path, deps: DependencyScope) -> Result<Target, CompileError> {
+        Ok(Target {
+            key: dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf()),
+            files: modules::collect_source_files(dir)?,
+            base_dir: dir.to_path_buf(),
+            deps,
+        })
+    }
+}
+
+// ---- Call graph ----
+
+/// Build the call graph of `entry_file` after type checking. Nodes are the
+/// program's own functions and methods (`Type.method`), plus any imported
+/// function they call; method calls have no resolved target and aren't drawn.
+pub fn call_graph(entry_file: &Path, stdlib_root: Option<&Path>) -> Result<Graph, CompileError> {
+    let (program, _source, _derived, _warnings) = crate::analyze_file_with_warnings(entry_file, stdlib_root)?;
+
+    // (label, function, declared in the entry module); imported names carry a module prefix
+    let mut callers: Vec<(String, &Spanned<Function>, bool)> = Vec::new();
+    for f in &program.functions {
+        let name = &f.node.name.node;
+        callers.push((name.clone(), f, !f.span.is_synthetic() && !name.contains('.') && !name.starts_with("__")));
+    }
+    let methods = program.classes.iter().map(|c| (&c.node.name.node, c.span, &c.node.methods))
+        .chain(program.enums.iter().map(|e| (&e.node.name.node, e.span, &e.node.methods)))
+        .chain(program.app.iter().map(|a| (&a.node.name.node, a.span, &a.node.methods)))
+        .chain(program.stages.iter().map(|s| (&s.node.name.node, s.span, &s.node.methods)));
+    for (owner, span, owner_methods) in methods {
+        for m in owner_methods {
+            callers.push((format!("{owner}.{}", m.node.name.node), m, !span.is_synthetic() && !owner.contains('.')));
+        }
+    }
+    let labels: HashMap<Uuid, &str> = callers.iter().map(|(name, f, _)| (f.node.id, name.as_str())).collect();
+
+    let mut graph = Graph::default();
+    let mut ids: HashMap<Uuid, usize> = HashMap::new();
+    let mut node = |graph: &mut Graph, id: Uuid, label: &str| {
+        *ids.entry(id).or_insert_with(|| {
+            graph.nodes.push(label.to_string());
+            graph.nodes.len() - 1
+        })
+    };
+
+    // Imported and compiler-generated functions only appear when something calls them
+    for (name, func, _) in callers.iter().filter(|(_, _, local)| *local) {
+        let from = node(&mut graph, func.node.id, name);
+        let mut collector = CallCollector::default();
+        walk_function(&mut collector, func);
+        for target in collector.targets {
+            if let Some(label) = labels.get(&target) {
+                let to = node(&mut graph, target, label);
+                graph.add_edge(from, to);
+            }
+        }
+    }
+    Ok(graph)
+}
+
+#[derive(Default)]
+struct CallCollector {
+    targets: Vec<Uuid>,
+}
+
+impl Visitor for CallCollector {
+    fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+        if let Expr::Call { target_id: Some(id), .. } = &expr.node {
+            self.targets.push(*id);
+        }
+        walk_expr(self, expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycle_edges_cover_loops_only() {
+        let graph = Graph {
+            nodes: ["a", "b", "c", "d"].map(String::from).to_vec(),
+            edges: vec![(0, 1), (1, 2), (2, 1), (2, 3), (3, 3)],
+        };
+        let cycles = graph.cycle_edges();
+        assert_eq!(cycles, HashSet::from([(1, 2), (2, 1), (3, 3)]));
+        assert!(graph.to_dot("g").contains("n1 -> n2 [color=red];"));
+        assert!(graph.to_dot("g").contains("n0 -> n1;"));
+    }
+}
@@ -0,0 +1,233 @@
+//! Incremental analysis for editors that re-analyze on every keystroke.
+//!
+//! An [`AnalysisCache`] remembers, per entry file, a fingerprint of every file
+//! the last analysis loaded and the body-check facts typeck recorded
+//! (`typeck::BodyCache`). On the next analysis each file gets two hashes: its
+//! full content, and its content with every function body cut out (its
+//! "API"). A file whose content is unchanged keeps its body checks unless a
+//! file it may depend on changed API:
+//!
+//! - The root component (the entry file and the siblings merged into it) and
+//!   each of its imports (with everything that import pulls in) form
+//!   components. A component only sees its own files, and the root sees
+//!   everything, so an API change re-checks its own component and the root.
+//! - An API change in a file that declares nothing, or a change to which
+//!   files were loaded, re-checks everything.
+//!
+//! Registration and the whole-program passes after body checking (error
+//! inference, monomorphization, ...) still run every time.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use crate::modules::ModuleGraph;
+use crate::parser::ast::*;
+use crate::typeck::BodyCache;
+
+/// Which frontend an analysis ran, since each checks a differently shaped program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Pipeline {
+    Analyze,
+    AnalyzeStandalone,
+    Editing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileFingerprint {
+    path: PathBuf,
+    content: u64,
+    api: u64,
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+    /// Fingerprints matching the facts in `bodies`
+    files: Vec<FileFingerprint>,
+    /// Fingerprints of the analysis in progress, and `bodies.commits()` when it started
+    pending: Option<(Vec<FileFingerprint>, u64)>,
+    bodies: BodyCache,
+}
+
+/// Type-check results kept between analyses of the same entry files.
+#[derive(Debug, Default)]
+pub struct AnalysisCache {
+    entries: HashMap<(PathBuf, Pipeline), Entry>,
+    checked: usize,
+    reused: usize,
+}
+
+impl AnalysisCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of function bodies (and other body-check units) the last
+    /// successful analysis type-checked.
+    pub fn last_checked(&self) -> usize {
+        self.checked
+    }
+
+    /// Number of body-check units the last successful analysis reused.
+    pub fn last_reused(&self) -> usize {
+        self.reused
+    }
+
+    /// Fingerprint the files in `graph` and mark which of them can reuse
+    /// their body checks. Call `finish` once the analysis succeeds.
+    pub(crate) fn prepare(&mut self, entry_file: &Path, pipeline: Pipeline, graph: &ModuleGraph) -> &mut BodyCache {
+        let files = fingerprints(graph);
+        let entry = self.entries.entry((entry_file.to_path_buf(), pipeline)).or_default();
+
+        // An analysis that failed after typeck committed left facts the old
+        // fingerprints don't describe
+        if let Some((_, commits)) = entry.pending.take()
+            && commits != entry.bodies.commits()
+        {
+            entry.files.clear();
+        }
+
+        let reusable = reusable_files(&entry.files, &files, graph);
+        entry.bodies.set_reusable(reusable);
+        entry.pending = Some((files, entry.bodies.commits()));
+        &mut entry.bodies
+    }
+
+    /// Record that the analysis `prepare` started succeeded.
+    pub(crate) fn finish(&mut self, entry_file: &Path, pipeline: Pipeline) {
+        let Some(entry) = self.entries.get_mut(&(entry_file.to_path_buf(), pipeline)) else {
+            return;
+        };
+        if let Some((files, _)) = entry.pending.take() {
+            entry.files = files;
+        }
+        self.checked = entry.bodies.checked();
+        self.reused = entry.bodies.reused();
+    }
+}
+
+/// File ids whose body checks are still valid, going from `old` to `new`.
+fn reusable_files(old: &[FileFingerprint], new: &[FileFingerprint], graph: &ModuleGraph) -> HashSet<u32> {
+    let same_files = old.len() == new.len() && old.iter().zip(new).all(|(o, n)| o.path == n.path);
+    if !same_files {
+        return HashSet::new();
+    }
+
+    let components = components(graph);
+    let mut changed = HashSet::new();
+    let mut stale_components = HashSet::new();
+    for (id, (o, n)) in old.iter().zip(new).enumerate() {
+        let id = id as u32;
+        if o.content == n.content {
+            continue;
+        }
+        changed.insert(id);
+        if o.api != n.api {
+            let Some(&component) = components.get(&id) else {
+                return HashSet::new();
+            };
+            stale_components.insert(component);
+            stale_components.insert(0);
+        }
+    }
+
+    (0..new.len() as u32)
+        .filter(|id| !changed.contains(id))
+        .filter(|id| components.get(id).is_none_or(|c| !stale_components.contains(c)))
+        .collect()
+}
+
+/// Component of each file that declares something: 0 for the root program,
+/// `i + 1` for the program of the `i`th import.
+fn components(graph: &ModuleGraph) -> HashMap<u32, usize> {
+    let mut components = HashMap::new();
+    let programs = std::iter::once(&graph.root).chain(graph.imports.iter().map(|(_, p, _)| p));
+    for (component, program) in programs.enumerate() {
+        for file in declared_files(program) {
+            components.entry(file).or_insert(component);
+        }
+    }
+    components
+}
+
+fn declared_files(program: &Program) -> HashSet<u32> {
+    let mut files = HashSet::new();
+    files.extend(program.imports.iter().map(|d| d.span.file_id));
+    files.extend(program.functions.iter().map(|d| d.span.file_id));
+    files.extend(program.extern_fns.iter().map(|d| d.span.file_id));
+    files.extend(program.classes.iter().map(|d| d.span.file_id));
+    files.extend(program.traits.iter().map(|d| d.span.file_id));
+    files.extend(program.enums.iter().map(|d| d.span.file_id));
+    files.extend(program.consts.iter().map(|d| d.span.file_id));
+    files.extend(program.globals.iter().map(|d| d.span.file_id));
+    files.extend(program.errors.iter().map(|d| d.span.file_id));
+    files.extend(program.stages.iter().map(|d| d.span.file_id));
+    files.extend(program.app.iter().map(|d| d.span.file_id));
+    files
+}
+
+fn fingerprints(graph: &ModuleGraph) -> Vec<FileFingerprint> {
+    let mut bodies: HashMap<u32, Vec<(usize, usize)>> = HashMap::new();
+    let programs = std::iter::once(&graph.root).chain(graph.imports.iter().map(|(_, p, _)| p));
+    for program in programs {
+        collect_body_spans(program, &mut bodies);
+    }
+
+    graph.source_map.files.iter().enumerate()
+        .map(|(id, (path, source))| {
+            let mut ranges = bodies.remove(&(id as u32)).unwrap_or_default();
+            ranges.sort_unstable();
+            FileFingerprint {
+                path: path.clone(),
+                content: hash(source.as_bytes()),
+                api: hash(&without_ranges(source, &ranges)),
+            }
+        })
+        .collect()
+}
+
+fn collect_body_spans(program: &Program, bodies: &mut HashMap<u32, Vec<(usize, usize)>>) {
+    let mut add = |block: &crate::span::Spanned<Block>| {
+        bodies.entry(block.span.file_id).or_default().push((block.span.start, block.span.end));
+    };
+    let methods = program.classes.iter().flat_map(|c| &c.node.methods)
+        .chain(program.enums.iter().flat_map(|e| &e.node.methods))
+        .chain(program.app.iter().flat_map(|a| &a.node.methods))
+        .chain(program.stages.iter().flat_map(|s| &s.node.methods));
+    for func in program.functions.iter().chain(methods) {
+        add(&func.node.body);
+    }
+    for tr in &program.traits {
+        for method in &tr.node.methods {
+            if let Some(body) = &method.body {
+                add(body);
+            }
+        }
+    }
+}
+
+/// `source` with the sorted byte `ranges` removed. Nested ranges are covered
+/// by the outer one.
+fn without_ranges(source: &str, ranges: &[(usize, usize)]) -> Vec<u8> {
+    let bytes = source.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut pos = 0;
+    for &(start, end) in ranges {
+        let (start, end) = (start.min(bytes.len()), end.min(bytes.len()));
+        if start >= pos {
+            out.extend_from_slice(&bytes[pos..start]);
+            pos = end;
+        } else {
+            pos = pos.max(end);
+        }
+    }
+    out.extend_from_slice(&bytes[pos..]);
+    out
+}
+
+fn hash(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
@@ -162,6 +162,51 @@ fn process_escapes(raw: &str, string_span: Span, quote_prefix_len: usize) -> Res
     Ok(result)
 }
 
+/// Strip the layout of a `"""` literal's raw content. A newline right after
+/// the opening quotes is dropped. When the closing quotes sit on their own
+/// line, that line's indentation is removed from every line, together with
+/// the line break before the closing quotes. Whitespace-only lines become
+/// empty; lines indented less than the closing quotes are kept as written.
+fn dedent_triple_quoted(raw: &str) -> String {
+    let body = raw.strip_prefix("\r\n").or_else(|| raw.strip_prefix('\n')).unwrap_or(raw);
+    let Some(last_nl) = body.rfind('\n') else {
+        return body.to_string();
+    };
+    let indent = &body[last_nl + 1..];
+    if !indent.chars().all(|c| c == ' ' || c == '\t') {
+        return body.to_string();
+    }
+    let content = &body[..last_nl];
+    let content = content.strip_suffix('\r').unwrap_or(content);
+    content
+        .split('\n')
+        .map(|line| match line.strip_prefix(indent) {
+            Some(rest) => rest,
+            None if line.trim().is_empty() => "",
+            None => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Collect the comments that `lex` discards, with their spans, so editing
+/// tools can write them back. `// @uuid:` hints are left out: the pretty
+/// printer regenerates those itself.
+pub fn lex_comments(source: &str) -> Vec<Spanned<String>> {
+    let mut comments = Vec::new();
+    let mut lexer = Token::lexer(source);
+    while let Some(result) = lexer.next() {
+        if matches!(result, Ok(Token::Comment)) {
+            let span = lexer.span();
+            let text = source[span.start..span.end].trim_end();
+            if !text.starts_with("// @uuid:") {
+                comments.push(Spanned::new(text.to_string(), Span::new(span.start, span.start + text.len())));
+            }
+        }
+    }
+    comments
+}
+
 pub fn lex(source: &str) -> Result<Vec<Spanned<Token>>, CompileError> {
     let mut tokens = Vec::new();
     let mut lexer = Token::lexer(source);
@@ -170,8 +215,12 @@ pub fn lex(source: &str) -> Result<Vec<Spanned<Token>>, CompileError> {
         let span = lexer.span();
         match result {
             Ok(tok) => {
-                // Skip comments
+                // Skip comments. A block comment spanning lines still ends
+                // the statement it sits in, like the newlines it replaces.
                 if matches!(tok, Token::Comment) {
+                    if source[span.start..span.end].contains('\n') {
+                        tokens.push(Spanned::new(Token::Newline, Span::new(span.start, span.end)));
+                    }
                     continue;
                 }
                 tokens.push(Spanned::new(tok, Span::new(span.start, span.end)));
@@ -179,12 +228,37 @@ pub fn lex(source: &str) -> Result<Vec<Spanned<Token>>, CompileError> {
             Err(()) => {
                 let slice = &source[span.start..span.end];
 
+                if slice.trim_start_matches('f').starts_with("\"\"\"") {
+                    return Err(CompileError::syntax(
+                        "unterminated triple-quoted string".to_string(),
+                        Span::new(span.start, span.start + slice.find('"').unwrap_or(0) + 3),
+                    ));
+                }
+
+                if slice.starts_with("/*") {
+                    return Err(CompileError::syntax(
+                        "unterminated block comment".to_string(),
+                        Span::new(span.start, span.start + 2),
+                    ));
+                }
+
+                // A float whose exponent has no digits (e.g. `1e`, `2.5E-`)
+                let is_float_missing_exponent = slice.starts_with(|c: char| c.is_ascii_digit())
+                    && slice.ends_with(['e', 'E', '+', '-']);
+                if is_float_missing_exponent {
+                    return Err(CompileError::syntax(
+                        format!("expected digits after exponent in float literal: {}", slice),
+                        Span::new(span.start, span.end),
+                    ));
+                }
+
                 // Check if this looks like an integer literal that's out of range
                 let is_number = slice.chars().all(|c| c.is_ascii_digit() || c == '_');
                 let is_hex = slice.starts_with("0x") || slice.starts_with("0X");
                 let is_binary = slice.starts_with("0b") || slice.starts_with("0B");
+                let is_octal = slice.starts_with("0o") || slice.starts_with("0O");
 
-                if is_number || is_hex || is_binary {
+                if is_number || is_hex || is_binary || is_octal {
                     // Special handling for binary literals with invalid digits
                     if is_binary {
                         let bin_part = &slice[2..];
@@ -207,10 +281,30 @@ pub fn lex(source: &str) -> Result<Vec<Spanned<Token>>, CompileError> {
                         }
                     }
 
+                    // Special handling for octal literals with invalid digits
+                    if is_octal {
+                        let oct_part = &slice[2..];
+
+                        // Check for empty octal literal
+                        if oct_part.is_empty() {
+                            return Err(CompileError::syntax(
+                                format!("expected digits after octal prefix: {}", slice),
+                                Span::new(span.start, span.end),
+                            ));
+                        }
+
+                        // Check for invalid digits in octal literal
+                        let has_non_octal = oct_part.chars().any(|c| !('0'..='7').contains(&c) && c != '_');
+                        if has_non_octal {
+                            return Err(CompileError::syntax(
+                                format!("invalid digit in octal literal: {}", slice),
+                                Span::new(span.start, span.end),
+                            ));
+                        }
+                    }
+
                     // Try parsing as i128 to see if it's just out of range
-                    let cleaned = if is_hex {
-                        slice[2..].replace('_', "")
-                    } else if is_binary {
+                    let cleaned = if is_hex || is_binary || is_octal {
                         slice[2..].replace('_', "")
                     } else {
                         slice.replace('_', "")
@@ -220,6 +314,8 @@ pub fn lex(source: &str) -> Result<Vec<Spanned<Token>>, CompileError> {
                         i128::from_str_radix(&cleaned, 16)
                     } else if is_binary {
                         i128::from_str_radix(&cleaned, 2)
+                    } else if is_octal {
+                        i128::from_str_radix(&cleaned, 8)
                     } else {
                         cleaned.parse::<i128>()
                     };
@@ -253,7 +349,16 @@ pub fn lex(source: &str) -> Result<Vec<Spanned<Token>>, CompileError> {
 
     // Process escape sequences in string literals
     for token in &mut tokens {
+        let triple = source[token.span.start..].trim_start_matches('f').starts_with("\"\"\"");
         match &token.node {
+            Token::StringLit(raw) if triple => {
+                let processed = process_escapes(&dedent_triple_quoted(raw), token.span, 3)?;
+                token.node = Token::StringLit(processed);
+            }
+            Token::FStringLit(raw) if triple => {
+                let processed = process_escapes(&dedent_triple_quoted(raw), token.span, 4)?;
+                token.node = Token::FStringLit(processed);
+            }
             Token::StringLit(raw) => {
                 let processed = process_escapes(raw, token.span, 1)?;
                 token.node = Token::StringLit(processed);
@@ -345,6 +450,65 @@ mod tests {
         assert!(tokens.iter().all(|t| !matches!(t.node, Token::Comment)));
     }
 
+    #[test]
+    fn lex_nested_block_comment_skipped() {
+        let src = "let x = 1 /* outer /* inner */ still comment */ + 2";
+        let tokens = lex(src).unwrap();
+        let kinds: Vec<_> = tokens.iter().map(|t| t.node.clone()).collect();
+        assert_eq!(kinds, vec![
+            Token::Let, Token::Ident, Token::Eq, Token::IntLit(1), Token::Plus, Token::IntLit(2),
+        ]);
+    }
+
+    #[test]
+    fn lex_multiline_block_comment_ends_statement() {
+        let src = "let x = 1 /* a\nb */ let y = 2";
+        let tokens = lex(src).unwrap();
+        assert!(matches!(tokens[4].node, Token::Newline));
+        assert!(matches!(tokens[5].node, Token::Let));
+    }
+
+    #[test]
+    fn lex_unterminated_block_comment_errors_at_opener() {
+        let src = "let x = 1\n/* open /* nested */ never closed\nlet y = 2";
+        let err = lex(src).unwrap_err();
+        match err {
+            CompileError::Syntax { msg, span } => {
+                assert_eq!(msg, "unterminated block comment");
+                assert_eq!(span.start, 10);
+                assert_eq!(span.end, 12);
+            }
+            other => panic!("expected syntax error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn lex_triple_quoted_string_dedents() {
+        let src = "let s = \"\"\"\n    a\n      \"b\"\n\n    c\n    \"\"\"";
+        let tokens = lex(src).unwrap();
+        assert_eq!(tokens[3].node, Token::StringLit("a\n  \"b\"\n\nc".to_string()));
+        assert_eq!(tokens.len(), 4);
+    }
+
+    #[test]
+    fn lex_triple_quoted_string_inline_and_fstring() {
+        let tokens = lex("\"\"\"say \"hi\" now\"\"\" f\"\"\"\n  {x}\n  \"\"\"").unwrap();
+        assert_eq!(tokens[0].node, Token::StringLit("say \"hi\" now".to_string()));
+        assert_eq!(tokens[1].node, Token::FStringLit("{x}".to_string()));
+    }
+
+    #[test]
+    fn lex_unterminated_triple_quoted_string_errors_at_opener() {
+        let src = "let s = \"\"\"abc\nlet y = 2";
+        match lex(src).unwrap_err() {
+            CompileError::Syntax { msg, span } => {
+                assert_eq!(msg, "unterminated triple-quoted string");
+                assert_eq!((span.start, span.end), (8, 11));
+            }
+            other => panic!("expected syntax error, got {other:?}"),
+        }
+    }
+
     #[test]
     fn lex_reserved_keywords() {
         // Test all language keywords
@@ -400,6 +564,22 @@ mod tests {
         assert!(matches!(tokens[9].node, Token::Percent));  // %
     }
 
+    #[test]
+    fn lex_octal_invalid_digit_error() {
+        let err = lex("0o18").unwrap_err();
+        assert!(err.to_string().contains("invalid digit in octal literal: 0o18"));
+        let err = lex("0o").unwrap_err();
+        assert!(err.to_string().contains("expected digits after octal prefix"));
+    }
+
+    #[test]
+    fn lex_float_missing_exponent_digits_error() {
+        let err = lex("1e").unwrap_err();
+        assert!(err.to_string().contains("expected digits after exponent in float literal: 1e"));
+        let err = lex("let x = 2.5E- 3").unwrap_err();
+        assert!(err.to_string().contains("2.5E-"));
+    }
+
     #[test]
     fn lex_multiple_decimal_points_adjacent_error() {
         // Test the validation that catches 1.2.3 (float followed by dot)
@@ -435,7 +615,7 @@ mod tests {
     #[test]
     fn lex_unexpected_character_error() {
         // Test that unexpected characters produce errors
-        let src = "let x = @";
+        let src = "let x = $";
         let result = lex(src);
         assert!(result.is_err());
         let err = result.unwrap_err();
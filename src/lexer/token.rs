@@ -38,12 +38,22 @@ pub enum Token {
     Raise,
     #[token("catch")]
     Catch,
+    #[token("try")]
+    Try,
+    #[token("finally")]
+    Finally,
+    #[token("is")]
+    Is,
     #[token("spawn")]
     Spawn,
     #[token("serve")]
     Serve,
     #[token("enum")]
     Enum,
+    #[token("const")]
+    Const,
+    #[token("global")]
+    Global,
     #[token("impl")]
     Impl,
     #[token("self")]
@@ -104,9 +114,9 @@ pub enum Token {
     Stream,
 
     // Literals
-    // Note: hex and binary patterns use \w* to match any characters after 0x/0b,
+    // Note: hex, binary and octal patterns use \w* to match any characters after 0x/0b/0o,
     // which are then validated by the callback for better error messages
-    #[regex(r"0[xX][\w]*|0[bB][\w]*|[0-9][0-9_]*", |lex| {
+    #[regex(r"0[xX][\w]*|0[bB][\w]*|0[oO][\w]*|[0-9][0-9_]*", |lex| {
         let s = lex.slice();
         if s.starts_with("0x") || s.starts_with("0X") {
             let hex_part = &s[2..];
@@ -168,6 +178,36 @@ pub enum Token {
                 Ok(val) if val >= i64::MIN as i128 && val <= i64::MAX as i128 + 1 => Some(val as i64),
                 _ => None,
             }
+        } else if s.starts_with("0o") || s.starts_with("0O") {
+            let oct_part = &s[2..];
+
+            // Reject empty octal (just "0o")
+            if oct_part.is_empty() {
+                return None;
+            }
+
+            // Reject leading underscore (0o_17)
+            if oct_part.starts_with('_') {
+                return None;
+            }
+
+            // Reject trailing underscore (0o17_)
+            if oct_part.ends_with('_') {
+                return None;
+            }
+
+            // Validate all characters are octal digits or underscores
+            if !oct_part.chars().all(|c| ('0'..='7').contains(&c) || c == '_') {
+                return None;
+            }
+
+            let cleaned = oct_part.replace('_', "");
+            // Parse as i128 first, then validate range
+            // Accept i64::MIN..=i64::MAX, plus (i64::MAX + 1) for the i64::MIN literal special case
+            match i128::from_str_radix(&cleaned, 8) {
+                Ok(val) if val >= i64::MIN as i128 && val <= i64::MAX as i128 + 1 => Some(val as i64),
+                _ => None,
+            }
         } else {
             // Parse as i128 first, then validate range
             // Accept i64::MIN..=i64::MAX, plus (i64::MAX + 1) for the i64::MIN literal special case
@@ -183,19 +223,23 @@ pub enum Token {
     })]
     IntLit(i64),
 
-    #[regex(r"[0-9][0-9_]*\.[0-9][0-9_]*([eE][+-]?[0-9][0-9_]*)?|[0-9][0-9_]*[eE][+-]?[0-9][0-9_]*", priority = 3, callback = |lex| lex.slice().replace('_', "").parse::<f64>().ok())]
+    // The last alternative matches an exponent with no digits (`1e`, `2.5E-`) so the
+    // parse fails and lex() reports it, rather than splitting it into `1` and `e`.
+    #[regex(r"[0-9][0-9_]*\.[0-9][0-9_]*([eE][+-]?[0-9][0-9_]*)?|[0-9][0-9_]*[eE][+-]?[0-9][0-9_]*|[0-9][0-9_]*(\.[0-9][0-9_]*)?[eE][+-]?", priority = 3, callback = |lex| lex.slice().replace('_', "").parse::<f64>().ok())]
     FloatLit(f64),
 
     #[regex(r#"f"([^"\\]|\\.)*""#, |lex| {
         let s = lex.slice();
         Some(s[2..s.len()-1].to_string())  // Strip f" and ", return raw content
     })]
+    #[token("f\"\"\"", triple_quoted)]
     FStringLit(String),
 
     #[regex(r#""([^"\\]|\\.)*""#, |lex| {
         let s = lex.slice();
         Some(s[1..s.len()-1].to_string())  // Strip " and ", return raw content
     })]
+    #[token("\"\"\"", triple_quoted)]
     StringLit(String),
 
     // Identifiers
@@ -289,6 +333,8 @@ pub enum Token {
     Dot,
     #[token("?")]
     Question,
+    #[token("@")]
+    At,
 
     // Newline (significant for statement termination)
     // Supports both LF (\n) and CRLF (\r\n) line endings
@@ -297,13 +343,61 @@ pub enum Token {
 
     // Comments (skip)
     #[regex(r"//[^\n]*")]
+    #[token("/*", block_comment)]
     Comment,
 }
 
+/// Consume the rest of a `"""` string whose opening quotes were just matched
+/// and return its raw content. Backslash escapes are skipped so `\"""` does
+/// not close the string. Fails if the closing `"""` is missing.
+fn triple_quoted(lex: &mut logos::Lexer<Token>) -> Option<String> {
+    let rest = lex.remainder();
+    let bytes = rest.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' {
+            i += 2;
+        } else if bytes[i..].starts_with(b"\"\"\"") {
+            lex.bump(i + 3);
+            return Some(rest[..i].to_string());
+        } else {
+            i += 1;
+        }
+    }
+    lex.bump(rest.len());
+    None
+}
+
+/// Consume the rest of a `/* ... */` comment whose opener was just matched.
+/// Block comments nest, so `/* a /* b */ c */` is one comment. Fails (and
+/// consumes the rest of the input) if the comment is never closed.
+fn block_comment(lex: &mut logos::Lexer<Token>) -> bool {
+    let rest = lex.remainder().as_bytes();
+    let mut depth = 1;
+    let mut i = 0;
+    while i < rest.len() {
+        if rest[i..].starts_with(b"/*") {
+            depth += 1;
+            i += 2;
+        } else if rest[i..].starts_with(b"*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                lex.bump(i);
+                return true;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    lex.bump(rest.len());
+    false
+}
+
 /// Returns true if the given string is a Pluto keyword.
 pub fn is_keyword(s: &str) -> bool {
     matches!(s, "fn" | "let" | "mut" | "return" | "if" | "else" | "while" | "true" | "false"
-        | "class" | "trait" | "app" | "inject" | "error" | "raise" | "catch" | "spawn" | "serve"
+        | "class" | "trait" | "app" | "inject" | "error" | "raise" | "catch" | "try" | "finally" | "is" | "spawn" | "serve"
         | "enum" | "impl" | "self" | "pub" | "for" | "in" | "break" | "continue"
         | "match" | "import" | "as" | "extern" | "uses" | "ambient" | "tests" | "test"
         | "invariant" | "requires" | "assert" | "select" | "default"
@@ -330,9 +424,14 @@ impl std::fmt::Display for Token {
             Token::Error => write!(f, "error"),
             Token::Raise => write!(f, "raise"),
             Token::Catch => write!(f, "catch"),
+            Token::Try => write!(f, "try"),
+            Token::Finally => write!(f, "finally"),
+            Token::Is => write!(f, "is"),
             Token::Spawn => write!(f, "spawn"),
             Token::Serve => write!(f, "serve"),
             Token::Enum => write!(f, "enum"),
+            Token::Const => write!(f, "const"),
+            Token::Global => write!(f, "global"),
             Token::Impl => write!(f, "impl"),
             Token::SelfVal => write!(f, "self"),
             Token::Pub => write!(f, "pub"),
@@ -409,6 +508,7 @@ impl std::fmt::Display for Token {
             Token::DotDot => write!(f, ".."),
             Token::Dot => write!(f, "."),
             Token::Question => write!(f, "?"),
+            Token::At => write!(f, "@"),
             Token::Newline => write!(f, "newline"),
             Token::Comment => write!(f, "comment"),
         }
@@ -425,7 +525,7 @@ mod tests {
     fn test_is_keyword_all_keywords() {
         let keywords = vec![
             "fn", "let", "mut", "return", "if", "else", "while", "true", "false",
-            "class", "trait", "app", "inject", "error", "raise", "catch", "spawn",
+            "class", "trait", "app", "inject", "error", "raise", "catch", "try", "finally", "is", "spawn",
             "serve",
             "enum", "impl", "self", "pub", "for", "in", "break", "continue",
             "match", "import", "as", "extern", "uses", "ambient", "tests", "test",
@@ -495,6 +595,8 @@ mod tests {
         assert_eq!(Token::Error.to_string(), "error");
         assert_eq!(Token::Raise.to_string(), "raise");
         assert_eq!(Token::Catch.to_string(), "catch");
+        assert_eq!(Token::Try.to_string(), "try");
+        assert_eq!(Token::Finally.to_string(), "finally");
     }
 
     #[test]
@@ -518,6 +620,7 @@ mod tests {
     #[test]
     fn test_display_enum() {
         assert_eq!(Token::Enum.to_string(), "enum");
+        assert_eq!(Token::Is.to_string(), "is");
     }
 
     #[test]
@@ -625,6 +728,7 @@ mod tests {
         assert_eq!(Token::Arrow.to_string(), "->");
         assert_eq!(Token::FatArrow.to_string(), "=>");
         assert_eq!(Token::Question.to_string(), "?");
+        assert_eq!(Token::At.to_string(), "@");
     }
 
     #[test]
@@ -868,19 +972,18 @@ mod tests {
     }
 
     #[test]
-    fn test_e_without_digits_is_not_scientific() {
+    fn test_e_without_digits_is_error() {
         let mut lex = Token::lexer("1e");
-        assert_eq!(lex.next(), Some(Ok(Token::IntLit(1))));
-        assert_eq!(lex.next(), Some(Ok(Token::Ident))); // 'e' as identifier
+        assert_eq!(lex.next(), Some(Err(())));
+        let mut lex = Token::lexer("2.5E-");
+        assert_eq!(lex.next(), Some(Err(())));
     }
 
     #[test]
-    fn test_e_minus_without_digits_is_not_scientific() {
-        // "1e-" — logos backtracks: IntLit(1), then Ident(e), then Minus
+    fn test_e_minus_without_digits_is_error() {
+        // "1e-" is a float with a missing exponent, not IntLit(1), Ident(e), Minus
         let mut lex = Token::lexer("1e-");
-        assert_eq!(lex.next(), Some(Ok(Token::IntLit(1))));
-        assert_eq!(lex.next(), Some(Ok(Token::Ident)));
-        assert_eq!(lex.next(), Some(Ok(Token::Minus)));
+        assert_eq!(lex.next(), Some(Err(())));
     }
 
     // ===== String escape sequence tests =====
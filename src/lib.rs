@@ -11,6 +11,7 @@ pub mod monomorphize;
 pub mod prelude;
 pub mod reflection;
 pub mod ambient;
+pub mod enum_methods;
 pub mod spawn;
 pub mod contracts;
 pub mod marshal;
@@ -26,10 +27,14 @@ pub mod plto_store;
 pub mod stages;
 pub mod cache;
 pub mod watch;
+pub mod repl;
 pub mod coverage;
 pub mod toolchain;
 pub mod server;
 pub mod docs;
+pub mod graph;
+pub mod incremental;
+pub mod scaffold;
 
 use diagnostics::{CompileError, CompileWarning};
 use std::path::{Path, PathBuf};
@@ -51,19 +56,33 @@ struct FrontendResult {
 /// Run the frontend for editing/analysis: prelude → stages → ambient → type check.
 /// Stops BEFORE transformations (spawn desugar, monomorphize, closure lift, reflection).
 /// This preserves the canonical (pre-transformation) AST for emit-ast and analyze.
-fn run_frontend_for_editing(program: &mut Program) -> Result<FrontendResult, CompileError> {
+fn run_frontend_for_editing(program: &mut Program, bodies: Option<&mut typeck::BodyCache>) -> Result<FrontendResult, CompileError> {
     prelude::inject_prelude(program)?;
+    enum_methods::lower_enum_methods(program)?;
     stages::flatten_stage_hierarchy(program)?;
     ambient::desugar_ambient(program)?;
     contracts::validate_contracts(program)?;
-    let (env, warnings) = typeck::type_check(program)?;
+    let (env, warnings) = type_check_with(program, bodies)?;
     Ok(FrontendResult { env, warnings })
 }
 
+/// Type check, reusing body checks from `bodies` when the caller keeps an incremental cache.
+fn type_check_with(program: &Program, bodies: Option<&mut typeck::BodyCache>) -> Result<(typeck::env::TypeEnv, Vec<CompileWarning>), CompileError> {
+    match bodies {
+        Some(bodies) => typeck::type_check_incremental(program, bodies),
+        None => typeck::type_check(program),
+    }
+}
+
 /// Run the full frontend pipeline for compilation: editing pipeline + transformations.
 /// This mutates the AST with spawn desugaring, monomorphization, closure lifting, etc.
 fn run_frontend(program: &mut Program, test_mode: bool) -> Result<FrontendResult, CompileError> {
+    run_frontend_cached(program, test_mode, None)
+}
+
+fn run_frontend_cached(program: &mut Program, test_mode: bool, bodies: Option<&mut typeck::BodyCache>) -> Result<FrontendResult, CompileError> {
     prelude::inject_prelude(program)?;
+    enum_methods::lower_enum_methods(program)?;
     stages::flatten_stage_hierarchy(program)?;
     ambient::desugar_ambient(program)?;
     spawn::desugar_spawn(program)?;
@@ -76,7 +95,7 @@ fn run_frontend(program: &mut Program, test_mode: bool) -> Result<FrontendResult
     }
     contracts::validate_contracts(program)?;
     marshal::generate_marshalers_phase_a(program)?;
-    let (mut env, warnings) = typeck::type_check(program)?;
+    let (mut env, warnings) = type_check_with(program, bodies)?;
     reflection::generate_type_info_impls(program, &env)?;
     monomorphize::monomorphize(program, &mut env)?;
     marshal::generate_marshalers_phase_b(program, &env)?;
@@ -99,55 +118,129 @@ pub fn parse_source(source: &str) -> Result<Program, CompileError> {
     Ok(program)
 }
 
+/// Format source text: parse it for editing and pretty-print it back. UUID hints
+/// in the source are reattached to their declarations and written out again, and
+/// the result must itself parse, so formatting never produces broken source.
+pub fn format_source(source: &str) -> Result<String, CompileError> {
+    let mut program = parse_for_editing(source)?;
+    let has_hints = pretty::restore_uuid_hints(&mut program, source);
+    if has_hints {
+        // Cross-references name declarations by UUID; re-resolve against the restored ones
+        xref::resolve_cross_refs(&mut program);
+    }
+    let formatted = pretty::pretty_print(&program, has_hints);
+    parse_source(&formatted).map_err(|e| {
+        CompileError::internal(format!("formatted output no longer parses: {e}"))
+    })?;
+    Ok(formatted)
+}
+
 /// Parse source for editing — no transforms (no monomorphize, no closure lift, no spawn desugar).
 /// Does NOT inject prelude (avoids serializing Option<T> etc. into user source).
 /// Resolves cross-references so xref IDs are available for user-defined declarations.
+/// Attaches comments so the pretty printer can write them back.
 pub fn parse_for_editing(source: &str) -> Result<parser::ast::Program, CompileError> {
     let tokens = lexer::lex(source)?;
     let mut parser = parser::Parser::new(&tokens, source);
     let mut program = parser.parse_program()?;
+    crate::parser::comments::attach_comments(&mut program, source, 0);
     xref::resolve_cross_refs(&mut program);
     Ok(program)
 }
 
+/// Default stack size for the compilation thread. Deeply nested expressions
+/// (e.g. classes with 100+ fields where the sum expression creates a deeply
+/// nested BinOp tree) recurse through the parser, typeck and codegen, and the
+/// default thread stack (typically 2-8MB) can overflow with ~100 levels.
+const DEFAULT_COMPILER_STACK_SIZE: usize = 16 * 1024 * 1024;
+
+/// Stack size for the compilation thread: `PLUTO_STACK_SIZE` if set, else 16MB.
+fn compiler_stack_size() -> Result<usize, CompileError> {
+    match std::env::var("PLUTO_STACK_SIZE") {
+        Ok(value) => parse_stack_size(&value).ok_or_else(|| {
+            CompileError::codegen(format!(
+                "invalid PLUTO_STACK_SIZE '{value}': expected a byte count such as 67108864 or 64M"
+            ))
+        }),
+        Err(_) => Ok(DEFAULT_COMPILER_STACK_SIZE),
+    }
+}
+
+/// Parse a byte count with an optional `K`/`M`/`G` suffix (powers of 1024).
+fn parse_stack_size(value: &str) -> Option<usize> {
+    let value = value.trim();
+    let (digits, multiplier) = match value.char_indices().last()? {
+        (i, 'k' | 'K') => (&value[..i], 1024),
+        (i, 'm' | 'M') => (&value[..i], 1024 * 1024),
+        (i, 'g' | 'G') => (&value[..i], 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+    let size = digits.parse::<usize>().ok()?.checked_mul(multiplier)?;
+    (size > 0).then_some(size)
+}
+
+/// Run `f` on a thread with a larger stack (see `compiler_stack_size`).
+/// A panic on that thread is reported as an internal compiler error.
+fn run_on_compiler_thread<T: Send>(
+    f: impl FnOnce() -> Result<T, CompileError> + Send,
+) -> Result<T, CompileError> {
+    run_with_stack_size(compiler_stack_size()?, f)
+}
+
+fn run_with_stack_size<T: Send>(
+    stack_size: usize,
+    f: impl FnOnce() -> Result<T, CompileError> + Send,
+) -> Result<T, CompileError> {
+    std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(stack_size)
+            .spawn_scoped(scope, f)
+            .map_err(|e| CompileError::internal(format!("failed to spawn compilation thread: {e}")))?
+            .join()
+            .unwrap_or_else(|payload| Err(ice_from_panic(payload)))
+    })
+}
+
+/// Run `f`, turning a panic inside it into an internal compiler error
+/// instead of unwinding into the caller (the CLI or a long-lived server).
+pub fn catch_ice<T>(f: impl FnOnce() -> Result<T, CompileError>) -> Result<T, CompileError> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(f))
+        .unwrap_or_else(|payload| Err(ice_from_panic(payload)))
+}
+
+fn ice_from_panic(payload: Box<dyn std::any::Any + Send>) -> CompileError {
+    let msg = if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    };
+    CompileError::internal(msg)
+}
+
 /// Compile a source string to object bytes (lex → parse → prelude → typeck → monomorphize → closures → codegen).
 /// No file I/O or linking. Useful for compile-fail tests that only need to check errors.
 pub fn compile_to_object(source: &str) -> Result<Vec<u8>, CompileError> {
-    // Run compilation on a thread with a larger stack (16MB) to handle deeply nested expressions
-    // like classes with 100+ fields where the sum expression creates a deeply nested BinOp tree.
-    // Default stack size (typically 2-8MB) can overflow with ~100 levels of recursion.
-    let source = source.to_string();
-    std::thread::Builder::new()
-        .stack_size(16 * 1024 * 1024)
-        .spawn(move || {
-            let mut program = parse_source(&source)?;
-            // Resolve QualifiedAccess for single-file programs (no module flattening)
-            modules::resolve_qualified_access_single_file(&mut program)?;
-            let result = run_frontend(&mut program, false)?;
-            codegen::codegen(&program, &result.env, &source, None)
-        })
-        .expect("failed to spawn compilation thread")
-        .join()
-        .expect("compilation thread panicked")
+    run_on_compiler_thread(|| {
+        let mut program = parse_source(source)?;
+        // Resolve QualifiedAccess for single-file programs (no module flattening)
+        modules::resolve_qualified_access_single_file(&mut program)?;
+        let result = run_frontend(&mut program, false)?;
+        codegen::codegen(&program, &result.env, source, None, false)
+    })
 }
 
 /// Compile a source string and return both the object bytes and any compiler warnings.
 pub fn compile_to_object_with_warnings(source: &str) -> Result<(Vec<u8>, Vec<CompileWarning>), CompileError> {
-    // Run compilation on a thread with a larger stack (16MB) to handle deeply nested expressions
-    let source = source.to_string();
-    std::thread::Builder::new()
-        .stack_size(16 * 1024 * 1024)
-        .spawn(move || {
-            let mut program = parse_source(&source)?;
-            // Resolve QualifiedAccess for single-file programs (no module flattening)
-            modules::resolve_qualified_access_single_file(&mut program)?;
-            let result = run_frontend(&mut program, false)?;
-            let obj = codegen::codegen(&program, &result.env, &source, None)?;
-            Ok((obj, result.warnings))
-        })
-        .expect("failed to spawn compilation thread")
-        .join()
-        .expect("compilation thread panicked")
+    run_on_compiler_thread(|| {
+        let mut program = parse_source(source)?;
+        // Resolve QualifiedAccess for single-file programs (no module flattening)
+        modules::resolve_qualified_access_single_file(&mut program)?;
+        let result = run_frontend(&mut program, false)?;
+        let obj = codegen::codegen(&program, &result.env, source, None, false)?;
+        Ok((obj, result.warnings))
+    })
 }
 
 /// Compile a source string directly (single-file, no module resolution).
@@ -169,20 +262,13 @@ pub fn compile(source: &str, output_path: &Path) -> Result<(), CompileError> {
 /// Compile a source string in test mode (lex → parse → prelude → typeck → monomorphize → closures → codegen).
 /// Tests are preserved and a test runner main is generated.
 pub fn compile_to_object_test_mode(source: &str) -> Result<Vec<u8>, CompileError> {
-    // Run compilation on a thread with a larger stack (16MB) to handle deeply nested expressions
-    let source = source.to_string();
-    std::thread::Builder::new()
-        .stack_size(16 * 1024 * 1024)
-        .spawn(move || {
-            let mut program = parse_source(&source)?;
-            // Resolve QualifiedAccess for single-file programs (no module flattening)
-            modules::resolve_qualified_access_single_file(&mut program)?;
-            let result = run_frontend(&mut program, true)?;
-            codegen::codegen(&program, &result.env, &source, None)
-        })
-        .expect("failed to spawn compilation thread")
-        .join()
-        .expect("compilation thread panicked")
+    run_on_compiler_thread(|| {
+        let mut program = parse_source(source)?;
+        // Resolve QualifiedAccess for single-file programs (no module flattening)
+        modules::resolve_qualified_access_single_file(&mut program)?;
+        let result = run_frontend(&mut program, true)?;
+        codegen::codegen(&program, &result.env, source, None, false)
+    })
 }
 
 /// Compile a source string in test mode directly (single-file, no module resolution).
@@ -213,23 +299,33 @@ pub fn compile_file(entry_file: &Path, output_path: &Path) -> Result<(), Compile
 
 /// Compile with an explicit stdlib root path.
 pub fn compile_file_with_stdlib(entry_file: &Path, output_path: &Path, stdlib_root: Option<&Path>) -> Result<(), CompileError> {
-    compile_file_impl(entry_file, output_path, stdlib_root, false, GcBackend::default(), false).map(|_| ())
+    compile_file_impl(entry_file, output_path, stdlib_root, false, GcBackend::default(), false, false).map(|_| ())
 }
 
 /// Compile with an explicit stdlib root path and GC backend.
 pub fn compile_file_with_options(entry_file: &Path, output_path: &Path, stdlib_root: Option<&Path>, gc: GcBackend, standalone: bool) -> Result<(), CompileError> {
-    compile_file_impl(entry_file, output_path, stdlib_root, standalone, gc, false).map(|_| ())
+    compile_file_impl(entry_file, output_path, stdlib_root, standalone, gc, false, false).map(|_| ())
+}
+
+/// Like `compile_file_with_options`, but instruments every function with stack-trace
+/// frames so an error escaping `main` prints where it was raised.
+pub fn compile_file_with_debug(entry_file: &Path, output_path: &Path, stdlib_root: Option<&Path>, gc: GcBackend, standalone: bool) -> Result<(), CompileError> {
+    compile_file_impl(entry_file, output_path, stdlib_root, standalone, gc, false, true).map(|_| ())
 }
 
 /// Compile with coverage instrumentation. Returns the coverage map.
 pub fn compile_file_with_coverage(entry_file: &Path, output_path: &Path, stdlib_root: Option<&Path>) -> Result<coverage::CoverageMap, CompileError> {
-    let (cov, _errs) = compile_file_impl(entry_file, output_path, stdlib_root, false, GcBackend::default(), true)?;
+    let (cov, _errs) = compile_file_impl(entry_file, output_path, stdlib_root, false, GcBackend::default(), true, false)?;
     cov.ok_or_else(|| CompileError::codegen("coverage map should have been generated".to_string()))
 }
 
 type FnErrorSets = std::collections::HashMap<String, std::collections::HashSet<String>>;
 
-fn compile_file_impl(entry_file: &Path, output_path: &Path, stdlib_root: Option<&Path>, skip_siblings: bool, gc: GcBackend, coverage: bool) -> Result<(Option<coverage::CoverageMap>, FnErrorSets), CompileError> {
+fn compile_file_impl(entry_file: &Path, output_path: &Path, stdlib_root: Option<&Path>, skip_siblings: bool, gc: GcBackend, coverage: bool, debug: bool) -> Result<(Option<coverage::CoverageMap>, FnErrorSets), CompileError> {
+    run_on_compiler_thread(|| compile_file_uncaught(entry_file, output_path, stdlib_root, skip_siblings, gc, coverage, debug))
+}
+
+fn compile_file_uncaught(entry_file: &Path, output_path: &Path, stdlib_root: Option<&Path>, skip_siblings: bool, gc: GcBackend, coverage: bool, debug: bool) -> Result<(Option<coverage::CoverageMap>, FnErrorSets), CompileError> {
     let entry_file = entry_file.canonicalize().map_err(|e|
         CompileError::codegen(format!("could not resolve path '{}': {e}", entry_file.display())))?;
 
@@ -237,6 +333,9 @@ fn compile_file_impl(entry_file: &Path, output_path: &Path, stdlib_root: Option<
     let data = std::fs::read(&entry_file)
         .map_err(|e| CompileError::codegen(format!("failed to read entry file: {e}")))?;
 
+    let entry_dir = entry_file.parent().unwrap_or(Path::new("."));
+    let pkg_graph = manifest::find_and_resolve(entry_dir)?;
+
     let (mut program, source, source_map) = if binary::is_binary_format(&data) {
         // Binary file: deserialize (already flattened, skip module resolution)
         let (program, source, _derived) = binary::deserialize_program(&data)
@@ -247,8 +346,6 @@ fn compile_file_impl(entry_file: &Path, output_path: &Path, stdlib_root: Option<
         let source = String::from_utf8(data).map_err(|e|
             CompileError::codegen(format!("entry file is not valid UTF-8: {e}")))?;
         let effective_stdlib = resolve_stdlib(stdlib_root);
-        let entry_dir = entry_file.parent().unwrap_or(Path::new("."));
-        let pkg_graph = manifest::find_and_resolve(entry_dir)?;
         let graph = if skip_siblings {
             modules::resolve_modules_no_siblings(&entry_file, effective_stdlib.as_deref(), &pkg_graph)?
         } else {
@@ -268,13 +365,14 @@ fn compile_file_impl(entry_file: &Path, output_path: &Path, stdlib_root: Option<
     } else {
         None
     };
-    let object_bytes = codegen::codegen(&program, &result.env, &source, cov_map.as_ref())?;
+    let object_bytes = codegen::codegen(&program, &result.env, &source, cov_map.as_ref(), debug)?;
 
     let obj_path = output_path.with_extension("o");
     std::fs::write(&obj_path, &object_bytes)
         .map_err(|e| CompileError::codegen(format!("failed to write object file: {e}")))?;
 
-    let config = LinkConfig::default_config(&obj_path, gc)?;
+    let mut config = LinkConfig::default_config(&obj_path, gc)?;
+    config.flags.extend(pkg_graph.link_flags());
     link_from_config(&config, output_path)?;
 
     let _ = std::fs::remove_file(&obj_path);
@@ -291,6 +389,16 @@ use parser::ast::Program;
 /// Returns the canonical (pre-transformation) AST + type-checked derived data.
 /// Use this for emit-ast and analyze commands.
 pub fn parse_file_for_editing(entry_file: &Path, stdlib_root: Option<&Path>) -> Result<(Program, String, derived::DerivedInfo), CompileError> {
+    parse_file_for_editing_impl(entry_file, stdlib_root, None)
+}
+
+/// Like `parse_file_for_editing`, but reuses the body checks `cache` kept from
+/// earlier calls for files that haven't changed.
+pub fn parse_file_for_editing_incremental(entry_file: &Path, stdlib_root: Option<&Path>, cache: &mut incremental::AnalysisCache) -> Result<(Program, String, derived::DerivedInfo), CompileError> {
+    parse_file_for_editing_impl(entry_file, stdlib_root, Some(cache))
+}
+
+fn parse_file_for_editing_impl(entry_file: &Path, stdlib_root: Option<&Path>, mut cache: Option<&mut incremental::AnalysisCache>) -> Result<(Program, String, derived::DerivedInfo), CompileError> {
     let entry_file = entry_file.canonicalize().map_err(|e|
         CompileError::codegen(format!("could not resolve path '{}': {e}", entry_file.display())))?;
 
@@ -311,12 +419,19 @@ pub fn parse_file_for_editing(entry_file: &Path, stdlib_root: Option<&Path>) ->
     let entry_dir = entry_file.parent().unwrap_or(Path::new("."));
     let pkg_graph = manifest::find_and_resolve(entry_dir)?;
     let graph = modules::resolve_modules(&entry_file, effective_stdlib.as_deref(), &pkg_graph)?;
+    let bodies = cache.as_deref_mut()
+        .map(|c| c.prepare(&entry_file, incremental::Pipeline::Editing, &graph));
 
     let (mut program, _source_map) = modules::flatten_modules(graph)?;
+    // The entry file is the first one loaded, so its spans carry file id 0
+    parser::comments::attach_comments(&mut program, &source, 0);
 
     // Type check without transformations (preserves canonical AST)
-    let result = run_frontend_for_editing(&mut program)?;
+    let result = run_frontend_for_editing(&mut program, bodies)?;
     let derived = derived::DerivedInfo::build(&result.env, &program, &source);
+    if let Some(cache) = cache {
+        cache.finish(&entry_file, incremental::Pipeline::Editing);
+    }
 
     Ok((program, source, derived))
 }
@@ -338,6 +453,24 @@ pub fn analyze_file_with_warnings(entry_file: &Path, stdlib_root: Option<&Path>)
 }
 
 pub fn analyze_file_with_warnings_impl(entry_file: &Path, stdlib_root: Option<&Path>, standalone: bool) -> Result<(Program, String, derived::DerivedInfo, Vec<CompileWarning>), CompileError> {
+    catch_ice(|| analyze_file_uncaught(entry_file, stdlib_root, standalone, None, None))
+}
+
+/// Like `analyze_file_with_warnings_impl`, but also hands back every file of the
+/// module graph in `sources`, so diagnostics can be located in the file they came
+/// from. `sources` stays empty when the module graph couldn't be resolved.
+pub fn analyze_file_with_sources(entry_file: &Path, stdlib_root: Option<&Path>, standalone: bool, sources: &mut modules::SourceMap) -> Result<(Program, String, derived::DerivedInfo, Vec<CompileWarning>), CompileError> {
+    catch_ice(|| analyze_file_uncaught(entry_file, stdlib_root, standalone, None, Some(sources)))
+}
+
+/// Like `analyze_file_with_warnings`, but reuses the body checks `cache` kept
+/// from earlier analyses of the same entry file. Only files that changed, and
+/// files that may depend on a changed signature, are type-checked again.
+pub fn analyze_file_incremental(entry_file: &Path, stdlib_root: Option<&Path>, cache: &mut incremental::AnalysisCache) -> Result<(Program, String, derived::DerivedInfo, Vec<CompileWarning>), CompileError> {
+    catch_ice(|| analyze_file_uncaught(entry_file, stdlib_root, false, Some(cache), None))
+}
+
+fn analyze_file_uncaught(entry_file: &Path, stdlib_root: Option<&Path>, standalone: bool, mut cache: Option<&mut incremental::AnalysisCache>, sources: Option<&mut modules::SourceMap>) -> Result<(Program, String, derived::DerivedInfo, Vec<CompileWarning>), CompileError> {
     let entry_file = entry_file.canonicalize().map_err(|e|
         CompileError::codegen(format!("could not resolve path '{}': {e}", entry_file.display())))?;
 
@@ -362,16 +495,24 @@ pub fn analyze_file_with_warnings_impl(entry_file: &Path, stdlib_root: Option<&P
     } else {
         modules::resolve_modules(&entry_file, effective_stdlib.as_deref(), &pkg_graph)?
     };
+    let pipeline = if standalone { incremental::Pipeline::AnalyzeStandalone } else { incremental::Pipeline::Analyze };
+    let bodies = cache.as_deref_mut().map(|c| c.prepare(&entry_file, pipeline, &graph));
 
     let (mut program, source_map) = modules::flatten_modules(graph)?;
+    let entry_file_id = source_map.files.iter()
+        .position(|(path, _)| path == &entry_file)
+        .map(|pos| pos as u32);
+    if let Some(sources) = sources {
+        *sources = source_map;
+    }
 
-    let result = run_frontend(&mut program, false)?;
+    let result = run_frontend_cached(&mut program, false, bodies)?;
     let derived = derived::DerivedInfo::build(&result.env, &program, &source);
+    if let Some(cache) = cache {
+        cache.finish(&entry_file, pipeline);
+    }
 
     // Filter warnings to only include those from the entry file
-    let entry_file_id = source_map.files.iter()
-        .position(|(path, _)| path == &entry_file)
-        .map(|pos| pos as u32);
 
     let filtered_warnings: Vec<CompileWarning> = if let Some(file_id) = entry_file_id {
         result.warnings.into_iter()
@@ -392,7 +533,9 @@ pub fn analyze_file_with_warnings_impl(entry_file: &Path, stdlib_root: Option<&P
 /// 3. Builds fresh DerivedInfo
 /// 4. Writes the updated .pluto file with new derived data
 ///
-/// The AST and source text are preserved unchanged.
+/// The AST and source text are preserved unchanged, as is the file's compression.
+/// A file written at an older `binary::FormatVersion` is migrated on read and
+/// rewritten at the current one.
 ///
 /// If the input is a .pt text file, it will be parsed first.
 pub fn analyze_and_update(
@@ -410,11 +553,12 @@ pub fn analyze_and_update(
     })?;
 
     // Determine if it's a binary .pluto or text .pt file
-    let (mut program, source) = if binary::is_binary_format(&data) {
+    let (mut program, source, compression) = if binary::is_binary_format(&data) {
         // Binary .pluto file - deserialize it (already flattened)
         let (program, source, _old_derived) = binary::deserialize_program(&data)
             .map_err(|e| CompileError::codegen(format!("failed to deserialize .pluto: {e}")))?;
-        (program, source)
+        let compression = binary::compression_of(&data).unwrap_or_default();
+        (program, source, compression)
     } else {
         // Text file - resolve and flatten modules
         let source = String::from_utf8(data).map_err(|e|
@@ -431,15 +575,15 @@ pub fn analyze_and_update(
         let (program, _source_map) = modules::flatten_modules(graph)?;
 
         // TODO: Store merged source from source_map instead of just entry file
-        (program, source)
+        (program, source, binary::Compression::None)
     };
 
     // Run analysis pipeline without transformations (preserves canonical AST)
-    let result = run_frontend_for_editing(&mut program)?;
+    let result = run_frontend_for_editing(&mut program, None)?;
     let derived = derived::DerivedInfo::build(&result.env, &program, &source);
 
     // Serialize with fresh derived data
-    let bytes = binary::serialize_program(&program, &source, &derived)
+    let bytes = binary::serialize_program_with(&program, &source, &derived, compression)
         .map_err(|e| CompileError::codegen(format!("failed to serialize .pluto: {e}")))?;
 
     // Determine output path - always write .pluto
@@ -539,6 +683,17 @@ fn compile_file_for_tests_impl(
     use_cache: bool,
     gc: GcBackend,
     coverage: bool,
+) -> Result<Option<coverage::CoverageMap>, CompileError> {
+    run_on_compiler_thread(|| compile_file_for_tests_uncaught(entry_file, output_path, stdlib_root, use_cache, gc, coverage))
+}
+
+fn compile_file_for_tests_uncaught(
+    entry_file: &Path,
+    output_path: &Path,
+    stdlib_root: Option<&Path>,
+    use_cache: bool,
+    gc: GcBackend,
+    coverage: bool,
 ) -> Result<Option<coverage::CoverageMap>, CompileError> {
     let entry_file = entry_file.canonicalize().map_err(|e|
         CompileError::codegen(format!("could not resolve path '{}': {e}", entry_file.display())))?;
@@ -546,6 +701,9 @@ fn compile_file_for_tests_impl(
     let data = std::fs::read(&entry_file)
         .map_err(|e| CompileError::codegen(format!("failed to read entry file: {e}")))?;
 
+    let entry_dir = entry_file.parent().unwrap_or(Path::new("."));
+    let pkg_graph = manifest::find_and_resolve(entry_dir)?;
+
     let (mut program, source, source_map) = if binary::is_binary_format(&data) {
         let (program, source, _derived) = binary::deserialize_program(&data)
             .map_err(|e| CompileError::codegen(format!("failed to deserialize: {e}")))?;
@@ -554,8 +712,6 @@ fn compile_file_for_tests_impl(
         let source = String::from_utf8(data).map_err(|e|
             CompileError::codegen(format!("entry file is not valid UTF-8: {e}")))?;
         let effective_stdlib = resolve_stdlib(stdlib_root);
-        let entry_dir = entry_file.parent().unwrap_or(Path::new("."));
-        let pkg_graph = manifest::find_and_resolve(entry_dir)?;
         // Use resolve_modules_no_siblings to compile test files in isolation and prevent test ID collisions
         let graph = modules::resolve_modules_no_siblings(&entry_file, effective_stdlib.as_deref(), &pkg_graph)?;
         let (program, source_map) = modules::flatten_modules(graph)?;
@@ -622,7 +778,7 @@ fn compile_file_for_tests_impl(
     } else {
         None
     };
-    let object_bytes = codegen::codegen(&program, &result.env, &source, cov_map.as_ref())?;
+    let object_bytes = codegen::codegen(&program, &result.env, &source, cov_map.as_ref(), false)?;
 
     // Save cache after successful compilation
     if use_cache {
@@ -637,7 +793,8 @@ fn compile_file_for_tests_impl(
     std::fs::write(&obj_path, &object_bytes)
         .map_err(|e| CompileError::codegen(format!("failed to write object file: {e}")))?;
 
-    let config = LinkConfig::test_config(&obj_path, gc)?;
+    let mut config = LinkConfig::test_config(&obj_path, gc)?;
+    config.flags.extend(pkg_graph.link_flags());
     link_from_config(&config, output_path)?;
 
     let _ = std::fs::remove_file(&obj_path);
@@ -653,6 +810,10 @@ pub enum GcBackend {
     MarkSweep,
     /// No-op allocator that never collects. Useful for benchmarking.
     Noop,
+    /// Mark-sweep with bump allocation out of arena blocks that are reset
+    /// wholesale once every object in them is dead. Cheaper allocation for
+    /// short-lived garbage.
+    Bump,
 }
 
 impl GcBackend {
@@ -660,6 +821,7 @@ impl GcBackend {
         match self {
             GcBackend::MarkSweep => "marksweep",
             GcBackend::Noop => "noop",
+            GcBackend::Bump => "bump",
         }
     }
 
@@ -667,10 +829,61 @@ impl GcBackend {
         match self {
             GcBackend::MarkSweep => include_str!("../runtime/gc/marksweep.c"),
             GcBackend::Noop => include_str!("../runtime/gc/noop.c"),
+            // bump.c supplies the object store, marksweep.c the collector
+            GcBackend::Bump => concat!(
+                include_str!("../runtime/gc/bump.c"),
+                include_str!("../runtime/gc/marksweep.c"),
+            ),
         }
     }
 }
 
+/// Collection policy for the mark-sweep and bump GCs, compiled into the
+/// runtime as `-D` defines. The `noop` backend ignores it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GcConfig {
+    /// Heap size in bytes at which the first collection runs. Later thresholds
+    /// never drop below it. Defaults to 256 KB.
+    pub initial_heap: u64,
+    /// After a collection, the next one runs once the heap reaches the surviving
+    /// bytes times this factor. Defaults to 2.0.
+    pub growth_factor: f64,
+}
+
+impl Default for GcConfig {
+    fn default() -> Self {
+        Self { initial_heap: 256 * 1024, growth_factor: 2.0 }
+    }
+}
+
+impl GcConfig {
+    /// The defaults, overridden by `PLUTO_GC_HEAP_MB` (a whole number of
+    /// megabytes) and `PLUTO_GC_GROWTH` (a factor above 1). Read when the
+    /// runtime is compiled, so the values are baked into the binary.
+    pub fn from_env() -> Result<Self, CompileError> {
+        let mut config = Self::default();
+        if let Ok(mb) = std::env::var("PLUTO_GC_HEAP_MB") {
+            config.initial_heap = mb.trim().parse::<u64>().ok()
+                .filter(|mb| *mb > 0)
+                .and_then(|mb| mb.checked_mul(1024 * 1024))
+                .ok_or_else(|| CompileError::link(format!("PLUTO_GC_HEAP_MB must be a positive number of megabytes, got '{mb}'")))?;
+        }
+        if let Ok(factor) = std::env::var("PLUTO_GC_GROWTH") {
+            config.growth_factor = factor.trim().parse::<f64>().ok()
+                .filter(|f| f.is_finite() && *f > 1.0)
+                .ok_or_else(|| CompileError::link(format!("PLUTO_GC_GROWTH must be a number greater than 1, got '{factor}'")))?;
+        }
+        Ok(config)
+    }
+
+    fn defines(&self) -> [String; 2] {
+        [
+            format!("-DPLUTO_GC_INITIAL_HEAP={}UL", self.initial_heap),
+            format!("-DPLUTO_GC_GROWTH_FACTOR={:?}", self.growth_factor),
+        ]
+    }
+}
+
 /// Compute a content-addressed cache key for the runtime object file.
 /// The key incorporates all C source content, compilation flags, GC backend,
 /// and host platform so that any change triggers a cache miss.
@@ -682,7 +895,7 @@ fn runtime_log(msg: &str) {
     }
 }
 
-fn runtime_cache_key(test_mode: bool, gc: GcBackend) -> String {
+fn runtime_cache_key(test_mode: bool, gc: GcBackend, config: &GcConfig) -> String {
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
 
@@ -693,6 +906,7 @@ fn runtime_cache_key(test_mode: bool, gc: GcBackend) -> String {
     include_str!("../runtime/builtins.h").hash(&mut hasher);
     test_mode.hash(&mut hasher);
     gc.name().hash(&mut hasher);
+    config.defines().hash(&mut hasher);
     std::env::consts::ARCH.hash(&mut hasher);
     std::env::consts::OS.hash(&mut hasher);
     format!("{:016x}", hasher.finish())
@@ -727,7 +941,8 @@ fn store_disk_cache(cache_key: &str, object_path: &Path) -> Result<(), CompileEr
 /// Compile gc, threading, and builtins C sources to a single linked object file.
 /// Uses a three-tier cache: OnceLock (in-process) → disk cache → full compilation.
 fn compile_runtime_object(test_mode: bool, gc: GcBackend) -> Result<PathBuf, CompileError> {
-    let cache_key = runtime_cache_key(test_mode, gc);
+    let config = GcConfig::from_env()?;
+    let cache_key = runtime_cache_key(test_mode, gc, &config);
     // The cache key is a content hash of the runtime sources — but a hash with no
     // reader can't answer "did my runtime change take effect?". `PLUTO_VERBOSE`
     // surfaces the cache decision (and the key), and `PLUTO_RUNTIME_NO_CACHE`
@@ -788,6 +1003,7 @@ fn compile_runtime_object(test_mode: bool, gc: GcBackend) -> Result<PathBuf, Com
     if test_mode {
         cmd.arg("-DPLUTO_TEST_MODE").arg("-Wno-deprecated-declarations");
     }
+    cmd.args(config.defines());
     cmd.arg("-I").arg(&dir);
     cmd.arg(&gc_c).arg("-o").arg(&gc_o);
     #[cfg(target_os = "linux")]
@@ -908,6 +1124,14 @@ fn cached_runtime_object(gc: GcBackend) -> Result<&'static Path, CompileError> {
                 Err(msg) => Err(CompileError::link(msg.clone())),
             }
         }
+        GcBackend::Bump => {
+            static CACHE: OnceLock<Result<PathBuf, String>> = OnceLock::new();
+            let result = CACHE.get_or_init(|| compile_runtime_object(false, GcBackend::Bump).map_err(|e| e.to_string()));
+            match result {
+                Ok(path) => Ok(path.as_path()),
+                Err(msg) => Err(CompileError::link(msg.clone())),
+            }
+        }
     }
 }
 
@@ -930,6 +1154,14 @@ fn cached_test_runtime_object(gc: GcBackend) -> Result<&'static Path, CompileErr
                 Err(msg) => Err(CompileError::link(msg.clone())),
             }
         }
+        GcBackend::Bump => {
+            static CACHE: OnceLock<Result<PathBuf, String>> = OnceLock::new();
+            let result = CACHE.get_or_init(|| compile_runtime_object(true, GcBackend::Bump).map_err(|e| e.to_string()));
+            match result {
+                Ok(path) => Ok(path.as_path()),
+                Err(msg) => Err(CompileError::link(msg.clone())),
+            }
+        }
     }
 }
 
@@ -1011,9 +1243,11 @@ pub fn detect_system_file(entry_file: &Path) -> Result<Option<parser::ast::Progr
 
     let source = String::from_utf8(data).map_err(|e|
         CompileError::codegen(format!("file is not valid UTF-8: {e}")))?;
-    let tokens = lexer::lex(&source)?;
-    let mut parser = parser::Parser::new(&tokens, &source);
-    let program = parser.parse_program()?;
+    // Parse on the compiler thread: deeply nested input needs its stack.
+    let program = run_on_compiler_thread(|| {
+        let tokens = lexer::lex(&source)?;
+        parser::Parser::new(&tokens, &source).parse_program()
+    })?;
     if program.system.is_some() {
         Ok(Some(program))
     } else {
@@ -1318,7 +1552,7 @@ pub fn compile_system_file_with_stdlib(
 
         let output_path = output_dir.join(member_name);
         let (_cov, fn_errors) =
-            compile_file_impl(&entry_file, &output_path, stdlib_root, true, GcBackend::default(), false)?;
+            compile_file_impl(&entry_file, &output_path, stdlib_root, true, GcBackend::default(), false, false)?;
         member_errors.insert(member_name.clone(), fn_errors);
         results.push((member_name.clone(), output_path));
     }
@@ -1392,14 +1626,67 @@ pub fn update_git_deps(dir: &Path) -> Result<(), CompileError> {
     if updated.is_empty() {
         eprintln!("no git dependencies to update");
     } else {
-        for name in &updated {
-            eprintln!("updated: {name}");
+        for (name, resolved) in &updated {
+            match resolved {
+                Some(tag) => eprintln!("updated: {name} ({tag})"),
+                None => eprintln!("updated: {name}"),
+            }
         }
     }
     Ok(())
 }
 
 
+#[cfg(test)]
+mod ice_tests {
+    use super::*;
+
+    #[test]
+    fn panic_on_compiler_thread_is_reported_as_ice() {
+        let err = run_with_stack_size(DEFAULT_COMPILER_STACK_SIZE, || -> Result<(), CompileError> { panic!("boom in codegen") })
+            .expect_err("panic should surface as an error");
+        assert!(matches!(&err, CompileError::Internal(msg) if msg == "boom in codegen"));
+        assert_eq!(err.to_string(), "internal compiler error: boom in codegen");
+    }
+
+    #[test]
+    fn catch_ice_keeps_formatted_panic_message() {
+        let err = catch_ice(|| -> Result<(), CompileError> { panic!("bad index {}", 3) }).unwrap_err();
+        assert!(matches!(err, CompileError::Internal(ref msg) if msg == "bad index 3"));
+    }
+
+    #[test]
+    fn stack_size_accepts_bytes_and_suffixes() {
+        assert_eq!(parse_stack_size("1048576"), Some(1024 * 1024));
+        assert_eq!(parse_stack_size("512k"), Some(512 * 1024));
+        assert_eq!(parse_stack_size(" 64M "), Some(64 * 1024 * 1024));
+        assert_eq!(parse_stack_size("1G"), Some(1024 * 1024 * 1024));
+        assert_eq!(parse_stack_size("0"), None);
+        assert_eq!(parse_stack_size("lots"), None);
+        assert_eq!(parse_stack_size("M"), None);
+        assert_eq!(parse_stack_size(""), None);
+    }
+
+    #[test]
+    fn larger_stack_size_allows_deeper_recursion() {
+        // ~48MB of frames: overflows the 16MB default, fits in 128MB.
+        fn deep(n: usize) -> usize {
+            let buf = std::hint::black_box([n as u8; 4096]);
+            if n == 0 { buf[0] as usize } else { deep(n - 1) + buf[1] as usize }
+        }
+        let depth = 12 * 1024;
+        let result = run_with_stack_size(128 * 1024 * 1024, || Ok(deep(depth)));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn catch_ice_passes_through_ordinary_results() {
+        assert_eq!(catch_ice(|| Ok(7)).unwrap(), 7);
+        let err = catch_ice(|| -> Result<(), CompileError> { Err(CompileError::codegen("nope")) }).unwrap_err();
+        assert!(matches!(err, CompileError::Codegen { .. }));
+    }
+}
+
 #[cfg(test)]
 mod runtime_cache_tests {
     use super::*;
@@ -1408,18 +1695,32 @@ mod runtime_cache_tests {
     fn cache_key_is_deterministic_and_discriminates() {
         // Same inputs -> same key, so a "cache hit" is genuinely the same runtime.
         assert_eq!(
-            runtime_cache_key(false, GcBackend::MarkSweep),
-            runtime_cache_key(false, GcBackend::MarkSweep),
+            runtime_cache_key(false, GcBackend::MarkSweep, &GcConfig::default()),
+            runtime_cache_key(false, GcBackend::MarkSweep, &GcConfig::default()),
         );
         // Different config -> different key, so caches never collide across GC
         // backend or test/non-test builds.
         assert_ne!(
-            runtime_cache_key(false, GcBackend::MarkSweep),
-            runtime_cache_key(false, GcBackend::Noop),
+            runtime_cache_key(false, GcBackend::MarkSweep, &GcConfig::default()),
+            runtime_cache_key(false, GcBackend::Noop, &GcConfig::default()),
+        );
+        assert_ne!(
+            runtime_cache_key(false, GcBackend::MarkSweep, &GcConfig::default()),
+            runtime_cache_key(false, GcBackend::Bump, &GcConfig::default()),
+        );
+        assert_ne!(
+            runtime_cache_key(false, GcBackend::MarkSweep, &GcConfig::default()),
+            runtime_cache_key(true, GcBackend::MarkSweep, &GcConfig::default()),
+        );
+        let bigger = GcConfig { initial_heap: 64 * 1024 * 1024, ..GcConfig::default() };
+        let faster = GcConfig { growth_factor: 1.5, ..GcConfig::default() };
+        assert_ne!(
+            runtime_cache_key(false, GcBackend::MarkSweep, &GcConfig::default()),
+            runtime_cache_key(false, GcBackend::MarkSweep, &bigger),
         );
         assert_ne!(
-            runtime_cache_key(false, GcBackend::MarkSweep),
-            runtime_cache_key(true, GcBackend::MarkSweep),
+            runtime_cache_key(false, GcBackend::MarkSweep, &GcConfig::default()),
+            runtime_cache_key(false, GcBackend::MarkSweep, &faster),
         );
     }
 }
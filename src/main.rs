@@ -8,10 +8,14 @@ struct Cli {
     #[arg(long, global = true)]
     stdlib: Option<PathBuf>,
 
-    /// Garbage collector backend: "marksweep" (default) or "noop"
+    /// Garbage collector backend: "marksweep" (default), "bump" or "noop"
     #[arg(long, global = true, default_value = "marksweep")]
     gc: String,
 
+    /// Fail instead of updating pluto.lock when git dependencies would resolve differently
+    #[arg(long, global = true)]
+    frozen: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -20,7 +24,8 @@ fn parse_gc_backend(s: &str) -> Result<pluto::GcBackend, String> {
     match s {
         "marksweep" => Ok(pluto::GcBackend::MarkSweep),
         "noop" => Ok(pluto::GcBackend::Noop),
-        other => Err(format!("unknown GC backend '{}'; expected 'marksweep' or 'noop'", other)),
+        "bump" => Ok(pluto::GcBackend::Bump),
+        other => Err(format!("unknown GC backend '{}'; expected 'marksweep', 'bump' or 'noop'", other)),
     }
 }
 
@@ -36,6 +41,17 @@ enum Commands {
         /// Compile file in isolation without merging sibling source files
         #[arg(long)]
         standalone: bool,
+        /// Print a stack trace when an error escapes main (adds per-call overhead)
+        #[arg(long)]
+        debug: bool,
+    },
+    /// Type-check a .pluto/.pt source file and report errors and warnings, without codegen or linking
+    Check {
+        /// Source file path
+        file: PathBuf,
+        /// Check file in isolation without merging sibling source files
+        #[arg(long)]
+        standalone: bool,
     },
     /// Compile and run a .pluto/.pt source file
     Run {
@@ -44,6 +60,21 @@ enum Commands {
         /// Enable code coverage instrumentation
         #[arg(long)]
         coverage: bool,
+        /// Coverage report to produce after the run: terminal (default) or json
+        #[arg(long, default_value = "terminal", value_parser = ["terminal", "json"], requires = "coverage")]
+        coverage_format: String,
+        /// Where to write the JSON coverage report (defaults to .pluto-coverage/coverage.json)
+        #[arg(long, requires = "coverage")]
+        coverage_output: Option<PathBuf>,
+        /// Print a stack trace when an error escapes main (adds per-call overhead)
+        #[arg(long)]
+        debug: bool,
+        /// Print GC statistics to stderr when the program exits
+        #[arg(long)]
+        gc_stats: bool,
+        /// Arguments passed to the program (after `--`)
+        #[arg(last = true)]
+        args: Vec<String>,
     },
     /// Run tests in a .pluto/.pt source file
     Test {
@@ -61,6 +92,12 @@ enum Commands {
         /// Enable code coverage instrumentation
         #[arg(long)]
         coverage: bool,
+        /// Coverage report to produce after the run: terminal (default) or json
+        #[arg(long, default_value = "terminal", value_parser = ["terminal", "json"], requires = "coverage")]
+        coverage_format: String,
+        /// Where to write the JSON coverage report (defaults to .pluto-coverage/coverage.json)
+        #[arg(long, requires = "coverage")]
+        coverage_output: Option<PathBuf>,
     },
     /// Analyze a .pt source file and emit a .pluto binary AST
     EmitAst {
@@ -69,6 +106,9 @@ enum Commands {
         /// Output binary path (.pluto)
         #[arg(short, long)]
         output: Option<PathBuf>,
+        /// Deflate-compress the file's sections
+        #[arg(long)]
+        compress: bool,
     },
     /// Read a .pluto binary AST and emit human-readable .pt source
     GeneratePt {
@@ -78,6 +118,39 @@ enum Commands {
         #[arg(short, long)]
         output: Option<PathBuf>,
     },
+    /// Format a .pt source file in place
+    Fmt {
+        /// Source file path (.pt)
+        file: PathBuf,
+        /// Exit non-zero if the file is not already formatted, without writing it
+        #[arg(long, conflicts_with = "stdout")]
+        check: bool,
+        /// Print the formatted source instead of writing it back
+        #[arg(long)]
+        stdout: bool,
+    },
+    /// Print the module import graph (or function call graph) of a source file
+    Graph {
+        /// Source file path
+        file: PathBuf,
+        /// Output format
+        #[arg(long, default_value = "dot", value_parser = ["dot"])]
+        format: String,
+        /// Render the function call graph instead of the module import graph
+        #[arg(long)]
+        functions: bool,
+        /// Write the graph to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Create a new project directory with pluto.toml, a starter source file, and .gitignore
+    New {
+        /// Directory to create; its name becomes the package name
+        path: PathBuf,
+        /// Scaffold a library module instead of an app
+        #[arg(long)]
+        lib: bool,
+    },
     /// Fetch latest versions of all git dependencies
     Update {
         /// Directory to search for pluto.toml (defaults to current dir)
@@ -97,6 +170,14 @@ enum Commands {
         /// .pluto or .pt file to analyze
         file: PathBuf,
     },
+    /// Serve analysis and compile/run/test requests over JSON-RPC from one long-lived process
+    Serve {
+        /// Listen on this Unix socket instead of stdin/stdout
+        #[arg(long)]
+        socket: Option<PathBuf>,
+    },
+    /// Start an interactive session that compiles and runs each input
+    Repl,
     /// Watch files and automatically recompile/rerun on changes
     Watch {
         #[command(subcommand)]
@@ -135,6 +216,27 @@ enum CoverageCommands {
         #[arg(short, long)]
         output: Option<PathBuf>,
     },
+    /// Fail when line coverage is below a threshold
+    Check {
+        /// Minimum line coverage of the whole run, in percent
+        #[arg(long)]
+        min: Option<f64>,
+        /// Minimum line coverage of lines that are new since the baseline, in percent
+        #[arg(long)]
+        min_diff: Option<f64>,
+        /// Baseline JSON report to diff against (defaults to <dir>/baseline.json)
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// Apply --min to each file as well as the total
+        #[arg(long)]
+        per_file: bool,
+        /// Save this run's coverage as the new baseline
+        #[arg(long)]
+        update_baseline: bool,
+        /// Coverage data directory (defaults to .pluto-coverage/)
+        #[arg(long, default_value = ".pluto-coverage")]
+        dir: PathBuf,
+    },
 }
 
 #[derive(Subcommand)]
@@ -161,6 +263,9 @@ enum WatchCommands {
         #[arg(long)]
         no_cache: bool,
     },
+    /// Read file paths on stdin and answer each with its diagnostics as a JSON line,
+    /// re-analyzing only when the file or its project changed
+    Analyze,
 }
 
 /// Get the appropriate filename to display in error messages.
@@ -174,6 +279,66 @@ fn error_filename(err: &pluto::diagnostics::CompileError) -> Option<String> {
     }
 }
 
+/// Read the coverage map and counters a `--coverage` run left in `dir`, exiting
+/// with a hint when either is missing.
+fn load_coverage(dir: &std::path::Path) -> (pluto::coverage::CoverageMap, pluto::coverage::CoverageData) {
+    let map_path = dir.join("coverage-map.json");
+    let data_path = dir.join("coverage-data.bin");
+
+    if !map_path.exists() {
+        eprintln!("error: coverage map not found at {}", map_path.display());
+        eprintln!("hint: run with --coverage flag first, e.g. `pluto test file.pluto --coverage`");
+        std::process::exit(1);
+    }
+    if !data_path.exists() {
+        eprintln!("error: coverage data not found at {}", data_path.display());
+        eprintln!("hint: run with --coverage flag first, e.g. `pluto test file.pluto --coverage`");
+        std::process::exit(1);
+    }
+
+    let map = match pluto::coverage::CoverageMap::read_json(&map_path) {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("error: failed to read coverage map: {e}");
+            std::process::exit(1);
+        }
+    };
+    let data = match pluto::coverage::CoverageData::read_binary(&data_path) {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("error: failed to read coverage data: {e}");
+            std::process::exit(1);
+        }
+    };
+    (map, data)
+}
+
+/// Report coverage for a `run` or `test` with `--coverage`: print the terminal
+/// summary, or write the JSON report to `output` (default .pluto-coverage/coverage.json).
+fn report_coverage(map: &pluto::coverage::CoverageMap, format: &str, output: Option<PathBuf>) {
+    let cov_dir = std::path::Path::new(".pluto-coverage");
+    let data = match pluto::coverage::CoverageData::read_binary(&cov_dir.join("coverage-data.bin")) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("warning: failed to read coverage data: {e}");
+            return;
+        }
+    };
+
+    if format == "json" {
+        let report = pluto::coverage::generate_json_report(map, &data);
+        let json = serde_json::to_string_pretty(&report).unwrap();
+        let path = output.unwrap_or_else(|| cov_dir.join("coverage.json"));
+        match std::fs::write(&path, &json) {
+            Ok(()) => eprintln!("JSON report written to {}", path.display()),
+            Err(e) => eprintln!("warning: failed to write {}: {e}", path.display()),
+        }
+    } else {
+        let stats = pluto::coverage::generate_terminal_report(map, &data);
+        pluto::coverage::print_terminal_summary(&stats);
+    }
+}
+
 /// Determines if we should delegate to the active version.
 /// Returns false for toolchain management commands (install, use, versions).
 fn should_delegate() -> bool {
@@ -231,6 +396,7 @@ fn main() {
     }
 
     let cli = Cli::parse();
+    pluto::manifest::set_frozen(cli.frozen);
 
     let stdlib = cli.stdlib.as_deref();
     let gc = match parse_gc_backend(&cli.gc) {
@@ -245,7 +411,7 @@ fn main() {
     let server = pluto::server::InProcessServer::new();
 
     match cli.command {
-        Commands::Compile { file, output, standalone } => {
+        Commands::Compile { file, output, standalone, debug } => {
             // Check if this is a system file (contains a `system` declaration)
             match pluto::detect_system_file(&file) {
                 Ok(Some(_program)) => {
@@ -276,6 +442,7 @@ fn main() {
                             gc,
                             coverage: false,
                             standalone,
+                            debug,
                         },
                     );
 
@@ -294,7 +461,22 @@ fn main() {
                 }
             }
         }
-        Commands::Run { file, coverage } => {
+        Commands::Check { file, standalone } => {
+            match pluto::analyze_file_with_warnings_impl(&file, stdlib, standalone) {
+                Ok((_program, source, _derived, warnings)) => {
+                    for w in &warnings {
+                        pluto::diagnostics::render_warning(&source, &file.display().to_string(), w);
+                    }
+                }
+                Err(err) => {
+                    let filename = error_filename(&err)
+                        .unwrap_or_else(|| file.to_string_lossy().to_string());
+                    eprintln!("error [{}]: {err}", filename);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Run { file, coverage, coverage_format, coverage_output, debug, gc_stats, args } => {
             // Reject system files — they produce multiple binaries
             match pluto::detect_system_file(&file) {
                 Ok(Some(_)) => {
@@ -323,7 +505,12 @@ fn main() {
                     }
                 }
             } else {
-                if let Err(err) = pluto::compile_file_with_options(&file, &tmp, stdlib, gc, false) {
+                let compiled = if debug {
+                    pluto::compile_file_with_debug(&file, &tmp, stdlib, gc, false)
+                } else {
+                    pluto::compile_file_with_options(&file, &tmp, stdlib, gc, false)
+                };
+                if let Err(err) = compiled {
                     let filename = error_filename(&err)
                         .unwrap_or_else(|| file.to_string_lossy().to_string());
                     eprintln!("error [{}]: {err}", filename);
@@ -341,7 +528,12 @@ fn main() {
                 }
             }
 
-            let status = std::process::Command::new(&tmp)
+            let mut program = std::process::Command::new(&tmp);
+            program.args(&args);
+            if gc_stats {
+                program.env("PLUTO_GC_STATS", "1");
+            }
+            let status = program
                 .status()
                 .unwrap_or_else(|e| {
                     eprintln!("error: could not run compiled binary: {e}");
@@ -350,26 +542,21 @@ fn main() {
 
             let _ = std::fs::remove_file(&tmp);
 
-            // Print coverage summary after run
-            if coverage_map.is_some() {
-                let cov_dir = std::path::Path::new(".pluto-coverage");
-                let data_path = cov_dir.join("coverage-data.bin");
-                match pluto::coverage::CoverageData::read_binary(&data_path) {
-                    Ok(data) => {
-                        let map = coverage_map.as_ref().unwrap();
-                        let stats = pluto::coverage::generate_terminal_report(map, &data);
-                        pluto::coverage::print_terminal_summary(&stats);
-                    }
-                    Err(e) => {
-                        eprintln!("warning: failed to read coverage data: {e}");
-                    }
-                }
+            // Report coverage after run
+            if let Some(ref map) = coverage_map {
+                report_coverage(map, &coverage_format, coverage_output);
             }
 
             if !status.success() {
                 std::process::exit(status.code().unwrap_or(1));
             }
         }
+        Commands::Repl => {
+            if let Err(err) = pluto::repl::run_repl(gc) {
+                eprintln!("error: {err}");
+                std::process::exit(1);
+            }
+        }
         Commands::Watch { command } => match command {
             WatchCommands::Run { file, no_clear } => {
                 if let Err(err) = pluto::watch::watch_run(&file, stdlib, no_clear) {
@@ -384,8 +571,15 @@ fn main() {
                     std::process::exit(1);
                 }
             }
+            WatchCommands::Analyze => {
+                let mut server = pluto::server::AnalyzeServer::new(cli.stdlib.clone());
+                if let Err(err) = server.serve(std::io::stdin().lock(), std::io::stdout().lock()) {
+                    eprintln!("Watch analyze error: {err}");
+                    std::process::exit(1);
+                }
+            }
         },
-        Commands::Test { file, seed, iterations, no_cache, coverage } => {
+        Commands::Test { file, seed, iterations, no_cache, coverage, coverage_format, coverage_output } => {
             let tmp = std::env::temp_dir().join("pluto_test");
             let use_cache = !no_cache;
             let coverage_map = match pluto::compile_file_for_tests_with_coverage(&file, &tmp, stdlib, use_cache, coverage) {
@@ -422,32 +616,22 @@ fn main() {
 
             let _ = std::fs::remove_file(&tmp);
 
-            // Print coverage summary after tests
-            if coverage_map.is_some() {
-                let cov_dir = std::path::Path::new(".pluto-coverage");
-                let data_path = cov_dir.join("coverage-data.bin");
-                match pluto::coverage::CoverageData::read_binary(&data_path) {
-                    Ok(data) => {
-                        let map = coverage_map.as_ref().unwrap();
-                        let stats = pluto::coverage::generate_terminal_report(map, &data);
-                        pluto::coverage::print_terminal_summary(&stats);
-                    }
-                    Err(e) => {
-                        eprintln!("warning: failed to read coverage data: {e}");
-                    }
-                }
+            // Report coverage after tests
+            if let Some(ref map) = coverage_map {
+                report_coverage(map, &coverage_format, coverage_output);
             }
 
             if !status.success() {
                 std::process::exit(status.code().unwrap_or(1));
             }
         }
-        Commands::EmitAst { file, output } => {
+        Commands::EmitAst { file, output, compress } => {
             let output = output.unwrap_or_else(|| file.with_extension("pluto"));
+            let compression = if compress { pluto::binary::Compression::Deflate } else { pluto::binary::Compression::None };
 
             match pluto::parse_file_for_editing(&file, stdlib) {
                 Ok((program, source, derived)) => {
-                    match pluto::plto_store::write_canonical(&output, &program, &source, derived) {
+                    match pluto::plto_store::write_canonical_with(&output, &program, &source, derived, compression) {
                         Ok(_) => {
                             println!("Wrote {}", output.display());
                         }
@@ -501,6 +685,36 @@ fn main() {
                 }
             }
         }
+        Commands::Fmt { file, check, stdout } => {
+            let source = match std::fs::read_to_string(&file) {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("error: failed to read {}: {e}", file.display());
+                    std::process::exit(1);
+                }
+            };
+            let formatted = match pluto::format_source(&source) {
+                Ok(f) => f,
+                Err(err) => {
+                    eprintln!("error [{}]: {err}", file.display());
+                    std::process::exit(1);
+                }
+            };
+
+            if check {
+                if formatted != source {
+                    eprintln!("{} is not formatted", file.display());
+                    std::process::exit(1);
+                }
+            } else if stdout {
+                print!("{}", formatted);
+            } else if formatted != source
+                && let Err(e) = std::fs::write(&file, &formatted)
+            {
+                eprintln!("error: failed to write {}: {e}", file.display());
+                std::process::exit(1);
+            }
+        }
         Commands::Sync { file, output } => {
             let pluto_path = output.unwrap_or_else(|| file.with_extension("pluto"));
 
@@ -549,6 +763,54 @@ fn main() {
                 }
             }
         }
+        Commands::Serve { socket } => {
+            let mut rpc = pluto::server::RpcServer::new(cli.stdlib.clone());
+            let result = match socket {
+                Some(path) => rpc.serve_unix(&path),
+                None => rpc.serve(std::io::stdin().lock(), std::io::stdout().lock()),
+            };
+            if let Err(e) = result {
+                eprintln!("error: {e}");
+                std::process::exit(1);
+            }
+        }
+        Commands::Graph { file, format: _, functions, output } => {
+            let graph = if functions {
+                pluto::graph::call_graph(&file, stdlib)
+            } else {
+                pluto::graph::import_graph(&file, stdlib)
+            };
+            let graph = match graph {
+                Ok(graph) => graph,
+                Err(err) => {
+                    let filename = error_filename(&err)
+                        .unwrap_or_else(|| file.to_string_lossy().to_string());
+                    eprintln!("error [{}]: {err}", filename);
+                    std::process::exit(1);
+                }
+            };
+            let dot = graph.to_dot(if functions { "calls" } else { "imports" });
+            match output {
+                Some(path) => {
+                    if let Err(e) = std::fs::write(&path, &dot) {
+                        eprintln!("error: failed to write {}: {e}", path.display());
+                        std::process::exit(1);
+                    }
+                }
+                None => print!("{dot}"),
+            }
+            if !functions && !graph.cycle_edges().is_empty() {
+                eprintln!("warning: import graph contains cycles (drawn in red)");
+            }
+        }
+        Commands::New { path, lib } => {
+            let kind = if lib { pluto::scaffold::ProjectKind::Lib } else { pluto::scaffold::ProjectKind::App };
+            if let Err(err) = pluto::scaffold::new_project(&path, kind) {
+                eprintln!("error: {err}");
+                std::process::exit(1);
+            }
+            eprintln!("created {} in {}", if lib { "library" } else { "app" }, path.display());
+        }
         Commands::Update { dir } => {
             if let Err(err) = pluto::update_git_deps(&dir) {
                 eprintln!("error: {err}");
@@ -557,34 +819,7 @@ fn main() {
         }
         Commands::Coverage { command } => match command {
             CoverageCommands::Report { format, dir, output } => {
-                let map_path = dir.join("coverage-map.json");
-                let data_path = dir.join("coverage-data.bin");
-
-                if !map_path.exists() {
-                    eprintln!("error: coverage map not found at {}", map_path.display());
-                    eprintln!("hint: run with --coverage flag first, e.g. `pluto test file.pluto --coverage`");
-                    std::process::exit(1);
-                }
-                if !data_path.exists() {
-                    eprintln!("error: coverage data not found at {}", data_path.display());
-                    eprintln!("hint: run with --coverage flag first, e.g. `pluto test file.pluto --coverage`");
-                    std::process::exit(1);
-                }
-
-                let map = match pluto::coverage::CoverageMap::read_json(&map_path) {
-                    Ok(m) => m,
-                    Err(e) => {
-                        eprintln!("error: failed to read coverage map: {e}");
-                        std::process::exit(1);
-                    }
-                };
-                let data = match pluto::coverage::CoverageData::read_binary(&data_path) {
-                    Ok(d) => d,
-                    Err(e) => {
-                        eprintln!("error: failed to read coverage data: {e}");
-                        std::process::exit(1);
-                    }
-                };
+                let (map, data) = load_coverage(&dir);
 
                 match format.as_str() {
                     "terminal" => {
@@ -648,6 +883,56 @@ fn main() {
                     }
                 }
             }
+            CoverageCommands::Check { min, min_diff, baseline, per_file, update_baseline, dir } => {
+                if min.is_none() && min_diff.is_none() && !update_baseline {
+                    eprintln!("error: expected --min, --min-diff, or --update-baseline");
+                    std::process::exit(1);
+                }
+                let (map, data) = load_coverage(&dir);
+                let report = pluto::coverage::generate_json_report(&map, &data);
+                let baseline_path = baseline.unwrap_or_else(|| dir.join("baseline.json"));
+
+                let base = if min_diff.is_some() {
+                    match pluto::coverage::JsonCoverageReport::read_json(&baseline_path) {
+                        Ok(b) => Some(b),
+                        Err(e) => {
+                            eprintln!("error: failed to read baseline {}: {e}", baseline_path.display());
+                            eprintln!("hint: record one with `pluto coverage check --update-baseline`");
+                            std::process::exit(1);
+                        }
+                    }
+                } else {
+                    None
+                };
+
+                let thresholds = pluto::coverage::CoverageThresholds { min, min_diff, per_file };
+                let failures = pluto::coverage::check_thresholds(&report, base.as_ref(), &thresholds);
+                for f in &failures {
+                    eprintln!(
+                        "FAIL {}: {:.1}% of {} covered ({}/{}), minimum is {}%",
+                        f.path.as_deref().unwrap_or("total"),
+                        f.percent,
+                        if f.new_lines { "new lines" } else { "lines" },
+                        f.covered,
+                        f.total,
+                        f.min,
+                    );
+                }
+
+                if update_baseline {
+                    let json = serde_json::to_string_pretty(&report).unwrap();
+                    if let Err(e) = std::fs::write(&baseline_path, json) {
+                        eprintln!("error: failed to write {}: {e}", baseline_path.display());
+                        std::process::exit(1);
+                    }
+                    eprintln!("Baseline written to {}", baseline_path.display());
+                }
+
+                if !failures.is_empty() {
+                    std::process::exit(1);
+                }
+                eprintln!("Coverage check passed: {:.1}% of lines covered", report.summary.line_percent);
+            }
         },
         Commands::Install { version } => {
             if let Err(e) = pluto::toolchain::install_version(&version) {
@@ -1,7 +1,8 @@
 use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::diagnostics::CompileError;
 use crate::git_cache::{self, GitRef};
@@ -15,6 +16,13 @@ pub struct PackageNode {
     pub name: String,
     pub root_dir: PathBuf,
     pub dependencies: DependencyScope,
+    /// Native libraries from `[link] libs`, passed to the linker as `-l<name>`.
+    pub link_libs: Vec<String>,
+    /// Library search directories from `[link] search` and `[build] link_search`,
+    /// resolved against `root_dir`.
+    pub link_search: Vec<PathBuf>,
+    /// Raw linker flags from `[build] link_flags`.
+    pub extra_link_flags: Vec<String>,
 }
 
 /// The full resolved package graph passed to module resolution.
@@ -40,6 +48,22 @@ impl PackageGraph {
             .unwrap_or(&EMPTY_SCOPE)
     }
 
+    /// Linker flags for the native libraries `extern "C"` functions come from:
+    /// every package's search directories, then its `[link]` libraries, then its
+    /// raw `[build] link_flags`, in package order and without duplicates.
+    pub fn link_flags(&self) -> Vec<String> {
+        let mut flags: Vec<String> = Vec::new();
+        let search = self.packages.values().flat_map(|p| p.link_search.iter().map(|d| format!("-L{}", d.display())));
+        let libs = self.packages.values().flat_map(|p| p.link_libs.iter().map(|l| format!("-l{l}")));
+        let extra = self.packages.values().flat_map(|p| p.extra_link_flags.iter().cloned());
+        for flag in search.chain(libs).chain(extra) {
+            if !flags.contains(&flag) {
+                flags.push(flag);
+            }
+        }
+        flags
+    }
+
     /// Returns deps for a given canonical package dir, or empty scope if unknown.
     /// Callers MUST pass already-canonicalized paths.
     pub fn deps_for(&self, canonical_dir: &Path) -> &DependencyScope {
@@ -56,6 +80,19 @@ struct TomlManifest {
     package: Option<TomlPackage>,
     #[serde(default)]
     dependencies: BTreeMap<String, TomlDep>,
+    #[serde(default)]
+    link: TomlLink,
+    #[serde(default)]
+    build: TomlBuild,
+}
+
+/// `[link]`: native libraries for `extern "C"` functions.
+#[derive(Deserialize, Default)]
+struct TomlLink {
+    #[serde(default)]
+    libs: Vec<String>,
+    #[serde(default)]
+    search: Vec<String>,
 }
 
 #[derive(Deserialize)]
@@ -77,6 +114,17 @@ struct TomlDep {
     rev: Option<String>,
     tag: Option<String>,
     branch: Option<String>,
+    /// Semver requirement resolved against the repository's tags, e.g. `"^1.2"`.
+    version: Option<String>,
+}
+
+/// `[build]`: extra linker input, e.g. for system libraries.
+#[derive(Deserialize, Default)]
+struct TomlBuild {
+    #[serde(default)]
+    link_flags: Vec<String>,
+    #[serde(default)]
+    link_search: Vec<String>,
 }
 
 // ---- Dependency spec validation ----
@@ -93,6 +141,7 @@ fn validate_dep_spec(
     let has_rev = dep.rev.is_some();
     let has_tag = dep.tag.is_some();
     let has_branch = dep.branch.is_some();
+    let has_version = dep.version.is_some();
 
     // Must have exactly one of path or git
     if has_path && has_git {
@@ -108,19 +157,19 @@ fn validate_dep_spec(
         ));
     }
 
-    // rev/tag/branch only valid with git
-    if has_path && (has_rev || has_tag || has_branch) {
+    // rev/tag/branch/version only valid with git
+    if has_path && (has_rev || has_tag || has_branch || has_version) {
         return Err(CompileError::manifest(
-            format!("dependency '{}': 'rev'/'tag'/'branch' are only valid with git dependencies", dep_name),
+            format!("dependency '{}': 'rev'/'tag'/'branch'/'version' are only valid with git dependencies", dep_name),
             manifest_path.to_path_buf(),
         ));
     }
 
-    // At most one of rev/tag/branch
-    let ref_count = [has_rev, has_tag, has_branch].iter().filter(|&&x| x).count();
+    // At most one of rev/tag/branch/version
+    let ref_count = [has_rev, has_tag, has_branch, has_version].iter().filter(|&&x| x).count();
     if ref_count > 1 {
         return Err(CompileError::manifest(
-            format!("dependency '{}': specify at most one of 'rev', 'tag', 'branch'", dep_name),
+            format!("dependency '{}': specify at most one of 'rev', 'tag', 'branch', 'version'", dep_name),
             manifest_path.to_path_buf(),
         ));
     }
@@ -135,6 +184,12 @@ fn validate_dep_spec(
             GitRef::Tag(tag.clone())
         } else if let Some(branch) = &dep.branch {
             GitRef::Branch(branch.clone())
+        } else if let Some(version) = &dep.version {
+            let req = semver::VersionReq::parse(version).map_err(|e| CompileError::manifest(
+                format!("dependency '{}': invalid version requirement '{}': {}", dep_name, version, e),
+                manifest_path.to_path_buf(),
+            ))?;
+            GitRef::Version(req)
         } else {
             GitRef::DefaultBranch
         };
@@ -255,6 +310,17 @@ fn validate_dep_name(name: &str, manifest_path: &Path) -> Result<(), CompileErro
     Ok(())
 }
 
+/// Library names become `-l` flags, so they must not smuggle in other linker options.
+fn validate_link_lib(lib: &str, manifest_path: &Path) -> Result<(), CompileError> {
+    if lib.is_empty() || lib.starts_with('-') || lib.chars().any(char::is_whitespace) {
+        return Err(CompileError::manifest(
+            format!("pluto.toml: invalid library name '{lib}' in [link] libs"),
+            manifest_path.to_path_buf(),
+        ));
+    }
+    Ok(())
+}
+
 fn validate_dep_path(name: &str, path: &Path, manifest_path: &Path) -> Result<(), CompileError> {
     if !path.exists() || !path.is_dir() {
         return Err(CompileError::manifest(
@@ -274,7 +340,15 @@ fn validate_dep_path(name: &str, path: &Path, manifest_path: &Path) -> Result<()
 /// Find pluto.toml by walking from start_dir up to .git or FS root.
 /// If found, parse and recursively resolve all transitive deps.
 /// Returns PackageGraph. If no manifest found, returns PackageGraph::empty().
+///
+/// Git dependencies are checked out at the commits recorded in `pluto.lock`
+/// next to the manifest; the lockfile is (re)written when resolution adds or
+/// changes an entry.
 pub fn find_and_resolve(start_dir: &Path) -> Result<PackageGraph, CompileError> {
+    resolve_root(start_dir, true)
+}
+
+fn resolve_root(start_dir: &Path, honor_lock: bool) -> Result<PackageGraph, CompileError> {
     let manifest_path = match find_manifest_walk(start_dir) {
         Some(p) => p,
         None => return Ok(PackageGraph::empty()),
@@ -292,14 +366,38 @@ pub fn find_and_resolve(start_dir: &Path) -> Result<PackageGraph, CompileError>
     let mut resolved_cache: HashSet<PathBuf> = HashSet::new();
     let mut packages: BTreeMap<PathBuf, PackageNode> = BTreeMap::new();
 
+    let lock_path = manifest_dir.join(LOCKFILE_NAME);
+    let existing = read_lockfile(&lock_path)?;
+    let mut lock = LockState {
+        locked: if honor_lock { existing.clone() } else { Lockfile::default() },
+        resolved: Lockfile::default(),
+    };
+
     resolve_package_node(
         &manifest_path,
         &canonical_root,
         &mut resolving_stack,
         &mut resolved_cache,
         &mut packages,
+        &mut lock,
     )?;
 
+    let mut resolved = lock.resolved;
+    resolved.git.sort();
+    resolved.git.dedup();
+    // Projects without git deps don't get a lockfile until they need one
+    let unneeded = resolved.git.is_empty() && !lock_path.exists();
+    if resolved != existing && !unneeded {
+        if FROZEN.load(Ordering::Relaxed) {
+            let problem = if lock_path.exists() { "is out of date" } else { "is missing" };
+            return Err(CompileError::manifest(
+                format!("{LOCKFILE_NAME} {problem} and --frozen forbids updating it"),
+                manifest_path,
+            ));
+        }
+        write_lockfile(&lock_path, &resolved, &manifest_path)?;
+    }
+
     Ok(PackageGraph {
         root_dir: Some(canonical_root),
         packages,
@@ -312,6 +410,7 @@ fn resolve_package_node(
     resolving_stack: &mut Vec<PathBuf>,
     resolved_cache: &mut HashSet<PathBuf>,
     packages: &mut BTreeMap<PathBuf, PackageNode>,
+    lock: &mut LockState,
 ) -> Result<(), CompileError> {
     // Already fully resolved (handles diamond deps)
     if resolved_cache.contains(canonical_dir) {
@@ -352,9 +451,7 @@ fn resolve_package_node(
 
         let dep_path = match dep_kind {
             DepKind::Path(ref p) => manifest_dir.join(p),
-            DepKind::Git(ref url, ref git_ref) => {
-                git_cache::ensure_cached(url, git_ref, manifest_path)?
-            }
+            DepKind::Git(ref url, ref git_ref) => lock.checkout(url, git_ref, manifest_path)?,
         };
 
         if let DepKind::Path(_) = dep_kind {
@@ -378,16 +475,35 @@ fn resolve_package_node(
                 resolving_stack,
                 resolved_cache,
                 packages,
+                lock,
             )?;
         }
         // If dep has no manifest, it's a leaf node — no PackageNode entry needed
         // (deps_for will return empty scope)
     }
 
+    for lib in &manifest.link.libs {
+        validate_link_lib(lib, manifest_path)?;
+    }
+    let mut link_search = Vec::new();
+    for dir in manifest.link.search.iter().chain(&manifest.build.link_search) {
+        let path = manifest_dir.join(dir);
+        if !path.is_dir() {
+            return Err(CompileError::manifest(
+                format!("pluto.toml: library search path '{}' does not exist", path.display()),
+                manifest_path.to_path_buf(),
+            ));
+        }
+        link_search.push(path);
+    }
+
     packages.insert(canonical_dir.clone(), PackageNode {
         name: pkg_name,
         root_dir: canonical_dir.clone(),
         dependencies: dep_scope,
+        link_libs: manifest.link.libs.clone(),
+        link_search,
+        extra_link_flags: manifest.build.link_flags.clone(),
     });
 
     resolving_stack.pop();
@@ -396,10 +512,94 @@ fn resolve_package_node(
     Ok(())
 }
 
+// ---- Lockfile ----
+
+const LOCKFILE_NAME: &str = "pluto.lock";
+
+const LOCKFILE_HEADER: &str = "\
+# Generated by pluto: the commit each git dependency resolved to.
+# Run `pluto update` to move dependencies forward.
+
+";
+
+static FROZEN: AtomicBool = AtomicBool::new(false);
+
+/// Make resolution fail instead of writing `pluto.lock` (the CLI's `--frozen`).
+pub fn set_frozen(frozen: bool) {
+    FROZEN.store(frozen, Ordering::Relaxed);
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
+struct Lockfile {
+    #[serde(default)]
+    git: Vec<LockedGitDep>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct LockedGitDep {
+    url: String,
+    /// The ref pluto.toml asked for, e.g. `tag v1.0`. The entry goes stale when it changes.
+    reference: String,
+    commit: String,
+}
+
+/// Lock entries consulted during resolution, and the ones resolution produced.
+struct LockState {
+    locked: Lockfile,
+    resolved: Lockfile,
+}
+
+impl LockState {
+    /// Check out a git dependency at its locked commit, or resolve `git_ref`
+    /// and lock whatever it checked out.
+    fn checkout(&mut self, url: &str, git_ref: &GitRef, manifest_path: &Path) -> Result<PathBuf, CompileError> {
+        let reference = git_ref.to_string();
+        let locked = self.locked.git.iter().find(|d| d.url == url && d.reference == reference);
+        let dir = match locked {
+            Some(dep) => git_cache::ensure_commit(url, &dep.commit, manifest_path)?,
+            None => git_cache::ensure_cached(url, git_ref, manifest_path)?,
+        };
+        let commit = git_cache::head_commit(&dir, url, manifest_path)?;
+        self.resolved.git.push(LockedGitDep { url: url.to_string(), reference, commit });
+        Ok(dir)
+    }
+}
+
+fn read_lockfile(lock_path: &Path) -> Result<Lockfile, CompileError> {
+    let content = match std::fs::read_to_string(lock_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Lockfile::default()),
+        Err(e) => return Err(CompileError::manifest(
+            format!("{LOCKFILE_NAME}: could not read file: {e}"),
+            lock_path.to_path_buf(),
+        )),
+    };
+    toml::from_str(&content).map_err(|e| {
+        CompileError::manifest(
+            format!("{LOCKFILE_NAME}: invalid syntax: {e}"),
+            lock_path.to_path_buf(),
+        )
+    })
+}
+
+fn write_lockfile(lock_path: &Path, lock: &Lockfile, manifest_path: &Path) -> Result<(), CompileError> {
+    let body = toml::to_string(lock).map_err(|e| {
+        CompileError::manifest(format!("{LOCKFILE_NAME}: {e}"), manifest_path.to_path_buf())
+    })?;
+    std::fs::write(lock_path, format!("{LOCKFILE_HEADER}{body}")).map_err(|e| {
+        CompileError::manifest(
+            format!("{LOCKFILE_NAME}: could not write file: {e}"),
+            lock_path.to_path_buf(),
+        )
+    })
+}
+
 // ---- Update command ----
 
-/// Find the manifest and re-fetch all git dependencies (direct deps only for v1).
-pub fn update_git_deps(start_dir: &Path) -> Result<Vec<String>, CompileError> {
+/// Find the manifest, re-fetch all git dependencies (direct deps only for v1),
+/// and rewrite `pluto.lock` from the fetched refs.
+/// Returns each updated dependency with the tag its `version` resolved to, if any.
+pub fn update_git_deps(start_dir: &Path) -> Result<Vec<(String, Option<String>)>, CompileError> {
     let manifest_path = match find_manifest_walk(start_dir) {
         Some(p) => p,
         None => return Err(CompileError::manifest(
@@ -415,10 +615,12 @@ pub fn update_git_deps(start_dir: &Path) -> Result<Vec<String>, CompileError> {
     for (dep_name, dep_spec) in &manifest.dependencies {
         let dep_kind = validate_dep_spec(dep_name, dep_spec, &manifest_path)?;
         if let DepKind::Git(url, git_ref) = dep_kind {
-            git_cache::fetch_and_update(&url, &git_ref, &manifest_path)?;
-            updated.push(dep_name.clone());
+            let resolved = git_cache::fetch_and_update(&url, &git_ref, &manifest_path)?;
+            updated.push((dep_name.clone(), resolved));
         }
     }
 
+    resolve_root(start_dir, false)?;
+
     Ok(updated)
 }
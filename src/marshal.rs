@@ -4,7 +4,7 @@ use uuid::Uuid;
 
 use crate::diagnostics::CompileError;
 use crate::parser::ast::{
-    Block, ClassDecl, Expr, Function, Param, Program, Stmt, TypeExpr,
+    Block, ClassDecl, Expr, Function, Param, Program, Stmt, TypeExpr, Visibility,
 };
 use crate::span::{Span, Spanned};
 use crate::typeck::env::TypeEnv;
@@ -262,7 +262,7 @@ fn collect_types_from_stage_methods(program: &Program) -> Result<HashSet<String>
     // Initial collection from stage method signatures
     for stage in &program.stages {
         for method in &stage.node.methods {
-            if !method.node.is_pub {
+            if !method.node.visibility.is_pub() {
                 continue; // Only pub methods cross boundaries
             }
 
@@ -371,8 +371,9 @@ fn instantiate_generic_class(template: &ClassDecl, mangled_name: &str, type_arg_
         methods: template.methods.clone(), // Methods not used in marshal generation
         invariants: template.invariants.clone(),
         impl_traits: template.impl_traits.clone(),
+        where_bounds: std::collections::HashMap::new(),
         uses: template.uses.clone(),
-        is_pub: template.is_pub,
+        visibility: template.visibility,
         lifecycle: template.lifecycle,
     })
 }
@@ -418,7 +419,9 @@ fn instantiate_generic_enum(template: &crate::parser::ast::EnumDecl, mangled_nam
         type_params: vec![],
         type_param_bounds: std::collections::HashMap::new(),
         variants: instantiated_variants,
-        is_pub: template.is_pub,
+        methods: vec![],
+        impl_traits: vec![],
+        visibility: template.visibility,
     })
 }
 
@@ -596,9 +599,11 @@ fn generate_marshal_class(class_decl: &ClassDecl) -> Result<Spanned<Function>, C
         return_type: None, // void
         contracts: vec![],
         body,
-        is_pub: false,
+        visibility: Visibility::Private,
         is_override: false,
         is_generator: false,
+        declared_errors: None,
+        no_coverage: false,
     };
 
     Ok(Spanned {
@@ -687,9 +692,11 @@ fn generate_unmarshal_class(class_decl: &ClassDecl) -> Result<Spanned<Function>,
         }),
         contracts: vec![],
         body,
-        is_pub: false,
+        visibility: Visibility::Private,
         is_override: false,
         is_generator: false,
+        declared_errors: None,
+        no_coverage: false,
     };
 
     Ok(Spanned {
@@ -813,9 +820,11 @@ fn generate_marshal_enum(enum_decl: &crate::parser::ast::EnumDecl) -> Result<Spa
         return_type: None, // void
         contracts: vec![],
         body,
-        is_pub: false,
+        visibility: Visibility::Private,
         is_override: false,
         is_generator: false,
+        declared_errors: None,
+        no_coverage: false,
     };
 
     Ok(Spanned { node: function, span: mk_span() })
@@ -1012,9 +1021,11 @@ fn generate_unmarshal_enum(enum_decl: &crate::parser::ast::EnumDecl) -> Result<S
         }),
         contracts: vec![],
         body,
-        is_pub: false,
+        visibility: Visibility::Private,
         is_override: false,
         is_generator: false,
+        declared_errors: None,
+        no_coverage: false,
     };
 
     Ok(Spanned { node: function, span: mk_span() })
@@ -1128,9 +1139,11 @@ fn mk_function(name: String, param: (&str, TypeExpr), ret: Option<TypeExpr>, bod
             return_type: ret.map(|t| Spanned { node: t, span: mk_span() }),
             contracts: vec![],
             body: Spanned { node: Block { stmts: body }, span: mk_span() },
-            is_pub: false,
+            visibility: Visibility::Private,
             is_override: false,
             is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
         },
         span: mk_span(),
     }
@@ -2918,8 +2931,9 @@ mod tests {
             methods: vec![],
             invariants: vec![],
             impl_traits: vec![],
+            where_bounds: HashMap::new(),
             uses: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             lifecycle: Lifecycle::Singleton,
         };
 
@@ -2970,8 +2984,9 @@ mod tests {
             methods: vec![],
             invariants: vec![],
             impl_traits: vec![],
+            where_bounds: HashMap::new(),
             uses: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             lifecycle: Lifecycle::Singleton,
         };
 
@@ -3034,7 +3049,9 @@ mod tests {
                     fields: vec![],
                 },
             ],
-            is_pub: false,
+            methods: vec![],
+            impl_traits: vec![],
+            visibility: Visibility::Private,
         };
 
         let result = instantiate_generic_enum(&template, "Option$$int", "int").unwrap();
@@ -3090,7 +3107,9 @@ mod tests {
                     is_remote: false,
                 }],
             }],
-            is_pub: false,
+            methods: vec![],
+            impl_traits: vec![],
+            visibility: Visibility::Private,
         };
 
         let result = instantiate_generic_enum(&template, "Result$$bool", "bool").unwrap();
@@ -129,6 +129,14 @@ impl VisitMut for FileIdSetter {
         if let Stmt::Let { name, .. } = &mut stmt.node {
             name.span.file_id = self.file_id;
         }
+        if let Stmt::LetStruct { fields, .. } = &mut stmt.node {
+            for (field, rename) in fields {
+                field.span.file_id = self.file_id;
+                if let Some(r) = rename {
+                    r.span.file_id = self.file_id;
+                }
+            }
+        }
         walk_stmt_mut(self, stmt);
     }
 
@@ -151,7 +159,7 @@ fn set_program_file_id(program: &mut Program, file_id: u32) {
 
 /// Load a file in either binary (PLTO) or text format, auto-detecting based on content.
 /// Binary files are deserialized directly; text files go through lex+parse.
-fn load_file_auto(path: &Path, source_map: &mut SourceMap) -> Result<(Program, u32), CompileError> {
+pub(crate) fn load_file_auto(path: &Path, source_map: &mut SourceMap) -> Result<(Program, u32), CompileError> {
     let data = std::fs::read(path).map_err(|e| {
         CompileError::codegen(format!("could not read '{}': {e}", path.display()))
     })?;
@@ -186,7 +194,7 @@ fn is_pluto_source(path: &Path) -> bool {
 
 /// Resolve a module file by name: tries `<name>.pluto` first, then `<name>.pt`.
 /// Returns `Some(path)` if found, `None` if neither exists.
-fn resolve_module_file(dir: &Path, name: &str) -> Option<PathBuf> {
+pub(crate) fn resolve_module_file(dir: &Path, name: &str) -> Option<PathBuf> {
     let pluto_path = dir.join(format!("{}.pluto", name));
     if pluto_path.is_file() {
         return Some(pluto_path);
@@ -200,7 +208,7 @@ fn resolve_module_file(dir: &Path, name: &str) -> Option<PathBuf> {
 
 /// Collect source files (.pluto and .pt) in a directory, deduplicating by stem.
 /// When both `name.pluto` and `name.pt` exist, prefer `name.pluto` (binary).
-fn collect_source_files(dir: &Path) -> Result<Vec<PathBuf>, CompileError> {
+pub(crate) fn collect_source_files(dir: &Path) -> Result<Vec<PathBuf>, CompileError> {
     let entries = std::fs::read_dir(dir).map_err(|e| {
         CompileError::codegen(format!("could not read directory '{}': {e}", dir.display()))
     })?;
@@ -227,13 +235,135 @@ fn collect_source_files(dir: &Path) -> Result<Vec<PathBuf>, CompileError> {
     Ok(deduped)
 }
 
+/// Push a canonical file or directory path onto the import resolution stack.
+/// If it is already on the stack the import closes a cycle, and the error
+/// lists every module in the loop, e.g. `a.pluto -> b.pluto -> a.pluto`.
+fn enter_import(stack: &mut Vec<PathBuf>, canonical: PathBuf) -> Result<(), CompileError> {
+    if let Some(start) = stack.iter().position(|p| *p == canonical) {
+        let cycle: Vec<String> = stack[start..].iter()
+            .chain(std::iter::once(&canonical))
+            .map(|p| p.display().to_string())
+            .collect();
+        return Err(CompileError::codegen(format!(
+            "circular import detected: {}", cycle.join(" -> ")
+        )));
+    }
+    stack.push(canonical);
+    Ok(())
+}
+
+/// Resolve a glob import (`import dir.*`): every source file directly inside
+/// the directory is loaded as its own submodule, bound as `binding.stem`.
+/// Sub-imports within each file resolve against that directory.
+#[allow(clippy::too_many_arguments)]
+fn resolve_glob_import(
+    import: &Spanned<ImportDecl>,
+    module_dir: &Path,
+    source_map: &mut SourceMap,
+    visited: &mut Vec<PathBuf>,
+    effective_stdlib: Option<&Path>,
+    current_deps: &DependencyScope,
+    pkg_graph: &PackageGraph,
+    parent_origin: ImportOrigin,
+) -> Result<Vec<(String, Program, ImportOrigin)>, CompileError> {
+    let path = &import.node.path;
+    let full_path = import.node.full_path();
+    let first_segment = &path[0].node;
+    let is_dep = current_deps.contains_key(first_segment);
+    if is_dep && module_dir.join(first_segment).is_dir() {
+        return Err(CompileError::syntax(
+            format!("import '{}' is ambiguous: declared as dependency and also exists locally", full_path),
+            path[0].span,
+        ));
+    }
+
+    let local_origin = if parent_origin == ImportOrigin::PackageDep { ImportOrigin::PackageDep } else { ImportOrigin::Local };
+    let (root, rest, deps, origin) = if is_dep {
+        let dep_path = &current_deps[first_segment];
+        let dep_canonical = dep_path.canonicalize().map_err(|e| {
+            CompileError::codegen(format!("cannot resolve dep path '{}': {e}", dep_path.display()))
+        })?;
+        (dep_path.clone(), &path[1..], pkg_graph.deps_for(&dep_canonical), ImportOrigin::PackageDep)
+    } else if first_segment == "std" {
+        let root = effective_stdlib.ok_or_else(|| CompileError::syntax(
+            format!(
+                "cannot import '{}': no stdlib root found (tried --stdlib flag, PLUTO_STDLIB env var, and ./stdlib relative to entry file)",
+                full_path
+            ),
+            import.span,
+        ))?;
+        (root.to_path_buf(), &path[1..], current_deps, local_origin)
+    } else {
+        (module_dir.to_path_buf(), &path[..], current_deps, local_origin)
+    };
+    let dir = rest.iter().fold(root, |dir, segment| dir.join(&segment.node));
+    if !dir.is_dir() {
+        return Err(CompileError::syntax(
+            format!("cannot find module '{}': '{}' is not a directory", full_path, dir.display()),
+            import.span,
+        ));
+    }
+
+    let entries = std::fs::read_dir(&dir).map_err(|e| {
+        CompileError::codegen(format!("could not read directory '{}': {e}", dir.display()))
+    })?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| is_pluto_source(p))
+        .collect();
+    files.sort();
+    if files.is_empty() {
+        return Err(CompileError::syntax(
+            format!("glob import '{}' matched no source files in '{}'", full_path, dir.display()),
+            import.span,
+        ));
+    }
+
+    // Each file becomes module `binding.stem`, so stems must be unique identifiers
+    let binding = import.node.binding_name();
+    let mut stems: HashMap<String, &PathBuf> = HashMap::new();
+    for file in &files {
+        let stem = file.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        let is_ident = stem.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && stem.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_ident {
+            return Err(CompileError::syntax(
+                format!("glob import '{}' cannot import '{}': '{}' is not a valid module name", full_path, file.display(), stem),
+                import.span,
+            ));
+        }
+        if let Some(prev) = stems.insert(stem.to_string(), file) {
+            return Err(CompileError::syntax(
+                format!(
+                    "glob import '{}' has conflicting modules: '{}' and '{}' both define module '{}.{}'",
+                    full_path, prev.display(), file.display(), binding, stem
+                ),
+                import.span,
+            ));
+        }
+    }
+
+    let mut modules = Vec::new();
+    for file in &files {
+        let stem = file.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        let canonical = file.canonicalize().unwrap_or_else(|_| file.clone());
+        enter_import(visited, canonical)?;
+        let (mut module_prog, _) = load_file_auto(file, source_map)?;
+        resolve_module_imports(&mut module_prog, &dir, source_map, visited, effective_stdlib, deps, pkg_graph, origin)?;
+        visited.pop();
+        modules.push((prefix_name(binding, stem), module_prog, origin));
+    }
+    Ok(modules)
+}
+
 /// Load all .pluto files in a directory and merge into one Program.
 /// If `mod.pluto` exists, only that file is loaded; otherwise all .pluto files are auto-merged.
 /// Sub-imports within loaded files are recursively resolved and flattened into the result.
 fn load_directory_module(
     dir: &Path,
     source_map: &mut SourceMap,
-    visited: &mut HashSet<PathBuf>,
+    visited: &mut Vec<PathBuf>,
     effective_stdlib: Option<&Path>,
     current_deps: &DependencyScope,
     pkg_graph: &PackageGraph,
@@ -241,12 +371,7 @@ fn load_directory_module(
 ) -> Result<Program, CompileError> {
     // Directory cycle detection with closure cleanup pattern
     let canonical_dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
-    if visited.contains(&canonical_dir) {
-        return Err(CompileError::codegen(format!(
-            "circular import detected: '{}'", dir.display()
-        )));
-    }
-    visited.insert(canonical_dir.clone());
+    enter_import(visited, canonical_dir)?;
     let result = (|| {
         let mut merged = Program {
             imports: Vec::new(),
@@ -255,6 +380,8 @@ fn load_directory_module(
             classes: Vec::new(),
             traits: Vec::new(),
             enums: Vec::new(),
+            consts: Vec::new(),
+            globals: Vec::new(),
             app: None,
             stages: Vec::new(),
             system: None,
@@ -262,6 +389,7 @@ fn load_directory_module(
             test_info: Vec::new(),
             tests: None,
             fallible_extern_fns: Vec::new(),
+            comments: Vec::new(),
         };
 
         let source_files = collect_source_files(dir)?;
@@ -273,6 +401,8 @@ fn load_directory_module(
             merged.classes.extend(program.classes);
             merged.traits.extend(program.traits);
             merged.enums.extend(program.enums);
+            merged.consts.extend(program.consts);
+            merged.globals.extend(program.globals);
             if let Some(app_decl) = program.app {
                 if merged.app.is_some() {
                     return Err(CompileError::codegen(format!(
@@ -310,7 +440,7 @@ fn load_directory_module(
 
         Ok(merged)
     })();
-    visited.remove(&canonical_dir);
+    visited.pop();
     result
 }
 
@@ -321,7 +451,7 @@ fn resolve_module_path(
     base_dir: &Path,
     source_map: &mut SourceMap,
     import_span: Span,
-    visited: &mut HashSet<PathBuf>,
+    visited: &mut Vec<PathBuf>,
     effective_stdlib: Option<&Path>,
     current_deps: &DependencyScope,
     pkg_graph: &PackageGraph,
@@ -347,16 +477,10 @@ fn resolve_module_path(
 
     if let Some(file_path) = resolve_module_file(&current_dir, &final_seg.node) {
         let canonical = file_path.canonicalize().unwrap_or_else(|_| file_path.clone());
-        if visited.contains(&canonical) {
-            return Err(CompileError::codegen(format!(
-                "circular import detected: '{}'",
-                file_path.display()
-            )));
-        }
-        visited.insert(canonical.clone());
+        enter_import(visited, canonical)?;
         let (mut module_prog, _) = load_file_auto(&file_path, source_map)?;
         resolve_module_imports(&mut module_prog, &current_dir, source_map, visited, effective_stdlib, current_deps, pkg_graph, parent_origin)?;
-        visited.remove(&canonical);
+        visited.pop();
         Ok(module_prog)
     } else if dir_path.is_dir() {
         load_directory_module(&dir_path, source_map, visited, effective_stdlib, current_deps, pkg_graph, parent_origin)
@@ -377,7 +501,7 @@ fn resolve_module_imports(
     program: &mut Program,
     module_dir: &Path,
     source_map: &mut SourceMap,
-    visited: &mut HashSet<PathBuf>,
+    visited: &mut Vec<PathBuf>,
     effective_stdlib: Option<&Path>,
     current_deps: &DependencyScope,
     pkg_graph: &PackageGraph,
@@ -408,6 +532,12 @@ fn resolve_module_imports(
         }
         imported_names.insert(binding_name.clone(), full_path.clone());
 
+        if import.node.glob {
+            let modules = resolve_glob_import(import, module_dir, source_map, visited, effective_stdlib, current_deps, pkg_graph, parent_origin)?;
+            resolved_imports.extend(modules);
+            continue;
+        }
+
         let first_segment = &import.node.path[0].node;
 
         if import.node.path.len() == 1 {
@@ -438,16 +568,10 @@ fn resolve_module_imports(
                 resolved_imports.push((binding_name, module_prog, origin));
             } else if let Some(file_path) = file_path_candidate {
                 let canonical = file_path.canonicalize().unwrap_or_else(|_| file_path.clone());
-                if visited.contains(&canonical) {
-                    return Err(CompileError::codegen(format!(
-                        "circular import detected: '{}'",
-                        file_path.display()
-                    )));
-                }
-                visited.insert(canonical.clone());
+                enter_import(visited, canonical)?;
                 let (mut module_prog, _) = load_file_auto(&file_path, source_map)?;
                 resolve_module_imports(&mut module_prog, module_dir, source_map, visited, effective_stdlib, current_deps, pkg_graph, parent_origin)?;
-                visited.remove(&canonical);
+                visited.pop();
                 let origin = if parent_origin == ImportOrigin::PackageDep { ImportOrigin::PackageDep } else { ImportOrigin::Local };
                 resolved_imports.push((binding_name, module_prog, origin));
             } else {
@@ -508,7 +632,7 @@ fn resolve_module_imports(
     }
 
     // Flatten resolved imports into the program
-    flatten_into_program(program, resolved_imports)?;
+    flatten_into_program(program, &imports_to_resolve, resolved_imports)?;
 
     Ok(())
 }
@@ -518,6 +642,23 @@ fn prefix_name(module_name: &str, name: &str) -> String {
     format!("{}.{}", module_name, name)
 }
 
+/// Join path segments with dots: `[handlers, auth]` → "handlers.auth".
+fn join_segments(segments: &[Spanned<String>]) -> String {
+    segments.iter().map(|s| s.node.as_str()).collect::<Vec<_>>().join(".")
+}
+
+/// Number of leading `segments` that name an imported module, or 0 if none.
+/// Glob-imported modules have dotted names (`handlers.auth`), so the longest
+/// matching prefix wins.
+fn module_prefix_len(segments: &[Spanned<String>], module_names: &HashSet<String>) -> usize {
+    if module_names.is_empty() {
+        return 0;
+    }
+    (1..=segments.len()).rev()
+        .find(|&n| module_names.contains(&join_segments(&segments[..n])))
+        .unwrap_or(0)
+}
+
 /// Validate that imported modules don't contain app or extern_rust declarations.
 fn validate_imported_modules(imports: &[(String, Program, ImportOrigin)]) -> Result<(), CompileError> {
     for (module_name, module_prog, _origin) in imports {
@@ -575,6 +716,7 @@ fn add_prefixed_items(
             if let Some(ret) = &mut method.return_type {
                 prefix_type_expr(&mut ret.node, module_name, module_prog);
             }
+            prefix_declared_errors(&mut method.declared_errors, module_name, module_prog);
         }
         target.traits.push(prefixed_trait);
     }
@@ -588,9 +730,36 @@ fn add_prefixed_items(
                 prefix_type_expr(&mut field.ty.node, module_name, module_prog);
             }
         }
+        for method in &mut prefixed_enum.node.methods {
+            prefix_function_types(&mut method.node, module_name, module_prog);
+        }
         target.enums.push(prefixed_enum);
     }
 
+    // Consts
+    for const_decl in &module_prog.consts {
+        let mut prefixed_const = const_decl.clone();
+        prefixed_const.node.name.node = prefix_name(module_name, &const_decl.node.name.node);
+        if let Some(ty) = &mut prefixed_const.node.ty {
+            prefix_type_expr(&mut ty.node, module_name, module_prog);
+        }
+        let mut rewriter = ModuleRewriter { module_name, module_prog };
+        rewriter.visit_expr_mut(&mut prefixed_const.node.value);
+        target.consts.push(prefixed_const);
+    }
+
+    // Globals
+    for global_decl in &module_prog.globals {
+        let mut prefixed_global = global_decl.clone();
+        prefixed_global.node.name.node = prefix_name(module_name, &global_decl.node.name.node);
+        if let Some(ty) = &mut prefixed_global.node.ty {
+            prefix_type_expr(&mut ty.node, module_name, module_prog);
+        }
+        let mut rewriter = ModuleRewriter { module_name, module_prog };
+        rewriter.visit_expr_mut(&mut prefixed_global.node.value);
+        target.globals.push(prefixed_global);
+    }
+
     // Errors
     for error_decl in &module_prog.errors {
         let mut prefixed_error = error_decl.clone();
@@ -625,11 +794,13 @@ fn add_prefixed_items(
 /// Adds ALL items (not just pub) since visibility is deferred.
 fn flatten_into_program(
     program: &mut Program,
+    decls: &[Spanned<ImportDecl>],
     imports: Vec<(String, Program, ImportOrigin)>,
 ) -> Result<(), CompileError> {
-    let import_names: HashSet<String> = imports.iter().map(|(n, _, _)| n.clone()).collect();
+    let import_names = qualified_import_names(decls, &imports);
 
     validate_imported_modules(&imports)?;
+    apply_selective_imports(program, decls, &imports)?;
 
     for (module_name, module_prog, _origin) in &imports {
         add_prefixed_items(program, module_name, module_prog)?;
@@ -640,6 +811,227 @@ fn flatten_into_program(
     Ok(())
 }
 
+/// Names of imported modules reachable through qualified access (`module.item`).
+/// A module imported only selectively (`import math.{sqrt}`) is still flattened
+/// under its binding name, but only its selected items are in scope.
+fn qualified_import_names(
+    decls: &[Spanned<ImportDecl>],
+    imports: &[(String, Program, ImportOrigin)],
+) -> HashSet<String> {
+    let whole: HashSet<&str> = decls.iter()
+        .filter(|d| d.node.items.is_empty())
+        .map(|d| d.node.binding_name())
+        .collect();
+    let selective_only: HashSet<&str> = decls.iter()
+        .filter(|d| !d.node.items.is_empty() && !whole.contains(d.node.binding_name()))
+        .map(|d| d.node.binding_name())
+        .collect();
+    imports.iter()
+        .map(|(n, _, _)| n.clone())
+        .filter(|n| !selective_only.contains(n.as_str()))
+        .collect()
+}
+
+/// What a selectively imported symbol names, which decides the positions
+/// where its local name is rewritten.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SelectedKind {
+    Function,
+    Class,
+    Trait,
+    Enum,
+    Error,
+    /// A const or global.
+    Value,
+}
+
+/// A symbol brought into scope by a selective import, with its prefixed name.
+struct SelectedItem {
+    target: String,
+    kind: SelectedKind,
+}
+
+fn item_kind_and_visibility(module_prog: &Program, name: &str) -> Option<(SelectedKind, Visibility)> {
+    let named = |n: &str| n == name;
+    if let Some(f) = module_prog.functions.iter().find(|f| named(&f.node.name.node)) {
+        return Some((SelectedKind::Function, f.node.visibility));
+    }
+    if let Some(c) = module_prog.classes.iter().find(|c| named(&c.node.name.node)) {
+        return Some((SelectedKind::Class, c.node.visibility));
+    }
+    if let Some(t) = module_prog.traits.iter().find(|t| named(&t.node.name.node)) {
+        return Some((SelectedKind::Trait, t.node.visibility));
+    }
+    if let Some(e) = module_prog.enums.iter().find(|e| named(&e.node.name.node)) {
+        return Some((SelectedKind::Enum, e.node.visibility));
+    }
+    if let Some(e) = module_prog.errors.iter().find(|e| named(&e.node.name.node)) {
+        return Some((SelectedKind::Error, e.node.visibility));
+    }
+    if let Some(c) = module_prog.consts.iter().find(|c| named(&c.node.name.node)) {
+        return Some((SelectedKind::Value, c.node.visibility));
+    }
+    module_prog.globals.iter()
+        .find(|g| named(&g.node.name.node))
+        .map(|g| (SelectedKind::Value, g.node.visibility))
+}
+
+/// Bind the symbols of selective imports (`import math.{sqrt, pow as power}`)
+/// in `program` by rewriting their unqualified uses to the prefixed names the
+/// module is flattened under. Must run before the modules' items are merged
+/// into `program`, so only the importing module's own code is rewritten.
+fn apply_selective_imports(
+    program: &mut Program,
+    decls: &[Spanned<ImportDecl>],
+    imports: &[(String, Program, ImportOrigin)],
+) -> Result<(), CompileError> {
+    if decls.iter().all(|d| d.node.items.is_empty()) {
+        return Ok(());
+    }
+
+    let mut declared: HashSet<&str> = HashSet::new();
+    declared.extend(program.functions.iter().map(|f| f.node.name.node.as_str()));
+    declared.extend(program.classes.iter().map(|c| c.node.name.node.as_str()));
+    declared.extend(program.traits.iter().map(|t| t.node.name.node.as_str()));
+    declared.extend(program.enums.iter().map(|e| e.node.name.node.as_str()));
+    declared.extend(program.errors.iter().map(|e| e.node.name.node.as_str()));
+    declared.extend(program.consts.iter().map(|c| c.node.name.node.as_str()));
+    declared.extend(program.globals.iter().map(|g| g.node.name.node.as_str()));
+    let bindings: HashSet<&str> = decls.iter()
+        .filter(|d| d.node.items.is_empty())
+        .map(|d| d.node.binding_name())
+        .collect();
+
+    let mut selected: HashMap<String, SelectedItem> = HashMap::new();
+    for decl in decls.iter().filter(|d| !d.node.items.is_empty()) {
+        let module = decl.node.binding_name();
+        let Some((_, module_prog, origin)) = imports.iter().find(|(n, _, _)| n == module) else {
+            continue;
+        };
+        for item in &decl.node.items {
+            let local = item.local_name();
+            let span = item.alias.as_ref().unwrap_or(&item.name).span;
+            let Some((kind, visibility)) = item_kind_and_visibility(module_prog, &item.name.node) else {
+                return Err(CompileError::syntax(
+                    format!("module '{}' has no item '{}'", decl.node.full_path(), item.name.node),
+                    item.name.span,
+                ));
+            };
+            let visible = match visibility {
+                Visibility::Public => true,
+                Visibility::Module => *origin == ImportOrigin::Local,
+                Visibility::Private => false,
+            };
+            if !visible {
+                return Err(CompileError::type_err(
+                    visibility_error_message(module, &item.name.node, visibility),
+                    item.name.span,
+                ));
+            }
+            if declared.contains(local) || bindings.contains(local) {
+                return Err(CompileError::syntax(
+                    format!("import of '{}' from '{}' collides with an existing name '{}'", item.name.node, decl.node.full_path(), local),
+                    span,
+                ));
+            }
+            let target = prefix_name(module, &item.name.node);
+            if let Some(prev) = selected.get(local)
+                && prev.target != target
+            {
+                return Err(CompileError::syntax(
+                    format!("'{}' is imported twice, as '{}' and '{}'", local, prev.target, target),
+                    span,
+                ));
+            }
+            selected.insert(local.to_string(), SelectedItem { target, kind });
+        }
+    }
+
+    SelectedItemRewriter { selected: &selected }.visit_program_mut(program);
+    Ok(())
+}
+
+/// Rewrites unqualified references to selectively imported symbols into their
+/// module-prefixed names, as if they had been written `module.item`.
+struct SelectedItemRewriter<'a> {
+    selected: &'a HashMap<String, SelectedItem>,
+}
+
+impl SelectedItemRewriter<'_> {
+    fn rename(&self, name: &mut String, kinds: &[SelectedKind]) {
+        if let Some(item) = self.selected.get(name.as_str())
+            && kinds.contains(&item.kind)
+        {
+            *name = item.target.clone();
+        }
+    }
+}
+
+const TYPE_KINDS: &[SelectedKind] = &[SelectedKind::Class, SelectedKind::Trait, SelectedKind::Enum, SelectedKind::Error];
+
+impl VisitMut for SelectedItemRewriter<'_> {
+    fn visit_expr_mut(&mut self, expr: &mut Spanned<Expr>) {
+        match &mut expr.node {
+            Expr::Call { name, .. } => self.rename(&mut name.node, &[SelectedKind::Function]),
+            Expr::Ident(name) => self.rename(name, &[SelectedKind::Value]),
+            Expr::StructLit { name, .. } => self.rename(&mut name.node, &[SelectedKind::Class]),
+            Expr::EnumUnit { enum_name, .. }
+            | Expr::EnumData { enum_name, .. }
+            | Expr::Is { enum_name, .. } => self.rename(&mut enum_name.node, &[SelectedKind::Enum]),
+            Expr::MethodRef { type_name, .. } => self.rename(&mut type_name.node, &[SelectedKind::Class, SelectedKind::Enum]),
+            // `Hue.Red` or `CONFIG.field`: resolve_qualified_access maps the prefixed name
+            Expr::QualifiedAccess { segments } => {
+                self.rename(&mut segments[0].node, &[SelectedKind::Enum, SelectedKind::Value]);
+            }
+            Expr::Catch { handlers, .. } => {
+                for handler in handlers {
+                    if let CatchHandler::Typed { error_type, .. } = handler {
+                        self.rename(&mut error_type.node, &[SelectedKind::Error]);
+                    }
+                }
+            }
+            _ => {}
+        }
+        walk_expr_mut(self, expr);
+    }
+
+    fn visit_stmt_mut(&mut self, stmt: &mut Spanned<Stmt>) {
+        match &mut stmt.node {
+            Stmt::Match { arms, .. } => {
+                for arm in arms {
+                    self.rename(&mut arm.enum_name.node, &[SelectedKind::Enum]);
+                }
+            }
+            Stmt::Raise { error_name, .. } => self.rename(&mut error_name.node, &[SelectedKind::Error]),
+            Stmt::Assign { target, .. } => self.rename(&mut target.node, &[SelectedKind::Value]),
+            _ => {}
+        }
+        walk_stmt_mut(self, stmt);
+    }
+
+    fn visit_type_expr_mut(&mut self, te: &mut Spanned<TypeExpr>) {
+        match &mut te.node {
+            TypeExpr::Named(name) | TypeExpr::Generic { name, .. } => self.rename(name, TYPE_KINDS),
+            _ => {}
+        }
+        walk_type_expr_mut(self, te);
+    }
+
+    fn visit_function_mut(&mut self, func: &mut Spanned<Function>) {
+        for error in func.node.declared_errors.iter_mut().flatten() {
+            self.rename(&mut error.node, &[SelectedKind::Error]);
+        }
+        walk_function_mut(self, func);
+    }
+
+    fn visit_class_mut(&mut self, class: &mut Spanned<ClassDecl>) {
+        for trait_name in &mut class.node.impl_traits {
+            self.rename(&mut trait_name.node, &[SelectedKind::Trait]);
+        }
+        walk_class_mut(self, class);
+    }
+}
+
 /// Compare two TypeExpr values ignoring source spans.
 fn type_expr_eq(a: &TypeExpr, b: &TypeExpr) -> bool {
     match (a, b) {
@@ -665,7 +1057,7 @@ fn type_expr_eq(a: &TypeExpr, b: &TypeExpr) -> bool {
 
 /// Check if two extern fn declarations have matching signatures.
 fn extern_fn_sigs_match(a: &ExternFnDecl, b: &ExternFnDecl) -> bool {
-    if a.params.len() != b.params.len() {
+    if a.params.len() != b.params.len() || a.c_abi != b.c_abi {
         return false;
     }
     for (pa, pb) in a.params.iter().zip(b.params.iter()) {
@@ -733,8 +1125,7 @@ fn resolve_modules_inner(
     let current_deps = pkg_graph.root_deps();
 
     // Circular import detection: track canonical paths in resolution stack
-    let mut visited = HashSet::new();
-    visited.insert(entry_file.clone());
+    let mut visited = vec![entry_file.clone()];
 
     // First, parse the entry file to discover imports
     let (entry_prog, _entry_file_id) = load_file_auto(&entry_file, &mut source_map)?;
@@ -781,6 +1172,8 @@ fn resolve_modules_inner(
             root.classes.extend(program.classes);
             root.traits.extend(program.traits);
             root.enums.extend(program.enums);
+            root.consts.extend(program.consts);
+            root.globals.extend(program.globals);
             if let Some(app_decl) = program.app {
                 if root.app.is_some() {
                     return Err(CompileError::codegen(
@@ -824,6 +1217,13 @@ fn resolve_modules_inner(
         }
         imported_names.insert(binding_name.clone(), full_path.clone());
 
+        if import.node.glob {
+            // Glob import (e.g., `import handlers.*`) — one module per file, resolved from entry_dir
+            let modules = resolve_glob_import(import, entry_dir, &mut source_map, &mut visited, effective_stdlib, current_deps, pkg_graph, ImportOrigin::Local)?;
+            imports.extend(modules);
+            continue;
+        }
+
         let first_segment = &import.node.path[0].node;
 
         if import.node.path.len() == 1 {
@@ -853,17 +1253,11 @@ fn resolve_modules_inner(
                 imports.push((binding_name, module_prog, ImportOrigin::Local));
             } else if let Some(file_path) = file_path_candidate {
                 let canonical = file_path.canonicalize().unwrap_or_else(|_| file_path.clone());
-                if visited.contains(&canonical) {
-                    return Err(CompileError::codegen(format!(
-                        "circular import detected: '{}'",
-                        file_path.display()
-                    )));
-                }
-                visited.insert(canonical.clone());
+                enter_import(&mut visited, canonical)?;
                 let (mut module_prog, _) = load_file_auto(&file_path, &mut source_map)?;
                 // Recursively resolve sub-imports
                 resolve_module_imports(&mut module_prog, entry_dir, &mut source_map, &mut visited, effective_stdlib, current_deps, pkg_graph, ImportOrigin::Local)?;
-                visited.remove(&canonical);
+                visited.pop();
                 imports.push((binding_name, module_prog, ImportOrigin::Local));
             } else {
                 // Check .deps/ directory (vendored dependencies)
@@ -944,25 +1338,34 @@ fn resolve_modules_inner(
 /// - Add ALL items with prefixed names (visibility deferred)
 /// - Rewrite qualified references in the root program's AST
 /// Validate that qualified cross-module references (`module.item`) target items
-/// declared `pub`. A qualified reference is only ever written from *outside* the
-/// module (intra-module code uses unqualified names), so any `M.item` reference
-/// requires `item` to be public in `M`.
+/// visible to the root program. A qualified reference is only ever written from
+/// *outside* the module (intra-module code uses unqualified names), so any
+/// `M.item` reference requires `item` to be `pub` in `M`, or `pub(module)` when
+/// `M` belongs to the same package rather than a dependency.
 struct VisibilityValidator<'a> {
     imports: &'a HashSet<String>,
-    pub_items: &'a HashMap<String, HashSet<String>>,
-    all_items: &'a HashMap<String, HashSet<String>>,
-    violations: Vec<(String, String, Span)>,
+    items: &'a HashMap<String, HashMap<String, Visibility>>,
+    origins: &'a HashMap<String, ImportOrigin>,
+    violations: Vec<(String, String, Visibility, Span)>,
 }
 
 impl VisibilityValidator<'_> {
     // Only flags a reference to an item that genuinely exists in the module and
-    // is non-pub — so imperfect name-splitting can miss but never over-flag.
+    // is not visible — so imperfect name-splitting can miss but never over-flag.
     fn check(&mut self, module: &str, item: &str, span: Span) {
-        if self.imports.contains(module)
-            && self.all_items.get(module).is_some_and(|s| s.contains(item))
-            && !self.pub_items.get(module).is_some_and(|s| s.contains(item))
-        {
-            self.violations.push((module.to_string(), item.to_string(), span));
+        if !self.imports.contains(module) {
+            return;
+        }
+        let Some(&visibility) = self.items.get(module).and_then(|s| s.get(item)) else {
+            return;
+        };
+        let visible = match visibility {
+            Visibility::Public => true,
+            Visibility::Module => self.origins.get(module) == Some(&ImportOrigin::Local),
+            Visibility::Private => false,
+        };
+        if !visible {
+            self.violations.push((module.to_string(), item.to_string(), visibility, span));
         }
     }
     // A `module.item` reference stored as a single dotted string (the item is the
@@ -978,18 +1381,26 @@ impl crate::visit::Visitor for VisibilityValidator<'_> {
     fn visit_expr(&mut self, expr: &Spanned<Expr>) {
         match &expr.node {
             // `module.func(...)` — a MethodCall on an Ident naming a module.
-            Expr::MethodCall { object, method, .. } => {
-                if let Expr::Ident(m) = &object.node {
-                    self.check(m, &method.node, expr.span);
-                }
-            }
+            Expr::MethodCall { object, method, .. } => match &object.node {
+                Expr::Ident(m) => self.check(m, &method.node, expr.span),
+                // `dir.module.func(...)` on a glob-imported module
+                Expr::QualifiedAccess { segments } => self.check(&join_segments(segments), &method.node, expr.span),
+                _ => {}
+            },
             Expr::Call { name, .. } => self.check_dotted(&name.node, name.span),
             Expr::StructLit { name, .. } => self.check_dotted(&name.node, name.span),
-            Expr::EnumUnit { enum_name, .. } | Expr::EnumData { enum_name, .. } => {
-                self.check_dotted(&enum_name.node, enum_name.span)
+            // `dir.module.Type { .. }` parses as enum data naming the module
+            Expr::EnumData { enum_name, variant, .. } if self.imports.contains(&enum_name.node) => {
+                self.check(&enum_name.node, &variant.node, enum_name.span);
             }
+            Expr::EnumUnit { enum_name, .. }
+            | Expr::EnumData { enum_name, .. }
+            | Expr::Is { enum_name, .. } => self.check_dotted(&enum_name.node, enum_name.span),
             Expr::QualifiedAccess { segments } if segments.len() >= 2 => {
-                self.check(&segments[0].node, &segments[1].node, expr.span);
+                let module_len = module_prefix_len(segments, self.imports).max(1);
+                if module_len < segments.len() {
+                    self.check(&join_segments(&segments[..module_len]), &segments[module_len].node, expr.span);
+                }
             }
             _ => {}
         }
@@ -1005,27 +1416,23 @@ impl crate::visit::Visitor for VisibilityValidator<'_> {
 
 fn validate_module_visibility(graph: &ModuleGraph) -> Result<(), CompileError> {
     let imports: HashSet<String> = graph.imports.iter().map(|(n, _, _)| n.clone()).collect();
-    let mut pub_items: HashMap<String, HashSet<String>> = HashMap::new();
-    let mut all_items: HashMap<String, HashSet<String>> = HashMap::new();
-    for (name, prog, _) in &graph.imports {
-        let mut all = HashSet::new();
-        let mut pubs = HashSet::new();
-        let mut add = |n: &str, is_pub: bool| {
-            all.insert(n.to_string());
-            if is_pub { pubs.insert(n.to_string()); }
-        };
-        for f in &prog.functions { add(&f.node.name.node, f.node.is_pub); }
-        for c in &prog.classes { add(&c.node.name.node, c.node.is_pub); }
-        for e in &prog.enums { add(&e.node.name.node, e.node.is_pub); }
-        for e in &prog.errors { add(&e.node.name.node, e.node.is_pub); }
-        for t in &prog.traits { add(&t.node.name.node, t.node.is_pub); }
-        drop(add);
-        all_items.insert(name.clone(), all);
-        pub_items.insert(name.clone(), pubs);
+    let mut items: HashMap<String, HashMap<String, Visibility>> = HashMap::new();
+    let mut origins: HashMap<String, ImportOrigin> = HashMap::new();
+    for (name, prog, origin) in &graph.imports {
+        let mut all = HashMap::new();
+        for f in &prog.functions { all.insert(f.node.name.node.clone(), f.node.visibility); }
+        for c in &prog.classes { all.insert(c.node.name.node.clone(), c.node.visibility); }
+        for e in &prog.enums { all.insert(e.node.name.node.clone(), e.node.visibility); }
+        for e in &prog.errors { all.insert(e.node.name.node.clone(), e.node.visibility); }
+        for c in &prog.consts { all.insert(c.node.name.node.clone(), c.node.visibility); }
+        for g in &prog.globals { all.insert(g.node.name.node.clone(), g.node.visibility); }
+        for t in &prog.traits { all.insert(t.node.name.node.clone(), t.node.visibility); }
+        items.insert(name.clone(), all);
+        origins.insert(name.clone(), *origin);
     }
 
     let mut v = VisibilityValidator {
-        imports: &imports, pub_items: &pub_items, all_items: &all_items,
+        imports: &imports, items: &items, origins: &origins,
         violations: Vec::new(),
     };
     use crate::visit::Visitor;
@@ -1035,19 +1442,27 @@ fn validate_module_visibility(graph: &ModuleGraph) -> Result<(), CompileError> {
     // qualified references are intra-module and must not be treated as the
     // user's cross-module access.
     v.visit_program(&graph.root);
-    if let Some((module, item, span)) = v.violations.into_iter().next() {
-        return Err(CompileError::type_err(
-            format!("'{item}' is private to module '{module}'; declare it `pub` to use it from another module"),
-            span,
-        ));
+    if let Some((module, item, visibility, span)) = v.violations.into_iter().next() {
+        return Err(CompileError::type_err(visibility_error_message(&module, &item, visibility), span));
     }
     Ok(())
 }
 
+fn visibility_error_message(module: &str, item: &str, visibility: Visibility) -> String {
+    if visibility == Visibility::Module {
+        format!("'{item}' is pub(module) in package '{module}' and cannot be used by dependent packages; declare it `pub` to export it")
+    } else {
+        format!("'{item}' is private to module '{module}'; declare it `pub` to use it from another module")
+    }
+}
+
 pub fn flatten_modules(mut graph: ModuleGraph) -> Result<(Program, SourceMap), CompileError> {
-    let import_names: HashSet<String> = graph.imports.iter().map(|(n, _, _)| n.clone()).collect();
+    let import_names = qualified_import_names(&graph.root.imports, &graph.imports);
 
     validate_imported_modules(&graph.imports)?;
+    let decls = std::mem::take(&mut graph.root.imports);
+    apply_selective_imports(&mut graph.root, &decls, &graph.imports)?;
+    graph.root.imports = decls;
     validate_module_visibility(&graph)?;
 
     // Filter out test functions from imported modules before merging
@@ -1138,10 +1553,20 @@ fn prefix_function_types(func: &mut Function, module_name: &str, module_prog: &P
     if let Some(ret) = &mut func.return_type {
         prefix_type_expr(&mut ret.node, module_name, module_prog);
     }
+    prefix_declared_errors(&mut func.declared_errors, module_name, module_prog);
     // Also rewrite expressions inside the body that reference internal types
     rewrite_block_for_module(&mut func.body.node, module_name, module_prog);
 }
 
+/// Prefix module-local error names in a declared error set, as `raise` does.
+fn prefix_declared_errors(errors: &mut Option<Vec<Spanned<String>>>, module_name: &str, module_prog: &Program) {
+    for err in errors.iter_mut().flatten() {
+        if module_prog.errors.iter().any(|e| e.node.name.node == err.node) {
+            err.node = prefix_name(module_name, &err.node);
+        }
+    }
+}
+
 /// Rewrite expressions inside a block for module-internal references.
 struct ModuleRewriter<'a> {
     module_name: &'a str,
@@ -1152,6 +1577,12 @@ impl VisitMut for ModuleRewriter<'_> {
     fn visit_expr_mut(&mut self, expr: &mut Spanned<Expr>) {
         // Handle expressions that need name prefixing
         match &mut expr.node {
+            Expr::Ident(name)
+                if self.module_prog.consts.iter().any(|c| c.node.name.node == *name)
+                    || self.module_prog.globals.iter().any(|g| g.node.name.node == *name) =>
+            {
+                *name = prefix_name(self.module_name, name);
+            }
             Expr::Call { name, .. } => {
                 // Prefix calls to module-internal functions (but NOT extern fns)
                 if self.module_prog.functions.iter().any(|f| f.node.name.node == name.node) {
@@ -1192,6 +1623,12 @@ impl VisitMut for ModuleRewriter<'_> {
             Expr::Cast { target_type, .. } => {
                 prefix_type_expr(&mut target_type.node, self.module_name, self.module_prog);
             }
+            Expr::Is { enum_name, .. } if is_module_type(&enum_name.node, self.module_prog) => {
+                enum_name.node = prefix_name(self.module_name, &enum_name.node);
+            }
+            Expr::MethodRef { type_name, .. } if is_module_type(&type_name.node, self.module_prog) => {
+                type_name.node = prefix_name(self.module_name, &type_name.node);
+            }
             Expr::Closure { params, .. } => {
                 for p in params {
                     prefix_type_expr(&mut p.ty.node, self.module_name, self.module_prog);
@@ -1234,9 +1671,17 @@ impl VisitMut for ModuleRewriter<'_> {
                     error_name.node = prefix_name(self.module_name, &error_name.node);
                 }
             }
+            Stmt::Assign { target, .. } => {
+                if self.module_prog.globals.iter().any(|g| g.node.name.node == target.node) {
+                    target.node = prefix_name(self.module_name, &target.node);
+                }
+            }
             Stmt::LetChan { elem_type, .. } => {
                 prefix_type_expr(&mut elem_type.node, self.module_name, self.module_prog);
             }
+            Stmt::LetStruct { ty, .. } => {
+                prefix_type_expr(&mut ty.node, self.module_name, self.module_prog);
+            }
             Stmt::Scope { bindings, .. } => {
                 for binding in bindings {
                     prefix_type_expr(&mut binding.ty.node, self.module_name, self.module_prog);
@@ -1304,6 +1749,9 @@ fn rewrite_program(program: &mut Program, import_names: &HashSet<String>) {
                 rewrite_type_expr(&mut field.ty, import_names);
             }
         }
+        for method in &mut enum_decl.node.methods {
+            rewrite_function_body(&mut method.node, import_names);
+        }
     }
     if let Some(app) = &mut program.app {
         for method in &mut app.node.methods {
@@ -1410,6 +1858,37 @@ impl VisitMut for QualifiedAccessRewriter<'_> {
                     };
                     return;
                 }
+                // Same for a dotted (glob-imported) module: handlers.auth.login()
+                if let Expr::QualifiedAccess { segments } = &object.node
+                    && module_prefix_len(segments, self.import_names) == segments.len()
+                {
+                    let qualified_name = prefix_name(&join_segments(segments), &method.node);
+                    let name_span = Span::new(object.span.start, method.span.end);
+                    for arg in args.iter_mut() {
+                        self.visit_expr_mut(arg);
+                    }
+                    expr.node = Expr::Call {
+                        name: Spanned::new(qualified_name, name_span),
+                        args: std::mem::take(args),
+                        type_args: vec![],
+                        target_id: None,
+                    };
+                    return;
+                }
+            }
+            // The parser reads `dir.module.Type { .. }` as enum data `dir.module` / `Type`;
+            // when `dir.module` is a glob-imported module it is a struct literal
+            Expr::EnumData { enum_name, variant, fields, .. } if self.import_names.contains(enum_name.node.as_str()) => {
+                let name = prefix_name(&enum_name.node, &variant.node);
+                let name_span = Span::new(enum_name.span.start, variant.span.end);
+                expr.node = Expr::StructLit {
+                    name: Spanned::new(name, name_span),
+                    type_args: vec![],
+                    fields: std::mem::take(fields),
+                    target_id: None,
+                };
+                walk_expr_mut(self, expr);
+                return;
             }
             Expr::FieldAccess { object, field } => {
                 // Check for module-qualified enum access: status.State.Active
@@ -1486,6 +1965,9 @@ impl VisitMut for QualifiedAccessRewriter<'_> {
             Stmt::LetChan { elem_type, .. } => {
                 rewrite_type_expr(elem_type, self.import_names);
             }
+            Stmt::LetStruct { ty, .. } => {
+                rewrite_type_expr(ty, self.import_names);
+            }
             Stmt::Scope { bindings, .. } => {
                 for binding in bindings {
                     rewrite_type_expr(&mut binding.ty, self.import_names);
@@ -1544,6 +2026,14 @@ fn resolve_qualified_access_in_program(program: &mut Program, module_names: &Has
             resolve_qualified_access_in_block(&mut method.node.body.node, module_names, &enum_name_map);
         }
     }
+    for enum_decl in &mut program.enums {
+        for method in &mut enum_decl.node.methods {
+            for contract in &mut method.node.contracts {
+                resolve_qualified_access_in_expr(&mut contract.node.expr.node, contract.node.expr.span, module_names, &enum_name_map);
+            }
+            resolve_qualified_access_in_block(&mut method.node.body.node, module_names, &enum_name_map);
+        }
+    }
     if let Some(app) = &mut program.app {
         for method in &mut app.node.methods {
             // Resolve in app method contracts
@@ -1572,7 +2062,7 @@ fn resolve_qualified_access_in_block(block: &mut Block, module_names: &HashSet<S
 
 fn resolve_qualified_access_in_stmt(stmt: &mut Stmt, module_names: &HashSet<String>, enum_name_map: &HashMap<String, String>) {
     match stmt {
-        Stmt::Let { value, .. } => {
+        Stmt::Let { value, .. } | Stmt::LetStruct { value, .. } => {
             resolve_qualified_access_in_expr(&mut value.node, value.span, module_names, enum_name_map);
         }
         Stmt::Return(Some(expr)) => {
@@ -1593,6 +2083,28 @@ fn resolve_qualified_access_in_stmt(stmt: &mut Stmt, module_names: &HashSet<Stri
                 resolve_qualified_access_in_block(&mut eb.node, module_names, enum_name_map);
             }
         }
+        Stmt::IfLet { value, then_block, else_block, .. } => {
+            resolve_qualified_access_in_expr(&mut value.node, value.span, module_names, enum_name_map);
+            resolve_qualified_access_in_block(&mut then_block.node, module_names, enum_name_map);
+            if let Some(eb) = else_block {
+                resolve_qualified_access_in_block(&mut eb.node, module_names, enum_name_map);
+            }
+        }
+        Stmt::IntMatch { expr, arms } => {
+            resolve_qualified_access_in_expr(&mut expr.node, expr.span, module_names, enum_name_map);
+            for arm in arms {
+                resolve_qualified_access_in_block(&mut arm.body.node, module_names, enum_name_map);
+            }
+        }
+        Stmt::NullableMatch { expr, some_arm, none_arm } => {
+            resolve_qualified_access_in_expr(&mut expr.node, expr.span, module_names, enum_name_map);
+            if let Some(arm) = some_arm {
+                resolve_qualified_access_in_block(&mut arm.body.node, module_names, enum_name_map);
+            }
+            if let Some(nb) = none_arm {
+                resolve_qualified_access_in_block(&mut nb.node, module_names, enum_name_map);
+            }
+        }
         Stmt::While { condition, body } => {
             resolve_qualified_access_in_expr(&mut condition.node, condition.span, module_names, enum_name_map);
             resolve_qualified_access_in_block(&mut body.node, module_names, enum_name_map);
@@ -1648,6 +2160,13 @@ fn resolve_qualified_access_in_stmt(stmt: &mut Stmt, module_names: &HashSet<Stri
             }
             resolve_qualified_access_in_block(&mut body.node, module_names, enum_name_map);
         }
+        Stmt::Try { body, handler, finally, .. } => {
+            resolve_qualified_access_in_block(&mut body.node, module_names, enum_name_map);
+            resolve_qualified_access_in_block(&mut handler.node, module_names, enum_name_map);
+            if let Some(finally) = finally {
+                resolve_qualified_access_in_block(&mut finally.node, module_names, enum_name_map);
+            }
+        }
         Stmt::Yield { value, .. } => {
             resolve_qualified_access_in_expr(&mut value.node, value.span, module_names, enum_name_map);
         }
@@ -1669,24 +2188,29 @@ fn resolve_qualified_access_in_expr(expr: &mut Expr, span: Span, module_names: &
                 return;
             }
 
-            // Check if first segment is a module name
-            let is_module_reference = module_names.contains(&segments[0].node);
+            // Check if the leading segments name a module (possibly dotted, for glob imports)
+            let module_len = module_prefix_len(segments, module_names);
 
-            if is_module_reference {
+            if module_len > 0 {
+                let module = join_segments(&segments[..module_len]);
+                let rest = &segments[module_len..];
                 // Convert module.Enum.Variant to EnumUnit
-                if segments.len() == 3 {
-                    let qualified_enum = format!("{}.{}", segments[0].node, segments[1].node);
-                    let enum_span = Span::new(segments[0].span.start, segments[1].span.end);
+                if rest.len() == 2 {
+                    let qualified_enum = prefix_name(&module, &rest[0].node);
+                    let enum_span = Span::new(segments[0].span.start, rest[0].span.end);
                     *expr = Expr::EnumUnit {
                         enum_name: Spanned::new(qualified_enum, enum_span),
-                        variant: segments[2].clone(),
+                        variant: rest[1].clone(),
                         type_args: vec![],
                         enum_id: None,
                         variant_id: None,
                     };
                     return;
                 }
-                // For 2-segment patterns (module.Type), keep as QualifiedAccess for type checking
+                // module.NAME as a value reads a flattened module-level item (a const)
+                if rest.len() == 1 {
+                    *expr = Expr::Ident(prefix_name(&module, &rest[0].node));
+                }
                 return;
             }
 
@@ -1773,6 +2297,10 @@ fn resolve_qualified_access_in_expr(expr: &mut Expr, span: Span, module_names: &
         Expr::Propagate { expr: inner } => {
             resolve_qualified_access_in_expr(&mut inner.node, inner.span, module_names, enum_name_map);
         }
+        Expr::PropagateMap { expr: inner, mapper } => {
+            resolve_qualified_access_in_expr(&mut inner.node, inner.span, module_names, enum_name_map);
+            resolve_qualified_access_in_expr(&mut mapper.node, mapper.span, module_names, enum_name_map);
+        }
         Expr::Catch { expr: inner, handlers } => {
             resolve_qualified_access_in_expr(&mut inner.node, inner.span, module_names, enum_name_map);
             for handler in handlers {
@@ -1797,7 +2325,7 @@ fn resolve_qualified_access_in_expr(expr: &mut Expr, span: Span, module_names: &
                 resolve_qualified_access_in_expr(&mut elem.node, elem.span, module_names, enum_name_map);
             }
         }
-        Expr::Cast { expr: inner, .. } => {
+        Expr::Cast { expr: inner, .. } | Expr::Is { expr: inner, .. } => {
             resolve_qualified_access_in_expr(&mut inner.node, inner.span, module_names, enum_name_map);
         }
         Expr::Range { start, end, .. } => {
@@ -1831,7 +2359,8 @@ fn resolve_qualified_access_in_expr(expr: &mut Expr, span: Span, module_names: &
             }
         }
         Expr::IntLit(_) | Expr::FloatLit(_) | Expr::BoolLit(_) | Expr::StringLit(_)
-        | Expr::Ident(_) | Expr::EnumUnit { .. } | Expr::ClosureCreate { .. } | Expr::NoneLit => {}
+        | Expr::Ident(_) | Expr::EnumUnit { .. } | Expr::ClosureCreate { .. } | Expr::NoneLit
+        | Expr::MethodRef { .. } => {}
     }
     let _ = span;
 }
@@ -2075,13 +2604,15 @@ mod tests {
             name: spanned("foo".to_string()),
             params: vec![],
             return_type: None,
-            is_pub: false,
+            visibility: Visibility::Private,
+            c_abi: false,
         };
         let b = ExternFnDecl {
             name: spanned("foo".to_string()),
             params: vec![],
             return_type: None,
-            is_pub: false,
+            visibility: Visibility::Private,
+            c_abi: false,
         };
         assert!(extern_fn_sigs_match(&a, &b));
     }
@@ -2107,7 +2638,8 @@ mod tests {
                 },
             ],
             return_type: Some(spanned(TypeExpr::Named("bool".to_string()))),
-            is_pub: false,
+            visibility: Visibility::Private,
+            c_abi: false,
         };
         let b = ExternFnDecl {
             name: spanned("foo".to_string()),
@@ -2126,7 +2658,8 @@ mod tests {
                 },
             ],
             return_type: Some(spanned(TypeExpr::Named("bool".to_string()))),
-            is_pub: false,
+            visibility: Visibility::Private,
+            c_abi: false,
         };
         assert!(extern_fn_sigs_match(&a, &b));
     }
@@ -2144,13 +2677,15 @@ mod tests {
                 is_mut: false,
             }],
             return_type: None,
-            is_pub: false,
+            visibility: Visibility::Private,
+            c_abi: false,
         };
         let b = ExternFnDecl {
             name: spanned("foo".to_string()),
             params: vec![],
             return_type: None,
-            is_pub: false,
+            visibility: Visibility::Private,
+            c_abi: false,
         };
         assert!(!extern_fn_sigs_match(&a, &b));
     }
@@ -2168,7 +2703,8 @@ mod tests {
                 is_mut: false,
             }],
             return_type: None,
-            is_pub: false,
+            visibility: Visibility::Private,
+            c_abi: false,
         };
         let b = ExternFnDecl {
             name: spanned("foo".to_string()),
@@ -2179,7 +2715,8 @@ mod tests {
                 is_mut: false,
             }],
             return_type: None,
-            is_pub: false,
+            visibility: Visibility::Private,
+            c_abi: false,
         };
         assert!(!extern_fn_sigs_match(&a, &b));
     }
@@ -2190,13 +2727,15 @@ mod tests {
             name: spanned("foo".to_string()),
             params: vec![],
             return_type: Some(spanned(TypeExpr::Named("int".to_string()))),
-            is_pub: false,
+            visibility: Visibility::Private,
+            c_abi: false,
         };
         let b = ExternFnDecl {
             name: spanned("foo".to_string()),
             params: vec![],
             return_type: Some(spanned(TypeExpr::Named("string".to_string()))),
-            is_pub: false,
+            visibility: Visibility::Private,
+            c_abi: false,
         };
         assert!(!extern_fn_sigs_match(&a, &b));
     }
@@ -2207,13 +2746,15 @@ mod tests {
             name: spanned("foo".to_string()),
             params: vec![],
             return_type: Some(spanned(TypeExpr::Named("int".to_string()))),
-            is_pub: false,
+            visibility: Visibility::Private,
+            c_abi: false,
         };
         let b = ExternFnDecl {
             name: spanned("foo".to_string()),
             params: vec![],
             return_type: None,
-            is_pub: false,
+            visibility: Visibility::Private,
+            c_abi: false,
         };
         assert!(!extern_fn_sigs_match(&a, &b));
     }
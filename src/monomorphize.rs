@@ -89,6 +89,7 @@ pub fn monomorphize(program: &mut Program, env: &mut TypeEnv) -> Result<(), Comp
             let mangled = mangle_name(
                 match &inst.kind {
                     InstKind::Function(n) | InstKind::Class(n) | InstKind::Enum(n) => n.as_str(),
+                    InstKind::Method(_, method) => method.as_str(),
                 },
                 &inst.type_args,
             );
@@ -103,6 +104,9 @@ pub fn monomorphize(program: &mut Program, env: &mut TypeEnv) -> Result<(), Comp
                 InstKind::Enum(name) => {
                     instantiate_enum(program, name, &inst.type_args, &mangled, span_offset);
                 }
+                InstKind::Method(class_name, method) => {
+                    instantiate_method(program, env, class_name, method, &inst.type_args, &mangled, span_offset)?;
+                }
             }
 
             processed.insert(inst);
@@ -119,6 +123,9 @@ pub fn monomorphize(program: &mut Program, env: &mut TypeEnv) -> Result<(), Comp
     program.functions.retain(|f| f.node.type_params.is_empty());
     program.classes.retain(|c| c.node.type_params.is_empty());
     program.enums.retain(|e| e.node.type_params.is_empty());
+    for class in &mut program.classes {
+        class.node.methods.retain(|m| m.node.type_params.is_empty());
+    }
 
     Ok(())
 }
@@ -167,6 +174,55 @@ fn instantiate_function(
     Ok(())
 }
 
+/// Add the instance `mangled` (`method$$T...`) of a generic method to its class.
+fn instantiate_method(
+    program: &mut Program,
+    env: &mut TypeEnv,
+    class_name: &str,
+    method: &str,
+    type_args: &[PlutoType],
+    mangled: &str,
+    span_offset: usize,
+) -> Result<(), CompileError> {
+    let class = program
+        .classes
+        .iter_mut()
+        .find(|c| c.node.name.node == class_name && c.node.type_params.is_empty())
+        .ok_or_else(|| CompileError::type_err(format!("class '{}' not found", class_name), Span::dummy()))?;
+    let template = class
+        .node
+        .methods
+        .iter()
+        .find(|m| m.node.name.node == method && !m.node.type_params.is_empty())
+        .ok_or_else(|| CompileError::type_err(format!("generic method '{}.{}' not found", class_name, method), Span::dummy()))?
+        .clone();
+
+    let type_params: Vec<String> = template.node.type_params.iter().map(|tp| tp.node.clone()).collect();
+    let bindings = build_type_expr_bindings(&type_params, type_args);
+
+    let mut func = template.node.clone();
+    func.id = Uuid::new_v4();
+    reassign_function_uuids(&mut func);
+    func.name = Spanned::new(mangled.to_string(), template.node.name.span);
+    func.type_params.clear();
+    substitute_in_function(&mut func, &bindings);
+    offset_function_spans(&mut func, span_offset);
+
+    class.node.methods.push(Spanned::new(func.clone(), Span::with_file(
+        template.span.start + span_offset,
+        template.span.end + span_offset,
+        template.span.file_id,
+    )));
+    if let Some(info) = env.classes.get_mut(class_name) {
+        info.methods.push(mangled.to_string());
+    }
+
+    // Type-check the body to discover transitive instantiations
+    crate::typeck::check_function(&func, env, Some(class_name))?;
+
+    Ok(())
+}
+
 fn instantiate_class(
     program: &mut Program,
     env: &mut TypeEnv,
@@ -192,6 +248,13 @@ fn instantiate_class(
     class.type_params.clear();
     substitute_in_class(&mut class, &bindings);
     offset_class_spans(&mut class, span_offset);
+    class.where_bounds.clear();
+    // Typeck already dropped the impl list (and the methods implementing it)
+    // for instantiations that fail the where clause; no vtable for those.
+    if let Some(info) = env.classes.get(mangled) {
+        class.impl_traits.retain(|t| info.impl_traits.contains(&t.node));
+        class.methods.retain(|m| info.methods.contains(&m.node.name.node));
+    }
 
     // Add to program (preserve template's file_id for DeclKeyMap)
     let spanned_class = Spanned::new(class.clone(), Span::with_file(
@@ -430,6 +493,10 @@ fn substitute_in_stmt(stmt: &mut Stmt, bindings: &HashMap<String, TypeExpr>) {
             }
             substitute_in_expr(&mut value.node, bindings);
         }
+        Stmt::LetStruct { ty, value, .. } => {
+            substitute_in_type_expr(&mut ty.node, bindings);
+            substitute_in_expr(&mut value.node, bindings);
+        }
         Stmt::Return(Some(expr)) => {
             substitute_in_expr(&mut expr.node, bindings);
         }
@@ -448,6 +515,13 @@ fn substitute_in_stmt(stmt: &mut Stmt, bindings: &HashMap<String, TypeExpr>) {
                 substitute_in_block(&mut eb.node, bindings);
             }
         }
+        Stmt::IfLet { value, then_block, else_block, .. } => {
+            substitute_in_expr(&mut value.node, bindings);
+            substitute_in_block(&mut then_block.node, bindings);
+            if let Some(eb) = else_block {
+                substitute_in_block(&mut eb.node, bindings);
+            }
+        }
         Stmt::While { condition, body } => {
             substitute_in_expr(&mut condition.node, bindings);
             substitute_in_block(&mut body.node, bindings);
@@ -470,6 +544,21 @@ fn substitute_in_stmt(stmt: &mut Stmt, bindings: &HashMap<String, TypeExpr>) {
                 }
             }
         }
+        Stmt::IntMatch { expr, arms } => {
+            substitute_in_expr(&mut expr.node, bindings);
+            for arm in arms.iter_mut() {
+                substitute_in_block(&mut arm.body.node, bindings);
+            }
+        }
+        Stmt::NullableMatch { expr, some_arm, none_arm } => {
+            substitute_in_expr(&mut expr.node, bindings);
+            if let Some(arm) = some_arm {
+                substitute_in_block(&mut arm.body.node, bindings);
+            }
+            if let Some(nb) = none_arm {
+                substitute_in_block(&mut nb.node, bindings);
+            }
+        }
         Stmt::Raise { fields, .. } => {
             for (_, expr) in fields.iter_mut() {
                 substitute_in_expr(&mut expr.node, bindings);
@@ -493,6 +582,13 @@ fn substitute_in_stmt(stmt: &mut Stmt, bindings: &HashMap<String, TypeExpr>) {
             }
             substitute_in_block(&mut body.node, bindings);
         }
+        Stmt::Try { body, handler, finally, .. } => {
+            substitute_in_block(&mut body.node, bindings);
+            substitute_in_block(&mut handler.node, bindings);
+            if let Some(finally) = finally {
+                substitute_in_block(&mut finally.node, bindings);
+            }
+        }
         Stmt::Select { arms, default } => {
             for arm in arms {
                 match &mut arm.op {
@@ -527,7 +623,7 @@ fn substitute_in_stmt(stmt: &mut Stmt, bindings: &HashMap<String, TypeExpr>) {
 fn substitute_in_expr(expr: &mut Expr, bindings: &HashMap<String, TypeExpr>) {
     match expr {
         Expr::IntLit(_) | Expr::FloatLit(_) | Expr::BoolLit(_)
-        | Expr::StringLit(_) | Expr::Ident(_) | Expr::NoneLit => {}
+        | Expr::StringLit(_) | Expr::Ident(_) | Expr::NoneLit | Expr::MethodRef { .. } => {}
         Expr::NullPropagate { expr } => {
             substitute_in_expr(&mut expr.node, bindings);
         }
@@ -550,6 +646,9 @@ fn substitute_in_expr(expr: &mut Expr, bindings: &HashMap<String, TypeExpr>) {
             substitute_in_expr(&mut inner.node, bindings);
             substitute_in_type_expr(&mut target_type.node, bindings);
         }
+        Expr::Is { expr: inner, .. } => {
+            substitute_in_expr(&mut inner.node, bindings);
+        }
         Expr::Call { args, type_args, .. } => {
             for arg in args.iter_mut() {
                 substitute_in_expr(&mut arg.node, bindings);
@@ -635,6 +734,10 @@ fn substitute_in_expr(expr: &mut Expr, bindings: &HashMap<String, TypeExpr>) {
         Expr::Propagate { expr } => {
             substitute_in_expr(&mut expr.node, bindings);
         }
+        Expr::PropagateMap { expr, mapper } => {
+            substitute_in_expr(&mut expr.node, bindings);
+            substitute_in_expr(&mut mapper.node, bindings);
+        }
         Expr::Catch { expr, handlers } => {
             substitute_in_expr(&mut expr.node, bindings);
             for handler in handlers {
@@ -777,6 +880,12 @@ impl VisitMut for MonomorphizeRewriter<'_> {
                     type_args.clear();
                 }
             }
+            Expr::MethodCall { method, .. } => {
+                // Generic method calls point at the method's instance
+                if let Some(mangled) = self.rewrites.get(&span_key) {
+                    method.node = mangled.clone();
+                }
+            }
             Expr::StructLit { name, type_args, .. } => {
                 if let Some(mangled) = self.rewrites.get(&span_key) {
                     name.node = mangled.clone();
@@ -900,7 +1009,7 @@ fn resolve_generic_te(te: &mut TypeExpr, env: &mut TypeEnv) -> Result<(), Compil
     match te {
         TypeExpr::Generic { name, type_args } => {
             // Built-in generic types (Map, Set) are kept as-is — no monomorphization needed
-            if name == "Map" || name == "Set" || name == "Task" || name == "Sender" || name == "Receiver" {
+            if name == "Map" || name == "Set" || name == "Task" || name == "Sender" || name == "Receiver" || name == "Mutex" {
                 for arg in type_args.iter_mut() {
                     resolve_generic_te(&mut arg.node, env)?;
                 }
@@ -952,6 +1061,8 @@ fn type_expr_to_pluto_type(te: &TypeExpr, env: &TypeEnv) -> Result<PlutoType, Co
             "void" => Ok(PlutoType::Void),
             "byte" => Ok(PlutoType::Byte),
             "bytes" => Ok(PlutoType::Bytes),
+            "WaitGroup" => Ok(PlutoType::WaitGroup),
+            "Atomic" => Ok(PlutoType::Atomic),
             _ => {
                 if env.classes.contains_key(name) || env.generic_classes.contains_key(name) {
                     Ok(PlutoType::Class(name.clone()))
@@ -996,6 +1107,9 @@ fn type_expr_to_pluto_type(te: &TypeExpr, env: &TypeEnv) -> Result<PlutoType, Co
             if name == "Task" && resolved_args.len() == 1 {
                 return Ok(PlutoType::Task(Box::new(resolved_args[0].clone())));
             }
+            if name == "Mutex" && resolved_args.len() == 1 {
+                return Ok(PlutoType::Mutex(Box::new(resolved_args[0].clone())));
+            }
             let mangled = crate::typeck::env::mangle_name(name, &resolved_args);
             Ok(PlutoType::Class(mangled))
         }
@@ -1191,9 +1305,11 @@ mod tests {
             return_type: None,
             body: spanned(Block { stmts: vec![] }),
             contracts: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             is_override: false,
             is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
         };
 
         reassign_function_uuids(&mut func);
@@ -1234,14 +1350,17 @@ mod tests {
                     return_type: None,
                     body: spanned(Block { stmts: vec![] }),
                     contracts: vec![],
-                    is_pub: false,
+                    visibility: Visibility::Private,
                     is_override: false,
                     is_generator: false,
+                    declared_errors: None,
+                    no_coverage: false,
                 }),
             ],
             impl_traits: vec![],
+            where_bounds: HashMap::new(),
             uses: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             lifecycle: Lifecycle::Singleton,
             invariants: vec![],
         };
@@ -1277,7 +1396,9 @@ mod tests {
                     ],
                 },
             ],
-            is_pub: false,
+            methods: vec![],
+            impl_traits: vec![],
+            visibility: Visibility::Private,
         };
 
         reassign_enum_uuids(&mut edecl);
@@ -1440,9 +1561,11 @@ mod tests {
             return_type: Some(spanned(TypeExpr::Named("T".to_string()))),
             body: spanned(Block { stmts: vec![] }),
             contracts: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             is_override: false,
             is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
         };
 
         let mut bindings = HashMap::new();
@@ -1478,9 +1601,11 @@ mod tests {
             return_type: None,
             body: spanned(Block { stmts: vec![] }),
             contracts: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             is_override: false,
             is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
         };
 
         let mut bindings = HashMap::new();
@@ -1504,9 +1629,11 @@ mod tests {
             return_type: Some(spanned(TypeExpr::Array(Box::new(spanned(TypeExpr::Named("T".to_string())))))),
             body: spanned(Block { stmts: vec![] }),
             contracts: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             is_override: false,
             is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
         };
 
         let mut bindings = HashMap::new();
@@ -1542,8 +1669,9 @@ mod tests {
             ],
             methods: vec![],
             impl_traits: vec![],
+            where_bounds: HashMap::new(),
             uses: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             lifecycle: Lifecycle::Singleton,
             invariants: vec![],
         };
@@ -1581,14 +1709,17 @@ mod tests {
                     return_type: None,
                     body: spanned(Block { stmts: vec![] }),
                     contracts: vec![],
-                    is_pub: false,
+                    visibility: Visibility::Private,
                     is_override: false,
                     is_generator: false,
+                    declared_errors: None,
+                    no_coverage: false,
                 }),
             ],
             impl_traits: vec![],
+            where_bounds: HashMap::new(),
             uses: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             lifecycle: Lifecycle::Singleton,
             invariants: vec![],
         };
@@ -1631,8 +1762,9 @@ mod tests {
             ],
             methods: vec![],
             impl_traits: vec![],
+            where_bounds: HashMap::new(),
             uses: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             lifecycle: Lifecycle::Singleton,
             invariants: vec![],
         };
@@ -1677,7 +1809,9 @@ mod tests {
                     fields: vec![],
                 },
             ],
-            is_pub: false,
+            methods: vec![],
+            impl_traits: vec![],
+            visibility: Visibility::Private,
         };
 
         let mut bindings = HashMap::new();
@@ -1728,7 +1862,9 @@ mod tests {
                     ],
                 },
             ],
-            is_pub: false,
+            methods: vec![],
+            impl_traits: vec![],
+            visibility: Visibility::Private,
         };
 
         let mut bindings = HashMap::new();
@@ -1773,9 +1909,11 @@ mod tests {
             return_type: None,
             body: spanned(Block { stmts: vec![] }),
             contracts: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             is_override: false,
             is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
         };
 
         offset_function_spans(&mut func, 1000);
@@ -1800,9 +1938,11 @@ mod tests {
             }),
             body: spanned(Block { stmts: vec![] }),
             contracts: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             is_override: false,
             is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
         };
 
         offset_function_spans(&mut func, 500);
@@ -1837,7 +1977,9 @@ mod tests {
                     fields: vec![],
                 },
             ],
-            is_pub: false,
+            methods: vec![],
+            impl_traits: vec![],
+            visibility: Visibility::Private,
         };
 
         offset_enum_spans(&mut edecl, 2000);
@@ -2995,8 +3137,9 @@ mod tests {
             ],
             methods: vec![],
             impl_traits: vec![],
+            where_bounds: HashMap::new(),
             uses: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             lifecycle: Lifecycle::Singleton,
             invariants: vec![],
         };
@@ -3034,16 +3177,19 @@ mod tests {
                         return_type: None,
                         body: spanned(Block { stmts: vec![] }),
                         contracts: vec![],
-                        is_pub: false,
+                        visibility: Visibility::Private,
                         is_override: false,
                         is_generator: false,
+                        declared_errors: None,
+                        no_coverage: false,
                     },
                     span: Span { start: 15, end: 30, file_id: 0 },
                 },
             ],
             impl_traits: vec![],
+            where_bounds: HashMap::new(),
             uses: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             lifecycle: Lifecycle::Singleton,
             invariants: vec![],
         };
@@ -3087,9 +3233,11 @@ mod tests {
             })),
             body: spanned(Block { stmts: vec![] }),
             contracts: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             is_override: false,
             is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
         };
 
         let mut env = TypeEnv::new();
@@ -3131,9 +3279,11 @@ mod tests {
                 ],
             }),
             contracts: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             is_override: false,
             is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
         };
 
         let mut env = TypeEnv::new();
@@ -3167,9 +3317,11 @@ mod tests {
                 })],
             }),
             contracts: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             is_override: false,
             is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
         };
 
         let mut bindings = HashMap::new();
@@ -3199,9 +3351,11 @@ mod tests {
                 kind: ContractKind::Requires,
                 expr: spanned(Expr::BoolLit(true)),
             })],
-            is_pub: false,
+            visibility: Visibility::Private,
             is_override: false,
             is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
         };
 
         let mut bindings = HashMap::new();
@@ -3230,8 +3384,9 @@ mod tests {
             }],
             methods: vec![],
             impl_traits: vec![],
+            where_bounds: HashMap::new(),
             uses: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             lifecycle: Lifecycle::Singleton,
             invariants: vec![spanned(ContractClause {
                 kind: ContractKind::Invariant,
@@ -3264,8 +3419,9 @@ mod tests {
             }],
             methods: vec![],
             impl_traits: vec![spanned("Printable".to_string())],
+            where_bounds: HashMap::new(),
             uses: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             lifecycle: Lifecycle::Singleton,
             invariants: vec![],
         };
@@ -3304,7 +3460,9 @@ mod tests {
                     }],
                 },
             ],
-            is_pub: false,
+            methods: vec![],
+            impl_traits: vec![],
+            visibility: Visibility::Private,
         };
 
         let mut bindings = HashMap::new();
@@ -3341,9 +3499,11 @@ mod tests {
                 },
                 span: Span { start: 20, end: 35, file_id: 0 },
             }],
-            is_pub: false,
+            visibility: Visibility::Private,
             is_override: false,
             is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
         };
 
         offset_function_spans(&mut func, 1000);
@@ -3370,8 +3530,9 @@ mod tests {
             fields: vec![],
             methods: vec![],
             impl_traits: vec![],
+            where_bounds: HashMap::new(),
             uses: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             lifecycle: Lifecycle::Singleton,
             invariants: vec![Spanned {
                 node: ContractClause {
@@ -3444,4 +3605,22 @@ mod tests {
             panic!("Expected Array");
         }
     }
+
+    // ── generic methods ─────────────────────────────────────────────────
+
+    #[test]
+    fn test_generic_method_instantiated_per_type_arg() {
+        let source = "class Logger {\n    fn log<T>(self, value: T) {\n    }\n}\n\nfn main() {\n    let l = Logger {}\n    l.log(1)\n    l.log(\"s\")\n    l.log(2)\n}\n";
+        let mut program = crate::parse_source(source).unwrap();
+        let (mut env, _) = crate::typeck::type_check(&program).unwrap();
+        monomorphize(&mut program, &mut env).unwrap();
+
+        let logger = program.classes.iter().find(|c| c.node.name.node == "Logger").unwrap();
+        let mut names: Vec<&str> = logger.node.methods.iter().map(|m| m.node.name.node.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["log$$int", "log$$string"]);
+        assert!(env.functions.contains_key("Logger$log$$int"));
+        assert!(env.functions.contains_key("Logger$log$$string"));
+        assert!(!env.functions.contains_key("Logger$log"));
+    }
 }
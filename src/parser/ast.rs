@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 
-use crate::span::Spanned;
+use crate::span::{Span, Spanned};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Program {
@@ -12,6 +12,10 @@ pub struct Program {
     pub classes: Vec<Spanned<ClassDecl>>,
     pub traits: Vec<Spanned<TraitDecl>>,
     pub enums: Vec<Spanned<EnumDecl>>,
+    #[serde(default)]
+    pub consts: Vec<Spanned<ConstDecl>>,
+    #[serde(default)]
+    pub globals: Vec<Spanned<GlobalDecl>>,
     pub app: Option<Spanned<AppDecl>>,
     pub stages: Vec<Spanned<StageDecl>>,
     pub system: Option<Spanned<SystemDecl>>,
@@ -19,6 +23,33 @@ pub struct Program {
     pub test_info: Vec<TestInfo>,
     pub tests: Option<Spanned<TestsDecl>>,
     pub fallible_extern_fns: Vec<String>,
+    /// Source comments, each attached to the node it sits next to so the
+    /// pretty printer can write it back. Only filled by the editing parsers.
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+}
+
+/// A `//` or `/* */` comment kept for pretty-printing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    /// The comment text, delimiters included.
+    pub text: String,
+    pub span: Span,
+    pub attach: CommentAttach,
+}
+
+/// Where a comment is written back, relative to the span of an AST node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CommentAttach {
+    /// On its own line(s) before the statement, member or declaration.
+    Before(Span),
+    /// After the statement, member or declaration, on its last line.
+    After(Span),
+    /// After the last member of a block (keyed by the block's span) or of a
+    /// class, enum, trait, app or stage (keyed by its name's span).
+    Inside(Span),
+    /// After the last top-level declaration.
+    EndOfFile,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +68,27 @@ pub struct TestInfo {
 pub struct ImportDecl {
     pub path: Vec<Spanned<String>>,
     pub alias: Option<Spanned<String>>,
+    /// `import dir.*`: every source file in the directory becomes a submodule.
+    #[serde(default)]
+    pub glob: bool,
+    /// `import math.{sqrt, pow as power}`: the symbols brought into scope
+    /// unqualified. Empty for a whole-module import.
+    #[serde(default)]
+    pub items: Vec<ImportItem>,
+}
+
+/// One symbol of a selective import, e.g. `pow as power`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportItem {
+    pub name: Spanned<String>,
+    pub alias: Option<Spanned<String>>,
+}
+
+impl ImportItem {
+    /// The name the symbol is used by in the importing module.
+    pub fn local_name(&self) -> &str {
+        self.alias.as_ref().unwrap_or(&self.name).node.as_str()
+    }
 }
 
 impl ImportDecl {
@@ -49,7 +101,8 @@ impl ImportDecl {
     }
 
     pub fn full_path(&self) -> String {
-        self.path.iter().map(|s| s.node.as_str()).collect::<Vec<_>>().join(".")
+        let path = self.path.iter().map(|s| s.node.as_str()).collect::<Vec<_>>().join(".");
+        if self.glob { format!("{path}.*") } else { path }
     }
 }
 
@@ -58,7 +111,39 @@ pub struct ExternFnDecl {
     pub name: Spanned<String>,
     pub params: Vec<Param>,
     pub return_type: Option<Spanned<TypeExpr>>,
-    pub is_pub: bool,
+    pub visibility: Visibility,
+    /// Declared `extern "C"`: a function from a native library rather than the
+    /// Pluto runtime. Limited to scalar types that map directly onto C.
+    #[serde(default)]
+    pub c_abi: bool,
+}
+
+/// Who may refer to a top-level declaration from outside its own module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Visibility {
+    #[default]
+    Private,
+    /// `pub(module)`: visible to other modules of the same package, but not
+    /// to packages that depend on it.
+    Module,
+    /// `pub`: visible everywhere.
+    Public,
+}
+
+impl Visibility {
+    pub fn is_pub(self) -> bool {
+        self == Visibility::Public
+    }
+}
+
+impl std::fmt::Display for Visibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Visibility::Private => Ok(()),
+            Visibility::Module => write!(f, "pub(module)"),
+            Visibility::Public => write!(f, "pub"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -88,8 +173,13 @@ pub struct ClassDecl {
     pub methods: Vec<Spanned<Function>>,
     pub invariants: Vec<Spanned<ContractClause>>,
     pub impl_traits: Vec<Spanned<String>>,
+    /// `where T: Trait` constraints on the `impl` list of a generic class. An
+    /// instantiation only conforms to `impl_traits` (and only gets the methods
+    /// implementing them) when its type arguments satisfy every constraint.
+    #[serde(default)]
+    pub where_bounds: HashMap<String, Vec<Spanned<String>>>,
     pub uses: Vec<Spanned<String>>,
-    pub is_pub: bool,
+    pub visibility: Visibility,
     pub lifecycle: Lifecycle,
 }
 
@@ -108,6 +198,13 @@ pub struct Field {
     pub is_remote: bool,
 }
 
+impl Field {
+    /// From the field's name to the end of its type.
+    pub fn span(&self) -> Span {
+        Span::with_file(self.name.span.start, self.ty.span.end, self.name.span.file_id)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppDecl {
     pub id: Uuid,
@@ -124,7 +221,7 @@ pub struct RequiredMethod {
     pub name: Spanned<String>,
     pub params: Vec<Param>,
     pub return_type: Option<Spanned<TypeExpr>>,
-    pub is_pub: bool,
+    pub visibility: Visibility,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -163,9 +260,17 @@ pub struct Function {
     pub return_type: Option<Spanned<TypeExpr>>,
     pub contracts: Vec<Spanned<ContractClause>>,
     pub body: Spanned<Block>,
-    pub is_pub: bool,
+    pub visibility: Visibility,
     pub is_override: bool,
     pub is_generator: bool,
+    /// Errors listed after the return type (`fn parse(s: string) int | ParseError`).
+    /// `None` leaves the error set inferred; `Some` bounds it, and typeck rejects
+    /// any raise or propagation of an error outside the list.
+    #[serde(default)]
+    pub declared_errors: Option<Vec<Spanned<String>>>,
+    /// Marked `@no_coverage`: coverage instrumentation skips the function entirely.
+    #[serde(default)]
+    pub no_coverage: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -206,6 +311,15 @@ pub enum Stmt {
         value: Spanned<Expr>,
         is_mut: bool,
     },
+    /// `let Point { x, y: py } = p` — binds fields of a class value to new
+    /// locals. Each binding is a field name plus an optional local name;
+    /// fields left out of the pattern are not bound.
+    LetStruct {
+        ty: Spanned<TypeExpr>,
+        fields: Vec<(Spanned<String>, Option<Spanned<String>>)>,
+        value: Spanned<Expr>,
+        is_mut: bool,
+    },
     Return(Option<Spanned<Expr>>),
     Assign {
         target: Spanned<String>,
@@ -221,6 +335,14 @@ pub enum Stmt {
         then_block: Spanned<Block>,
         else_block: Option<Spanned<Block>>,
     },
+    /// `if let x = maybe { ... } else { ... }` — runs `then_block` with `binding`
+    /// bound to the unwrapped value when `value` is not `none`, otherwise `else_block`.
+    IfLet {
+        binding: Spanned<String>,
+        value: Spanned<Expr>,
+        then_block: Spanned<Block>,
+        else_block: Option<Spanned<Block>>,
+    },
     While {
         condition: Spanned<Expr>,
         body: Spanned<Block>,
@@ -239,6 +361,20 @@ pub enum Stmt {
         expr: Spanned<Expr>,
         arms: Vec<MatchArm>,
     },
+    /// `match n { 0 { ... } 1..10 | 20 { ... } _ { ... } }` over an int.
+    /// Arms are tried in order. Without a `_` arm the patterns must cover
+    /// every int. Lowered to an if/else chain after type checking.
+    IntMatch {
+        expr: Spanned<Expr>,
+        arms: Vec<IntMatchArm>,
+    },
+    /// `match maybe { some(x) { ... } none { ... } }` over a nullable value.
+    /// Type checking requires both arms; lowered like `IfLet`.
+    NullableMatch {
+        expr: Spanned<Expr>,
+        some_arm: Option<SomeArm>,
+        none_arm: Option<Spanned<Block>>,
+    },
     Raise {
         error_name: Spanned<String>,
         fields: Vec<(Spanned<String>, Spanned<Expr>)>,
@@ -272,6 +408,17 @@ pub enum Stmt {
         service: Spanned<Expr>,
         port: Spanned<Expr>,
     },
+    /// `try { ... } catch err { ... }` — any error propagated (`!`) or raised
+    /// inside `body` jumps to `handler` with `var` bound to the error, instead
+    /// of leaving the function. Nested tries catch at the innermost handler.
+    /// An optional `finally { ... }` runs on every way out of body and handler:
+    /// falling through, returning, break/continue, or an error leaving the handler.
+    Try {
+        body: Spanned<Block>,
+        var: Spanned<String>,
+        handler: Spanned<Block>,
+        finally: Option<Spanned<Block>>,
+    },
     Break,
     Continue,
     Expr(Spanned<Expr>),
@@ -289,6 +436,53 @@ pub enum SelectOp {
     Send { channel: Spanned<Expr>, value: Spanned<Expr> },
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntMatchArm {
+    /// Alternatives separated by `|`; the arm runs if any of them matches
+    pub patterns: Vec<Spanned<IntPattern>>,
+    pub body: Spanned<Block>,
+}
+
+/// The `some(binding) { ... }` arm of a match on a nullable value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SomeArm {
+    pub binding: Spanned<String>,
+    pub body: Spanned<Block>,
+}
+
+/// A pattern over ints. Bounds are int literals (optionally negated) or
+/// names of int consts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum IntPattern {
+    Value(Box<Spanned<Expr>>),
+    /// `a..b`, `a..=b`, `a..`, `..b` or `..=b`
+    Range {
+        start: Option<Box<Spanned<Expr>>>,
+        end: Option<Box<Spanned<Expr>>>,
+        inclusive: bool,
+    },
+    Wildcard,
+}
+
+impl IntPattern {
+    /// The bound expressions of this pattern
+    pub fn exprs(&self) -> Vec<&Spanned<Expr>> {
+        match self {
+            IntPattern::Value(e) => vec![e],
+            IntPattern::Range { start, end, .. } => start.iter().chain(end.iter()).map(|e| &**e).collect(),
+            IntPattern::Wildcard => Vec::new(),
+        }
+    }
+
+    pub fn exprs_mut(&mut self) -> Vec<&mut Spanned<Expr>> {
+        match self {
+            IntPattern::Value(e) => vec![e],
+            IntPattern::Range { start, end, .. } => start.iter_mut().chain(end.iter_mut()).map(|e| &mut **e).collect(),
+            IntPattern::Wildcard => Vec::new(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SelectArm {
     pub op: SelectOp,
@@ -379,6 +573,13 @@ pub enum Expr {
     Propagate {
         expr: Box<Spanned<Expr>>,
     },
+    /// `call()! map_err(mapper)` — like `Propagate`, but on the error path the
+    /// current error is passed to `mapper` (a `fn(Error) E` closure) and the
+    /// error it returns is raised in place of the original.
+    PropagateMap {
+        expr: Box<Spanned<Expr>>,
+        mapper: Box<Spanned<Expr>>,
+    },
     Catch {
         expr: Box<Spanned<Expr>>,
         /// One or more chained handlers: `expr catch h1 catch h2 ...`. Typed
@@ -390,6 +591,16 @@ pub enum Expr {
         expr: Box<Spanned<Expr>>,
         target_type: Spanned<TypeExpr>,
     },
+    /// `value is Enum.Variant` — true when the enum value holds that variant.
+    /// In `if x is Enum.Variant { ... }` the block may read the variant's
+    /// fields as `x.field`.
+    Is {
+        expr: Box<Spanned<Expr>>,
+        enum_name: Spanned<String>,
+        variant: Spanned<String>,
+        enum_id: Option<Uuid>,
+        variant_id: Option<Uuid>,
+    },
     Range {
         start: Box<Spanned<Expr>>,
         end: Box<Spanned<Expr>>,
@@ -409,6 +620,13 @@ pub enum Expr {
         type_args: Vec<Spanned<TypeExpr>>,
         args: Vec<Spanned<Expr>>,
     },
+    /// Unbound method reference: `Point::distance` as a `fn(Point, ...)` value.
+    /// Bound references (`p.distance`) stay `FieldAccess` until typeck tells
+    /// them apart from fields; both are lifted to closures after typeck.
+    MethodRef {
+        type_name: Spanned<String>,
+        method: Spanned<String>,
+    },
     /// Ambiguous qualified access pattern (a.b.c)
     /// Resolved during module flattening to either FieldAccess chain or kept for type checker (enums)
     QualifiedAccess {
@@ -479,7 +697,7 @@ pub struct TraitDecl {
     pub id: Uuid,
     pub name: Spanned<String>,
     pub methods: Vec<TraitMethod>,
-    pub is_pub: bool,
+    pub visibility: Visibility,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -490,6 +708,9 @@ pub struct TraitMethod {
     pub return_type: Option<Spanned<TypeExpr>>,
     pub contracts: Vec<Spanned<ContractClause>>,
     pub body: Option<Spanned<Block>>,
+    /// Declared error set; implementations may only raise errors listed here.
+    #[serde(default)]
+    pub declared_errors: Option<Vec<Spanned<String>>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -499,7 +720,38 @@ pub struct EnumDecl {
     pub type_params: Vec<Spanned<String>>,
     pub type_param_bounds: HashMap<String, Vec<Spanned<String>>>,
     pub variants: Vec<EnumVariant>,
-    pub is_pub: bool,
+    /// Methods (`fn f(self)`) and associated functions (`fn f()`) declared in
+    /// the enum body. Lowered to top-level `Enum$name` functions before typeck.
+    #[serde(default)]
+    pub methods: Vec<Spanned<Function>>,
+    /// Traits named in `enum E impl Trait`. Enums are never trait objects, so
+    /// this is checked for conformance and drives operator overloading only.
+    #[serde(default)]
+    pub impl_traits: Vec<Spanned<String>>,
+    pub visibility: Visibility,
+}
+
+/// A top-level `const NAME: type = value`. The initializer must be a
+/// compile-time constant; uses are replaced by its value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstDecl {
+    pub id: Uuid,
+    pub name: Spanned<String>,
+    pub ty: Option<Spanned<TypeExpr>>,
+    pub value: Spanned<Expr>,
+    pub visibility: Visibility,
+}
+
+/// A top-level `global mut NAME: type = value`: a single process-wide
+/// variable. The initializer must be a compile-time constant. Accesses are
+/// not synchronized, so tasks that share a global race on it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalDecl {
+    pub id: Uuid,
+    pub name: Spanned<String>,
+    pub ty: Option<Spanned<TypeExpr>>,
+    pub value: Spanned<Expr>,
+    pub visibility: Visibility,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -507,7 +759,7 @@ pub struct ErrorDecl {
     pub id: Uuid,
     pub name: Spanned<String>,
     pub fields: Vec<Field>,
-    pub is_pub: bool,
+    pub visibility: Visibility,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -534,6 +786,25 @@ pub struct EnumVariant {
     pub fields: Vec<Field>,
 }
 
+/// Name given to the `index`-th payload of a tuple-style variant such as
+/// `Circle(float)`. Positional payloads are stored as ordinary named fields.
+pub fn positional_field_name(index: usize) -> String {
+    format!("_{index}")
+}
+
+/// True when `names` are exactly `_0, _1, ...`, i.e. the fields of a
+/// tuple-style variant, so they can be written back positionally.
+pub fn is_positional_fields<'a>(names: impl IntoIterator<Item = &'a str>) -> bool {
+    let mut count = 0;
+    for (i, name) in names.into_iter().enumerate() {
+        if name != positional_field_name(i) {
+            return false;
+        }
+        count += 1;
+    }
+    count > 0
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MatchArm {
     pub enum_name: Spanned<String>,
@@ -571,6 +842,8 @@ mod tests {
                 Spanned::new("collections".to_string(), Span::dummy()),
             ],
             alias: None,
+            glob: false,
+            items: Vec::new(),
         };
         assert_eq!(import.binding_name(), "collections");
     }
@@ -583,6 +856,8 @@ mod tests {
                 Spanned::new("collections".to_string(), Span::dummy()),
             ],
             alias: Some(Spanned::new("col".to_string(), Span::dummy())),
+            glob: false,
+            items: Vec::new(),
         };
         assert_eq!(import.binding_name(), "col");
     }
@@ -592,6 +867,8 @@ mod tests {
         let import = ImportDecl {
             path: vec![Spanned::new("math".to_string(), Span::dummy())],
             alias: None,
+            glob: false,
+            items: Vec::new(),
         };
         assert_eq!(import.binding_name(), "math");
     }
@@ -601,6 +878,8 @@ mod tests {
         let import = ImportDecl {
             path: vec![Spanned::new("math".to_string(), Span::dummy())],
             alias: None,
+            glob: false,
+            items: Vec::new(),
         };
         assert_eq!(import.full_path(), "math");
     }
@@ -613,6 +892,8 @@ mod tests {
                 Spanned::new("math".to_string(), Span::dummy()),
             ],
             alias: None,
+            glob: false,
+            items: Vec::new(),
         };
         assert_eq!(import.full_path(), "std.math");
     }
@@ -626,6 +907,8 @@ mod tests {
                 Spanned::new("map".to_string(), Span::dummy()),
             ],
             alias: None,
+            glob: false,
+            items: Vec::new(),
         };
         assert_eq!(import.full_path(), "std.collections.map");
     }
@@ -638,11 +921,25 @@ mod tests {
                 Spanned::new("math".to_string(), Span::dummy()),
             ],
             alias: Some(Spanned::new("m".to_string(), Span::dummy())),
+            glob: false,
+            items: Vec::new(),
         };
         // full_path should return the actual path, not the alias
         assert_eq!(import.full_path(), "std.math");
     }
 
+    #[test]
+    fn test_full_path_glob() {
+        let import = ImportDecl {
+            path: vec![Spanned::new("handlers".to_string(), Span::dummy())],
+            alias: None,
+            glob: true,
+            items: Vec::new(),
+        };
+        assert_eq!(import.full_path(), "handlers.*");
+        assert_eq!(import.binding_name(), "handlers");
+    }
+
     // ===== Lifecycle Display tests =====
 
     #[test]
@@ -0,0 +1,180 @@
+//! Attach source comments to the AST nodes they sit next to, so the pretty
+//! printer can write them back.
+//!
+//! A comment belongs to the innermost block or declaration body containing
+//! it. Within that container it trails the member that ends earlier on the
+//! same line; otherwise it leads the member that contains or follows it;
+//! otherwise it goes after the container's last member.
+
+use crate::lexer;
+use crate::parser::ast::*;
+use crate::span::{Span, Spanned};
+use crate::visit::{walk_app, walk_block, walk_class, walk_enum, walk_stage, walk_trait, Visitor};
+
+/// A node whose members comments can sit between.
+struct Container {
+    span: Span,
+    /// Key for comments after the last member; `None` for the program.
+    key: Option<Span>,
+    members: Vec<Span>,
+}
+
+struct ContainerCollector {
+    file_id: u32,
+    containers: Vec<Container>,
+}
+
+impl ContainerCollector {
+    fn push(&mut self, span: Span, key: Option<Span>, members: impl IntoIterator<Item = Span>) {
+        if span.file_id == self.file_id {
+            let members = members.into_iter().filter(|m| m.file_id == self.file_id).collect();
+            self.containers.push(Container { span, key, members });
+        }
+    }
+}
+
+impl Visitor for ContainerCollector {
+    fn visit_block(&mut self, block: &Spanned<Block>) {
+        self.push(block.span, Some(block.span), block.node.stmts.iter().map(|s| s.span));
+        walk_block(self, block);
+    }
+
+    fn visit_class(&mut self, class: &Spanned<ClassDecl>) {
+        let c = &class.node;
+        let members = c.fields.iter().filter(|f| !f.is_injected).map(Field::span)
+            .chain(c.invariants.iter().map(|i| i.span))
+            .chain(c.methods.iter().map(|m| m.span));
+        self.push(class.span, Some(c.name.span), members);
+        walk_class(self, class);
+    }
+
+    fn visit_enum(&mut self, enum_decl: &Spanned<EnumDecl>) {
+        let e = &enum_decl.node;
+        let members = e.variants.iter().map(|v| v.name.span).chain(e.methods.iter().map(|m| m.span));
+        self.push(enum_decl.span, Some(e.name.span), members);
+        walk_enum(self, enum_decl);
+    }
+
+    fn visit_trait(&mut self, trait_decl: &Spanned<TraitDecl>) {
+        let t = &trait_decl.node;
+        self.push(trait_decl.span, Some(t.name.span), t.methods.iter().map(|m| m.name.span));
+        walk_trait(self, trait_decl);
+    }
+
+    fn visit_app(&mut self, app: &Spanned<AppDecl>) {
+        self.push(app.span, Some(app.node.name.span), app.node.methods.iter().map(|m| m.span));
+        walk_app(self, app);
+    }
+
+    fn visit_stage(&mut self, stage: &Spanned<StageDecl>) {
+        let s = &stage.node;
+        let members = s.required_methods.iter().map(|r| r.span).chain(s.methods.iter().map(|m| m.span));
+        self.push(stage.span, Some(s.name.span), members);
+        walk_stage(self, stage);
+    }
+}
+
+/// Spans of the top-level declarations the pretty printer writes.
+fn top_level_spans(program: &Program) -> Vec<Span> {
+    let mut spans: Vec<Span> = Vec::new();
+    spans.extend(program.imports.iter().map(|i| i.span));
+    spans.extend(program.extern_fns.iter().map(|e| e.span));
+    spans.extend(program.consts.iter().map(|c| c.span));
+    spans.extend(program.globals.iter().map(|g| g.span));
+    spans.extend(program.errors.iter().map(|e| e.span));
+    spans.extend(program.traits.iter().map(|t| t.span));
+    spans.extend(program.enums.iter().map(|e| e.span));
+    spans.extend(program.classes.iter().map(|c| c.span));
+    spans.extend(program.functions.iter().map(|f| f.span));
+    spans.extend(program.app.iter().map(|a| a.span));
+    spans.extend(program.stages.iter().map(|s| s.span));
+    spans.extend(program.tests.iter().map(|t| t.span));
+    spans
+}
+
+/// Fill `program.comments` from the comments in `source`, the text of the
+/// file whose spans carry `file_id`.
+pub fn attach_comments(program: &mut Program, source: &str, file_id: u32) {
+    let mut collector = ContainerCollector { file_id, containers: Vec::new() };
+    collector.push(Span::with_file(0, source.len(), file_id), None, top_level_spans(program));
+    collector.visit_program(program);
+
+    program.comments = lexer::lex_comments(source)
+        .into_iter()
+        .map(|c| {
+            let span = Span::with_file(c.span.start, c.span.end, file_id);
+            let attach = attach_point(&collector.containers, source, span);
+            Comment { text: c.node, span, attach }
+        })
+        .collect();
+}
+
+fn attach_point(containers: &[Container], source: &str, comment: Span) -> CommentAttach {
+    let Some(container) = containers
+        .iter()
+        .filter(|c| c.span.start <= comment.start && comment.end <= c.span.end)
+        .min_by_key(|c| c.span.end - c.span.start)
+    else {
+        return CommentAttach::EndOfFile;
+    };
+
+    let line_start = source[..comment.start].rfind('\n').map_or(0, |i| i + 1);
+    if !source[line_start..comment.start].trim().is_empty()
+        && let Some(prev) = container.members.iter().filter(|m| m.end <= comment.start).max_by_key(|m| m.end)
+        && prev.end >= line_start
+    {
+        return CommentAttach::After(*prev);
+    }
+    if let Some(member) = container.members.iter().find(|m| m.start <= comment.start && comment.end <= m.end) {
+        return CommentAttach::Before(*member);
+    }
+    if let Some(next) = container.members.iter().filter(|m| m.start >= comment.end).min_by_key(|m| m.start) {
+        return CommentAttach::Before(*next);
+    }
+    container.key.map_or(CommentAttach::EndOfFile, CommentAttach::Inside)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn attach(source: &str) -> Program {
+        let tokens = lexer::lex(source).unwrap();
+        let mut program = Parser::new(&tokens, source).parse_program().unwrap();
+        attach_comments(&mut program, source, 0);
+        program
+    }
+
+    #[test]
+    fn trailing_comment_attaches_after_statement() {
+        let program = attach("fn main() {\n    let x = 1 // one\n    print(x)\n}\n");
+        let stmt = program.functions[0].node.body.node.stmts[0].span;
+        assert_eq!(program.comments.len(), 1);
+        assert_eq!(program.comments[0].text, "// one");
+        assert_eq!(program.comments[0].attach, CommentAttach::After(stmt));
+    }
+
+    #[test]
+    fn standalone_comment_leads_next_member() {
+        let program = attach("// header\nfn main() {\n    /* a\n       b */\n    print(1)\n}\n");
+        let func = program.functions[0].span;
+        let stmt = program.functions[0].node.body.node.stmts[0].span;
+        assert_eq!(program.comments[0].attach, CommentAttach::Before(func));
+        assert_eq!(program.comments[1].attach, CommentAttach::Before(stmt));
+    }
+
+    #[test]
+    fn comment_after_last_member_stays_inside() {
+        let program = attach("class Point {\n    x: int\n    // more later\n}\n\n// the end\n");
+        let name = program.classes[0].node.name.span;
+        assert_eq!(program.comments[0].attach, CommentAttach::Inside(name));
+        assert_eq!(program.comments[1].attach, CommentAttach::EndOfFile);
+    }
+
+    #[test]
+    fn uuid_hints_are_not_comments() {
+        let program = attach("// @uuid: 00000000-0000-0000-0000-000000000000\nfn main() {\n}\n");
+        assert!(program.comments.is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod ast;
+pub mod comments;
 
 use std::collections::{HashMap, HashSet};
 use std::collections::hash_map::DefaultHasher;
@@ -11,6 +12,11 @@ use crate::lexer::token::Token;
 use crate::span::{Span, Spanned};
 use ast::*;
 
+/// `name: value` initializers of a struct literal or data variant.
+type FieldInits = Vec<(Spanned<String>, Spanned<Expr>)>;
+/// `field` / `field: rename` bindings of a match arm.
+type MatchBindings = Vec<(Spanned<String>, Option<Spanned<String>>)>;
+
 pub struct Parser<'a> {
     tokens: &'a [Spanned<Token>],
     source: &'a str,
@@ -23,6 +29,28 @@ pub struct Parser<'a> {
     /// Consumed before reading from `tokens`.
     split_tokens: Vec<Spanned<Token>>,
     split_pos: usize,
+    /// Current expression/block/type nesting depth, bounded by `max_depth` so
+    /// adversarial input gets a syntax error instead of overflowing the stack.
+    depth: usize,
+    max_depth: usize,
+}
+
+/// Default bound on expression, block and type nesting. Each level costs
+/// several parser frames (and more in later passes), so this stays well inside
+/// the main thread's stack even in debug builds.
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 128;
+
+/// Nesting limit for new parsers: `PLUTO_MAX_NESTING_DEPTH` if set to a
+/// positive integer, else `DEFAULT_MAX_NESTING_DEPTH`.
+fn max_nesting_depth() -> usize {
+    static DEPTH: std::sync::OnceLock<usize> = std::sync::OnceLock::new();
+    *DEPTH.get_or_init(|| {
+        std::env::var("PLUTO_MAX_NESTING_DEPTH")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|&d| d > 0)
+            .unwrap_or(DEFAULT_MAX_NESTING_DEPTH)
+    })
 }
 
 impl<'a> Parser<'a> {
@@ -30,12 +58,12 @@ impl<'a> Parser<'a> {
         // Seed with prelude enum names so all parse paths (including interpolation
         // sub-parsers) know about Option, Result, etc.
         let enum_names = crate::prelude::prelude_enum_names().clone();
-        Self { tokens, source, pos: 0, restrict_struct_lit: false, enum_names, file_path: None, split_tokens: Vec::new(), split_pos: 0 }
+        Self { tokens, source, pos: 0, restrict_struct_lit: false, enum_names, file_path: None, split_tokens: Vec::new(), split_pos: 0, depth: 0, max_depth: max_nesting_depth() }
     }
 
     /// Constructor without prelude seeding — used only to parse the prelude source itself.
     pub fn new_without_prelude(tokens: &'a [Spanned<Token>], source: &'a str) -> Self {
-        Self { tokens, source, pos: 0, restrict_struct_lit: false, enum_names: HashSet::new(), file_path: None, split_tokens: Vec::new(), split_pos: 0 }
+        Self { tokens, source, pos: 0, restrict_struct_lit: false, enum_names: HashSet::new(), file_path: None, split_tokens: Vec::new(), split_pos: 0, depth: 0, max_depth: max_nesting_depth() }
     }
 
     /// Constructor with extra enum names added to the prelude set.
@@ -47,13 +75,36 @@ impl<'a> Parser<'a> {
     ) -> Self {
         let mut enum_names = crate::prelude::prelude_enum_names().clone();
         enum_names.extend(extra_enum_names);
-        Self { tokens, source, pos: 0, restrict_struct_lit: false, enum_names, file_path: None, split_tokens: Vec::new(), split_pos: 0 }
+        Self { tokens, source, pos: 0, restrict_struct_lit: false, enum_names, file_path: None, split_tokens: Vec::new(), split_pos: 0, depth: 0, max_depth: max_nesting_depth() }
     }
 
     /// Constructor with file path for generating unique test IDs
     pub fn new_with_path(tokens: &'a [Spanned<Token>], source: &'a str, file_path: String) -> Self {
         let enum_names = crate::prelude::prelude_enum_names().clone();
-        Self { tokens, source, pos: 0, restrict_struct_lit: false, enum_names, file_path: Some(file_path), split_tokens: Vec::new(), split_pos: 0 }
+        Self { tokens, source, pos: 0, restrict_struct_lit: false, enum_names, file_path: Some(file_path), split_tokens: Vec::new(), split_pos: 0, depth: 0, max_depth: max_nesting_depth() }
+    }
+
+    /// Override the nesting limit (e.g. for servers parsing untrusted input).
+    pub fn with_max_nesting_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Enter one level of nesting, failing once the limit is exceeded. Callers
+    /// restore the depth they started at once the nested construct is parsed.
+    fn enter_nesting(&mut self) -> Result<(), CompileError> {
+        if self.depth >= self.max_depth {
+            let span = self.peek().map(|t| t.span).unwrap_or_else(|| self.eof_span());
+            return Err(CompileError::syntax(
+                format!(
+                    "expression too deeply nested (limit is {} levels); split it into smaller pieces or raise PLUTO_MAX_NESTING_DEPTH",
+                    self.max_depth
+                ),
+                span,
+            ));
+        }
+        self.depth += 1;
+        Ok(())
     }
 
     /// Generate a unique test ID prefix from file path to avoid collisions when multiple files are compiled together
@@ -221,6 +272,22 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Like `expect_ident`, but also accepts the `error` keyword. Function names
+    /// and the name after a `.` can't start an error declaration or type, so a
+    /// module can export a function called `error` (e.g. `log.error`).
+    fn expect_member_name(&mut self) -> Result<Spanned<String>, CompileError> {
+        self.skip_newlines();
+        if self.split_pos >= self.split_tokens.len()
+            && let Some(tok) = self.tokens.get(self.pos)
+            && matches!(tok.node, Token::Error)
+        {
+            let span = tok.span;
+            self.pos += 1;
+            return Ok(Spanned::new("error".to_string(), span));
+        }
+        self.expect_ident()
+    }
+
     fn eof_span(&self) -> Span {
         if let Some(last) = self.tokens.last() {
             Span::new(last.span.end, last.span.end)
@@ -336,7 +403,7 @@ impl<'a> Parser<'a> {
     /// Parse `name: expr, ...` field list inside `{ }`. Assumes `{` already consumed.
     /// Returns fields and the closing `}` span end.
     #[allow(clippy::type_complexity)]
-    fn parse_field_list(&mut self) -> Result<(Vec<(Spanned<String>, Spanned<Expr>)>, usize), CompileError> {
+    fn parse_field_list(&mut self) -> Result<(FieldInits, usize), CompileError> {
         self.skip_newlines();
         let fields = self.parse_comma_list(&Token::RBrace, false, |p| {
             let fname = p.expect_ident()?;
@@ -348,6 +415,35 @@ impl<'a> Parser<'a> {
         Ok((fields, close.span.end))
     }
 
+    /// Parse `(value, ...)` after a tuple-style variant, naming each value
+    /// after its position. Returns the fields and the end of the `)`.
+    fn parse_positional_args(&mut self) -> Result<(FieldInits, usize), CompileError> {
+        self.expect(&Token::LParen)?;
+        self.skip_newlines();
+        let args = self.parse_comma_list(&Token::RParen, true, |p| p.parse_expr(0))?;
+        let close = self.expect(&Token::RParen)?;
+        let fields = args
+            .into_iter()
+            .enumerate()
+            .map(|(i, arg)| (Spanned::new(positional_field_name(i), arg.span), arg))
+            .collect();
+        Ok((fields, close.span.end))
+    }
+
+    /// Parse `(name, ...)` bindings of a tuple-style variant pattern. Each
+    /// name binds the payload at its position.
+    fn parse_positional_bindings(&mut self) -> Result<MatchBindings, CompileError> {
+        self.expect(&Token::LParen)?;
+        self.skip_newlines();
+        let names = self.parse_comma_list(&Token::RParen, true, |p| p.expect_ident())?;
+        self.expect(&Token::RParen)?;
+        Ok(names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| (Spanned::new(positional_field_name(i), name.span), Some(name)))
+            .collect())
+    }
+
     fn pre_scan_enum_names(&mut self) {
         let saved = self.pos;
         let mut i = 0;
@@ -376,6 +472,8 @@ impl<'a> Parser<'a> {
         let mut classes = Vec::new();
         let mut traits = Vec::new();
         let mut enums = Vec::new();
+        let mut consts = Vec::new();
+        let mut globals = Vec::new();
         let mut app = None;
         let mut stages = Vec::new();
         let mut system = None;
@@ -390,14 +488,16 @@ impl<'a> Parser<'a> {
             self.skip_newlines();
         }
 
-        while let Some(tok) = self.peek() {
-            // Handle `pub` modifier
-            let is_pub = if matches!(tok.node, Token::Pub) {
-                self.advance(); // consume 'pub'
+        while self.peek().is_some() {
+            let no_coverage = self.parse_no_coverage()?;
+
+            // Handle `pub` / `pub(module)` modifier
+            let visibility = if matches!(self.peek().map(|t| &t.node), Some(Token::Pub)) {
+                let visibility = self.parse_visibility()?;
                 self.skip_newlines();
-                true
+                visibility
             } else {
-                false
+                Visibility::Private
             };
 
             // Parse optional lifecycle modifier: scoped | transient
@@ -431,6 +531,13 @@ impl<'a> Parser<'a> {
                 )
             })?;
 
+            if no_coverage && !matches!(tok.node, Token::Fn) {
+                return Err(CompileError::syntax(
+                    "'@no_coverage' can only be used on functions",
+                    tok.span,
+                ));
+            }
+
             match &tok.node {
                 Token::App => {
                     if lifecycle != Lifecycle::Singleton {
@@ -439,7 +546,7 @@ impl<'a> Parser<'a> {
                             tok.span,
                         ));
                     }
-                    if is_pub {
+                    if visibility != Visibility::Private {
                         return Err(CompileError::syntax(
                             "app declarations cannot be pub",
                             tok.span,
@@ -456,7 +563,7 @@ impl<'a> Parser<'a> {
                 }
                 Token::Class => {
                     let mut class = self.parse_class()?;
-                    class.node.is_pub = is_pub;
+                    class.node.visibility = visibility;
                     class.node.lifecycle = lifecycle;
                     classes.push(class);
                 }
@@ -468,7 +575,8 @@ impl<'a> Parser<'a> {
                         ));
                     }
                     let mut func = self.parse_function()?;
-                    func.node.is_pub = is_pub;
+                    func.node.visibility = visibility;
+                    func.node.no_coverage = no_coverage;
                     functions.push(func);
                 }
                 Token::Trait => {
@@ -479,7 +587,7 @@ impl<'a> Parser<'a> {
                         ));
                     }
                     let mut tr = self.parse_trait()?;
-                    tr.node.is_pub = is_pub;
+                    tr.node.visibility = visibility;
                     traits.push(tr);
                 }
                 Token::Enum => {
@@ -490,9 +598,31 @@ impl<'a> Parser<'a> {
                         ));
                     }
                     let mut e = self.parse_enum_decl()?;
-                    e.node.is_pub = is_pub;
+                    e.node.visibility = visibility;
                     enums.push(e);
                 }
+                Token::Const => {
+                    if lifecycle != Lifecycle::Singleton {
+                        return Err(CompileError::syntax(
+                            "lifecycle modifiers (scoped, transient) can only be used on classes",
+                            tok.span,
+                        ));
+                    }
+                    let mut c = self.parse_const_decl()?;
+                    c.node.visibility = visibility;
+                    consts.push(c);
+                }
+                Token::Global => {
+                    if lifecycle != Lifecycle::Singleton {
+                        return Err(CompileError::syntax(
+                            "lifecycle modifiers (scoped, transient) can only be used on classes",
+                            tok.span,
+                        ));
+                    }
+                    let mut g = self.parse_global_decl()?;
+                    g.node.visibility = visibility;
+                    globals.push(g);
+                }
                 Token::Error => {
                     if lifecycle != Lifecycle::Singleton {
                         return Err(CompileError::syntax(
@@ -501,7 +631,7 @@ impl<'a> Parser<'a> {
                         ));
                     }
                     let mut err_decl = self.parse_error_decl()?;
-                    err_decl.node.is_pub = is_pub;
+                    err_decl.node.visibility = visibility;
                     errors.push(err_decl);
                 }
                 Token::Extern => {
@@ -511,10 +641,10 @@ impl<'a> Parser<'a> {
                             tok.span,
                         ));
                     }
-                    // Only extern fn is supported
+                    // Only extern fn and extern "C" fn are supported
                     let next = self.peek_nth(1);
-                    if matches!(next, Some(t) if matches!(t.node, Token::Fn)) {
-                        extern_fns.push(self.parse_extern_fn(is_pub)?);
+                    if matches!(next, Some(t) if matches!(t.node, Token::Fn | Token::StringLit(_))) {
+                        extern_fns.push(self.parse_extern_fn(visibility)?);
                     } else {
                         return Err(CompileError::syntax(
                             "expected 'fn' after 'extern'",
@@ -529,7 +659,7 @@ impl<'a> Parser<'a> {
                             tok.span,
                         ));
                     }
-                    if is_pub {
+                    if visibility != Visibility::Private {
                         return Err(CompileError::syntax(
                             "tests declarations cannot be pub",
                             tok.span,
@@ -553,7 +683,7 @@ impl<'a> Parser<'a> {
                             tok.span,
                         ));
                     }
-                    if is_pub {
+                    if visibility != Visibility::Private {
                         return Err(CompileError::syntax(
                             "tests cannot be pub",
                             tok.span,
@@ -576,7 +706,7 @@ impl<'a> Parser<'a> {
                             tok.span,
                         ));
                     }
-                    if is_pub {
+                    if visibility != Visibility::Private {
                         return Err(CompileError::syntax(
                             "system declarations cannot be pub",
                             tok.span,
@@ -598,7 +728,7 @@ impl<'a> Parser<'a> {
                             tok.span,
                         ));
                     }
-                    if is_pub {
+                    if visibility != Visibility::Private {
                         return Err(CompileError::syntax(
                             "stage declarations cannot be pub",
                             tok.span,
@@ -609,7 +739,7 @@ impl<'a> Parser<'a> {
                 }
                 _ => {
                     return Err(CompileError::syntax(
-                        format!("expected 'fn', 'class', 'trait', 'enum', 'error', 'app', 'stage', 'system', 'test', 'tests', 'extern fn', or 'extern rust', found {}", tok.node),
+                        format!("expected 'fn', 'class', 'trait', 'enum', 'error', 'const', 'global', 'app', 'stage', 'system', 'test', 'tests', 'extern fn', or 'extern \"C\" fn', found {}", tok.node),
                         tok.span,
                     ));
                 }
@@ -662,7 +792,7 @@ impl<'a> Parser<'a> {
             ));
         }
 
-        Ok(Program { imports, functions, extern_fns,  classes, traits, enums, app, stages, system, errors, test_info, tests, fallible_extern_fns: Vec::new() })
+        Ok(Program { imports, functions, extern_fns,  classes, traits, enums, consts, globals, app, stages, system, errors, test_info, tests, fallible_extern_fns: Vec::new(), comments: Vec::new() })
     }
 
     /// Parse a bare `test "name" { body }` block into a TestInfo + synthetic Function.
@@ -713,9 +843,11 @@ impl<'a> Parser<'a> {
             return_type: None,
             contracts: Vec::new(),
             body,
-            is_pub: false,
+            visibility: Visibility::Private,
             is_override: false,
             is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
         }, Span::new(start, end));
 
         Ok((info, func))
@@ -797,17 +929,55 @@ impl<'a> Parser<'a> {
 
         // Parse dotted path segments: import std.io.fs
         // Use peek_raw() so a newline stops the path (prevents `import a\n.b` from parsing as `import a.b`)
+        let mut glob = false;
+        let mut items = Vec::new();
+        let mut end = path[0].span.end;
         while self.peek_raw().is_some() && matches!(self.peek_raw().unwrap().node, Token::Dot) {
             self.advance(); // consume '.'
+            // Glob import: `import handlers.*` ends the path
+            if matches!(self.peek_raw().map(|t| &t.node), Some(Token::Star)) {
+                end = self.advance().unwrap().span.end;
+                glob = true;
+                break;
+            }
+            // Selective import: `import math.{sqrt, pow as power}` ends the path
+            if matches!(self.peek_raw().map(|t| &t.node), Some(Token::LBrace)) {
+                self.advance(); // consume '{'
+                while !matches!(self.peek().map(|t| &t.node), Some(Token::RBrace)) {
+                    let name = self.expect_ident()?;
+                    let alias = if matches!(self.peek().map(|t| &t.node), Some(Token::As)) {
+                        self.advance(); // consume 'as'
+                        Some(self.expect_ident()?)
+                    } else {
+                        None
+                    };
+                    items.push(ImportItem { name, alias });
+                    if !matches!(self.peek().map(|t| &t.node), Some(Token::Comma)) {
+                        break;
+                    }
+                    self.advance(); // consume ','
+                }
+                let close = self.expect(&Token::RBrace)?;
+                end = close.span.end;
+                if items.is_empty() {
+                    return Err(CompileError::syntax("selective import must name at least one item", Span::new(start, end)));
+                }
+                break;
+            }
             let segment = self.expect_ident()?;
+            end = segment.span.end;
             path.push(segment);
         }
 
-        let mut end = path.last().unwrap().span.end;
-
         // Parse optional alias: `as name`
         let alias = if self.peek_raw().is_some() && matches!(self.peek_raw().unwrap().node, Token::As) {
-            self.advance(); // consume 'as'
+            let as_tok = self.advance().unwrap().span; // consume 'as'
+            if !items.is_empty() {
+                return Err(CompileError::syntax(
+                    "a selective import cannot be aliased; alias its items instead (`import m.{item as name}`)",
+                    as_tok,
+                ));
+            }
             let alias_name = self.expect_ident()?;
             end = alias_name.span.end;
             Some(alias_name)
@@ -816,12 +986,63 @@ impl<'a> Parser<'a> {
         };
 
         self.consume_statement_end()?;
-        Ok(Spanned::new(ImportDecl { path, alias }, Span::new(start, end)))
+        Ok(Spanned::new(ImportDecl { path, alias, glob, items }, Span::new(start, end)))
     }
 
-    fn parse_extern_fn(&mut self, is_pub: bool) -> Result<Spanned<ExternFnDecl>, CompileError> {
+    /// Parse an optional `pub` or `pub(module)` modifier.
+    fn parse_visibility(&mut self) -> Result<Visibility, CompileError> {
+        if !matches!(self.peek().map(|t| &t.node), Some(Token::Pub)) {
+            return Ok(Visibility::Private);
+        }
+        self.advance(); // consume 'pub'
+        if !matches!(self.peek_raw().map(|t| &t.node), Some(Token::LParen)) {
+            return Ok(Visibility::Public);
+        }
+        self.advance(); // consume '('
+        let scope = self.expect_ident()?;
+        if scope.node != "module" {
+            return Err(CompileError::syntax(
+                format!("expected 'module' in visibility modifier, found '{}'", scope.node),
+                scope.span,
+            ));
+        }
+        self.expect(&Token::RParen)?;
+        Ok(Visibility::Module)
+    }
+
+    /// Parse an optional `@no_coverage` attribute, returning whether it was present.
+    fn parse_no_coverage(&mut self) -> Result<bool, CompileError> {
+        if !matches!(self.peek().map(|t| &t.node), Some(Token::At)) {
+            return Ok(false);
+        }
+        self.advance(); // consume '@'
+        let name = self.expect_ident()?;
+        if name.node != "no_coverage" {
+            return Err(CompileError::syntax(
+                format!("unknown attribute '@{}'", name.node),
+                name.span,
+            ));
+        }
+        self.skip_newlines();
+        Ok(true)
+    }
+
+    fn parse_extern_fn(&mut self, visibility: Visibility) -> Result<Spanned<ExternFnDecl>, CompileError> {
         let extern_tok = self.expect(&Token::Extern)?;
         let start = extern_tok.span.start;
+        let c_abi = match self.peek() {
+            Some(Spanned { node: Token::StringLit(abi), span }) => {
+                if abi != "C" {
+                    return Err(CompileError::syntax(
+                        format!("unsupported extern ABI \"{abi}\"; only \"C\" is supported"),
+                        *span,
+                    ));
+                }
+                self.advance();
+                true
+            }
+            _ => false,
+        };
         self.expect(&Token::Fn)?;
         let name = self.expect_ident()?;
         self.expect(&Token::LParen)?;
@@ -847,7 +1068,7 @@ impl<'a> Parser<'a> {
         };
 
         self.consume_statement_end()?;
-        Ok(Spanned::new(ExternFnDecl { name, params, return_type, is_pub }, Span::new(start, end)))
+        Ok(Spanned::new(ExternFnDecl { name, params, return_type, visibility, c_abi }, Span::new(start, end)))
     }
 
     fn parse_bracket_deps(&mut self) -> Result<Vec<Field>, CompileError> {
@@ -949,29 +1170,27 @@ impl<'a> Parser<'a> {
                 lifecycle_overrides.push((class_name, Lifecycle::Transient));
                 self.consume_statement_end()?;
             } else {
-                // Parse optional 'pub' before methods/requires
-                let is_pub = if matches!(self.peek().expect("token should exist after is_some check").node, Token::Pub) {
-                    self.advance();
-                    true
-                } else {
-                    false
-                };
+                // Parse optional '@no_coverage' and 'pub' before methods/requires
+                let no_coverage = self.parse_no_coverage()?;
+                let visibility = self.parse_visibility()?;
 
                 if matches!(self.peek().expect("token should exist after is_some check").node, Token::Requires) {
                     // `requires fn name(self, ...) ReturnType`
                     let mut req = self.parse_required_method()?;
-                    req.node.is_pub = is_pub;
+                    req.node.visibility = visibility;
                     required_methods.push(req);
                 } else if matches!(self.peek().expect("token should exist after is_some check").node, Token::Override) {
                     // `override fn name(self, ...) { ... }`
                     self.advance(); // consume 'override'
                     let mut method = self.parse_method()?;
-                    method.node.is_pub = is_pub;
+                    method.node.visibility = visibility;
                     method.node.is_override = true;
+                    method.node.no_coverage |= no_coverage;
                     methods.push(method);
                 } else {
                     let mut method = self.parse_method()?;
-                    method.node.is_pub = is_pub;
+                    method.node.visibility = visibility;
+                    method.node.no_coverage |= no_coverage;
                     methods.push(method);
                 }
             }
@@ -1049,7 +1268,7 @@ impl<'a> Parser<'a> {
             name,
             params,
             return_type,
-            is_pub: false,
+            visibility: Visibility::Private,
         }, Span::new(start, end)))
     }
 
@@ -1092,11 +1311,29 @@ impl<'a> Parser<'a> {
         let start = enum_tok.span.start;
         let name = self.expect_ident()?;
         let (type_params, type_param_bounds) = self.parse_type_params()?;
+
+        // Check for `impl Trait1, Trait2`
+        let mut impl_traits = Vec::new();
+        if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::Impl) {
+            self.advance(); // consume 'impl'
+            impl_traits.push(self.expect_ident()?);
+            while self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::Comma) {
+                self.advance(); // consume ','
+                impl_traits.push(self.expect_ident()?);
+            }
+        }
+
         self.expect(&Token::LBrace)?;
         self.skip_newlines();
 
         let mut variants = Vec::new();
+        let mut methods = Vec::new();
         while self.peek().is_some() && !matches!(self.peek().expect("token should exist after is_some check").node, Token::RBrace) {
+            if matches!(self.peek().expect("token should exist after is_some check").node, Token::Fn | Token::At) {
+                methods.push(self.parse_method()?);
+                self.skip_newlines();
+                continue;
+            }
             let vname = self.expect_ident()?;
             let fields = if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::LBrace) {
                 self.expect(&Token::LBrace)?;
@@ -1109,6 +1346,24 @@ impl<'a> Parser<'a> {
                 })?;
                 self.expect(&Token::RBrace)?;
                 fields
+            } else if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::LParen) {
+                // Tuple-style variant: Circle(float) gets fields _0, _1, ...
+                self.expect(&Token::LParen)?;
+                self.skip_newlines();
+                let types = self.parse_comma_list(&Token::RParen, true, |p| p.parse_type())?;
+                self.expect(&Token::RParen)?;
+                types
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, fty)| Field {
+                        id: Uuid::new_v4(),
+                        name: Spanned::new(positional_field_name(i), fty.span),
+                        ty: fty,
+                        is_injected: false,
+                        is_ambient: false,
+                        is_remote: false,
+                    })
+                    .collect()
             } else {
                 Vec::new()
             };
@@ -1119,7 +1374,50 @@ impl<'a> Parser<'a> {
         let close = self.expect(&Token::RBrace)?;
         let end = close.span.end;
 
-        Ok(Spanned::new(EnumDecl { id: Uuid::new_v4(), name, type_params, type_param_bounds, variants, is_pub: false }, Span::new(start, end)))
+        Ok(Spanned::new(EnumDecl { id: Uuid::new_v4(), name, type_params, type_param_bounds, variants, methods, impl_traits, visibility: Visibility::Private }, Span::new(start, end)))
+    }
+
+    fn parse_const_decl(&mut self) -> Result<Spanned<ConstDecl>, CompileError> {
+        let const_tok = self.expect(&Token::Const)?;
+        let start = const_tok.span.start;
+        let name = self.expect_ident()?;
+        let ty = if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::Colon) {
+            self.advance(); // consume ':'
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+        self.expect(&Token::Eq)?;
+        let value = self.parse_expr(0)?;
+        let end = value.span.end;
+        self.consume_statement_end()?;
+
+        Ok(Spanned::new(ConstDecl { id: Uuid::new_v4(), name, ty, value, visibility: Visibility::Private }, Span::new(start, end)))
+    }
+
+    fn parse_global_decl(&mut self) -> Result<Spanned<GlobalDecl>, CompileError> {
+        let global_span = self.expect(&Token::Global)?.span;
+        let start = global_span.start;
+        if !(self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::Mut)) {
+            return Err(CompileError::syntax(
+                "globals must be declared 'global mut'; use 'const' for an immutable value",
+                global_span,
+            ));
+        }
+        self.advance(); // consume 'mut'
+        let name = self.expect_ident()?;
+        let ty = if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::Colon) {
+            self.advance(); // consume ':'
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+        self.expect(&Token::Eq)?;
+        let value = self.parse_expr(0)?;
+        let end = value.span.end;
+        self.consume_statement_end()?;
+
+        Ok(Spanned::new(GlobalDecl { id: Uuid::new_v4(), name, ty, value, visibility: Visibility::Private }, Span::new(start, end)))
     }
 
     fn parse_error_decl(&mut self) -> Result<Spanned<ErrorDecl>, CompileError> {
@@ -1150,7 +1448,7 @@ impl<'a> Parser<'a> {
         let close = self.expect(&Token::RBrace)?;
         let end = close.span.end;
 
-        Ok(Spanned::new(ErrorDecl { id: Uuid::new_v4(), name, fields, is_pub: false }, Span::new(start, end)))
+        Ok(Spanned::new(ErrorDecl { id: Uuid::new_v4(), name, fields, visibility: Visibility::Private }, Span::new(start, end)))
     }
 
     fn parse_trait(&mut self) -> Result<Spanned<TraitDecl>, CompileError> {
@@ -1169,7 +1467,7 @@ impl<'a> Parser<'a> {
         let close = self.expect(&Token::RBrace)?;
         let end = close.span.end;
 
-        Ok(Spanned::new(TraitDecl { id: Uuid::new_v4(), name, methods, is_pub: false }, Span::new(start, end)))
+        Ok(Spanned::new(TraitDecl { id: Uuid::new_v4(), name, methods, visibility: Visibility::Private }, Span::new(start, end)))
     }
 
     fn parse_trait_method(&mut self) -> Result<TraitMethod, CompileError> {
@@ -1220,8 +1518,8 @@ impl<'a> Parser<'a> {
         self.expect(&Token::RParen)?;
 
         // Check for return type - use peek_raw() to detect newline boundary
-        let return_type = if let Some(next_raw) = self.peek_raw() && matches!(next_raw.node, Token::Newline | Token::RBrace | Token::Requires) {
-            // Newline, closing brace, or contract - no return type
+        let return_type = if let Some(next_raw) = self.peek_raw() && matches!(next_raw.node, Token::Newline | Token::RBrace | Token::Requires | Token::Pipe) {
+            // Newline, closing brace, contract, or error set - no return type
             None
         } else if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::LBrace) {
             // Opening brace (method body) - no return type
@@ -1232,6 +1530,7 @@ impl<'a> Parser<'a> {
         } else {
             None
         };
+        let declared_errors = self.parse_declared_errors()?;
 
         // Parse optional requires contracts
         let contracts = self.parse_contracts()?;
@@ -1246,7 +1545,7 @@ impl<'a> Parser<'a> {
             None
         };
 
-        Ok(TraitMethod { id: Uuid::new_v4(), name, params, return_type, contracts, body })
+        Ok(TraitMethod { id: Uuid::new_v4(), name, params, return_type, contracts, body, declared_errors })
     }
 
     fn parse_class(&mut self) -> Result<Spanned<ClassDecl>, CompileError> {
@@ -1286,6 +1585,13 @@ impl<'a> Parser<'a> {
             Vec::new()
         };
 
+        // Optional `where T: Trait` constraining the impl list
+        let where_bounds = if !impl_traits.is_empty() && self.eat_contextual_keyword("where") {
+            self.parse_where_clause()?
+        } else {
+            HashMap::new()
+        };
+
         self.expect(&Token::LBrace)?;
         self.skip_newlines();
 
@@ -1295,7 +1601,7 @@ impl<'a> Parser<'a> {
         let mut invariants = Vec::new();
 
         while self.peek().is_some() && !matches!(self.peek().expect("token should exist after is_some check").node, Token::RBrace) {
-            if matches!(self.peek().expect("token should exist after is_some check").node, Token::Fn) {
+            if matches!(self.peek().expect("token should exist after is_some check").node, Token::Fn | Token::At) {
                 methods.push(self.parse_method()?);
             } else if matches!(self.peek().expect("token should exist after is_some check").node, Token::Invariant) {
                 let inv_tok = self.advance().expect("token should exist after peek");
@@ -1325,13 +1631,36 @@ impl<'a> Parser<'a> {
         let close = self.expect(&Token::RBrace)?;
         let end = close.span.end;
 
-        Ok(Spanned::new(ClassDecl { id: Uuid::new_v4(), name, type_params, type_param_bounds, fields, methods, invariants, impl_traits, uses, is_pub: false, lifecycle: Lifecycle::Singleton }, Span::new(start, end)))
+        Ok(Spanned::new(ClassDecl { id: Uuid::new_v4(), name, type_params, type_param_bounds, fields, methods, invariants, impl_traits, where_bounds, uses, visibility: Visibility::Private, lifecycle: Lifecycle::Singleton }, Span::new(start, end)))
+    }
+
+    /// Parse the constraints after `where`: `T: Trait1 + Trait2, U: Trait3`.
+    fn parse_where_clause(&mut self) -> Result<HashMap<String, Vec<Spanned<String>>>, CompileError> {
+        let mut bounds: HashMap<String, Vec<Spanned<String>>> = HashMap::new();
+        loop {
+            let param = self.expect_ident()?;
+            self.expect(&Token::Colon)?;
+            let traits = bounds.entry(param.node).or_default();
+            traits.push(self.expect_ident()?);
+            while self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::Plus) {
+                self.advance(); // consume '+'
+                traits.push(self.expect_ident()?);
+            }
+            if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::Comma) {
+                self.advance(); // consume ','
+            } else {
+                break;
+            }
+        }
+        Ok(bounds)
     }
 
     fn parse_method(&mut self) -> Result<Spanned<Function>, CompileError> {
+        let no_coverage = self.parse_no_coverage()?;
         let fn_tok = self.expect(&Token::Fn)?;
         let start = fn_tok.span.start;
-        let name = self.expect_ident()?;
+        let name = self.expect_member_name()?;
+        let (type_params, type_param_bounds) = self.parse_type_params()?;
         self.expect(&Token::LParen)?;
 
         let mut params = Vec::new();
@@ -1377,11 +1706,12 @@ impl<'a> Parser<'a> {
         }
         self.expect(&Token::RParen)?;
 
-        let return_type = if self.peek().is_some() && !matches!(self.peek().expect("token should exist after is_some check").node, Token::LBrace | Token::Requires) {
+        let return_type = if self.peek().is_some() && !matches!(self.peek().expect("token should exist after is_some check").node, Token::LBrace | Token::Requires | Token::Pipe) {
             Some(self.parse_type()?)
         } else {
             None
         };
+        let declared_errors = self.parse_declared_errors()?;
 
         // Reject generator methods (Phase 1: generators are top-level functions only)
         if return_type.as_ref().is_some_and(|rt| matches!(rt.node, TypeExpr::Stream(_))) {
@@ -1397,7 +1727,7 @@ impl<'a> Parser<'a> {
         let end = body.span.end;
 
         Ok(Spanned::new(
-            Function { id: Uuid::new_v4(), name, type_params: vec![], type_param_bounds: HashMap::new(), params, return_type, contracts, body, is_pub: false, is_override: false, is_generator: false },
+            Function { id: Uuid::new_v4(), name, type_params, type_param_bounds, params, return_type, contracts, body, visibility: Visibility::Private, is_override: false, is_generator: false, declared_errors, no_coverage },
             Span::new(start, end),
         ))
     }
@@ -1486,10 +1816,30 @@ impl<'a> Parser<'a> {
         Ok(contracts)
     }
 
+    /// Parse an optional declared error set following the return type:
+    /// `| ParseError | io.IOError`. Returns `None` when no `|` follows.
+    fn parse_declared_errors(&mut self) -> Result<Option<Vec<Spanned<String>>>, CompileError> {
+        let mut errors = Vec::new();
+        while self.peek_raw().is_some_and(|t| matches!(t.node, Token::Pipe)) {
+            self.advance(); // consume '|'
+            let first = self.expect_ident()?;
+            let name = if self.peek_raw().is_some_and(|t| matches!(t.node, Token::Dot)) {
+                self.advance(); // consume '.'
+                let second = self.expect_ident()?;
+                let span = Span::new(first.span.start, second.span.end);
+                Spanned::new(format!("{}.{}", first.node, second.node), span)
+            } else {
+                first
+            };
+            errors.push(name);
+        }
+        Ok(if errors.is_empty() { None } else { Some(errors) })
+    }
+
     fn parse_function(&mut self) -> Result<Spanned<Function>, CompileError> {
         let fn_tok = self.expect(&Token::Fn)?;
         let start = fn_tok.span.start;
-        let name = self.expect_ident()?;
+        let name = self.expect_member_name()?;
         let (type_params, type_param_bounds) = self.parse_type_params()?;
         self.expect(&Token::LParen)?;
         let params = self.parse_comma_list(&Token::RParen, true, |p| {
@@ -1501,11 +1851,12 @@ impl<'a> Parser<'a> {
         self.expect(&Token::RParen)?;
 
         // Return type: if next non-newline token is not '{' or a contract keyword, it's a return type
-        let return_type = if self.peek().is_some() && !matches!(self.peek().expect("token should exist after is_some check").node, Token::LBrace | Token::Requires) {
+        let return_type = if self.peek().is_some() && !matches!(self.peek().expect("token should exist after is_some check").node, Token::LBrace | Token::Requires | Token::Pipe) {
             Some(self.parse_type()?)
         } else {
             None
         };
+        let declared_errors = self.parse_declared_errors()?;
 
         let contracts = self.parse_contracts()?;
 
@@ -1516,13 +1867,21 @@ impl<'a> Parser<'a> {
             Function {
                 id: Uuid::new_v4(), name, type_params, type_param_bounds, params,
                 is_generator: return_type.as_ref().is_some_and(|rt| matches!(rt.node, TypeExpr::Stream(_))),
-                return_type, contracts, body, is_pub: false, is_override: false,
+                return_type, contracts, body, visibility: Visibility::Private, is_override: false, declared_errors, no_coverage: false,
             },
             Span::new(start, end),
         ))
     }
 
     fn parse_type(&mut self) -> Result<Spanned<TypeExpr>, CompileError> {
+        let saved_depth = self.depth;
+        self.enter_nesting()?;
+        let result = self.parse_type_nested();
+        self.depth = saved_depth;
+        result
+    }
+
+    fn parse_type_nested(&mut self) -> Result<Spanned<TypeExpr>, CompileError> {
         self.skip_newlines();
         let mut result = if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::Stream) {
             // Stream type: stream T
@@ -1560,13 +1919,23 @@ impl<'a> Parser<'a> {
                 Box::new(Spanned::new(TypeExpr::Named("void".to_string()), Span::new(end, end)))
             };
             Ok(Spanned::new(TypeExpr::Fn { params, return_type }, Span::new(start, end)))
+        } else if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::Error) {
+            // The generic error type, e.g. a `map_err` mapper's parameter: (e: error) => ...
+            let tok = self.advance().expect("token should exist after peek");
+            Ok(Spanned::new(TypeExpr::Named("error".to_string()), tok.span))
         } else {
             let ident = self.expect_ident()?;
-            // Check for qualified type: module.Type
+            // Check for qualified type: module.Type (or dir.module.Type for glob-imported modules)
             if self.peek_raw().is_some() && matches!(self.peek_raw().unwrap().node, Token::Dot) {
+                let mut module = ident.node;
                 self.advance(); // consume '.'
-                let type_name = self.expect_ident()?;
-                let qualified_name = format!("{}.{}", ident.node, type_name.node);
+                let mut type_name = self.expect_ident()?;
+                while self.peek_raw().is_some() && matches!(self.peek_raw().unwrap().node, Token::Dot) {
+                    self.advance(); // consume '.'
+                    module = format!("{}.{}", module, type_name.node);
+                    type_name = self.expect_ident()?;
+                }
+                let qualified_name = format!("{}.{}", module, type_name.node);
                 let end_span = type_name.span.end;
                 // Check for generic type args on qualified type: module.Type<int, string>
                 if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::Lt) {
@@ -1575,7 +1944,7 @@ impl<'a> Parser<'a> {
                     Ok(Spanned::new(TypeExpr::Generic { name: qualified_name, type_args }, Span::new(ident.span.start, end)))
                 } else {
                     let span = Span::new(ident.span.start, end_span);
-                    Ok(Spanned::new(TypeExpr::Qualified { module: ident.node, name: type_name.node }, span))
+                    Ok(Spanned::new(TypeExpr::Qualified { module, name: type_name.node }, span))
                 }
             } else if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::Lt) {
                 // Generic type: Type<int, string>
@@ -1597,6 +1966,14 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_block(&mut self) -> Result<Spanned<Block>, CompileError> {
+        let saved_depth = self.depth;
+        self.enter_nesting()?;
+        let result = self.parse_block_nested();
+        self.depth = saved_depth;
+        result
+    }
+
+    fn parse_block_nested(&mut self) -> Result<Spanned<Block>, CompileError> {
         let open = self.expect(&Token::LBrace)?;
         let start = open.span.start;
         let mut stmts = Vec::new();
@@ -1631,6 +2008,7 @@ impl<'a> Parser<'a> {
             Token::Raise => self.parse_raise_stmt(),
             Token::Assert => self.parse_assert_stmt(),
             Token::Serve => self.parse_serve_stmt(),
+            Token::Try => self.parse_try_stmt(),
             Token::Break => {
                 let span = self.advance().expect("token should exist after peek").span;
                 self.consume_statement_end()?;
@@ -1855,6 +2233,19 @@ impl<'a> Parser<'a> {
             return self.parse_let_chan(start);
         }
 
+        // Struct destructuring: let Point { x, y } = p
+        if self.peek().is_some_and(|t| matches!(t.node, Token::Ident))
+            && self.peek_nth(1).is_some_and(|t| matches!(t.node, Token::LBrace | Token::Lt))
+        {
+            let ty = self.parse_type()?;
+            let fields = self.parse_match_bindings()?;
+            self.expect(&Token::Eq)?;
+            let value = self.parse_expr(0)?;
+            let end = value.span.end;
+            self.consume_statement_end()?;
+            return Ok(Spanned::new(Stmt::LetStruct { ty, fields, value, is_mut }, Span::new(start, end)));
+        }
+
         let name = self.expect_ident()?;
 
         let ty = if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::Colon) {
@@ -1943,6 +2334,17 @@ impl<'a> Parser<'a> {
     fn parse_if_stmt(&mut self) -> Result<Spanned<Stmt>, CompileError> {
         let if_tok = self.expect(&Token::If)?;
         let start = if_tok.span.start;
+
+        // `if let x = maybe { ... }` unwraps a nullable value
+        let binding = if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::Let) {
+            self.advance(); // consume 'let'
+            let name = self.expect_ident()?;
+            self.expect(&Token::Eq)?;
+            Some(name)
+        } else {
+            None
+        };
+
         let old_restrict = self.restrict_struct_lit;
         self.restrict_struct_lit = true;
         let condition = self.parse_expr(0)?;
@@ -1968,10 +2370,11 @@ impl<'a> Parser<'a> {
 
         let end = else_block.as_ref().map_or(then_block.span.end, |b| b.span.end);
 
-        Ok(Spanned::new(
-            Stmt::If { condition, then_block, else_block },
-            Span::new(start, end),
-        ))
+        let stmt = match binding {
+            Some(binding) => Stmt::IfLet { binding, value: condition, then_block, else_block },
+            None => Stmt::If { condition, then_block, else_block },
+        };
+        Ok(Spanned::new(stmt, Span::new(start, end)))
     }
 
     fn parse_while_stmt(&mut self) -> Result<Spanned<Stmt>, CompileError> {
@@ -2018,28 +2421,22 @@ impl<'a> Parser<'a> {
         self.expect(&Token::LBrace)?;
         self.skip_newlines();
 
+        if self.is_nullable_arm_ahead() {
+            return self.parse_nullable_match_arms(start, scrutinee);
+        }
+        if self.is_int_arm_ahead() {
+            return self.parse_int_match_arms(start, scrutinee);
+        }
+
         let mut arms = Vec::new();
         while self.peek().is_some() && !matches!(self.peek().expect("token should exist after is_some check").node, Token::RBrace) {
-            let first_name = self.expect_ident()?;
-            self.expect(&Token::Dot)?;
-            let second_name = self.expect_ident()?;
+            let (enum_name, variant_name) = self.parse_match_arm_variant()?;
 
-            // Check if this is module.Enum.Variant (qualified) or Enum.Variant (local)
-            let (enum_name, variant_name) = if self.peek().is_some()
-                && matches!(self.peek().expect("token should exist after is_some check").node, Token::Dot)
-            {
-                // module.Enum.Variant — consume the extra dot and variant
-                self.advance(); // consume '.'
-                let variant = self.expect_ident()?;
-                let qualified = format!("{}.{}", first_name.node, second_name.node);
-                let span = Span::new(first_name.span.start, second_name.span.end);
-                (Spanned::new(qualified, span), variant)
-            } else {
-                // Enum.Variant (local)
-                (first_name, second_name)
-            };
-
-            let (bindings, body) = if self.is_match_bindings_ahead() {
+            let (bindings, body) = if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::LParen) {
+                let bindings = self.parse_positional_bindings()?;
+                let body = self.parse_block()?;
+                (bindings, body)
+            } else if self.is_match_bindings_ahead() {
                 // Parse bindings: { field_name, field_name: rename }
                 self.expect(&Token::LBrace)?;
                 self.skip_newlines();
@@ -2075,11 +2472,186 @@ impl<'a> Parser<'a> {
             arms.push(MatchArm { enum_name, variant_name, type_args: vec![], bindings, body, enum_id: None, variant_id: None });
             self.skip_newlines();
         }
-
-        let close = self.expect(&Token::RBrace)?;
-        let end = close.span.end;
-
-        Ok(Spanned::new(Stmt::Match { expr: scrutinee, arms }, Span::new(start, end)))
+
+        let close = self.expect(&Token::RBrace)?;
+        let end = close.span.end;
+
+        Ok(Spanned::new(Stmt::Match { expr: scrutinee, arms }, Span::new(start, end)))
+    }
+
+    /// True when the next match arm is `some(x)` or `none` rather than `Enum.Variant`.
+    fn is_nullable_arm_ahead(&self) -> bool {
+        match self.tokens.get(self.pos) {
+            Some(tok) if matches!(tok.node, Token::None) => true,
+            Some(tok) if matches!(tok.node, Token::Ident) => {
+                &self.source[tok.span.start..tok.span.end] == "some"
+                    && matches!(self.tokens.get(self.pos + 1).map(|t| &t.node), Some(Token::LParen))
+            }
+            _ => false,
+        }
+    }
+
+    /// Parse the arms of `match maybe { some(x) { ... } none { ... } }` (the opening
+    /// brace is already consumed). Missing arms are reported by the type checker.
+    fn parse_nullable_match_arms(
+        &mut self,
+        start: usize,
+        scrutinee: Spanned<Expr>,
+    ) -> Result<Spanned<Stmt>, CompileError> {
+        let mut some_arm: Option<SomeArm> = None;
+        let mut none_arm: Option<Spanned<Block>> = None;
+
+        while self.peek().is_some() && !matches!(self.peek().expect("token should exist after is_some check").node, Token::RBrace) {
+            let arm_start = self.peek().expect("token should exist after is_some check").span;
+            if !self.is_nullable_arm_ahead() {
+                return Err(CompileError::syntax(
+                    "expected 'some(..)' or 'none' arm in match on a nullable value",
+                    arm_start,
+                ));
+            }
+            if matches!(self.tokens[self.pos].node, Token::None) {
+                self.advance(); // consume 'none'
+                let body = self.parse_block()?;
+                if none_arm.is_some() {
+                    return Err(CompileError::syntax("duplicate 'none' arm", arm_start));
+                }
+                none_arm = Some(body);
+            } else {
+                self.advance(); // consume 'some'
+                self.expect(&Token::LParen)?;
+                let binding = self.expect_ident()?;
+                self.expect(&Token::RParen)?;
+                let body = self.parse_block()?;
+                if some_arm.is_some() {
+                    return Err(CompileError::syntax("duplicate 'some' arm", arm_start));
+                }
+                some_arm = Some(SomeArm { binding, body });
+            }
+            self.skip_newlines();
+        }
+
+        let close = self.expect(&Token::RBrace)?;
+        Ok(Spanned::new(
+            Stmt::NullableMatch { expr: scrutinee, some_arm, none_arm },
+            Span::new(start, close.span.end),
+        ))
+    }
+
+    /// True when the next match arm is an int pattern (`0`, `-1`, `1..10`, `..0`,
+    /// `_` or a const name) rather than `Enum.Variant`.
+    fn is_int_arm_ahead(&self) -> bool {
+        match self.tokens.get(self.pos) {
+            Some(tok) => match tok.node {
+                Token::IntLit(_) | Token::Minus | Token::DotDot | Token::DotDotEq => true,
+                Token::Ident => !matches!(self.tokens.get(self.pos + 1).map(|t| &t.node), Some(Token::Dot)),
+                _ => false,
+            },
+            None => false,
+        }
+    }
+
+    /// Parse the arms of `match n { 0 { ... } 1..10 | 20 { ... } _ { ... } }`
+    /// (the opening brace is already consumed).
+    fn parse_int_match_arms(
+        &mut self,
+        start: usize,
+        scrutinee: Spanned<Expr>,
+    ) -> Result<Spanned<Stmt>, CompileError> {
+        let mut arms = Vec::new();
+        while self.peek().is_some() && !matches!(self.peek().expect("token should exist after is_some check").node, Token::RBrace) {
+            if !self.is_int_arm_ahead() {
+                let tok = self.peek().expect("token should exist after is_some check");
+                return Err(CompileError::syntax(
+                    "expected an int pattern arm in match on an int value",
+                    tok.span,
+                ));
+            }
+            let mut patterns = vec![self.parse_int_pattern()?];
+            while self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::Pipe) {
+                self.advance(); // consume '|'
+                patterns.push(self.parse_int_pattern()?);
+            }
+            let body = self.parse_block()?;
+            arms.push(IntMatchArm { patterns, body });
+            self.skip_newlines();
+        }
+
+        let close = self.expect(&Token::RBrace)?;
+        Ok(Spanned::new(
+            Stmt::IntMatch { expr: scrutinee, arms },
+            Span::new(start, close.span.end),
+        ))
+    }
+
+    fn parse_int_pattern(&mut self) -> Result<Spanned<IntPattern>, CompileError> {
+        self.skip_newlines();
+        let tok = self.peek().ok_or_else(|| CompileError::syntax("expected int pattern", Span::new(self.source.len(), self.source.len())))?;
+        let start = tok.span;
+        if matches!(tok.node, Token::Ident) && &self.source[tok.span.start..tok.span.end] == "_" {
+            self.advance();
+            return Ok(Spanned::new(IntPattern::Wildcard, start));
+        }
+
+        let lo = if matches!(tok.node, Token::DotDot | Token::DotDotEq) {
+            None
+        } else {
+            Some(self.parse_int_pattern_bound()?)
+        };
+        let inclusive = match self.peek_raw().map(|t| &t.node) {
+            Some(Token::DotDot) => false,
+            Some(Token::DotDotEq) => true,
+            _ => {
+                let value = lo.expect("bound was parsed since the pattern does not start with a range");
+                let span = value.span;
+                return Ok(Spanned::new(IntPattern::Value(value), span));
+            }
+        };
+        let range_tok = self.advance().expect("token should exist after peek").span;
+        let has_upper = self.peek_raw().is_some_and(|t| match t.node {
+            Token::IntLit(_) | Token::Minus => true,
+            Token::Ident => &self.source[t.span.start..t.span.end] != "_",
+            _ => false,
+        });
+        let hi = if has_upper { Some(self.parse_int_pattern_bound()?) } else { None };
+        if hi.is_none() && (inclusive || lo.is_none()) {
+            return Err(CompileError::syntax("expected an upper bound after range in pattern", range_tok));
+        }
+        let end = hi.as_ref().map_or(range_tok.end, |h| h.span.end);
+        Ok(Spanned::new(
+            IntPattern::Range { start: lo, end: hi, inclusive },
+            Span::new(start.start, end),
+        ))
+    }
+
+    /// A bound in an int pattern: an int literal, optionally negated, or a const name.
+    fn parse_int_pattern_bound(&mut self) -> Result<Box<Spanned<Expr>>, CompileError> {
+        self.skip_newlines();
+        let tok = self.peek().ok_or_else(|| CompileError::syntax("expected int pattern", Span::new(self.source.len(), self.source.len())))?.clone();
+        match tok.node {
+            Token::IntLit(n) => {
+                self.advance();
+                Ok(Box::new(Spanned::new(Expr::IntLit(n), tok.span)))
+            }
+            Token::Minus => {
+                self.advance();
+                let lit = self.advance().cloned();
+                match lit {
+                    // Fold the sign so that `-9223372036854775808` stays representable
+                    Some(Spanned { node: Token::IntLit(n), span }) => {
+                        Ok(Box::new(Spanned::new(Expr::IntLit(n.wrapping_neg()), Span::new(tok.span.start, span.end))))
+                    }
+                    _ => Err(CompileError::syntax("expected an int literal after '-' in pattern", tok.span)),
+                }
+            }
+            Token::Ident => {
+                let name = self.expect_ident()?;
+                Ok(Box::new(Spanned::new(Expr::Ident(name.node), name.span)))
+            }
+            _ => Err(CompileError::syntax(
+                format!("expected an int literal or const in pattern, found {}", tok.node),
+                tok.span,
+            )),
+        }
     }
 
     fn is_match_bindings_ahead(&self) -> bool {
@@ -2253,31 +2825,33 @@ impl<'a> Parser<'a> {
         ))
     }
 
-    fn parse_match_expr_arm(&mut self) -> Result<MatchExprArm, CompileError> {
-        // Parse enum and variant name
-        let first_ident = self.expect_ident()?;
+    /// Parse a match arm's `Enum.Variant`. The enum may be module-qualified:
+    /// `module.Enum.Variant`, or `dir.module.Enum.Variant` for a glob import.
+    fn parse_match_arm_variant(&mut self) -> Result<(Spanned<String>, Spanned<String>), CompileError> {
+        let mut enum_name = self.expect_ident()?;
         self.expect(&Token::Dot)?;
-        let second_ident = self.expect_ident()?;
-
-        let (enum_name, variant_name) = if self.peek().is_some()
+        let mut variant = self.expect_ident()?;
+        while self.peek().is_some()
             && matches!(self.peek().expect("token should exist after is_some check").node, Token::Dot)
         {
-            // Module qualified: module.Enum.Variant
-            self.advance();
-            let third_ident = self.expect_ident()?;
-            (
-                Spanned::new(
-                    format!("{}.{}", first_ident.node, second_ident.node),
-                    Span::new(first_ident.span.start, second_ident.span.end),
-                ),
-                third_ident,
-            )
-        } else {
-            (first_ident, second_ident)
-        };
+            self.advance(); // consume '.'
+            let span = Span::new(enum_name.span.start, variant.span.end);
+            enum_name = Spanned::new(format!("{}.{}", enum_name.node, variant.node), span);
+            variant = self.expect_ident()?;
+        }
+        Ok((enum_name, variant))
+    }
+
+    fn parse_match_expr_arm(&mut self) -> Result<MatchExprArm, CompileError> {
+        // Parse enum and variant name
+        let (enum_name, variant_name) = self.parse_match_arm_variant()?;
 
         // Parse bindings if present
         let bindings = if self.peek().is_some()
+            && matches!(self.peek().expect("token should exist after is_some check").node, Token::LParen)
+        {
+            self.parse_positional_bindings()?
+        } else if self.peek().is_some()
             && matches!(self.peek().expect("token should exist after is_some check").node, Token::LBrace)
             && self.is_match_expr_bindings_ahead()
         {
@@ -2305,9 +2879,7 @@ impl<'a> Parser<'a> {
         })
     }
 
-    fn parse_match_bindings(
-        &mut self,
-    ) -> Result<Vec<(Spanned<String>, Option<Spanned<String>>)>, CompileError> {
+    fn parse_match_bindings(&mut self) -> Result<MatchBindings, CompileError> {
         self.expect(&Token::LBrace)?;
         self.skip_newlines();
 
@@ -2541,6 +3113,32 @@ impl<'a> Parser<'a> {
         Ok(Spanned::new(Stmt::Serve { service, port }, Span::new(start, end)))
     }
 
+    fn parse_try_stmt(&mut self) -> Result<Spanned<Stmt>, CompileError> {
+        let try_tok = self.expect(&Token::Try)?;
+        let start = try_tok.span.start;
+        let body = self.parse_block()?;
+        let catch_tok = self.expect(&Token::Catch)?;
+        let catch_span = catch_tok.span;
+        if !self.is_catch_wildcard_ahead() {
+            return Err(CompileError::syntax(
+                "expected 'catch <name> { ... }' after a try block",
+                catch_span,
+            ));
+        }
+        let var = self.expect_ident()?;
+        let handler = self.parse_block()?;
+        let mut end = handler.span.end;
+        let finally = if self.peek().is_some_and(|t| matches!(t.node, Token::Finally)) {
+            self.expect(&Token::Finally)?;
+            let block = self.parse_block()?;
+            end = block.span.end;
+            Some(block)
+        } else {
+            None
+        };
+        Ok(Spanned::new(Stmt::Try { body, var, handler, finally }, Span::new(start, end)))
+    }
+
     fn parse_raise_stmt(&mut self) -> Result<Spanned<Stmt>, CompileError> {
         let raise_tok = self.expect(&Token::Raise)?;
         let start = raise_tok.span.start;
@@ -2655,15 +3253,48 @@ impl<'a> Parser<'a> {
 
     // Pratt parser for expressions
     fn parse_expr(&mut self, min_bp: u8) -> Result<Spanned<Expr>, CompileError> {
+        let saved_depth = self.depth;
+        self.enter_nesting()?;
+        let result = self.parse_expr_nested(min_bp);
+        self.depth = saved_depth;
+        result
+    }
+
+    fn parse_expr_nested(&mut self, min_bp: u8) -> Result<Spanned<Expr>, CompileError> {
         let mut lhs = self.parse_prefix()?;
 
+        let mut folded = false;
         while let Some(tok) = self.peek().cloned() {
+            // Every pass after the first has wrapped `lhs` in one more node, so
+            // long operator and call chains nest the AST like parentheses do.
+            if folded {
+                self.enter_nesting()?;
+            }
+            folded = true;
 
             // Dot notation (postfix) — highest precedence
             if matches!(tok.node, Token::Dot) {
                 self.skip_newlines();
                 self.advance(); // consume '.'
-                let field_name = self.expect_ident()?;
+                let field_name = self.expect_member_name()?;
+
+                // Tuple-style enum construction: EnumName.Variant(value, ...)
+                if matches!(&lhs.node, Expr::Ident(n) if self.enum_names.contains(n))
+                    && self.peek().is_some()
+                    && matches!(self.peek().expect("token should exist after is_some check").node, Token::LParen)
+                {
+                    let enum_name = match &lhs.node {
+                        Expr::Ident(n) => Spanned::new(n.clone(), lhs.span),
+                        _ => unreachable!(),
+                    };
+                    let (fields, close_end) = self.parse_positional_args()?;
+                    let span = Span::new(lhs.span.start, close_end);
+                    lhs = Spanned::new(
+                        Expr::EnumData { enum_name, variant: field_name, type_args: vec![], fields, enum_id: None, variant_id: None },
+                        span,
+                    );
+                    continue;
+                }
 
                 // Check if it's a method call
                 if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::LParen) {
@@ -2761,10 +3392,11 @@ impl<'a> Parser<'a> {
                         let span = Span::new(segments[0].span.start, close_end);
 
                         if segments.len() >= 3 {
-                            // Pattern: module.Enum.Variant { fields }
-                            let qualified_enum = format!("{}.{}", segments[0].node, segments[1].node);
-                            let variant = segments.last().unwrap().clone();
-                            let enum_span = Span::new(segments[0].span.start, segments[1].span.end);
+                            // Pattern: module.Enum.Variant { fields } (the module may itself be dotted)
+                            let (variant, enum_path) = segments.split_last().unwrap();
+                            let qualified_enum = enum_path.iter().map(|s| s.node.as_str()).collect::<Vec<_>>().join(".");
+                            let variant = variant.clone();
+                            let enum_span = Span::new(segments[0].span.start, enum_path.last().unwrap().span.end);
 
                             lhs = Spanned::new(
                                 Expr::EnumData {
@@ -2847,8 +3479,25 @@ impl<'a> Parser<'a> {
 
             // Postfix ! — error propagation (must be on same line via peek_raw)
             if self.peek_raw().is_some() && matches!(self.peek_raw().unwrap().node, Token::Bang) {
-                let bang = self.advance().expect("token should exist after peek");
-                let span = Span::new(lhs.span.start, bang.span.end);
+                let bang_end = self.advance().expect("token should exist after peek").span.end;
+                // Optional `map_err(mapper)` on the same line transforms the error
+                if self.split_pos >= self.split_tokens.len()
+                    && self.tokens.get(self.pos + 1).is_some_and(|t| matches!(t.node, Token::LParen))
+                    && self.eat_contextual_keyword("map_err")
+                {
+                    self.advance(); // consume '('
+                    self.skip_newlines();
+                    let mapper = self.parse_expr(0)?;
+                    self.skip_newlines();
+                    let close = self.expect(&Token::RParen)?;
+                    let span = Span::new(lhs.span.start, close.span.end);
+                    lhs = Spanned::new(
+                        Expr::PropagateMap { expr: Box::new(lhs), mapper: Box::new(mapper) },
+                        span,
+                    );
+                    continue;
+                }
+                let span = Span::new(lhs.span.start, bang_end);
                 lhs = Spanned::new(
                     Expr::Propagate { expr: Box::new(lhs) },
                     span,
@@ -2902,6 +3551,35 @@ impl<'a> Parser<'a> {
                 continue;
             }
 
+            // `is` — variant test, at comparison precedence: `x is Enum.Variant`
+            // or `x is module.Enum.Variant`
+            if min_bp < 13 && self.peek_raw().is_some_and(|t| matches!(t.node, Token::Is)) {
+                self.advance(); // consume 'is'
+                let first = self.expect_ident()?;
+                self.expect(&Token::Dot)?;
+                let second = self.expect_ident()?;
+                let (enum_name, variant) = if self.peek_raw().is_some_and(|t| matches!(t.node, Token::Dot)) {
+                    self.advance(); // consume '.'
+                    let variant = self.expect_ident()?;
+                    let qualified = format!("{}.{}", first.node, second.node);
+                    (Spanned::new(qualified, Span::new(first.span.start, second.span.end)), variant)
+                } else {
+                    (first, second)
+                };
+                let span = Span::new(lhs.span.start, variant.span.end);
+                lhs = Spanned::new(
+                    Expr::Is {
+                        expr: Box::new(lhs),
+                        enum_name,
+                        variant,
+                        enum_id: None,
+                        variant_id: None,
+                    },
+                    span,
+                );
+                continue;
+            }
+
             // Check for generic enum expression: EnumName<type_args>.Variant
             if matches!(&lhs.node, Expr::Ident(n) if self.enum_names.contains(n))
                 && matches!(tok.node, Token::Lt)
@@ -2936,6 +3614,21 @@ impl<'a> Parser<'a> {
                         },
                         span,
                     );
+                } else if self.peek().is_some() && matches!(self.peek().expect("token should exist after is_some check").node, Token::LParen) {
+                    // EnumName<type_args>.Variant(value, ...)
+                    let (fields, close_end) = self.parse_positional_args()?;
+                    let span = Span::new(lhs.span.start, close_end);
+                    lhs = Spanned::new(
+                        Expr::EnumData {
+                            enum_name: Spanned::new(enum_name_str, enum_name_span),
+                            variant,
+                            type_args,
+                            fields,
+                            enum_id: None,
+                            variant_id: None,
+                        },
+                        span,
+                    );
                 } else {
                     let span = Span::new(lhs.span.start, variant.span.end);
                     lhs = Spanned::new(
@@ -3270,6 +3963,8 @@ impl<'a> Parser<'a> {
                     if i < self.tokens.len() && matches!(self.tokens[i].node, Token::Lt) {
                         return self.parse_static_trait_call(ident);
                     }
+                    // No type args: `Type::method` is a method reference
+                    return self.parse_method_ref(ident);
                 }
             }
         }
@@ -3393,6 +4088,15 @@ impl<'a> Parser<'a> {
         ))
     }
 
+    /// Parse unbound method reference: TypeName::method
+    fn parse_method_ref(&mut self, type_name: Spanned<String>) -> Result<Spanned<Expr>, CompileError> {
+        let start = type_name.span.start;
+        self.expect(&Token::DoubleColon)?;
+        let method = self.expect_ident()?;
+        let span = Span::new(start, method.span.end);
+        Ok(Spanned::new(Expr::MethodRef { type_name, method }, span))
+    }
+
     fn is_at_end(&self) -> bool {
         let mut i = self.pos;
         while i < self.tokens.len() {
@@ -3404,10 +4108,70 @@ impl<'a> Parser<'a> {
         true
     }
 
+    /// Source offsets of the expressions inside an f-string token, keyed by the index
+    /// of the part they become. Scans the raw source text so escape sequences before
+    /// an expression don't skew its position.
+    fn interp_expr_offsets(&self, span: crate::span::Span) -> HashMap<usize, usize> {
+        let mut offsets = HashMap::new();
+        // Skip f" (or f""" for triple-quoted strings)
+        let quotes = if self.source.get(span.start..).is_some_and(|s| s.starts_with("f\"\"\"")) { 3 } else { 1 };
+        let base = span.start + 1 + quotes;
+        let Some(raw) = self.source.get(base..span.end.saturating_sub(quotes)) else {
+            return offsets;
+        };
+        let bytes = raw.as_bytes();
+        let mut has_lit = false;
+        let mut part = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' if bytes.get(i + 1) == Some(&b'u') => {
+                    while i < bytes.len() && bytes[i] != b'}' {
+                        i += 1;
+                    }
+                    i += 1;
+                    has_lit = true;
+                }
+                b'\\' => {
+                    i += 2;
+                    has_lit = true;
+                }
+                b'{' | b'}' if bytes.get(i + 1) == Some(&bytes[i]) => {
+                    i += 2;
+                    has_lit = true;
+                }
+                b'{' => {
+                    if has_lit {
+                        part += 1;
+                        has_lit = false;
+                    }
+                    offsets.insert(part, base + i + 1);
+                    part += 1;
+                    let mut depth = 1;
+                    i += 1;
+                    while i < bytes.len() && depth > 0 {
+                        match bytes[i] {
+                            b'{' => depth += 1,
+                            b'}' => depth -= 1,
+                            _ => {}
+                        }
+                        i += 1;
+                    }
+                }
+                _ => {
+                    i += 1;
+                    has_lit = true;
+                }
+            }
+        }
+        offsets
+    }
+
     fn parse_string_interp(&self, raw: &str, span: crate::span::Span) -> Result<Spanned<Expr>, CompileError> {
         let mut parts: Vec<StringInterpPart> = Vec::new();
         let mut lit_buf = String::new();
         let mut chars = raw.char_indices().peekable();
+        let expr_offsets = self.interp_expr_offsets(span);
 
         while let Some(&(_, ch)) = chars.peek() {
             if ch == '{' {
@@ -3448,9 +4212,24 @@ impl<'a> Parser<'a> {
                             }
                         }
                     }
-                    // Sub-parse the expression
-                    let tokens = crate::lexer::lex(&expr_str)?;
-                    let mut sub_parser = Parser::new(&tokens, &expr_str);
+                    // Sub-parse the expression. When the expression appears verbatim in the
+                    // source, shift its tokens to file offsets so diagnostics point at it.
+                    let base = expr_offsets.get(&parts.len()).copied().filter(|&b| {
+                        self.source.get(b..b + expr_str.len()) == Some(expr_str.as_str())
+                    });
+                    let mut tokens = crate::lexer::lex(&expr_str)?;
+                    let mut sub_parser = match base {
+                        Some(base) => {
+                            for tok in &mut tokens {
+                                tok.span.start += base;
+                                tok.span.end += base;
+                            }
+                            Parser::new(&tokens, self.source)
+                        }
+                        None => Parser::new(&tokens, &expr_str),
+                    };
+                    sub_parser.depth = self.depth;
+                    sub_parser.max_depth = self.max_depth;
                     let expr = sub_parser.parse_expr(0)?;
                     if !sub_parser.is_at_end() {
                         return Err(CompileError::syntax(
@@ -3879,6 +4658,28 @@ mod tests {
         assert_eq!(c.methods[0].node.params[0].name.node, "self");
     }
 
+    #[test]
+    fn nesting_limit_points_at_first_too_deep_expression() {
+        let src = "fn main() {\n    let x = (((1)))\n}";
+        let tokens = lex(src).unwrap();
+        let err = Parser::new(&tokens, src).with_max_nesting_depth(4).parse_program().unwrap_err();
+        let CompileError::Syntax { msg, span } = err else { panic!("expected syntax error, got {err:?}") };
+        assert!(msg.contains("too deeply nested (limit is 4 levels)"), "{msg}");
+        assert_eq!(&src[span.start..span.end], "1");
+        assert!(Parser::new(&tokens, src).with_max_nesting_depth(5).parse_program().is_ok());
+    }
+
+    #[test]
+    fn parse_class_impl_where_clause() {
+        let prog = parse("class Box<T, U> impl Printable where T: Printable + Eq, U: Printable {\n    value: T\n}");
+        let c = &prog.classes[0].node;
+        assert_eq!(c.impl_traits[0].node, "Printable");
+        let t_bounds: Vec<&str> = c.where_bounds["T"].iter().map(|b| b.node.as_str()).collect();
+        assert_eq!(t_bounds, vec!["Printable", "Eq"]);
+        assert_eq!(c.where_bounds["U"][0].node, "Printable");
+        assert!(c.type_param_bounds.is_empty());
+    }
+
     #[test]
     fn parse_struct_literal() {
         let prog = parse("fn main() {\n    let p = Point { x: 1, y: 2 }\n}");
@@ -3978,6 +4779,34 @@ mod tests {
         assert_eq!(prog.imports[0].node.full_path(), "std.io.fs");
     }
 
+    #[test]
+    fn parse_glob_import() {
+        let prog = parse("import api.handlers.*\n\nfn main() { }");
+        assert_eq!(prog.imports.len(), 1);
+        assert!(prog.imports[0].node.glob);
+        assert_eq!(prog.imports[0].node.path.len(), 2);
+        assert_eq!(prog.imports[0].node.binding_name(), "handlers");
+        assert_eq!(prog.imports[0].node.full_path(), "api.handlers.*");
+    }
+
+    #[test]
+    fn parse_selective_import() {
+        let prog = parse("import std.math.{sqrt, pow as power}\n\nfn main() { }");
+        assert_eq!(prog.imports.len(), 1);
+        let import = &prog.imports[0].node;
+        assert_eq!(import.full_path(), "std.math");
+        assert_eq!(import.binding_name(), "math");
+        let items: Vec<(&str, &str)> = import.items.iter().map(|i| (i.name.node.as_str(), i.local_name())).collect();
+        assert_eq!(items, vec![("sqrt", "sqrt"), ("pow", "power")]);
+    }
+
+    #[test]
+    fn parse_selective_import_rejects_whole_alias() {
+        let tokens = lex("import math.{sqrt} as m\n").unwrap();
+        let mut parser = Parser::new(&tokens, "import math.{sqrt} as m\n");
+        assert!(parser.parse_program().is_err());
+    }
+
     #[test]
     fn parse_import_alias() {
         let prog = parse("import std.io as io\n\nfn main() { }");
@@ -3993,33 +4822,79 @@ mod tests {
         assert_eq!(prog.extern_fns[0].node.name.node, "__pluto_print");
         assert_eq!(prog.extern_fns[0].node.params.len(), 1);
         assert!(prog.extern_fns[0].node.return_type.is_none());
-        assert!(!prog.extern_fns[0].node.is_pub);
+        assert_eq!(prog.extern_fns[0].node.visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn parse_extern_c_fn_decl() {
+        let prog = parse("extern \"C\" fn clock() int\nextern fn __pluto_print(s: string)\n\nfn main() { }");
+        assert!(prog.extern_fns[0].node.c_abi);
+        assert_eq!(prog.extern_fns[0].node.name.node, "clock");
+        assert!(!prog.extern_fns[1].node.c_abi);
+
+        let src = "extern \"Rust\" fn f()\n\nfn main() { }";
+        let tokens = lex(src).unwrap();
+        let err = Parser::new(&tokens, src).parse_program().unwrap_err();
+        assert!(err.to_string().contains("unsupported extern ABI \"Rust\""), "{err}");
     }
 
     #[test]
     fn parse_pub_extern_fn_with_return() {
         let prog = parse("pub extern fn __read(path: string) string\n\nfn main() { }");
         assert_eq!(prog.extern_fns.len(), 1);
-        assert!(prog.extern_fns[0].node.is_pub);
+        assert_eq!(prog.extern_fns[0].node.visibility, Visibility::Public);
         assert!(prog.extern_fns[0].node.return_type.is_some());
     }
 
     #[test]
     fn parse_pub_function() {
         let prog = parse("pub fn add(a: int, b: int) int {\n    return a + b\n}");
-        assert!(prog.functions[0].node.is_pub);
+        assert_eq!(prog.functions[0].node.visibility, Visibility::Public);
     }
 
     #[test]
     fn parse_non_pub_function() {
         let prog = parse("fn add(a: int, b: int) int {\n    return a + b\n}");
-        assert!(!prog.functions[0].node.is_pub);
+        assert_eq!(prog.functions[0].node.visibility, Visibility::Private);
     }
 
     #[test]
     fn parse_pub_class() {
         let prog = parse("pub class Point {\n    x: int\n}");
-        assert!(prog.classes[0].node.is_pub);
+        assert_eq!(prog.classes[0].node.visibility, Visibility::Public);
+    }
+
+    #[test]
+    fn parse_pub_module_visibility() {
+        let prog = parse("pub(module) fn helper() int {\n    return 1\n}\n\npub(module) class Point {\n    x: int\n}");
+        assert_eq!(prog.functions[0].node.visibility, Visibility::Module);
+        assert_eq!(prog.classes[0].node.visibility, Visibility::Module);
+    }
+
+    #[test]
+    fn parse_pub_unknown_scope_is_error() {
+        let src = "pub(crate) fn helper() { }";
+        let tokens = lex(src).unwrap();
+        let mut parser = Parser::new(&tokens, src);
+        assert!(parser.parse_program().is_err());
+    }
+
+    #[test]
+    fn parse_no_coverage_attribute() {
+        let prog = parse("@no_coverage\npub fn debug() { }\n\nclass C {\n    @no_coverage\n    fn dump(self) { }\n\n    fn run(self) { }\n}");
+        assert!(prog.functions[0].node.no_coverage);
+        assert_eq!(prog.functions[0].node.visibility, Visibility::Public);
+        assert!(prog.classes[0].node.methods[0].node.no_coverage);
+        assert!(!prog.classes[0].node.methods[1].node.no_coverage);
+    }
+
+    #[test]
+    fn parse_no_coverage_rejects_misuse() {
+        for src in ["@no_coverage\nclass C {\n    x: int\n}", "@inline\nfn f() { }"] {
+            let tokens = lex(src).unwrap();
+            let mut parser = Parser::new(&tokens, src);
+            assert!(parser.parse_program().is_err(), "{src}");
+        }
     }
 
     #[test]
@@ -4086,6 +4961,68 @@ mod tests {
         assert_eq!(e.variants[1].fields[0].name.node, "reason");
     }
 
+    #[test]
+    fn parse_enum_decl_methods() {
+        let prog = parse("enum Shape {\n    Circle(float)\n    Square(float)\n\n    fn area(self) float {\n        return 0.0\n    }\n\n    fn unit() Shape {\n        return Shape.Square(1.0)\n    }\n}\n\nfn main() { }");
+        let e = &prog.enums[0].node;
+        assert_eq!(e.variants.len(), 2);
+        assert_eq!(e.methods.len(), 2);
+        assert_eq!(e.methods[0].node.name.node, "area");
+        assert_eq!(e.methods[0].node.params[0].name.node, "self");
+        assert!(e.methods[1].node.params.is_empty());
+    }
+
+    #[test]
+    fn parse_enum_decl_impl_list() {
+        let prog = parse("enum Level impl Eq, Ord {\n    Low\n    High\n}\n\nfn main() { }");
+        let e = &prog.enums[0].node;
+        let traits: Vec<&str> = e.impl_traits.iter().map(|t| t.node.as_str()).collect();
+        assert_eq!(traits, vec!["Eq", "Ord"]);
+        assert_eq!(e.variants.len(), 2);
+    }
+
+    #[test]
+    fn parse_const_decl() {
+        let prog = parse("pub const MAX: int = 10 * 2\nconst NAME = \"x\"\n\nfn main() { }");
+        assert_eq!(prog.consts.len(), 2);
+        let c = &prog.consts[0].node;
+        assert_eq!(c.name.node, "MAX");
+        assert_eq!(c.visibility, Visibility::Public);
+        assert!(matches!(c.ty.as_ref().map(|t| &t.node), Some(TypeExpr::Named(n)) if n == "int"));
+        assert!(matches!(c.value.node, Expr::BinOp { .. }));
+        assert!(prog.consts[1].node.ty.is_none());
+    }
+
+    #[test]
+    fn parse_global_decl() {
+        let prog = parse("global mut COUNT: int = 0\npub global mut RATE = 0.5\n\nfn main() { }");
+        assert_eq!(prog.globals.len(), 2);
+        let g = &prog.globals[0].node;
+        assert_eq!(g.name.node, "COUNT");
+        assert!(matches!(g.ty.as_ref().map(|t| &t.node), Some(TypeExpr::Named(n)) if n == "int"));
+        assert!(matches!(g.value.node, Expr::IntLit(0)));
+        assert_eq!(prog.globals[1].node.visibility, Visibility::Public);
+        assert!(prog.globals[1].node.ty.is_none());
+    }
+
+    #[test]
+    fn parse_enum_decl_tuple_variant() {
+        let prog = parse("enum Shape {\n    Circle(float)\n    Rect(float, float)\n}\n\nfn main() {\n    let s = Shape.Circle(1.0)\n}");
+        let e = &prog.enums[0].node;
+        let names: Vec<_> = e.variants[1].fields.iter().map(|f| f.name.node.as_str()).collect();
+        assert_eq!(names, vec!["_0", "_1"]);
+        match &prog.functions[0].node.body.node.stmts[0].node {
+            Stmt::Let { value, .. } => match &value.node {
+                Expr::EnumData { variant, fields, .. } => {
+                    assert_eq!(variant.node, "Circle");
+                    assert_eq!(fields[0].0.node, "_0");
+                }
+                other => panic!("expected EnumData, got {other:?}"),
+            },
+            _ => panic!("expected let"),
+        }
+    }
+
     #[test]
     fn parse_enum_unit_expr() {
         let prog = parse("enum Color {\n    Red\n}\n\nfn main() {\n    let c = Color.Red\n}");
@@ -4104,6 +5041,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_is_expr() {
+        let prog = parse("fn main() {\n    let b = s is Shape.Circle && s is geo.Shape.Rect\n}");
+        let f = &prog.functions[0].node;
+        match &f.body.node.stmts[0].node {
+            Stmt::Let { value, .. } => match &value.node {
+                Expr::BinOp { op: BinOp::And, lhs, rhs } => {
+                    match &lhs.node {
+                        Expr::Is { expr, enum_name, variant, .. } => {
+                            assert!(matches!(&expr.node, Expr::Ident(n) if n == "s"));
+                            assert_eq!(enum_name.node, "Shape");
+                            assert_eq!(variant.node, "Circle");
+                        }
+                        _ => panic!("expected Is, got {:?}", lhs.node),
+                    }
+                    match &rhs.node {
+                        Expr::Is { enum_name, variant, .. } => {
+                            assert_eq!(enum_name.node, "geo.Shape");
+                            assert_eq!(variant.node, "Rect");
+                        }
+                        _ => panic!("expected Is, got {:?}", rhs.node),
+                    }
+                }
+                _ => panic!("expected &&, got {:?}", value.node),
+            },
+            _ => panic!("expected let"),
+        }
+    }
+
     #[test]
     fn parse_enum_data_expr() {
         let prog = parse("enum Status {\n    Suspended { reason: string }\n}\n\nfn main() {\n    let s = Status.Suspended { reason: \"banned\" }\n}");
@@ -4155,6 +5121,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_if_let() {
+        let prog = parse("fn main() {\n    let m: int? = 1\n    if let x = m {\n        print(x)\n    } else {\n        print(0)\n    }\n}");
+        let f = &prog.functions[0].node;
+        match &f.body.node.stmts[1].node {
+            Stmt::IfLet { binding, value, else_block, .. } => {
+                assert_eq!(binding.node, "x");
+                assert!(matches!(value.node, Expr::Ident(ref n) if n == "m"));
+                assert!(else_block.is_some());
+            }
+            _ => panic!("expected if let"),
+        }
+    }
+
+    #[test]
+    fn parse_match_nullable_arms() {
+        let prog = parse("fn main() {\n    let m: int? = 1\n    match m {\n        none {\n            print(0)\n        }\n        some(v) {\n            print(v)\n        }\n    }\n}");
+        let f = &prog.functions[0].node;
+        match &f.body.node.stmts[1].node {
+            Stmt::NullableMatch { some_arm: Some(some_arm), none_arm, .. } => {
+                assert_eq!(some_arm.binding.node, "v");
+                assert_eq!(some_arm.body.node.stmts.len(), 1);
+                assert!(none_arm.is_some());
+            }
+            _ => panic!("expected nullable match"),
+        }
+    }
+
+    #[test]
+    fn parse_match_int_patterns() {
+        let prog = parse("fn main() {\n    let n = 3\n    match n {\n        -1 | 0 {\n            print(0)\n        }\n        1..10 {\n            print(1)\n        }\n        ..=-2 | 10.. {\n            print(2)\n        }\n    }\n}");
+        let f = &prog.functions[0].node;
+        match &f.body.node.stmts[1].node {
+            Stmt::IntMatch { arms, .. } => {
+                assert_eq!(arms.len(), 3);
+                assert!(matches!(arms[0].patterns[0].node, IntPattern::Value(ref v) if matches!(v.node, Expr::IntLit(-1))));
+                assert!(matches!(arms[1].patterns[0].node, IntPattern::Range { start: Some(_), end: Some(_), inclusive: false }));
+                assert!(matches!(arms[2].patterns[0].node, IntPattern::Range { start: None, end: Some(_), inclusive: true }));
+                assert!(matches!(arms[2].patterns[1].node, IntPattern::Range { start: Some(_), end: None, .. }));
+            }
+            _ => panic!("expected int match"),
+        }
+    }
+
     #[test]
     fn parse_string_interpolation() {
         // String interpolation requires f-prefix
@@ -4549,6 +5559,16 @@ mod tests {
         assert!(prog.classes[0].node.type_params.is_empty());
     }
 
+    #[test]
+    fn parse_generic_method() {
+        let prog = parse("class Logger {\n    fn log<T: Show>(self, value: T) {\n    }\n}");
+        let m = &prog.classes[0].node.methods[0].node;
+        assert!(prog.classes[0].node.type_params.is_empty());
+        assert_eq!(m.type_params.len(), 1);
+        assert_eq!(m.type_params[0].node, "T");
+        assert_eq!(m.type_param_bounds["T"][0].node, "Show");
+    }
+
     #[test]
     fn parse_class_uses_single() {
         let prog = parse("class Foo uses Logger {\n}");
@@ -4715,6 +5735,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_propagate_map_err() {
+        let prog = parse("fn foo() int { return bar()! map_err((e: error) => Wrapped {}) }");
+        let f = &prog.functions[0].node;
+        match &f.body.node.stmts[0].node {
+            Stmt::Return(Some(expr)) => match &expr.node {
+                Expr::PropagateMap { expr, mapper } => {
+                    assert!(matches!(expr.node, Expr::Call { .. }));
+                    assert!(matches!(mapper.node, Expr::Closure { .. }));
+                }
+                _ => panic!("expected propagate with map_err"),
+            },
+            _ => panic!("expected return"),
+        }
+    }
+
+    #[test]
+    fn parse_unbound_method_ref() {
+        let prog = parse("fn main() { let f = Point::distance }");
+        let f = &prog.functions[0].node;
+        match &f.body.node.stmts[0].node {
+            Stmt::Let { value, .. } => match &value.node {
+                Expr::MethodRef { type_name, method } => {
+                    assert_eq!(type_name.node, "Point");
+                    assert_eq!(method.node, "distance");
+                }
+                _ => panic!("expected method reference"),
+            },
+            _ => panic!("expected let"),
+        }
+    }
+
+    #[test]
+    fn parse_try_catch_stmt() {
+        let prog = parse("fn main() { try { foo()! } catch e { print(\"failed\") } }");
+        let f = &prog.functions[0].node;
+        match &f.body.node.stmts[0].node {
+            Stmt::Try { body, var, handler, finally } => {
+                assert_eq!(body.node.stmts.len(), 1);
+                assert_eq!(var.node, "e");
+                assert_eq!(handler.node.stmts.len(), 1);
+                assert!(finally.is_none());
+            }
+            _ => panic!("expected try statement"),
+        }
+    }
+
+    #[test]
+    fn parse_try_catch_finally_stmt() {
+        let prog = parse("fn main() {\n    try { foo()! } catch e { print(\"failed\") }\n    finally { print(\"done\") }\n}");
+        let f = &prog.functions[0].node;
+        let stmt = &f.body.node.stmts[0];
+        match &stmt.node {
+            Stmt::Try { finally: Some(block), .. } => {
+                assert_eq!(block.node.stmts.len(), 1);
+                assert_eq!(stmt.span.end, block.span.end);
+            }
+            _ => panic!("expected try statement with a finally block"),
+        }
+    }
+
     #[test]
     fn parse_nested_nullable_rejected() {
         // Parser doesn't reject nested nullable (typeck does),
@@ -5250,6 +6331,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_let_struct_destructuring() {
+        let prog = parse("fn main() {\n    let Point { x, y: py } = p\n}");
+        let f = &prog.functions[0].node;
+        match &f.body.node.stmts[0].node {
+            Stmt::LetStruct { ty, fields, is_mut, .. } => {
+                assert!(matches!(&ty.node, TypeExpr::Named(n) if n == "Point"));
+                assert_eq!(fields.len(), 2);
+                assert_eq!(fields[0].0.node, "x");
+                assert!(fields[0].1.is_none());
+                assert_eq!(fields[1].1.as_ref().map(|r| r.node.as_str()), Some("py"));
+                assert!(!is_mut);
+            }
+            _ => panic!("expected let struct statement, got {:?}", f.body.node.stmts[0].node),
+        }
+    }
+
     #[test]
     fn parse_let_chan_with_capacity() {
         let prog = parse("fn main() {\n    let (s, r) = chan<int>(10)\n}");
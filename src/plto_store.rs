@@ -5,7 +5,7 @@
 //! - Fresh derived metadata
 //! - Atomic writes (temp file + rename on Unix)
 
-use crate::binary::{self, BinaryError};
+use crate::binary::{self, BinaryError, Compression};
 use crate::derived::DerivedInfo;
 use crate::parser::ast::Program;
 use std::path::Path;
@@ -20,15 +20,26 @@ pub enum StoreError {
 
 /// Write canonical .pluto file with fresh derived data.
 pub fn write_canonical(
+    path: &Path,
+    program: &Program,
+    source: &str,
+    derived: DerivedInfo,
+) -> Result<(), StoreError> {
+    write_canonical_with(path, program, source, derived, Compression::None)
+}
+
+/// Write canonical .pluto file with fresh derived data and the given section compression.
+pub fn write_canonical_with(
     path: &Path,
     program: &Program,
     source: &str,
     mut derived: DerivedInfo,
+    compression: Compression,
 ) -> Result<(), StoreError> {
     // Ensure source hash is fresh
     derived.source_hash = DerivedInfo::compute_source_hash(source);
 
-    let bytes = binary::serialize_program(program, source, &derived)?;
+    let bytes = binary::serialize_program_with(program, source, &derived, compression)?;
 
     // Atomic write on Unix: temp file + rename
     #[cfg(unix)]
@@ -71,7 +82,7 @@ pub fn write_canonical_stale(
     Ok(())
 }
 
-/// Read canonical .pluto file (supports v2 and v3).
+/// Read canonical .pluto file (any supported version, compressed or not).
 pub fn read_canonical(path: &Path) -> Result<(Program, String, DerivedInfo), StoreError> {
     let bytes = std::fs::read(path)?;
     let (program, source, derived) = binary::deserialize_program(&bytes)?;
@@ -19,6 +19,11 @@ struct PreludeData {
 
 static PRELUDE: OnceLock<PreludeData> = OnceLock::new();
 
+/// Prelude names that user code may declare itself. They joined the prelude
+/// after programs could already use them, so a user declaration replaces the
+/// prelude's instead of conflicting with it.
+const SHADOWABLE: &[&str] = &["Ordering", "Add", "Sub", "Mul", "Eq", "Ord"];
+
 fn get_prelude() -> &'static PreludeData {
     PRELUDE.get_or_init(|| {
         let tokens = crate::lexer::lex(PRELUDE_SOURCE).expect("prelude must lex");
@@ -61,13 +66,23 @@ pub fn inject_prelude(program: &mut Program) -> Result<(), CompileError> {
     let data = get_prelude();
 
     // Check if prelude is already injected (idempotency check)
-    // If the first enum is a prelude enum, assume prelude is already there
-    if !program.enums.is_empty() && data.enum_names.contains(&program.enums[0].node.name.node) {
+    // If the first enum is the prelude's first enum, assume prelude is already there
+    if program.enums.first().is_some_and(|e| e.node.name.node == data.enums[0].node.name.node) {
         return Ok(());
     }
 
+    let user_names: HashSet<String> = program.enums.iter().map(|e| e.node.name.node.clone())
+        .chain(program.classes.iter().map(|c| c.node.name.node.clone()))
+        .chain(program.traits.iter().map(|t| t.node.name.node.clone()))
+        .chain(program.errors.iter().map(|e| e.node.name.node.clone()))
+        .collect();
+    let shadowed = |name: &String| SHADOWABLE.contains(&name.as_str()) && user_names.contains(name);
+
     // Check for conflicts with prelude enums
     for prelude_name in &data.enum_names {
+        if SHADOWABLE.contains(&prelude_name.as_str()) {
+            continue;
+        }
         // Check enums
         for e in &program.enums {
             if &e.node.name.node == prelude_name {
@@ -120,6 +135,9 @@ pub fn inject_prelude(program: &mut Program) -> Result<(), CompileError> {
 
     // Check for conflicts with prelude classes
     for prelude_name in &data.class_names {
+        if SHADOWABLE.contains(&prelude_name.as_str()) {
+            continue;
+        }
         // Check enums
         for e in &program.enums {
             if &e.node.name.node == prelude_name {
@@ -172,6 +190,9 @@ pub fn inject_prelude(program: &mut Program) -> Result<(), CompileError> {
 
     // Check for conflicts with prelude traits
     for prelude_name in &data.trait_names {
+        if SHADOWABLE.contains(&prelude_name.as_str()) {
+            continue;
+        }
         // Check enums
         for e in &program.enums {
             if &e.node.name.node == prelude_name {
@@ -223,17 +244,17 @@ pub fn inject_prelude(program: &mut Program) -> Result<(), CompileError> {
     }
 
     // Prepend prelude enums to the program
-    let mut prelude_enums = data.enums.clone();
+    let mut prelude_enums: Vec<_> = data.enums.iter().filter(|e| !shadowed(&e.node.name.node)).cloned().collect();
     prelude_enums.append(&mut program.enums);
     program.enums = prelude_enums;
 
     // Prepend prelude classes to the program
-    let mut prelude_classes = data.classes.clone();
+    let mut prelude_classes: Vec<_> = data.classes.iter().filter(|c| !shadowed(&c.node.name.node)).cloned().collect();
     prelude_classes.append(&mut program.classes);
     program.classes = prelude_classes;
 
     // Prepend prelude traits to the program
-    let mut prelude_traits = data.traits.clone();
+    let mut prelude_traits: Vec<_> = data.traits.iter().filter(|t| !shadowed(&t.node.name.node)).cloned().collect();
     prelude_traits.append(&mut program.traits);
     program.traits = prelude_traits;
 
@@ -1,4 +1,5 @@
 use crate::parser::ast::*;
+use crate::span::{Span, Spanned};
 
 /// Pretty-print a `Program` AST back into valid Pluto source text.
 ///
@@ -6,10 +7,71 @@ use crate::parser::ast::*;
 /// top-level declaration for stable identification across text ↔ binary conversions.
 pub fn pretty_print(program: &Program, include_uuid_hints: bool) -> String {
     let mut pp = PrettyPrinter::new(include_uuid_hints);
+    pp.load_comments(&program.comments);
     pp.emit_program(program);
     pp.buf
 }
 
+/// Pretty-print a `Program`, but write the given text verbatim in place of any
+/// top-level declaration (error, trait, enum, class, function or app) whose
+/// UUID is a key of `verbatim`. Lets editors keep the author's formatting for
+/// declarations they did not touch. Uses no UUID hints.
+pub fn pretty_print_with_verbatim(
+    program: &Program,
+    verbatim: std::collections::HashMap<uuid::Uuid, String>,
+) -> String {
+    let mut pp = PrettyPrinter::new(false);
+    pp.verbatim = verbatim;
+    pp.load_comments(&program.comments);
+    pp.emit_program(program);
+    pp.buf
+}
+
+/// Give each top-level declaration the UUID from a `// @uuid: <uuid>` hint on
+/// the line directly above it, as `pretty_print` writes them. Returns whether
+/// the source had any hints, so a reformat can keep emitting them.
+pub fn restore_uuid_hints(program: &mut Program, source: &str) -> bool {
+    let mut hints: std::collections::HashMap<usize, uuid::Uuid> = std::collections::HashMap::new();
+    for (line_no, line) in source.lines().enumerate() {
+        if let Some(rest) = line.trim().strip_prefix("// @uuid:")
+            && let Ok(id) = uuid::Uuid::parse_str(rest.trim())
+        {
+            hints.insert(line_no, id);
+        }
+    }
+    if hints.is_empty() {
+        return false;
+    }
+
+    let hint_for = |start: usize| {
+        let line_no = source[..start.min(source.len())].matches('\n').count();
+        line_no.checked_sub(1).and_then(|above| hints.get(&above).copied())
+    };
+    macro_rules! restore {
+        ($items:expr) => {
+            for item in $items {
+                if let Some(id) = hint_for(item.span.start) {
+                    item.node.id = id;
+                }
+            }
+        };
+    }
+    restore!(&mut program.consts);
+    restore!(&mut program.globals);
+    restore!(&mut program.errors);
+    restore!(&mut program.traits);
+    restore!(&mut program.enums);
+    restore!(&mut program.classes);
+    restore!(&mut program.functions);
+    restore!(&mut program.stages);
+    if let Some(app) = &mut program.app
+        && let Some(id) = hint_for(app.span.start)
+    {
+        app.node.id = id;
+    }
+    true
+}
+
 /// Pretty-print a single function declaration.
 pub fn pretty_print_function(func: &Function, include_uuid_hints: bool) -> String {
     let mut pp = PrettyPrinter::new(include_uuid_hints);
@@ -62,6 +124,11 @@ struct PrettyPrinter {
     buf: String,
     indent: usize,
     include_uuid_hints: bool,
+    /// Original text to copy for top-level declarations, keyed by UUID.
+    verbatim: std::collections::HashMap<uuid::Uuid, String>,
+    /// Comments still to write, keyed by where they attach. Each is taken when
+    /// written; whatever is left goes at the end of the program.
+    comments: std::collections::HashMap<CommentAttach, Vec<(usize, String)>>,
 }
 
 impl PrettyPrinter {
@@ -70,6 +137,50 @@ impl PrettyPrinter {
             buf: String::new(),
             indent: 0,
             include_uuid_hints,
+            verbatim: std::collections::HashMap::new(),
+            comments: std::collections::HashMap::new(),
+        }
+    }
+
+    fn load_comments(&mut self, comments: &[Comment]) {
+        for c in comments {
+            self.comments.entry(c.attach).or_default().push((c.span.start, c.text.clone()));
+        }
+    }
+
+    /// Write the comments attached at `attach`, each on its own line.
+    fn emit_comment_lines(&mut self, attach: CommentAttach) {
+        for (_, text) in self.comments.remove(&attach).unwrap_or_default() {
+            self.write_indent();
+            self.write(&text);
+            self.newline();
+        }
+    }
+
+    fn emit_comments_before(&mut self, span: Span) {
+        self.emit_comment_lines(CommentAttach::Before(span));
+    }
+
+    fn emit_comments_inside(&mut self, span: Span) {
+        self.emit_comment_lines(CommentAttach::Inside(span));
+    }
+
+    /// Write the comments trailing the node at `span` on its last line.
+    fn emit_comments_after(&mut self, span: Span) {
+        for (_, text) in self.comments.remove(&CommentAttach::After(span)).unwrap_or_default() {
+            self.write(" ");
+            self.write(&text);
+        }
+    }
+
+    /// Write the verbatim text registered for `id`, if any.
+    fn emit_verbatim(&mut self, id: &uuid::Uuid) -> bool {
+        match self.verbatim.get(id) {
+            Some(text) => {
+                self.buf.push_str(text);
+                true
+            }
+            None => false,
         }
     }
 
@@ -121,7 +232,9 @@ impl PrettyPrinter {
         // 1. Imports
         if !program.imports.is_empty() {
             for imp in &program.imports {
+                self.emit_comments_before(imp.span);
                 self.emit_import(&imp.node);
+                self.emit_comments_after(imp.span);
                 self.newline();
             }
             has_output = true;
@@ -130,35 +243,81 @@ impl PrettyPrinter {
         // 2. Extern fn
         for ext in &program.extern_fns {
             sep!(self, has_output);
+            self.emit_comments_before(ext.span);
             self.emit_extern_fn(&ext.node);
+            self.emit_comments_after(ext.span);
             self.newline();
         }
 
+        // 3. Consts (grouped, no blank lines between them)
+        if !program.consts.is_empty() {
+            sep!(self, has_output);
+            for c in &program.consts {
+                self.emit_comments_before(c.span);
+                if !self.emit_verbatim(&c.node.id) {
+                    self.emit_uuid_hint(&c.node.id);
+                    self.emit_const_decl(&c.node);
+                }
+                self.emit_comments_after(c.span);
+                self.newline();
+            }
+        }
+
+        // 3b. Globals (grouped like consts)
+        if !program.globals.is_empty() {
+            sep!(self, has_output);
+            for g in &program.globals {
+                self.emit_comments_before(g.span);
+                if !self.emit_verbatim(&g.node.id) {
+                    self.emit_uuid_hint(&g.node.id);
+                    self.emit_global_decl(&g.node);
+                }
+                self.emit_comments_after(g.span);
+                self.newline();
+            }
+        }
+
         // 4. Errors
         for err in &program.errors {
             sep!(self, has_output);
-            self.emit_error_decl_with_hint(&err.node);
+            self.emit_comments_before(err.span);
+            if !self.emit_verbatim(&err.node.id) {
+                self.emit_error_decl_with_hint(&err.node);
+            }
+            self.emit_comments_after(err.span);
             self.newline();
         }
 
         // 5. Traits
         for tr in &program.traits {
             sep!(self, has_output);
-            self.emit_trait_decl_with_hint(&tr.node);
+            self.emit_comments_before(tr.span);
+            if !self.emit_verbatim(&tr.node.id) {
+                self.emit_trait_decl_with_hint(&tr.node);
+            }
+            self.emit_comments_after(tr.span);
             self.newline();
         }
 
         // 6. Enums
         for en in &program.enums {
             sep!(self, has_output);
-            self.emit_enum_decl_with_hint(&en.node);
+            self.emit_comments_before(en.span);
+            if !self.emit_verbatim(&en.node.id) {
+                self.emit_enum_decl_with_hint(&en.node);
+            }
+            self.emit_comments_after(en.span);
             self.newline();
         }
 
         // 7. Classes
         for cls in &program.classes {
             sep!(self, has_output);
-            self.emit_class_decl_with_hint(&cls.node);
+            self.emit_comments_before(cls.span);
+            if !self.emit_verbatim(&cls.node.id) {
+                self.emit_class_decl_with_hint(&cls.node);
+            }
+            self.emit_comments_after(cls.span);
             self.newline();
         }
 
@@ -174,35 +333,48 @@ impl PrettyPrinter {
                 continue;
             }
             sep!(self, has_output);
-            self.emit_function_with_hint(&func.node);
+            self.emit_comments_before(func.span);
+            if !self.emit_verbatim(&func.node.id) {
+                self.emit_function_with_hint(&func.node);
+            }
+            self.emit_comments_after(func.span);
             self.newline();
         }
 
         // 9. App
         if let Some(app) = &program.app {
             sep!(self, has_output);
-            self.emit_app_decl_with_hint(&app.node);
+            self.emit_comments_before(app.span);
+            if !self.emit_verbatim(&app.node.id) {
+                self.emit_app_decl_with_hint(&app.node);
+            }
+            self.emit_comments_after(app.span);
             self.newline();
         }
 
         // 9b. Stages
         for stage in &program.stages {
             sep!(self, has_output);
+            self.emit_comments_before(stage.span);
             self.emit_stage_decl_with_hint(&stage.node);
+            self.emit_comments_after(stage.span);
             self.newline();
         }
 
         // 10. Test blocks
         if let Some(tests_decl) = &program.tests {
             sep!(self, has_output);
+            self.emit_comments_before(tests_decl.span);
             self.write(&format!("tests[scheduler: {}]", tests_decl.node.strategy));
             self.write(" {");
             self.newline();
             self.indent();
             for test in &program.test_info {
                 if let Some(func) = program.functions.iter().find(|f| f.node.name.node == test.fn_name) {
+                    self.emit_comments_before(func.span);
                     self.write_indent();
                     self.emit_test_info(test, &func.node);
+                    self.emit_comments_after(func.span);
                     self.newline();
                 }
             }
@@ -213,12 +385,25 @@ impl PrettyPrinter {
             for test in &program.test_info {
                 if let Some(func) = program.functions.iter().find(|f| f.node.name.node == test.fn_name) {
                     sep!(self, has_output);
+                    self.emit_comments_before(func.span);
                     self.emit_test_info(test, &func.node);
+                    self.emit_comments_after(func.span);
                     self.newline();
                 }
             }
         }
 
+        // 11. Comments after the last declaration, and any whose node was not written
+        let mut rest: Vec<(usize, String)> = self.comments.drain().flat_map(|(_, texts)| texts).collect();
+        if !rest.is_empty() {
+            rest.sort();
+            sep!(self, has_output);
+            for (_, text) in rest {
+                self.write(&text);
+                self.newline();
+            }
+        }
+
         // Remove trailing newline if present
         while self.buf.ends_with('\n') {
             self.buf.pop();
@@ -232,17 +417,32 @@ impl PrettyPrinter {
         self.write("import ");
         let path: Vec<&str> = imp.path.iter().map(|s| s.node.as_str()).collect();
         self.write(&path.join("."));
+        if imp.glob {
+            self.write(".*");
+        }
+        if !imp.items.is_empty() {
+            let items: Vec<String> = imp.items.iter().map(|item| match &item.alias {
+                Some(alias) => format!("{} as {}", item.name.node, alias.node),
+                None => item.name.node.clone(),
+            }).collect();
+            self.write(&format!(".{{{}}}", items.join(", ")));
+        }
         if let Some(alias) = &imp.alias {
             self.write(" as ");
             self.write(&alias.node);
         }
     }
 
-    fn emit_extern_fn(&mut self, ext: &ExternFnDecl) {
-        if ext.is_pub {
-            self.write("pub ");
+    fn emit_visibility(&mut self, visibility: Visibility) {
+        if visibility != Visibility::Private {
+            self.write(&visibility.to_string());
+            self.write(" ");
         }
-        self.write("extern fn ");
+    }
+
+    fn emit_extern_fn(&mut self, ext: &ExternFnDecl) {
+        self.emit_visibility(ext.visibility);
+        self.write(if ext.c_abi { "extern \"C\" fn " } else { "extern fn " });
         self.write(&ext.name.node);
         self.write("(");
         self.emit_params(&ext.params);
@@ -256,9 +456,7 @@ impl PrettyPrinter {
     // ── Error ────────────────────────────────────────────────────────
 
     fn emit_error_decl(&mut self, err: &ErrorDecl) {
-        if err.is_pub {
-            self.write("pub ");
-        }
+        self.emit_visibility(err.visibility);
         self.write("error ");
         self.write(&err.name.node);
         self.write(" {");
@@ -275,6 +473,30 @@ impl PrettyPrinter {
         self.write("}");
     }
 
+    fn emit_const_decl(&mut self, c: &ConstDecl) {
+        self.emit_visibility(c.visibility);
+        self.write("const ");
+        self.write(&c.name.node);
+        if let Some(ty) = &c.ty {
+            self.write(": ");
+            self.emit_type_expr(&ty.node);
+        }
+        self.write(" = ");
+        self.emit_expr(&c.value.node, 0);
+    }
+
+    fn emit_global_decl(&mut self, g: &GlobalDecl) {
+        self.emit_visibility(g.visibility);
+        self.write("global mut ");
+        self.write(&g.name.node);
+        if let Some(ty) = &g.ty {
+            self.write(": ");
+            self.emit_type_expr(&ty.node);
+        }
+        self.write(" = ");
+        self.emit_expr(&g.value.node, 0);
+    }
+
     fn emit_error_decl_with_hint(&mut self, err: &ErrorDecl) {
         self.emit_uuid_hint(&err.id);
         self.emit_error_decl(err);
@@ -283,9 +505,7 @@ impl PrettyPrinter {
     // ── Trait ────────────────────────────────────────────────────────
 
     fn emit_trait_decl(&mut self, tr: &TraitDecl) {
-        if tr.is_pub {
-            self.write("pub ");
-        }
+        self.emit_visibility(tr.visibility);
         self.write("trait ");
         self.write(&tr.name.node);
         self.write(" {");
@@ -295,9 +515,12 @@ impl PrettyPrinter {
             if i > 0 {
                 self.newline();
             }
+            self.emit_comments_before(method.name.span);
             self.emit_trait_method(method);
+            self.emit_comments_after(method.name.span);
             self.newline();
         }
+        self.emit_comments_inside(tr.name.span);
         self.dedent();
         self.write("}");
     }
@@ -318,29 +541,47 @@ impl PrettyPrinter {
             self.write(" ");
             self.emit_type_expr(&ret.node);
         }
+        self.emit_declared_errors(&method.declared_errors);
         self.emit_contracts(&method.contracts);
         if let Some(body) = &method.body {
             self.write(" ");
-            self.emit_block(&body.node);
+            self.emit_block(body);
         }
     }
 
     // ── Enum ─────────────────────────────────────────────────────────
 
     fn emit_enum_decl(&mut self, en: &EnumDecl) {
-        if en.is_pub {
-            self.write("pub ");
-        }
+        self.emit_visibility(en.visibility);
         self.write("enum ");
         self.write(&en.name.node);
         self.emit_type_params(&en.type_params);
+        if !en.impl_traits.is_empty() {
+            self.write(" impl ");
+            for (i, t) in en.impl_traits.iter().enumerate() {
+                if i > 0 {
+                    self.write(", ");
+                }
+                self.write(&t.node);
+            }
+        }
         self.write(" {");
         self.newline();
         self.indent();
         for variant in &en.variants {
+            self.emit_comments_before(variant.name.span);
             self.write_indent();
             self.write(&variant.name.node);
-            if !variant.fields.is_empty() {
+            if is_positional_fields(variant.fields.iter().map(|f| f.name.node.as_str())) {
+                self.write("(");
+                for (i, field) in variant.fields.iter().enumerate() {
+                    if i > 0 {
+                        self.write(", ");
+                    }
+                    self.emit_type_expr(&field.ty.node);
+                }
+                self.write(")");
+            } else if !variant.fields.is_empty() {
                 self.write(" {");
                 self.newline();
                 self.indent();
@@ -355,12 +596,49 @@ impl PrettyPrinter {
                 self.write_indent();
                 self.write("}");
             }
+            self.emit_comments_after(variant.name.span);
             self.newline();
         }
+        for method in &en.methods {
+            self.newline();
+            self.emit_comments_before(method.span);
+            self.write_indent();
+            self.emit_function_header(&method.node);
+            self.write(" ");
+            self.emit_block(&method.node.body);
+            self.emit_comments_after(method.span);
+            self.newline();
+        }
+        self.emit_comments_inside(en.name.span);
         self.dedent();
         self.write("}");
     }
 
+    /// Bindings of a match arm: `(a, b)` for a tuple-style variant,
+    /// `{ field, field: rename }` otherwise.
+    fn emit_match_bindings(&mut self, bindings: &[(crate::span::Spanned<String>, Option<crate::span::Spanned<String>>)]) {
+        if bindings.is_empty() {
+            return;
+        }
+        let positional = is_positional_fields(bindings.iter().map(|(f, _)| f.node.as_str()));
+        self.write(if positional { "(" } else { " { " });
+        for (i, (field, rename)) in bindings.iter().enumerate() {
+            if i > 0 {
+                self.write(", ");
+            }
+            match rename {
+                Some(r) if positional => self.write(&r.node),
+                Some(r) => {
+                    self.write(&field.node);
+                    self.write(": ");
+                    self.write(&r.node);
+                }
+                None => self.write(&field.node),
+            }
+        }
+        self.write(if positional { ")" } else { " }" });
+    }
+
     fn emit_enum_decl_with_hint(&mut self, en: &EnumDecl) {
         self.emit_uuid_hint(&en.id);
         self.emit_enum_decl(en);
@@ -369,9 +647,7 @@ impl PrettyPrinter {
     // ── Class ────────────────────────────────────────────────────────
 
     fn emit_class_decl(&mut self, cls: &ClassDecl) {
-        if cls.is_pub {
-            self.write("pub ");
-        }
+        self.emit_visibility(cls.visibility);
         match cls.lifecycle {
             Lifecycle::Scoped => self.write("scoped "),
             Lifecycle::Transient => self.write("transient "),
@@ -405,6 +681,24 @@ impl PrettyPrinter {
                 }
                 self.write(&t.node);
             }
+            if !cls.where_bounds.is_empty() {
+                self.write(" where ");
+                let mut params: Vec<&String> = cls.where_bounds.keys().collect();
+                params.sort_by_key(|p| cls.type_params.iter().position(|tp| &tp.node == *p));
+                for (i, param) in params.into_iter().enumerate() {
+                    if i > 0 {
+                        self.write(", ");
+                    }
+                    self.write(param);
+                    self.write(": ");
+                    for (j, b) in cls.where_bounds[param].iter().enumerate() {
+                        if j > 0 {
+                            self.write(" + ");
+                        }
+                        self.write(&b.node);
+                    }
+                }
+            }
         }
 
         // uses traits
@@ -425,10 +719,12 @@ impl PrettyPrinter {
         // Non-injected fields
         let regular_fields: Vec<&Field> = cls.fields.iter().filter(|f| !f.is_injected).collect();
         for field in &regular_fields {
+            self.emit_comments_before(field.span());
             self.write_indent();
             self.write(&field.name.node);
             self.write(": ");
             self.emit_type_expr(&field.ty.node);
+            self.emit_comments_after(field.span());
             self.newline();
         }
 
@@ -437,9 +733,11 @@ impl PrettyPrinter {
             if !regular_fields.is_empty() {
                 self.newline();
             }
+            self.emit_comments_before(inv.span);
             self.write_indent();
             self.write("invariant ");
             self.emit_expr(&inv.node.expr.node, 0);
+            self.emit_comments_after(inv.span);
             self.newline();
         }
 
@@ -453,13 +751,16 @@ impl PrettyPrinter {
             if i > 0 {
                 self.newline();
             }
+            self.emit_comments_before(method.span);
             self.write_indent();
             self.emit_function_header(&method.node);
             self.write(" ");
-            self.emit_block(&method.node.body.node);
+            self.emit_block(&method.node.body);
+            self.emit_comments_after(method.span);
             self.newline();
         }
 
+        self.emit_comments_inside(cls.name.span);
         self.dedent();
         self.write("}");
     }
@@ -474,7 +775,7 @@ impl PrettyPrinter {
     fn emit_function(&mut self, func: &Function) {
         self.emit_function_header(func);
         self.write(" ");
-        self.emit_block(&func.body.node);
+        self.emit_block(&func.body);
     }
 
     fn emit_function_with_hint(&mut self, func: &Function) {
@@ -483,9 +784,20 @@ impl PrettyPrinter {
     }
 
     fn emit_function_header(&mut self, func: &Function) {
-        if func.is_pub {
-            self.write("pub ");
+        self.emit_no_coverage(func);
+        self.emit_function_signature(func);
+    }
+
+    /// `@no_coverage` goes on its own line above the function, at the current indent.
+    fn emit_no_coverage(&mut self, func: &Function) {
+        if func.no_coverage {
+            self.write("@no_coverage\n");
+            self.write_indent();
         }
+    }
+
+    fn emit_function_signature(&mut self, func: &Function) {
+        self.emit_visibility(func.visibility);
         self.write("fn ");
         self.write(&func.name.node);
         self.emit_type_params(&func.type_params);
@@ -496,9 +808,17 @@ impl PrettyPrinter {
             self.write(" ");
             self.emit_type_expr(&ret.node);
         }
+        self.emit_declared_errors(&func.declared_errors);
         self.emit_contracts(&func.contracts);
     }
 
+    fn emit_declared_errors(&mut self, errors: &Option<Vec<crate::span::Spanned<String>>>) {
+        for err in errors.iter().flatten() {
+            self.write(" | ");
+            self.write(&err.node);
+        }
+    }
+
     // ── Contracts ────────────────────────────────────────────────────
 
     fn emit_contracts(&mut self, contracts: &[crate::span::Spanned<ContractClause>]) {
@@ -567,13 +887,16 @@ impl PrettyPrinter {
             if i > 0 {
                 self.newline();
             }
+            self.emit_comments_before(method.span);
             self.write_indent();
             self.emit_function_header(&method.node);
             self.write(" ");
-            self.emit_block(&method.node.body.node);
+            self.emit_block(&method.node.body);
+            self.emit_comments_after(method.span);
             self.newline();
         }
 
+        self.emit_comments_inside(app.name.span);
         self.dedent();
         self.write("}");
     }
@@ -643,10 +966,9 @@ impl PrettyPrinter {
             if i > 0 {
                 self.newline();
             }
+            self.emit_comments_before(req.span);
             self.write_indent();
-            if req.node.is_pub {
-                self.write("pub ");
-            }
+            self.emit_visibility(req.node.visibility);
             self.write("requires fn ");
             self.write(&req.node.name.node);
             self.write("(");
@@ -656,6 +978,7 @@ impl PrettyPrinter {
                 self.write(" ");
                 self.emit_type_expr(&ret.node);
             }
+            self.emit_comments_after(req.span);
             self.newline();
         }
 
@@ -668,16 +991,20 @@ impl PrettyPrinter {
             if i > 0 {
                 self.newline();
             }
+            self.emit_comments_before(method.span);
             self.write_indent();
+            self.emit_no_coverage(&method.node);
             if method.node.is_override {
                 self.write("override ");
             }
-            self.emit_function_header(&method.node);
+            self.emit_function_signature(&method.node);
             self.write(" ");
-            self.emit_block(&method.node.body.node);
+            self.emit_block(&method.node.body);
+            self.emit_comments_after(method.span);
             self.newline();
         }
 
+        self.emit_comments_inside(stage.name.span);
         self.dedent();
         self.write("}");
     }
@@ -693,7 +1020,7 @@ impl PrettyPrinter {
         self.write("test \"");
         self.write(&escape_string(&test.display_name));
         self.write("\" ");
-        self.emit_block(&func.body.node);
+        self.emit_block(&func.body);
     }
 
     // ── Type expressions ─────────────────────────────────────────────
@@ -784,15 +1111,18 @@ impl PrettyPrinter {
 
     // ── Block ────────────────────────────────────────────────────────
 
-    fn emit_block(&mut self, block: &Block) {
+    fn emit_block(&mut self, block: &Spanned<Block>) {
         self.write("{");
         self.newline();
         self.indent();
-        for stmt in &block.stmts {
+        for stmt in &block.node.stmts {
+            self.emit_comments_before(stmt.span);
             self.write_indent();
             self.emit_stmt(&stmt.node);
+            self.emit_comments_after(stmt.span);
             self.newline();
         }
+        self.emit_comments_inside(block.span);
         self.dedent();
         self.write_indent();
         self.write("}");
@@ -800,6 +1130,22 @@ impl PrettyPrinter {
 
     // ── Statements ───────────────────────────────────────────────────
 
+    fn emit_int_pattern(&mut self, pat: &IntPattern) {
+        match pat {
+            IntPattern::Value(e) => self.emit_expr(&e.node, 0),
+            IntPattern::Range { start, end, inclusive } => {
+                if let Some(start) = start {
+                    self.emit_expr(&start.node, 0);
+                }
+                self.write(if *inclusive { "..=" } else { ".." });
+                if let Some(end) = end {
+                    self.emit_expr(&end.node, 0);
+                }
+            }
+            IntPattern::Wildcard => self.write("_"),
+        }
+    }
+
     fn emit_stmt(&mut self, stmt: &Stmt) {
         match stmt {
             Stmt::Let { name, ty, value, is_mut } => {
@@ -816,6 +1162,25 @@ impl PrettyPrinter {
                 self.write(" = ");
                 self.emit_expr(&value.node, 0);
             }
+            Stmt::LetStruct { ty, fields, value, is_mut } => {
+                if *is_mut {
+                    self.write("let mut ");
+                } else {
+                    self.write("let ");
+                }
+                self.emit_type_expr(&ty.node);
+                self.write(" {");
+                for (i, (field, rename)) in fields.iter().enumerate() {
+                    self.write(if i > 0 { ", " } else { " " });
+                    self.write(&field.node);
+                    if let Some(r) = rename {
+                        self.write(": ");
+                        self.write(&r.node);
+                    }
+                }
+                self.write(if fields.is_empty() { "} = " } else { " } = " });
+                self.emit_expr(&value.node, 0);
+            }
             Stmt::Return(expr) => {
                 self.write("return");
                 if let Some(e) = expr {
@@ -847,24 +1212,47 @@ impl PrettyPrinter {
                 self.write("if ");
                 self.emit_expr(&condition.node, 0);
                 self.write(" ");
-                self.emit_block(&then_block.node);
+                self.emit_block(then_block);
                 if let Some(else_blk) = else_block {
                     self.write(" else ");
                     // Check if this is an else-if (single stmt that is an If)
                     if else_blk.node.stmts.len() == 1
-                        && let Stmt::If { .. } = &else_blk.node.stmts[0].node
+                        && let Stmt::If { .. } | Stmt::IfLet { .. } = &else_blk.node.stmts[0].node
+                    {
+                        self.emit_stmt(&else_blk.node.stmts[0].node);
+                        return;
+                    }
+                    self.emit_block(else_blk);
+                }
+            }
+            Stmt::IfLet {
+                binding,
+                value,
+                then_block,
+                else_block,
+            } => {
+                self.write("if let ");
+                self.write(&binding.node);
+                self.write(" = ");
+                self.emit_expr(&value.node, 0);
+                self.write(" ");
+                self.emit_block(then_block);
+                if let Some(else_blk) = else_block {
+                    self.write(" else ");
+                    if else_blk.node.stmts.len() == 1
+                        && let Stmt::If { .. } | Stmt::IfLet { .. } = &else_blk.node.stmts[0].node
                     {
                         self.emit_stmt(&else_blk.node.stmts[0].node);
                         return;
                     }
-                    self.emit_block(&else_blk.node);
+                    self.emit_block(else_blk);
                 }
             }
             Stmt::While { condition, body } => {
                 self.write("while ");
                 self.emit_expr(&condition.node, 0);
                 self.write(" ");
-                self.emit_block(&body.node);
+                self.emit_block(body);
             }
             Stmt::For {
                 var,
@@ -876,7 +1264,7 @@ impl PrettyPrinter {
                 self.write(" in ");
                 self.emit_expr(&iterable.node, 0);
                 self.write(" ");
-                self.emit_block(&body.node);
+                self.emit_block(body);
             }
             Stmt::IndexAssign {
                 object,
@@ -890,6 +1278,52 @@ impl PrettyPrinter {
                 self.write(" = ");
                 self.emit_expr(&value.node, 0);
             }
+            Stmt::IntMatch { expr, arms } => {
+                self.write("match ");
+                self.emit_expr(&expr.node, 0);
+                self.write(" {");
+                self.newline();
+                self.indent();
+                for arm in arms {
+                    self.write_indent();
+                    for (i, pat) in arm.patterns.iter().enumerate() {
+                        if i > 0 {
+                            self.write(" | ");
+                        }
+                        self.emit_int_pattern(&pat.node);
+                    }
+                    self.write(" ");
+                    self.emit_block(&arm.body);
+                    self.newline();
+                }
+                self.dedent();
+                self.write_indent();
+                self.write("}");
+            }
+            Stmt::NullableMatch { expr, some_arm, none_arm } => {
+                self.write("match ");
+                self.emit_expr(&expr.node, 0);
+                self.write(" {");
+                self.newline();
+                self.indent();
+                if let Some(arm) = some_arm {
+                    self.write_indent();
+                    self.write("some(");
+                    self.write(&arm.binding.node);
+                    self.write(") ");
+                    self.emit_block(&arm.body);
+                    self.newline();
+                }
+                if let Some(nb) = none_arm {
+                    self.write_indent();
+                    self.write("none ");
+                    self.emit_block(nb);
+                    self.newline();
+                }
+                self.dedent();
+                self.write_indent();
+                self.write("}");
+            }
             Stmt::Match { expr, arms } => {
                 self.write("match ");
                 self.emit_expr(&expr.node, 0);
@@ -911,22 +1345,9 @@ impl PrettyPrinter {
                     }
                     self.write(".");
                     self.write(&arm.variant_name.node);
-                    if !arm.bindings.is_empty() {
-                        self.write(" { ");
-                        for (i, (field_name, rename)) in arm.bindings.iter().enumerate() {
-                            if i > 0 {
-                                self.write(", ");
-                            }
-                            self.write(&field_name.node);
-                            if let Some(rename) = rename {
-                                self.write(": ");
-                                self.write(&rename.node);
-                            }
-                        }
-                        self.write(" }");
-                    }
+                    self.emit_match_bindings(&arm.bindings);
                     self.write(" ");
-                    self.emit_block(&arm.body.node);
+                    self.emit_block(&arm.body);
                     self.newline();
                 }
                 self.dedent();
@@ -993,13 +1414,13 @@ impl PrettyPrinter {
                             self.write(") ");
                         }
                     }
-                    self.emit_block(&arm.body.node);
+                    self.emit_block(&arm.body);
                     self.newline();
                 }
                 if let Some(def) = default {
                     self.write_indent();
                     self.write("default ");
-                    self.emit_block(&def.node);
+                    self.emit_block(def);
                     self.newline();
                 }
                 self.dedent();
@@ -1024,7 +1445,19 @@ impl PrettyPrinter {
                     self.emit_type_expr(&binding.ty.node);
                 }
                 self.write("| ");
-                self.emit_block(&body.node);
+                self.emit_block(body);
+            }
+            Stmt::Try { body, var, handler, finally } => {
+                self.write("try ");
+                self.emit_block(body);
+                self.write(" catch ");
+                self.write(&var.node);
+                self.write(" ");
+                self.emit_block(handler);
+                if let Some(finally) = finally {
+                    self.write(" finally ");
+                    self.emit_block(finally);
+                }
             }
             Stmt::Yield { value } => {
                 self.write("yield ");
@@ -1215,16 +1648,27 @@ impl PrettyPrinter {
                 }
                 self.write(".");
                 self.write(&variant.node);
-                self.write(" { ");
-                for (i, (fname, fval)) in fields.iter().enumerate() {
-                    if i > 0 {
-                        self.write(", ");
+                if is_positional_fields(fields.iter().map(|(f, _)| f.node.as_str())) {
+                    self.write("(");
+                    for (i, (_, fval)) in fields.iter().enumerate() {
+                        if i > 0 {
+                            self.write(", ");
+                        }
+                        self.emit_expr(&fval.node, 0);
                     }
-                    self.write(&fname.node);
-                    self.write(": ");
-                    self.emit_expr(&fval.node, 0);
+                    self.write(")");
+                } else {
+                    self.write(" { ");
+                    for (i, (fname, fval)) in fields.iter().enumerate() {
+                        if i > 0 {
+                            self.write(", ");
+                        }
+                        self.write(&fname.node);
+                        self.write(": ");
+                        self.emit_expr(&fval.node, 0);
+                    }
+                    self.write(" }");
                 }
-                self.write(" }");
             }
             Expr::StringInterp { parts } => {
                 self.write("f\"");
@@ -1262,7 +1706,7 @@ impl PrettyPrinter {
                     self.emit_expr(&ret_expr.node, 0);
                     return;
                 }
-                self.emit_block(&body.node);
+                self.emit_block(body);
             }
             Expr::MapLit {
                 key_type,
@@ -1318,6 +1762,12 @@ impl PrettyPrinter {
                 self.emit_expr(&expr.node, 25);
                 self.write("!");
             }
+            Expr::PropagateMap { expr, mapper } => {
+                self.emit_expr(&expr.node, 25);
+                self.write("! map_err(");
+                self.emit_expr(&mapper.node, 0);
+                self.write(")");
+            }
             Expr::Catch { expr, handlers } => {
                 self.emit_expr(&expr.node, 25);
                 for handler in handlers {
@@ -1330,7 +1780,7 @@ impl PrettyPrinter {
                         self.write(" catch ");
                         self.write(&var.node);
                         self.write(" ");
-                        self.emit_block(&body.node);
+                        self.emit_block(body);
                     }
                     CatchHandler::Typed { var, error_type, body } => {
                         self.write(" catch ");
@@ -1338,7 +1788,7 @@ impl PrettyPrinter {
                         self.write(": ");
                         self.write(&error_type.node);
                         self.write(" ");
-                        self.emit_block(&body.node);
+                        self.emit_block(body);
                     }
                   }
                 }
@@ -1348,6 +1798,21 @@ impl PrettyPrinter {
                 self.write(" as ");
                 self.emit_type_expr(&target_type.node);
             }
+            Expr::Is { expr, enum_name, variant, .. } => {
+                // Same precedence as the comparison operators
+                let need_parens = 13 < parent_prec;
+                if need_parens {
+                    self.write("(");
+                }
+                self.emit_expr(&expr.node, 14);
+                self.write(" is ");
+                self.write(&enum_name.node);
+                self.write(".");
+                self.write(&variant.node);
+                if need_parens {
+                    self.write(")");
+                }
+            }
             Expr::Range {
                 start,
                 end,
@@ -1380,6 +1845,11 @@ impl PrettyPrinter {
                 self.emit_expr(&expr.node, 25);
                 self.write("?");
             }
+            Expr::MethodRef { type_name, method } => {
+                self.write(&type_name.node);
+                self.write("::");
+                self.write(&method.node);
+            }
             Expr::StaticTraitCall { trait_name, method_name, type_args, args } => {
                 self.write(&trait_name.node);
                 self.write("::");
@@ -1404,9 +1874,9 @@ impl PrettyPrinter {
                 self.write("if ");
                 self.emit_expr(&condition.node, 0);
                 self.write(" ");
-                self.emit_block(&then_block.node);
+                self.emit_block(then_block);
                 self.write(" else ");
-                self.emit_block(&else_block.node);
+                self.emit_block(else_block);
             }
             Expr::Match { expr, arms } => {
                 self.write("match ");
@@ -1418,20 +1888,7 @@ impl PrettyPrinter {
                     self.write(&arm.enum_name.node);
                     self.write(".");
                     self.write(&arm.variant_name.node);
-                    if !arm.bindings.is_empty() {
-                        self.write(" { ");
-                        for (i, (field, rename)) in arm.bindings.iter().enumerate() {
-                            if i > 0 {
-                                self.write(", ");
-                            }
-                            self.write(&field.node);
-                            if let Some(r) = rename {
-                                self.write(": ");
-                                self.write(&r.node);
-                            }
-                        }
-                        self.write(" }");
-                    }
+                    self.emit_match_bindings(&arm.bindings);
                     self.write(" => ");
                     self.emit_expr(&arm.value.node, 0);
                 }
@@ -1563,6 +2020,14 @@ mod tests {
         assert_roundtrip_stable(src);
     }
 
+    #[test]
+    fn test_function_with_declared_errors() {
+        let src = "error ParseError {}\n\nerror Empty {}\n\nfn parse(s: string) int | ParseError | Empty {\n    return 0\n}\n";
+        let result = pp(src);
+        assert!(result.contains("fn parse(s: string) int | ParseError | Empty {"));
+        assert_roundtrip_stable(src);
+    }
+
     #[test]
     fn test_pub_function() {
         let src = "pub fn greet(name: string) string {\n    return name\n}\n";
@@ -1571,6 +2036,15 @@ mod tests {
         assert_roundtrip_stable(src);
     }
 
+    #[test]
+    fn test_pub_module_declarations() {
+        let src = "pub(module) class Point {\n    x: int\n}\n\npub(module) fn origin() Point {\n    return Point { x: 0 }\n}\n";
+        let result = pp(src);
+        assert!(result.starts_with("pub(module) class Point"));
+        assert!(result.contains("pub(module) fn origin"));
+        assert_roundtrip_stable(src);
+    }
+
     // ── Let / Assign ─────────────────────────────────────────────────
 
     #[test]
@@ -1589,6 +2063,23 @@ mod tests {
         assert_roundtrip_stable(src);
     }
 
+    #[test]
+    fn test_let_struct_destructuring() {
+        let src = "fn main() {\n    let mut Point { x, y: py } = p\n}\n";
+        let result = pp(src);
+        assert!(result.contains("let mut Point { x, y: py } = p"));
+        assert_roundtrip_stable(src);
+    }
+
+    #[test]
+    fn test_try_catch_finally() {
+        let src = "fn main() {\n    try {\n        f()!\n    } catch e {\n        print(1)\n    } finally {\n        print(2)\n    }\n}\n";
+        let result = pp(src);
+        assert!(result.contains("} catch e {"));
+        assert!(result.contains("} finally {"));
+        assert_roundtrip_stable(src);
+    }
+
     #[test]
     fn test_assign() {
         let src = "fn main() {\n    let x = 1\n    x = 2\n}\n";
@@ -1599,6 +2090,25 @@ mod tests {
 
     // ── Control flow ─────────────────────────────────────────────────
 
+    #[test]
+    fn test_if_let_else() {
+        let src = "fn main() {\n    let m: int? = 1\n    if let x = m {\n        print(x)\n    } else if let y = m {\n        print(y)\n    }\n}\n";
+        let result = pp(src);
+        assert!(result.contains("if let x = m {"));
+        assert!(result.contains("} else if let y = m {"));
+        assert_roundtrip_stable(src);
+    }
+
+    #[test]
+    fn test_int_match() {
+        let src = "fn main() {\n    match 3 {\n        0 | -1 {\n            print(0)\n        }\n        1..=9 {\n            print(1)\n        }\n        _ {\n            print(2)\n        }\n    }\n}\n";
+        let result = pp(src);
+        assert!(result.contains("0 | -1 {"));
+        assert!(result.contains("1..=9 {"));
+        assert!(result.contains("_ {"));
+        assert_roundtrip_stable(src);
+    }
+
     #[test]
     fn test_if_else() {
         let src = "fn main() {\n    if true {\n        return\n    } else {\n        return\n    }\n}\n";
@@ -1777,6 +2287,14 @@ fn main() {
         assert_roundtrip_stable(src);
     }
 
+    #[test]
+    fn test_class_impl_where_clause() {
+        let src = "class Pair<A, B> impl Greetable where A: Greetable, B: Greetable + Named {\n    a: A\n    b: B\n}\n";
+        let result = pp(src);
+        assert_eq!(result, src);
+        assert_roundtrip_stable(src);
+    }
+
     #[test]
     fn test_trait_abstract_and_default() {
         let src = r#"trait Animal {
@@ -1814,6 +2332,25 @@ fn main() {
         assert_roundtrip_stable(src);
     }
 
+    #[test]
+    fn test_enum_with_methods() {
+        let src = r#"enum Shape impl Eq {
+    Circle(float)
+    Square(float)
+
+    fn area(self) float {
+        return 0.0
+    }
+}
+
+fn main() {
+}
+"#;
+        let result = pp(src);
+        assert_eq!(result, src);
+        assert_roundtrip_stable(src);
+    }
+
     #[test]
     fn test_error_decl() {
         let src = r#"error NotFound {
@@ -1907,6 +2444,14 @@ fn main() {
         assert_roundtrip_stable(src);
     }
 
+    #[test]
+    fn test_is() {
+        let src = "enum Color {\n    Red\n    Blue\n}\n\nfn main() {\n    let c = Color.Red\n    let ok = !(c is Color.Blue) && c is Color.Red\n}\n";
+        let result = pp(src);
+        assert!(result.contains("!(c is Color.Blue) && c is Color.Red"));
+        assert_roundtrip_stable(src);
+    }
+
     #[test]
     fn test_range() {
         let src = "fn main() {\n    for i in 0..10 {\n        return\n    }\n}\n";
@@ -2123,6 +2668,22 @@ fn main() {
         assert_roundtrip_stable(src);
     }
 
+    #[test]
+    fn test_selective_import() {
+        let src = "import math.{sqrt, pow as power}\n\nfn main() {\n}\n";
+        let result = pp(src);
+        assert!(result.contains("import math.{sqrt, pow as power}"));
+        assert_roundtrip_stable(src);
+    }
+
+    #[test]
+    fn test_glob_import() {
+        let src = "import handlers.* as h\n\nfn main() {\n}\n";
+        let result = pp(src);
+        assert!(result.contains("import handlers.* as h"));
+        assert_roundtrip_stable(src);
+    }
+
     #[test]
     fn test_extern_fn() {
         let src = "extern fn sleep(ms: int)\n\nfn main() {\n}\n";
@@ -2131,6 +2692,14 @@ fn main() {
         assert_roundtrip_stable(src);
     }
 
+    #[test]
+    fn test_extern_c_fn() {
+        let src = "extern \"C\" fn labs(x: int) int\n\nfn main() {\n}\n";
+        let result = pp(src);
+        assert!(result.contains("extern \"C\" fn labs(x: int) int"), "{result}");
+        assert_roundtrip_stable(src);
+    }
+
     #[test]
     fn test_app_with_ambient() {
         let src = r#"class Logger {
@@ -2324,6 +2893,57 @@ fn main() {
         assert_roundtrip_stable(src);
     }
 
+    #[test]
+    fn test_no_coverage_roundtrip() {
+        let src = "class Debug {\n    n: int\n\n    @no_coverage\n    fn dump(self) {\n        print(self.n)\n    }\n}\n\n@no_coverage\npub fn trace() {\n}\n";
+        let result = pp(src);
+        assert!(result.contains("    @no_coverage\n    fn dump(self) {"), "{result}");
+        assert!(result.contains("@no_coverage\npub fn trace() {"), "{result}");
+        assert_roundtrip_stable(src);
+    }
+
+    #[test]
+    fn test_const_roundtrip() {
+        let src = "pub const MAX: int = 10 * 2\nconst NAME = \"x\"\n\nfn main() {\n    print(MAX)\n}\n";
+        let result = pp(src);
+        assert!(result.starts_with("pub const MAX: int = 10 * 2\nconst NAME = \"x\"\n\nfn main()"), "{result}");
+        assert_roundtrip_stable(src);
+    }
+
+    #[test]
+    fn test_global_roundtrip() {
+        let src = "const START = 1\n\nglobal mut COUNT: int = START\npub global mut RATE = 0.5\n\nfn main() {\n    COUNT = COUNT + 1\n}\n";
+        let result = pp(src);
+        assert!(result.starts_with("const START = 1\n\nglobal mut COUNT: int = START\npub global mut RATE = 0.5\n\nfn main()"), "{result}");
+        assert_roundtrip_stable(src);
+    }
+
+    #[test]
+    fn test_tuple_variant_roundtrip() {
+        let src = r#"enum Shape {
+    Circle(float)
+    Rect(float, float)
+}
+
+fn main() {
+    let s = Shape.Rect(1.0, 2.0)
+    match s {
+        Shape.Circle(r) {
+            return
+        }
+        Shape.Rect(w, h) {
+            return
+        }
+    }
+}
+"#;
+        let result = pp(src);
+        assert!(result.contains("    Rect(float, float)\n"));
+        assert!(result.contains("Shape.Rect(1.0, 2.0)"));
+        assert!(result.contains("Shape.Rect(w, h) {"));
+        assert_roundtrip_stable(src);
+    }
+
     #[test]
     fn test_match_with_renames() {
         let src = r#"enum Shape {
@@ -1,6 +1,6 @@
 /// Reflection intrinsics - generates TypeInfo implementations for all types
 use crate::diagnostics::CompileError;
-use crate::parser::ast::{Block, Expr, Function, Program, Stmt, TypeExpr};
+use crate::parser::ast::{Block, Expr, Function, Program, Stmt, TypeExpr, Visibility};
 use crate::span::{Span, Spanned};
 use crate::typeck::env::TypeEnv;
 use std::collections::HashMap;
@@ -71,9 +71,11 @@ fn generate_type_name_impl(type_name: &str) -> Result<Spanned<Function>, Compile
         }),
         contracts: vec![],
         body,
-        is_pub: false,
+        visibility: Visibility::Private,
         is_override: false,
         is_generator: false,
+        declared_errors: None,
+        no_coverage: false,
     };
 
     Ok(Spanned {
@@ -192,9 +194,11 @@ fn generate_kind_impl_for_class(class_name: &str, env: &TypeEnv) -> Result<Spann
         }),
         contracts: vec![],
         body,
-        is_pub: false,
+        visibility: Visibility::Private,
         is_override: false,
         is_generator: false,
+        declared_errors: None,
+        no_coverage: false,
     };
 
     Ok(Spanned {
@@ -341,9 +345,11 @@ fn generate_kind_impl_for_enum(enum_name: &str, env: &TypeEnv) -> Result<Spanned
         }),
         contracts: vec![],
         body,
-        is_pub: false,
+        visibility: Visibility::Private,
         is_override: false,
         is_generator: false,
+        declared_errors: None,
+        no_coverage: false,
     };
 
     Ok(Spanned {
@@ -0,0 +1,203 @@
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::diagnostics::CompileError;
+use crate::{link_from_config, GcBackend, LinkConfig};
+
+static SESSIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// What a single REPL input produced.
+#[derive(Debug)]
+pub enum ReplOutcome {
+    /// The input declared top-level items, which now persist across inputs.
+    Declared(Vec<String>),
+    /// The input ran inside a synthetic `main`.
+    Ran { stdout: String, stderr: String, exit_code: i32 },
+}
+
+/// One top-level snippet the session has kept, with the names it declares.
+struct Declaration {
+    names: Vec<String>,
+    source: String,
+}
+
+/// An interactive session. Declarations accumulate; every other input is compiled
+/// into a synthetic `main` appended to them, linked, and run.
+pub struct Repl {
+    declarations: Vec<Declaration>,
+    gc: GcBackend,
+    dir: PathBuf,
+    runs: usize,
+}
+
+impl Repl {
+    pub fn new(gc: GcBackend) -> Result<Self, CompileError> {
+        let session = SESSIONS.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("pluto_repl_{}_{session}", std::process::id()));
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| CompileError::codegen(format!("failed to create REPL directory: {e}")))?;
+        Ok(Self { declarations: Vec::new(), gc, dir, runs: 0 })
+    }
+
+    /// Evaluate one complete input. Errors leave the session unchanged.
+    pub fn eval(&mut self, input: &str) -> Result<ReplOutcome, CompileError> {
+        if let Ok(program) = crate::parse_source(input) {
+            let names = declared_names(&program);
+            if !names.is_empty() {
+                return self.declare(input, names);
+            }
+        }
+
+        // An expression of a printable type echoes its value; anything else runs as statements.
+        let echoed = format!("fn main() {{\n    print({})\n}}\n", input.trim());
+        let object = match crate::compile_to_object(&self.source_with(&echoed)) {
+            Ok(object) => object,
+            Err(_) => crate::compile_to_object(&self.source_with(&format!("fn main() {{\n{input}\n}}\n")))?,
+        };
+        self.run(&object)
+    }
+
+    fn declare(&mut self, input: &str, names: Vec<String>) -> Result<ReplOutcome, CompileError> {
+        if names.iter().any(|n| n == "main") {
+            return Err(CompileError::codegen("cannot declare 'main' in the REPL; it is generated for each input".to_string()));
+        }
+        // A redeclared name replaces the snippet that declared it before.
+        let kept: Vec<&Declaration> = self
+            .declarations
+            .iter()
+            .filter(|d| !d.names.iter().any(|n| names.contains(n)))
+            .collect();
+        let mut source = String::new();
+        for decl in &kept {
+            source.push_str(&decl.source);
+            source.push('\n');
+        }
+        source.push_str(input);
+        source.push_str("\nfn main() {\n}\n");
+        crate::compile_to_object(&source)?;
+
+        self.declarations.retain(|d| !d.names.iter().any(|n| names.contains(n)));
+        self.declarations.push(Declaration { names: names.clone(), source: input.to_string() });
+        Ok(ReplOutcome::Declared(names))
+    }
+
+    fn source_with(&self, main: &str) -> String {
+        let mut source = String::new();
+        for decl in &self.declarations {
+            source.push_str(&decl.source);
+            source.push('\n');
+        }
+        source.push_str(main);
+        source
+    }
+
+    fn run(&mut self, object: &[u8]) -> Result<ReplOutcome, CompileError> {
+        self.runs += 1;
+        let obj_path = self.dir.join(format!("input{}.o", self.runs));
+        let bin_path = self.dir.join(format!("input{}", self.runs));
+        std::fs::write(&obj_path, object)
+            .map_err(|e| CompileError::codegen(format!("failed to write object file: {e}")))?;
+        let config = LinkConfig::default_config(&obj_path, self.gc)?;
+        link_from_config(&config, &bin_path)?;
+        let _ = std::fs::remove_file(&obj_path);
+
+        let output = Command::new(&bin_path)
+            .output()
+            .map_err(|e| CompileError::codegen(format!("failed to run input: {e}")))?;
+        let _ = std::fs::remove_file(&bin_path);
+        Ok(ReplOutcome::Ran {
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            exit_code: output.status.code().unwrap_or(-1),
+        })
+    }
+}
+
+impl Drop for Repl {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Names of the top-level items a parsed input declares.
+fn declared_names(program: &crate::parser::ast::Program) -> Vec<String> {
+    let mut names = Vec::new();
+    names.extend(program.functions.iter().map(|f| f.node.name.node.clone()));
+    names.extend(program.extern_fns.iter().map(|f| f.node.name.node.clone()));
+    names.extend(program.classes.iter().map(|c| c.node.name.node.clone()));
+    names.extend(program.traits.iter().map(|t| t.node.name.node.clone()));
+    names.extend(program.enums.iter().map(|e| e.node.name.node.clone()));
+    names.extend(program.consts.iter().map(|c| c.node.name.node.clone()));
+    names.extend(program.errors.iter().map(|e| e.node.name.node.clone()));
+    names
+}
+
+/// Whether `input` still has unclosed braces, brackets or parentheses, so the
+/// REPL should keep reading lines before evaluating it.
+pub fn is_incomplete(input: &str) -> bool {
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in input.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => depth -= 1,
+            _ => {}
+        }
+    }
+    depth > 0
+}
+
+/// Run an interactive session on stdin/stdout until EOF or `:quit`.
+pub fn run_repl(gc: GcBackend) -> Result<(), CompileError> {
+    let mut repl = Repl::new(gc)?;
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut buffer = String::new();
+
+    loop {
+        print!("{}", if buffer.is_empty() { "pluto> " } else { "...... " });
+        let _ = std::io::stdout().flush();
+
+        let Some(Ok(line)) = lines.next() else { break };
+        if buffer.is_empty() {
+            match line.trim() {
+                "" => continue,
+                ":quit" | ":q" => break,
+                _ => {}
+            }
+        }
+        buffer.push_str(&line);
+        buffer.push('\n');
+        if is_incomplete(&buffer) {
+            continue;
+        }
+
+        let input = std::mem::take(&mut buffer);
+        match repl.eval(&input) {
+            Ok(ReplOutcome::Declared(names)) => println!("declared {}", names.join(", ")),
+            Ok(ReplOutcome::Ran { stdout, stderr, exit_code }) => {
+                print!("{stdout}");
+                eprint!("{stderr}");
+                if exit_code != 0 {
+                    eprintln!("exited with status {exit_code}");
+                }
+            }
+            Err(err) => eprintln!("error: {err}"),
+        }
+    }
+    println!();
+    Ok(())
+}
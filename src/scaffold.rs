@@ -0,0 +1,85 @@
+//! Project scaffolding for `pluto new`.
+//!
+//! Creates a directory holding a `pluto.toml`, a starter source file, and a
+//! `.gitignore`: an `app` entry point by default, or a library module whose
+//! `pub` declarations other packages import as a dependency.
+
+use crate::diagnostics::CompileError;
+use std::fs;
+use std::path::Path;
+
+/// What `pluto new` scaffolds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectKind {
+    /// A runnable program with `main.pluto` holding an `app`.
+    App,
+    /// A library with `lib.pluto` holding `pub` functions and tests.
+    Lib,
+}
+
+const GITIGNORE: &str = "\
+# Compiler output and caches
+a.out
+.pluto-cache/
+.pluto-coverage/
+";
+
+const APP_MAIN: &str = "\
+app Main {
+    fn main(self) {
+        print(\"Hello, world!\")
+    }
+}
+";
+
+const LIB_SOURCE: &str = "\
+// `pub` declarations are visible to packages that depend on this one.
+
+pub fn greet(name: string) string {
+    return f\"Hello, {name}!\"
+}
+
+test \"greet\" {
+    expect(greet(\"Pluto\")).to_equal(\"Hello, Pluto!\")
+}
+";
+
+/// Create a new project in `dir`, which must not exist yet or be empty.
+/// The package is named after the directory.
+pub fn new_project(dir: &Path, kind: ProjectKind) -> Result<(), CompileError> {
+    let manifest_path = dir.join("pluto.toml");
+    let io_err = |what: &str, e: std::io::Error| {
+        CompileError::manifest(format!("failed to {what}: {e}"), manifest_path.clone())
+    };
+
+    if dir.exists() {
+        let empty = dir.is_dir() && fs::read_dir(dir).map_err(|e| io_err("read directory", e))?.next().is_none();
+        if !empty {
+            return Err(CompileError::manifest(
+                format!("destination '{}' already exists and is not empty", dir.display()),
+                manifest_path,
+            ));
+        }
+    }
+    fs::create_dir_all(dir).map_err(|e| io_err("create project directory", e))?;
+
+    let canonical = dir.canonicalize().map_err(|e| io_err("resolve project directory", e))?;
+    let name = canonical.file_name().and_then(|n| n.to_str()).ok_or_else(|| {
+        CompileError::manifest(
+            format!("cannot derive a package name from '{}'", dir.display()),
+            manifest_path.clone(),
+        )
+    })?;
+
+    let (source_name, source) = match kind {
+        ProjectKind::App => ("main.pluto", APP_MAIN),
+        ProjectKind::Lib => ("lib.pluto", LIB_SOURCE),
+    };
+    let name = toml::Value::String(name.to_string());
+    let manifest = format!("[package]\nname = {name}\nversion = \"0.1.0\"\n\n[dependencies]\n");
+
+    fs::write(&manifest_path, manifest).map_err(|e| io_err("write pluto.toml", e))?;
+    fs::write(dir.join(source_name), source).map_err(|e| io_err(&format!("write {source_name}"), e))?;
+    fs::write(dir.join(".gitignore"), GITIGNORE).map_err(|e| io_err("write .gitignore", e))?;
+    Ok(())
+}
@@ -0,0 +1,180 @@
+//! Incremental analyze server.
+//!
+//! `AnalyzeServer` lets an editor re-check a file on every save from one long-lived
+//! process. It reads one file path per line and writes one `AnalyzeResult` JSON object
+//! per line, with the errors and warnings `analyze_file_with_warnings` reports.
+//!
+//! Results are cached by canonical path, together with a content hash of every input
+//! the analysis could have read: the Pluto sources under the file's directory (sibling
+//! files and local imports) and the `pluto.toml` manifest. A request is answered from
+//! the cache unless one of those hashes changed or a file was added or removed.
+//! When one did, the file is re-analyzed through an `AnalysisCache`, so only the
+//! changed files and those depending on a changed signature are type-checked again.
+
+use super::types::*;
+use crate::derived::DerivedInfo;
+use crate::diagnostics::CompileError;
+use crate::incremental::AnalysisCache;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Paths and content hashes of the files an analysis depends on, sorted by path.
+type Inputs = Vec<(PathBuf, String)>;
+
+struct CachedAnalysis {
+    inputs: Inputs,
+    result: AnalyzeResult,
+}
+
+/// Long-lived analyzer that answers file paths with their diagnostics.
+pub struct AnalyzeServer {
+    stdlib: Option<PathBuf>,
+    cache: HashMap<PathBuf, CachedAnalysis>,
+    analysis: AnalysisCache,
+}
+
+impl AnalyzeServer {
+    /// Create a server. `stdlib` overrides the stdlib root, as `--stdlib` does.
+    pub fn new(stdlib: Option<PathBuf>) -> Self {
+        Self { stdlib, cache: HashMap::new(), analysis: AnalysisCache::new() }
+    }
+
+    /// Answer each path read from `reader` with a JSON line on `writer`, until EOF.
+    pub fn serve<R: BufRead, W: Write>(&mut self, reader: R, mut writer: W) -> io::Result<()> {
+        for line in reader.lines() {
+            let line = line?;
+            let path = line.trim();
+            if path.is_empty() {
+                continue;
+            }
+            let result = self.analyze(Path::new(path));
+            writeln!(writer, "{}", serde_json::to_string(&result).map_err(io::Error::other)?)?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Analyze `path`, reusing the previous result when none of its inputs changed.
+    pub fn analyze(&mut self, path: &Path) -> AnalyzeResult {
+        let started = Instant::now();
+        let canon = match path.canonicalize() {
+            Ok(canon) => canon,
+            Err(e) => {
+                let err = CompileError::codegen(format!("could not resolve path '{}': {e}", path.display()));
+                return failed(path.to_path_buf(), &err, started);
+            }
+        };
+
+        let inputs = project_inputs(&canon);
+        if let Some(cached) = self.cache.get(&canon)
+            && cached.inputs == inputs
+        {
+            let mut result = cached.result.clone();
+            result.cached = true;
+            result.elapsed_ms = elapsed_ms(started);
+            return result;
+        }
+
+        let result = match crate::analyze_file_incremental(&canon, self.stdlib.as_deref(), &mut self.analysis) {
+            // Warnings are filtered to the entry file, so its source locates them
+            Ok((_program, source, _derived, warnings)) => AnalyzeResult {
+                success: true,
+                path: canon.clone(),
+                errors: vec![],
+                warnings: warnings.iter().map(|w| Diagnostic::from_compile_warning(w, Some(&source))).collect(),
+                cached: false,
+                elapsed_ms: elapsed_ms(started),
+            },
+            Err(err) => failed(canon.clone(), &err, started),
+        };
+        self.cache.insert(canon, CachedAnalysis { inputs, result: result.clone() });
+        result
+    }
+}
+
+fn failed(path: PathBuf, err: &CompileError, started: Instant) -> AnalyzeResult {
+    let mut diag = Diagnostic::from_compile_error(err, None);
+    if let Some(span) = &mut diag.span
+        && let Some(source) = error_source(err, &path)
+    {
+        *span = DiagnosticSpan::from_offset(span.start, span.end, &source);
+    }
+    AnalyzeResult {
+        success: false,
+        path,
+        errors: vec![diag],
+        warnings: vec![],
+        cached: false,
+        elapsed_ms: elapsed_ms(started),
+    }
+}
+
+/// Text of the file an error's span points into, when that file is known: the
+/// sibling file a `SiblingFile` error names, or the entry file (file id 0).
+fn error_source(err: &CompileError, entry: &Path) -> Option<String> {
+    match err {
+        CompileError::SiblingFile { path, .. } => std::fs::read_to_string(path).ok(),
+        CompileError::Syntax { span, .. } | CompileError::Type { span, .. } if span.file_id == 0 => {
+            std::fs::read_to_string(entry).ok()
+        }
+        _ => None,
+    }
+}
+
+fn elapsed_ms(started: Instant) -> f64 {
+    started.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Hash every Pluto source under the entry file's directory, plus the manifest.
+fn project_inputs(entry: &Path) -> Inputs {
+    let mut inputs = Vec::new();
+    if let Some(dir) = entry.parent() {
+        collect_inputs(dir, &mut inputs);
+    }
+    inputs.sort();
+    inputs
+}
+
+fn collect_inputs(dir: &Path, inputs: &mut Inputs) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let hidden = path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            collect_inputs(&path, inputs);
+        } else if path.extension().is_some_and(|ext| ext == "pluto" || ext == "pt")
+            || path.file_name().is_some_and(|n| n == "pluto.toml")
+        {
+            let hash = std::fs::read(&path)
+                .map(|data| DerivedInfo::compute_source_hash(&String::from_utf8_lossy(&data)))
+                .unwrap_or_default();
+            inputs.push((path, hash));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inputs_track_sources_and_manifest_only() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("main.pt"), "fn main() {\n}\n").unwrap();
+        std::fs::create_dir(dir.path().join("geo")).unwrap();
+        std::fs::write(dir.path().join("geo").join("point.pluto"), "pub fn f() {\n}\n").unwrap();
+        std::fs::write(dir.path().join("pluto.toml"), "").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not pluto").unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".git").join("x.pt"), "").unwrap();
+
+        let inputs = project_inputs(&dir.path().join("main.pt"));
+        let names: Vec<_> = inputs.iter().map(|(p, _)| p.strip_prefix(dir.path()).unwrap().to_path_buf()).collect();
+        assert_eq!(names, vec![PathBuf::from("geo/point.pluto"), PathBuf::from("main.pt"), PathBuf::from("pluto.toml")]);
+    }
+}
@@ -589,8 +589,8 @@ impl Visitor for XrefCollector {
     }
 }
 
-/// Helper to run an XrefCollector across a program.
-fn collect_xrefs(program: &Program, collector: &mut XrefCollector) {
+/// Helper to run an XrefCollector (or any other reference visitor) across a program.
+fn collect_xrefs<V: Visitor>(program: &Program, collector: &mut V) {
     // Walk all top-level functions
     for func in &program.functions {
         collector.visit_block(&func.node.body);
@@ -623,11 +623,111 @@ fn collect_xrefs(program: &Program, collector: &mut XrefCollector) {
     }
 }
 
+// ========== Definition lookup ==========
+
+/// Visitor that finds the narrowest resolved reference covering a byte offset.
+struct TargetAtOffset {
+    offset: usize,
+    /// (span length, target UUID) of the best match so far.
+    best: Option<(usize, Uuid)>,
+}
+
+impl TargetAtOffset {
+    fn new(offset: usize) -> Self {
+        Self { offset, best: None }
+    }
+
+    fn consider(&mut self, name: &Spanned<String>, id: Option<Uuid>) {
+        let Some(id) = id else { return };
+        if name.span.start <= self.offset && self.offset <= name.span.end {
+            let len = name.span.end - name.span.start;
+            if self.best.is_none_or(|(best_len, _)| len < best_len) {
+                self.best = Some((len, id));
+            }
+        }
+    }
+}
+
+impl Visitor for TargetAtOffset {
+    fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+        match &expr.node {
+            Expr::Call { name, target_id, .. } | Expr::StructLit { name, target_id, .. } => {
+                self.consider(name, *target_id);
+            }
+            Expr::EnumUnit { enum_name, variant, enum_id, variant_id, .. }
+            | Expr::EnumData { enum_name, variant, enum_id, variant_id, .. }
+            | Expr::Is { enum_name, variant, enum_id, variant_id, .. } => {
+                self.consider(enum_name, *enum_id);
+                self.consider(variant, *variant_id);
+            }
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+
+    fn visit_stmt(&mut self, stmt: &Spanned<Stmt>) {
+        match &stmt.node {
+            Stmt::Raise { error_name, error_id, .. } => self.consider(error_name, *error_id),
+            Stmt::Match { arms, .. } => {
+                for arm in arms {
+                    self.consider(&arm.enum_name, arm.enum_id);
+                    self.consider(&arm.variant_name, arm.variant_id);
+                }
+            }
+            _ => {}
+        }
+        walk_stmt(self, stmt);
+    }
+}
+
+/// Name spans of every declaration defined (not imported) in a program.
+fn declaration_names(program: &Program) -> Vec<(Uuid, &Spanned<String>)> {
+    let mut names = Vec::new();
+    for f in &program.functions {
+        names.push((f.node.id, &f.node.name));
+    }
+    for c in &program.classes {
+        names.push((c.node.id, &c.node.name));
+        for m in &c.node.methods {
+            names.push((m.node.id, &m.node.name));
+        }
+    }
+    for e in &program.enums {
+        names.push((e.node.id, &e.node.name));
+        for v in &e.node.variants {
+            names.push((v.id, &v.name));
+        }
+    }
+    for t in &program.traits {
+        names.push((t.node.id, &t.node.name));
+    }
+    for e in &program.errors {
+        names.push((e.node.id, &e.node.name));
+    }
+    names.retain(|(_, name)| !InProcessServer::is_imported(&name.node));
+    names
+}
+
+/// Resolve the declaration UUID referenced at `offset`, either by a use site or
+/// by the declaration's own name.
+fn target_at(program: &Program, offset: usize) -> Option<Uuid> {
+    let on_decl = declaration_names(program)
+        .into_iter()
+        .find(|(_, name)| name.span.start <= offset && offset <= name.span.end)
+        .map(|(id, _)| id);
+    if on_decl.is_some() {
+        return on_decl;
+    }
+    let mut finder = TargetAtOffset::new(offset);
+    collect_xrefs(program, &mut finder);
+    finder.best.map(|(_, id)| id)
+}
+
 // ========== DiagnosticSpan helper ==========
 
 impl DiagnosticSpan {
     /// Create a DiagnosticSpan from byte offsets and source text.
-    fn from_offset(start: usize, end: usize, source: &str) -> Self {
+    pub(crate) fn from_offset(start: usize, end: usize, source: &str) -> Self {
         let line = source[..start.min(source.len())]
             .chars()
             .filter(|c| *c == '\n')
@@ -644,6 +744,7 @@ impl DiagnosticSpan {
             end,
             line: Some(line),
             column: Some(column),
+            file_id: 0,
         }
     }
 }
@@ -1060,6 +1161,32 @@ impl CompilerService for InProcessServer {
         })
     }
 
+    fn definition_at(&self, path: &Path, offset: usize) -> Result<Option<XrefSite>, ServiceError> {
+        let cached = self.get_module(path)?;
+        let Some(id) = target_at(&cached.program, offset) else {
+            return Ok(None);
+        };
+
+        Ok(self.modules.iter().find_map(|(module_path, module)| {
+            declaration_names(&module.program)
+                .into_iter()
+                .find(|(decl_id, _)| *decl_id == id)
+                .map(|(_, name)| XrefSite {
+                    module_path: module_path.clone(),
+                    span: DiagnosticSpan::from_offset(name.span.start, name.span.end, &module.source),
+                    context: Some(name.node.clone()),
+                })
+        }))
+    }
+
+    fn references_at(&self, path: &Path, offset: usize) -> Result<Vec<XrefSite>, ServiceError> {
+        let cached = self.get_module(path)?;
+        Ok(match target_at(&cached.program, offset) {
+            Some(id) => self.usages_of(id),
+            None => Vec::new(),
+        })
+    }
+
     // ===== Source Access =====
 
     fn get_source(&self, path: &Path, range: Option<ByteRange>) -> Result<String, ServiceError> {
@@ -1154,7 +1281,12 @@ impl CompilerService for InProcessServer {
     }
 
     fn compile(&self, path: &Path, output: &Path, opts: &CompileOptions) -> CompileResult {
-        match crate::compile_file_with_options(path, output, opts.stdlib.as_deref(), opts.gc, opts.standalone) {
+        let compiled = if opts.debug {
+            crate::compile_file_with_debug(path, output, opts.stdlib.as_deref(), opts.gc, opts.standalone)
+        } else {
+            crate::compile_file_with_options(path, output, opts.stdlib.as_deref(), opts.gc, opts.standalone)
+        };
+        match compiled {
             Ok(()) => CompileResult {
                 success: true,
                 path: path.to_path_buf(),
@@ -3,7 +3,9 @@
 //! This module defines the `CompilerService` trait, which provides a protocol-agnostic
 //! interface to all compiler operations. Implementations can be:
 //! - `InProcessServer`: Direct calls to the compiler library (Phase 5)
-//! - Socket server: Remote calls via protocol (Phase 6)
+//! - `RpcServer`: JSON-RPC over stdio or a Unix socket, backed by an `InProcessServer`
+//!
+//! `AnalyzeServer` is a lighter front end that only answers file paths with diagnostics.
 //!
 //! The trait is used by multiple frontends:
 //! - CLI: Terminal-based interface with human-readable formatting
@@ -12,8 +14,12 @@
 
 pub mod types;
 pub mod in_process;
+pub mod rpc;
+pub mod analyze;
 
 pub use in_process::InProcessServer;
+pub use rpc::RpcServer;
+pub use analyze::AnalyzeServer;
 
 use std::path::Path;
 use types::*;
@@ -29,7 +35,7 @@ use uuid::Uuid;
 /// Methods are grouped by concern:
 /// 1. **Module Management** (5 methods) - Loading and tracking modules
 /// 2. **Declaration Inspection** (3 methods) - Querying declarations
-/// 3. **Cross-References & Analysis** (7 methods) - Finding usages, call graphs, etc.
+/// 3. **Cross-References & Analysis** (9 methods) - Finding usages, call graphs, etc.
 /// 4. **Source Access** (2 methods) - Reading source code
 /// 5. **Compilation & Execution** (4 methods) - Building and running programs
 /// 6. **Analysis** (1 method) - Enriching binaries with derived data
@@ -38,7 +44,7 @@ use uuid::Uuid;
 /// ## Implementations
 ///
 /// - `InProcessServer`: Calls compiler library directly, maintains module cache
-/// - Future socket server: Sends requests over network to remote compiler service
+/// - `RpcServer`: Exposes a subset of these methods over JSON-RPC for external editors
 pub trait CompilerService {
     // ========== Module Management (5 methods) ==========
 
@@ -84,7 +90,7 @@ pub trait CompilerService {
     /// Returns matches from every module. Optionally filter by kind.
     fn find_declaration(&self, name: &str, filter: Option<DeclKind>) -> Vec<DeclMatch>;
 
-    // ========== Cross-References & Analysis (7 methods) ==========
+    // ========== Cross-References & Analysis (9 methods) ==========
 
     /// Find all call sites that invoke a given function across all loaded modules.
     fn callers_of(&self, id: Uuid) -> Vec<XrefSite>;
@@ -112,6 +118,18 @@ pub trait CompilerService {
     /// Get error handling info for a function: whether it is fallible and its error set.
     fn error_set(&self, path: &Path, id: Uuid) -> Result<ErrorSetInfo, ServiceError>;
 
+    /// Find the declaration referenced at a byte offset in a loaded module.
+    ///
+    /// The offset may sit on a use (call, struct literal, enum variant, raise) or on the
+    /// declaration's own name. Returns the name span of the declaration, with the
+    /// declaration name as context, or `None` if nothing resolvable is at the offset.
+    fn definition_at(&self, path: &Path, offset: usize) -> Result<Option<XrefSite>, ServiceError>;
+
+    /// Find all usages of the declaration referenced at a byte offset in a loaded module.
+    ///
+    /// Resolves the offset like `definition_at`, then returns `usages_of` for it.
+    fn references_at(&self, path: &Path, offset: usize) -> Result<Vec<XrefSite>, ServiceError>;
+
     // ========== Source Access (2 methods) ==========
 
     /// Get source text from a loaded module, optionally at a specific byte range.
@@ -0,0 +1,435 @@
+//! JSON-RPC front end for the compiler service.
+//!
+//! `RpcServer` lets editors and scripts that don't speak LSP query the compiler without
+//! spawning a process per request. It reads newline-delimited JSON-RPC 2.0 requests
+//! (one object per line) and writes one response line per request. Requests without
+//! an `id` are notifications and get no response.
+//!
+//! Methods:
+//! - `analyze` `{path}`: load the file and return its `ModuleSummary`
+//! - `diagnostics` `{path}`: type-check the file and return its `CheckResult`
+//! - `definition` `{path, offset | line, column}`: the declaration referenced at a position
+//! - `references` `{path, offset | line, column}`: all usages of that declaration
+//! - `compile` `{path, output?, standalone?, debug?}`: build a binary (`CompileResult`)
+//! - `run` `{path, timeout_ms?, cwd?}`: compile and run, capturing output (`RunResult`)
+//! - `test` `{path, timeout_ms?, cwd?}`: compile in test mode and run the tests (`TestResult`)
+//!
+//! Lines and columns are 1-based; `offset` is a byte offset and takes precedence.
+//!
+//! Analyzed modules stay warm between requests and are only re-analyzed once the
+//! file changes on disk. Diagnostics are re-checked once any file of the module
+//! graph changes. Because the process is long-lived, the compiled C runtime
+//! and the parsed prelude are built once and shared by every compile, run and test
+//! request, which makes the server usable as a compile daemon.
+
+use super::types::*;
+use super::{CompilerService, InProcessServer};
+use crate::modules::SourceMap;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Service-level failure (missing file, compile error while loading, ...).
+const SERVICE_ERROR: i64 = -32000;
+
+/// A JSON-RPC error object.
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+impl From<ServiceError> for RpcError {
+    fn from(err: ServiceError) -> Self {
+        Self::new(SERVICE_ERROR, err.to_string())
+    }
+}
+
+/// Paths of the files a check read, with each file's (mtime, length), if it still exists.
+type Stamps = Vec<(PathBuf, Option<(SystemTime, u64)>)>;
+
+/// JSON-RPC server backed by an `InProcessServer`.
+pub struct RpcServer {
+    service: InProcessServer,
+    stdlib: Option<PathBuf>,
+    /// Last check result per file, with the stamps of every file of its module graph
+    /// at the time of the check.
+    diagnostics: HashMap<PathBuf, (Stamps, CheckResult)>,
+}
+
+impl RpcServer {
+    /// Create a server. `stdlib` overrides the stdlib root, as `--stdlib` does.
+    pub fn new(stdlib: Option<PathBuf>) -> Self {
+        Self {
+            service: InProcessServer::new(),
+            stdlib,
+            diagnostics: HashMap::new(),
+        }
+    }
+
+    /// Serve requests from `reader` until EOF, writing responses to `writer`.
+    pub fn serve<R: BufRead, W: Write>(&mut self, reader: R, mut writer: W) -> io::Result<()> {
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_line(&line) {
+                writeln!(writer, "{response}")?;
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Listen on a Unix socket and serve connections one at a time.
+    ///
+    /// All connections share the same warm module cache. A stale socket file left
+    /// behind by a previous server is replaced.
+    #[cfg(unix)]
+    pub fn serve_unix(&mut self, socket: &Path) -> io::Result<()> {
+        use std::os::unix::fs::FileTypeExt;
+        use std::os::unix::net::UnixListener;
+
+        if let Ok(meta) = std::fs::symlink_metadata(socket)
+            && meta.file_type().is_socket()
+        {
+            std::fs::remove_file(socket)?;
+        }
+        let listener = UnixListener::bind(socket)?;
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let reader = io::BufReader::new(stream.try_clone()?);
+            if let Err(e) = self.serve(reader, stream) {
+                eprintln!("warning: rpc connection closed: {e}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle one request line and return the response line, or `None` for a notification.
+    pub fn handle_line(&mut self, line: &str) -> Option<String> {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(e) => {
+                let err = RpcError::new(PARSE_ERROR, format!("parse error: {e}"));
+                return Some(error_response(Value::Null, err).to_string());
+            }
+        };
+
+        let id = request.get("id").cloned();
+        let result = match request.get("method").and_then(Value::as_str) {
+            Some(method) => {
+                let params = request.get("params").cloned().unwrap_or(Value::Null);
+                self.dispatch(method, &params)
+            }
+            None => Err(RpcError::new(INVALID_REQUEST, "request has no method")),
+        };
+
+        let id = id?;
+        let response = match result {
+            Ok(value) => json!({ "jsonrpc": "2.0", "id": id, "result": value }),
+            Err(err) => error_response(id, err),
+        };
+        Some(response.to_string())
+    }
+
+    fn dispatch(&mut self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        if !matches!(
+            method,
+            "analyze" | "diagnostics" | "definition" | "references" | "compile" | "run" | "test"
+        ) {
+            return Err(RpcError::new(METHOD_NOT_FOUND, format!("unknown method '{method}'")));
+        }
+        let path = PathBuf::from(str_param(params, "path")?);
+        match method {
+            "analyze" => {
+                let path = self.ensure_loaded(&path)?;
+                to_value(self.summary(&path)?)
+            }
+            "diagnostics" => to_value(self.check(&path)?),
+            "definition" => {
+                let path = self.ensure_loaded(&path)?;
+                let offset = self.offset_param(&path, params)?;
+                to_value(self.service.definition_at(&path, offset)?)
+            }
+            "references" => {
+                let path = self.ensure_loaded(&path)?;
+                let offset = self.offset_param(&path, params)?;
+                to_value(self.service.references_at(&path, offset)?)
+            }
+            "compile" => {
+                let output = match params.get("output").and_then(Value::as_str) {
+                    Some(out) => PathBuf::from(out),
+                    None => path.with_extension(""),
+                };
+                let opts = CompileOptions {
+                    stdlib: self.stdlib.clone(),
+                    standalone: bool_param(params, "standalone"),
+                    debug: bool_param(params, "debug"),
+                    ..Default::default()
+                };
+                let mut result = self.service.compile(&path, &output, &opts);
+                fill_diagnostics(&mut result.errors, &entry_sources(&path));
+                to_value(result)
+            }
+            "run" => {
+                let opts = RunOptions {
+                    stdlib: self.stdlib.clone(),
+                    timeout_ms: params.get("timeout_ms").and_then(Value::as_u64),
+                    cwd: params.get("cwd").and_then(Value::as_str).map(PathBuf::from),
+                };
+                let mut result = self.service.run(&path, &opts);
+                fill_diagnostics(&mut result.compile_errors, &entry_sources(&path));
+                to_value(result)
+            }
+            "test" => {
+                let opts = TestOptions {
+                    stdlib: self.stdlib.clone(),
+                    timeout_ms: params.get("timeout_ms").and_then(Value::as_u64),
+                    cwd: params.get("cwd").and_then(Value::as_str).map(PathBuf::from),
+                };
+                let mut result = self.service.test(&path, &opts);
+                fill_diagnostics(&mut result.compile_errors, &entry_sources(&path));
+                to_value(result)
+            }
+            _ => unreachable!("method names are checked above"),
+        }
+    }
+
+    fn load_options(&self) -> LoadOptions {
+        LoadOptions { stdlib: self.stdlib.clone() }
+    }
+
+    /// Load `path` if it isn't cached yet or has changed on disk. Returns the cache key.
+    fn ensure_loaded(&mut self, path: &Path) -> Result<PathBuf, RpcError> {
+        if !path.exists() {
+            return Err(ServiceError::ModuleNotFound(path.to_path_buf()).into());
+        }
+        let canon = path.canonicalize().map_err(ServiceError::Io)?;
+        let status = self.service.module_status().into_iter().find(|m| m.path == canon);
+        let opts = self.load_options();
+        match status {
+            Some(s) if !s.is_stale => {}
+            Some(_) => {
+                self.service.reload_module(&canon, &opts)?;
+            }
+            None => {
+                self.service.load_module(&canon, &opts)?;
+            }
+        }
+        Ok(canon)
+    }
+
+    /// Summary of an already-loaded module.
+    fn summary(&self, path: &Path) -> Result<ModuleSummary, RpcError> {
+        let decls = self.service.list_declarations(path, None)?;
+        let count = |kind| decls.iter().filter(|d| d.kind == kind).count();
+        let name = self
+            .service
+            .list_modules()
+            .into_iter()
+            .find(|m| m.path == path)
+            .map(|m| m.name)
+            .unwrap_or_default();
+        Ok(ModuleSummary {
+            path: path.to_path_buf(),
+            name,
+            function_count: count(DeclKind::Function),
+            class_count: count(DeclKind::Class),
+            enum_count: count(DeclKind::Enum),
+            trait_count: count(DeclKind::Trait),
+            error_count: count(DeclKind::Error),
+            app_count: count(DeclKind::App),
+        })
+    }
+
+    /// Check `path`, reusing the previous result if no file of its module graph has
+    /// changed since.
+    fn check(&mut self, path: &Path) -> Result<CheckResult, RpcError> {
+        let canon = path.canonicalize().map_err(ServiceError::Io)?;
+        if let Some((stamps, result)) = self.diagnostics.get(&canon)
+            && stamps.iter().all(|(file, stamp)| file_stamp(file) == *stamp)
+        {
+            return Ok(result.clone());
+        }
+
+        // Standalone, as `CompilerService::check`: only the requested file is analyzed
+        let mut sources = SourceMap::new();
+        let mut result = match crate::analyze_file_with_sources(&canon, self.stdlib.as_deref(), true, &mut sources) {
+            Ok((_program, _source, _derived, warnings)) => CheckResult {
+                success: true,
+                path: canon.clone(),
+                errors: vec![],
+                warnings: warnings.iter().map(|w| Diagnostic::from_compile_warning(w, None)).collect(),
+            },
+            Err(err) => CheckResult {
+                success: false,
+                path: canon.clone(),
+                errors: vec![Diagnostic::from_compile_error(&err, None)],
+                warnings: vec![],
+            },
+        };
+        if sources.files.is_empty() {
+            // The module graph couldn't be resolved (e.g. a syntax error in the entry
+            // file); locate what we can and check again next time
+            fill_diagnostics(&mut result.errors, &entry_sources(&canon));
+            return Ok(result);
+        }
+        fill_diagnostics(&mut result.errors, &sources);
+        fill_diagnostics(&mut result.warnings, &sources);
+        let stamps = sources.files.iter().map(|(file, _)| (file.clone(), file_stamp(file))).collect();
+        self.diagnostics.insert(canon, (stamps, result.clone()));
+        Ok(result)
+    }
+
+    /// Read the position of a `definition`/`references` request as a byte offset.
+    fn offset_param(&self, path: &Path, params: &Value) -> Result<usize, RpcError> {
+        if let Some(offset) = params.get("offset").and_then(Value::as_u64) {
+            return Ok(offset as usize);
+        }
+        let line = usize_param(params, "line")?;
+        let column = usize_param(params, "column")?;
+        let source = self.service.get_source(path, None)?;
+        offset_of(&source, line, column).ok_or_else(|| {
+            RpcError::new(INVALID_PARAMS, format!("position {line}:{column} is outside the file"))
+        })
+    }
+}
+
+fn error_response(id: Value, err: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": err.code, "message": err.message },
+    })
+}
+
+fn to_value<T: serde::Serialize>(value: T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::new(SERVICE_ERROR, e.to_string()))
+}
+
+fn str_param<'a>(params: &'a Value, name: &str) -> Result<&'a str, RpcError> {
+    params
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("missing string parameter '{name}'")))
+}
+
+fn bool_param(params: &Value, name: &str) -> bool {
+    params.get(name).and_then(Value::as_bool).unwrap_or(false)
+}
+
+fn usize_param(params: &Value, name: &str) -> Result<usize, RpcError> {
+    params
+        .get(name)
+        .and_then(Value::as_u64)
+        .map(|n| n as usize)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("missing integer parameter '{name}'")))
+}
+
+/// Convert a 1-based line and column (in characters) to a byte offset.
+fn offset_of(source: &str, line: usize, column: usize) -> Option<usize> {
+    let line_start = if line == 1 {
+        0
+    } else {
+        source.match_indices('\n').nth(line.checked_sub(2)?)?.0 + 1
+    };
+    let line_text = source[line_start..].split('\n').next().unwrap_or("");
+    let col = column.checked_sub(1)?;
+    if col == line_text.chars().count() {
+        return Some(line_start + line_text.len());
+    }
+    line_text.char_indices().nth(col).map(|(i, _)| line_start + i)
+}
+
+/// The (mtime, length) of `path`, or `None` if it can't be read.
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// A source map holding only the entry file, for results that don't come with their
+/// module graph. Spans in other files are left without line:col.
+fn entry_sources(path: &Path) -> SourceMap {
+    let mut sources = SourceMap::new();
+    if let Ok(source) = std::fs::read_to_string(path) {
+        sources.add_file(path.to_path_buf(), source);
+    }
+    sources
+}
+
+/// Diagnostics from the service carry byte spans only; editors want line:col as well.
+/// Each span is located in its own file of `sources`.
+fn fill_diagnostics(diags: &mut [Diagnostic], sources: &SourceMap) {
+    for diag in diags {
+        let Some(file_id) = diag.span.as_ref().map(|span| span.file_id) else { continue };
+        if let Some((_, source)) = sources.get_source(file_id) {
+            fill_line_column(diag, source);
+        }
+    }
+}
+
+fn fill_line_column(diag: &mut Diagnostic, source: &str) {
+    if let Some(span) = &mut diag.span
+        && span.line.is_none()
+        && span.start <= source.len()
+    {
+        let before = &source[..span.start];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        span.line = Some(before.matches('\n').count() + 1);
+        span.column = Some(before[line_start..].chars().count() + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_of_converts_line_and_column() {
+        let src = "fn main() {\n    foo()\n}\n";
+        assert_eq!(offset_of(src, 1, 1), Some(0));
+        assert_eq!(offset_of(src, 2, 5), Some(16));
+        assert_eq!(offset_of(src, 2, 10), Some(21));
+        assert_eq!(offset_of(src, 2, 11), None);
+        assert_eq!(offset_of(src, 9, 1), None);
+    }
+
+    #[test]
+    fn unknown_method_and_bad_json_are_errors() {
+        let mut server = RpcServer::new(None);
+        let resp: Value =
+            serde_json::from_str(&server.handle_line("{not json").unwrap()).unwrap();
+        assert_eq!(resp["error"]["code"], PARSE_ERROR);
+
+        let resp: Value = serde_json::from_str(
+            &server
+                .handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"nope","params":{"path":"x.pluto"}}"#)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(resp["id"], 1);
+        assert_eq!(resp["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn notifications_get_no_response() {
+        let mut server = RpcServer::new(None);
+        assert!(server
+            .handle_line(r#"{"jsonrpc":"2.0","method":"diagnostics","params":{"path":"missing.pluto"}}"#)
+            .is_none());
+    }
+}
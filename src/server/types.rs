@@ -28,6 +28,8 @@ pub struct CompileOptions {
     pub coverage: bool,
     /// Compile file in isolation without merging sibling .pluto files
     pub standalone: bool,
+    /// Instrument functions so uncaught errors print a stack trace
+    pub debug: bool,
 }
 
 /// Options for running programs.
@@ -80,6 +82,18 @@ pub struct CheckResult {
     pub warnings: Vec<Diagnostic>,
 }
 
+/// Diagnostics the analyze server returns for one file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyzeResult {
+    pub success: bool,
+    pub path: PathBuf,
+    pub errors: Vec<Diagnostic>,
+    pub warnings: Vec<Diagnostic>,
+    /// True when no input changed since the previous request, so the earlier result was reused.
+    pub cached: bool,
+    pub elapsed_ms: f64,
+}
+
 /// Result of compiling a file.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompileResult {
@@ -128,6 +142,10 @@ pub struct DiagnosticSpan {
     pub end: usize,
     pub line: Option<usize>,
     pub column: Option<usize>,
+    /// Source-map id of the file the span points into (0 is the entry file).
+    /// Not serialized: ids only mean something within one compile.
+    #[serde(skip)]
+    pub file_id: u32,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -151,6 +169,7 @@ impl Diagnostic {
             CompileError::Toolchain(msg) => (msg.clone(), None),
             CompileError::Network(msg) => (msg.clone(), None),
             CompileError::VersionNotFound(msg) => (msg.clone(), None),
+            CompileError::Internal(msg) => (format!("internal compiler error: {msg}"), None),
         };
 
         Self {
@@ -185,6 +204,7 @@ impl DiagnosticSpan {
             end: span.end,
             line,
             column,
+            file_id: span.file_id,
         }
     }
 }
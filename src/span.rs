@@ -4,7 +4,7 @@ use serde::{Serialize, Deserialize};
 pub const SYNTHETIC_FILE_ID: u32 = u32::MAX;
 
 /// Byte-offset span in source code.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Span {
     pub start: usize,
     pub end: usize,
@@ -2,10 +2,11 @@ use std::path::Path;
 
 use crate::binary;
 use crate::parser::ast::{
-    EnumVariant, Field, Function, Param, Program, TraitMethod, TypeExpr,
+    ClassDecl, EnumVariant, Field, Function, Param, Program, TraitMethod, TypeExpr,
 };
 use crate::span::Spanned;
 use crate::xref;
+use uuid::Uuid;
 
 /// Compare two TypeExpr values semantically (ignoring spans).
 fn types_equal(t1: &Spanned<TypeExpr>, t2: &Spanned<TypeExpr>) -> bool {
@@ -87,6 +88,8 @@ pub enum SyncError {
 
 /// Sync human edits from a `.pt` text file back to a `.pluto` binary,
 /// preserving UUIDs from the existing binary where declarations match by name.
+/// Inside classes present in both versions, added, removed, and renamed fields
+/// and methods are reported as `ClassName.member` entries.
 ///
 /// If `pluto_path` does not exist, creates a fresh `.pluto` binary (all new UUIDs).
 pub fn sync_pt_to_pluto(pt_path: &Path, pluto_path: &Path) -> Result<SyncResult, SyncError> {
@@ -190,7 +193,7 @@ fn transplant_program(new: &mut Program, old: &Program) -> SyncResult {
         |c| c.node.name.node.clone(),
         |new_c, old_c| {
             new_c.node.id = old_c.node.id;
-            transplant_fields(&mut new_c.node.fields, &old_c.node.fields);
+            transplant_class_fields(&mut new_c.node.fields, &old_c.node.fields);
             transplant_methods(&mut new_c.node.methods, &old_c.node.methods);
         },
         None::<fn(&_, &_) -> f64>,
@@ -200,6 +203,11 @@ fn transplant_program(new: &mut Program, old: &Program) -> SyncResult {
         &mut unchanged,
         "class",
     );
+    for new_c in &new.classes {
+        if let Some(old_c) = old.classes.iter().find(|c| c.node.id == new_c.node.id) {
+            class_member_changes(&new_c.node, &old_c.node, &mut added, &mut removed, &mut modified);
+        }
+    }
 
     // Enums
     transplant_decls(
@@ -443,6 +451,55 @@ fn transplant_fields(new_fields: &mut [Field], old_fields: &[Field]) {
     }
 }
 
+/// Match class fields by name, then treat an unmatched field that kept its
+/// position and type as renamed, so it keeps its UUID.
+fn transplant_class_fields(new_fields: &mut [Field], old_fields: &[Field]) {
+    transplant_fields(new_fields, old_fields);
+    let has = |fields: &[Field], name: &str| fields.iter().any(|f| f.name.node == name);
+    let renamed: Vec<usize> = new_fields.iter().enumerate()
+        .filter(|(i, new_f)| old_fields.get(*i).is_some_and(|old_f| {
+            !has(old_fields, &new_f.name.node)
+                && !has(new_fields, &old_f.name.node)
+                && types_equal(&new_f.ty, &old_f.ty)
+        }))
+        .map(|(i, _)| i)
+        .collect();
+    for i in renamed {
+        new_fields[i].id = old_fields[i].id;
+    }
+}
+
+/// Report methods and fields added, removed, or renamed inside a class that
+/// exists in both versions, as `ClassName.member` entries. Expects UUIDs to
+/// have been transplanted already, so members are matched by UUID.
+fn class_member_changes(
+    new: &ClassDecl,
+    old: &ClassDecl,
+    added: &mut Vec<String>,
+    removed: &mut Vec<String>,
+    modified: &mut Vec<String>,
+) {
+    let class = &new.name.node;
+    let members = |c: &ClassDecl| -> Vec<(Uuid, &'static str, String)> {
+        c.fields.iter().map(|f| (f.id, "field", f.name.node.clone()))
+            .chain(c.methods.iter().map(|m| (m.node.id, "fn", m.node.name.node.clone())))
+            .collect()
+    };
+    let (new_members, old_members) = (members(new), members(old));
+    for (id, kind, name) in &new_members {
+        match old_members.iter().find(|(old_id, _, _)| old_id == id) {
+            None => added.push(format!("{kind} {class}.{name}")),
+            Some((_, _, old_name)) if old_name != name => modified.push(format!("{kind} {class}.{name}")),
+            Some(_) => {}
+        }
+    }
+    for (id, kind, name) in &old_members {
+        if !new_members.iter().any(|(new_id, _, _)| new_id == id) {
+            removed.push(format!("{kind} {class}.{name}"));
+        }
+    }
+}
+
 /// Match methods by name, copy UUIDs and recurse into params.
 fn transplant_methods(new_methods: &mut [Spanned<Function>], old_methods: &[Spanned<Function>]) {
     for new_m in new_methods.iter_mut() {
@@ -544,7 +601,7 @@ mod tests {
 
     // ===== Unit tests for transplant helpers =====
 
-    use crate::parser::ast::{TypeExpr, Block};
+    use crate::parser::ast::{TypeExpr, Block, Visibility};
     use crate::span::Span;
     use uuid::Uuid;
 
@@ -582,9 +639,11 @@ mod tests {
                 return_type: None,
                 contracts: vec![],
                 body: Spanned::new(Block { stmts: vec![] }, Span::dummy()),
-                is_pub: false,
+                visibility: Visibility::Private,
                 is_override: false,
                 is_generator: false,
+                declared_errors: None,
+                no_coverage: false,
             },
             Span::dummy(),
         )
@@ -612,6 +671,7 @@ mod tests {
             return_type: None,
             contracts: vec![],
             body: None,
+            declared_errors: None,
         }
     }
 
@@ -698,6 +758,31 @@ mod tests {
         assert_eq!(new_fields[1].id, old_y_id);
     }
 
+    #[test]
+    fn test_transplant_class_fields_prefers_name_matches() {
+        let old_x_id = Uuid::new_v4();
+        let old_y_id = Uuid::new_v4();
+        let mut new_fields = vec![make_field("y", Uuid::new_v4()), make_field("width", Uuid::new_v4())];
+        let old_fields = vec![make_field("x", old_x_id), make_field("y", old_y_id)];
+
+        transplant_class_fields(&mut new_fields, &old_fields);
+
+        // "y" matches by name, so "width" (in y's old slot) is a new field
+        assert_eq!(new_fields[0].id, old_y_id);
+        assert_ne!(new_fields[1].id, old_x_id);
+    }
+
+    #[test]
+    fn test_transplant_class_fields_rename_at_same_position() {
+        let old_id = Uuid::new_v4();
+        let mut new_fields = vec![make_field("total", Uuid::new_v4())];
+        let old_fields = vec![make_field("count", old_id)];
+
+        transplant_class_fields(&mut new_fields, &old_fields);
+
+        assert_eq!(new_fields[0].id, old_id);
+    }
+
     #[test]
     fn test_transplant_methods_matching_names() {
         let old_method_id = Uuid::new_v4();
@@ -5,7 +5,7 @@ use crate::visit::{walk_expr, walk_stmt, Visitor};
 use super::env::{mangle_method, TypeEnv};
 use super::types::PlutoType;
 use super::resolve::resolve_type;
-use super::infer::infer_expr;
+use super::infer::{infer_expr, infer_let_init};
 use super::types_compatible;
 use crate::parser::ast::Expr;
 
@@ -23,29 +23,79 @@ pub(crate) fn check_function(func: &Function, env: &mut TypeEnv, class_name: Opt
 
 /// Checks if ALL control flow paths through a block terminate with `return` or `raise`.
 /// - `return` and `raise` both terminate a path
-/// - `if/else` terminates only if BOTH branches terminate (if without else never terminates)
+/// - `if/else` (and `if let`/`else`) terminates only if BOTH branches terminate (if without else never terminates)
 /// - `match` terminates only if ALL arms terminate
+/// - `try/catch` terminates only if both the body and the handler terminate
 /// - `while`/`for` never guarantee termination (body may execute 0 times)
+/// - `unreachable(...)` never returns, so it ends a path like `return` or `raise`
 pub(crate) fn all_paths_return(block: &Block) -> bool {
     for stmt in &block.stmts {
         match &stmt.node {
             Stmt::Return(_) | Stmt::Raise { .. } => return true,
-            Stmt::If { then_block, else_block: Some(else_block), .. } => {
+            Stmt::Expr(expr) if is_unreachable_call(&expr.node) => return true,
+            Stmt::If { then_block, else_block: Some(else_block), .. }
+            | Stmt::IfLet { then_block, else_block: Some(else_block), .. } => {
                 if all_paths_return(&then_block.node) && all_paths_return(&else_block.node) {
                     return true;
                 }
             }
+            Stmt::NullableMatch { some_arm: Some(some_arm), none_arm: Some(none_arm), .. }
+                if all_paths_return(&some_arm.body.node) && all_paths_return(&none_arm.node) =>
+            {
+                return true;
+            }
             Stmt::Match { arms, .. } => {
                 if !arms.is_empty() && arms.iter().all(|arm| all_paths_return(&arm.body.node)) {
                     return true;
                 }
             }
+            Stmt::IntMatch { arms, .. }
+                if !arms.is_empty() && arms.iter().all(|arm| all_paths_return(&arm.body.node)) =>
+            {
+                return true;
+            }
+            Stmt::Try { body, handler, finally, .. }
+                if (all_paths_return(&body.node) && all_paths_return(&handler.node))
+                    || finally.as_ref().is_some_and(|f| all_paths_return(&f.node)) =>
+            {
+                return true;
+            }
             _ => {}
         }
     }
     false
 }
 
+/// Whether `expr` calls the `unreachable` builtin. Functions and extern fns
+/// can't take a builtin's name, and calls inside a module are qualified with
+/// the module name, so the bare name always refers to the builtin.
+pub(crate) fn is_unreachable_call(expr: &Expr) -> bool {
+    matches!(expr, Expr::Call { name, .. } if name.node == "unreachable")
+}
+
+/// The type bound by `if let` / `match ... some(x)`: the value must be `T?`
+/// for a known, non-nullable `T`.
+fn nullable_binding_type(value: &Spanned<Expr>, env: &mut TypeEnv) -> Result<PlutoType, CompileError> {
+    let value_type = infer_expr(&value.node, value.span, env, None)?;
+    match value_type {
+        PlutoType::Nullable(inner) => match *inner {
+            PlutoType::Void => Err(CompileError::type_err(
+                "cannot unwrap `none`: its type is unknown".to_string(),
+                value.span,
+            )),
+            PlutoType::Nullable(_) => Err(CompileError::type_err(
+                format!("cannot unwrap nested nullable type {inner}?; nested nullable types (T??) are not allowed"),
+                value.span,
+            )),
+            inner => Ok(inner),
+        },
+        other => Err(CompileError::type_err(
+            format!("'if let' and 'some'/'none' match arms require a nullable value, found {other}"),
+            value.span,
+        )),
+    }
+}
+
 fn check_function_body(func: &Function, env: &mut TypeEnv, class_name: Option<&str>) -> Result<(), CompileError> {
     env.invalidated_task_vars.clear();
     env.push_scope();
@@ -56,10 +106,16 @@ fn check_function_body(func: &Function, env: &mut TypeEnv, class_name: Option<&s
             if let Some(cn) = class_name {
                 PlutoType::Class(cn.to_string())
             } else {
-                return Err(CompileError::type_err(
-                    "'self' used outside of class method",
-                    p.name.span,
-                ));
+                // Lowered enum methods carry `self` as an explicit enum-typed param
+                match resolve_type(&p.ty, env)? {
+                    ty @ PlutoType::Enum(_) => ty,
+                    _ => {
+                        return Err(CompileError::type_err(
+                            "'self' used outside of class method",
+                            p.name.span,
+                        ));
+                    }
+                }
             }
         } else {
             resolve_type(&p.ty, env)?
@@ -151,7 +207,14 @@ fn check_stmt(
             let hint = ty.as_ref()
                 .map(|t| resolve_type(t, env))
                 .transpose()?;
-            let val_type = infer_expr(&value.node, value.span, env, hint.as_ref())?;
+            let val_type = infer_let_init(value, env, hint.as_ref())?;
+            // A guard may only come straight from lock(); copying one would unlock twice
+            if matches!(val_type, PlutoType::MutexGuard(_)) && !matches!(&value.node, Expr::MethodCall { method, .. } if method.node == "lock") {
+                return Err(CompileError::type_err(
+                    "a MutexGuard can only be bound directly from lock()".to_string(),
+                    value.span,
+                ));
+            }
             // Reject bare `none` without a type annotation (Nullable(Void) is the sentinel
             // for unresolved none literals — it can appear directly or nested in containers)
             if ty.is_none() && contains_unresolved_none(&val_type) {
@@ -160,6 +223,25 @@ fn check_stmt(
                     value.span,
                 ));
             }
+            // `let _ = expr` evaluates expr and discards it without binding a name
+            if name.node == "_" {
+                if matches!(val_type, PlutoType::MutexGuard(_)) {
+                    return Err(CompileError::type_err(
+                        "a MutexGuard cannot be discarded with `let _`; it would never be unlocked".to_string(),
+                        value.span,
+                    ));
+                }
+                if let Some(declared_ty) = ty {
+                    let expected = resolve_type(declared_ty, env)?;
+                    if !types_compatible(&val_type, &expected, env) {
+                        return Err(CompileError::type_err(
+                            format!("type mismatch: expected {expected}, found {val_type}"),
+                            value.span,
+                        ));
+                    }
+                }
+                return Ok(());
+            }
             // Check for collisions with global declarations (only for let, not params/bindings)
             env.check_global_name_collision(&name.node, name.span)?;
             if let Some(declared_ty) = ty {
@@ -190,6 +272,54 @@ fn check_stmt(
                 env.scope_tainted.insert(name.node.clone(), ());
             }
         }
+        Stmt::LetStruct { ty, fields, value, is_mut } => {
+            let expected = resolve_type(ty, env)?;
+            let class_fields = match &expected {
+                PlutoType::Class(class_name) => env.classes.get(class_name).map(|c| c.fields.clone()),
+                _ => None,
+            };
+            let (PlutoType::Class(class_name), Some(class_fields)) = (&expected, class_fields) else {
+                return Err(CompileError::type_err(
+                    format!("cannot destructure {expected}; only classes can be destructured with `let`"),
+                    ty.span,
+                ));
+            };
+            let val_type = infer_expr(&value.node, value.span, env, Some(&expected))?;
+            if val_type != expected {
+                return Err(CompileError::type_err(
+                    format!("type mismatch: expected {expected}, found {val_type}"),
+                    value.span,
+                ));
+            }
+            let mut bound: Vec<&str> = Vec::new();
+            for (field, rename) in fields {
+                let Some((_, field_type, _)) = class_fields.iter().find(|(n, _, _)| *n == field.node) else {
+                    return Err(CompileError::type_err(
+                        format!("class '{class_name}' has no field '{}'", field.node),
+                        field.span,
+                    ));
+                };
+                let local = rename.as_ref().unwrap_or(field);
+                // `_` skips a field, as in `let _ = expr`
+                if local.node == "_" {
+                    continue;
+                }
+                if bound.contains(&local.node.as_str()) {
+                    return Err(CompileError::type_err(
+                        format!("'{}' is bound more than once in this pattern", local.node),
+                        local.span,
+                    ));
+                }
+                bound.push(&local.node);
+                env.check_global_name_collision(&local.node, local.span)?;
+                env.define(local.node.clone(), field_type.clone(), local.span)?;
+                if !is_mut {
+                    env.mark_immutable(&local.node);
+                }
+                let depth = env.scope_depth() - 1;
+                env.variable_decls.insert((local.node.clone(), depth), local.span);
+            }
+        }
         Stmt::Return(value) => {
             // Generators: bare return is allowed (means "done"), return with value is not
             if env.current_generator_elem.is_some() {
@@ -204,6 +334,18 @@ fn check_stmt(
             }
             let actual = match value {
                 Some(expr) => infer_expr(&expr.node, expr.span, env, Some(return_type))?,
+                // Bare return never falls back to a default value
+                None if *return_type != PlutoType::Void => {
+                    let hint = if matches!(return_type, PlutoType::Nullable(_)) {
+                        "use 'return none' to return no value"
+                    } else {
+                        "bare return is only allowed in functions that return nothing"
+                    };
+                    return Err(CompileError::type_err(
+                        format!("missing return value: expected {return_type}; {hint}"),
+                        span,
+                    ));
+                }
                 None => PlutoType::Void,
             };
             if !types_compatible(&actual, return_type, env) {
@@ -224,20 +366,30 @@ fn check_stmt(
             }
         }
         Stmt::Assign { target, value } => {
-            let var_type = env.lookup(&target.node).ok_or_else(|| {
-                CompileError::type_err(
-                    format!("undefined variable '{}'", target.node),
-                    target.span,
-                )
-            })?.clone();
+            let var_type = env.lookup(&target.node)
+                .or_else(|| env.globals.get(&target.node).map(|g| &g.ty))
+                .ok_or_else(|| {
+                    let msg = if env.consts.contains_key(&target.node) {
+                        format!("cannot assign to const '{}'", target.node)
+                    } else {
+                        format!("undefined variable '{}'", target.node)
+                    };
+                    CompileError::type_err(msg, target.span)
+                })?.clone();
             if matches!(&var_type, PlutoType::Sender(_) | PlutoType::Receiver(_)) {
                 return Err(CompileError::type_err(
                     "cannot reassign channel sender/receiver variable".to_string(),
                     target.span,
                 ));
             }
+            if matches!(&var_type, PlutoType::MutexGuard(_)) {
+                return Err(CompileError::type_err(
+                    "cannot reassign a MutexGuard variable".to_string(),
+                    target.span,
+                ));
+            }
             // Check if variable is immutable (declared without mut)
-            if env.is_immutable(&target.node) {
+            if env.lookup(&target.node).is_some() && env.is_immutable(&target.node) {
                 return Err(CompileError::type_err(
                     format!("cannot assign to immutable variable '{}'", target.node),
                     target.span,
@@ -279,7 +431,32 @@ fn check_stmt(
                     condition.span,
                 ));
             }
+            // `if x is Enum.Variant { ... }` narrows x for the then-block,
+            // unless the block reassigns it.
+            let saved_guards = env.variant_guards.clone();
+            let mut guards = Vec::new();
+            collect_variant_guards(&condition.node, &mut guards);
+            for (var, variant) in guards {
+                if !block_assigns_var(then_block, &var) {
+                    env.variant_guards.insert(var, variant);
+                }
+            }
+            env.push_scope();
+            let result = check_block(&then_block.node, env, return_type);
+            env.pop_scope();
+            env.variant_guards = saved_guards;
+            result?;
+            if let Some(else_blk) = else_block {
+                env.push_scope();
+                check_block(&else_blk.node, env, return_type)?;
+                env.pop_scope();
+            }
+        }
+        Stmt::IfLet { binding, value, then_block, else_block } => {
+            let inner = nullable_binding_type(value, env)?;
             env.push_scope();
+            env.define(binding.node.clone(), inner, binding.span)?;
+            env.mark_immutable(&binding.node);
             check_block(&then_block.node, env, return_type)?;
             env.pop_scope();
             if let Some(else_blk) = else_block {
@@ -331,6 +508,12 @@ fn check_stmt(
         Stmt::Match { expr, arms } => {
             check_match_stmt(expr, arms, span, env, return_type)?;
         }
+        Stmt::IntMatch { expr, arms } => {
+            check_int_match_stmt(expr, arms, span, env, return_type)?;
+        }
+        Stmt::NullableMatch { expr, some_arm, none_arm } => {
+            check_nullable_match_stmt(expr, some_arm.as_ref(), none_arm.as_ref(), span, env, return_type)?;
+        }
         Stmt::Raise { error_name, fields, .. } => {
             check_raise(error_name, fields, span, env)?;
         }
@@ -457,6 +640,20 @@ fn check_stmt(
         Stmt::Scope { seeds, bindings, body } => {
             check_scope_stmt(seeds, bindings, body, span, env, return_type)?;
         }
+        Stmt::Try { body, var, handler, finally } => {
+            env.push_scope();
+            check_block(&body.node, env, return_type)?;
+            env.pop_scope();
+            env.push_scope();
+            env.define(var.node.clone(), PlutoType::Error, var.span)?;
+            check_block(&handler.node, env, return_type)?;
+            env.pop_scope();
+            if let Some(finally) = finally {
+                env.push_scope();
+                check_block(&finally.node, env, return_type)?;
+                env.pop_scope();
+            }
+        }
         Stmt::Yield { value } => {
             let elem_type = match &env.current_generator_elem {
                 Some(t) => t.clone(),
@@ -801,6 +998,46 @@ pub(super) fn root_variable(expr: &Expr) -> Option<&str> {
     }
 }
 
+/// Variant tests on plain variables in an `if` condition, including each
+/// operand of `&&`: `x is Shape.Circle && y is Shape.Rect` → [(x, Circle), (y, Rect)].
+pub(super) fn collect_variant_guards(cond: &Expr, guards: &mut Vec<(String, String)>) {
+    match cond {
+        Expr::Is { expr, variant, .. } => {
+            if let Expr::Ident(var) = &expr.node {
+                guards.push((var.clone(), variant.node.clone()));
+            }
+        }
+        Expr::BinOp { op: BinOp::And, lhs, rhs } => {
+            collect_variant_guards(&lhs.node, guards);
+            collect_variant_guards(&rhs.node, guards);
+        }
+        _ => {}
+    }
+}
+
+/// Finds `var = ...` anywhere in a block.
+struct AssignFinder<'a> {
+    var: &'a str,
+    found: bool,
+}
+
+impl Visitor for AssignFinder<'_> {
+    fn visit_stmt(&mut self, stmt: &Spanned<Stmt>) {
+        if let Stmt::Assign { target, .. } = &stmt.node
+            && target.node == self.var
+        {
+            self.found = true;
+        }
+        walk_stmt(self, stmt);
+    }
+}
+
+fn block_assigns_var(block: &Spanned<Block>, var: &str) -> bool {
+    let mut finder = AssignFinder { var, found: false };
+    finder.visit_block(block);
+    finder.found
+}
+
 /// Collect all `Expr::Ident` names referenced in a block.
 struct IdentCollector<'a> {
     idents: &'a mut std::collections::HashSet<String>,
@@ -865,8 +1102,8 @@ fn check_field_assign(
                 field.span,
             )
         })?;
-    let val_type = infer_expr(&value.node, value.span, env, None)?;
-    if val_type != field_type {
+    let val_type = infer_expr(&value.node, value.span, env, Some(&field_type))?;
+    if !types_compatible(&val_type, &field_type, env) {
         return Err(CompileError::type_err(
             format!("field '{}': expected {field_type}, found {val_type}", field.node),
             value.span,
@@ -1032,7 +1269,132 @@ fn check_match_stmt(
     Ok(())
 }
 
-fn check_raise(
+fn check_int_match_stmt(
+    expr: &Spanned<Expr>,
+    arms: &[IntMatchArm],
+    span: crate::span::Span,
+    env: &mut TypeEnv,
+    return_type: &PlutoType,
+) -> Result<(), CompileError> {
+    let scrutinee_type = infer_expr(&expr.node, expr.span, env, None)?;
+    if scrutinee_type != PlutoType::Int {
+        return Err(CompileError::type_err(
+            format!("int patterns require an int value, found {scrutinee_type}"),
+            expr.span,
+        ));
+    }
+
+    // Each pattern covers an inclusive interval; i128 keeps `hi + 1` from overflowing
+    let mut covered: Vec<(i128, i128)> = Vec::new();
+    let mut has_wildcard = false;
+    for arm in arms {
+        for pat in &arm.patterns {
+            match &pat.node {
+                IntPattern::Wildcard => has_wildcard = true,
+                IntPattern::Value(e) => {
+                    let v = int_pattern_bound(e, env)? as i128;
+                    covered.push((v, v));
+                }
+                IntPattern::Range { start, end, inclusive } => {
+                    let lo = match start {
+                        Some(e) => int_pattern_bound(e, env)? as i128,
+                        None => i64::MIN as i128,
+                    };
+                    let hi = match end {
+                        Some(e) if *inclusive => int_pattern_bound(e, env)? as i128,
+                        Some(e) => int_pattern_bound(e, env)? as i128 - 1,
+                        None => i64::MAX as i128,
+                    };
+                    if lo > hi {
+                        return Err(CompileError::type_err(
+                            "range pattern matches no values".to_string(),
+                            pat.span,
+                        ));
+                    }
+                    covered.push((lo, hi));
+                }
+            }
+        }
+        env.push_scope();
+        check_block(&arm.body.node, env, return_type)?;
+        env.pop_scope();
+    }
+
+    if !has_wildcard && let Some((lo, hi)) = first_uncovered_int(covered) {
+        let missing = if lo == hi {
+            lo.to_string()
+        } else if hi == i64::MAX as i128 {
+            format!("{lo}..")
+        } else if lo == i64::MIN as i128 {
+            format!("..={hi}")
+        } else {
+            format!("{lo}..={hi}")
+        };
+        return Err(CompileError::type_err(
+            format!("non-exhaustive match: int values {missing} are not covered; add a `_` arm"),
+            span,
+        ));
+    }
+    Ok(())
+}
+
+fn check_nullable_match_stmt(
+    expr: &Spanned<Expr>,
+    some_arm: Option<&SomeArm>,
+    none_arm: Option<&Spanned<Block>>,
+    span: crate::span::Span,
+    env: &mut TypeEnv,
+    return_type: &PlutoType,
+) -> Result<(), CompileError> {
+    let inner = nullable_binding_type(expr, env)?;
+    // Exhaustiveness check
+    let Some(some_arm) = some_arm else {
+        return Err(CompileError::type_err(
+            "non-exhaustive match on nullable value: missing 'some(..)' arm".to_string(),
+            span,
+        ));
+    };
+    let Some(none_arm) = none_arm else {
+        return Err(CompileError::type_err(
+            "non-exhaustive match on nullable value: missing 'none' arm".to_string(),
+            span,
+        ));
+    };
+    env.push_scope();
+    env.define(some_arm.binding.node.clone(), inner, some_arm.binding.span)?;
+    env.mark_immutable(&some_arm.binding.node);
+    check_block(&some_arm.body.node, env, return_type)?;
+    env.pop_scope();
+    env.push_scope();
+    check_block(&none_arm.node, env, return_type)?;
+    env.pop_scope();
+    Ok(())
+}
+
+/// The value of a bound in an int pattern, which must be an int literal or an int const.
+fn int_pattern_bound(e: &Spanned<Expr>, env: &TypeEnv) -> Result<i64, CompileError> {
+    super::const_eval::eval_const_int(&e.node, env).ok_or_else(|| {
+        CompileError::type_err(
+            "int pattern bounds must be int literals or int consts".to_string(),
+            e.span,
+        )
+    })
+}
+
+/// The lowest run of ints not covered by any of the inclusive intervals, if any.
+fn first_uncovered_int(mut covered: Vec<(i128, i128)>) -> Option<(i128, i128)> {
+    covered.sort_unstable();
+    let mut next = i64::MIN as i128;
+    for (lo, hi) in covered {
+        if lo > next {
+            return Some((next, lo - 1));
+        }
+        next = next.max(hi + 1);
+    }
+    (next <= i64::MAX as i128).then_some((next, i64::MAX as i128))
+}
+
+pub(crate) fn check_raise(
     error_name: &Spanned<String>,
     fields: &[(Spanned<String>, Spanned<Expr>)],
     span: crate::span::Span,
@@ -1084,7 +1446,7 @@ pub(crate) fn enforce_mut_self(program: &Program, env: &TypeEnv) -> Result<(), C
         let class_name = &c.name.node;
         for method in &c.methods {
             let m = &method.node;
-            if m.params.is_empty() || m.params[0].name.node != "self" {
+            if !m.type_params.is_empty() || m.params.is_empty() || m.params[0].name.node != "self" {
                 continue;
             }
             if m.params[0].is_mut {
@@ -1173,7 +1535,7 @@ impl Visitor for SelfMutationChecker<'_> {
                     return;
                 }
             }
-            Stmt::Let { value, .. } => {
+            Stmt::Let { value, .. } | Stmt::LetStruct { value, .. } => {
                 if let Err(e) = check_expr_for_mut_method_call(&value.node, value.span, self.class_name, self.env) {
                     self.error = Some(e);
                     return;
@@ -1231,7 +1593,14 @@ fn check_expr_for_mut_method_call(
             // Recurse into object
             check_expr_for_mut_method_call(&object.node, object.span, class_name, env)?;
         }
-        Expr::Propagate { expr: inner } | Expr::Cast { expr: inner, .. } | Expr::Spawn { call: inner } => {
+        Expr::Propagate { expr: inner }
+        | Expr::Cast { expr: inner, .. }
+        | Expr::Is { expr: inner, .. }
+        | Expr::Spawn { call: inner } => {
+            check_expr_for_mut_method_call(&inner.node, inner.span, class_name, env)?;
+        }
+        Expr::PropagateMap { expr: inner, .. } => {
+            // The mapper is a closure — same as Closure below, not recursed into
             check_expr_for_mut_method_call(&inner.node, inner.span, class_name, env)?;
         }
         Expr::Catch { expr: inner, handlers } => {
@@ -1303,6 +1672,17 @@ mod tests {
         Spanned { node, span: mk_span() }
     }
 
+    // ---- first_uncovered_int tests ----
+
+    #[test]
+    fn first_uncovered_int_finds_gaps() {
+        let (min, max) = (i64::MIN as i128, i64::MAX as i128);
+        assert_eq!(first_uncovered_int(vec![(min, -1), (0, 8), (9, max)]), None);
+        assert_eq!(first_uncovered_int(vec![(5, max), (min, 0)]), Some((1, 4)));
+        assert_eq!(first_uncovered_int(vec![(0, 0), (1, 1)]), Some((min, -1)));
+        assert_eq!(first_uncovered_int(vec![(min, 10), (0, 3)]), Some((11, max)));
+    }
+
     // ---- root_variable tests ----
 
     #[test]
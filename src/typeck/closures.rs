@@ -7,7 +7,7 @@ use crate::visit::{walk_expr, Visitor};
 use super::env::TypeEnv;
 use super::types::PlutoType;
 use super::resolve::resolve_type;
-use super::infer::infer_expr;
+use super::infer::{infer_expr, infer_let_init};
 use super::check::check_block;
 
 pub(crate) fn infer_closure(
@@ -66,6 +66,14 @@ pub(crate) fn infer_closure(
     let mut seen = HashSet::new();
     collect_free_vars_block(&body.node, &param_names, outer_depth, env, &mut captures, &mut seen);
 
+    // A captured guard could outlive the block that releases its lock
+    if let Some((name, _)) = captures.iter().find(|(_, ty)| matches!(ty, PlutoType::MutexGuard(_))) {
+        return Err(CompileError::type_err(
+            format!("closure cannot capture MutexGuard '{name}'"),
+            span,
+        ));
+    }
+
     // Store captures keyed by span
     env.closure_captures.insert((span.start, span.end), captures.clone());
 
@@ -100,7 +108,7 @@ fn infer_closure_return_type(block: &Block, env: &mut TypeEnv) -> Result<PlutoTy
                 let hint = ty.as_ref()
                     .map(|t| resolve_type(t, env))
                     .transpose()?;
-                let val_type = infer_expr(&value.node, value.span, env, hint.as_ref())?;
+                let val_type = infer_let_init(value, env, hint.as_ref())?;
                 if let Some(declared_ty) = ty {
                     let expected = resolve_type(declared_ty, env)?;
                     env.define_unchecked(name.node.clone(), expected);
@@ -108,6 +116,17 @@ fn infer_closure_return_type(block: &Block, env: &mut TypeEnv) -> Result<PlutoTy
                     env.define_unchecked(name.node.clone(), val_type);
                 }
             }
+            Stmt::LetStruct { ty, fields, .. } => {
+                if let PlutoType::Class(class_name) = resolve_type(ty, env)? {
+                    let class_fields = env.classes.get(&class_name).map(|c| c.fields.clone()).unwrap_or_default();
+                    for (field, rename) in fields {
+                        let local = rename.as_ref().unwrap_or(field);
+                        if let Some((_, field_type, _)) = class_fields.iter().find(|(n, _, _)| *n == field.node) {
+                            env.define_unchecked(local.node.clone(), field_type.clone());
+                        }
+                    }
+                }
+            }
             Stmt::LetChan { sender, receiver, elem_type, .. } => {
                 let resolved = resolve_type(elem_type, env)?;
                 env.define_unchecked(sender.node.clone(), PlutoType::Sender(Box::new(resolved.clone())));
@@ -149,6 +168,17 @@ impl Visitor for FreeVarCollector<'_> {
             return;
         }
 
+        // Calling a closure held in an outer variable captures that variable
+        if let Expr::Call { name, .. } = &expr.node
+            && !self.param_names.contains(name.node.as_str())
+            && !self.seen.contains(&name.node)
+            && let Some((ty @ PlutoType::Fn(..), depth)) = self.env.lookup_with_depth(&name.node)
+            && depth < self.outer_depth
+        {
+            self.seen.insert(name.node.clone());
+            self.captures.push((name.node.clone(), ty.clone()));
+        }
+
         // Handle QualifiedAccess panic
         if let Expr::QualifiedAccess { segments } = &expr.node {
             panic!(
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use crate::parser::ast::{BinOp, Expr, UnaryOp};
+
+use super::env::{ConstInfo, TypeEnv};
+
+/// Evaluate an integer expression at compile time. Identifiers resolve to
+/// `const` declarations unless a local variable shadows them. Returns `None`
+/// if the expression is not a compile-time constant, or if evaluating it
+/// would overflow or divide by zero.
+pub(crate) fn eval_const_int(expr: &Expr, env: &TypeEnv) -> Option<i64> {
+    match expr {
+        Expr::IntLit(n) => Some(*n),
+        Expr::Ident(name) if env.lookup(name).is_none() => match env.consts.get(name)?.value {
+            Expr::IntLit(n) => Some(n),
+            _ => None,
+        },
+        Expr::UnaryOp { op, operand } => {
+            let v = eval_const_int(&operand.node, env)?;
+            match op {
+                UnaryOp::Neg => v.checked_neg(),
+                UnaryOp::BitNot => Some(!v),
+                UnaryOp::Not => None,
+            }
+        }
+        Expr::BinOp { op, lhs, rhs } => {
+            let l = eval_const_int(&lhs.node, env)?;
+            let r = eval_const_int(&rhs.node, env)?;
+            int_binop(*op, l, r)
+        }
+        _ => None,
+    }
+}
+
+/// Apply an arithmetic or bitwise operator to two constant ints.
+fn int_binop(op: BinOp, l: i64, r: i64) -> Option<i64> {
+    match op {
+        BinOp::Add => l.checked_add(r),
+        BinOp::Sub => l.checked_sub(r),
+        BinOp::Mul => l.checked_mul(r),
+        BinOp::Div => l.checked_div(r),
+        BinOp::Mod => l.checked_rem(r),
+        BinOp::BitAnd => Some(l & r),
+        BinOp::BitOr => Some(l | r),
+        BinOp::BitXor => Some(l ^ r),
+        BinOp::Shl => u32::try_from(r).ok().and_then(|r| l.checked_shl(r)),
+        BinOp::Shr => u32::try_from(r).ok().and_then(|r| l.checked_shr(r)),
+        BinOp::Eq | BinOp::Neq | BinOp::Lt | BinOp::Gt | BinOp::LtEq | BinOp::GtEq
+        | BinOp::And | BinOp::Or => None,
+    }
+}
+
+/// Evaluate a `const` initializer. `consts` holds the constants declared
+/// before it, which the initializer may reference by name. The result is a
+/// literal expression (int, float, bool or string), or `None` if the
+/// initializer is not a compile-time constant.
+pub(crate) fn eval_const_expr(expr: &Expr, consts: &HashMap<String, ConstInfo>) -> Option<Expr> {
+    match expr {
+        Expr::IntLit(_) | Expr::FloatLit(_) | Expr::BoolLit(_) | Expr::StringLit(_) => Some(expr.clone()),
+        Expr::Ident(name) => consts.get(name).map(|c| c.value.clone()),
+        Expr::UnaryOp { op, operand } => {
+            let v = eval_const_expr(&operand.node, consts)?;
+            match (op, v) {
+                (UnaryOp::Neg, Expr::IntLit(n)) => n.checked_neg().map(Expr::IntLit),
+                (UnaryOp::Neg, Expr::FloatLit(f)) => Some(Expr::FloatLit(-f)),
+                (UnaryOp::BitNot, Expr::IntLit(n)) => Some(Expr::IntLit(!n)),
+                (UnaryOp::Not, Expr::BoolLit(b)) => Some(Expr::BoolLit(!b)),
+                _ => None,
+            }
+        }
+        Expr::BinOp { op, lhs, rhs } => {
+            let l = eval_const_expr(&lhs.node, consts)?;
+            let r = eval_const_expr(&rhs.node, consts)?;
+            match (l, r) {
+                (Expr::IntLit(l), Expr::IntLit(r)) => match op {
+                    BinOp::Eq => Some(Expr::BoolLit(l == r)),
+                    BinOp::Neq => Some(Expr::BoolLit(l != r)),
+                    BinOp::Lt => Some(Expr::BoolLit(l < r)),
+                    BinOp::Gt => Some(Expr::BoolLit(l > r)),
+                    BinOp::LtEq => Some(Expr::BoolLit(l <= r)),
+                    BinOp::GtEq => Some(Expr::BoolLit(l >= r)),
+                    _ => int_binop(*op, l, r).map(Expr::IntLit),
+                },
+                (Expr::FloatLit(l), Expr::FloatLit(r)) => match op {
+                    BinOp::Add => Some(Expr::FloatLit(l + r)),
+                    BinOp::Sub => Some(Expr::FloatLit(l - r)),
+                    BinOp::Mul => Some(Expr::FloatLit(l * r)),
+                    BinOp::Div => Some(Expr::FloatLit(l / r)),
+                    BinOp::Lt => Some(Expr::BoolLit(l < r)),
+                    BinOp::Gt => Some(Expr::BoolLit(l > r)),
+                    BinOp::LtEq => Some(Expr::BoolLit(l <= r)),
+                    BinOp::GtEq => Some(Expr::BoolLit(l >= r)),
+                    _ => None,
+                },
+                (Expr::BoolLit(l), Expr::BoolLit(r)) => match op {
+                    BinOp::And => Some(Expr::BoolLit(l && r)),
+                    BinOp::Or => Some(Expr::BoolLit(l || r)),
+                    BinOp::Eq => Some(Expr::BoolLit(l == r)),
+                    BinOp::Neq => Some(Expr::BoolLit(l != r)),
+                    _ => None,
+                },
+                (Expr::StringLit(l), Expr::StringLit(r)) => match op {
+                    BinOp::Add => Some(Expr::StringLit(l + &r)),
+                    BinOp::Eq => Some(Expr::BoolLit(l == r)),
+                    BinOp::Neq => Some(Expr::BoolLit(l != r)),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::span::{Span, Spanned};
+
+    fn sp(e: Expr) -> Box<Spanned<Expr>> {
+        Box::new(Spanned::new(e, Span::dummy()))
+    }
+
+    fn bin(op: BinOp, l: Expr, r: Expr) -> Expr {
+        Expr::BinOp { op, lhs: sp(l), rhs: sp(r) }
+    }
+
+    #[test]
+    fn literals_and_arithmetic() {
+        let env = TypeEnv::new();
+        assert_eq!(eval_const_int(&Expr::IntLit(4), &env), Some(4));
+        assert_eq!(eval_const_int(&bin(BinOp::Mul, Expr::IntLit(2), Expr::IntLit(8)), &env), Some(16));
+        assert_eq!(
+            eval_const_int(&Expr::UnaryOp { op: UnaryOp::Neg, operand: sp(Expr::IntLit(1)) }, &env),
+            Some(-1)
+        );
+        assert_eq!(eval_const_int(&bin(BinOp::Shl, Expr::IntLit(1), Expr::IntLit(10)), &env), Some(1024));
+    }
+
+    #[test]
+    fn int_exprs_resolve_consts_unless_shadowed() {
+        let mut env = TypeEnv::new();
+        env.consts.insert("NEG".to_string(), ConstInfo { ty: crate::typeck::types::PlutoType::Int, value: Expr::IntLit(-1) });
+        let e = bin(BinOp::Mul, Expr::Ident("NEG".to_string()), Expr::IntLit(3));
+        assert_eq!(eval_const_int(&e, &env), Some(-3));
+        env.push_scope();
+        env.define("NEG".to_string(), crate::typeck::types::PlutoType::Int, Span::dummy()).unwrap();
+        assert_eq!(eval_const_int(&e, &env), None);
+    }
+
+    #[test]
+    fn const_exprs_reference_earlier_consts() {
+        let mut consts = HashMap::new();
+        consts.insert("N".to_string(), ConstInfo { ty: crate::typeck::types::PlutoType::Int, value: Expr::IntLit(8) });
+        let e = bin(BinOp::Mul, Expr::Ident("N".to_string()), Expr::IntLit(2));
+        assert!(matches!(eval_const_expr(&e, &consts), Some(Expr::IntLit(16))));
+        let e = bin(BinOp::Add, Expr::StringLit("a".into()), Expr::StringLit("b".into()));
+        assert!(matches!(eval_const_expr(&e, &consts), Some(Expr::StringLit(s)) if s == "ab"));
+        let e = bin(BinOp::Lt, Expr::FloatLit(1.0), Expr::FloatLit(2.0));
+        assert!(matches!(eval_const_expr(&e, &consts), Some(Expr::BoolLit(true))));
+        assert!(eval_const_expr(&Expr::Ident("M".to_string()), &consts).is_none());
+        assert!(eval_const_expr(&bin(BinOp::Add, Expr::IntLit(1), Expr::FloatLit(1.0)), &consts).is_none());
+    }
+
+    #[test]
+    fn non_constant_or_invalid() {
+        let env = TypeEnv::new();
+        assert_eq!(eval_const_int(&Expr::Ident("n".to_string()), &env), None);
+        assert_eq!(eval_const_int(&bin(BinOp::Div, Expr::IntLit(1), Expr::IntLit(0)), &env), None);
+        assert_eq!(eval_const_int(&bin(BinOp::Add, Expr::IntLit(i64::MAX), Expr::IntLit(1)), &env), None);
+        assert_eq!(eval_const_int(&bin(BinOp::Lt, Expr::IntLit(1), Expr::IntLit(2)), &env), None);
+    }
+}
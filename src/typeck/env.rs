@@ -1,7 +1,7 @@
 use std::collections::{HashMap, HashSet};
 use super::types::PlutoType;
 use crate::diagnostics::CompileError;
-use crate::parser::ast::{ContractClause, Lifecycle, TypeExpr};
+use crate::parser::ast::{BinOp, ContractClause, Expr, Lifecycle, TypeExpr};
 use crate::span::{Span, Spanned};
 use crate::visit::scope_tracker::ScopeTracker;
 
@@ -37,6 +37,8 @@ pub struct EnumInfo {
     /// Temporary storage for raw AST type expressions during registration
     /// Vec of (variant_name, Vec of (field_name, field_type))
     pub variant_type_exprs: Vec<(String, Vec<(String, Spanned<TypeExpr>)>)>,
+    /// Traits from `enum E impl Trait` (conformance-checked, never dynamic).
+    pub impl_traits: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -44,6 +46,14 @@ pub struct ErrorInfo {
     pub fields: Vec<(String, PlutoType)>,
 }
 
+/// A top-level `const` or `global mut`: its type and value (the initial
+/// value, for a global), a literal expression.
+#[derive(Debug, Clone)]
+pub struct ConstInfo {
+    pub ty: PlutoType,
+    pub value: Expr,
+}
+
 #[derive(Debug, Clone)]
 pub struct GenericFuncSig {
     pub type_params: Vec<String>,
@@ -60,6 +70,7 @@ pub struct GenericClassInfo {
     pub methods: Vec<String>,
     pub method_sigs: HashMap<String, FuncSig>,  // method_name → sig (may contain TypeParam)
     pub impl_traits: Vec<String>,
+    pub where_bounds: HashMap<String, Vec<String>>,  // T -> [Trait1, Trait2], gates impl_traits
     pub mut_self_methods: HashSet<String>,
     pub lifecycle: Lifecycle,
 }
@@ -80,6 +91,8 @@ pub struct Instantiation {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum InstKind {
     Function(String),
+    /// A generic method of a non-generic class: (class, method).
+    Method(String, String),
     Class(String),
     Enum(String),
 }
@@ -107,12 +120,29 @@ pub enum MethodResolution {
     ChannelTrySend,
     /// Channel try_recv — fallible (ChannelClosed + ChannelEmpty)
     ChannelTryRecv,
+    /// Channel recv_timeout — fallible (ChannelClosed + ChannelTimeout)
+    ChannelRecvTimeout,
     /// Task.detach() — infallible
     TaskDetach,
     /// Task.cancel() — infallible
     TaskCancel,
 }
 
+/// A method taken as a value (`obj.method` or `Type::method`). Recorded during
+/// type inference; closure lifting turns it into a closure that calls the method.
+#[derive(Debug, Clone)]
+pub struct MethodRefInfo {
+    /// Type the method is called on
+    pub receiver: PlutoType,
+    pub method: String,
+    /// Parameter types, excluding the receiver
+    pub params: Vec<PlutoType>,
+    pub return_type: PlutoType,
+    /// Bound references: the variable the receiver path is rooted at, captured
+    /// when the reference is created. `None` for `Type::method`.
+    pub capture: Option<(String, PlutoType)>,
+}
+
 /// How a field of a scoped class gets its value during a scope block.
 #[derive(Debug, Clone)]
 pub enum FieldWiring {
@@ -143,13 +173,30 @@ pub struct TypeEnv {
     pub builtins: HashSet<String>,
     pub classes: HashMap<String, ClassInfo>,
     pub traits: HashMap<String, TraitInfo>,
+    /// Operator traits that overload operators: those with the prelude's shape,
+    /// not user traits shadowing their names
+    pub operator_traits: HashSet<String>,
     pub enums: HashMap<String, EnumInfo>,
     pub errors: HashMap<String, ErrorInfo>,
+    pub consts: HashMap<String, ConstInfo>,
+    /// Process-wide `global mut` variables
+    pub globals: HashMap<String, ConstInfo>,
     pub extern_fns: HashSet<String>,
     /// Captures for each closure, keyed by (start, end) byte offset of the Expr::Closure node
     pub closure_captures: HashMap<(usize, usize), Vec<(String, PlutoType)>>,
     /// Lifted closure function name → captured variable names and types
     pub closure_fns: HashMap<String, Vec<(String, PlutoType)>>,
+    /// Lifted closure function name → the function whose body it was lifted from
+    pub closure_owners: HashMap<String, String>,
+    /// Method references, keyed by [`TypeEnv::body_key`] of the FieldAccess or MethodRef node
+    pub method_refs: HashMap<(String, Span), MethodRefInfo>,
+    /// Variables narrowed to one variant by an enclosing `if x is Enum.Variant`: name → variant
+    pub variant_guards: HashMap<String, String>,
+    /// Variant payload reads `x.field` under an `is` guard, keyed by [`TypeEnv::body_key`]
+    /// of the field name, mapped to the variant the field belongs to
+    pub variant_field_accesses: HashMap<(String, Span), String>,
+    /// Type each `unreachable()` call stands in for, keyed by [`TypeEnv::body_key`] of its name
+    pub unreachable_types: HashMap<(String, Span), PlutoType>,
     pub app: Option<(String, ClassInfo)>,
     pub stages: Vec<(String, ClassInfo)>,
     pub di_order: Vec<String>,
@@ -166,8 +213,14 @@ pub struct TypeEnv {
     pub generic_rewrites: HashMap<(usize, usize), String>,
     /// Method resolutions recorded during type inference, keyed by (current_fn_mangled_name, method.span.start)
     pub method_resolutions: HashMap<(String, usize), MethodResolution>,
-    /// Built-in call sites that are fallible, keyed by (current_fn_mangled_name, call_name.span.start)
-    pub fallible_builtin_calls: HashSet<(String, usize)>,
+    /// Built-in call sites that are fallible, keyed by (current_fn_mangled_name, call_name.span.start),
+    /// mapped to the error type they raise
+    pub fallible_builtin_calls: HashMap<(String, usize), String>,
+    /// Error type produced by each `map_err` mapper, keyed by (current_fn_mangled_name, mapper.span.start)
+    pub error_mappers: HashMap<(String, usize), String>,
+    /// Bare fallible calls inside a `try` body, keyed by [`TypeEnv::body_key`] of the
+    /// function or method name. Each one propagates to the handler as if written with `!`.
+    pub implicit_propagations: HashSet<(String, Span)>,
     /// Currently being type-checked function's mangled name (set by check_function)
     pub current_fn: Option<String>,
     /// Ambient types declared in the app (for validation)
@@ -220,6 +273,13 @@ pub struct TypeEnv {
     /// Return type of the current function/closure being type-checked.
     /// Used to validate that `?` (null propagation) is only used in functions returning `T?` or `void`.
     pub current_function_return: Option<PlutoType>,
+    /// Start offset of the `let` initializer that may call `Mutex.lock()`.
+    /// Guards are only released at scope exit, so `lock()` is rejected anywhere else.
+    pub guard_let_init: Option<usize>,
+    /// Every generic instantiation requested while recording a body-check unit
+    /// for the incremental cache, including ones that already existed. `None`
+    /// when nothing is recording.
+    pub instantiation_log: Option<Vec<Instantiation>>,
 }
 
 impl Default for TypeEnv {
@@ -233,6 +293,7 @@ impl TypeEnv {
         let mut builtins = HashSet::new();
         builtins.insert("print".to_string());
         builtins.insert("time_ns".to_string());
+        builtins.insert("num_cpus".to_string());
         builtins.insert("abs".to_string());
         builtins.insert("min".to_string());
         builtins.insert("max".to_string());
@@ -246,19 +307,36 @@ impl TypeEnv {
         builtins.insert("tan".to_string());
         builtins.insert("log".to_string());
         builtins.insert("gc_heap_size".to_string());
+        builtins.insert("gc_collect".to_string());
+        builtins.insert("gc_stats".to_string());
         builtins.insert("expect".to_string());
+        builtins.insert("unreachable".to_string());
         builtins.insert("bytes_new".to_string());
+        builtins.insert("args".to_string());
+        builtins.insert("read_line".to_string());
+        builtins.insert("read_all".to_string());
+        builtins.insert("wait_group".to_string());
+        builtins.insert("atomic".to_string());
+        builtins.insert("mutex".to_string());
         Self {
             variables: ScopeTracker::with_initial_scope(),
             functions: HashMap::new(),
             builtins,
             classes: HashMap::new(),
             traits: HashMap::new(),
+            operator_traits: HashSet::new(),
             enums: HashMap::new(),
             errors: HashMap::new(),
+            consts: HashMap::new(),
+            globals: HashMap::new(),
             extern_fns: HashSet::new(),
             closure_captures: HashMap::new(),
             closure_fns: HashMap::new(),
+            closure_owners: HashMap::new(),
+            method_refs: HashMap::new(),
+            variant_guards: HashMap::new(),
+            variant_field_accesses: HashMap::new(),
+            unreachable_types: HashMap::new(),
             app: None,
             stages: Vec::new(),
             di_order: Vec::new(),
@@ -271,7 +349,9 @@ impl TypeEnv {
             generic_rewrites: HashMap::new(),
             method_resolutions: HashMap::new(),
             remote_types: HashSet::new(),
-            fallible_builtin_calls: HashSet::new(),
+            fallible_builtin_calls: HashMap::new(),
+            error_mappers: HashMap::new(),
+            implicit_propagations: HashSet::new(),
             current_fn: None,
             ambient_types: HashSet::new(),
             loop_depth: 0,
@@ -292,6 +372,8 @@ impl TypeEnv {
             generators: HashSet::new(),
             current_generator_elem: None,
             current_function_return: None,
+            guard_let_init: None,
+            instantiation_log: None,
         }
     }
 
@@ -407,6 +489,33 @@ impl TypeEnv {
             .unwrap_or(false)
     }
 
+    /// Whether a class or enum type declares `impl trait_name`.
+    pub fn type_implements_trait(&self, ty: &PlutoType, trait_name: &str) -> bool {
+        match ty {
+            PlutoType::Class(name) => self.class_implements_trait(name, trait_name),
+            PlutoType::Enum(name) => self.enums.get(name).is_some_and(|e| e.impl_traits.iter().any(|t| t == trait_name)),
+            _ => false,
+        }
+    }
+
+    /// Key for a side-table entry recorded while checking the current body.
+    /// Spans alone repeat across files and across generic instances of one body.
+    pub fn body_key(&self, span: Span) -> (String, Span) {
+        (self.current_fn.clone().unwrap_or_default(), span)
+    }
+
+    /// The function whose body `fn_name` was checked as part of: a lifted
+    /// closure was checked inside the function it was written in.
+    pub fn body_owner<'a>(&'a self, fn_name: &'a str) -> &'a str {
+        self.closure_owners.get(fn_name).map_or(fn_name, String::as_str)
+    }
+
+    /// Whether `ty`'s operators dispatch to `trait_name`: it implements the trait
+    /// and the trait is an operator trait.
+    pub fn implements_operator_trait(&self, ty: &PlutoType, trait_name: &str) -> bool {
+        self.operator_traits.contains(trait_name) && self.type_implements_trait(ty, trait_name)
+    }
+
     pub fn is_fn_fallible(&self, name: &str) -> bool {
         self.fn_errors.get(name).is_some_and(|e| !e.is_empty())
     }
@@ -443,6 +552,7 @@ impl TypeEnv {
             Some(MethodResolution::ChannelRecv) => Ok(true),
             Some(MethodResolution::ChannelTrySend) => Ok(true),
             Some(MethodResolution::ChannelTryRecv) => Ok(true),
+            Some(MethodResolution::ChannelRecvTimeout) => Ok(true),
             Some(MethodResolution::TaskDetach) => Ok(false),
             Some(MethodResolution::TaskCancel) => Ok(false),
             None => Err(format!(
@@ -472,6 +582,22 @@ impl TypeEnv {
     }
 }
 
+/// The prelude's operator traits and the method each one's operators call.
+pub const OPERATOR_TRAITS: [(&str, &str); 5] = [("Add", "add"), ("Sub", "sub"), ("Mul", "mul"), ("Eq", "eq"), ("Ord", "compare")];
+
+/// The trait and method an overloadable operator dispatches to when its
+/// operands are a class or enum implementing that trait.
+pub fn operator_trait(op: &BinOp) -> Option<(&'static str, &'static str)> {
+    match op {
+        BinOp::Add => Some(("Add", "add")),
+        BinOp::Sub => Some(("Sub", "sub")),
+        BinOp::Mul => Some(("Mul", "mul")),
+        BinOp::Eq | BinOp::Neq => Some(("Eq", "eq")),
+        BinOp::Lt | BinOp::Gt | BinOp::LtEq | BinOp::GtEq => Some(("Ord", "compare")),
+        _ => None,
+    }
+}
+
 pub fn mangle_method(class_or_app: &str, method: &str) -> String {
     format!("{}${}", class_or_app, method)
 }
@@ -511,6 +637,10 @@ fn mangle_type(ty: &PlutoType) -> String {
         }
         PlutoType::Nullable(inner) => format!("nullable${}", mangle_type(inner)),
         PlutoType::Stream(inner) => format!("stream${}", mangle_type(inner)),
+        PlutoType::WaitGroup => "waitgroup".into(),
+        PlutoType::Atomic => "atomic".into(),
+        PlutoType::Mutex(inner) => format!("mutex${}", mangle_type(inner)),
+        PlutoType::MutexGuard(inner) => format!("mutexguard${}", mangle_type(inner)),
     }
 }
 
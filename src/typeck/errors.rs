@@ -3,8 +3,8 @@ use std::collections::{HashMap, HashSet};
 use crate::diagnostics::CompileError;
 use crate::parser::ast::*;
 use crate::span::Spanned;
-use crate::visit::{walk_expr, Visitor};
-use super::env::{mangle_method, MethodResolution, TypeEnv};
+use crate::visit::{walk_expr, walk_stmt, Visitor};
+use super::env::{mangle_method, MethodResolution, TypeEnv, OPERATOR_TRAITS};
 
 pub(crate) fn infer_error_sets(program: &Program, env: &mut TypeEnv) {
     let mut direct_errors: HashMap<String, HashSet<String>> = HashMap::new();
@@ -24,6 +24,7 @@ pub(crate) fn infer_error_sets(program: &Program, env: &mut TypeEnv) {
         if !class.node.type_params.is_empty() { continue; }
         let class_name = &class.node.name.node;
         for method in &class.node.methods {
+            if !method.node.type_params.is_empty() { continue; }
             let mangled = mangle_method(class_name, &method.node.name.node);
             let (directs, edges) = collect_block_effects(&method.node.body.node, &mangled, env);
             direct_errors.insert(mangled.clone(), directs);
@@ -153,7 +154,7 @@ fn collect_stmt_effects(
                 collect_expr_effects(&val.node, direct_errors, edges, current_fn, env);
             }
         }
-        Stmt::Let { value, .. } => {
+        Stmt::Let { value, .. } | Stmt::LetStruct { value, .. } => {
             collect_expr_effects(&value.node, direct_errors, edges, current_fn, env);
         }
         Stmt::Expr(expr) => {
@@ -175,7 +176,8 @@ fn collect_stmt_effects(
             collect_expr_effects(&index.node, direct_errors, edges, current_fn, env);
             collect_expr_effects(&value.node, direct_errors, edges, current_fn, env);
         }
-        Stmt::If { condition, then_block, else_block } => {
+        Stmt::If { condition, then_block, else_block }
+        | Stmt::IfLet { value: condition, then_block, else_block, .. } => {
             collect_expr_effects(&condition.node, direct_errors, edges, current_fn, env);
             for s in &then_block.node.stmts {
                 collect_stmt_effects(&s.node, direct_errors, edges, current_fn, env);
@@ -206,6 +208,23 @@ fn collect_stmt_effects(
                 }
             }
         }
+        Stmt::IntMatch { expr, arms } => {
+            collect_expr_effects(&expr.node, direct_errors, edges, current_fn, env);
+            for arm in arms {
+                for s in &arm.body.node.stmts {
+                    collect_stmt_effects(&s.node, direct_errors, edges, current_fn, env);
+                }
+            }
+        }
+        Stmt::NullableMatch { expr, some_arm, none_arm } => {
+            collect_expr_effects(&expr.node, direct_errors, edges, current_fn, env);
+            let arm_blocks = some_arm.iter().map(|arm| &arm.body).chain(none_arm);
+            for block in arm_blocks {
+                for s in &block.node.stmts {
+                    collect_stmt_effects(&s.node, direct_errors, edges, current_fn, env);
+                }
+            }
+        }
         Stmt::LetChan { capacity, .. } => {
             if let Some(cap) = capacity {
                 collect_expr_effects(&cap.node, direct_errors, edges, current_fn, env);
@@ -254,6 +273,19 @@ fn collect_stmt_effects(
             collect_expr_effects(&service.node, direct_errors, edges, current_fn, env);
             collect_expr_effects(&port.node, direct_errors, edges, current_fn, env);
         }
+        // Everything raised or propagated inside the body lands in the handler,
+        // so only the handler's and the finally block's own effects escape to
+        // the enclosing fn.
+        Stmt::Try { body, handler, finally, .. } => {
+            let mut caught_direct = HashSet::new();
+            let mut caught_edges = HashSet::new();
+            for s in &body.node.stmts {
+                collect_stmt_effects(&s.node, &mut caught_direct, &mut caught_edges, current_fn, env);
+            }
+            for s in handler.node.stmts.iter().chain(finally.iter().flat_map(|f| &f.node.stmts)) {
+                collect_stmt_effects(&s.node, direct_errors, edges, current_fn, env);
+            }
+        }
         Stmt::Yield { value, .. } => {
             collect_expr_effects(&value.node, direct_errors, edges, current_fn, env);
         }
@@ -272,12 +304,11 @@ fn collect_expr_effects(
         Expr::Propagate { expr: inner } => {
             match &inner.node {
                 Expr::Call { name, args, .. } => {
-                    if name.node == "pow"
-                        && env
-                            .fallible_builtin_calls
-                            .contains(&(current_fn.to_string(), name.span.start))
+                    if let Some(error_name) = env
+                        .fallible_builtin_calls
+                        .get(&(current_fn.to_string(), name.span.start))
                     {
-                        direct_errors.insert("MathError".to_string());
+                        direct_errors.insert(error_name.clone());
                     } else {
                         edges.insert(name.node.clone());
                     }
@@ -338,6 +369,10 @@ fn collect_expr_effects(
                             direct_errors.insert("ChannelClosed".to_string());
                             direct_errors.insert("ChannelEmpty".to_string());
                         }
+                        Some(MethodResolution::ChannelRecvTimeout) => {
+                            direct_errors.insert("ChannelClosed".to_string());
+                            direct_errors.insert("ChannelTimeout".to_string());
+                        }
                         Some(MethodResolution::TaskDetach) => {}
                         Some(MethodResolution::TaskCancel) => {}
                         Some(MethodResolution::Builtin) => {}
@@ -347,6 +382,28 @@ fn collect_expr_effects(
                 _ => collect_expr_effects(&inner.node, direct_errors, edges, current_fn, env),
             }
         }
+        Expr::PropagateMap { expr: inner, mapper } => {
+            // The call's own errors never escape: the mapper replaces them with
+            // its declared error type, so only the receiver/arguments contribute.
+            match &inner.node {
+                Expr::Call { args, .. } => {
+                    for arg in args {
+                        collect_expr_effects(&arg.node, direct_errors, edges, current_fn, env);
+                    }
+                }
+                Expr::MethodCall { object, args, .. } => {
+                    collect_expr_effects(&object.node, direct_errors, edges, current_fn, env);
+                    for arg in args {
+                        collect_expr_effects(&arg.node, direct_errors, edges, current_fn, env);
+                    }
+                }
+                _ => collect_expr_effects(&inner.node, direct_errors, edges, current_fn, env),
+            }
+            if let Some(error_name) = env.error_mappers.get(&(current_fn.to_string(), mapper.span.start)) {
+                direct_errors.insert(error_name.clone());
+            }
+            collect_expr_effects(&mapper.node, direct_errors, edges, current_fn, env);
+        }
         Expr::Catch { expr: inner, handlers } => {
             match &inner.node {
                 Expr::Call { args, .. } => {
@@ -382,7 +439,7 @@ fn collect_expr_effects(
         Expr::UnaryOp { operand, .. } => {
             collect_expr_effects(&operand.node, direct_errors, edges, current_fn, env);
         }
-        Expr::Cast { expr: inner, .. } => {
+        Expr::Cast { expr: inner, .. } | Expr::Is { expr: inner, .. } => {
             collect_expr_effects(&inner.node, direct_errors, edges, current_fn, env);
         }
         Expr::Call { args, .. } => {
@@ -495,24 +552,58 @@ fn collect_expr_effects(
             )
         }
         Expr::IntLit(_) | Expr::FloatLit(_) | Expr::BoolLit(_) | Expr::StringLit(_)
-        | Expr::Ident(_) | Expr::EnumUnit { .. } | Expr::ClosureCreate { .. } | Expr::NoneLit => {}
+        | Expr::Ident(_) | Expr::EnumUnit { .. } | Expr::ClosureCreate { .. } | Expr::NoneLit
+        | Expr::MethodRef { .. } => {}
     }
 }
 
 // ── Phase 2c: Error handling enforcement ──────────────────────────────────────
 
-pub(crate) fn enforce_error_handling(program: &Program, env: &TypeEnv) -> Result<(), CompileError> {
+/// Operators have no syntax for handling errors, so the methods behind an
+/// operator trait (`Add`, `Eq`, `Ord`, ...) must not raise.
+fn enforce_infallible_operators(program: &Program, env: &TypeEnv) -> Result<(), CompileError> {
+    let impls = program.classes.iter()
+        .filter(|c| c.node.type_params.is_empty())
+        .map(|c| (&c.node.name.node, &c.node.impl_traits))
+        .chain(program.enums.iter()
+            .filter(|e| e.node.type_params.is_empty())
+            .map(|e| (&e.node.name.node, &e.node.impl_traits)));
+    for (type_name, impl_traits) in impls {
+        for trait_name in impl_traits {
+            let Some((_, method)) = OPERATOR_TRAITS.iter().find(|(t, _)| *t == trait_name.node) else {
+                continue;
+            };
+            if !env.operator_traits.contains(&trait_name.node) {
+                continue;
+            }
+            if env.is_fn_fallible(&mangle_method(type_name, method)) {
+                return Err(CompileError::type_err(
+                    format!(
+                        "method '{method}' of '{type_name}' can raise an error, but operator trait '{}' methods must not; handle the error inside the method",
+                        trait_name.node
+                    ),
+                    trait_name.span,
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn enforce_error_handling(program: &Program, env: &mut TypeEnv) -> Result<(), CompileError> {
+    enforce_infallible_operators(program, env)?;
     for func in &program.functions {
         if !func.node.type_params.is_empty() { continue; }
         let current_fn = func.node.name.node.clone();
-        enforce_block(&func.node.body.node, &current_fn, env)?;
+        enforce_body(&func.node.body.node, &current_fn, env)?;
     }
     for class in &program.classes {
         if !class.node.type_params.is_empty() { continue; }
         let class_name = &class.node.name.node;
         for method in &class.node.methods {
+            if !method.node.type_params.is_empty() { continue; }
             let current_fn = mangle_method(class_name, &method.node.name.node);
-            enforce_block(&method.node.body.node, &current_fn, env)?;
+            enforce_body(&method.node.body.node, &current_fn, env)?;
         }
     }
     for class in &program.classes {
@@ -526,7 +617,7 @@ pub(crate) fn enforce_error_handling(program: &Program, env: &TypeEnv) -> Result
                     for tm in &trait_decl.node.methods {
                         if let Some(body) = &tm.body && !class_method_names.contains(&tm.name.node) {
                             let current_fn = mangle_method(class_name, &tm.name.node);
-                            enforce_block(&body.node, &current_fn, env)?;
+                            enforce_body(&body.node, &current_fn, env)?;
                         }
                     }
                 }
@@ -537,7 +628,7 @@ pub(crate) fn enforce_error_handling(program: &Program, env: &TypeEnv) -> Result
         let app_name = &app_spanned.node.name.node;
         for method in &app_spanned.node.methods {
             let current_fn = mangle_method(app_name, &method.node.name.node);
-            enforce_block(&method.node.body.node, &current_fn, env)?;
+            enforce_body(&method.node.body.node, &current_fn, env)?;
         }
     }
     // Enforce error handling in stage methods
@@ -545,12 +636,30 @@ pub(crate) fn enforce_error_handling(program: &Program, env: &TypeEnv) -> Result
         let stage_name = &stage_spanned.node.name.node;
         for method in &stage_spanned.node.methods {
             let current_fn = mangle_method(stage_name, &method.node.name.node);
-            enforce_block(&method.node.body.node, &current_fn, env)?;
+            enforce_body(&method.node.body.node, &current_fn, env)?;
         }
     }
     Ok(())
 }
 
+/// Enforce one function or method body, first recording which of its calls
+/// propagate implicitly because they sit bare inside a `try` body.
+fn enforce_body(block: &Block, current_fn: &str, env: &mut TypeEnv) -> Result<(), CompileError> {
+    let mut finder = ImplicitPropagationFinder {
+        current_fn,
+        env,
+        try_depth: 0,
+        handled: HashSet::new(),
+        sites: Vec::new(),
+    };
+    for stmt in &block.stmts {
+        finder.visit_stmt(stmt);
+    }
+    let sites = finder.sites;
+    env.implicit_propagations.extend(sites);
+    enforce_block(block, current_fn, env)
+}
+
 fn enforce_block(block: &Block, current_fn: &str, env: &TypeEnv) -> Result<(), CompileError> {
     for stmt in &block.stmts {
         enforce_stmt(&stmt.node, stmt.span, current_fn, env)?;
@@ -565,7 +674,7 @@ fn enforce_stmt(
     env: &TypeEnv,
 ) -> Result<(), CompileError> {
     match stmt {
-        Stmt::Let { value, .. } => enforce_expr(&value.node, value.span, current_fn, env),
+        Stmt::Let { value, .. } | Stmt::LetStruct { value, .. } => enforce_expr(&value.node, value.span, current_fn, env),
         Stmt::Expr(expr) => enforce_expr(&expr.node, expr.span, current_fn, env),
         Stmt::Return(Some(expr)) => enforce_expr(&expr.node, expr.span, current_fn, env),
         Stmt::Return(None) => Ok(()),
@@ -579,7 +688,8 @@ fn enforce_stmt(
             enforce_expr(&index.node, index.span, current_fn, env)?;
             enforce_expr(&value.node, value.span, current_fn, env)
         }
-        Stmt::If { condition, then_block, else_block } => {
+        Stmt::If { condition, then_block, else_block }
+        | Stmt::IfLet { value: condition, then_block, else_block, .. } => {
             enforce_expr(&condition.node, condition.span, current_fn, env)?;
             enforce_block(&then_block.node, current_fn, env)?;
             if let Some(eb) = else_block {
@@ -602,6 +712,23 @@ fn enforce_stmt(
             }
             Ok(())
         }
+        Stmt::IntMatch { expr, arms } => {
+            enforce_expr(&expr.node, expr.span, current_fn, env)?;
+            for arm in arms {
+                enforce_block(&arm.body.node, current_fn, env)?;
+            }
+            Ok(())
+        }
+        Stmt::NullableMatch { expr, some_arm, none_arm } => {
+            enforce_expr(&expr.node, expr.span, current_fn, env)?;
+            if let Some(arm) = some_arm {
+                enforce_block(&arm.body.node, current_fn, env)?;
+            }
+            if let Some(nb) = none_arm {
+                enforce_block(&nb.node, current_fn, env)?;
+            }
+            Ok(())
+        }
         Stmt::Raise { fields, .. } => {
             for (_, val) in fields {
                 enforce_expr(&val.node, val.span, current_fn, env)?;
@@ -648,6 +775,14 @@ fn enforce_stmt(
             enforce_expr(&port.node, port.span, current_fn, env)?;
             Ok(())
         }
+        Stmt::Try { body, handler, finally, .. } => {
+            enforce_block(&body.node, current_fn, env)?;
+            enforce_block(&handler.node, current_fn, env)?;
+            if let Some(finally) = finally {
+                enforce_block(&finally.node, current_fn, env)?;
+            }
+            Ok(())
+        }
         Stmt::Yield { value, .. } => {
             enforce_expr(&value.node, value.span, current_fn, env)?;
             Ok(())
@@ -667,11 +802,11 @@ fn enforce_expr(
             for arg in args {
                 enforce_expr(&arg.node, arg.span, current_fn, env)?;
             }
-            let is_fallible_pow = name.node == "pow"
-                && env
-                    .fallible_builtin_calls
-                    .contains(&(current_fn.to_string(), name.span.start));
-            if is_fallible_pow || env.is_fn_fallible(&name.node) {
+            let is_fallible_builtin = env
+                .fallible_builtin_calls
+                .contains_key(&(current_fn.to_string(), name.span.start));
+            let is_implicit = env.implicit_propagations.contains(&(current_fn.to_string(), name.span));
+            if (is_fallible_builtin || env.is_fn_fallible(&name.node)) && !is_implicit {
                 return Err(CompileError::type_err(
                     format!(
                         "call to fallible function '{}' must be handled with ! or catch",
@@ -689,7 +824,8 @@ fn enforce_expr(
             }
             let is_fallible = env.resolve_method_fallibility(current_fn, method.span.start)
                 .map_err(|msg| CompileError::type_err(msg, method.span))?;
-            if is_fallible {
+            let is_implicit = env.implicit_propagations.contains(&(current_fn.to_string(), method.span));
+            if is_fallible && !is_implicit {
                 return Err(CompileError::type_err(
                     format!("call to fallible method '{}' must be handled with ! or catch", method.node),
                     span,
@@ -697,54 +833,21 @@ fn enforce_expr(
             }
             Ok(())
         }
-        Expr::Propagate { expr: inner } => match &inner.node {
-            Expr::Call { name, args, .. } => {
-                for arg in args {
-                    enforce_expr(&arg.node, arg.span, current_fn, env)?;
-                }
-                let is_fallible_pow = name.node == "pow"
-                    && env
-                        .fallible_builtin_calls
-                        .contains(&(current_fn.to_string(), name.span.start));
-                if !is_fallible_pow && !env.is_fn_fallible(&name.node) {
-                    return Err(CompileError::type_err(
-                        format!("'!' applied to infallible function '{}'", name.node),
-                        span,
-                    ));
-                }
-                Ok(())
-            }
-            Expr::MethodCall { object, method, args } => {
-                enforce_expr(&object.node, object.span, current_fn, env)?;
-                for arg in args {
-                    enforce_expr(&arg.node, arg.span, current_fn, env)?;
-                }
-                let is_fallible = env.resolve_method_fallibility(current_fn, method.span.start)
-                    .map_err(|msg| CompileError::type_err(msg, method.span))?;
-                if !is_fallible {
-                    return Err(CompileError::type_err(
-                        format!("'!' applied to infallible method '{}'", method.node),
-                        span,
-                    ));
-                }
-                Ok(())
-            }
-            _ => Err(CompileError::type_err(
-                "! can only be applied to function calls",
-                inner.span,
-            )),
-        },
+        Expr::Propagate { expr: inner } => enforce_propagated_call(inner, span, current_fn, env),
+        Expr::PropagateMap { expr: inner, mapper } => {
+            enforce_propagated_call(inner, span, current_fn, env)?;
+            enforce_expr(&mapper.node, mapper.span, current_fn, env)
+        }
         Expr::Catch { expr: inner, handlers } => {
             match &inner.node {
                 Expr::Call { name, args, .. } => {
                     for arg in args {
                         enforce_expr(&arg.node, arg.span, current_fn, env)?;
                     }
-                    let is_fallible_pow = name.node == "pow"
-                        && env
-                            .fallible_builtin_calls
-                            .contains(&(current_fn.to_string(), name.span.start));
-                    if !is_fallible_pow && !env.is_fn_fallible(&name.node) {
+                    let is_fallible_builtin = env
+                        .fallible_builtin_calls
+                        .contains_key(&(current_fn.to_string(), name.span.start));
+                    if !is_fallible_builtin && !env.is_fn_fallible(&name.node) {
                         return Err(CompileError::type_err(
                             format!("catch applied to infallible function '{}'", name.node),
                             span,
@@ -811,14 +914,23 @@ fn enforce_expr(
             enforce_expr(&rhs.node, rhs.span, current_fn, env)
         }
         Expr::UnaryOp { operand, .. } => enforce_expr(&operand.node, operand.span, current_fn, env),
-        Expr::Cast { expr: inner, .. } => enforce_expr(&inner.node, inner.span, current_fn, env),
+        Expr::Cast { expr: inner, .. } | Expr::Is { expr: inner, .. } => {
+            enforce_expr(&inner.node, inner.span, current_fn, env)
+        }
         Expr::StructLit { fields, .. } => {
             for (_, val) in fields {
                 enforce_expr(&val.node, val.span, current_fn, env)?;
             }
             Ok(())
         }
-        Expr::FieldAccess { object, .. } => enforce_expr(&object.node, object.span, current_fn, env),
+        Expr::FieldAccess { object, field } => {
+            enforce_expr(&object.node, object.span, current_fn, env)?;
+            if env.method_refs.contains_key(&(current_fn.to_string(), span)) {
+                enforce_method_ref(field, current_fn, env)?;
+            }
+            Ok(())
+        }
+        Expr::MethodRef { method, .. } => enforce_method_ref(method, current_fn, env),
         Expr::ArrayLit { elements } => {
             for e in elements {
                 enforce_expr(&e.node, e.span, current_fn, env)?;
@@ -927,6 +1039,321 @@ fn enforce_expr(
     }
 }
 
+/// A method reference becomes a plain closure, which has nowhere to send an
+/// error, so the referenced method must be infallible.
+fn enforce_method_ref(
+    method: &Spanned<String>,
+    current_fn: &str,
+    env: &TypeEnv,
+) -> Result<(), CompileError> {
+    let is_fallible = env.resolve_method_fallibility(current_fn, method.span.start)
+        .map_err(|msg| CompileError::type_err(msg, method.span))?;
+    if is_fallible {
+        return Err(CompileError::type_err(
+            format!(
+                "cannot reference fallible method '{}' as a value; wrap the call in a closure that handles its errors",
+                method.node
+            ),
+            method.span,
+        ));
+    }
+    Ok(())
+}
+
+/// The operand of `!` (with or without `map_err`) must be a fallible call.
+fn enforce_propagated_call(
+    inner: &Spanned<Expr>,
+    span: crate::span::Span,
+    current_fn: &str,
+    env: &TypeEnv,
+) -> Result<(), CompileError> {
+    match &inner.node {
+        Expr::Call { name, args, .. } => {
+            for arg in args {
+                enforce_expr(&arg.node, arg.span, current_fn, env)?;
+            }
+            let is_fallible_builtin = env
+                .fallible_builtin_calls
+                .contains_key(&(current_fn.to_string(), name.span.start));
+            if !is_fallible_builtin && !env.is_fn_fallible(&name.node) {
+                return Err(CompileError::type_err(
+                    format!("'!' applied to infallible function '{}'", name.node),
+                    span,
+                ));
+            }
+            Ok(())
+        }
+        Expr::MethodCall { object, method, args } => {
+            enforce_expr(&object.node, object.span, current_fn, env)?;
+            for arg in args {
+                enforce_expr(&arg.node, arg.span, current_fn, env)?;
+            }
+            let is_fallible = env.resolve_method_fallibility(current_fn, method.span.start)
+                .map_err(|msg| CompileError::type_err(msg, method.span))?;
+            if !is_fallible {
+                return Err(CompileError::type_err(
+                    format!("'!' applied to infallible method '{}'", method.node),
+                    span,
+                ));
+            }
+            Ok(())
+        }
+        _ => Err(CompileError::type_err(
+            "! can only be applied to function calls",
+            inner.span,
+        )),
+    }
+}
+
+/// Check inferred error sets against the sets declared in signatures
+/// (`fn parse(s: string) int | ParseError`). A function may only raise or
+/// propagate errors it declares; a class method implementing a trait method
+/// with a declared set is bounded by the trait's declaration as well.
+pub(crate) fn enforce_declared_errors(program: &Program, env: &TypeEnv) -> Result<(), CompileError> {
+    for func in &program.functions {
+        if !func.node.type_params.is_empty() { continue; }
+        let name = &func.node.name.node;
+        check_declared_errors(&func.node, name, name, &func.node.declared_errors, None, env)?;
+    }
+    for class in &program.classes {
+        if !class.node.type_params.is_empty() { continue; }
+        let class_name = &class.node.name.node;
+        for method in &class.node.methods {
+            if !method.node.type_params.is_empty() { continue; }
+            let mangled = mangle_method(class_name, &method.node.name.node);
+            let label = format!("{class_name}.{}", method.node.name.node);
+            check_declared_errors(&method.node, &mangled, &label, &method.node.declared_errors, None, env)?;
+        }
+    }
+    if let Some(app_spanned) = &program.app {
+        let app_name = &app_spanned.node.name.node;
+        for method in &app_spanned.node.methods {
+            let mangled = mangle_method(app_name, &method.node.name.node);
+            let label = format!("{app_name}.{}", method.node.name.node);
+            check_declared_errors(&method.node, &mangled, &label, &method.node.declared_errors, None, env)?;
+        }
+    }
+    for stage_spanned in &program.stages {
+        let stage_name = &stage_spanned.node.name.node;
+        for method in &stage_spanned.node.methods {
+            let mangled = mangle_method(stage_name, &method.node.name.node);
+            let label = format!("{stage_name}.{}", method.node.name.node);
+            check_declared_errors(&method.node, &mangled, &label, &method.node.declared_errors, None, env)?;
+        }
+    }
+
+    // Trait methods: validate the declared names, then bound every implementation
+    for trait_decl in &program.traits {
+        for tm in &trait_decl.node.methods {
+            let Some(declared) = &tm.declared_errors else { continue };
+            validate_declared_names(declared, env)?;
+            for class in &program.classes {
+                if !class.node.type_params.is_empty()
+                    || !class.node.impl_traits.iter().any(|t| t.node == trait_decl.node.name.node)
+                {
+                    continue;
+                }
+                let class_name = &class.node.name.node;
+                let mangled = mangle_method(class_name, &tm.name.node);
+                let label = format!("{class_name}.{}", tm.name.node);
+                let trait_name = trait_decl.node.name.node.as_str();
+                if let Some(method) = class.node.methods.iter().find(|m| m.node.name.node == tm.name.node) {
+                    check_declared_errors(&method.node, &mangled, &label, &tm.declared_errors, Some(trait_name), env)?;
+                } else if let Some(body) = &tm.body {
+                    // Inherited default body: report at the trait's method
+                    let undeclared = undeclared_errors(&mangled, declared, env);
+                    if let Some(first) = undeclared.first() {
+                        let (span, err) = find_undeclared_site(&body.node, &undeclared, &mangled, env)
+                            .unwrap_or((tm.name.span, first.clone()));
+                        return Err(undeclared_error(&label, &err, declared, Some(trait_name), span));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check one function body against a declared error set. `trait_name` is set
+/// when the declaration comes from a trait method the function implements.
+fn check_declared_errors(
+    func: &Function,
+    mangled: &str,
+    label: &str,
+    declared: &Option<Vec<Spanned<String>>>,
+    trait_name: Option<&str>,
+    env: &TypeEnv,
+) -> Result<(), CompileError> {
+    let Some(declared) = declared else { return Ok(()) };
+    if trait_name.is_none() {
+        validate_declared_names(declared, env)?;
+    }
+    let undeclared = undeclared_errors(mangled, declared, env);
+    if let Some(first) = undeclared.first() {
+        let (span, err) = find_undeclared_site(&func.body.node, &undeclared, mangled, env)
+            .unwrap_or((func.name.span, first.clone()));
+        return Err(undeclared_error(label, &err, declared, trait_name, span));
+    }
+    Ok(())
+}
+
+fn validate_declared_names(declared: &[Spanned<String>], env: &TypeEnv) -> Result<(), CompileError> {
+    for err in declared {
+        if !env.errors.contains_key(&err.node) {
+            return Err(CompileError::type_err(
+                format!("unknown error type '{}'", err.node),
+                err.span,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Inferred errors of `mangled` missing from `declared`, sorted for stable diagnostics.
+fn undeclared_errors(mangled: &str, declared: &[Spanned<String>], env: &TypeEnv) -> Vec<String> {
+    let mut undeclared: Vec<String> = env.fn_errors.get(mangled)
+        .map(|errs| errs.iter()
+            .filter(|e| !declared.iter().any(|d| d.node == **e))
+            .cloned()
+            .collect())
+        .unwrap_or_default();
+    undeclared.sort();
+    undeclared
+}
+
+fn undeclared_error(
+    label: &str,
+    err: &str,
+    declared: &[Spanned<String>],
+    trait_name: Option<&str>,
+    span: crate::span::Span,
+) -> CompileError {
+    let declared_list = declared.iter().map(|d| d.node.as_str()).collect::<Vec<_>>().join(" | ");
+    let msg = match trait_name {
+        Some(t) => format!(
+            "'{label}' can raise {err}, but trait '{t}' only declares {declared_list}"
+        ),
+        None => format!(
+            "'{label}' can raise {err}, but its signature only declares {declared_list}"
+        ),
+    };
+    CompileError::type_err(msg, span)
+}
+
+/// Find the first `raise` or `!` in a body that lets one of `undeclared` escape,
+/// returning its span and the error it lets through.
+fn find_undeclared_site(
+    block: &Block,
+    undeclared: &[String],
+    current_fn: &str,
+    env: &TypeEnv,
+) -> Option<(crate::span::Span, String)> {
+    let mut finder = UndeclaredSiteFinder { undeclared, current_fn, env, found: None };
+    for stmt in &block.stmts {
+        finder.visit_stmt(stmt);
+    }
+    finder.found
+}
+
+struct UndeclaredSiteFinder<'a> {
+    undeclared: &'a [String],
+    current_fn: &'a str,
+    env: &'a TypeEnv,
+    found: Option<(crate::span::Span, String)>,
+}
+
+impl Visitor for UndeclaredSiteFinder<'_> {
+    fn visit_stmt(&mut self, stmt: &Spanned<Stmt>) {
+        if self.found.is_some() { return; }
+        if let Stmt::Raise { error_name, .. } = &stmt.node
+            && self.undeclared.contains(&error_name.node)
+        {
+            self.found = Some((stmt.span, error_name.node.clone()));
+            return;
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+        if self.found.is_some() { return; }
+        match &expr.node {
+            // Closure bodies have their own error sets
+            Expr::Closure { .. } => return,
+            Expr::Propagate { expr: inner } => {
+                let errs = inner_error_set(&inner.node, self.current_fn, self.env);
+                if let Some(err) = self.undeclared.iter().find(|e| errs.contains(*e)) {
+                    self.found = Some((expr.span, err.clone()));
+                    return;
+                }
+            }
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
+
+/// Finds the fallible calls inside `try` bodies that are neither propagated
+/// with `!` nor caught. Such a call propagates to the innermost handler as if
+/// it were written with `!`. Closure bodies start outside any `try`.
+struct ImplicitPropagationFinder<'a> {
+    current_fn: &'a str,
+    env: &'a TypeEnv,
+    try_depth: usize,
+    /// Spans of calls that are the operand of `!` or `catch`
+    handled: HashSet<crate::span::Span>,
+    sites: Vec<(String, crate::span::Span)>,
+}
+
+impl Visitor for ImplicitPropagationFinder<'_> {
+    fn visit_stmt(&mut self, stmt: &Spanned<Stmt>) {
+        if let Stmt::Try { body, handler, finally, .. } = &stmt.node {
+            self.try_depth += 1;
+            self.visit_block(body);
+            self.try_depth -= 1;
+            self.visit_block(handler);
+            if let Some(finally) = finally {
+                self.visit_block(finally);
+            }
+            return;
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+        match &expr.node {
+            Expr::Closure { .. } => {
+                let depth = std::mem::take(&mut self.try_depth);
+                walk_expr(self, expr);
+                self.try_depth = depth;
+                return;
+            }
+            Expr::Propagate { expr: inner }
+            | Expr::PropagateMap { expr: inner, .. }
+            | Expr::Catch { expr: inner, .. } => {
+                self.handled.insert(inner.span);
+            }
+            Expr::Call { name, .. } if self.try_depth > 0 && !self.handled.contains(&expr.span) => {
+                let is_fallible_builtin = self.env
+                    .fallible_builtin_calls
+                    .contains_key(&(self.current_fn.to_string(), name.span.start));
+                if is_fallible_builtin || self.env.is_fn_fallible(&name.node) {
+                    self.sites.push((self.current_fn.to_string(), name.span));
+                }
+            }
+            // Resolution errors are reported by enforcement
+            Expr::MethodCall { method, .. }
+                if self.try_depth > 0
+                    && !self.handled.contains(&expr.span)
+                    && self.env.resolve_method_fallibility(self.current_fn, method.span.start) == Ok(true) =>
+            {
+                self.sites.push((self.current_fn.to_string(), method.span));
+            }
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+}
+
 /// Visitor that detects Expr::Propagate nodes in an expression tree.
 struct PropagateDetector {
     found: bool,
@@ -934,7 +1361,7 @@ struct PropagateDetector {
 
 impl Visitor for PropagateDetector {
     fn visit_expr(&mut self, expr: &Spanned<Expr>) {
-        if matches!(expr.node, Expr::Propagate { .. }) {
+        if matches!(expr.node, Expr::Propagate { .. } | Expr::PropagateMap { .. }) {
             self.found = true;
             // No need to recurse once found (optimization)
             return;
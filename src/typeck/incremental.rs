@@ -0,0 +1,203 @@
+//! Incremental body checking.
+//!
+//! Checking a function body only reads the signatures registered before it and
+//! writes side tables keyed by spans in that body (method resolutions, closure
+//! captures, generic rewrites, ...). So each body is a unit: with a
+//! [`BodyCache`], the facts a unit wrote are recorded, and on the next check a
+//! unit whose files the caller marked reusable replays them instead of running.
+//! Replaying in the original order leaves the `TypeEnv` exactly as a full
+//! check would. Deciding which files are reusable (unchanged, and no
+//! dependency's signatures changed) is the caller's job.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::diagnostics::CompileError;
+use crate::span::Span;
+use super::env::{InstKind, Instantiation, MethodRefInfo, MethodResolution, ScopeResolution, TypeEnv};
+use super::resolve::{
+    ensure_generic_class_instantiated, ensure_generic_enum_instantiated,
+    ensure_generic_func_instantiated, ensure_generic_method_instantiated,
+};
+use super::types::PlutoType;
+
+/// Identifies one body-checking unit across runs: a function, method,
+/// inherited default method, class invariant list or trait contract list.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct UnitKey {
+    pub kind: &'static str,
+    pub name: String,
+    pub span: Span,
+}
+
+/// The side-table entries one unit wrote.
+#[derive(Debug, Clone, Default)]
+struct BodyFacts {
+    closure_captures: HashMap<(usize, usize), Vec<(String, PlutoType)>>,
+    method_refs: HashMap<(String, Span), MethodRefInfo>,
+    variant_field_accesses: HashMap<(String, Span), String>,
+    unreachable_types: HashMap<(String, Span), PlutoType>,
+    generic_rewrites: HashMap<(usize, usize), String>,
+    method_resolutions: HashMap<(String, usize), MethodResolution>,
+    fallible_builtin_calls: HashMap<(String, usize), String>,
+    error_mappers: HashMap<(String, usize), String>,
+    spawn_target_fns: HashMap<(usize, usize), String>,
+    closure_return_types: HashMap<(usize, usize), PlutoType>,
+    variable_decls: HashMap<(String, usize), Span>,
+    variable_reads: HashSet<(String, usize)>,
+    scope_resolutions: HashMap<(usize, usize), ScopeResolution>,
+    scope_tainted_closures: HashSet<(usize, usize)>,
+    /// Generic instances the unit asked for, replayed through `ensure_*`
+    instantiations: Vec<Instantiation>,
+}
+
+impl BodyFacts {
+    /// Move the recorded side tables out of `env`, leaving them empty.
+    fn take(env: &mut TypeEnv) -> Self {
+        use std::mem::take;
+        Self {
+            closure_captures: take(&mut env.closure_captures),
+            method_refs: take(&mut env.method_refs),
+            variant_field_accesses: take(&mut env.variant_field_accesses),
+            unreachable_types: take(&mut env.unreachable_types),
+            generic_rewrites: take(&mut env.generic_rewrites),
+            method_resolutions: take(&mut env.method_resolutions),
+            fallible_builtin_calls: take(&mut env.fallible_builtin_calls),
+            error_mappers: take(&mut env.error_mappers),
+            spawn_target_fns: take(&mut env.spawn_target_fns),
+            closure_return_types: take(&mut env.closure_return_types),
+            variable_decls: take(&mut env.variable_decls),
+            variable_reads: take(&mut env.variable_reads),
+            scope_resolutions: take(&mut env.scope_resolutions),
+            scope_tainted_closures: take(&mut env.scope_tainted_closures),
+            instantiations: env.instantiation_log.take().unwrap_or_default(),
+        }
+    }
+
+    /// Put side tables taken with [`BodyFacts::take`] back into `env`.
+    fn restore(self, env: &mut TypeEnv) {
+        env.closure_captures = self.closure_captures;
+        env.method_refs = self.method_refs;
+        env.variant_field_accesses = self.variant_field_accesses;
+        env.unreachable_types = self.unreachable_types;
+        env.generic_rewrites = self.generic_rewrites;
+        env.method_resolutions = self.method_resolutions;
+        env.fallible_builtin_calls = self.fallible_builtin_calls;
+        env.error_mappers = self.error_mappers;
+        env.spawn_target_fns = self.spawn_target_fns;
+        env.closure_return_types = self.closure_return_types;
+        env.variable_decls = self.variable_decls;
+        env.variable_reads = self.variable_reads;
+        env.scope_resolutions = self.scope_resolutions;
+        env.scope_tainted_closures = self.scope_tainted_closures;
+    }
+
+    /// Add these facts to `env`, as if the unit had just been checked.
+    fn replay(&self, env: &mut TypeEnv) {
+        env.closure_captures.extend(self.closure_captures.clone());
+        env.method_refs.extend(self.method_refs.clone());
+        env.variant_field_accesses.extend(self.variant_field_accesses.clone());
+        env.unreachable_types.extend(self.unreachable_types.clone());
+        env.generic_rewrites.extend(self.generic_rewrites.clone());
+        env.method_resolutions.extend(self.method_resolutions.clone());
+        env.fallible_builtin_calls.extend(self.fallible_builtin_calls.clone());
+        env.error_mappers.extend(self.error_mappers.clone());
+        env.spawn_target_fns.extend(self.spawn_target_fns.clone());
+        env.closure_return_types.extend(self.closure_return_types.clone());
+        env.variable_decls.extend(self.variable_decls.clone());
+        env.variable_reads.extend(self.variable_reads.iter().cloned());
+        env.scope_resolutions.extend(self.scope_resolutions.clone());
+        env.scope_tainted_closures.extend(self.scope_tainted_closures.iter().copied());
+        for inst in &self.instantiations {
+            match &inst.kind {
+                InstKind::Function(base) => { ensure_generic_func_instantiated(base, &inst.type_args, env); }
+                InstKind::Method(class, method) => { ensure_generic_method_instantiated(class, method, &inst.type_args, env); }
+                InstKind::Class(base) => { ensure_generic_class_instantiated(base, &inst.type_args, env); }
+                InstKind::Enum(base) => { ensure_generic_enum_instantiated(base, &inst.type_args, env); }
+            }
+        }
+    }
+}
+
+/// Body-check facts kept between type checks of the same program.
+#[derive(Debug, Default)]
+pub struct BodyCache {
+    /// Facts from the last successful check
+    units: HashMap<UnitKey, BodyFacts>,
+    /// Facts recorded by the check in progress
+    recorded: HashMap<UnitKey, BodyFacts>,
+    /// File ids whose units may be replayed
+    reusable: HashSet<u32>,
+    checked: usize,
+    reused: usize,
+    /// Bumped by every commit, so callers can tell whether a check got that far
+    commits: u64,
+}
+
+impl BodyCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow the next check to replay units that belong only to these files.
+    pub fn set_reusable(&mut self, files: HashSet<u32>) {
+        self.reusable = files;
+    }
+
+    /// Number of units the last check actually type-checked.
+    pub fn checked(&self) -> usize {
+        self.checked
+    }
+
+    /// Number of units the last check replayed from the cache.
+    pub fn reused(&self) -> usize {
+        self.reused
+    }
+
+    /// How many checks have committed their facts.
+    pub fn commits(&self) -> u64 {
+        self.commits
+    }
+
+    pub(crate) fn begin(&mut self) {
+        self.recorded.clear();
+        self.checked = 0;
+        self.reused = 0;
+    }
+
+    /// Keep what the check recorded. Only called once it succeeded, so a
+    /// failed check leaves the previous facts in place.
+    pub(crate) fn commit(&mut self) {
+        self.units = std::mem::take(&mut self.recorded);
+        self.commits += 1;
+    }
+
+    /// Check one unit with `check`, or replay it when every file in `owners`
+    /// is reusable and the unit was recorded before.
+    pub(crate) fn unit(
+        &mut self,
+        env: &mut TypeEnv,
+        key: UnitKey,
+        owners: &[u32],
+        check: impl FnOnce(&mut TypeEnv) -> Result<(), CompileError>,
+    ) -> Result<(), CompileError> {
+        if owners.iter().all(|f| self.reusable.contains(f))
+            && let Some(facts) = self.units.get(&key)
+        {
+            facts.replay(env);
+            self.reused += 1;
+            self.recorded.insert(key, facts.clone());
+            return Ok(());
+        }
+
+        let outer = BodyFacts::take(env);
+        env.instantiation_log = Some(Vec::new());
+        let result = check(env);
+        let facts = BodyFacts::take(env);
+        outer.restore(env);
+        facts.replay(env);
+        result?;
+        self.checked += 1;
+        self.recorded.insert(key, facts);
+        Ok(())
+    }
+}
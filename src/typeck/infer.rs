@@ -3,9 +3,10 @@ use std::collections::HashMap;
 use crate::diagnostics::CompileError;
 use crate::parser::ast::*;
 use crate::span::Spanned;
-use super::env::{mangle_method, TypeEnv};
+use super::env::{mangle_method, mangle_name, operator_trait, TypeEnv};
 use super::types::PlutoType;
-use super::resolve::{resolve_type, unify, ensure_generic_func_instantiated, ensure_generic_class_instantiated, ensure_generic_enum_instantiated, validate_type_bounds};
+use super::resolve::{resolve_type, unify, ensure_generic_func_instantiated, ensure_generic_method_instantiated, ensure_generic_class_instantiated, ensure_generic_enum_instantiated, validate_type_bounds};
+use super::check::is_unreachable_call;
 use super::closures::infer_closure;
 use super::types_compatible;
 
@@ -43,11 +44,17 @@ pub(crate) fn infer_expr(
                 env.variable_reads.insert((name.clone(), depth));
             }
             env.lookup(name)
+                .or_else(|| env.consts.get(name).map(|c| &c.ty))
+                .or_else(|| env.globals.get(name).map(|g| &g.ty))
                 .cloned()
-                .ok_or_else(|| CompileError::type_err(
-                    format!("undefined variable '{name}'"),
-                    span,
-                ))
+                .ok_or_else(|| {
+                    let msg = if name == "_" {
+                        "'_' discards a value and cannot be read".to_string()
+                    } else {
+                        format!("undefined variable '{name}'")
+                    };
+                    CompileError::type_err(msg, span)
+                })
         }
         Expr::BinOp { op, lhs, rhs } => infer_binop(op, lhs, rhs, span, env),
         Expr::UnaryOp { op, operand } => {
@@ -72,13 +79,13 @@ pub(crate) fn infer_expr(
                     Ok(PlutoType::Bool)
                 }
                 UnaryOp::BitNot => {
-                    if t != PlutoType::Int {
+                    if t != PlutoType::Int && t != PlutoType::Byte {
                         return Err(CompileError::type_err(
                             format!("cannot apply '~' to type {t}"),
                             span,
                         ));
                     }
-                    Ok(PlutoType::Int)
+                    Ok(t)
                 }
             }
         }
@@ -98,12 +105,43 @@ pub(crate) fn infer_expr(
                 )),
             }
         }
-        Expr::Call { name, args, type_args, .. } => infer_call(name, args, type_args, span, env),
+        Expr::Is { expr: inner, enum_name, variant, .. } => {
+            let inner_type = infer_expr(&inner.node, inner.span, env, None)?;
+            let PlutoType::Enum(actual) = &inner_type else {
+                return Err(CompileError::type_err(
+                    format!("'is' requires an enum value, found {inner_type}"),
+                    inner.span,
+                ));
+            };
+            // Accept exact match, or base generic name match (e.g., "Option" matches "Option$$int")
+            let enum_matches = enum_name.node == *actual
+                || (env.generic_enums.contains_key(&enum_name.node)
+                    && actual.starts_with(&format!("{}$$", enum_name.node)));
+            if !enum_matches {
+                return Err(CompileError::type_err(
+                    format!("'is' tests enum '{}', but the value is enum '{actual}'", enum_name.node),
+                    enum_name.span,
+                ));
+            }
+            let has_variant = env.enums.get(actual)
+                .is_some_and(|info| info.variants.iter().any(|(n, _)| *n == variant.node));
+            if !has_variant {
+                return Err(CompileError::type_err(
+                    format!("enum '{actual}' has no variant '{}'", variant.node),
+                    variant.span,
+                ));
+            }
+            Ok(PlutoType::Bool)
+        }
+        Expr::Call { name, args, type_args, .. } => infer_call(name, args, type_args, span, env, expected),
         Expr::StructLit { name, fields: lit_fields, type_args, .. } => {
             infer_struct_lit(name, lit_fields, type_args, span, env)
         }
         Expr::FieldAccess { object, field } => {
             let obj_type = infer_expr(&object.node, object.span, env, None)?;
+            if let Some(fn_type) = super::method_ref::infer_bound_method_ref(object, &obj_type, field, span, env)? {
+                return Ok(fn_type);
+            }
             match &obj_type {
                 // A typed-catch var is typed as Class(error_name); resolve its
                 // fields from the error declaration.
@@ -139,6 +177,34 @@ pub(crate) fn infer_expr(
                 PlutoType::Error if field.node == "message" && env.errors.contains_key("MathError") => {
                     Ok(PlutoType::String)
                 }
+                // Variant payloads are only readable where an `is` guard has
+                // narrowed the variable to one variant.
+                PlutoType::Enum(enum_name) => {
+                    let narrowed = match &object.node {
+                        Expr::Ident(var) => env.variant_guards.get(var).cloned(),
+                        _ => None,
+                    };
+                    let Some(variant) = narrowed else {
+                        return Err(CompileError::type_err(
+                            format!(
+                                "cannot access field '{}' of enum '{enum_name}' without checking its variant; \
+                                 use `if x is {enum_name}.Variant {{ ... }}` or match",
+                                field.node
+                            ),
+                            field.span,
+                        ));
+                    };
+                    let field_type = env.enums.get(enum_name)
+                        .and_then(|info| info.variants.iter().find(|(n, _)| *n == variant))
+                        .and_then(|(_, fields)| fields.iter().find(|(n, _)| *n == field.node))
+                        .map(|(_, t)| t.clone())
+                        .ok_or_else(|| CompileError::type_err(
+                            format!("variant '{enum_name}.{variant}' has no field '{}'", field.node),
+                            field.span,
+                        ))?;
+                    env.variant_field_accesses.insert(env.body_key(field.span), variant);
+                    Ok(field_type)
+                }
                 _ => Err(CompileError::type_err(
                     format!("field access on non-class type {obj_type}"),
                     object.span,
@@ -179,6 +245,13 @@ pub(crate) fn infer_expr(
         }
         Expr::Index { object, index } => {
             let obj_type = infer_expr(&object.node, object.span, env, None)?;
+            // Slicing with a range (`s[1..3]`, `arr[0..=2]`) keeps the container type
+            if let Expr::Range { .. } = &index.node
+                && matches!(obj_type, PlutoType::Array(_) | PlutoType::String)
+            {
+                infer_expr(&index.node, index.span, env, None)?;
+                return Ok(obj_type);
+            }
             match &obj_type {
                 PlutoType::Array(elem) => {
                     let idx_type = infer_expr(&index.node, index.span, env, None)?;
@@ -238,6 +311,14 @@ pub(crate) fn infer_expr(
             let inner_type = infer_expr(&expr.node, expr.span, env, None)?;
             Ok(inner_type)
         }
+        Expr::PropagateMap { expr, mapper } => {
+            let inner_type = infer_expr(&expr.node, expr.span, env, None)?;
+            let error_name = infer_error_mapper(mapper, env)?;
+            if let Some(current_fn) = &env.current_fn {
+                env.error_mappers.insert((current_fn.clone(), mapper.span.start), error_name);
+            }
+            Ok(inner_type)
+        }
         Expr::Catch { expr, handlers } => infer_catch(expr, handlers, span, env),
         Expr::MethodCall { object, method, args } => {
             infer_method_call(object, method, args, span, env)
@@ -388,6 +469,9 @@ pub(crate) fn infer_expr(
                 )),
             }
         }
+        Expr::MethodRef { type_name, method } => {
+            super::method_ref::infer_unbound_method_ref(type_name, method, span, env)
+        }
         Expr::StaticTraitCall { trait_name, method_name, type_args, args } => {
             // Look up the trait and clone the information we need
             let (method_sig, is_static) = {
@@ -481,6 +565,20 @@ pub(crate) fn infer_expr(
             let else_type = infer_block_type(&else_block.node, env)?;
             env.pop_scope();
 
+            // A branch ending in `unreachable()` takes the other branch's type
+            if let Some(Stmt::Expr(e)) = then_block.node.stmts.last().map(|s| &s.node)
+                && is_unreachable_call(&e.node)
+            {
+                record_unreachable_type(&e.node, &else_type, env);
+                return Ok(else_type);
+            }
+            if let Some(Stmt::Expr(e)) = else_block.node.stmts.last().map(|s| &s.node)
+                && is_unreachable_call(&e.node)
+            {
+                record_unreachable_type(&e.node, &then_type, env);
+                return Ok(then_type);
+            }
+
             // Unify branch types
             unify_branch_types(
                 &then_type,
@@ -602,7 +700,7 @@ pub(crate) fn infer_expr(
                 }
 
                 // Infer arm value type
-                let arm_type = infer_expr(&arm.value.node, arm.value.span, env, None)?;
+                let arm_type = infer_expr(&arm.value.node, arm.value.span, env, expected)?;
                 arm_types.push((arm_type, arm.value.span));
 
                 env.pop_scope();
@@ -618,9 +716,18 @@ pub(crate) fn infer_expr(
                 }
             }
 
-            // All arms must unify to same type
-            let first_type = &arm_types[0].0;
-            for (arm_type, arm_span) in &arm_types[1..] {
+            // All arms must unify to same type. `unreachable()` arms take the
+            // type of the others.
+            let diverges: Vec<bool> = arms.iter().map(|arm| is_unreachable_call(&arm.value.node)).collect();
+            let first_type = arm_types.iter().zip(&diverges)
+                .find(|(_, d)| !**d)
+                .map_or(&arm_types[0].0, |((t, _), _)| t)
+                .clone();
+            for (arm, _) in arms.iter().zip(&diverges).filter(|(_, d)| **d) {
+                record_unreachable_type(&arm.value.node, &first_type, env);
+            }
+            let first_type = &first_type;
+            for ((arm_type, arm_span), _) in arm_types.iter().zip(&diverges).filter(|(_, d)| !**d) {
                 if !types_compatible(arm_type, first_type, env) {
                     return Err(CompileError::type_err(
                         format!(
@@ -643,6 +750,14 @@ pub(crate) fn infer_expr(
     }
 }
 
+/// Pin the type an `unreachable()` call stands in for once its surroundings
+/// have decided it.
+fn record_unreachable_type(expr: &Expr, ty: &PlutoType, env: &mut TypeEnv) {
+    if let Expr::Call { name, .. } = expr {
+        env.unreachable_types.insert(env.body_key(name.span), ty.clone());
+    }
+}
+
 fn validate_hashable_key(ty: &PlutoType, span: crate::span::Span) -> Result<(), CompileError> {
     match ty {
         PlutoType::Int | PlutoType::Float | PlutoType::Bool | PlutoType::String | PlutoType::Enum(_) | PlutoType::Byte => Ok(()),
@@ -661,7 +776,46 @@ fn infer_binop(
     env: &mut TypeEnv,
 ) -> Result<PlutoType, CompileError> {
     let lt = infer_expr(&lhs.node, lhs.span, env, None)?;
-    let rt = infer_expr(&rhs.node, rhs.span, env, None)?;
+    // `unreachable()` on the right stands in for a value of the left's type
+    let rhs_expected = if is_unreachable_call(&rhs.node) { Some(&lt) } else { None };
+    // `x is Enum.Variant && x.field ...` — the right side only runs once the
+    // left side holds, so it sees the same narrowing as an `if` body.
+    let rt = if *op == BinOp::And {
+        let saved_guards = env.variant_guards.clone();
+        let mut guards = Vec::new();
+        super::check::collect_variant_guards(&lhs.node, &mut guards);
+        env.variant_guards.extend(guards);
+        let rt = infer_expr(&rhs.node, rhs.span, env, rhs_expected);
+        env.variant_guards = saved_guards;
+        rt?
+    } else {
+        infer_expr(&rhs.node, rhs.span, env, rhs_expected)?
+    };
+
+    // Operators on classes and enums dispatch to their operator trait
+    if let (PlutoType::Class(_) | PlutoType::Enum(_), Some((trait_name, method))) = (&lt, operator_trait(op)) {
+        if env.implements_operator_trait(&lt, trait_name) {
+            if lt != rt {
+                return Err(CompileError::type_err(
+                    format!("operand type mismatch: {lt} vs {rt}"),
+                    span,
+                ));
+            }
+            return Ok(match op {
+                BinOp::Add | BinOp::Sub | BinOp::Mul => lt,
+                _ => PlutoType::Bool,
+            });
+        }
+        // Without `Eq`, `==` and `!=` keep comparing identity; a user trait
+        // shadowing an operator trait's name leaves the operator unsupported
+        if trait_name != "Eq" && env.operator_traits.contains(trait_name) {
+            let kind = if trait_name == "Ord" { "comparison" } else { "operator" };
+            return Err(CompileError::type_err(
+                format!("{kind} not supported for type {lt}; implement trait '{trait_name}' (method '{method}')"),
+                span,
+            ));
+        }
+    }
 
     match op {
         BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Mod => {
@@ -726,13 +880,25 @@ fn infer_binop(
             Ok(PlutoType::Bool)
         }
         BinOp::BitAnd | BinOp::BitOr | BinOp::BitXor | BinOp::Shl | BinOp::Shr => {
-            if lt != PlutoType::Int || rt != PlutoType::Int {
-                return Err(CompileError::type_err(
+            let is_shift = matches!(op, BinOp::Shl | BinOp::Shr);
+            match (&lt, &rt) {
+                (PlutoType::Int, PlutoType::Int) => Ok(PlutoType::Int),
+                // Bytes stay bytes; a shift amount may be given as an int
+                (PlutoType::Byte, PlutoType::Byte) => Ok(PlutoType::Byte),
+                (PlutoType::Byte, PlutoType::Int) if is_shift => Ok(PlutoType::Byte),
+                (PlutoType::Byte, _) if is_shift => Err(CompileError::type_err(
+                    format!("shift amount must be int or byte, found {rt}"),
+                    span,
+                )),
+                (PlutoType::Byte, _) | (_, PlutoType::Byte) => Err(CompileError::type_err(
+                    format!("bitwise operators on byte require byte operands, found {lt} and {rt}"),
+                    span,
+                )),
+                _ => Err(CompileError::type_err(
                     format!("bitwise operators require int operands, found {lt} and {rt}"),
                     span,
-                ));
+                )),
             }
-            Ok(PlutoType::Int)
         }
     }
 }
@@ -743,6 +909,7 @@ fn infer_call(
     call_type_args: &[Spanned<TypeExpr>],
     span: crate::span::Span,
     env: &mut TypeEnv,
+    expected: Option<&PlutoType>,
 ) -> Result<PlutoType, CompileError> {
     // Reject explicit type args on builtins
     if !call_type_args.is_empty() && env.builtins.contains(&name.node) {
@@ -779,7 +946,7 @@ fn infer_call(
                 }
                 Ok(PlutoType::Void)
             }
-            "time_ns" | "gc_heap_size" => {
+            "time_ns" | "num_cpus" | "gc_heap_size" => {
                 if !args.is_empty() {
                     return Err(CompileError::type_err(
                         format!("{}() expects 0 arguments, got {}", name.node, args.len()),
@@ -788,6 +955,24 @@ fn infer_call(
                 }
                 Ok(PlutoType::Int)
             }
+            "gc_collect" => {
+                if !args.is_empty() {
+                    return Err(CompileError::type_err(
+                        format!("gc_collect() expects 0 arguments, got {}", args.len()),
+                        span,
+                    ));
+                }
+                Ok(PlutoType::Void)
+            }
+            "gc_stats" => {
+                if !args.is_empty() {
+                    return Err(CompileError::type_err(
+                        format!("gc_stats() expects 0 arguments, got {}", args.len()),
+                        span,
+                    ));
+                }
+                Ok(PlutoType::Class("GcStats".to_string()))
+            }
             "bytes_new" => {
                 if !args.is_empty() {
                     return Err(CompileError::type_err(
@@ -797,6 +982,74 @@ fn infer_call(
                 }
                 Ok(PlutoType::Bytes)
             }
+            "args" => {
+                if !args.is_empty() {
+                    return Err(CompileError::type_err(
+                        format!("args() expects 0 arguments, got {}", args.len()),
+                        span,
+                    ));
+                }
+                Ok(PlutoType::Array(Box::new(PlutoType::String)))
+            }
+            "read_line" | "read_all" => {
+                if !args.is_empty() {
+                    return Err(CompileError::type_err(
+                        format!("{}() expects 0 arguments, got {}", name.node, args.len()),
+                        span,
+                    ));
+                }
+                // Both can fail on a read error; read_line reports EOF as none
+                if let Some(current_fn) = &env.current_fn {
+                    env.fallible_builtin_calls
+                        .insert((current_fn.clone(), name.span.start), "IOError".to_string());
+                }
+                Ok(if name.node == "read_line" {
+                    PlutoType::Nullable(Box::new(PlutoType::String))
+                } else {
+                    PlutoType::String
+                })
+            }
+            "wait_group" => {
+                if !args.is_empty() {
+                    return Err(CompileError::type_err(
+                        format!("wait_group() expects 0 arguments, got {}", args.len()),
+                        span,
+                    ));
+                }
+                Ok(PlutoType::WaitGroup)
+            }
+            "atomic" => {
+                if args.len() != 1 {
+                    return Err(CompileError::type_err(
+                        format!("atomic() expects 1 argument, got {}", args.len()),
+                        span,
+                    ));
+                }
+                let t = infer_expr(&args[0].node, args[0].span, env, None)?;
+                if t != PlutoType::Int {
+                    return Err(CompileError::type_err(
+                        format!("atomic(): expected int, found {t}"),
+                        args[0].span,
+                    ));
+                }
+                Ok(PlutoType::Atomic)
+            }
+            "mutex" => {
+                if args.len() != 1 {
+                    return Err(CompileError::type_err(
+                        format!("mutex() expects 1 argument, got {}", args.len()),
+                        span,
+                    ));
+                }
+                let t = infer_expr(&args[0].node, args[0].span, env, None)?;
+                if matches!(t, PlutoType::Void | PlutoType::MutexGuard(_)) {
+                    return Err(CompileError::type_err(
+                        format!("mutex(): cannot protect a value of type {t}"),
+                        args[0].span,
+                    ));
+                }
+                Ok(PlutoType::Mutex(Box::new(t)))
+            }
             "abs" => {
                 if args.len() != 1 {
                     return Err(CompileError::type_err(
@@ -855,7 +1108,7 @@ fn infer_call(
                     PlutoType::Int => {
                         if let Some(current_fn) = &env.current_fn {
                             env.fallible_builtin_calls
-                                .insert((current_fn.clone(), name.span.start));
+                                .insert((current_fn.clone(), name.span.start), "MathError".to_string());
                         }
                         Ok(PlutoType::Int)
                     }
@@ -892,6 +1145,27 @@ fn infer_call(
                 let inner_type = infer_expr(&args[0].node, args[0].span, env, None)?;
                 Ok(inner_type)  // passthrough — returns the inner type directly
             }
+            "unreachable" => {
+                if args.len() > 1 {
+                    return Err(CompileError::type_err(
+                        format!("unreachable() takes at most 1 argument, got {}", args.len()),
+                        span,
+                    ));
+                }
+                if let Some(msg) = args.first() {
+                    let msg_type = infer_expr(&msg.node, msg.span, env, None)?;
+                    if msg_type != PlutoType::String {
+                        return Err(CompileError::type_err(
+                            format!("unreachable() message must be a string, found {msg_type}"),
+                            msg.span,
+                        ));
+                    }
+                }
+                // Never returns, so it takes whatever type its position asks for
+                let ty = expected.cloned().unwrap_or(PlutoType::Void);
+                env.unreachable_types.insert(env.body_key(name.span), ty.clone());
+                Ok(ty)
+            }
             _ => Err(CompileError::type_err(
                 format!("unknown builtin '{}'", name.node),
                 name.span,
@@ -900,7 +1174,8 @@ fn infer_call(
     }
 
     // Check if calling a closure variable
-    if let Some(PlutoType::Fn(param_types, ret_type)) = env.lookup(&name.node).cloned() {
+    if let Some((PlutoType::Fn(param_types, ret_type), depth)) = env.lookup_with_depth(&name.node).map(|(t, d)| (t.clone(), d)) {
+        env.variable_reads.insert((name.node.clone(), depth));
         if args.len() != param_types.len() {
             return Err(CompileError::type_err(
                 format!(
@@ -963,30 +1238,19 @@ fn infer_call(
             for arg in args {
                 arg_types.push(infer_expr(&arg.node, arg.span, env, None)?);
             }
-            let mut bindings = HashMap::new();
-            for (param_ty, arg_ty) in gen_sig.params.iter().zip(&arg_types) {
-                if !unify(param_ty, arg_ty, &mut bindings) {
-                    return Err(CompileError::type_err(
-                        format!("cannot infer type parameters for '{}'", name.node),
-                        span,
-                    ));
-                }
-            }
-            // Check all type params are bound
-            for tp in &gen_sig.type_params {
-                if !bindings.contains_key(tp) {
-                    return Err(CompileError::type_err(
-                        format!("cannot infer type parameter '{}' for '{}'", tp, name.node),
-                        span,
-                    ));
-                }
-            }
+            let bindings = infer_call_type_args(&name.node, &gen_sig.type_params, &gen_sig.params, &arg_types, args, span)?;
             gen_sig.type_params.iter()
                 .map(|tp| bindings[tp].clone())
                 .collect()
         };
-        // Validate type bounds before instantiation
-        validate_type_bounds(&gen_sig.type_params, &type_args, &gen_sig.type_param_bounds, env, span, &name.node)?;
+        // Validate type bounds before instantiation, pointing at the argument
+        // that bound each type parameter
+        let arg_span_for = |param: &str| {
+            gen_sig.params.iter().zip(args)
+                .find(|(p, _)| mentions_type_param(p, param))
+                .map_or(span, |(_, arg)| arg.span)
+        };
+        validate_type_bounds(&gen_sig.type_params, &type_args, &gen_sig.type_param_bounds, env, &arg_span_for, &name.node)?;
         let mangled = ensure_generic_func_instantiated(&name.node, &type_args, env);
         // Store rewrite
         env.generic_rewrites.insert((span.start, span.end), mangled.clone());
@@ -1042,6 +1306,81 @@ fn infer_call(
     Ok(sig_clone.return_type)
 }
 
+/// Bind a generic function's type parameters by unifying each parameter type
+/// against the concrete argument type.
+///
+/// A binding taken from `none` or an untyped empty collection is only a
+/// placeholder and gives way to a concrete one from a later argument. Errors
+/// name the argument at fault: a parameter bound to two different types, an
+/// argument whose shape cannot match its parameter, or a parameter no
+/// argument mentions.
+fn infer_call_type_args(
+    fn_name: &str,
+    type_params: &[String],
+    params: &[PlutoType],
+    arg_types: &[PlutoType],
+    args: &[Spanned<Expr>],
+    span: crate::span::Span,
+) -> Result<HashMap<String, PlutoType>, CompileError> {
+    let mut bindings: HashMap<String, PlutoType> = HashMap::new();
+    let mut bound_by: HashMap<String, usize> = HashMap::new();
+    for (i, (param_ty, arg_ty)) in params.iter().zip(arg_types).enumerate() {
+        let mut local = HashMap::new();
+        if !unify(param_ty, arg_ty, &mut local) {
+            return Err(CompileError::type_err(
+                format!(
+                    "cannot infer type parameters for '{fn_name}': argument {} expected {param_ty}, found {arg_ty}",
+                    i + 1
+                ),
+                args[i].span,
+            ));
+        }
+        for (tp, ty) in local {
+            match bindings.get(&tp) {
+                Some(existing) if *existing == ty || contains_void(&ty) => {}
+                Some(existing) if contains_void(existing) => {
+                    bindings.insert(tp.clone(), ty);
+                    bound_by.insert(tp, i);
+                }
+                Some(existing) => {
+                    return Err(CompileError::type_err(
+                        format!(
+                            "cannot infer type parameters for '{fn_name}': conflicting types for '{tp}', {existing} from argument {} but {ty} from argument {}",
+                            bound_by[&tp] + 1,
+                            i + 1
+                        ),
+                        args[i].span,
+                    ));
+                }
+                None => {
+                    bindings.insert(tp.clone(), ty);
+                    bound_by.insert(tp, i);
+                }
+            }
+        }
+    }
+    if let Some(tp) = type_params.iter().find(|tp| !bindings.contains_key(*tp)) {
+        return Err(CompileError::type_err(
+            format!(
+                "cannot infer type parameters for '{fn_name}': no argument determines '{tp}'; pass type arguments explicitly, e.g. {fn_name}<...>(...)"
+            ),
+            span,
+        ));
+    }
+    Ok(bindings)
+}
+
+/// Whether a type still carries the placeholder element type of a `none`
+/// literal or an untyped empty collection.
+fn contains_void(ty: &PlutoType) -> bool {
+    match ty {
+        PlutoType::Void => true,
+        PlutoType::Nullable(inner) | PlutoType::Array(inner) | PlutoType::Set(inner) => contains_void(inner),
+        PlutoType::Map(k, v) => contains_void(k) || contains_void(v),
+        _ => false,
+    }
+}
+
 fn infer_struct_lit(
     name: &Spanned<String>,
     lit_fields: &[(Spanned<String>, Spanned<Expr>)],
@@ -1069,7 +1408,7 @@ fn infer_struct_lit(
             .map(|a| resolve_type(a, env))
             .collect::<Result<Vec<_>, _>>()?;
         // Validate type bounds
-        validate_type_bounds(&gen_info.type_params, &resolved_args, &gen_info.type_param_bounds, env, span, &name.node)?;
+        validate_type_bounds(&gen_info.type_params, &resolved_args, &gen_info.type_param_bounds, env, &|_| span, &name.node)?;
         let mangled = ensure_generic_class_instantiated(&name.node, &resolved_args, env);
         env.generic_rewrites.insert((span.start, span.end), mangled.clone());
         let ci = env.classes.get(&mangled)
@@ -1077,6 +1416,11 @@ fn infer_struct_lit(
             .clone();
         (ci, mangled)
     } else {
+        // Error literal (e.g. returned from a `map_err` mapper): validated like a raise
+        if !env.classes.contains_key(&name.node) && env.errors.contains_key(&name.node) {
+            super::check::check_raise(name, lit_fields, span, env)?;
+            return Ok(PlutoType::Class(name.node.clone()));
+        }
         let ci = env.classes.get(&name.node).ok_or_else(|| {
             CompileError::type_err(
                 format!("unknown class '{}'", name.node),
@@ -1164,7 +1508,7 @@ fn infer_enum_unit(
             .map(|a| resolve_type(a, env))
             .collect::<Result<Vec<_>, _>>()?;
         // Validate type bounds
-        validate_type_bounds(&gen_info.type_params, &resolved_args, &gen_info.type_param_bounds, env, span, &enum_name.node)?;
+        validate_type_bounds(&gen_info.type_params, &resolved_args, &gen_info.type_param_bounds, env, &|_| span, &enum_name.node)?;
         let mangled = ensure_generic_enum_instantiated(&enum_name.node, &resolved_args, env);
         env.generic_rewrites.insert((span.start, span.end), mangled.clone());
         let ei = env.enums.get(&mangled)
@@ -1326,7 +1670,7 @@ fn infer_enum_data(
             .map(|a| resolve_type(a, env))
             .collect::<Result<Vec<_>, _>>()?;
         // Validate type bounds
-        validate_type_bounds(&gen_info.type_params, &resolved_args, &gen_info.type_param_bounds, env, span, &enum_name.node)?;
+        validate_type_bounds(&gen_info.type_params, &resolved_args, &gen_info.type_param_bounds, env, &|_| span, &enum_name.node)?;
         let mangled = ensure_generic_enum_instantiated(&enum_name.node, &resolved_args, env);
         env.generic_rewrites.insert((span.start, span.end), mangled.clone());
         let ei = env.enums.get(&mangled)
@@ -1399,6 +1743,27 @@ fn infer_enum_data(
     }
 }
 
+/// Validate a `map_err` mapper: it must be a `fn(error) E` where `E` is a
+/// declared error type. Returns `E`.
+fn infer_error_mapper(
+    mapper: &Spanned<Expr>,
+    env: &mut TypeEnv,
+) -> Result<String, CompileError> {
+    let mapper_type = infer_expr(&mapper.node, mapper.span, env, None)?;
+    if let PlutoType::Fn(params, ret) = &mapper_type
+        && params.len() == 1
+        && params[0] == PlutoType::Error
+        && let PlutoType::Class(error_name) = ret.as_ref()
+        && env.errors.contains_key(error_name)
+    {
+        return Ok(error_name.clone());
+    }
+    Err(CompileError::type_err(
+        format!("map_err expects a closure of type fn(error) E where E is a declared error, found {mapper_type}"),
+        mapper.span,
+    ))
+}
+
 fn infer_catch(
     expr: &Spanned<Expr>,
     handlers: &[CatchHandler],
@@ -1424,7 +1789,7 @@ fn infer_catch(
                 infer_catch_body(var, PlutoType::Class(error_type.node.clone()), body, env)?
             }
             CatchHandler::Shorthand(fallback) => {
-                Some(infer_expr(&fallback.node, fallback.span, env, None)?)
+                Some(infer_expr(&fallback.node, fallback.span, env, Some(&success_type))?)
             }
         };
         // A diverging handler (ends in `return`) imposes no type constraint.
@@ -1751,6 +2116,111 @@ fn infer_method_call(
                 }
                 return Ok(PlutoType::Int);
             }
+            "sort" => {
+                if !args.is_empty() {
+                    return Err(CompileError::type_err(
+                        format!("sort() expects 0 arguments, got {}", args.len()),
+                        span,
+                    ));
+                }
+                let naturally_ordered = matches!(
+                    **elem,
+                    PlutoType::Int | PlutoType::Float | PlutoType::String | PlutoType::Byte | PlutoType::Bool
+                );
+                if !naturally_ordered && !env.implements_operator_trait(elem, "Ord") {
+                    return Err(CompileError::type_err(
+                        format!(
+                            "sort(): {} has no natural order; implement trait 'Ord' or use sort_by() with a comparator",
+                            **elem
+                        ),
+                        method.span,
+                    ));
+                }
+                if let Some(ref current) = env.current_fn {
+                    env.method_resolutions.insert(
+                        (current.clone(), method.span.start),
+                        super::env::MethodResolution::Builtin,
+                    );
+                }
+                return Ok(PlutoType::Void);
+            }
+            "sort_by" => {
+                if args.len() != 1 {
+                    return Err(CompileError::type_err(
+                        format!("sort_by() expects 1 argument, got {}", args.len()),
+                        span,
+                    ));
+                }
+                let comparator = PlutoType::Fn(vec![(**elem).clone(), (**elem).clone()], Box::new(PlutoType::Int));
+                let arg_type = infer_expr(&args[0].node, args[0].span, env, Some(&comparator))?;
+                if arg_type != comparator {
+                    return Err(CompileError::type_err(
+                        format!("sort_by(): expected comparator {comparator}, found {arg_type}"),
+                        args[0].span,
+                    ));
+                }
+                if let Some(ref current) = env.current_fn {
+                    env.method_resolutions.insert(
+                        (current.clone(), method.span.start),
+                        super::env::MethodResolution::Builtin,
+                    );
+                }
+                return Ok(PlutoType::Void);
+            }
+            "join" => {
+                if args.len() != 1 {
+                    return Err(CompileError::type_err(
+                        format!("join() expects 1 argument, got {}", args.len()),
+                        span,
+                    ));
+                }
+                if **elem != PlutoType::String {
+                    return Err(CompileError::type_err(
+                        format!(
+                            "join() requires an array of strings, found {obj_type}; convert the elements with map_to_string() first"
+                        ),
+                        method.span,
+                    ));
+                }
+                let sep_type = infer_expr(&args[0].node, args[0].span, env, None)?;
+                if sep_type != PlutoType::String {
+                    return Err(CompileError::type_err(
+                        format!("join(): expected string separator, found {sep_type}"),
+                        args[0].span,
+                    ));
+                }
+                if let Some(ref current) = env.current_fn {
+                    env.method_resolutions.insert(
+                        (current.clone(), method.span.start),
+                        super::env::MethodResolution::Builtin,
+                    );
+                }
+                return Ok(PlutoType::String);
+            }
+            "map_to_string" => {
+                if !args.is_empty() {
+                    return Err(CompileError::type_err(
+                        format!("map_to_string() expects 0 arguments, got {}", args.len()),
+                        span,
+                    ));
+                }
+                if !matches!(
+                    **elem,
+                    PlutoType::Int | PlutoType::Float | PlutoType::String | PlutoType::Byte | PlutoType::Bool
+                ) {
+                    return Err(CompileError::type_err(
+                        format!("map_to_string(): cannot convert {} to string", **elem),
+                        method.span,
+                    ));
+                }
+                if let Some(ref current) = env.current_fn {
+                    env.method_resolutions.insert(
+                        (current.clone(), method.span.start),
+                        super::env::MethodResolution::Builtin,
+                    );
+                }
+                return Ok(PlutoType::Array(Box::new(PlutoType::String)));
+            }
             _ => {
                 return Err(CompileError::type_err(
                     format!("array has no method '{}'", method.node),
@@ -2007,6 +2477,19 @@ fn infer_method_call(
                 builtin(env, method);
                 return Ok(PlutoType::Void);
             }
+            "push_str" => {
+                if args.len() != 1 {
+                    return Err(CompileError::type_err("push_str() expects 1 argument".to_string(), span));
+                }
+                let arg_type = infer_expr(&args[0].node, args[0].span, env, None)?;
+                if arg_type != PlutoType::String {
+                    return Err(CompileError::type_err(
+                        format!("push_str(): expected string, found {arg_type}"), args[0].span,
+                    ));
+                }
+                builtin(env, method);
+                return Ok(PlutoType::Void);
+            }
             "to_string" => {
                 if !args.is_empty() {
                     return Err(CompileError::type_err("to_string() expects 0 arguments".to_string(), span));
@@ -2083,6 +2566,21 @@ fn infer_method_call(
                 }
                 return Ok(PlutoType::Void);
             }
+            "is_full" => {
+                if !args.is_empty() {
+                    return Err(CompileError::type_err(
+                        format!("is_full() expects 0 arguments, got {}", args.len()),
+                        span,
+                    ));
+                }
+                if let Some(ref current) = env.current_fn {
+                    env.method_resolutions.insert(
+                        (current.clone(), method.span.start),
+                        super::env::MethodResolution::Builtin,
+                    );
+                }
+                return Ok(PlutoType::Bool);
+            }
             _ => {
                 return Err(CompileError::type_err(
                     format!("Sender has no method '{}'", method.node),
@@ -2124,6 +2622,43 @@ fn infer_method_call(
                 }
                 return Ok(*inner.clone());
             }
+            "recv_timeout" => {
+                if args.len() != 1 {
+                    return Err(CompileError::type_err(
+                        format!("recv_timeout() expects 1 argument, got {}", args.len()),
+                        span,
+                    ));
+                }
+                let arg_type = infer_expr(&args[0].node, args[0].span, env, None)?;
+                if arg_type != PlutoType::Int {
+                    return Err(CompileError::type_err(
+                        format!("recv_timeout(): expected int milliseconds, found {arg_type}"),
+                        args[0].span,
+                    ));
+                }
+                if let Some(ref current) = env.current_fn {
+                    env.method_resolutions.insert(
+                        (current.clone(), method.span.start),
+                        super::env::MethodResolution::ChannelRecvTimeout,
+                    );
+                }
+                return Ok(*inner.clone());
+            }
+            "len" => {
+                if !args.is_empty() {
+                    return Err(CompileError::type_err(
+                        format!("len() expects 0 arguments, got {}", args.len()),
+                        span,
+                    ));
+                }
+                if let Some(ref current) = env.current_fn {
+                    env.method_resolutions.insert(
+                        (current.clone(), method.span.start),
+                        super::env::MethodResolution::Builtin,
+                    );
+                }
+                return Ok(PlutoType::Int);
+            }
             _ => {
                 return Err(CompileError::type_err(
                     format!("Receiver has no method '{}'", method.node),
@@ -2132,6 +2667,166 @@ fn infer_method_call(
             }
         }
     }
+    // WaitGroup methods
+    if obj_type == PlutoType::WaitGroup {
+        match method.node.as_str() {
+            "add" => {
+                if args.len() != 1 {
+                    return Err(CompileError::type_err(
+                        format!("add() expects 1 argument, got {}", args.len()),
+                        span,
+                    ));
+                }
+                let arg_type = infer_expr(&args[0].node, args[0].span, env, None)?;
+                if arg_type != PlutoType::Int {
+                    return Err(CompileError::type_err(
+                        format!("add(): expected int, found {arg_type}"),
+                        args[0].span,
+                    ));
+                }
+            }
+            "done" | "wait" => {
+                if !args.is_empty() {
+                    return Err(CompileError::type_err(
+                        format!("{}() expects 0 arguments, got {}", method.node, args.len()),
+                        span,
+                    ));
+                }
+            }
+            _ => {
+                return Err(CompileError::type_err(
+                    format!("WaitGroup has no method '{}'", method.node),
+                    method.span,
+                ));
+            }
+        }
+        if let Some(ref current) = env.current_fn {
+            env.method_resolutions.insert(
+                (current.clone(), method.span.start),
+                super::env::MethodResolution::Builtin,
+            );
+        }
+        return Ok(PlutoType::Void);
+    }
+    // Atomic methods
+    if obj_type == PlutoType::Atomic {
+        let (arity, result) = match method.node.as_str() {
+            "load" => (0, PlutoType::Int),
+            "store" => (1, PlutoType::Void),
+            "add" => (1, PlutoType::Int),
+            "compare_and_swap" => (2, PlutoType::Bool),
+            _ => {
+                return Err(CompileError::type_err(
+                    format!("Atomic has no method '{}'", method.node),
+                    method.span,
+                ));
+            }
+        };
+        if args.len() != arity {
+            return Err(CompileError::type_err(
+                format!(
+                    "{}() expects {arity} argument{}, got {}",
+                    method.node,
+                    if arity == 1 { "" } else { "s" },
+                    args.len()
+                ),
+                span,
+            ));
+        }
+        for arg in args {
+            let arg_type = infer_expr(&arg.node, arg.span, env, None)?;
+            if arg_type != PlutoType::Int {
+                return Err(CompileError::type_err(
+                    format!("{}(): expected int, found {arg_type}", method.node),
+                    arg.span,
+                ));
+            }
+        }
+        if let Some(ref current) = env.current_fn {
+            env.method_resolutions.insert(
+                (current.clone(), method.span.start),
+                super::env::MethodResolution::Builtin,
+            );
+        }
+        return Ok(result);
+    }
+    // Mutex methods
+    if let PlutoType::Mutex(inner) = &obj_type {
+        if method.node != "lock" {
+            return Err(CompileError::type_err(
+                format!("Mutex has no method '{}'", method.node),
+                method.span,
+            ));
+        }
+        if !args.is_empty() {
+            return Err(CompileError::type_err(
+                format!("lock() expects 0 arguments, got {}", args.len()),
+                span,
+            ));
+        }
+        if env.guard_let_init.take() != Some(span.start) {
+            return Err(CompileError::type_err(
+                "the result of lock() must be bound with 'let' so the lock is released at the end of the block",
+                span,
+            ));
+        }
+        if env.current_generator_elem.is_some() {
+            return Err(CompileError::type_err(
+                "lock() is not allowed in generator functions",
+                span,
+            ));
+        }
+        if let Some(ref current) = env.current_fn {
+            env.method_resolutions.insert(
+                (current.clone(), method.span.start),
+                super::env::MethodResolution::Builtin,
+            );
+        }
+        return Ok(PlutoType::MutexGuard(inner.clone()));
+    }
+    // MutexGuard methods
+    if let PlutoType::MutexGuard(inner) = &obj_type {
+        let result = match method.node.as_str() {
+            "get" => {
+                if !args.is_empty() {
+                    return Err(CompileError::type_err(
+                        format!("get() expects 0 arguments, got {}", args.len()),
+                        span,
+                    ));
+                }
+                (**inner).clone()
+            }
+            "set" => {
+                if args.len() != 1 {
+                    return Err(CompileError::type_err(
+                        format!("set() expects 1 argument, got {}", args.len()),
+                        span,
+                    ));
+                }
+                let arg_type = infer_expr(&args[0].node, args[0].span, env, Some(inner))?;
+                if arg_type != **inner {
+                    return Err(CompileError::type_err(
+                        format!("set() expects {}, found {}", inner, arg_type),
+                        args[0].span,
+                    ));
+                }
+                PlutoType::Void
+            }
+            _ => {
+                return Err(CompileError::type_err(
+                    format!("MutexGuard has no method '{}'", method.node),
+                    method.span,
+                ));
+            }
+        };
+        if let Some(ref current) = env.current_fn {
+            env.method_resolutions.insert(
+                (current.clone(), method.span.start),
+                super::env::MethodResolution::Builtin,
+            );
+        }
+        return Ok(result);
+    }
     if obj_type == PlutoType::String {
         let builtin = |env: &mut TypeEnv, method: &Spanned<String>| {
             if let Some(ref current) = env.current_fn {
@@ -2142,7 +2837,7 @@ fn infer_method_call(
             }
         };
         match method.node.as_str() {
-            "len" | "trim" | "to_upper" | "to_lower" => {
+            "len" | "char_count" | "trim" | "to_upper" | "to_lower" => {
                 if !args.is_empty() {
                     return Err(CompileError::type_err(
                         format!("{}() expects 0 arguments", method.node), span,
@@ -2150,7 +2845,7 @@ fn infer_method_call(
                 }
                 builtin(env, method);
                 return Ok(match method.node.as_str() {
-                    "len" => PlutoType::Int,
+                    "len" | "char_count" => PlutoType::Int,
                     _ => PlutoType::String,
                 });
             }
@@ -2251,6 +2946,16 @@ fn infer_method_call(
                 builtin(env, method);
                 return Ok(PlutoType::Array(Box::new(PlutoType::String)));
             }
+            "chars" | "lines" | "bytes" => {
+                if !args.is_empty() {
+                    return Err(CompileError::type_err(
+                        format!("{}() expects 0 arguments", method.node), span,
+                    ));
+                }
+                builtin(env, method);
+                let elem = if method.node == "bytes" { PlutoType::Byte } else { PlutoType::String };
+                return Ok(PlutoType::Array(Box::new(elem)));
+            }
             "to_int" => {
                 if !args.is_empty() {
                     return Err(CompileError::type_err(
@@ -2351,6 +3056,15 @@ fn infer_method_call(
                     method.span,
                 )
             })?;
+        if super::register::mentions_self_type(method_sig) {
+            return Err(CompileError::type_err(
+                format!(
+                    "method '{}' of trait '{trait_name}' uses 'Self', so it cannot be called on a trait object; call it on a concrete type",
+                    method.node
+                ),
+                method.span,
+            ));
+        }
 
         // Check non-self args
         let expected_args = method_sig.params[1..].to_vec();
@@ -2400,8 +3114,10 @@ fn infer_method_call(
         return Ok(method_sig.return_type.clone());
     }
 
+    // Enum methods are lowered to `Enum$method` functions, so they resolve
+    // like class methods.
     let class_name = match &obj_type {
-        PlutoType::Class(name) => name.clone(),
+        PlutoType::Class(name) | PlutoType::Enum(name) => name.clone(),
         _ => {
             return Err(CompileError::type_err(
                 format!("method call on non-class type {obj_type}"),
@@ -2411,6 +3127,20 @@ fn infer_method_call(
     };
 
     let mangled = mangle_method(&class_name, &method.node);
+    // Check caller-side mutability: cannot call mut self method on immutable binding
+    if env.mut_self_methods.contains(&mangled) && let Some(root) = super::check::root_variable(&object.node) && root != "self" && env.is_immutable(root) {
+        return Err(CompileError::type_err(
+            format!(
+                "cannot call mutating method '{}' on immutable variable '{}'; declare with 'let mut' to allow mutation",
+                method.node, root
+            ),
+            method.span,
+        ));
+    }
+    if let Some(gen_sig) = env.generic_functions.get(&mangled).cloned() {
+        let is_enum = matches!(obj_type, PlutoType::Enum(_));
+        return infer_generic_method_call(&class_name, is_enum, &gen_sig, method, args, span, env);
+    }
     if let Some(ref current) = env.current_fn {
         // A call whose receiver type is a stage referenced by a `remote` dep
         // crosses a service boundary — record it as a remote call.
@@ -2424,19 +3154,10 @@ fn infer_method_call(
             resolution,
         );
     }
-    // Check caller-side mutability: cannot call mut self method on immutable binding
-    if env.mut_self_methods.contains(&mangled) && let Some(root) = super::check::root_variable(&object.node) && root != "self" && env.is_immutable(root) {
-        return Err(CompileError::type_err(
-            format!(
-                "cannot call mutating method '{}' on immutable variable '{}'; declare with 'let mut' to allow mutation",
-                method.node, root
-            ),
-            method.span,
-        ));
-    }
     let sig = env.functions.get(&mangled).ok_or_else(|| {
+        let kind = if matches!(obj_type, PlutoType::Enum(_)) { "enum" } else { "class" };
         CompileError::type_err(
-            format!("class '{class_name}' has no method '{}'", method.node),
+            format!("{kind} '{class_name}' has no method '{}'", method.node),
             method.span,
         )
     })?.clone();
@@ -2472,6 +3193,63 @@ fn infer_method_call(
     Ok(sig.return_type.clone())
 }
 
+/// Call a generic method: bind its type parameters from the arguments,
+/// instantiate `Owner$method$$T...`, and record the rewrite monomorphize uses
+/// to point the call at that instance.
+fn infer_generic_method_call(
+    owner: &str,
+    is_enum: bool,
+    gen_sig: &super::env::GenericFuncSig,
+    method: &Spanned<String>,
+    args: &[Spanned<Expr>],
+    span: crate::span::Span,
+    env: &mut TypeEnv,
+) -> Result<PlutoType, CompileError> {
+    // params[0] is self
+    let expected_args = &gen_sig.params[1..];
+    if args.len() != expected_args.len() {
+        return Err(CompileError::type_err(
+            format!(
+                "method '{}' expects {} arguments, got {}",
+                method.node,
+                expected_args.len(),
+                args.len()
+            ),
+            span,
+        ));
+    }
+    let mut arg_types = Vec::new();
+    for arg in args {
+        arg_types.push(infer_expr(&arg.node, arg.span, env, None)?);
+    }
+    let bindings = infer_call_type_args(&method.node, &gen_sig.type_params, expected_args, &arg_types, args, span)?;
+    let type_args: Vec<PlutoType> = gen_sig.type_params.iter()
+        .map(|tp| bindings[tp].clone())
+        .collect();
+    let arg_span_for = |param: &str| {
+        expected_args.iter().zip(args)
+            .find(|(p, _)| mentions_type_param(p, param))
+            .map_or(span, |(_, arg)| arg.span)
+    };
+    validate_type_bounds(&gen_sig.type_params, &type_args, &gen_sig.type_param_bounds, env, &arg_span_for, &method.node)?;
+
+    // Enum methods are already top-level functions, so they instantiate as one
+    let instance = if is_enum {
+        ensure_generic_func_instantiated(&mangle_method(owner, &method.node), &type_args, env)
+    } else {
+        ensure_generic_method_instantiated(owner, &method.node, &type_args, env)
+    };
+    env.generic_rewrites.insert((span.start, span.end), mangle_name(&method.node, &type_args));
+    if let Some(ref current) = env.current_fn {
+        env.method_resolutions.insert(
+            (current.clone(), method.span.start),
+            super::env::MethodResolution::Class { mangled_name: instance.clone() },
+        );
+    }
+    Ok(env.functions.get(&instance)
+        .expect("generic method should be registered after instantiation")
+        .return_type.clone())
+}
 
 /// Infer the type of a block (the last expression, or void)
 fn infer_block_type(
@@ -2531,6 +3309,27 @@ fn infer_block_type(
     }
 }
 
+/// Whether `ty` refers to the type parameter `param` anywhere inside it.
+fn mentions_type_param(ty: &PlutoType, param: &str) -> bool {
+    matches!(ty, PlutoType::TypeParam(name) if name == param)
+        || ty.any_inner_type(&|inner| mentions_type_param(inner, param))
+}
+
+/// Infer the initializer of a `let`. This is the one position where
+/// `Mutex.lock()` may appear, since the guard's block releases the lock.
+pub(crate) fn infer_let_init(
+    value: &Spanned<Expr>,
+    env: &mut TypeEnv,
+    expected: Option<&PlutoType>,
+) -> Result<PlutoType, CompileError> {
+    if matches!(&value.node, Expr::MethodCall { method, .. } if method.node == "lock") {
+        env.guard_let_init = Some(value.span.start);
+    }
+    let result = infer_expr(&value.node, value.span, env, expected);
+    env.guard_let_init = None;
+    result
+}
+
 /// Helper to check statements (needed by infer_block_type)
 fn infer_stmt(stmt: &crate::parser::ast::Stmt, env: &mut TypeEnv) -> Result<(), CompileError> {
     use crate::parser::ast::Stmt;
@@ -2539,7 +3338,7 @@ fn infer_stmt(stmt: &crate::parser::ast::Stmt, env: &mut TypeEnv) -> Result<(),
     // since type checking will validate everything later
     match stmt {
         Stmt::Let { value, .. } => {
-            infer_expr(&value.node, value.span, env, None)?;
+            infer_let_init(value, env, None)?;
             Ok(())
         }
         Stmt::Expr(expr) => {
@@ -0,0 +1,312 @@
+use std::collections::HashSet;
+
+use crate::diagnostics::{CompileWarning, WarningKind};
+use crate::parser::ast::*;
+use crate::span::Spanned;
+use crate::visit::{walk_block, walk_expr, walk_stmt, Visitor};
+use super::env::{mangle_method, MethodResolution, TypeEnv};
+use super::types::PlutoType;
+
+/// Collect the non-fatal diagnostics for a type-checked program: unused
+/// variables, discarded results of fallible calls, unreachable statements,
+/// and spawned tasks that capture `self` of a non-singleton instance.
+/// Names starting with `_` opt out of the first two.
+pub(crate) fn generate_warnings(env: &TypeEnv, program: &Program) -> Vec<CompileWarning> {
+    let mut warnings = Vec::new();
+    unused_variables(env, program, &mut warnings);
+    unused_results(env, program, &mut warnings);
+    unreachable_code(program, &mut warnings);
+    spawn_captures_self(env, program, &mut warnings);
+
+    // Sort for deterministic output
+    warnings.sort_by_key(|w| w.span.start);
+    warnings
+}
+
+fn unused_variables(env: &TypeEnv, program: &Program, warnings: &mut Vec<CompileWarning>) {
+    // Collect function parameter names to exclude from unused-variable warnings
+    let mut param_names = HashSet::new();
+    for func in &program.functions {
+        for p in &func.node.params {
+            param_names.insert(p.name.node.clone());
+        }
+    }
+    if let Some(app) = &program.app {
+        for m in &app.node.methods {
+            for p in &m.node.params {
+                param_names.insert(p.name.node.clone());
+            }
+        }
+    }
+    for stage in &program.stages {
+        for m in &stage.node.methods {
+            for p in &m.node.params {
+                param_names.insert(p.name.node.clone());
+            }
+        }
+    }
+    for class in &program.classes {
+        for method in &class.node.methods {
+            for p in &method.node.params {
+                param_names.insert(p.name.node.clone());
+            }
+        }
+    }
+
+    for ((name, depth), decl_span) in &env.variable_decls {
+        // Skip _-prefixed variables (intentionally unused convention)
+        if name.starts_with('_') {
+            continue;
+        }
+        // Skip function parameters
+        if param_names.contains(name) {
+            continue;
+        }
+        // Skip if variable was read
+        if env.variable_reads.contains(&(name.clone(), *depth)) {
+            continue;
+        }
+        warnings.push(CompileWarning {
+            msg: format!("unused variable '{name}'"),
+            span: *decl_span,
+            kind: WarningKind::UnusedVariable,
+        });
+    }
+}
+
+/// Warn when a statement discards the value of a call that can raise, e.g.
+/// `parse(s)!` on its own line. A `catch` already handles the error, so
+/// `parse(s) catch 0` is not warned. Binding the value to a `_`-prefixed name
+/// marks the discard as deliberate.
+fn unused_results(env: &TypeEnv, program: &Program, warnings: &mut Vec<CompileWarning>) {
+    for func in &program.functions {
+        if !func.node.type_params.is_empty() { continue; }
+        lint_body(&func.node.body.node, &func.node.name.node, env, warnings);
+    }
+    for class in &program.classes {
+        if !class.node.type_params.is_empty() { continue; }
+        let class_name = &class.node.name.node;
+        for method in &class.node.methods {
+            if !method.node.type_params.is_empty() { continue; }
+            let current_fn = mangle_method(class_name, &method.node.name.node);
+            lint_body(&method.node.body.node, &current_fn, env, warnings);
+        }
+    }
+    if let Some(app) = &program.app {
+        let app_name = &app.node.name.node;
+        for method in &app.node.methods {
+            let current_fn = mangle_method(app_name, &method.node.name.node);
+            lint_body(&method.node.body.node, &current_fn, env, warnings);
+        }
+    }
+    for stage in &program.stages {
+        let stage_name = &stage.node.name.node;
+        for method in &stage.node.methods {
+            let current_fn = mangle_method(stage_name, &method.node.name.node);
+            lint_body(&method.node.body.node, &current_fn, env, warnings);
+        }
+    }
+}
+
+fn lint_body(block: &Block, current_fn: &str, env: &TypeEnv, warnings: &mut Vec<CompileWarning>) {
+    let mut linter = UnusedResultLinter { current_fn, env, warnings };
+    for stmt in &block.stmts {
+        linter.visit_stmt(stmt);
+    }
+}
+
+struct UnusedResultLinter<'a> {
+    current_fn: &'a str,
+    env: &'a TypeEnv,
+    warnings: &'a mut Vec<CompileWarning>,
+}
+
+impl UnusedResultLinter<'_> {
+    /// The name of the callee if `call` invokes a fallible function that
+    /// returns a value, i.e. a result the caller is expected to use.
+    fn must_use_callee(&self, call: &Expr) -> Option<String> {
+        let (key, display) = match call {
+            Expr::Call { name, .. } => (name.node.clone(), name.node.clone()),
+            Expr::MethodCall { method, .. } => {
+                let res_key = (self.current_fn.to_string(), method.span.start);
+                match self.env.method_resolutions.get(&res_key) {
+                    Some(MethodResolution::Class { mangled_name }) =>
+                        (mangled_name.clone(), method.node.clone()),
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        if !self.env.is_fn_fallible(&key) {
+            return None;
+        }
+        let sig = self.env.functions.get(&key)?;
+        if sig.return_type == PlutoType::Void {
+            return None;
+        }
+        Some(display)
+    }
+}
+
+impl Visitor for UnusedResultLinter<'_> {
+    fn visit_stmt(&mut self, stmt: &Spanned<Stmt>) {
+        if let Stmt::Expr(expr) = &stmt.node {
+            let call = match &expr.node {
+                Expr::Propagate { expr: inner } | Expr::PropagateMap { expr: inner, .. } => Some(&inner.node),
+                _ => None,
+            };
+            if let Some(name) = call.and_then(|c| self.must_use_callee(c)) {
+                self.warnings.push(CompileWarning {
+                    msg: format!(
+                        "unused result of '{name}', which can raise; use the value or bind it to a '_'-prefixed name"
+                    ),
+                    span: expr.span,
+                    kind: WarningKind::UnusedResult,
+                });
+            }
+        }
+        walk_stmt(self, stmt);
+    }
+}
+
+/// Warn at the first statement of a block that follows a statement control
+/// can never continue past. Mirrors the `terminated` tracking in codegen's
+/// `lower_stmt`, so a warning marks exactly the code that is never emitted.
+fn unreachable_code(program: &Program, warnings: &mut Vec<CompileWarning>) {
+    let mut linter = UnreachableLinter { warnings };
+    for func in &program.functions {
+        linter.visit_block(&func.node.body);
+    }
+    for class in &program.classes {
+        for method in &class.node.methods {
+            linter.visit_block(&method.node.body);
+        }
+    }
+    for trait_decl in &program.traits {
+        for tm in &trait_decl.node.methods {
+            if let Some(body) = &tm.body {
+                linter.visit_block(body);
+            }
+        }
+    }
+    if let Some(app) = &program.app {
+        for method in &app.node.methods {
+            linter.visit_block(&method.node.body);
+        }
+    }
+    for stage in &program.stages {
+        for method in &stage.node.methods {
+            linter.visit_block(&method.node.body);
+        }
+    }
+}
+
+struct UnreachableLinter<'a> {
+    warnings: &'a mut Vec<CompileWarning>,
+}
+
+impl Visitor for UnreachableLinter<'_> {
+    fn visit_block(&mut self, block: &Spanned<Block>) {
+        if let Some(pos) = block.node.stmts.iter().position(|s| stmt_terminates(&s.node))
+            && let Some(dead) = block.node.stmts.get(pos + 1)
+        {
+            self.warnings.push(CompileWarning {
+                msg: "unreachable code".to_string(),
+                span: dead.span,
+                kind: WarningKind::UnreachableCode,
+            });
+        }
+        walk_block(self, block);
+    }
+}
+
+fn block_terminates(block: &Block) -> bool {
+    block.stmts.iter().any(|s| stmt_terminates(&s.node))
+}
+
+/// Whether control never falls through `stmt` to the next statement.
+fn stmt_terminates(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Return(_) | Stmt::Raise { .. } | Stmt::Break | Stmt::Continue | Stmt::Serve { .. } => true,
+        Stmt::Expr(expr) => super::check::is_unreachable_call(&expr.node),
+        Stmt::If { then_block, else_block: Some(else_block), .. }
+        | Stmt::IfLet { then_block, else_block: Some(else_block), .. } =>
+            block_terminates(&then_block.node) && block_terminates(&else_block.node),
+        Stmt::Match { arms, .. } =>
+            !arms.is_empty() && arms.iter().all(|arm| block_terminates(&arm.body.node)),
+        Stmt::IntMatch { arms, .. } =>
+            !arms.is_empty() && arms.iter().all(|arm| block_terminates(&arm.body.node)),
+        Stmt::NullableMatch { some_arm: Some(some_arm), none_arm: Some(none_arm), .. } =>
+            block_terminates(&some_arm.body.node) && block_terminates(&none_arm.node),
+        Stmt::Try { body, handler, finally, .. } =>
+            (block_terminates(&body.node) && block_terminates(&handler.node))
+                || finally.as_ref().is_some_and(|f| block_terminates(&f.node)),
+        Stmt::Select { arms, default: None } =>
+            !arms.is_empty() && arms.iter().all(|arm| block_terminates(&arm.body.node)),
+        _ => false,
+    }
+}
+
+/// Warn when a method of a scoped or transient class spawns a task that
+/// refers to `self`. The task can keep running after the scope that owns the
+/// instance has ended, so it may observe a torn-down object. Singletons live
+/// for the whole program and are exempt. Uses the lifecycle recorded in the
+/// env, which includes lifecycles inferred from scoped dependencies.
+fn spawn_captures_self(env: &TypeEnv, program: &Program, warnings: &mut Vec<CompileWarning>) {
+    for class in &program.classes {
+        let class_name = &class.node.name.node;
+        let lifecycle = env.classes.get(class_name)
+            .map_or(class.node.lifecycle, |info| info.lifecycle);
+        if lifecycle == Lifecycle::Singleton {
+            continue;
+        }
+        let mut linter = SpawnSelfLinter { class_name, lifecycle, warnings };
+        for method in &class.node.methods {
+            linter.visit_block(&method.node.body);
+        }
+    }
+}
+
+struct SpawnSelfLinter<'a> {
+    class_name: &'a str,
+    lifecycle: Lifecycle,
+    warnings: &'a mut Vec<CompileWarning>,
+}
+
+impl Visitor for SpawnSelfLinter<'_> {
+    fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+        if let Expr::Spawn { call } = &expr.node {
+            let mut finder = SelfFinder { found: false };
+            finder.visit_expr(call);
+            if finder.found {
+                self.warnings.push(CompileWarning {
+                    msg: format!(
+                        "spawned task captures 'self' of {} class '{}'; the task may outlive the instance's scope",
+                        self.lifecycle, self.class_name
+                    ),
+                    span: expr.span,
+                    kind: WarningKind::SpawnCapturesScopedSelf,
+                });
+                // One warning per outermost spawn
+                return;
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
+
+/// Finds any reference to `self`, whether the spawn still holds the original
+/// call or has already been desugared into a closure.
+struct SelfFinder {
+    found: bool,
+}
+
+impl Visitor for SelfFinder {
+    fn visit_expr(&mut self, expr: &Spanned<Expr>) {
+        if matches!(&expr.node, Expr::Ident(name) if name == "self") {
+            self.found = true;
+            return;
+        }
+        walk_expr(self, expr);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::diagnostics::CompileError;
+use crate::parser::ast::Expr;
+use crate::span::{Span, Spanned};
+use super::env::{mangle_method, MethodRefInfo, MethodResolution, TypeEnv};
+use super::types::PlutoType;
+
+/// Resolve `object.name` as a bound method reference when `name` names a
+/// method rather than a field of the receiver. Returns `Ok(None)` when it is
+/// not a method reference, leaving the caller to report field errors.
+pub(super) fn infer_bound_method_ref(
+    object: &Spanned<Expr>,
+    obj_type: &PlutoType,
+    field: &Spanned<String>,
+    span: Span,
+    env: &mut TypeEnv,
+) -> Result<Option<PlutoType>, CompileError> {
+    let Some((params, return_type, resolution)) = lookup_method(obj_type, &field.node, env) else {
+        return Ok(None);
+    };
+
+    // The receiver is captured by value, so it has to be a variable path
+    // (`p`, `self.items`) rather than an arbitrary expression.
+    let root = super::check::root_variable(&object.node).ok_or_else(|| {
+        CompileError::type_err(
+            format!(
+                "cannot reference method '{}' on a temporary value; bind the receiver with 'let' first",
+                field.node
+            ),
+            object.span,
+        )
+    })?;
+    let root_type = env.lookup(root).cloned().ok_or_else(|| {
+        CompileError::type_err(format!("undefined variable '{root}'"), object.span)
+    })?;
+
+    if is_mut_self_method(obj_type, &field.node, env) && root != "self" && env.is_immutable(root) {
+        return Err(CompileError::type_err(
+            format!(
+                "cannot reference mutating method '{}' on immutable variable '{root}'; declare with 'let mut' to allow mutation",
+                field.node
+            ),
+            field.span,
+        ));
+    }
+
+    record(env, field.span, span, resolution, MethodRefInfo {
+        receiver: obj_type.clone(),
+        method: field.node.clone(),
+        params: params.clone(),
+        return_type: return_type.clone(),
+        capture: Some((root.to_string(), root_type)),
+    });
+    Ok(Some(PlutoType::Fn(params, Box::new(return_type))))
+}
+
+/// Resolve `Type::method` to a function taking the receiver as its first parameter.
+pub(super) fn infer_unbound_method_ref(
+    type_name: &Spanned<String>,
+    method: &Spanned<String>,
+    span: Span,
+    env: &mut TypeEnv,
+) -> Result<PlutoType, CompileError> {
+    let receiver = if env.classes.contains_key(&type_name.node) {
+        PlutoType::Class(type_name.node.clone())
+    } else if let Some(trait_info) = env.traits.get(&type_name.node) {
+        if trait_info.static_methods.contains(&method.node) {
+            return Err(CompileError::type_err(
+                format!(
+                    "'{}::{}' is a static method; call it as '{}::{}<T>()'",
+                    type_name.node, method.node, type_name.node, method.node
+                ),
+                method.span,
+            ));
+        }
+        PlutoType::Trait(type_name.node.clone())
+    } else {
+        return Err(CompileError::type_err(
+            format!("unknown class or trait '{}' in method reference", type_name.node),
+            type_name.span,
+        ));
+    };
+
+    let (params, return_type, resolution) = lookup_method(&receiver, &method.node, env)
+        .ok_or_else(|| {
+            CompileError::type_err(
+                format!("'{}' has no method '{}'", type_name.node, method.node),
+                method.span,
+            )
+        })?;
+
+    let mut fn_params = vec![receiver.clone()];
+    fn_params.extend(params.iter().cloned());
+    record(env, method.span, span, resolution, MethodRefInfo {
+        receiver,
+        method: method.node.clone(),
+        params,
+        return_type: return_type.clone(),
+        capture: None,
+    });
+    Ok(PlutoType::Fn(fn_params, Box::new(return_type)))
+}
+
+fn record(env: &mut TypeEnv, method_span: Span, span: Span, resolution: MethodResolution, info: MethodRefInfo) {
+    // The resolution lets error enforcement check the method's fallibility
+    // the same way it does for a call.
+    if let Some(ref current) = env.current_fn {
+        env.method_resolutions.insert((current.clone(), method_span.start), resolution);
+    }
+    env.method_refs.insert(env.body_key(span), info);
+}
+
+/// Signature (excluding the receiver) of `method` on `receiver`, if it is a method.
+/// Class fields shadow methods of the same name.
+fn lookup_method(
+    receiver: &PlutoType,
+    method: &str,
+    env: &TypeEnv,
+) -> Option<(Vec<PlutoType>, PlutoType, MethodResolution)> {
+    match receiver {
+        PlutoType::Class(class_name) => {
+            let class_info = env.classes.get(class_name)?;
+            if class_info.fields.iter().any(|(n, _, _)| n == method) {
+                return None;
+            }
+            let mangled_name = mangle_method(class_name, method);
+            let sig = env.functions.get(&mangled_name)?;
+            let resolution = if env.remote_types.contains(class_name) {
+                MethodResolution::RemoteClass { mangled_name: mangled_name.clone() }
+            } else {
+                MethodResolution::Class { mangled_name: mangled_name.clone() }
+            };
+            Some((sig.params[1..].to_vec(), sig.return_type.clone(), resolution))
+        }
+        PlutoType::Trait(trait_name) => {
+            let trait_info = env.traits.get(trait_name)?;
+            if trait_info.static_methods.contains(method) {
+                return None;
+            }
+            let (_, sig) = trait_info.methods.iter().find(|(n, _)| n == method)?;
+            let resolution = MethodResolution::TraitDynamic {
+                trait_name: trait_name.clone(),
+                method_name: method.to_string(),
+            };
+            Some((sig.params[1..].to_vec(), sig.return_type.clone(), resolution))
+        }
+        PlutoType::Array(elem) => {
+            let elem = (**elem).clone();
+            let (params, ret) = match method {
+                "len" => (vec![], PlutoType::Int),
+                "push" => (vec![elem], PlutoType::Void),
+                "pop" | "first" | "last" => (vec![], elem),
+                "is_empty" => (vec![], PlutoType::Bool),
+                "clear" | "reverse" => (vec![], PlutoType::Void),
+                "contains" => (vec![elem], PlutoType::Bool),
+                "index_of" => (vec![elem], PlutoType::Int),
+                "remove_at" => (vec![PlutoType::Int], elem),
+                "insert_at" => (vec![PlutoType::Int, elem], PlutoType::Void),
+                _ => return None,
+            };
+            Some((params, ret, MethodResolution::Builtin))
+        }
+        _ => None,
+    }
+}
+
+fn is_mut_self_method(receiver: &PlutoType, method: &str, env: &TypeEnv) -> bool {
+    match receiver {
+        PlutoType::Class(class_name) => env.mut_self_methods.contains(&mangle_method(class_name, method)),
+        PlutoType::Trait(trait_name) => env.traits.get(trait_name)
+            .is_some_and(|t| t.mut_self_methods.contains(method)),
+        _ => false,
+    }
+}
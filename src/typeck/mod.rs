@@ -7,13 +7,19 @@ mod infer;
 mod check;
 mod closures;
 mod errors;
+mod lints;
+mod const_eval;
+mod method_ref;
+mod incremental;
 
 // Re-exports for external use
-pub(crate) use check::check_function;
+pub(crate) use check::{check_function, is_unreachable_call};
+pub(crate) use const_eval::eval_const_int;
 pub(crate) use register::check_trait_conformance;
 pub(crate) use resolve::resolve_type_for_monomorphize;
+pub use incremental::BodyCache;
 
-use crate::diagnostics::{CompileError, CompileWarning, WarningKind};
+use crate::diagnostics::{CompileError, CompileWarning};
 use crate::parser::ast::Program;
 use env::{ErrorInfo, TypeEnv};
 use types::PlutoType;
@@ -49,6 +55,19 @@ fn types_compatible(actual: &PlutoType, expected: &PlutoType, env: &TypeEnv) ->
 }
 
 pub fn type_check(program: &Program) -> Result<(TypeEnv, Vec<CompileWarning>), CompileError> {
+    type_check_impl(program, None)
+}
+
+/// Like `type_check`, but replays the body checks `cache` recorded for files it
+/// marks reusable and records the rest for next time.
+pub fn type_check_incremental(program: &Program, cache: &mut BodyCache) -> Result<(TypeEnv, Vec<CompileWarning>), CompileError> {
+    cache.begin();
+    let result = type_check_impl(program, Some(&mut *cache))?;
+    cache.commit();
+    Ok(result)
+}
+
+fn type_check_impl(program: &Program, cache: Option<&mut BodyCache>) -> Result<(TypeEnv, Vec<CompileWarning>), CompileError> {
     let mut env = TypeEnv::new();
 
     // Pass 0: Register names only (no type resolution)
@@ -72,6 +91,12 @@ pub fn type_check(program: &Program) -> Result<(TypeEnv, Vec<CompileWarning>), C
     env.errors.entry("ChannelEmpty".to_string()).or_insert(ErrorInfo {
         fields: vec![("message".to_string(), PlutoType::String)],
     });
+    env.errors.entry("ChannelTimeout".to_string()).or_insert(ErrorInfo {
+        fields: vec![("message".to_string(), PlutoType::String)],
+    });
+    env.errors.entry("IOError".to_string()).or_insert(ErrorInfo {
+        fields: vec![("message".to_string(), PlutoType::String)],
+    });
     env.errors.entry("TaskCancelled".to_string()).or_insert(ErrorInfo {
         fields: vec![("message".to_string(), PlutoType::String)],
     });
@@ -89,7 +114,10 @@ pub fn type_check(program: &Program) -> Result<(TypeEnv, Vec<CompileWarning>), C
     // Pass 1: Resolve types now that all names are registered
     register::resolve_trait_signatures(program, &mut env)?;
     register::resolve_enum_fields(program, &mut env)?;
+    register::register_operator_traits(&mut env);
     register::resolve_class_fields(program, &mut env)?;
+    register::register_consts(program, &mut env)?;
+    register::register_globals(program, &mut env)?;
     register::register_extern_fns(program, &mut env)?;
     register::register_functions(program, &mut env)?;
     register::register_method_sigs(program, &mut env)?;
@@ -97,7 +125,7 @@ pub fn type_check(program: &Program) -> Result<(TypeEnv, Vec<CompileWarning>), C
     register::register_stage_fields_and_methods(program, &mut env)?;
     register::validate_di_graph(program, &mut env)?;
     register::check_trait_conformance(program, &mut env)?;
-    register::check_all_bodies(program, &mut env)?;
+    register::check_all_bodies(program, &mut env, cache)?;
     check::enforce_mut_self(program, &env)?;
     // Seed Rust FFI fallible functions into fn_errors before inference
     // so that infer_error_sets can propagate RustError through callers.
@@ -107,70 +135,14 @@ pub fn type_check(program: &Program) -> Result<(TypeEnv, Vec<CompileWarning>), C
             .insert("RustError".to_string());
     }
     errors::infer_error_sets(program, &mut env);
-    errors::enforce_error_handling(program, &env)?;
+    errors::enforce_error_handling(program, &mut env)?;
+    errors::enforce_declared_errors(program, &env)?;
     crate::concurrency::infer_synchronization(program, &mut env);
 
-    let warnings = generate_warnings(&env, program);
+    let warnings = lints::generate_warnings(&env, program);
     Ok((env, warnings))
 }
 
-fn generate_warnings(env: &TypeEnv, program: &Program) -> Vec<CompileWarning> {
-    let mut warnings = Vec::new();
-
-    // Collect function parameter names to exclude from unused-variable warnings
-    let mut param_names = std::collections::HashSet::new();
-    for func in &program.functions {
-        for p in &func.node.params {
-            param_names.insert(p.name.node.clone());
-        }
-    }
-    if let Some(app) = &program.app {
-        for m in &app.node.methods {
-            for p in &m.node.params {
-                param_names.insert(p.name.node.clone());
-            }
-        }
-    }
-    for stage in &program.stages {
-        for m in &stage.node.methods {
-            for p in &m.node.params {
-                param_names.insert(p.name.node.clone());
-            }
-        }
-    }
-    for class in &program.classes {
-        for method in &class.node.methods {
-            for p in &method.node.params {
-                param_names.insert(p.name.node.clone());
-            }
-        }
-    }
-
-    for ((name, depth), decl_span) in &env.variable_decls {
-        // Skip _-prefixed variables (intentionally unused convention)
-        if name.starts_with('_') {
-            continue;
-        }
-        // Skip function parameters
-        if param_names.contains(name) {
-            continue;
-        }
-        // Skip if variable was read
-        if env.variable_reads.contains(&(name.clone(), *depth)) {
-            continue;
-        }
-        warnings.push(CompileWarning {
-            msg: format!("unused variable '{name}'"),
-            span: *decl_span,
-            kind: WarningKind::UnusedVariable,
-        });
-    }
-
-    // Sort for deterministic output
-    warnings.sort_by_key(|w| w.span.start);
-    warnings
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
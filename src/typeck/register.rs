@@ -4,11 +4,12 @@ use uuid::Uuid;
 
 use crate::diagnostics::CompileError;
 use crate::parser::ast::*;
-use crate::span::Spanned;
+use crate::span::{Span, Spanned};
 use super::env::{self, mangle_method, ClassInfo, EnumInfo, ErrorInfo, FuncSig, GenericClassInfo, GenericEnumInfo, GenericFuncSig, TraitInfo, TypeEnv};
 use super::types::PlutoType;
-use super::resolve::{resolve_type, resolve_type_with_params};
+use super::resolve::{resolve_type, resolve_type_with_params, substitute_pluto_type};
 use super::check::{check_function, all_paths_return};
+use super::incremental::{BodyCache, UnitKey};
 use crate::parser::ast::ContractKind;
 
 /// Type-check requires contracts on a function or method.
@@ -31,6 +32,8 @@ fn check_function_contracts(
             if p.name.node == "self" {
                 if let Some(cn) = class_name {
                     env.define_unchecked("self".to_string(), PlutoType::Class(cn.to_string()));
+                } else if let Ok(ty @ PlutoType::Enum(_)) = resolve_type(&p.ty, env) {
+                    env.define_unchecked("self".to_string(), ty);
                 }
             } else {
                 let ty = resolve_type(&p.ty, env)?;
@@ -123,6 +126,8 @@ pub(crate) fn resolve_trait_signatures(program: &Program, env: &mut TypeEnv) ->
         let t = &trait_decl.node;
         let trait_name = &t.name.node;
 
+        // `Self` in a signature stands for the implementing type
+        let self_type: HashSet<String> = HashSet::from(["Self".to_string()]);
         let mut methods = Vec::new();
         for m in &t.methods {
             // Trait methods can be instance methods (with self) or static methods (without self)
@@ -133,14 +138,21 @@ pub(crate) fn resolve_trait_signatures(program: &Program, env: &mut TypeEnv) ->
                 if p.name.node == "self" {
                     param_types.push(PlutoType::Void); // placeholder for self
                 } else {
-                    param_types.push(resolve_type(&p.ty, env)?);
+                    param_types.push(resolve_type_with_params(&p.ty, env, &self_type)?);
                 }
             }
             let return_type = match &m.return_type {
-                Some(rt) => resolve_type(rt, env)?,
+                Some(rt) => resolve_type_with_params(rt, env, &self_type)?,
                 None => PlutoType::Void,
             };
-            methods.push((m.name.node.clone(), FuncSig { params: param_types, return_type }));
+            let sig = FuncSig { params: param_types, return_type };
+            if m.body.is_some() && mentions_self_type(&sig) {
+                return Err(CompileError::type_err(
+                    format!("default method '{}' of trait '{}' cannot use 'Self'; declare it without a body", m.name.node, trait_name),
+                    m.name.span,
+                ));
+            }
+            methods.push((m.name.node.clone(), sig));
         }
 
         // Update the TraitInfo with resolved method signatures
@@ -228,6 +240,12 @@ pub(crate) fn register_enum_names(program: &Program, env: &mut TypeEnv) -> Resul
                     }
                 }
             }
+            if let Some(t) = e.impl_traits.first() {
+                return Err(CompileError::type_err(
+                    format!("generic enum '{}' cannot implement traits", e.name.node),
+                    t.span,
+                ));
+            }
             env.generic_enums.insert(e.name.node.clone(), GenericEnumInfo {
                 type_params: e.type_params.iter().map(|tp| tp.node.clone()).collect(),
                 type_param_bounds: enum_bounds,
@@ -245,14 +263,57 @@ pub(crate) fn register_enum_names(program: &Program, env: &mut TypeEnv) -> Resul
             variant_type_exprs.push((v.name.node.clone(), field_type_exprs));
         }
 
+        let mut impl_traits = Vec::new();
+        for t in &e.impl_traits {
+            if !env.traits.contains_key(&t.node) {
+                return Err(CompileError::type_err(format!("unknown trait '{}'", t.node), t.span));
+            }
+            if impl_traits.contains(&t.node) {
+                return Err(CompileError::type_err(
+                    format!("trait '{}' appears multiple times in impl list for enum '{}'", t.node, e.name.node),
+                    t.span,
+                ));
+            }
+            impl_traits.push(t.node.clone());
+        }
+
         env.enums.insert(e.name.node.clone(), EnumInfo {
             variants: Vec::new(),  // Will be populated in Pass 1
             variant_type_exprs,
+            impl_traits,
         });
     }
     Ok(())
 }
 
+/// Pass 1: Record which operator traits overload operators. User code may
+/// shadow a prelude operator trait or `Ordering`; the name then keeps its
+/// operators only if the trait still has the prelude's `fn m(self, other: Self) R`.
+pub(crate) fn register_operator_traits(env: &mut TypeEnv) {
+    let self_type = PlutoType::TypeParam("Self".to_string());
+    let prelude_ordering = env.enums.get("Ordering").is_some_and(|info| {
+        info.variants.iter()
+            .map(|(name, fields)| (name.as_str(), fields.is_empty()))
+            .eq([("Less", true), ("Equal", true), ("Greater", true)])
+    });
+    for (trait_name, method) in env::OPERATOR_TRAITS {
+        let return_type = match trait_name {
+            "Eq" => PlutoType::Bool,
+            "Ord" if prelude_ordering => PlutoType::Enum("Ordering".to_string()),
+            "Ord" => continue,
+            _ => self_type.clone(),
+        };
+        let params = [PlutoType::Void, self_type.clone()];
+        let has_shape = env.traits.get(trait_name).is_some_and(|info| {
+            matches!(info.methods.as_slice(), [(name, sig)]
+                if name == method && sig.params == params && sig.return_type == return_type)
+        });
+        if has_shape {
+            env.operator_traits.insert(trait_name.to_string());
+        }
+    }
+}
+
 /// Pass 1: Resolve enum variant field types now that all classes/enums are registered.
 pub(crate) fn resolve_enum_fields(program: &Program, env: &mut TypeEnv) -> Result<(), CompileError> {
     for enum_decl in &program.enums {
@@ -338,6 +399,92 @@ pub(crate) fn register_stage_placeholders(program: &Program, env: &mut TypeEnv)
     Ok(())
 }
 
+/// Evaluate `const` initializers in declaration order, so a const may use
+/// the ones declared before it.
+pub(crate) fn register_consts(program: &Program, env: &mut TypeEnv) -> Result<(), CompileError> {
+    for const_decl in &program.consts {
+        let c = &const_decl.node;
+        if env.consts.contains_key(&c.name.node) {
+            return Err(CompileError::type_err(
+                format!("const '{}' is already declared", c.name.node),
+                c.name.span,
+            ));
+        }
+        let value = super::const_eval::eval_const_expr(&c.value.node, &env.consts).ok_or_else(|| {
+            CompileError::type_err(
+                format!(
+                    "initializer of const '{}' is not a compile-time constant; only literals, other consts and operators on them are allowed",
+                    c.name.node
+                ),
+                c.value.span,
+            )
+        })?;
+        let ty = match &value {
+            Expr::IntLit(_) => PlutoType::Int,
+            Expr::FloatLit(_) => PlutoType::Float,
+            Expr::BoolLit(_) => PlutoType::Bool,
+            _ => PlutoType::String,
+        };
+        if let Some(declared) = &c.ty {
+            let declared = resolve_type(declared, env)?;
+            if declared != ty {
+                return Err(CompileError::type_err(
+                    format!("const '{}' is declared as {declared} but its value is {ty}", c.name.node),
+                    c.value.span,
+                ));
+            }
+        }
+        env.consts.insert(c.name.node.clone(), env::ConstInfo { ty, value });
+    }
+    Ok(())
+}
+
+/// Register `global mut` variables. Like consts, their initializers must be
+/// compile-time constants (and may use consts); only int, float and bool
+/// globals are supported.
+pub(crate) fn register_globals(program: &Program, env: &mut TypeEnv) -> Result<(), CompileError> {
+    for global_decl in &program.globals {
+        let g = &global_decl.node;
+        if env.globals.contains_key(&g.name.node) || env.consts.contains_key(&g.name.node) {
+            return Err(CompileError::type_err(
+                format!("global '{}' is already declared", g.name.node),
+                g.name.span,
+            ));
+        }
+        let value = super::const_eval::eval_const_expr(&g.value.node, &env.consts).ok_or_else(|| {
+            CompileError::type_err(
+                format!(
+                    "initializer of global '{}' is not a compile-time constant; only literals, consts and operators on them are allowed",
+                    g.name.node
+                ),
+                g.value.span,
+            )
+        })?;
+        let ty = match &value {
+            Expr::IntLit(_) => PlutoType::Int,
+            Expr::FloatLit(_) => PlutoType::Float,
+            Expr::BoolLit(_) => PlutoType::Bool,
+            _ => {
+                return Err(CompileError::type_err(
+                    format!("global '{}' must be an int, float or bool", g.name.node),
+                    g.value.span,
+                ));
+            }
+        };
+        if let Some(declared) = &g.ty {
+            let declared = resolve_type(declared, env)?;
+            if declared != ty {
+                return Err(CompileError::type_err(
+                    format!("global '{}' is declared as {declared} but its value is {ty}", g.name.node),
+                    g.value.span,
+                ));
+            }
+        }
+        env.globals.insert(g.name.node.clone(), env::ConstInfo { ty, value });
+    }
+    Ok(())
+}
+
 pub(crate) fn register_errors(program: &Program, env: &mut TypeEnv) -> Result<(), CompileError> {
     for error_decl in &program.errors {
         let e = &error_decl.node;
@@ -469,6 +616,8 @@ pub(crate) fn resolve_class_fields(program: &Program, env: &mut TypeEnv) -> Resu
                 }
             }
 
+            reject_generic_methods(&c.methods, &format!("generic class '{}'", c.name.node))?;
+
             // Validate trait names for generic classes
             for trait_name in &c.impl_traits {
                 if !env.traits.contains_key(&trait_name.node) {
@@ -518,6 +667,24 @@ pub(crate) fn resolve_class_fields(program: &Program, env: &mut TypeEnv) -> Resu
                     }
                 }
             }
+            let mut where_bounds: HashMap<String, Vec<String>> = HashMap::new();
+            for (tp, trait_names) in &c.where_bounds {
+                if !tp_names.contains(tp) {
+                    return Err(CompileError::type_err(
+                        format!("'{}' in where clause is not a type parameter of class '{}'", tp, c.name.node),
+                        trait_names[0].span,
+                    ));
+                }
+                for trait_name in trait_names {
+                    if !env.traits.contains_key(&trait_name.node) {
+                        return Err(CompileError::type_err(
+                            format!("unknown trait '{}' in where clause for '{}'", trait_name.node, tp),
+                            trait_name.span,
+                        ));
+                    }
+                }
+                where_bounds.insert(tp.clone(), trait_names.iter().map(|t| t.node.clone()).collect());
+            }
             let mut fields = Vec::new();
             for f in &c.fields {
                 let ty = resolve_type_with_params(&f.ty, env, &tp_names)?;
@@ -558,11 +725,18 @@ pub(crate) fn resolve_class_fields(program: &Program, env: &mut TypeEnv) -> Resu
                 methods: method_names,
                 method_sigs,
                 impl_traits: c.impl_traits.iter().map(|t| t.node.clone()).collect(),
+                where_bounds,
                 mut_self_methods: generic_mut_self,
                 lifecycle: c.lifecycle,
             });
             continue;
         }
+        if let Some(bound) = c.where_bounds.values().flatten().next() {
+            return Err(CompileError::type_err(
+                format!("where clause on non-generic class '{}'; it only applies to type parameters", c.name.node),
+                bound.span,
+            ));
+        }
         // Check for duplicate field names
         let mut seen_fields = HashSet::new();
         for f in &c.fields {
@@ -613,10 +787,22 @@ pub(crate) fn register_extern_fns(program: &Program, env: &mut TypeEnv) -> Resul
     for ext in &program.extern_fns {
         let e = &ext.node;
 
+        if env.builtins.contains(&e.name.node) {
+            return Err(CompileError::type_err(
+                format!("extern function '{}' cannot shadow builtin '{}'", e.name.node, e.name.node),
+                e.name.span,
+            ));
+        }
+
         // Validate only primitive types allowed
         let mut param_types = Vec::new();
         for p in &e.params {
             let ty = resolve_type(&p.ty, env)?;
+            if e.c_abi {
+                check_c_abi_type(&ty, p.ty.span)?;
+                param_types.push(ty);
+                continue;
+            }
             match &ty {
                 PlutoType::Int | PlutoType::Float | PlutoType::Bool | PlutoType::String | PlutoType::Void | PlutoType::Array(_) => {}
                 _ => {
@@ -633,6 +819,7 @@ pub(crate) fn register_extern_fns(program: &Program, env: &mut TypeEnv) -> Resul
             Some(t) => {
                 let ty = resolve_type(t, env)?;
                 match &ty {
+                    _ if e.c_abi => check_c_abi_type(&ty, t.span)?,
                     PlutoType::Int | PlutoType::Float | PlutoType::Bool | PlutoType::String | PlutoType::Void | PlutoType::Array(_) => {}
                     _ => {
                         return Err(CompileError::type_err(
@@ -655,6 +842,19 @@ pub(crate) fn register_extern_fns(program: &Program, env: &mut TypeEnv) -> Resul
     Ok(())
 }
 
+/// `extern "C"` functions are called directly with the platform C ABI, so only
+/// types with a fixed C representation are allowed: `int` (int64_t), `float`
+/// (double), and `bool`.
+fn check_c_abi_type(ty: &PlutoType, span: Span) -> Result<(), CompileError> {
+    match ty {
+        PlutoType::Int | PlutoType::Float | PlutoType::Bool => Ok(()),
+        _ => Err(CompileError::type_err(
+            format!("extern \"C\" functions only support int, float, and bool, got '{}'", ty),
+            span,
+        )),
+    }
+}
+
 pub(crate) fn register_functions(program: &Program, env: &mut TypeEnv) -> Result<(), CompileError> {
     for func in &program.functions {
         let f = &func.node;
@@ -702,68 +902,9 @@ pub(crate) fn register_functions(program: &Program, env: &mut TypeEnv) -> Result
         }
 
         if !f.type_params.is_empty() {
-            // Check for duplicate type parameters
-            let mut seen_tparams: HashSet<&str> = HashSet::new();
-            for tp in &f.type_params {
-                if !seen_tparams.insert(&tp.node) {
-                    return Err(CompileError::type_err(
-                        format!("type parameter '{}' is already declared in function '{}'", tp.node, f.name.node),
-                        tp.span,
-                    ));
-                }
-                // Check that type parameter doesn't shadow a class name
-                if env.classes.contains_key(&tp.node) || env.generic_classes.contains_key(&tp.node) {
-                    return Err(CompileError::type_err(
-                        format!("type parameter '{}' in function '{}' shadows class '{}'", tp.node, f.name.node, tp.node),
-                        tp.span,
-                    ));
-                }
-            }
-
             // Generic function — register in generic_functions with TypeParam types
-            let tp_names: std::collections::HashSet<String> = f.type_params.iter().map(|tp| tp.node.clone()).collect();
-            let mut param_types = Vec::new();
-            for p in &f.params {
-                param_types.push(resolve_type_with_params(&p.ty, env, &tp_names)?);
-            }
-            let return_type = match &f.return_type {
-                Some(t) => resolve_type_with_params(t, env, &tp_names)?,
-                None => PlutoType::Void,
-            };
-
-            // Verify non-void generic functions have a return or raise on every control flow path
-            if !matches!(return_type, PlutoType::Void) && !all_paths_return(&f.body.node) {
-                return Err(CompileError::type_err(
-                    format!("missing return statement in function with return type {}", return_type),
-                    f.body.span,
-                ));
-            }
-
-            // Extract and validate type param bounds
-            let bounds: HashMap<String, Vec<String>> = f.type_param_bounds.iter()
-                .map(|(tp, traits)| {
-                    (tp.clone(), traits.iter().map(|t| t.node.clone()).collect())
-                })
-                .collect();
-            for (tp, trait_names) in &f.type_param_bounds {
-                if !tp_names.contains(tp) {
-                    continue; // shouldn't happen from parser, but defensive
-                }
-                for trait_name in trait_names {
-                    if !env.traits.contains_key(&trait_name.node) {
-                        return Err(CompileError::type_err(
-                            format!("unknown trait '{}' in type bound for '{}'", trait_name.node, tp),
-                            trait_name.span,
-                        ));
-                    }
-                }
-            }
-            env.generic_functions.insert(f.name.node.clone(), GenericFuncSig {
-                type_params: f.type_params.iter().map(|tp| tp.node.clone()).collect(),
-                type_param_bounds: bounds,
-                params: param_types,
-                return_type,
-            });
+            let sig = generic_func_sig(f, "function", None, env)?;
+            env.generic_functions.insert(f.name.node.clone(), sig);
             continue;
         }
 
@@ -775,6 +916,17 @@ pub(crate) fn register_functions(program: &Program, env: &mut TypeEnv) -> Result
             Some(t) => resolve_type(t, env)?,
             None => PlutoType::Void,
         };
+        // main's return value becomes the process exit code. Nullable returns
+        // stay allowed so `?` can be used in main (none exits with 0).
+        if f.name.node == "main"
+            && let Some(t) = &f.return_type
+            && !matches!(return_type, PlutoType::Int | PlutoType::Void | PlutoType::Nullable(_))
+        {
+            return Err(CompileError::type_err(
+                format!("main must return int or nothing, found {return_type}"),
+                t.span,
+            ));
+        }
         if matches!(&return_type, PlutoType::Stream(_)) {
             env.generators.insert(f.name.node.clone());
         }
@@ -786,6 +938,92 @@ pub(crate) fn register_functions(program: &Program, env: &mut TypeEnv) -> Result
     Ok(())
 }
 
+/// Build the signature of a generic function or method, with its type
+/// parameters left as `TypeParam`. `self_class` types a method's `self`.
+fn generic_func_sig(
+    f: &Function,
+    kind: &str,
+    self_class: Option<&str>,
+    env: &mut TypeEnv,
+) -> Result<GenericFuncSig, CompileError> {
+    // Check for duplicate type parameters
+    let mut seen_tparams: HashSet<&str> = HashSet::new();
+    for tp in &f.type_params {
+        if !seen_tparams.insert(&tp.node) {
+            return Err(CompileError::type_err(
+                format!("type parameter '{}' is already declared in {kind} '{}'", tp.node, f.name.node),
+                tp.span,
+            ));
+        }
+        // Check that type parameter doesn't shadow a class name
+        if env.classes.contains_key(&tp.node) || env.generic_classes.contains_key(&tp.node) {
+            return Err(CompileError::type_err(
+                format!("type parameter '{}' in {kind} '{}' shadows class '{}'", tp.node, f.name.node, tp.node),
+                tp.span,
+            ));
+        }
+    }
+
+    let tp_names: std::collections::HashSet<String> = f.type_params.iter().map(|tp| tp.node.clone()).collect();
+    let mut param_types = Vec::new();
+    for p in &f.params {
+        match self_class {
+            Some(class_name) if p.name.node == "self" => param_types.push(PlutoType::Class(class_name.to_string())),
+            _ => param_types.push(resolve_type_with_params(&p.ty, env, &tp_names)?),
+        }
+    }
+    let return_type = match &f.return_type {
+        Some(t) => resolve_type_with_params(t, env, &tp_names)?,
+        None => PlutoType::Void,
+    };
+
+    // Verify non-void generic functions have a return or raise on every control flow path
+    if !matches!(return_type, PlutoType::Void) && !all_paths_return(&f.body.node) {
+        return Err(CompileError::type_err(
+            format!("missing return statement in function with return type {}", return_type),
+            f.body.span,
+        ));
+    }
+
+    // Extract and validate type param bounds
+    let bounds: HashMap<String, Vec<String>> = f.type_param_bounds.iter()
+        .map(|(tp, traits)| {
+            (tp.clone(), traits.iter().map(|t| t.node.clone()).collect())
+        })
+        .collect();
+    for (tp, trait_names) in &f.type_param_bounds {
+        if !tp_names.contains(tp) {
+            continue; // shouldn't happen from parser, but defensive
+        }
+        for trait_name in trait_names {
+            if !env.traits.contains_key(&trait_name.node) {
+                return Err(CompileError::type_err(
+                    format!("unknown trait '{}' in type bound for '{}'", trait_name.node, tp),
+                    trait_name.span,
+                ));
+            }
+        }
+    }
+    Ok(GenericFuncSig {
+        type_params: f.type_params.iter().map(|tp| tp.node.clone()).collect(),
+        type_param_bounds: bounds,
+        params: param_types,
+        return_type,
+    })
+}
+
+/// Generic methods are instantiated per class, so only non-generic classes and
+/// enums may declare them.
+fn reject_generic_methods(methods: &[Spanned<Function>], owner: &str) -> Result<(), CompileError> {
+    match methods.iter().find(|m| !m.node.type_params.is_empty()) {
+        Some(m) => Err(CompileError::type_err(
+            format!("method '{}' of {owner} cannot declare type parameters; generic methods are only supported on non-generic classes and enums", m.node.name.node),
+            m.node.type_params[0].span,
+        )),
+        None => Ok(()),
+    }
+}
+
 pub(crate) fn register_method_sigs(program: &Program, env: &mut TypeEnv) -> Result<(), CompileError> {
     for class in &program.classes {
         let c = &class.node;
@@ -795,6 +1033,15 @@ pub(crate) fn register_method_sigs(program: &Program, env: &mut TypeEnv) -> Resu
         for method in &c.methods {
             let m = &method.node;
             let mangled = mangle_method(class_name, &m.name.node);
+            if !m.type_params.is_empty() {
+                // Generic method — instantiated per call site like a generic function
+                let sig = generic_func_sig(m, "method", Some(class_name), env)?;
+                if !m.params.is_empty() && m.params[0].name.node == "self" && m.params[0].is_mut {
+                    env.mut_self_methods.insert(mangled.clone());
+                }
+                env.generic_functions.insert(mangled, sig);
+                continue;
+            }
             method_names.push(m.name.node.clone());
 
             let mut param_types = Vec::new();
@@ -865,6 +1112,8 @@ pub(crate) fn register_app_fields_and_methods(program: &Program, env: &mut TypeE
             env.ambient_types.insert(ambient_type.node.clone());
         }
 
+        reject_generic_methods(&app.methods, &format!("app '{app_name}'"))?;
+
         // Register app methods (mangled as AppName_methodname)
         let mut method_names = Vec::new();
         let mut has_main = false;
@@ -963,6 +1212,8 @@ pub(crate) fn register_stage_fields_and_methods(program: &Program, env: &mut Typ
             env.ambient_types.insert(ambient_type.node.clone());
         }
 
+        reject_generic_methods(&stage.methods, &format!("stage '{stage_name}'"))?;
+
         // Register stage methods (mangled as StageName_methodname)
         let mut method_names = Vec::new();
         let mut has_main = false;
@@ -1484,38 +1735,10 @@ pub(crate) fn check_trait_conformance(program: &Program, env: &mut TypeEnv) -> R
                             trait_name_spanned.span,
                         )
                     })?;
-                    // Compare non-self params
-                    let trait_non_self = &trait_sig.params[1..];
-                    let class_non_self = &class_sig.params[1..];
-                    if trait_non_self.len() != class_non_self.len() {
-                        return Err(CompileError::type_err(
-                            format!(
-                                "method '{}' of class '{}' has wrong number of parameters for trait '{}'",
-                                method_name, class_name, trait_name
-                            ),
-                            trait_name_spanned.span,
-                        ));
-                    }
-                    for (i, (tp, cp)) in trait_non_self.iter().zip(class_non_self).enumerate() {
-                        if tp != cp {
-                            return Err(CompileError::type_err(
-                                format!(
-                                    "method '{}' parameter {} type mismatch: trait '{}' expects {}, class '{}' has {}",
-                                    method_name, i + 1, trait_name, tp, class_name, cp
-                                ),
-                                trait_name_spanned.span,
-                            ));
-                        }
-                    }
-                    if trait_sig.return_type != class_sig.return_type {
-                        return Err(CompileError::type_err(
-                            format!(
-                                "method '{}' return type mismatch: trait '{}' expects {}, class '{}' returns {}",
-                                method_name, trait_name, trait_sig.return_type, class_name, class_sig.return_type
-                            ),
-                            trait_name_spanned.span,
-                        ));
-                    }
+                    check_impl_signature(
+                        "class", class_name, &PlutoType::Class(class_name.clone()),
+                        method_name, trait_name, trait_sig, class_sig, trait_name_spanned.span,
+                    )?;
                     // Check mut self conformance
                     let trait_mut = trait_info.mut_self_methods.contains(method_name);
                     let class_mut = env.mut_self_methods.contains(&mangled);
@@ -1590,15 +1813,129 @@ pub(crate) fn check_trait_conformance(program: &Program, env: &mut TypeEnv) -> R
             }
         }
     }
+
+    // Enum methods were lowered to `Enum$method` functions before typeck
+    for enum_decl in &program.enums {
+        let e = &enum_decl.node;
+        for trait_name_spanned in &e.impl_traits {
+            let trait_name = &trait_name_spanned.node;
+            let trait_info = env.traits[trait_name].clone();
+            for (method_name, trait_sig) in &trait_info.methods {
+                let Some(enum_sig) = env.functions.get(&mangle_method(&e.name.node, method_name)) else {
+                    let reason = if trait_info.default_methods.contains(method_name) {
+                        "; enums do not inherit default methods"
+                    } else {
+                        ""
+                    };
+                    return Err(CompileError::type_err(
+                        format!(
+                            "enum '{}' does not implement required method '{}' from trait '{}'{reason}",
+                            e.name.node, method_name, trait_name
+                        ),
+                        trait_name_spanned.span,
+                    ));
+                };
+                if trait_sig.params.is_empty() != enum_sig.params.is_empty() || trait_info.mut_self_methods.contains(method_name) {
+                    return Err(CompileError::type_err(
+                        format!(
+                            "method '{}' of enum '{}' does not match the receiver of trait '{}'",
+                            method_name, e.name.node, trait_name
+                        ),
+                        trait_name_spanned.span,
+                    ));
+                }
+                check_impl_signature(
+                    "enum", &e.name.node, &PlutoType::Enum(e.name.node.clone()),
+                    method_name, trait_name, trait_sig, enum_sig, trait_name_spanned.span,
+                )?;
+            }
+        }
+    }
     Ok(())
 }
 
-pub(crate) fn check_all_bodies(program: &Program, env: &mut TypeEnv) -> Result<(), CompileError> {
+/// Whether a trait method signature mentions `Self`.
+pub(crate) fn mentions_self_type(sig: &FuncSig) -> bool {
+    let erased = HashMap::from([("Self".to_string(), PlutoType::Void)]);
+    sig.params.iter()
+        .chain(std::iter::once(&sig.return_type))
+        .any(|t| substitute_pluto_type(t, &erased) != *t)
+}
+
+/// Compare an implementing method's signature against the trait's, with the
+/// trait's `Self` standing for the implementing type.
+#[allow(clippy::too_many_arguments)]
+fn check_impl_signature(
+    kind: &str,
+    type_name: &str,
+    implementor: &PlutoType,
+    method_name: &str,
+    trait_name: &str,
+    trait_sig: &FuncSig,
+    impl_sig: &FuncSig,
+    span: crate::span::Span,
+) -> Result<(), CompileError> {
+    let bindings = HashMap::from([("Self".to_string(), implementor.clone())]);
+    // Compare non-self params
+    let trait_non_self = &trait_sig.params[1..];
+    let impl_non_self = &impl_sig.params[1..];
+    if trait_non_self.len() != impl_non_self.len() {
+        return Err(CompileError::type_err(
+            format!(
+                "method '{}' of {} '{}' has wrong number of parameters for trait '{}'",
+                method_name, kind, type_name, trait_name
+            ),
+            span,
+        ));
+    }
+    for (i, (tp, cp)) in trait_non_self.iter().zip(impl_non_self).enumerate() {
+        let tp = substitute_pluto_type(tp, &bindings);
+        if tp != *cp {
+            return Err(CompileError::type_err(
+                format!(
+                    "method '{}' parameter {} type mismatch: trait '{}' expects {}, {} '{}' has {}",
+                    method_name, i + 1, trait_name, tp, kind, type_name, cp
+                ),
+                span,
+            ));
+        }
+    }
+    let trait_ret = substitute_pluto_type(&trait_sig.return_type, &bindings);
+    if trait_ret != impl_sig.return_type {
+        return Err(CompileError::type_err(
+            format!(
+                "method '{}' return type mismatch: trait '{}' expects {}, {} '{}' returns {}",
+                method_name, trait_name, trait_ret, kind, type_name, impl_sig.return_type
+            ),
+            span,
+        ));
+    }
+    Ok(())
+}
+
+/// Run one body-checking unit, through the incremental cache when there is one.
+fn body_unit(
+    env: &mut TypeEnv,
+    cache: &mut Option<&mut BodyCache>,
+    key: impl FnOnce() -> UnitKey,
+    owners: &[u32],
+    check: impl FnOnce(&mut TypeEnv) -> Result<(), CompileError>,
+) -> Result<(), CompileError> {
+    match cache {
+        Some(cache) => cache.unit(env, key(), owners, check),
+        None => check(env),
+    }
+}
+
+pub(crate) fn check_all_bodies(program: &Program, env: &mut TypeEnv, mut cache: Option<&mut BodyCache>) -> Result<(), CompileError> {
     // Check function bodies and contracts
     for func in &program.functions {
         if !func.node.type_params.is_empty() { continue; } // Skip generic functions
-        check_function(&func.node, env, None)?;
-        check_function_contracts(&func.node, env, None)?;
+        let key = || UnitKey { kind: "fn", name: func.node.name.node.clone(), span: func.span };
+        body_unit(env, &mut cache, key, &[func.span.file_id], |env| {
+            check_function(&func.node, env, None)?;
+            check_function_contracts(&func.node, env, None)
+        })?;
     }
 
     // Check method bodies and contracts
@@ -1606,70 +1943,28 @@ pub(crate) fn check_all_bodies(program: &Program, env: &mut TypeEnv) -> Result<(
         let c = &class.node;
         if !c.type_params.is_empty() { continue; } // Skip generic classes
         for method in &c.methods {
-            check_function(&method.node, env, Some(&c.name.node))?;
-            check_function_contracts(&method.node, env, Some(&c.name.node))?;
+            if !method.node.type_params.is_empty() { continue; } // Skip generic methods
+            let key = || UnitKey { kind: "method", name: mangle_method(&c.name.node, &method.node.name.node), span: method.span };
+            body_unit(env, &mut cache, key, &[method.span.file_id, class.span.file_id], |env| {
+                check_function(&method.node, env, Some(&c.name.node))?;
+                check_function_contracts(&method.node, env, Some(&c.name.node))
+            })?;
         }
         // Type-check class invariants
         if !c.invariants.is_empty() {
-            env.push_scope();
-            env.define_unchecked("self".to_string(), PlutoType::Class(c.name.node.clone()));
-            for inv in &c.invariants {
-                let inv_type = super::infer::infer_expr(&inv.node.expr.node, inv.node.expr.span, env, None)?;
-                if inv_type != PlutoType::Bool {
-                    return Err(CompileError::type_err(
-                        format!("invariant expression must be bool, found {inv_type}"),
-                        inv.node.expr.span,
-                    ));
-                }
-            }
-            env.pop_scope();
+            let key = || UnitKey { kind: "invariants", name: c.name.node.clone(), span: class.span };
+            body_unit(env, &mut cache, key, &[class.span.file_id], |env| check_class_invariants(c, env))?;
         }
     }
 
     // Type-check trait method contracts (requires on abstract trait methods)
     for trait_decl in &program.traits {
         let t = &trait_decl.node;
-        for m in &t.methods {
-            if m.contracts.is_empty() {
-                continue;
-            }
-            // Resolve param types and return type
-            let mut param_types = Vec::new();
-            for p in &m.params {
-                if p.name.node == "self" {
-                    param_types.push(("self".to_string(), PlutoType::Void));
-                } else {
-                    let ty = resolve_type(&p.ty, env)?;
-                    param_types.push((p.name.node.clone(), ty));
-                }
-            }
-            let _return_type = match &m.return_type {
-                Some(rt) => resolve_type(rt, env)?,
-                None => PlutoType::Void,
-            };
-
-            // Check requires clauses
-            let has_requires = m.contracts.iter().any(|c| c.node.kind == ContractKind::Requires);
-            if has_requires {
-                env.push_scope();
-                for (name, ty) in &param_types {
-                    env.define_unchecked(name.clone(), ty.clone());
-                }
-                for contract in &m.contracts {
-                    if contract.node.kind == ContractKind::Requires {
-                        let ty = super::infer::infer_expr(&contract.node.expr.node, contract.node.expr.span, env, None)?;
-                        if ty != PlutoType::Bool {
-                            return Err(CompileError::type_err(
-                                format!("requires expression must be bool, found {ty}"),
-                                contract.node.expr.span,
-                            ));
-                        }
-                    }
-                }
-                env.pop_scope();
-            }
-
+        if t.methods.iter().all(|m| m.contracts.is_empty()) {
+            continue;
         }
+        let key = || UnitKey { kind: "trait_contracts", name: t.name.node.clone(), span: trait_decl.span };
+        body_unit(env, &mut cache, key, &[trait_decl.span.file_id], |env| check_trait_contracts(t, env))?;
     }
 
     // Type-check default method bodies for classes that inherit them
@@ -1698,11 +1993,15 @@ pub(crate) fn check_all_bodies(program: &Program, env: &mut TypeEnv) -> Result<(
                                 return_type: trait_method.return_type.clone(),
                                 contracts: trait_method.contracts.clone(),
                                 body: body.clone(),
-                                is_pub: false,
+                                visibility: Visibility::Private,
                                 is_override: false,
                                 is_generator: false,
+                                declared_errors: None,
+                                no_coverage: false,
                             };
-                            check_function(&tmp_func, env, Some(class_name))?;
+                            let key = || UnitKey { kind: "default_method", name: mangle_method(class_name, &trait_method.name.node), span: class.span };
+                            let owners = [class.span.file_id, trait_decl.span.file_id];
+                            body_unit(env, &mut cache, key, &owners, |env| check_function(&tmp_func, env, Some(class_name)))?;
                         }
                     }
                 }
@@ -1715,8 +2014,11 @@ pub(crate) fn check_all_bodies(program: &Program, env: &mut TypeEnv) -> Result<(
         let app = &app_spanned.node;
         let app_name = &app.name.node;
         for method in &app.methods {
-            check_function(&method.node, env, Some(app_name))?;
-            check_function_contracts(&method.node, env, Some(app_name))?;
+            let key = || UnitKey { kind: "method", name: mangle_method(app_name, &method.node.name.node), span: method.span };
+            body_unit(env, &mut cache, key, &[method.span.file_id, app_spanned.span.file_id], |env| {
+                check_function(&method.node, env, Some(app_name))?;
+                check_function_contracts(&method.node, env, Some(app_name))
+            })?;
         }
     }
 
@@ -1725,8 +2027,73 @@ pub(crate) fn check_all_bodies(program: &Program, env: &mut TypeEnv) -> Result<(
         let stage = &stage_spanned.node;
         let stage_name = &stage.name.node;
         for method in &stage.methods {
-            check_function(&method.node, env, Some(stage_name))?;
-            check_function_contracts(&method.node, env, Some(stage_name))?;
+            let key = || UnitKey { kind: "method", name: mangle_method(stage_name, &method.node.name.node), span: method.span };
+            body_unit(env, &mut cache, key, &[method.span.file_id, stage_spanned.span.file_id], |env| {
+                check_function(&method.node, env, Some(stage_name))?;
+                check_function_contracts(&method.node, env, Some(stage_name))
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Type-check a class's invariant expressions, each of which must be bool.
+fn check_class_invariants(c: &ClassDecl, env: &mut TypeEnv) -> Result<(), CompileError> {
+    env.push_scope();
+    env.define_unchecked("self".to_string(), PlutoType::Class(c.name.node.clone()));
+    for inv in &c.invariants {
+        let inv_type = super::infer::infer_expr(&inv.node.expr.node, inv.node.expr.span, env, None)?;
+        if inv_type != PlutoType::Bool {
+            return Err(CompileError::type_err(
+                format!("invariant expression must be bool, found {inv_type}"),
+                inv.node.expr.span,
+            ));
+        }
+    }
+    env.pop_scope();
+    Ok(())
+}
+
+/// Type-check the `requires` contracts on a trait's abstract methods.
+fn check_trait_contracts(t: &TraitDecl, env: &mut TypeEnv) -> Result<(), CompileError> {
+    for m in &t.methods {
+        if m.contracts.is_empty() {
+            continue;
+        }
+        // Resolve param types and return type
+        let mut param_types = Vec::new();
+        for p in &m.params {
+            if p.name.node == "self" {
+                param_types.push(("self".to_string(), PlutoType::Void));
+            } else {
+                let ty = resolve_type(&p.ty, env)?;
+                param_types.push((p.name.node.clone(), ty));
+            }
+        }
+        let _return_type = match &m.return_type {
+            Some(rt) => resolve_type(rt, env)?,
+            None => PlutoType::Void,
+        };
+
+        // Check requires clauses
+        let has_requires = m.contracts.iter().any(|c| c.node.kind == ContractKind::Requires);
+        if has_requires {
+            env.push_scope();
+            for (name, ty) in &param_types {
+                env.define_unchecked(name.clone(), ty.clone());
+            }
+            for contract in &m.contracts {
+                if contract.node.kind == ContractKind::Requires {
+                    let ty = super::infer::infer_expr(&contract.node.expr.node, contract.node.expr.span, env, None)?;
+                    if ty != PlutoType::Bool {
+                        return Err(CompileError::type_err(
+                            format!("requires expression must be bool, found {ty}"),
+                            contract.node.expr.span,
+                        ));
+                    }
+                }
+            }
+            env.pop_scope();
         }
     }
     Ok(())
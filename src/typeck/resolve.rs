@@ -6,7 +6,7 @@ use crate::span::{Span, Spanned};
 use super::env::{self, mangle_method, ClassInfo, EnumInfo, FuncSig, InstKind, Instantiation, TypeEnv};
 use super::types::{GenericKind, PlutoType};
 
-/// Try to resolve a built-in generic type (Map, Set, Task, Sender, Receiver).
+/// Try to resolve a built-in generic type (Map, Set, Task, Sender, Receiver, Mutex).
 /// Returns `Some(Ok(...))` on success, `Some(Err(...))` on arity mismatch, `None` if not a builtin.
 fn resolve_builtin_generic(name: &str, resolved_args: &[PlutoType], span: Span) -> Option<Result<PlutoType, CompileError>> {
     match name {
@@ -22,7 +22,7 @@ fn resolve_builtin_generic(name: &str, resolved_args: &[PlutoType], span: Span)
                 Box::new(resolved_args[1].clone()),
             )))
         }
-        "Set" | "Task" | "Sender" | "Receiver" => {
+        "Set" | "Task" | "Sender" | "Receiver" | "Mutex" => {
             if resolved_args.len() != 1 {
                 return Some(Err(CompileError::type_err(
                     format!("{name} expects 1 type argument, got {}", resolved_args.len()),
@@ -34,6 +34,7 @@ fn resolve_builtin_generic(name: &str, resolved_args: &[PlutoType], span: Span)
                 "Set" => PlutoType::Set(inner),
                 "Task" => PlutoType::Task(inner),
                 "Sender" => PlutoType::Sender(inner),
+                "Mutex" => PlutoType::Mutex(inner),
                 _ => PlutoType::Receiver(inner),
             };
             Some(Ok(ty))
@@ -52,6 +53,9 @@ pub(crate) fn resolve_type(ty: &Spanned<TypeExpr>, env: &mut TypeEnv) -> Result<
             "void" => Ok(PlutoType::Void),
             "byte" => Ok(PlutoType::Byte),
             "bytes" => Ok(PlutoType::Bytes),
+            "WaitGroup" => Ok(PlutoType::WaitGroup),
+            "Atomic" => Ok(PlutoType::Atomic),
+            "error" => Ok(PlutoType::Error),
             _ => {
                 if env.classes.contains_key(name) {
                     Ok(PlutoType::Class(name.clone()))
@@ -59,6 +63,9 @@ pub(crate) fn resolve_type(ty: &Spanned<TypeExpr>, env: &mut TypeEnv) -> Result<
                     Ok(PlutoType::Trait(name.clone()))
                 } else if env.enums.contains_key(name) {
                     Ok(PlutoType::Enum(name.clone()))
+                } else if env.errors.contains_key(name) {
+                    // Error values are typed like a typed-catch binding
+                    Ok(PlutoType::Class(name.clone()))
                 } else {
                     Err(CompileError::type_err(
                         format!("unknown type '{name}'"),
@@ -111,12 +118,12 @@ pub(crate) fn resolve_type(ty: &Spanned<TypeExpr>, env: &mut TypeEnv) -> Result<
                 Ok(PlutoType::Enum(mangled))
             } else if env.generic_classes.contains_key(name.as_str()) {
                 let gen_info = env.generic_classes.get(name.as_str()).unwrap().clone();
-                validate_type_bounds(&gen_info.type_params, &resolved_args, &gen_info.type_param_bounds, env, ty.span, name)?;
+                validate_type_bounds(&gen_info.type_params, &resolved_args, &gen_info.type_param_bounds, env, &|_| ty.span, name)?;
                 let m = ensure_generic_class_instantiated(name, &resolved_args, env);
                 Ok(PlutoType::Class(m))
             } else if env.generic_enums.contains_key(name.as_str()) {
                 let gen_info = env.generic_enums.get(name.as_str()).unwrap().clone();
-                validate_type_bounds(&gen_info.type_params, &resolved_args, &gen_info.type_param_bounds, env, ty.span, name)?;
+                validate_type_bounds(&gen_info.type_params, &resolved_args, &gen_info.type_param_bounds, env, &|_| ty.span, name)?;
                 let m = ensure_generic_enum_instantiated(name, &resolved_args, env);
                 Ok(PlutoType::Enum(m))
             } else {
@@ -198,12 +205,12 @@ pub(crate) fn resolve_type_with_params(
                     Ok(PlutoType::Enum(mangled))
                 } else if env.generic_classes.contains_key(name.as_str()) {
                     let gen_info = env.generic_classes.get(name.as_str()).unwrap().clone();
-                    validate_type_bounds(&gen_info.type_params, &resolved_args, &gen_info.type_param_bounds, env, ty.span, name)?;
+                    validate_type_bounds(&gen_info.type_params, &resolved_args, &gen_info.type_param_bounds, env, &|_| ty.span, name)?;
                     let m = ensure_generic_class_instantiated(name, &resolved_args, env);
                     Ok(PlutoType::Class(m))
                 } else if env.generic_enums.contains_key(name.as_str()) {
                     let gen_info = env.generic_enums.get(name.as_str()).unwrap().clone();
-                    validate_type_bounds(&gen_info.type_params, &resolved_args, &gen_info.type_param_bounds, env, ty.span, name)?;
+                    validate_type_bounds(&gen_info.type_params, &resolved_args, &gen_info.type_param_bounds, env, &|_| ty.span, name)?;
                     let m = ensure_generic_enum_instantiated(name, &resolved_args, env);
                     Ok(PlutoType::Enum(m))
                 } else {
@@ -315,6 +322,13 @@ pub(crate) fn unify(pattern: &PlutoType, concrete: &PlutoType, bindings: &mut Ha
                 false
             }
         }
+        PlutoType::Mutex(pt) => {
+            if let PlutoType::Mutex(ct) = concrete {
+                unify(pt, ct, bindings)
+            } else {
+                false
+            }
+        }
         PlutoType::Nullable(p_inner) => {
             if let PlutoType::Nullable(c_inner) = concrete {
                 unify(p_inner, c_inner, bindings)
@@ -374,6 +388,7 @@ pub(crate) fn resolve_generic_instances(ty: &PlutoType, env: &mut TypeEnv) -> Pl
         PlutoType::Task(t) => PlutoType::Task(Box::new(resolve_generic_instances(t, env))),
         PlutoType::Sender(t) => PlutoType::Sender(Box::new(resolve_generic_instances(t, env))),
         PlutoType::Receiver(t) => PlutoType::Receiver(Box::new(resolve_generic_instances(t, env))),
+        PlutoType::Mutex(t) => PlutoType::Mutex(Box::new(resolve_generic_instances(t, env))),
         PlutoType::Nullable(inner) => PlutoType::Nullable(Box::new(resolve_generic_instances(inner, env))),
         PlutoType::Stream(inner) => PlutoType::Stream(Box::new(resolve_generic_instances(inner, env))),
         _ => ty.clone(),
@@ -382,29 +397,28 @@ pub(crate) fn resolve_generic_instances(ty: &PlutoType, env: &mut TypeEnv) -> Pl
 
 /// Validate that concrete type arguments satisfy their type parameter bounds.
 /// Each type parameter may have bounds like `T: Trait1 + Trait2`, meaning the
-/// concrete type must be a class that implements all the required traits.
+/// concrete type must be a class that implements all the required traits (or
+/// a trait object of that trait). `span_for` maps a type parameter to the span
+/// to report, so call sites can point at the argument that bound it.
 pub(crate) fn validate_type_bounds(
     type_params: &[String],
     type_args: &[PlutoType],
     bounds: &HashMap<String, Vec<String>>,
     env: &TypeEnv,
-    span: crate::span::Span,
+    span_for: &dyn Fn(&str) -> Span,
     generic_name: &str,
 ) -> Result<(), CompileError> {
     for (param, arg) in type_params.iter().zip(type_args.iter()) {
         if let Some(required_traits) = bounds.get(param) {
             for trait_name in required_traits {
-                let satisfies = match arg {
-                    PlutoType::Class(class_name) => env.class_implements_trait(class_name, trait_name),
-                    _ => false,
-                };
-                if !satisfies {
+                if !satisfies_trait(arg, trait_name, env) {
                     return Err(CompileError::type_err(
                         format!(
-                            "type {} does not satisfy bound '{}: {}' required by '{}'",
-                            arg, param, trait_name, generic_name
+                            "type {} does not satisfy bound '{}: {}' required by '{}': {}",
+                            arg, param, trait_name, generic_name,
+                            unsatisfied_bound_reason(arg, trait_name, env)
                         ),
-                        span,
+                        span_for(param),
                     ));
                 }
             }
@@ -413,10 +427,74 @@ pub(crate) fn validate_type_bounds(
     Ok(())
 }
 
+fn satisfies_trait(arg: &PlutoType, trait_name: &str, env: &TypeEnv) -> bool {
+    match arg {
+        PlutoType::Class(class_name) => env.class_implements_trait(class_name, trait_name),
+        PlutoType::Trait(name) => name == trait_name,
+        _ => false,
+    }
+}
+
+/// Explain why `arg` does not implement `trait_name`: the trait methods it
+/// lacks, or the missing `impl` clause when it already has them all.
+fn unsatisfied_bound_reason(arg: &PlutoType, trait_name: &str, env: &TypeEnv) -> String {
+    let PlutoType::Class(class_name) = arg else {
+        return format!("only classes can implement traits, and {arg} is not a class");
+    };
+    let (Some(class), Some(trait_info)) = (env.classes.get(class_name), env.traits.get(trait_name)) else {
+        return format!("{class_name} does not implement {trait_name}");
+    };
+    let missing: Vec<String> = trait_info.methods.iter()
+        .map(|(name, _)| name)
+        .filter(|name| !trait_info.default_methods.contains(name) && !class.methods.contains(name))
+        .map(|name| format!("'{name}'"))
+        .collect();
+    match missing.len() {
+        0 => format!("{class_name} does not declare 'impl {trait_name}'"),
+        1 => format!("{class_name} is missing method {}", missing[0]),
+        _ => format!("{class_name} is missing methods {}", missing.join(", ")),
+    }
+}
+
 pub(crate) fn ensure_generic_func_instantiated(
     base_name: &str,
     type_args: &[PlutoType],
     env: &mut TypeEnv,
+) -> String {
+    log_instantiation(env, InstKind::Function(base_name.to_string()), type_args);
+    register_generic_instance(base_name, type_args, InstKind::Function(base_name.to_string()), env)
+}
+
+/// Like `ensure_generic_func_instantiated`, for a generic method of a
+/// non-generic class. The instance is the method `method$$T...` of the class,
+/// so its mangled name is `Class$method$$T...`.
+pub(crate) fn ensure_generic_method_instantiated(
+    class_name: &str,
+    method: &str,
+    type_args: &[PlutoType],
+    env: &mut TypeEnv,
+) -> String {
+    let base_name = env::mangle_method(class_name, method);
+    let kind = InstKind::Method(class_name.to_string(), method.to_string());
+    log_instantiation(env, kind.clone(), type_args);
+    let mangled = register_generic_instance(&base_name, type_args, kind, env);
+    if env.mut_self_methods.contains(&base_name) {
+        env.mut_self_methods.insert(mangled.clone());
+    }
+    mangled
+}
+
+fn log_instantiation(env: &mut TypeEnv, kind: InstKind, type_args: &[PlutoType]) {
+    if let Some(log) = &mut env.instantiation_log {
+        log.push(Instantiation { kind, type_args: type_args.to_vec() });
+    }
+}
+
+fn register_generic_instance(
+    base_name: &str,
+    type_args: &[PlutoType],
+    kind: InstKind,
+    env: &mut TypeEnv,
 ) -> String {
     let mangled = env::mangle_name(base_name, type_args);
     if env.functions.contains_key(&mangled) {
@@ -438,7 +516,7 @@ pub(crate) fn ensure_generic_func_instantiated(
         return_type: concrete_ret,
     });
     env.instantiations.insert(Instantiation {
-        kind: InstKind::Function(base_name.to_string()),
+        kind,
         type_args: type_args.to_vec(),
     });
     mangled
@@ -449,6 +527,7 @@ pub(crate) fn ensure_generic_class_instantiated(
     type_args: &[PlutoType],
     env: &mut TypeEnv,
 ) -> String {
+    log_instantiation(env, InstKind::Class(base_name.to_string()), type_args);
     let mangled = env::mangle_name(base_name, type_args);
     if env.classes.contains_key(&mangled) {
         return mangled;
@@ -463,15 +542,32 @@ pub(crate) fn ensure_generic_class_instantiated(
     let concrete_fields: Vec<(String, PlutoType, bool)> = gen_info.fields.iter()
         .map(|(n, t, inj)| (n.clone(), resolve_generic_instances(&substitute_pluto_type(t, &bindings), env), *inj))
         .collect();
+    // An unmet where clause drops the impl list along with the methods that
+    // implement it, so e.g. `Box<int>` neither conforms nor has `show`.
+    let where_holds = gen_info.where_bounds.iter().all(|(tp, traits)| {
+        traits.iter().all(|t| satisfies_trait(&bindings[tp], t, env))
+    });
+    let (impl_traits, gated_methods): (Vec<String>, HashSet<String>) = if where_holds {
+        (gen_info.impl_traits.clone(), HashSet::new())
+    } else {
+        let gated = gen_info.impl_traits.iter()
+            .filter_map(|t| env.traits.get(t))
+            .flat_map(|t| t.methods.iter().map(|(m, _)| m.clone()))
+            .collect();
+        (Vec::new(), gated)
+    };
     env.classes.insert(mangled.clone(), ClassInfo {
         fields: concrete_fields,
-        methods: gen_info.methods.clone(),
-        impl_traits: gen_info.impl_traits.clone(),
+        methods: gen_info.methods.iter().filter(|m| !gated_methods.contains(*m)).cloned().collect(),
+        impl_traits: impl_traits.clone(),
         lifecycle: gen_info.lifecycle,
     });
     // Also register concrete method signatures
     // Need to substitute self type as well (it references the base class name)
     for (method_name, sig) in &gen_info.method_sigs {
+        if gated_methods.contains(method_name) {
+            continue;
+        }
         let concrete_params: Vec<PlutoType> = sig.params.iter()
             .map(|p| {
                 if *p == PlutoType::Class(base_name.to_string()) {
@@ -493,7 +589,7 @@ pub(crate) fn ensure_generic_class_instantiated(
         });
     }
     // Register default trait methods for the concrete class
-    for trait_name in &gen_info.impl_traits {
+    for trait_name in &impl_traits {
         if let Some(trait_info) = env.traits.get(trait_name).cloned() {
             for (method_name, trait_sig) in &trait_info.methods {
                 if !gen_info.methods.contains(method_name) && trait_info.default_methods.contains(method_name) {
@@ -537,6 +633,7 @@ pub(crate) fn ensure_generic_enum_instantiated(
     type_args: &[PlutoType],
     env: &mut TypeEnv,
 ) -> String {
+    log_instantiation(env, InstKind::Enum(base_name.to_string()), type_args);
     let mangled = env::mangle_name(base_name, type_args);
     if env.enums.contains_key(&mangled) {
         return mangled;
@@ -559,6 +656,7 @@ pub(crate) fn ensure_generic_enum_instantiated(
     env.enums.insert(mangled.clone(), EnumInfo {
         variants: concrete_variants,
         variant_type_exprs: Vec::new(),  // Empty for instantiated enums
+        impl_traits: Vec::new(),
     });
     env.instantiations.insert(Instantiation {
         kind: InstKind::Enum(base_name.to_string()),
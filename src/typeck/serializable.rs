@@ -35,7 +35,7 @@ pub fn validate_serializable_types(program: &Program, env: &TypeEnv) -> Result<(
         // Walk all methods in each stage
         for method in &stage.node.methods {
             // Only check pub methods (these cross stage boundaries)
-            if !method.node.is_pub {
+            if !method.node.visibility.is_pub() {
                 continue;
             }
 
@@ -167,6 +167,10 @@ fn check_serializable(
         PlutoType::Task(_) => Err("Task<T> is a runtime handle and cannot be serialized".to_string()),
         PlutoType::Sender(_) => Err("Sender<T> is a runtime handle and cannot be serialized".to_string()),
         PlutoType::Receiver(_) => Err("Receiver<T> is a runtime handle and cannot be serialized".to_string()),
+        PlutoType::WaitGroup => Err("WaitGroup is a runtime handle and cannot be serialized".to_string()),
+        PlutoType::Atomic => Err("Atomic is a runtime handle and cannot be serialized".to_string()),
+        PlutoType::Mutex(_) => Err("Mutex<T> is a runtime handle and cannot be serialized".to_string()),
+        PlutoType::MutexGuard(_) => Err("MutexGuard<T> is a runtime handle and cannot be serialized".to_string()),
         PlutoType::Trait(_) => Err("trait types cannot be serialized (vtable pointer with no concrete type)".to_string()),
 
         // Stream is special — will be handled by streaming RPC (Phase 8), not marshaling
@@ -198,6 +202,8 @@ fn resolve_type_expr(ty_expr: &TypeExpr, env: &TypeEnv) -> Result<PlutoType, Com
                 "string" => Ok(PlutoType::String),
                 "byte" => Ok(PlutoType::Byte),
                 "void" => Ok(PlutoType::Void),
+                "WaitGroup" => Ok(PlutoType::WaitGroup),
+                "Atomic" => Ok(PlutoType::Atomic),
                 _ => {
                     // Check if it's a class, enum, or trait
                     if env.classes.contains_key(name) {
@@ -280,6 +286,16 @@ fn resolve_type_expr(ty_expr: &TypeExpr, env: &TypeEnv) -> Result<PlutoType, Com
                     let inner = resolve_type_expr(&type_args[0].node, env)?;
                     Ok(PlutoType::Receiver(Box::new(inner)))
                 }
+                "Mutex" => {
+                    if type_args.len() != 1 {
+                        return Err(CompileError::type_err(
+                            format!("Mutex requires 1 type argument, got {}", type_args.len()),
+                            Span { start: 0, end: 0, file_id: 0 },
+                        ));
+                    }
+                    let inner = resolve_type_expr(&type_args[0].node, env)?;
+                    Ok(PlutoType::Mutex(Box::new(inner)))
+                }
                 _ => {
                     // User-defined generic class/enum (should have been monomorphized)
                     Err(CompileError::type_err(
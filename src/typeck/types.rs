@@ -30,6 +30,18 @@ pub enum PlutoType {
     GenericInstance(GenericKind, std::string::String, Vec<PlutoType>),
     Nullable(Box<PlutoType>),
     Stream(Box<PlutoType>),
+    /// Counter that `wait()`s until every registered task has called `done()`.
+    /// Shared by reference across spawns, like channels.
+    WaitGroup,
+    /// Integer updated with atomic operations. Shared by reference across
+    /// spawns, so every task sees the same counter.
+    Atomic,
+    /// Lock-protected value. Shared by reference across spawns; the value
+    /// is only reachable through the guard returned by `lock()`.
+    Mutex(Box<PlutoType>),
+    /// Guard returned by `Mutex.lock()`. The lock is released when the guard's
+    /// enclosing block exits. Not nameable in type annotations.
+    MutexGuard(Box<PlutoType>),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
@@ -53,6 +65,8 @@ impl PlutoType {
             PlutoType::Task(t) => PlutoType::Task(Box::new(f(t))),
             PlutoType::Sender(t) => PlutoType::Sender(Box::new(f(t))),
             PlutoType::Receiver(t) => PlutoType::Receiver(Box::new(f(t))),
+            PlutoType::Mutex(t) => PlutoType::Mutex(Box::new(f(t))),
+            PlutoType::MutexGuard(t) => PlutoType::MutexGuard(Box::new(f(t))),
             PlutoType::Nullable(inner) => PlutoType::Nullable(Box::new(f(inner))),
             PlutoType::GenericInstance(kind, name, args) => PlutoType::GenericInstance(
                 kind.clone(),
@@ -72,7 +86,8 @@ impl PlutoType {
             PlutoType::Fn(params, ret) => params.iter().any(|p| pred(p)) || pred(ret),
             PlutoType::Map(k, v) => pred(k) || pred(v),
             PlutoType::Set(t) | PlutoType::Task(t) | PlutoType::Sender(t)
-            | PlutoType::Receiver(t) | PlutoType::Nullable(t) => pred(t),
+            | PlutoType::Receiver(t) | PlutoType::Nullable(t)
+            | PlutoType::Mutex(t) | PlutoType::MutexGuard(t) => pred(t),
             PlutoType::GenericInstance(_, _, args) => args.iter().any(|a| pred(a)),
             _ => false,
         }
@@ -111,6 +126,10 @@ impl std::fmt::Display for PlutoType {
             PlutoType::Receiver(inner) => write!(f, "Receiver<{inner}>"),
             PlutoType::Nullable(inner) => write!(f, "{inner}?"),
             PlutoType::Stream(inner) => write!(f, "stream {inner}"),
+            PlutoType::WaitGroup => write!(f, "WaitGroup"),
+            PlutoType::Atomic => write!(f, "Atomic"),
+            PlutoType::Mutex(inner) => write!(f, "Mutex<{inner}>"),
+            PlutoType::MutexGuard(inner) => write!(f, "MutexGuard<{inner}>"),
             PlutoType::GenericInstance(_, name, args) => {
                 write!(f, "{name}<")?;
                 for (i, a) in args.iter().enumerate() {
@@ -173,6 +192,16 @@ pub fn pluto_type_to_type_expr(ty: &PlutoType) -> TypeExpr {
         PlutoType::Range => TypeExpr::Named("range".to_string()),
         PlutoType::Byte => TypeExpr::Named("byte".to_string()),
         PlutoType::Bytes => TypeExpr::Named("bytes".to_string()),
+        PlutoType::WaitGroup => TypeExpr::Named("WaitGroup".to_string()),
+        PlutoType::Atomic => TypeExpr::Named("Atomic".to_string()),
+        PlutoType::Mutex(t) => TypeExpr::Generic {
+            name: "Mutex".to_string(),
+            type_args: vec![Spanned::dummy(pluto_type_to_type_expr(t))],
+        },
+        PlutoType::MutexGuard(t) => TypeExpr::Generic {
+            name: "MutexGuard".to_string(),
+            type_args: vec![Spanned::dummy(pluto_type_to_type_expr(t))],
+        },
         PlutoType::GenericInstance(_, name, args) => TypeExpr::Generic {
             name: name.clone(),
             type_args: args.iter()
@@ -320,6 +349,8 @@ mod tests {
             PlutoType::Bytes,
             PlutoType::Range,
             PlutoType::Error,
+            PlutoType::WaitGroup,
+            PlutoType::Atomic,
         ];
         for ty in leaf_types {
             let result = ty.map_inner_types(&|_| PlutoType::Float);
@@ -489,6 +520,14 @@ mod tests {
         assert_eq!(ty.to_string(), "Receiver<int>");
     }
 
+    #[test]
+    fn test_display_mutex() {
+        let ty = PlutoType::Mutex(Box::new(PlutoType::Int));
+        assert_eq!(ty.to_string(), "Mutex<int>");
+        let guard = PlutoType::MutexGuard(Box::new(PlutoType::Int));
+        assert_eq!(guard.to_string(), "MutexGuard<int>");
+    }
+
     #[test]
     fn test_display_stream() {
         let ty = PlutoType::Stream(Box::new(PlutoType::Int));
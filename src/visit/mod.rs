@@ -261,6 +261,18 @@ pub fn walk_program<V: Visitor>(v: &mut V, program: &Program) {
     for enum_decl in &program.enums {
         v.visit_enum(enum_decl);
     }
+    for const_decl in &program.consts {
+        if let Some(ty) = &const_decl.node.ty {
+            v.visit_type_expr(ty);
+        }
+        v.visit_expr(&const_decl.node.value);
+    }
+    for global_decl in &program.globals {
+        if let Some(ty) = &global_decl.node.ty {
+            v.visit_type_expr(ty);
+        }
+        v.visit_expr(&global_decl.node.value);
+    }
     if let Some(app) = &program.app {
         v.visit_app(app);
     }
@@ -359,6 +371,9 @@ pub fn walk_enum<V: Visitor>(v: &mut V, enum_decl: &Spanned<EnumDecl>) {
             v.visit_type_expr(&field.ty);
         }
     }
+    for method in &enum_decl.node.methods {
+        v.visit_function(method);
+    }
 }
 
 pub fn walk_error<V: Visitor>(v: &mut V, error_decl: &Spanned<ErrorDecl>) {
@@ -422,6 +437,10 @@ pub fn walk_stmt<V: Visitor>(v: &mut V, stmt: &Spanned<Stmt>) {
             }
             v.visit_expr(value);
         }
+        Stmt::LetStruct { ty, value, .. } => {
+            v.visit_type_expr(ty);
+            v.visit_expr(value);
+        }
         Stmt::Return(Some(expr)) => v.visit_expr(expr),
         Stmt::Return(None) | Stmt::Break | Stmt::Continue => {}
         Stmt::Assign { value, .. } => v.visit_expr(value),
@@ -440,6 +459,18 @@ pub fn walk_stmt<V: Visitor>(v: &mut V, stmt: &Spanned<Stmt>) {
                 v.visit_block(eb);
             }
         }
+        Stmt::IfLet {
+            value,
+            then_block,
+            else_block,
+            ..
+        } => {
+            v.visit_expr(value);
+            v.visit_block(then_block);
+            if let Some(eb) = else_block {
+                v.visit_block(eb);
+            }
+        }
         Stmt::While { condition, body } => {
             v.visit_expr(condition);
             v.visit_block(body);
@@ -466,6 +497,26 @@ pub fn walk_stmt<V: Visitor>(v: &mut V, stmt: &Spanned<Stmt>) {
                 v.visit_block(&arm.body);
             }
         }
+        Stmt::IntMatch { expr, arms } => {
+            v.visit_expr(expr);
+            for arm in arms {
+                for pat in &arm.patterns {
+                    for e in pat.node.exprs() {
+                        v.visit_expr(e);
+                    }
+                }
+                v.visit_block(&arm.body);
+            }
+        }
+        Stmt::NullableMatch { expr, some_arm, none_arm } => {
+            v.visit_expr(expr);
+            if let Some(arm) = some_arm {
+                v.visit_block(&arm.body);
+            }
+            if let Some(nb) = none_arm {
+                v.visit_block(nb);
+            }
+        }
         Stmt::Raise { fields, .. } => {
             for (_, val) in fields {
                 v.visit_expr(val);
@@ -509,6 +560,13 @@ pub fn walk_stmt<V: Visitor>(v: &mut V, stmt: &Spanned<Stmt>) {
             }
             v.visit_block(body);
         }
+        Stmt::Try { body, handler, finally, .. } => {
+            v.visit_block(body);
+            v.visit_block(handler);
+            if let Some(finally) = finally {
+                v.visit_block(finally);
+            }
+        }
         Stmt::Assert { expr } => v.visit_expr(expr),
         Stmt::Serve { service, port } => {
             v.visit_expr(service);
@@ -531,11 +589,16 @@ pub fn walk_expr<V: Visitor>(v: &mut V, expr: &Spanned<Expr>) {
         | Expr::StringLit(_)
         | Expr::NoneLit
         | Expr::Ident(_)
+        | Expr::MethodRef { .. }
         | Expr::ClosureCreate { .. } => {}
 
         // Unary wrappers
         Expr::UnaryOp { operand, .. } => v.visit_expr(operand),
         Expr::Propagate { expr: inner } => v.visit_expr(inner),
+        Expr::PropagateMap { expr: inner, mapper } => {
+            v.visit_expr(inner);
+            v.visit_expr(mapper);
+        }
         Expr::NullPropagate { expr: inner } => v.visit_expr(inner),
         Expr::Spawn { call } => v.visit_expr(call),
         Expr::Cast {
@@ -545,6 +608,7 @@ pub fn walk_expr<V: Visitor>(v: &mut V, expr: &Spanned<Expr>) {
             v.visit_expr(inner);
             v.visit_type_expr(target_type);
         }
+        Expr::Is { expr: inner, .. } => v.visit_expr(inner),
         Expr::FieldAccess { object, .. } => v.visit_expr(object),
 
         // Binary
@@ -817,6 +881,18 @@ pub fn walk_program_mut<V: VisitMut>(v: &mut V, program: &mut Program) {
     for enum_decl in &mut program.enums {
         v.visit_enum_mut(enum_decl);
     }
+    for const_decl in &mut program.consts {
+        if let Some(ty) = &mut const_decl.node.ty {
+            v.visit_type_expr_mut(ty);
+        }
+        v.visit_expr_mut(&mut const_decl.node.value);
+    }
+    for global_decl in &mut program.globals {
+        if let Some(ty) = &mut global_decl.node.ty {
+            v.visit_type_expr_mut(ty);
+        }
+        v.visit_expr_mut(&mut global_decl.node.value);
+    }
     if let Some(app) = &mut program.app {
         v.visit_app_mut(app);
     }
@@ -892,6 +968,9 @@ pub fn walk_enum_mut<V: VisitMut>(v: &mut V, enum_decl: &mut Spanned<EnumDecl>)
             v.visit_type_expr_mut(&mut field.ty);
         }
     }
+    for method in &mut enum_decl.node.methods {
+        v.visit_function_mut(method);
+    }
 }
 
 pub fn walk_error_mut<V: VisitMut>(v: &mut V, error_decl: &mut Spanned<ErrorDecl>) {
@@ -944,6 +1023,10 @@ pub fn walk_stmt_mut<V: VisitMut>(v: &mut V, stmt: &mut Spanned<Stmt>) {
             }
             v.visit_expr_mut(value);
         }
+        Stmt::LetStruct { ty, value, .. } => {
+            v.visit_type_expr_mut(ty);
+            v.visit_expr_mut(value);
+        }
         Stmt::Return(Some(expr)) => v.visit_expr_mut(expr),
         Stmt::Return(None) | Stmt::Break | Stmt::Continue => {}
         Stmt::Assign { value, .. } => v.visit_expr_mut(value),
@@ -962,6 +1045,18 @@ pub fn walk_stmt_mut<V: VisitMut>(v: &mut V, stmt: &mut Spanned<Stmt>) {
                 v.visit_block_mut(eb);
             }
         }
+        Stmt::IfLet {
+            value,
+            then_block,
+            else_block,
+            ..
+        } => {
+            v.visit_expr_mut(value);
+            v.visit_block_mut(then_block);
+            if let Some(eb) = else_block {
+                v.visit_block_mut(eb);
+            }
+        }
         Stmt::While { condition, body } => {
             v.visit_expr_mut(condition);
             v.visit_block_mut(body);
@@ -988,6 +1083,26 @@ pub fn walk_stmt_mut<V: VisitMut>(v: &mut V, stmt: &mut Spanned<Stmt>) {
                 v.visit_block_mut(&mut arm.body);
             }
         }
+        Stmt::IntMatch { expr, arms } => {
+            v.visit_expr_mut(expr);
+            for arm in arms {
+                for pat in &mut arm.patterns {
+                    for e in pat.node.exprs_mut() {
+                        v.visit_expr_mut(e);
+                    }
+                }
+                v.visit_block_mut(&mut arm.body);
+            }
+        }
+        Stmt::NullableMatch { expr, some_arm, none_arm } => {
+            v.visit_expr_mut(expr);
+            if let Some(arm) = some_arm {
+                v.visit_block_mut(&mut arm.body);
+            }
+            if let Some(nb) = none_arm {
+                v.visit_block_mut(nb);
+            }
+        }
         Stmt::Raise { fields, .. } => {
             for (_, val) in fields {
                 v.visit_expr_mut(val);
@@ -1031,6 +1146,13 @@ pub fn walk_stmt_mut<V: VisitMut>(v: &mut V, stmt: &mut Spanned<Stmt>) {
             }
             v.visit_block_mut(body);
         }
+        Stmt::Try { body, handler, finally, .. } => {
+            v.visit_block_mut(body);
+            v.visit_block_mut(handler);
+            if let Some(finally) = finally {
+                v.visit_block_mut(finally);
+            }
+        }
         Stmt::Assert { expr } => v.visit_expr_mut(expr),
         Stmt::Serve { service, port } => {
             v.visit_expr_mut(service);
@@ -1049,10 +1171,15 @@ pub fn walk_expr_mut<V: VisitMut>(v: &mut V, expr: &mut Spanned<Expr>) {
         | Expr::StringLit(_)
         | Expr::NoneLit
         | Expr::Ident(_)
+        | Expr::MethodRef { .. }
         | Expr::ClosureCreate { .. } => {}
 
         Expr::UnaryOp { operand, .. } => v.visit_expr_mut(operand),
         Expr::Propagate { expr: inner } => v.visit_expr_mut(inner),
+        Expr::PropagateMap { expr: inner, mapper } => {
+            v.visit_expr_mut(inner);
+            v.visit_expr_mut(mapper);
+        }
         Expr::NullPropagate { expr: inner } => v.visit_expr_mut(inner),
         Expr::Spawn { call } => v.visit_expr_mut(call),
         Expr::Cast {
@@ -1062,6 +1189,7 @@ pub fn walk_expr_mut<V: VisitMut>(v: &mut V, expr: &mut Spanned<Expr>) {
             v.visit_expr_mut(inner);
             v.visit_type_expr_mut(target_type);
         }
+        Expr::Is { expr: inner, .. } => v.visit_expr_mut(inner),
         Expr::FieldAccess { object, .. } => v.visit_expr_mut(object),
 
         Expr::BinOp { lhs, rhs, .. } => {
@@ -1264,6 +1392,7 @@ mod tests {
                 Expr::Closure { .. } => "Closure",
                 Expr::Catch { .. } => "Catch",
                 Expr::Propagate { .. } => "Propagate",
+                Expr::PropagateMap { .. } => "PropagateMap",
                 Expr::Cast { .. } => "Cast",
                 Expr::StringInterp { .. } => "StringInterp",
                 Expr::Range { .. } => "Range",
@@ -1273,9 +1402,11 @@ mod tests {
                 Expr::SetLit { .. } => "SetLit",
                 Expr::NullPropagate { .. } => "NullPropagate",
                 Expr::StaticTraitCall { .. } => "StaticTraitCall",
+                Expr::MethodRef { .. } => "MethodRef",
                 Expr::QualifiedAccess { .. } => "QualifiedAccess",
                 Expr::If { .. } => "If",
                 Expr::Match { .. } => "Match",
+                Expr::Is { .. } => "Is",
             };
             self.visited.insert(expr_type.to_string());
             walk_expr(self, expr);
@@ -1536,6 +1667,23 @@ mod tests {
         assert!(collector.visited.contains("IntLit"));
     }
 
+    #[test]
+    fn test_walk_expr_visits_is_operand() {
+        let is_expr = dummy(Expr::Is {
+            expr: Box::new(dummy(Expr::Ident("shape".to_string()))),
+            enum_name: dummy("Shape".to_string()),
+            variant: dummy("Circle".to_string()),
+            enum_id: None,
+            variant_id: None,
+        });
+
+        let mut collector = ExprCollector::default();
+        collector.visit_expr(&is_expr);
+
+        assert!(collector.visited.contains("Is"));
+        assert!(collector.visited.contains("Ident"));
+    }
+
     // ============================================================================
     // Test: walk_expr handles StringInterp parts
     // ============================================================================
@@ -1622,6 +1770,50 @@ mod tests {
         assert!(collector.visited.contains("IntLit"));
     }
 
+    #[test]
+    fn test_walk_stmt_visits_if_let_value_and_branches() {
+        let if_let = dummy(Stmt::IfLet {
+            binding: dummy("x".to_string()),
+            value: dummy(Expr::NoneLit),
+            then_block: dummy(Block {
+                stmts: vec![dummy(Stmt::Expr(dummy(Expr::IntLit(1))))],
+            }),
+            else_block: Some(dummy(Block {
+                stmts: vec![dummy(Stmt::Expr(dummy(Expr::BoolLit(false))))],
+            })),
+        });
+
+        let mut collector = ExprCollector::default();
+        collector.visit_stmt(&if_let);
+
+        assert!(collector.visited.contains("NoneLit"));
+        assert!(collector.visited.contains("IntLit"));
+        assert!(collector.visited.contains("BoolLit"));
+    }
+
+    #[test]
+    fn test_walk_stmt_visits_try_body_and_handler() {
+        let try_stmt = dummy(Stmt::Try {
+            body: dummy(Block {
+                stmts: vec![dummy(Stmt::Expr(dummy(Expr::IntLit(1))))],
+            }),
+            var: dummy("e".to_string()),
+            handler: dummy(Block {
+                stmts: vec![dummy(Stmt::Expr(dummy(Expr::BoolLit(false))))],
+            }),
+            finally: Some(dummy(Block {
+                stmts: vec![dummy(Stmt::Expr(dummy(Expr::FloatLit(1.5))))],
+            })),
+        });
+
+        let mut collector = ExprCollector::default();
+        collector.visit_stmt(&try_stmt);
+
+        assert!(collector.visited.contains("IntLit"));
+        assert!(collector.visited.contains("BoolLit"));
+        assert!(collector.visited.contains("FloatLit"));
+    }
+
     #[test]
     fn test_walk_stmt_visits_let_value() {
         let let_stmt = dummy(Stmt::Let {
@@ -1637,6 +1829,21 @@ mod tests {
         assert!(collector.visited.contains("IntLit"));
     }
 
+    #[test]
+    fn test_walk_stmt_visits_let_struct_value() {
+        let let_stmt = dummy(Stmt::LetStruct {
+            ty: dummy(TypeExpr::Named("Point".to_string())),
+            fields: vec![(dummy("x".to_string()), None)],
+            value: dummy(Expr::Ident("p".to_string())),
+            is_mut: false,
+        });
+
+        let mut collector = ExprCollector::default();
+        collector.visit_stmt(&let_stmt);
+
+        assert!(collector.visited.contains("Ident"));
+    }
+
     #[test]
     fn test_walk_stmt_visits_match_arms() {
         let match_stmt = dummy(Stmt::Match {
@@ -1,9 +1,11 @@
 use crossbeam_channel::{select, unbounded, Receiver, Sender};
 use notify::{Event, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::time::Duration;
 
+use crate::derived::DerivedInfo;
 use crate::diagnostics::CompileError;
 
 /// Watch a Pluto file and automatically recompile and rerun when changes are detected
@@ -20,25 +22,27 @@ pub fn watch_run(
         .map_err(|e| CompileError::codegen(format!("failed to spawn process: {}", e)))?;
     print_separator();
 
-    // Get all files to watch (entry + transitive imports)
-    let watched_files = get_watched_files(entry_file, stdlib)?;
+    // Hash the files reachable from the entry (entry + transitive imports)
+    let mut files = WatchedFiles::resolve(entry_file, stdlib);
 
     // Setup file watcher
     let (tx, rx) = unbounded();
     let mut watcher = create_watcher(tx)?;
-
-    for file in &watched_files {
-        watcher.watch(file, RecursiveMode::NonRecursive)
-            .map_err(|e| CompileError::codegen(format!("failed to watch file {}: {}", file.display(), e)))?;
-    }
+    let mut watched_dirs = HashSet::new();
+    watch_dirs(&mut watcher, &files, &mut watched_dirs)?;
 
     // Event loop
     loop {
         // Wait for file change
-        wait_for_change(&rx);
+        let mut paths = wait_for_change(&rx);
 
         // Debounce
-        debounce_events(&rx);
+        debounce_events(&rx, &mut paths);
+
+        // Ignore files outside the import graph and saves that didn't change content
+        let Some(changed) = files.changed(&paths) else {
+            continue;
+        };
 
         // Kill running process
         graceful_kill(&mut child)
@@ -50,8 +54,14 @@ pub fn watch_run(
         }
 
         // Recompile
-        println!("File changed, recompiling...");
-        match compile_entry_file(entry_file, stdlib) {
+        println!("{} changed, recompiling...", changed.display());
+        let compiled = compile_entry_file(entry_file, stdlib);
+
+        // The edit may have added or removed imports
+        files = WatchedFiles::resolve(entry_file, stdlib);
+        watch_dirs(&mut watcher, &files, &mut watched_dirs)?;
+
+        match compiled {
             Ok(new_binary) => {
                 // Spawn new process
                 match spawn_process(&new_binary) {
@@ -124,18 +134,21 @@ fn graceful_kill(child: &mut Child) -> std::io::Result<()> {
     Ok(())
 }
 
-/// Wait for the first file change event
-fn wait_for_change(rx: &Receiver<Event>) {
+/// Wait for the first file change event and return the paths it names
+fn wait_for_change(rx: &Receiver<Event>) -> Vec<PathBuf> {
     // Block until we get an event
-    let _ = rx.recv();
+    rx.recv().map(|event| event.paths).unwrap_or_default()
 }
 
-/// Debounce events by waiting for a quiet period
-fn debounce_events(rx: &Receiver<Event>) {
+/// Debounce events by waiting for a quiet period, collecting their paths
+fn debounce_events(rx: &Receiver<Event>, paths: &mut Vec<PathBuf>) {
     loop {
         select! {
-            recv(rx) -> _event => {
+            recv(rx) -> event => {
                 // Got another event, keep waiting
+                if let Ok(event) = event {
+                    paths.extend(event.paths);
+                }
             }
             default(Duration::from_millis(100)) => {
                 // No events for 100ms, we're done
@@ -145,35 +158,84 @@ fn debounce_events(rx: &Receiver<Event>) {
     }
 }
 
-/// Get all files to watch (entry file + transitive imports)
-fn get_watched_files(entry_file: &Path, stdlib: Option<&Path>) -> Result<Vec<PathBuf>, CompileError> {
-    // Create an empty package graph for module resolution
-    let pkg_graph = crate::manifest::PackageGraph::empty();
-
-    // Use module resolution to discover all imported files
-    match crate::modules::resolve_modules(entry_file, stdlib, &pkg_graph) {
-        Ok(graph) => {
-            // Extract all file paths from the source map
-            let mut files: Vec<PathBuf> = graph.source_map
-                .files
-                .iter()
-                .filter_map(|(path, _source)| path.canonicalize().ok())
-                .collect();
-
-            // Always watch the entry file
-            if let Ok(canonical_entry) = entry_file.canonicalize() {
-                if !files.contains(&canonical_entry) {
-                    files.push(canonical_entry);
-                }
-            }
+/// Content hashes of the files reachable from the entry file through imports.
+/// File events for anything else, or for a file whose content is unchanged
+/// (e.g. a save without edits), don't trigger a rebuild.
+struct WatchedFiles {
+    hashes: HashMap<PathBuf, String>,
+}
+
+impl WatchedFiles {
+    /// Build the import graph of `entry_file` and hash every file in it
+    fn resolve(entry_file: &Path, stdlib: Option<&Path>) -> Self {
+        // Create an empty package graph for module resolution
+        let pkg_graph = crate::manifest::PackageGraph::empty();
+
+        // Use module resolution to discover all imported files. If it fails,
+        // fall back to watching just the entry file
+        let mut files: Vec<PathBuf> = match crate::modules::resolve_modules(entry_file, stdlib, &pkg_graph) {
+            Ok(graph) => graph.source_map.files.into_iter().map(|(path, _source)| path).collect(),
+            Err(_) => vec![],
+        };
+
+        // Always watch the entry file
+        files.push(entry_file.to_path_buf());
+
+        let hashes = files
+            .into_iter()
+            .filter_map(|path| path.canonicalize().ok())
+            .map(|path| {
+                let hash = hash_file(&path);
+                (path, hash)
+            })
+            .collect();
+        Self { hashes }
+    }
 
-            Ok(files)
+    /// Directories holding the watched files. Watching directories rather than
+    /// files keeps working when editors save by replacing the file.
+    fn dirs(&self) -> HashSet<PathBuf> {
+        self.hashes.keys().filter_map(|path| path.parent().map(Path::to_path_buf)).collect()
+    }
+
+    /// Return the first of `paths` that is in the import graph and whose content
+    /// changed since it was last hashed, recording the new hashes
+    fn changed(&mut self, paths: &[PathBuf]) -> Option<PathBuf> {
+        let mut first = None;
+        for path in paths {
+            let Ok(path) = path.canonicalize() else { continue };
+            let Some(old_hash) = self.hashes.get_mut(&path) else { continue };
+            let hash = hash_file(&path);
+            if *old_hash != hash {
+                *old_hash = hash;
+                first.get_or_insert(path);
+            }
         }
-        Err(_) => {
-            // If module resolution fails, fall back to watching just the entry file
-            Ok(vec![entry_file.canonicalize().unwrap_or_else(|_| entry_file.to_path_buf())])
+        first
+    }
+}
+
+fn hash_file(path: &Path) -> String {
+    std::fs::read(path)
+        .map(|data| DerivedInfo::compute_source_hash(&String::from_utf8_lossy(&data)))
+        .unwrap_or_default()
+}
+
+/// Start watching the directories of `files` that aren't watched yet
+fn watch_dirs(
+    watcher: &mut notify::RecommendedWatcher,
+    files: &WatchedFiles,
+    watched: &mut HashSet<PathBuf>,
+) -> Result<(), CompileError> {
+    for dir in files.dirs() {
+        if watched.contains(&dir) {
+            continue;
         }
+        watcher.watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| CompileError::codegen(format!("failed to watch directory {}: {}", dir.display(), e)))?;
+        watched.insert(dir);
     }
+    Ok(())
 }
 
 /// Create a file watcher with the given sender
@@ -204,25 +266,27 @@ pub fn watch_test(
         .map_err(|e| CompileError::codegen(format!("failed to run tests: {}", e)))?;
     print_test_separator(exit_code);
 
-    // Get all files to watch (entry + transitive imports)
-    let watched_files = get_watched_files(entry_file, stdlib)?;
+    // Hash the files reachable from the entry (entry + transitive imports)
+    let mut files = WatchedFiles::resolve(entry_file, stdlib);
 
     // Setup file watcher
     let (tx, rx) = unbounded();
     let mut watcher = create_watcher(tx)?;
-
-    for file in &watched_files {
-        watcher.watch(file, RecursiveMode::NonRecursive)
-            .map_err(|e| CompileError::codegen(format!("failed to watch file {}: {}", file.display(), e)))?;
-    }
+    let mut watched_dirs = HashSet::new();
+    watch_dirs(&mut watcher, &files, &mut watched_dirs)?;
 
     // Event loop
     loop {
         // Wait for file change
-        wait_for_change(&rx);
+        let mut paths = wait_for_change(&rx);
 
         // Debounce
-        debounce_events(&rx);
+        debounce_events(&rx, &mut paths);
+
+        // Ignore files outside the import graph and saves that didn't change content
+        let Some(changed) = files.changed(&paths) else {
+            continue;
+        };
 
         // Clear terminal
         if !no_clear {
@@ -230,8 +294,14 @@ pub fn watch_test(
         }
 
         // Recompile and run tests
-        println!("File changed, recompiling tests...");
-        match compile_test_file(entry_file, stdlib, use_cache) {
+        println!("{} changed, recompiling tests...", changed.display());
+        let compiled = compile_test_file(entry_file, stdlib, use_cache);
+
+        // The edit may have added or removed imports
+        files = WatchedFiles::resolve(entry_file, stdlib);
+        watch_dirs(&mut watcher, &files, &mut watched_dirs)?;
+
+        match compiled {
             Ok(new_binary) => {
                 match run_tests(&new_binary) {
                     Ok(exit_code) => {
@@ -289,3 +359,32 @@ fn print_test_separator(exit_code: i32) {
 fn print_separator() {
     println!("\n{}\n", "=".repeat(60));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_reachable_content_changes_trigger_rebuilds() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("main.pluto");
+        std::fs::write(&main, "import math\n\nfn main() {\n    print(math.add(1, 2))\n}\n").unwrap();
+        std::fs::create_dir(dir.path().join("math")).unwrap();
+        let add = dir.path().join("math").join("add.pluto");
+        std::fs::write(&add, "pub fn add(a: int, b: int) int {\n    return a + b\n}\n").unwrap();
+        std::fs::create_dir(dir.path().join("scratch")).unwrap();
+        let unused = dir.path().join("scratch").join("notes.pluto");
+        std::fs::write(&unused, "fn unused() {\n}\n").unwrap();
+
+        let mut files = WatchedFiles::resolve(&main, None);
+
+        // Saving without edits, or editing a file nothing imports, is ignored
+        std::fs::write(&add, "pub fn add(a: int, b: int) int {\n    return a + b\n}\n").unwrap();
+        std::fs::write(&unused, "fn unused() {\n    print(1)\n}\n").unwrap();
+        assert_eq!(files.changed(&[add.clone(), unused.clone()]), None);
+
+        std::fs::write(&add, "pub fn add(a: int, b: int) int {\n    return b + a\n}\n").unwrap();
+        assert_eq!(files.changed(&[unused, add.clone()]), Some(add.canonicalize().unwrap()));
+        assert_eq!(files.changed(std::slice::from_ref(&add)), None);
+    }
+}
@@ -8,7 +8,7 @@ use crate::visit::{walk_expr_mut, walk_stmt_mut, VisitMut};
 
 /// Index of declaration names to their UUIDs, built from the final program AST.
 struct DeclIndex {
-    /// Function name → UUID (includes top-level fns, class methods as ClassName$method, app methods as AppName$method)
+    /// Function name → UUID (includes top-level fns, class and enum methods as TypeName$method, app methods as AppName$method)
     fn_index: HashMap<String, Uuid>,
     /// Class name → UUID
     class_index: HashMap<String, Uuid>,
@@ -48,6 +48,10 @@ impl DeclIndex {
                     v.id,
                 );
             }
+            for m in &e.node.methods {
+                let mangled = mangle_method(&e.node.name.node, &m.node.name.node);
+                fn_index.insert(mangled, m.node.id);
+            }
         }
 
         for err in &program.errors {
@@ -106,6 +110,13 @@ pub fn resolve_cross_refs(program: &mut Program) {
         }
     }
 
+    // Walk enum methods
+    for e in &mut program.enums {
+        for m in &mut e.node.methods {
+            resolve_block(&mut m.node.body.node, &index);
+        }
+    }
+
     // Walk app methods
     if let Some(app) = &mut program.app {
         for m in &mut app.node.methods {
@@ -135,7 +146,8 @@ impl VisitMut for XrefResolver<'_> {
             Expr::StructLit { name, target_id, .. } => {
                 *target_id = self.index.class_index.get(&name.node).copied();
             }
-            Expr::EnumUnit { enum_name, variant, enum_id, variant_id, .. } => {
+            Expr::EnumUnit { enum_name, variant, enum_id, variant_id, .. }
+            | Expr::Is { enum_name, variant, enum_id, variant_id, .. } => {
                 *enum_id = self.index.enum_index.get(&enum_name.node).copied();
                 *variant_id = self.index.variant_index.get(
                     &(enum_name.node.clone(), variant.node.clone())
@@ -225,9 +237,11 @@ mod tests {
             return_type: None,
             contracts: vec![],
             body: empty_block(),
-            is_pub: false,
+            visibility: Visibility::Private,
             is_override: false,
             is_generator: false,
+            declared_errors: None,
+            no_coverage: false,
         })
     }
 
@@ -239,6 +253,8 @@ mod tests {
             classes: vec![],
             traits: vec![],
             enums: vec![],
+            consts: vec![],
+            globals: vec![],
             app: None,
             stages: vec![],
             system: None,
@@ -246,6 +262,7 @@ mod tests {
             test_info: vec![],
             tests: None,
             fallible_extern_fns: vec![],
+            comments: vec![],
         }
     }
 
@@ -294,8 +311,9 @@ mod tests {
             methods: vec![],
             invariants: vec![],
             impl_traits: vec![],
+            where_bounds: std::collections::HashMap::new(),
             uses: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             lifecycle: Lifecycle::Singleton,
         }));
 
@@ -336,7 +354,9 @@ mod tests {
                 name: sp("Red".to_string()),
                 fields: vec![],
             }],
-            is_pub: false,
+            methods: vec![],
+            impl_traits: vec![],
+            visibility: Visibility::Private,
         }));
 
         let mut caller = make_function("main");
@@ -378,7 +398,9 @@ mod tests {
                 name: sp("Circle".to_string()),
                 fields: vec![],
             }],
-            is_pub: false,
+            methods: vec![],
+            impl_traits: vec![],
+            visibility: Visibility::Private,
         }));
 
         let mut caller = make_function("main");
@@ -414,7 +436,7 @@ mod tests {
             id: err_id,
             name: sp("NotFound".to_string()),
             fields: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
         }));
 
         let mut caller = make_function("main");
@@ -451,7 +473,9 @@ mod tests {
                 name: sp("Some".to_string()),
                 fields: vec![],
             }],
-            is_pub: false,
+            methods: vec![],
+            impl_traits: vec![],
+            visibility: Visibility::Private,
         }));
 
         let mut caller = make_function("main");
@@ -614,14 +638,17 @@ mod tests {
                 return_type: None,
                 contracts: vec![],
                 body: empty_block(),
-                is_pub: false,
+                visibility: Visibility::Private,
                 is_override: false,
                 is_generator: false,
+                declared_errors: None,
+                no_coverage: false,
             })],
             invariants: vec![],
             impl_traits: vec![],
+            where_bounds: std::collections::HashMap::new(),
             uses: vec![],
-            is_pub: false,
+            visibility: Visibility::Private,
             lifecycle: Lifecycle::Singleton,
         }));
 
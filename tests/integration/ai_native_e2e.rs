@@ -537,3 +537,47 @@ fn main() {}
     // Hash should be different from original
     assert_ne!(hash1, hash3, "hash should change when source changes");
 }
+
+#[test]
+fn test_analyze_rejects_newer_format_version() {
+    let temp = TempDir::new().unwrap();
+    let source = "fn main() {}\n";
+    let program = pluto::parse_for_editing(source).unwrap();
+    let mut bytes = pluto::binary::serialize_program(&program, source, &Default::default()).unwrap();
+    bytes[4..8].copy_from_slice(&(pluto::binary::FormatVersion::CURRENT.0 + 1).to_le_bytes());
+    fs::write(temp.path().join("future.pluto"), &bytes).unwrap();
+
+    let output = run_pluto(&["analyze", "future.pluto"], &temp);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("newer than this build reads"), "{stderr}");
+    assert_eq!(fs::read(temp.path().join("future.pluto")).unwrap(), bytes);
+}
+
+#[test]
+fn test_compressed_pluto_round_trip() {
+    use pluto::binary::{compression_of, Compression};
+
+    let temp = TempDir::new().unwrap();
+    let source = "fn add(x: int, y: int) int {\n    return x + y\n}\n\nfn main() {\n    let result = add(1, 2)\n}\n";
+    fs::write(temp.path().join("math.pt"), source).unwrap();
+
+    let output = run_pluto(&["emit-ast", "math.pt", "--compress"], &temp);
+    assert!(output.status.success(), "emit-ast failed: {}", String::from_utf8_lossy(&output.stderr));
+    let data = fs::read(temp.path().join("math.pluto")).unwrap();
+    assert_eq!(compression_of(&data).unwrap(), Compression::Deflate);
+
+    // analyze refreshes derived data without dropping the compression
+    let output = run_pluto(&["analyze", "math.pluto"], &temp);
+    assert!(output.status.success(), "analyze failed: {}", String::from_utf8_lossy(&output.stderr));
+    let data = fs::read(temp.path().join("math.pluto")).unwrap();
+    assert_eq!(compression_of(&data).unwrap(), Compression::Deflate);
+
+    let (_, source2, derived) = pluto::binary::deserialize_program(&data).unwrap();
+    assert_eq!(source2, source);
+    assert!(!derived.is_stale(&source2));
+
+    let output = run_pluto(&["generate-pt", "math.pluto", "-o", "out.pt"], &temp);
+    assert!(output.status.success(), "generate-pt failed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(fs::read_to_string(temp.path().join("out.pt")).unwrap().contains("fn add(x: int, y: int) int"));
+}
@@ -0,0 +1,116 @@
+//! Integration tests for the incremental analyze server (`pluto watch analyze`).
+
+use std::io::Write;
+use std::path::Path;
+use std::process::Stdio;
+
+use pluto::server::AnalyzeServer;
+use tempfile::TempDir;
+
+mod common;
+
+const MAIN: &str = "import util\n\nfn main() {\n    print(util.twice(21) + 1)\n}\n";
+const UTIL: &str = "pub fn twice(n: int) int {\n    return n * 2\n}\n";
+
+fn project() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("main.pt"), MAIN).unwrap();
+    std::fs::write(dir.path().join("util.pt"), UTIL).unwrap();
+    dir
+}
+
+fn write(path: &Path, text: &str) {
+    std::fs::write(path, text).unwrap();
+}
+
+#[test]
+fn unchanged_project_is_answered_from_cache() {
+    let dir = project();
+    let main = dir.path().join("main.pt");
+    let mut server = AnalyzeServer::new(None);
+
+    let first = server.analyze(&main);
+    assert!(first.success, "{:?}", first.errors);
+    assert!(!first.cached);
+
+    let second = server.analyze(&main);
+    assert!(second.success);
+    assert!(second.cached);
+}
+
+#[test]
+fn editing_the_entry_file_reanalyzes() {
+    let dir = project();
+    let main = dir.path().join("main.pt");
+    let mut server = AnalyzeServer::new(None);
+    assert!(server.analyze(&main).success);
+
+    write(&main, "import util\n\nfn main() {\n    let x: string = util.twice(21)\n}\n");
+    let result = server.analyze(&main);
+    assert!(!result.cached);
+    assert!(!result.success);
+    let span = result.errors[0].span.as_ref().unwrap();
+    assert_eq!(span.line, Some(4));
+}
+
+#[test]
+fn editing_an_imported_file_reanalyzes() {
+    let dir = project();
+    let main = dir.path().join("main.pt");
+    let mut server = AnalyzeServer::new(None);
+    assert!(server.analyze(&main).success);
+
+    write(&dir.path().join("util.pt"), "pub fn twice(n: int) string {\n    return \"no\"\n}\n");
+    let result = server.analyze(&main);
+    assert!(!result.cached);
+    assert!(!result.success, "changing util.pt must invalidate main.pt");
+
+    write(&dir.path().join("util.pt"), UTIL);
+    let fixed = server.analyze(&main);
+    assert!(!fixed.cached);
+    assert!(fixed.success, "{:?}", fixed.errors);
+}
+
+#[test]
+fn warnings_carry_line_and_column() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = dir.path().join("main.pt");
+    write(&main, "fn main() {\n    let unused = 1\n}\n");
+    let result = AnalyzeServer::new(None).analyze(&main);
+    assert!(result.success);
+    let warning = &result.warnings[0];
+    assert!(warning.message.contains("unused"), "{}", warning.message);
+    let span = warning.span.as_ref().unwrap();
+    assert_eq!((span.line, span.column), (Some(2), Some(9)));
+}
+
+#[test]
+fn missing_file_is_an_error_result() {
+    let result = AnalyzeServer::new(None).analyze(Path::new("/definitely/not/here.pt"));
+    assert!(!result.success);
+    assert!(result.errors[0].message.contains("could not resolve path"));
+}
+
+#[test]
+fn cli_answers_each_path_with_a_json_line() {
+    let dir = project();
+    let main = dir.path().join("main.pt");
+    let mut child = common::pluto()
+        .arg("watch")
+        .arg("analyze")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let input = format!("{0}\n\n{0}\n", main.display());
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<serde_json::Value> = stdout.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["success"], true);
+    assert_eq!(lines[0]["cached"], false);
+    assert_eq!(lines[1]["cached"], true);
+}
@@ -1,5 +1,5 @@
 mod common;
-use common::{compile_and_run_stdout, compile_should_fail_with};
+use common::{compile_and_run_output, compile_and_run_stdout, compile_should_fail_with};
 
 #[test]
 fn array_literal_and_index() {
@@ -262,6 +262,31 @@ fn array_slice_empty() {
     assert_eq!(out, "0\n");
 }
 
+#[test]
+fn array_range_slice() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let a = [10, 20, 30, 40, 50]\n    let b = a[1..3]\n    print(b.len())\n    print(b[0])\n    print(b[1])\n    let c = a[2..=4]\n    print(c.len())\n    print(c[2])\n    print([\"x\", \"y\", \"z\"][1..3][1])\n}",
+    );
+    assert_eq!(out, "2\n20\n30\n3\n50\nz\n");
+}
+
+#[test]
+fn array_range_slice_backwards_aborts() {
+    let (_, stderr, code) = compile_and_run_output(
+        "fn main() {\n    let a = [1, 2]\n    let i = 2\n    print(a[i..1].len())\n}",
+    );
+    assert_ne!(code, 0, "backwards slice should abort");
+    assert!(stderr.contains("array slice out of bounds: range 2..1, length 2"), "stderr: {stderr}");
+}
+
+#[test]
+fn array_range_slice_assignment_rejected() {
+    compile_should_fail_with(
+        "fn main() {\n    let a = [1, 2]\n    a[0..1] = [3]\n}",
+        "array index must be int, found range",
+    );
+}
+
 // ── reverse ──────────────────────────────────────────────────────────────────
 
 #[test]
@@ -340,6 +365,105 @@ fn array_index_of_string() {
     assert_eq!(out, "1\n-1\n");
 }
 
+// ── sort / sort_by ───────────────────────────────────────────────────────────
+
+#[test]
+fn array_sort_primitives() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let a = [5, 3, -1, 9, 3]\n    a.sort()\n    for x in a {\n        print(x)\n    }\n    let s = [\"pear\", \"apple\", \"app\"]\n    s.sort()\n    for x in s {\n        print(x)\n    }\n    let f = [2.5, 0.5]\n    f.sort()\n    print(f[0])\n}",
+    );
+    assert_eq!(out, "-1\n3\n3\n5\n9\napp\napple\npear\n0.5\n");
+}
+
+#[test]
+fn array_sort_empty_and_single() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let e: [int] = []\n    e.sort()\n    print(e.len())\n    let one = [7]\n    one.sort_by((a: int, b: int) => b - a)\n    print(one[0])\n}",
+    );
+    assert_eq!(out, "0\n7\n");
+}
+
+#[test]
+fn array_sort_by_closure() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let a = [1, 5, 2, 4]\n    a.sort_by((x: int, y: int) => y - x)\n    for x in a {\n        print(x)\n    }\n}",
+    );
+    assert_eq!(out, "5\n4\n2\n1\n");
+}
+
+#[test]
+fn array_sort_by_is_stable() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let words = [\"ccc\", \"a\", \"bb\", \"dd\", \"e\"]\n    words.sort_by((x: string, y: string) => x.len() - y.len())\n    for w in words {\n        print(w)\n    }\n}",
+    );
+    assert_eq!(out, "a\ne\nbb\ndd\nccc\n");
+}
+
+#[test]
+fn array_sort_by_float_elements() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let a = [1.5, 3.5, 2.5]\n    a.sort_by((x: float, y: float) => {\n        if x < y {\n            return 1\n        }\n        if x > y {\n            return -1\n        }\n        return 0\n    })\n    print(a[0])\n    print(a[2])\n}",
+    );
+    assert_eq!(out, "3.5\n1.5\n");
+}
+
+#[test]
+fn array_sort_ord_class_is_stable() {
+    let out = compile_and_run_stdout(r#"
+class Item impl Ord {
+    rank: int
+    name: string
+
+    fn compare(self, other: Item) Ordering {
+        if self.rank < other.rank { return Ordering.Less }
+        if self.rank > other.rank { return Ordering.Greater }
+        return Ordering.Equal
+    }
+}
+fn main() {
+    let items = [Item { rank: 2, name: "a" }, Item { rank: 1, name: "b" }, Item { rank: 2, name: "c" }, Item { rank: 1, name: "d" }]
+    items.sort()
+    for it in items {
+        print(it.name)
+    }
+}
+"#);
+    assert_eq!(out, "b\nd\na\nc\n");
+}
+
+#[test]
+fn array_sort_by_allocating_comparator() {
+    // Comparators that allocate may trigger a collection mid-sort
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let a: [int] = []\n    let mut i = 0\n    while i < 20000 {\n        a.push((i * 7919) % 10007)\n        i = i + 1\n    }\n    a.sort_by((x: int, y: int) => {\n        let pair = [x, y]\n        return pair[0] - pair[1]\n    })\n    let mut sorted = true\n    i = 1\n    while i < a.len() {\n        if a[i - 1] > a[i] {\n            sorted = false\n        }\n        i = i + 1\n    }\n    print(sorted)\n}",
+    );
+    assert_eq!(out, "true\n");
+}
+
+// ── join / map_to_string ─────────────────────────────────────────────────────
+
+#[test]
+fn array_join_strings() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    print([\"a\", \"b\", \"c\"].join(\", \"))\n    print([\"solo\"].join(\"-\"))\n    let e: [string] = []\n    print(e.join(\"-\").len())\n}",
+    );
+    assert_eq!(out, "a, b, c\nsolo\n0\n");
+}
+
+#[test]
+fn array_join_split_round_trip() {
+    let out = compile_and_run_stdout("fn main() {\n    print(\"x y z\".split(\" \").join(\"/\"))\n}");
+    assert_eq!(out, "x/y/z\n");
+}
+
+#[test]
+fn array_map_to_string_primitives() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    print([1, -2, 3].map_to_string().join(\"+\"))\n    print([1.5, 2.0].map_to_string().join(\" \"))\n    print([true, false].map_to_string().join(\",\"))\n    print([7 as byte].map_to_string()[0])\n}",
+    );
+    assert_eq!(out, "1+-2+3\n1.5 2\ntrue,false\n7\n");
+}
+
 // ── type errors ──────────────────────────────────────────────────────────────
 
 #[test]
@@ -361,3 +485,40 @@ fn array_remove_at_wrong_type_rejected() {
 fn array_insert_at_wrong_value_type_rejected() {
     compile_should_fail_with("fn main() {\n    let a = [1, 2]\n    a.insert_at(0, \"x\")\n}", "insert_at(): expected int, found string");
 }
+
+#[test]
+fn array_sort_unordered_element_rejected() {
+    compile_should_fail_with(
+        "class P {\n    x: int\n}\n\nfn main() {\n    let a = [P { x: 1 }]\n    a.sort()\n}",
+        "sort(): P has no natural order; implement trait 'Ord' or use sort_by() with a comparator",
+    );
+}
+
+#[test]
+fn array_sort_by_wrong_comparator_rejected() {
+    compile_should_fail_with(
+        "fn main() {\n    let a = [1, 2]\n    a.sort_by((x: int, y: int) => x < y)\n}",
+        "sort_by(): expected comparator fn(int, int) int, found fn(int, int) bool",
+    );
+}
+
+#[test]
+fn array_join_non_string_rejected() {
+    compile_should_fail_with(
+        "fn main() {\n    let a = [1, 2]\n    a.join(\",\")\n}",
+        "join() requires an array of strings, found [int]; convert the elements with map_to_string() first",
+    );
+}
+
+#[test]
+fn array_join_wrong_separator_rejected() {
+    compile_should_fail_with("fn main() {\n    let a = [\"x\"]\n    a.join(1)\n}", "join(): expected string separator, found int");
+}
+
+#[test]
+fn array_map_to_string_class_rejected() {
+    compile_should_fail_with(
+        "class P {\n    x: int\n}\n\nfn main() {\n    let a = [P { x: 1 }]\n    a.map_to_string()\n}",
+        "map_to_string(): cannot convert P to string",
+    );
+}
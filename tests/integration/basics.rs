@@ -1,5 +1,5 @@
 mod common;
-use common::{compile_and_run, compile_batch_stdout, compile_should_fail_with, pluto};
+use common::{compile_and_run, compile_and_run_stdout, compile_batch_stdout, compile_should_fail_with, pluto};
 use std::collections::HashMap;
 use std::sync::OnceLock;
 
@@ -52,6 +52,10 @@ fn batch() -> &'static HashMap<String, String> {
                 "comments_ignored",
                 "// this is a comment\nfn main() {\n    // another comment\n    let x = 42 // inline comment\n    print(x)\n}",
             ),
+            (
+                "nested_block_comments_ignored",
+                "/* header\n   /* nested */\n*/\nfn main() {\n    let x = 40 /* outer /* inner */ still comment */ + 2\n    /*\n    print(0) // commented out\n    */\n    print(x)\n}",
+            ),
             (
                 "parenthesized_expressions",
                 "fn main() {\n    print((2 + 3) * 4)\n    print(2 + 3 * 4)\n}",
@@ -174,6 +178,11 @@ fn comments_ignored() {
     assert_eq!(batch()["comments_ignored"], "42\n");
 }
 
+#[test]
+fn nested_block_comments_ignored() {
+    assert_eq!(batch()["nested_block_comments_ignored"], "42\n");
+}
+
 #[test]
 fn parenthesized_expressions() {
     assert_eq!(batch()["parenthesized_expressions"], "20\n14\n");
@@ -291,10 +300,59 @@ fn nested_function_calls() {
     assert_eq!(code, 0);
 }
 
+#[test]
+fn main_returning_int_sets_exit_code() {
+    let code = compile_and_run("fn main() int {\n    print(1)\n    return 3\n}");
+    assert_eq!(code, 3);
+}
+
+#[test]
+fn main_returning_int_from_branch() {
+    let code = compile_and_run(
+        "fn check(n: int) bool {\n    return n > 10\n}\n\nfn main() int {\n    if check(5) {\n        return 0\n    }\n    return 2\n}",
+    );
+    assert_eq!(code, 2);
+}
+
+#[test]
+fn main_returning_nullable_int_sets_exit_code() {
+    let pick = "fn pick(n: int) int? {\n    if n > 0 {\n        return n\n    }\n    return none\n}\n\n";
+    let code = compile_and_run(&format!("{pick}fn main() int? {{\n    let v = pick(3)?\n    return v\n}}"));
+    assert_eq!(code, 3);
+    let code = compile_and_run(&format!("{pick}fn main() int? {{\n    let v = pick(0)?\n    return v + 1\n}}"));
+    assert_eq!(code, 0);
+    let code = compile_and_run(&format!("{pick}fn main() int? {{\n    return pick(4)\n}}"));
+    assert_eq!(code, 4);
+}
+
+#[test]
+fn main_returning_zero_succeeds() {
+    let out = compile_and_run_stdout("fn main() int {\n    print(7)\n    return 0\n}");
+    assert_eq!(out, "7\n");
+}
+
 // ============================================================
 // Non-batched tests — compile-fail checks
 // ============================================================
 
+#[test]
+fn main_non_int_return_rejected() {
+    compile_should_fail_with("fn main() string {\n    return \"no\"\n}", "main must return int or nothing, found string");
+}
+
+#[test]
+fn main_returning_nullable_non_int_exits_zero() {
+    let code = compile_and_run("fn main() string? {\n    return \"done\"\n}");
+    assert_eq!(code, 0);
+    let code = compile_and_run("fn main() float? {\n    return 2.5\n}");
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn main_int_missing_return_rejected() {
+    compile_should_fail_with("fn main() int {\n    print(1)\n}", "missing return statement");
+}
+
 #[test]
 fn type_error_rejected() {
     compile_should_fail_with("fn main() {\n    let x: int = true\n}", "type mismatch: expected int, found bool");
@@ -336,6 +394,30 @@ fn return_type_mismatch_rejected() {
     );
 }
 
+#[test]
+fn bare_return_in_int_function_rejected() {
+    compile_should_fail_with(
+        "fn foo(x: int) int {\n    if x > 0 {\n        return\n    }\n    return x\n}\n\nfn main() {\n    foo(1)\n}",
+        "missing return value: expected int; bare return is only allowed in functions that return nothing",
+    );
+}
+
+#[test]
+fn bare_return_in_nullable_function_suggests_none() {
+    compile_should_fail_with(
+        "fn foo(x: int) int? {\n    if x > 0 {\n        return\n    }\n    return x\n}\n\nfn main() {\n    foo(1)\n}",
+        "missing return value: expected int?; use 'return none' to return no value",
+    );
+}
+
+#[test]
+fn return_none_in_nullable_function() {
+    let out = compile_and_run_stdout(
+        "fn foo(x: int) int? {\n    if x > 0 {\n        return none\n    }\n    return x\n}\n\nfn main() {\n    if foo(1) == none {\n        print(\"none\")\n    }\n    if foo(-2) != none {\n        print(\"some\")\n    }\n}",
+    );
+    assert_eq!(out, "none\nsome\n");
+}
+
 #[test]
 fn arg_type_mismatch_rejected() {
     compile_should_fail_with(
@@ -360,6 +442,20 @@ fn extern_fn_class_param_rejected() {
     );
 }
 
+#[test]
+fn extern_c_fn_calls_libc() {
+    let out = compile_and_run_stdout("extern \"C\" fn labs(x: int) int\n\nfn main() {\n    print(labs(-42))\n}");
+    assert_eq!(out, "42\n");
+}
+
+#[test]
+fn extern_c_fn_string_param_rejected() {
+    compile_should_fail_with(
+        "extern \"C\" fn puts(s: string) int\n\nfn main() {\n}",
+        "extern \"C\" functions only support int, float, and bool, got 'string'",
+    );
+}
+
 #[test]
 fn extern_fn_duplicate_name_rejected() {
     compile_should_fail_with(
@@ -405,3 +501,66 @@ fn cli_run_subcommand() {
     assert!(output.status.success(), "CLI run failed: {}", String::from_utf8_lossy(&output.stderr));
     assert_eq!(String::from_utf8_lossy(&output.stdout), "99\n");
 }
+
+#[test]
+fn cli_run_propagates_main_exit_code() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("test.pluto");
+    std::fs::write(&src, "fn main() int {\n    return 4\n}").unwrap();
+    let output = pluto().arg("run").arg(&src).output().unwrap();
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[test]
+fn cli_run_forwards_args_after_double_dash() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("test.pluto");
+    std::fs::write(&src, "fn main() {\n    let a = args()\n    print(a.len())\n    for s in a {\n        print(s)\n    }\n}").unwrap();
+    let output = pluto().arg("run").arg(&src).arg("--").arg("hello").arg("--flag").output().unwrap();
+    assert!(output.status.success(), "CLI run failed: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "2\nhello\n--flag\n");
+}
+
+#[test]
+fn cli_check_reports_warnings_without_building() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("test.pluto");
+    std::fs::write(&src, "fn main() {\n    let x = 1\n}").unwrap();
+    let output = pluto().arg("check").arg(&src).current_dir(dir.path()).output().unwrap();
+    assert!(output.status.success(), "CLI check failed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unused variable 'x'"));
+    let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+    assert_eq!(entries.len(), 1, "check should not write any output");
+}
+
+#[test]
+fn cli_check_fails_on_type_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("test.pluto");
+    std::fs::write(&src, "fn main() {\n    let x: int = \"hello\"\n    print(x)\n}").unwrap();
+    let output = pluto().arg("check").arg(&src).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Type error"), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn args_empty_without_arguments() {
+    let out = compile_and_run_stdout("fn main() {\n    print(args().len())\n}");
+    assert_eq!(out, "0\n");
+}
+
+#[test]
+fn let_underscore_discards_value() {
+    let out = compile_and_run_stdout(
+        "fn tick() int {\n    print(\"tick\")\n    return 1\n}\n\nfn main() {\n    let _ = tick()\n    let _ = tick()\n    let _: int = 2\n    print(\"done\")\n}",
+    );
+    assert_eq!(out, "tick\ntick\ndone\n");
+}
+
+#[test]
+fn let_underscore_cannot_be_read() {
+    compile_should_fail_with(
+        "fn main() {\n    let _ = 1\n    print(_)\n}",
+        "'_' discards a value and cannot be read",
+    );
+}
@@ -230,6 +230,72 @@ fn main() int {
     assert_eq!(out, "unsigned_correct\n");
 }
 
+// ── Byte bitwise ops ─────────────────────────────────────────────────────────
+
+#[test]
+fn byte_shift_right_is_logical() {
+    let out = compile_and_run_stdout(r#"
+fn main() int {
+    let b = 0xFF as byte >> 1
+    if b == 0x7F as byte {
+        print("logical")
+    }
+    print(b)
+    print(0x80 as byte >> 7)
+    print(0x80 as byte >> 1 as byte)
+    return 0
+}
+"#);
+    assert_eq!(out, "logical\n127\n1\n64\n");
+}
+
+#[test]
+fn byte_shift_left_wraps() {
+    let out = compile_and_run_stdout(r#"
+fn main() int {
+    let b = 0x81 as byte
+    print(b << 1)
+    print(0xFF as byte << 4)
+    print((b << 1) as int)
+    return 0
+}
+"#);
+    assert_eq!(out, "2\n240\n2\n");
+}
+
+#[test]
+fn byte_and_or_xor_not() {
+    let out = compile_and_run_stdout(r#"
+fn main() int {
+    let a = 0xF0 as byte
+    let b = 0x3C as byte
+    print(a & b)
+    print(a | b)
+    print(a ^ b)
+    print(~a)
+    print(~a > 0x0E as byte)
+    return 0
+}
+"#);
+    assert_eq!(out, "48\n252\n204\n15\ntrue\n");
+}
+
+#[test]
+fn byte_bitwise_with_int_rejected() {
+    compile_should_fail_with(
+        "fn main() {\n    let x = 0xF0 as byte & 0x0F\n}",
+        "bitwise operators on byte require byte operands, found byte and int",
+    );
+}
+
+#[test]
+fn byte_shift_amount_must_be_integer() {
+    compile_should_fail_with(
+        "fn main() {\n    let x = 0xF0 as byte >> 1.0\n}",
+        "shift amount must be int or byte, found float",
+    );
+}
+
 // ── Bytes new, push, len ─────────────────────────────────────────────────────
 
 #[test]
@@ -568,3 +634,63 @@ fn main() int {
 "#);
     assert_eq!(out, "100\n0\n99\n");
 }
+
+#[test]
+fn bytes_push_million_is_amortized() {
+    // Reallocating on every push would make this quadratic and time out
+    let out = compile_and_run_stdout_timeout(r#"
+fn main() {
+    let buf = bytes_new()
+    for i in 0..1000000 {
+        buf.push((i % 256) as byte)
+    }
+    print(buf.len())
+    print(buf[255] as int)
+    print(buf[999999] as int)
+}
+"#, 10);
+    assert_eq!(out, "1000000\n255\n63\n");
+}
+
+// ── push_str ─────────────────────────────────────────────────────────────────
+
+#[test]
+fn bytes_push_str_appends_string_bytes() {
+    let out = compile_and_run_stdout(r#"
+fn main() {
+    let buf = bytes_new()
+    buf.push(91 as byte)
+    buf.push_str("héllo")
+    buf.push_str("")
+    buf.push(93 as byte)
+    print(buf.len())
+    print(buf.to_string())
+}
+"#);
+    assert_eq!(out, "8\n[héllo]\n");
+}
+
+#[test]
+fn bytes_push_str_grows_across_many_appends() {
+    let out = compile_and_run_stdout_timeout(r#"
+fn main() {
+    let buf = bytes_new()
+    for i in 0..100000 {
+        buf.push_str("abc")
+    }
+    print(buf.len())
+    print(buf[299999] as int)
+}
+"#, 10);
+    assert_eq!(out, "300000\n99\n");
+}
+
+#[test]
+fn bytes_push_str_rejects_non_string() {
+    compile_should_fail_with(r#"
+fn main() {
+    let buf = bytes_new()
+    buf.push_str(5)
+}
+"#, "push_str(): expected string, found int");
+}
@@ -310,6 +310,223 @@ fn main() {
     assert_eq!(out.trim(), "-1");
 }
 
+// ── Timed receive (recv_timeout) ────────────────────────────────────────────
+
+/// Classifies the outcome of a timed receive so tests can tell the errors apart.
+const RECV_TIMEOUT_HELPERS: &str = r#"
+extern fn __pluto_time_sleep_ns(ns: int)
+
+fn wait(rx: Receiver<int>, ms: int) string {
+    let v = rx.recv_timeout(ms) catch err: ChannelTimeout {
+        return "timeout"
+    }
+    catch err: ChannelClosed {
+        return "closed"
+    }
+    return f"got {v}"
+}
+"#;
+
+#[test]
+fn chan_recv_timeout_buffered_value() {
+    let out = compile_and_run_stdout(&format!("{RECV_TIMEOUT_HELPERS}{}", r#"
+fn main() {
+    let (tx, rx) = chan<int>(1)
+    tx.send(5)!
+    print(wait(rx, 10))
+}
+"#));
+    assert_eq!(out.trim(), "got 5");
+}
+
+#[test]
+fn chan_recv_timeout_elapses() {
+    let out = compile_and_run_stdout(&format!("{RECV_TIMEOUT_HELPERS}{}", r#"
+fn main() {
+    let (tx, rx) = chan<int>(1)
+    let start = time_ns()
+    print(wait(rx, 30))
+    print(time_ns() - start >= 30000000)
+}
+"#));
+    assert_eq!(out.trim(), "timeout\ntrue");
+}
+
+#[test]
+fn chan_recv_timeout_value_arrives_during_wait() {
+    let out = compile_and_run_stdout_timeout(&format!("{RECV_TIMEOUT_HELPERS}{}", r#"
+fn produce(tx: Sender<int>) {
+    __pluto_time_sleep_ns(20000000)
+    tx.send(9)!
+}
+
+fn main() {
+    let (tx, rx) = chan<int>(1)
+    let t = spawn produce(tx)
+    print(wait(rx, 5000))
+    t.get()!
+}
+"#), 15);
+    assert_eq!(out.trim(), "got 9");
+}
+
+#[test]
+fn chan_recv_timeout_close_during_wait() {
+    // The close wakes the receiver well before the 5s deadline
+    let out = compile_and_run_stdout_timeout(&format!("{RECV_TIMEOUT_HELPERS}{}", r#"
+fn closer(tx: Sender<int>) {
+    __pluto_time_sleep_ns(20000000)
+    tx.close()
+}
+
+fn main() {
+    let (tx, rx) = chan<int>(1)
+    let t = spawn closer(tx)
+    let start = time_ns()
+    print(wait(rx, 5000))
+    print(time_ns() - start < 2000000000)
+    t.get()
+}
+"#), 15);
+    assert_eq!(out.trim(), "closed\ntrue");
+}
+
+#[test]
+fn chan_recv_timeout_drains_before_reporting_close() {
+    let out = compile_and_run_stdout(&format!("{RECV_TIMEOUT_HELPERS}{}", r#"
+fn main() {
+    let (tx, rx) = chan<int>(2)
+    tx.send(1)!
+    tx.close()
+    print(wait(rx, 10))
+    print(wait(rx, 10))
+}
+"#));
+    assert_eq!(out.trim(), "got 1\nclosed");
+}
+
+#[test]
+fn chan_recv_timeout_must_be_handled() {
+    compile_should_fail_with(r#"
+fn main() {
+    let (tx, rx) = chan<int>(1)
+    let v = rx.recv_timeout(10)
+}
+"#, "must be handled");
+}
+
+#[test]
+fn chan_recv_timeout_requires_int_millis() {
+    compile_should_fail_with(r#"
+fn main() {
+    let (tx, rx) = chan<int>(1)
+    let v = rx.recv_timeout("soon") catch 0
+}
+"#, "recv_timeout(): expected int milliseconds, found string");
+}
+
+#[test]
+fn chan_recv_timeout_test_mode_empty_times_out() {
+    // Sequential test mode has no other task to send, so the timeout fires at once
+    let (stdout, stderr, code) = compile_test_and_run(r#"
+test "timed receive on an empty channel" {
+    let (tx, rx) = chan<int>(1)
+    let v = rx.recv_timeout(1000) catch -1
+    expect(v).to_equal(-1)
+}
+"#);
+    assert_eq!(code, 0, "stdout: {stdout}\nstderr: {stderr}");
+}
+
+// ── Introspection (len / is_full) ───────────────────────────────────────────
+
+#[test]
+fn chan_len_tracks_queued_items() {
+    let out = compile_and_run_stdout(r#"
+fn main() {
+    let (tx, rx) = chan<int>(4)
+    print(rx.len())
+    tx.send(1)!
+    tx.send(2)!
+    tx.send(3)!
+    print(rx.len())
+    let v = rx.recv()!
+    print(rx.len())
+}
+"#);
+    assert_eq!(out, "0\n3\n2\n");
+}
+
+#[test]
+fn chan_is_full_at_capacity() {
+    let out = compile_and_run_stdout(r#"
+fn main() {
+    let (tx, rx) = chan<int>(2)
+    print(tx.is_full())
+    tx.send(1)!
+    print(tx.is_full())
+    tx.send(2)!
+    print(tx.is_full())
+    let v = rx.recv()!
+    print(tx.is_full())
+}
+"#);
+    assert_eq!(out, "false\nfalse\ntrue\nfalse\n");
+}
+
+#[test]
+fn chan_len_after_close_counts_buffered() {
+    // Closing does not drop buffered values, so len still reports them
+    let out = compile_and_run_stdout(r#"
+fn main() {
+    let (tx, rx) = chan<string>(3)
+    tx.send("a")!
+    tx.send("b")!
+    tx.close()
+    print(rx.len())
+}
+"#);
+    assert_eq!(out.trim(), "2");
+}
+
+#[test]
+fn chan_len_and_is_full_test_mode() {
+    let (stdout, stderr, code) = compile_test_and_run(r#"
+test "channel introspection in the test runtime" {
+    let (tx, rx) = chan<int>(2)
+    expect(rx.len()).to_equal(0)
+    tx.send(7)!
+    tx.send(8)!
+    expect(rx.len()).to_equal(2)
+    expect(tx.is_full()).to_be_true()
+    let v = rx.recv()!
+    expect(rx.len()).to_equal(1)
+    expect(tx.is_full()).to_be_false()
+}
+"#);
+    assert_eq!(code, 0, "stdout: {stdout}\nstderr: {stderr}");
+}
+
+#[test]
+fn chan_len_wrong_arg_count() {
+    compile_should_fail_with(r#"
+fn main() {
+    let (tx, rx) = chan<int>(1)
+    let n = rx.len(1)
+}
+"#, "len() expects 0 arguments, got 1");
+}
+
+#[test]
+fn chan_is_full_not_on_receiver() {
+    compile_should_fail_with(r#"
+fn main() {
+    let (tx, rx) = chan<int>(1)
+    let full = rx.is_full()
+}
+"#, "Receiver has no method 'is_full'");
+}
+
 // ── For-in on Receiver ──────────────────────────────────────────────────────
 
 #[test]
@@ -487,6 +704,18 @@ fn main() {
 "#, "capacity must be int");
 }
 
+#[test]
+fn chan_const_capacity_expression() {
+    let out = compile_and_run_stdout(r#"
+fn main() {
+    let (tx, rx) = chan<int>(2 * 2)
+    tx.send(4)!
+    print(rx.recv()!)
+}
+"#);
+    assert_eq!(out.trim(), "4");
+}
+
 #[test]
 fn chan_unknown_method_compile_fail() {
     compile_should_fail_with(r#"
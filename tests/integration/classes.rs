@@ -494,3 +494,71 @@ fn nested_field_access_uppercase_field() {
     );
     assert_eq!(out, "77\n");
 }
+
+// ── destructuring let ──────────────────────────────────────────────────────
+
+const POINT: &str = "class Point {\n    x: int\n    y: int\n    label: string\n}\n\n";
+
+#[test]
+fn let_destructures_class_fields() {
+    let out = compile_and_run_stdout(&format!(
+        "{POINT}fn main() {{\n    let p = Point {{ x: 3, y: 4, label: \"p\" }}\n    let Point {{ x, y }} = p\n    print(x + y)\n}}"
+    ));
+    assert_eq!(out, "7\n");
+}
+
+#[test]
+fn let_destructuring_renames_fields() {
+    let out = compile_and_run_stdout(&format!(
+        "{POINT}fn origin() Point {{\n    return Point {{ x: 0, y: 7, label: \"o\" }}\n}}\n\nfn main() {{\n    let Point {{ y: py, label: name }} = origin()\n    print(py)\n    print(name)\n}}"
+    ));
+    assert_eq!(out, "7\no\n");
+}
+
+#[test]
+fn let_mut_destructuring_allows_reassignment() {
+    let out = compile_and_run_stdout(&format!(
+        "{POINT}fn main() {{\n    let p = Point {{ x: 3, y: 4, label: \"p\" }}\n    let mut Point {{ x }} = p\n    x = x + 1\n    print(x)\n    print(p.x)\n}}"
+    ));
+    assert_eq!(out, "4\n3\n");
+}
+
+#[test]
+fn let_destructures_generic_class() {
+    let out = compile_and_run_stdout(
+        "class Pair<A, B> {\n    first: A\n    second: B\n}\n\nfn main() {\n    let pr = Pair<int, string> { first: 1, second: \"two\" }\n    let Pair<int, string> { first, second } = pr\n    print(first)\n    print(second)\n}",
+    );
+    assert_eq!(out, "1\ntwo\n");
+}
+
+#[test]
+fn let_destructuring_inside_closure_and_generator() {
+    let out = compile_and_run_stdout(&format!(
+        "{POINT}fn coords(p: Point) stream int {{\n    let Point {{ x, y }} = p\n    yield x\n    yield y\n}}\n\nfn main() {{\n    let p = Point {{ x: 3, y: 4, label: \"p\" }}\n    let f = () => {{\n        let Point {{ label }} = p\n        return label\n    }}\n    print(f())\n    for c in coords(p) {{\n        print(c)\n    }}\n}}"
+    ));
+    assert_eq!(out, "p\n3\n4\n");
+}
+
+#[test]
+fn let_destructured_binding_is_immutable() {
+    compile_should_fail_with(
+        &format!("{POINT}fn main() {{\n    let p = Point {{ x: 3, y: 4, label: \"p\" }}\n    let Point {{ x }} = p\n    x = 5\n}}"),
+        "cannot assign to immutable variable 'x'",
+    );
+}
+
+#[test]
+fn let_destructuring_unknown_field_rejected() {
+    compile_should_fail_with(
+        &format!("{POINT}fn main() {{\n    let p = Point {{ x: 3, y: 4, label: \"p\" }}\n    let Point {{ z }} = p\n}}"),
+        "class 'Point' has no field 'z'",
+    );
+}
+
+#[test]
+fn let_destructuring_type_mismatch_rejected() {
+    compile_should_fail_with(
+        &format!("{POINT}class Other {{\n    x: int\n}}\n\nfn main() {{\n    let o = Other {{ x: 1 }}\n    let Point {{ x }} = o\n}}"),
+        "type mismatch: expected Point, found Other",
+    );
+}
@@ -1,5 +1,5 @@
 mod common;
-use common::compile_and_run_stdout;
+use common::{compile_and_run_stdout, compile_should_fail_with};
 
 #[test]
 fn closure_basic() {
@@ -41,6 +41,14 @@ fn closure_capture() {
     assert_eq!(out.trim(), "15");
 }
 
+#[test]
+fn closure_calls_captured_closure() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let inc = (x: int) => x + 1\n    let f = (x: int) => inc(inc(x))\n    print(f(5))\n}",
+    );
+    assert_eq!(out.trim(), "7");
+}
+
 #[test]
 fn closure_capture_by_value() {
     let out = compile_and_run_stdout(
@@ -159,3 +167,185 @@ fn nested_closures_with_if_expr() {
     );
     assert_eq!(out.trim(), "10");
 }
+
+#[test]
+fn bound_method_ref_passed_as_closure() {
+    let out = compile_and_run_stdout(
+        r#"
+        class Counter {
+            step: int
+
+            fn scale(self, x: int) int {
+                return x * self.step
+            }
+        }
+
+        fn apply(f: fn(int) int, x: int) int {
+            return f(x)
+        }
+
+        fn main() {
+            let c = Counter { step: 3 }
+            let f = c.scale
+            print(apply(f, 5))
+            print(apply(c.scale, 7))
+        }
+        "#,
+    );
+    assert_eq!(out.trim(), "15\n21");
+}
+
+#[test]
+fn bound_method_ref_captures_receiver() {
+    let out = compile_and_run_stdout(
+        r#"
+        class Counter {
+            step: int
+
+            fn scale(self, x: int) int {
+                return x * self.step
+            }
+        }
+
+        fn main() {
+            let mut c = Counter { step: 2 }
+            let f = c.scale
+            c = Counter { step: 10 }
+            print(f(4))
+        }
+        "#,
+    );
+    assert_eq!(out.trim(), "8");
+}
+
+#[test]
+fn bound_method_ref_on_array_builtin() {
+    let out = compile_and_run_stdout(
+        r#"
+        fn each(xs: [int], f: fn(int)) {
+            for x in xs {
+                f(x)
+            }
+        }
+
+        fn main() {
+            let mut out: [int] = []
+            each([4, 5, 6], out.push)
+            print(out.len())
+            print(out[2])
+        }
+        "#,
+    );
+    assert_eq!(out.trim(), "3\n6");
+}
+
+#[test]
+fn unbound_method_ref_takes_receiver_first() {
+    let out = compile_and_run_stdout(
+        r#"
+        class Point {
+            x: int
+            y: int
+
+            fn dot(self, other: Point) int {
+                return self.x * other.x + self.y * other.y
+            }
+        }
+
+        fn main() {
+            let f = Point::dot
+            print(f(Point { x: 1, y: 2 }, Point { x: 3, y: 4 }))
+        }
+        "#,
+    );
+    assert_eq!(out.trim(), "11");
+}
+
+#[test]
+fn unbound_trait_method_ref_dispatches_dynamically() {
+    let out = compile_and_run_stdout(
+        r#"
+        trait Shape {
+            fn area(self) int
+        }
+
+        class Square impl Shape {
+            side: int
+
+            fn area(self) int {
+                return self.side * self.side
+            }
+        }
+
+        class Rect impl Shape {
+            w: int
+            h: int
+
+            fn area(self) int {
+                return self.w * self.h
+            }
+        }
+
+        fn main() {
+            let area = Shape::area
+            let a: Shape = Square { side: 3 }
+            let b: Shape = Rect { w: 2, h: 5 }
+            print(area(a))
+            print(area(b))
+        }
+        "#,
+    );
+    assert_eq!(out.trim(), "9\n10");
+}
+
+#[test]
+fn method_ref_to_fallible_method_rejected() {
+    compile_should_fail_with(
+        r#"
+        error Bad {}
+
+        class C {
+            n: int
+
+            fn get(self) int {
+                if self.n < 0 {
+                    raise Bad {}
+                }
+                return self.n
+            }
+        }
+
+        fn main() {
+            let c = C { n: 1 }
+            let f = c.get
+            print(f())
+        }
+        "#,
+        "cannot reference fallible method 'get' as a value",
+    );
+}
+
+#[test]
+fn method_ref_on_temporary_rejected() {
+    compile_should_fail_with(
+        r#"
+        class C {
+            n: int
+
+            fn get(self) int {
+                return self.n
+            }
+        }
+
+        fn make() C {
+            return C { n: 1 }
+        }
+
+        fn main() {
+            let f = make().get
+            print(f())
+        }
+        "#,
+        "cannot reference method 'get' on a temporary value",
+    );
+}
@@ -959,6 +959,286 @@ fn main() {
     assert_eq!(out.trim(), "ok");
 }
 
+#[test]
+fn cancel_interrupts_infinite_loop() {
+    // The task never blocks, so only its loop's safepoint can stop it
+    let out = compile_and_run_stdout_timeout(r#"
+error Overflow {
+    at: int
+}
+
+fn spin() int {
+    let mut i = 0
+    while true {
+        i = i + 1
+        if i < 0 {
+            raise Overflow { at: i }
+        }
+    }
+    return i
+}
+
+fn main() {
+    let t = spawn spin()
+    t.cancel()
+    let result = t.get() catch -1
+    print(result)
+}
+"#, 10);
+    assert_eq!(out.trim(), "-1");
+}
+
+#[test]
+fn cancel_unwinds_through_finally_and_guards() {
+    // Cancelled inside a call made while holding a guard: the catch doesn't
+    // see the cancellation, the finally block runs and the guard is released
+    let out = compile_and_run_stdout_timeout(r#"
+error Stop {
+    at: int
+}
+
+fn step(n: int) int {
+    return n + 1
+}
+
+fn work(m: Mutex<int>, started: Sender<int>) int {
+    let g = m.lock()
+    let mut n = 0
+    try {
+        started.send(1)!
+        while true {
+            n = step(n)
+        }
+    } catch e {
+        print("caught")
+    } finally {
+        print("finally")
+    }
+    if n < 0 {
+        raise Stop { at: n }
+    }
+    return n
+}
+
+fn main() {
+    let m = mutex(0)
+    let (tx, rx) = chan<int>(1)
+    let t = spawn work(m, tx)
+    rx.recv()!
+    t.cancel()
+    let result = t.get() catch -1
+    print(result)
+    let g = m.lock()
+    print("unlocked")
+}
+"#, 10);
+    assert_eq!(out, "finally\n-1\nunlocked\n");
+}
+
+#[test]
+fn cancel_infallible_task_get_exits() {
+    // Loop-free recursion stops at function-entry safepoints. get() on an
+    // infallible task can't raise, so the cancellation ends the program.
+    let (stdout, stderr, code) = compile_and_run_output(r#"
+fn fib(n: int) int {
+    if n < 2 {
+        return n
+    }
+    return fib(n - 1) + fib(n - 2)
+}
+
+fn main() {
+    let t = spawn fib(50)
+    t.cancel()
+    print(t.get())
+}
+"#);
+    assert_eq!(stdout, "");
+    assert!(stderr.contains("pluto: get() on a cancelled task"), "stderr: {stderr}");
+    assert_eq!(code, 1);
+}
+
+#[test]
+fn cancel_elsewhere_leaves_infallible_get_unhandled() {
+    // A cancel() on some other task doesn't make this get() fallible
+    let out = compile_and_run_stdout(r#"
+fn one() int {
+    return 1
+}
+
+fn stop(t: Task<int>) {
+    t.cancel()
+}
+
+fn main() {
+    let t = spawn one()
+    print(t.get())
+}
+"#);
+    assert_eq!(out, "1\n");
+}
+
+// ── Run slots (PLUTO_THREADS) ─────────────────────────────────────────
+
+fn run_with_threads(source: &str, threads: &str) -> (String, String, i32) {
+    let bin = CompiledBinary::compile(source);
+    let output = std::process::Command::new(&bin.path).env("PLUTO_THREADS", threads).output().unwrap();
+    (
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+        output.status.code().unwrap_or(-1),
+    )
+}
+
+#[test]
+fn pluto_threads_one_starts_tasks_in_spawn_order() {
+    // Main keeps the only slot until it blocks in get(); the tasks then take
+    // it in the order they were spawned
+    let src = r#"
+fn work(id: int) int {
+    print(id)
+    return id
+}
+
+fn main() {
+    let mut tasks: [Task<int>] = []
+    for id in 0..5 {
+        tasks.push(spawn work(id))
+    }
+    let mut sum = 0
+    for t in tasks {
+        sum = sum + (t.get() catch 0)
+    }
+    print(sum)
+}
+"#;
+    for _ in 0..3 {
+        let (stdout, stderr, code) = run_with_threads(src, "1");
+        assert_eq!(code, 0, "stderr: {stderr}");
+        assert_eq!(stdout, "0\n1\n2\n3\n4\n10\n");
+    }
+}
+
+#[test]
+fn pluto_threads_one_hands_slot_over_while_blocked() {
+    // Each stage blocks on a channel or mutex held by another task, so with a
+    // single slot the pipeline only finishes if blocked threads give it up
+    let (stdout, stderr, code) = run_with_threads(r#"
+fn produce(tx: Sender<int>, n: int) {
+    for i in 0..n {
+        tx.send(i)!
+    }
+    tx.close()
+}
+
+fn double(rx: Receiver<int>, tx: Sender<int>, total: Mutex<int>) {
+    for v in rx {
+        let g = total.lock()
+        g.set(g.get() + v)
+        tx.send(v * 2)!
+    }
+    tx.close()
+}
+
+fn main() {
+    let (tx1, rx1) = chan<int>(1)
+    let (tx2, rx2) = chan<int>(1)
+    let total = mutex(0)
+    let p = spawn produce(tx1, 200)
+    let d = spawn double(rx1, tx2, total)
+    let mut sum = 0
+    for v in rx2 {
+        sum = sum + v
+    }
+    p.get()!
+    d.get()!
+    let g = total.lock()
+    print(sum)
+    print(g.get())
+}
+"#, "1");
+    assert_eq!(code, 0, "stderr: {stderr}");
+    assert_eq!(stdout, "39800\n19900\n");
+}
+
+#[test]
+fn pluto_threads_one_time_slices_spinning_tasks() {
+    // Main spins until the last task runs; it only gets to run once main's
+    // time slice is up and main passes the slot on at a safepoint
+    let (stdout, stderr, code) = run_with_threads(r#"
+fn spin(a: Atomic) int {
+    while a.load() == 0 {
+    }
+    return 7
+}
+
+fn set(a: Atomic) {
+    a.store(1)
+}
+
+fn main() {
+    let a = atomic(0)
+    let t = spawn spin(a)
+    let s = spawn set(a)
+    while a.load() == 0 {
+    }
+    s.get()
+    print(t.get())
+}
+"#, "1");
+    assert_eq!(code, 0, "stderr: {stderr}");
+    assert_eq!(stdout, "7\n");
+}
+
+#[test]
+fn pluto_threads_one_sleep_gives_up_the_slot() {
+    // nap() takes the only slot first, but sleeping hands it to work()
+    let (stdout, stderr, code) = run_with_threads(r#"
+extern fn __pluto_time_sleep_ns(ns: int)
+extern fn __pluto_time_ns() int
+
+fn nap() int {
+    __pluto_time_sleep_ns(1000000000)
+    return 1
+}
+
+fn work() int {
+    return 2
+}
+
+fn main() {
+    let start = __pluto_time_ns()
+    let s = spawn nap()
+    let w = spawn work()
+    print(w.get())
+    print(__pluto_time_ns() - start < 500000000)
+    print(s.get())
+}
+"#, "1");
+    assert_eq!(code, 0, "stderr: {stderr}");
+    assert_eq!(stdout, "2\ntrue\n1\n");
+}
+
+#[test]
+fn pluto_threads_rejects_invalid_values() {
+    for threads in ["0", "-2", "four"] {
+        let (_, stderr, code) = run_with_threads("fn main() {\n    print(1)\n}", threads);
+        assert_eq!(code, 1, "PLUTO_THREADS={threads} should be rejected");
+        assert!(stderr.contains("PLUTO_THREADS must be a positive integer"), "stderr: {stderr}");
+    }
+}
+
+#[test]
+fn num_cpus_builtin() {
+    let out = compile_and_run_stdout(r#"
+fn main() {
+    let n = num_cpus()
+    print(n >= 1)
+}
+"#);
+    assert_eq!(out, "true\n");
+}
+
 // ── Spawn method calls ────────────────────────────────────────────────
 
 #[test]
@@ -1639,3 +1919,374 @@ fn task_get_in_if_expr_condition() {
     );
     assert_eq!(out.trim(), "100");
 }
+
+// ── WaitGroup ─────────────────────────────────────────────────────────
+
+#[test]
+fn waitgroup_waits_for_all_tasks() {
+    // Workers finish at staggered times; wait() must not return until all four are done
+    let out = compile_and_run_stdout_timeout(r#"
+extern fn __pluto_time_sleep_ns(ns: int)
+
+fn worker(id: int, wg: WaitGroup, tx: Sender<int>) {
+    __pluto_time_sleep_ns(id * 10000000)
+    tx.send(id)!
+    wg.done()
+}
+
+fn main() {
+    let wg = wait_group()
+    let (tx, rx) = chan<int>(8)
+    wg.add(4)
+    spawn worker(1, wg, tx).detach()
+    spawn worker(2, wg, tx).detach()
+    spawn worker(3, wg, tx).detach()
+    spawn worker(4, wg, tx).detach()
+    wg.wait()
+    print(rx.len())
+}
+"#, 10);
+    assert_eq!(out.trim(), "4");
+}
+
+#[test]
+fn waitgroup_reusable_after_wait() {
+    let out = compile_and_run_stdout_timeout(r#"
+fn worker(wg: WaitGroup) int {
+    wg.done()
+    return 1
+}
+
+fn main() {
+    let wg = wait_group()
+    wg.add(2)
+    let a = spawn worker(wg)
+    let b = spawn worker(wg)
+    wg.wait()
+    wg.add(1)
+    let c = spawn worker(wg)
+    wg.wait()
+    print(a.get() + b.get() + c.get())
+}
+"#, 10);
+    assert_eq!(out.trim(), "3");
+}
+
+#[test]
+fn waitgroup_wait_with_zero_count_returns() {
+    let out = compile_and_run_stdout(r#"
+fn main() {
+    let wg = wait_group()
+    wg.wait()
+    print("ok")
+}
+"#);
+    assert_eq!(out.trim(), "ok");
+}
+
+#[test]
+fn waitgroup_negative_counter_aborts() {
+    let (_stdout, stderr, code) = compile_and_run_output(r#"
+fn main() {
+    let wg = wait_group()
+    wg.add(1)
+    wg.done()
+    wg.done()
+}
+"#);
+    assert_eq!(code, 1);
+    assert!(stderr.contains("WaitGroup counter went negative"), "stderr: {stderr}");
+}
+
+#[test]
+fn compile_fail_waitgroup_add_non_int() {
+    compile_should_fail_with(r#"
+fn main() {
+    let wg = wait_group()
+    wg.add("two")
+}
+"#, "add(): expected int, found string");
+}
+
+#[test]
+fn compile_fail_waitgroup_unknown_method() {
+    compile_should_fail_with(r#"
+fn main() {
+    let wg = wait_group()
+    wg.join()
+}
+"#, "WaitGroup has no method 'join'");
+}
+
+// ── Atomic ────────────────────────────────────────────────────────────
+
+#[test]
+fn atomic_counter_across_tasks() {
+    let out = compile_and_run_stdout_timeout(r#"
+fn bump(counter: Atomic) int {
+    let mut i = 0
+    while i < 1000 {
+        counter.add(1)
+        i = i + 1
+    }
+    return 0
+}
+
+fn main() {
+    let counter = atomic(0)
+    let mut tasks: [Task<int>] = []
+    let mut i = 0
+    while i < 8 {
+        tasks.push(spawn bump(counter))
+        i = i + 1
+    }
+    for t in tasks {
+        t.get() catch 0
+    }
+    print(counter.load())
+}
+"#, 10);
+    assert_eq!(out.trim(), "8000");
+}
+
+#[test]
+fn atomic_in_class_field_shared_with_task() {
+    // The class is deep-copied at spawn, but the atomic inside it is not
+    let out = compile_and_run_stdout_timeout(r#"
+class Stats {
+    hits: Atomic
+    label: string
+}
+
+fn record(stats: Stats) int {
+    return stats.hits.add(5)
+}
+
+fn main() {
+    let stats = Stats { hits: atomic(1), label: "req" }
+    let t = spawn record(stats)
+    print(t.get())
+    print(stats.hits.load())
+}
+"#, 10);
+    assert_eq!(out, "6\n6\n");
+}
+
+#[test]
+fn atomic_store_and_compare_and_swap() {
+    let out = compile_and_run_stdout(r#"
+fn main() {
+    let a = atomic(3)
+    print(a.add(4))
+    a.store(10)
+    print(a.compare_and_swap(7, 20))
+    print(a.load())
+    print(a.compare_and_swap(10, 20))
+    print(a.load())
+}
+"#);
+    assert_eq!(out, "7\nfalse\n10\ntrue\n20\n");
+}
+
+#[test]
+fn compile_fail_atomic_non_int() {
+    compile_should_fail_with(r#"
+fn main() {
+    let a = atomic(0)
+    a.add(1.5)
+}
+"#, "add(): expected int, found float");
+}
+
+#[test]
+fn compile_fail_atomic_unknown_method() {
+    compile_should_fail_with(r#"
+fn main() {
+    let a = atomic(0)
+    a.increment()
+}
+"#, "Atomic has no method 'increment'");
+}
+
+// ── Mutex ─────────────────────────────────────────────────────────────
+
+#[test]
+fn mutex_counter_two_tasks() {
+    let out = compile_and_run_stdout_timeout(r#"
+fn bump(counter: Mutex<int>, n: int) int {
+    let mut i = 0
+    while i < n {
+        let g = counter.lock()
+        g.set(g.get() + 1)
+        i = i + 1
+    }
+    return n
+}
+
+fn main() {
+    let counter = mutex(0)
+    let a = spawn bump(counter, 50000)
+    let b = spawn bump(counter, 50000)
+    a.get()
+    b.get()
+    let g = counter.lock()
+    print(g.get())
+}
+"#, 10);
+    assert_eq!(out.trim(), "100000");
+}
+
+#[test]
+fn mutex_guard_released_on_every_exit() {
+    // Each function relocks the mutex, so a guard leaked by break, continue,
+    // return, raise or a nested block would hang the next lock()
+    let out = compile_and_run_stdout_timeout(r#"
+error Stop {
+    at: int
+}
+
+fn first_over(m: Mutex<[int]>, limit: int) int {
+    let g = m.lock()
+    for x in g.get() {
+        if x > limit {
+            return x
+        }
+    }
+    return -1
+}
+
+fn fail_locked(m: Mutex<[int]>) int {
+    let g = m.lock()
+    raise Stop { at: g.get().len() }
+    return 0
+}
+
+fn main() {
+    let m = mutex([1, 20, 3])
+    print(first_over(m, 10))
+    print(first_over(m, 100))
+    for i in 0..5 {
+        let g = m.lock()
+        if i == 1 {
+            continue
+        }
+        if i == 3 {
+            break
+        }
+        g.get().push(i)
+    }
+    if true {
+        let g = m.lock()
+        g.get().push(9)
+    }
+    let n = fail_locked(m) catch -1
+    print(n)
+    try {
+        let g = m.lock()
+        raise Stop { at: g.get().len() }
+    } catch e {
+        print("caught")
+    }
+    let g = m.lock()
+    print(g.get().len())
+}
+"#, 10);
+    assert_eq!(out, "20\n-1\n-1\ncaught\n6\n");
+}
+
+#[test]
+fn mutex_in_class_field_shared_with_task() {
+    // The class is deep-copied at spawn, but the mutex inside it is not
+    let out = compile_and_run_stdout_timeout(r#"
+class Inbox {
+    items: Mutex<[string]>
+}
+
+fn deliver(inbox: Inbox, msg: string) int {
+    let g = inbox.items.lock()
+    g.get().push(msg)
+    return g.get().len()
+}
+
+fn main() {
+    let inbox = Inbox { items: mutex(["a"]) }
+    let t = spawn deliver(inbox, "b")
+    print(t.get())
+    let g = inbox.items.lock()
+    print(g.get().len())
+}
+"#, 10);
+    assert_eq!(out, "2\n2\n");
+}
+
+#[test]
+fn compile_fail_mutex_lock_not_bound() {
+    compile_should_fail_with(r#"
+fn main() {
+    let m = mutex(0)
+    print(m.lock().get())
+}
+"#, "the result of lock() must be bound with 'let'");
+}
+
+#[test]
+fn compile_fail_mutex_guard_copied() {
+    compile_should_fail_with(r#"
+fn main() {
+    let m = mutex(0)
+    let g = m.lock()
+    let h = g
+}
+"#, "a MutexGuard can only be bound directly from lock()");
+}
+
+#[test]
+fn compile_fail_mutex_guard_captured() {
+    compile_should_fail_with(r#"
+fn main() {
+    let m = mutex(0)
+    let g = m.lock()
+    let f = () => g.get()
+}
+"#, "closure cannot capture MutexGuard 'g'");
+}
+
+#[test]
+fn compile_fail_mutex_set_wrong_type() {
+    compile_should_fail_with(r#"
+fn main() {
+    let m = mutex(0)
+    let g = m.lock()
+    g.set("x")
+}
+"#, "set() expects int, found string");
+}
+
+#[test]
+fn gc_stops_task_in_loop_free_recursion() {
+    // The task recurses without loops or allocation, so only the safepoint at
+    // function entry lets main's collections stop it
+    let out = compile_and_run_stdout_timeout(r#"
+class Obj {
+    value: int
+}
+
+fn fib(n: int) int {
+    if n < 2 {
+        return n
+    }
+    return fib(n - 1) + fib(n - 2)
+}
+
+fn main() {
+    let t = spawn fib(30)
+    let mut i = 0
+    while i < 100000 {
+        let tmp = Obj { value: i }
+        i = i + 1
+    }
+    print(t.get())
+}
+"#, 30);
+    assert_eq!(out.trim(), "832040");
+}
@@ -0,0 +1,137 @@
+mod common;
+use common::{compile_and_run_stdout, compile_should_fail_with};
+
+// ── Positive tests ─────────────────────────────────────────────────────
+
+#[test]
+fn const_values_of_each_type() {
+    let out = compile_and_run_stdout(r#"
+const MAX: int = 100
+const RATE: float = 0.5
+const NAME: string = "pluto"
+const VERBOSE: bool = false
+
+fn main() {
+    print(MAX)
+    print(RATE * 4.0)
+    print(NAME)
+    print(VERBOSE)
+}
+"#);
+    assert_eq!(out, "100\n2\npluto\nfalse\n");
+}
+
+#[test]
+fn const_initializer_uses_earlier_consts() {
+    let out = compile_and_run_stdout(r#"
+const KB = 1024
+const MB = KB * KB
+const BIG = MB > 1000000
+const GREETING = "hello, " + "world"
+
+fn main() {
+    print(MB)
+    print(BIG)
+    print(GREETING)
+}
+"#);
+    assert_eq!(out, "1048576\ntrue\nhello, world\n");
+}
+
+#[test]
+fn const_used_in_functions_closures_and_interpolation() {
+    let out = compile_and_run_stdout(r#"
+const LIMIT = 3
+
+fn count() int {
+    let mut n = 0
+    for i in 0..LIMIT {
+        n = n + i
+    }
+    return n
+}
+
+fn main() {
+    let add = (x: int) => x + LIMIT
+    print(count())
+    print(add(4))
+    print(f"limit is {LIMIT}")
+}
+"#);
+    assert_eq!(out, "3\n7\nlimit is 3\n");
+}
+
+#[test]
+fn local_binding_shadows_const() {
+    let out = compile_and_run_stdout(r#"
+const N = 1
+
+fn main() {
+    let N = 2
+    print(N)
+}
+"#);
+    assert_eq!(out, "2\n");
+}
+
+// ── Negative tests ─────────────────────────────────────────────────────
+
+#[test]
+fn const_non_constant_initializer_rejected() {
+    compile_should_fail_with(r#"
+fn compute() int {
+    return 1
+}
+
+const X = compute()
+
+fn main() {
+    print(X)
+}
+"#, "initializer of const 'X' is not a compile-time constant");
+}
+
+#[test]
+fn const_forward_reference_rejected() {
+    compile_should_fail_with(r#"
+const A = B + 1
+const B = 1
+
+fn main() {
+    print(A)
+}
+"#, "initializer of const 'A' is not a compile-time constant");
+}
+
+#[test]
+fn const_type_annotation_mismatch_rejected() {
+    compile_should_fail_with(r#"
+const X: string = 1
+
+fn main() {
+    print(X)
+}
+"#, "const 'X' is declared as string but its value is int");
+}
+
+#[test]
+fn const_assignment_rejected() {
+    compile_should_fail_with(r#"
+const X = 1
+
+fn main() {
+    X = 2
+}
+"#, "cannot assign to const 'X'");
+}
+
+#[test]
+fn duplicate_const_rejected() {
+    compile_should_fail_with(r#"
+const X = 1
+const X = 2
+
+fn main() {
+}
+"#, "const 'X' is already declared");
+}
@@ -1,5 +1,5 @@
 mod common;
-use common::{compile_and_run, compile_and_run_stdout, compile_should_fail, compile_should_fail_with};
+use common::{compile_and_run, compile_and_run_output, compile_and_run_stdout, compile_should_fail, compile_should_fail_with};
 
 #[test]
 fn if_else() {
@@ -342,3 +342,110 @@ fn continue_in_closure_rejected() {
         "can only be used inside a loop",
     );
 }
+
+// ── unreachable() ──
+
+#[test]
+fn unreachable_aborts_with_message() {
+    let (stdout, stderr, code) = compile_and_run_output(r#"
+fn main() {
+    print("before")
+    unreachable("impossible")
+    print("after")
+}
+"#);
+    assert_eq!(stdout, "before\n");
+    assert_ne!(code, 0);
+    assert!(
+        stderr.contains("entered unreachable code: impossible"),
+        "stderr should contain the message, got: {stderr}"
+    );
+}
+
+#[test]
+fn unreachable_without_message() {
+    let (_, stderr, code) = compile_and_run_output("fn main() {\n    unreachable()\n}");
+    assert_ne!(code, 0);
+    assert!(stderr.contains("entered unreachable code"), "got: {stderr}");
+}
+
+#[test]
+fn unreachable_typechecks_in_any_position() {
+    let out = compile_and_run_stdout(r#"
+enum Dir {
+    North
+    South
+}
+
+fn code(d: Dir) int {
+    return match d {
+        Dir.North => 1,
+        Dir.South => unreachable("no south")
+    }
+}
+
+fn half(n: int) float {
+    if n % 2 == 0 {
+        return (n / 2) as float
+    }
+    unreachable()
+}
+
+fn main() {
+    print(code(Dir.North))
+    print(half(4))
+    let ok: bool = if true { true } else { unreachable() }
+    print(ok)
+    let s = if ok { "yes" } else { unreachable("never") }
+    print(s)
+    print(1 + unreachable_if(false))
+}
+
+fn unreachable_if(b: bool) int {
+    if b {
+        return unreachable("b was true")
+    }
+    return 1
+}
+"#);
+    assert_eq!(out, "1\n2\ntrue\nyes\n2\n");
+}
+
+#[test]
+fn unreachable_in_generic_takes_each_instance_type() {
+    let out = compile_and_run_stdout(r#"
+fn pick<T>(x: T, ok: bool) T {
+    if ok {
+        return x
+    }
+    return unreachable("no")
+}
+
+fn main() {
+    print(pick(1, true))
+    print(pick(2.5, true))
+    print(pick(false, true))
+}
+"#);
+    assert_eq!(out, "1\n2.5\nfalse\n");
+}
+
+#[test]
+fn unreachable_message_must_be_string() {
+    compile_should_fail_with(
+        "fn main() {\n    unreachable(5)\n}",
+        "unreachable() message must be a string, found int",
+    );
+}
+
+#[test]
+fn unreachable_cannot_be_redefined() {
+    compile_should_fail_with(
+        "fn unreachable() {\n}\n\nfn f() int {\n    unreachable()\n}\n\nfn main() {\n    print(f())\n}",
+        "function 'unreachable' cannot shadow builtin 'unreachable'",
+    );
+    compile_should_fail_with(
+        "extern fn unreachable()\n\nfn f() int {\n    unreachable()\n}\n\nfn main() {\n    print(f())\n}",
+        "extern function 'unreachable' cannot shadow builtin 'unreachable'",
+    );
+}
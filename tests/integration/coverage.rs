@@ -256,7 +256,7 @@ fn terminal_report_all_covered() {
                 branch_id: 0,
             },
         ],
-        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string() }],
+        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string(), excluded_lines: vec![] }],
     };
     let data = CoverageData { counters: vec![1, 3, 2] };
     let stats = generate_terminal_report(&map, &data);
@@ -300,7 +300,7 @@ fn terminal_report_partial_coverage() {
                 branch_id: 0,
             },
         ],
-        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string() }],
+        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string(), excluded_lines: vec![] }],
     };
     // foo called, bar not
     let data = CoverageData { counters: vec![1, 5, 0, 0] };
@@ -316,7 +316,7 @@ fn terminal_report_partial_coverage() {
 fn terminal_report_no_points() {
     let map = CoverageMap {
         points: vec![],
-        files: vec![CoverageFile { id: 0, path: "empty.pluto".to_string() }],
+        files: vec![CoverageFile { id: 0, path: "empty.pluto".to_string(), excluded_lines: vec![] }],
     };
     let data = CoverageData { counters: vec![] };
     let stats = generate_terminal_report(&map, &data);
@@ -521,6 +521,128 @@ fn main() {
         "should have partial coverage with only-else branch taken");
 }
 
+#[test]
+fn coverage_run_writes_json_report() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("main.pluto"), r#"fn add(a: int, b: int) int {
+    return a + b
+}
+
+fn main() {
+    print(add(1, 2))
+    print(add(3, 4))
+}
+"#).unwrap();
+
+    let output = common::pluto()
+        .args(["run", "main.pluto", "--coverage", "--coverage-format", "json"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3\n7\n");
+
+    let json = std::fs::read_to_string(dir.path().join(".pluto-coverage/coverage.json")).unwrap();
+    let report: JsonCoverageReport = serde_json::from_str(&json).unwrap();
+    assert_eq!(report.summary.covered_lines, report.summary.total_lines);
+    let file = &report.files[0];
+    let add = file.function_details.iter().find(|f| f.name == "add").unwrap();
+    assert_eq!(add.hit_count, 2);
+    assert!(file.line_details.iter().any(|l| l.line == 2 && l.hit_count == 2));
+}
+
+#[test]
+fn coverage_format_requires_coverage() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("main.pluto"), "fn main() {\n}\n").unwrap();
+
+    let output = common::pluto()
+        .args(["test", "main.pluto", "--coverage-format", "json"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--coverage"));
+}
+
+fn coverage_check(dir: &std::path::Path, args: &[&str]) -> std::process::Output {
+    common::pluto()
+        .args(["coverage", "check"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn coverage_check_enforces_total_threshold() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("main.pluto"), r#"fn unused() {
+    print(0)
+}
+
+fn main() {
+    print(1)
+}
+"#).unwrap();
+    let run = common::pluto()
+        .args(["run", "main.pluto", "--coverage"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(run.status.success(), "{}", String::from_utf8_lossy(&run.stderr));
+
+    let pass = coverage_check(dir.path(), &["--min", "50", "--per-file"]);
+    assert!(pass.status.success(), "{}", String::from_utf8_lossy(&pass.stderr));
+
+    let fail = coverage_check(dir.path(), &["--min", "80", "--per-file"]);
+    assert!(!fail.status.success());
+    let stderr = String::from_utf8_lossy(&fail.stderr);
+    assert!(stderr.contains("main.pluto: 50.0% of lines covered (1/2), minimum is 80%"), "{stderr}");
+    assert!(stderr.contains("FAIL total:"), "{stderr}");
+
+    let nothing = coverage_check(dir.path(), &[]);
+    assert!(!nothing.status.success());
+}
+
+#[test]
+fn coverage_check_gates_new_lines_against_baseline() {
+    let dir = tempfile::tempdir().unwrap();
+    let run = |source: &str| {
+        std::fs::write(dir.path().join("main.pluto"), source).unwrap();
+        let out = common::pluto()
+            .args(["run", "main.pluto", "--coverage"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    };
+
+    let base = "fn main() {\n    print(1)\n}\n";
+    run(base);
+    let missing = coverage_check(dir.path(), &["--min-diff", "90"]);
+    assert!(!missing.status.success());
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("--update-baseline"));
+
+    let save = coverage_check(dir.path(), &["--update-baseline"]);
+    assert!(save.status.success(), "{}", String::from_utf8_lossy(&save.stderr));
+    assert!(dir.path().join(".pluto-coverage/baseline.json").exists());
+
+    // A new, never-called function: total coverage stays above 60% but none of the new lines ran
+    run(&format!("{base}\nfn added() {{\n    print(2)\n}}\n"));
+    let total_only = coverage_check(dir.path(), &["--min", "60"]);
+    assert!(!total_only.status.success(), "half the lines are covered");
+    let diff = coverage_check(dir.path(), &["--min-diff", "90"]);
+    assert!(!diff.status.success());
+    let stderr = String::from_utf8_lossy(&diff.stderr);
+    assert!(stderr.contains("main.pluto: 0.0% of new lines covered (0/1), minimum is 90%"), "{stderr}");
+
+    // Re-running the original program adds no lines, so the diff gate passes
+    run(base);
+    let unchanged = coverage_check(dir.path(), &["--min-diff", "90"]);
+    assert!(unchanged.status.success(), "{}", String::from_utf8_lossy(&unchanged.stderr));
+}
+
 // ── Phase 2: Branch coverage tests ─────────────────────────────────────────
 
 #[test]
@@ -634,7 +756,7 @@ fn coverage_branch_count_in_report() {
                 branch_id: 1,
             },
         ],
-        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string() }],
+        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string(), excluded_lines: vec![] }],
     };
     // Statement covered, then branch covered, else branch not covered
     let data = CoverageData { counters: vec![1, 3, 0] };
@@ -900,7 +1022,7 @@ fn coverage_lcov_basic_format() {
                 branch_id: 0,
             },
         ],
-        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string() }],
+        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string(), excluded_lines: vec![] }],
     };
     let data = CoverageData { counters: vec![1, 3, 0] };
     let lcov = generate_lcov(&map, &data);
@@ -918,6 +1040,178 @@ fn coverage_lcov_basic_format() {
     assert!(lcov.contains("end_of_record"), "should have end record");
 }
 
+#[test]
+fn coverage_map_has_select_arm_points() {
+    let map = build_map(
+        r#"
+fn main() {
+    let (tx, rx) = chan<int>(1)
+    select {
+        val = rx.recv() {
+            print(val)
+        }
+        tx.send(1) {
+            print(0)
+        }
+        default {
+            print(-1)
+        }
+    }
+}
+"#,
+    );
+    let arm_points: Vec<_> = map.points.iter().filter(|p| matches!(p.kind, CoverageKind::SelectArm { .. })).collect();
+    assert_eq!(arm_points.len(), 2, "should have 2 SelectArm points");
+    assert_eq!(arm_points[1].kind, CoverageKind::SelectArm { index: 1 });
+    assert_eq!(map.points.iter().filter(|p| p.kind == CoverageKind::SelectDefault).count(), 1);
+}
+
+#[test]
+fn coverage_map_has_short_circuit_points() {
+    let map = build_map(
+        r#"
+fn main() {
+    let a = true
+    let b = false
+    if a && b || a {
+        print(1)
+    }
+}
+"#,
+    );
+    let left: Vec<_> = map.points.iter().filter(|p| p.kind == CoverageKind::ShortCircuitLeft).collect();
+    let right: Vec<_> = map.points.iter().filter(|p| p.kind == CoverageKind::ShortCircuitRight).collect();
+    assert_eq!(left.len(), 2, "one pair per && and ||");
+    assert_eq!(right.len(), 2);
+    assert!(left.iter().all(|p| p.branch_id == 3));
+    assert!(right.iter().all(|p| p.branch_id == 4));
+}
+
+#[test]
+fn coverage_short_circuit_and_select_end_to_end() {
+    let dir = tempfile::tempdir().unwrap();
+    let source_path = dir.path().join("main.pluto");
+    let bin_path = dir.path().join("test_bin");
+
+    std::fs::write(&source_path, r#"fn main() {
+    let a = false
+    let b = true
+    if a && b {
+        print(1)
+    }
+    let (tx, rx) = chan<int>(1)
+    select {
+        val = rx.recv() {
+            print(val)
+        }
+        default {
+            print(0)
+        }
+    }
+}
+"#).unwrap();
+
+    let map = pluto::compile_file_with_coverage(&source_path, &bin_path, None).unwrap();
+    let status = std::process::Command::new(&bin_path)
+        .current_dir(dir.path())
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let data = CoverageData::read_binary(&dir.path().join(".pluto-coverage/coverage-data.bin")).unwrap();
+    let report = generate_json_report(&map, &data);
+    let hits = |kind: CoverageKind| {
+        report.files[0].branch_details.iter().find(|b| b.kind == kind).unwrap().hit_count
+    };
+    // `a` is false, so `&&` was decided by its left operand
+    assert_eq!(hits(CoverageKind::ShortCircuitLeft), 1);
+    assert_eq!(hits(CoverageKind::ShortCircuitRight), 0);
+    // Nothing was sent, so select fell through to default
+    assert_eq!(hits(CoverageKind::SelectDefault), 1);
+    assert_eq!(hits(CoverageKind::SelectArm { index: 0 }), 0);
+
+    let lcov = generate_lcov(&map, &data);
+    assert!(lcov.contains("BRDA:4,"), "short-circuit branches on line 4:\n{lcov}");
+    assert!(lcov.contains(",4,-\n"), "untaken right side is reported:\n{lcov}");
+}
+
+#[test]
+fn coverage_map_skips_no_coverage_functions() {
+    let map = build_map(r#"@no_coverage
+fn debug_dump(x: int) {
+    print(x)
+}
+
+fn main() {
+    print(1)
+}
+"#);
+    assert!(map.points.iter().all(|p| p.function_name == "main"), "{:?}", map.points);
+    assert_eq!(map.files[0].excluded_lines, vec![2, 3, 4]);
+    let ids: Vec<u32> = map.points.iter().map(|p| p.id).collect();
+    assert_eq!(ids, (0..map.num_points()).collect::<Vec<_>>());
+}
+
+#[test]
+fn coverage_map_skips_ignored_statements() {
+    let map = build_map(r#"fn main() {
+    let a = 1
+    print(a) // coverage: ignore
+    // coverage: ignore
+    if a > 0 {
+        print(2)
+    }
+    print(3)
+}
+"#);
+    let stmt_lines: Vec<u32> = map.points.iter()
+        .filter(|p| p.kind == CoverageKind::Statement)
+        .map(|p| p.line)
+        .collect();
+    assert_eq!(stmt_lines, vec![2, 8]);
+    assert!(!map.points.iter().any(|p| p.kind.is_branch()));
+    assert_eq!(map.files[0].excluded_lines, vec![3, 5, 6, 7]);
+}
+
+#[test]
+fn coverage_excluded_function_does_not_lower_total() {
+    let dir = tempfile::tempdir().unwrap();
+    let source_path = dir.path().join("main.pluto");
+    let bin_path = dir.path().join("test_bin");
+
+    std::fs::write(&source_path, r#"@no_coverage
+fn debug_dump(x: int) {
+    let show = (n: int) => {
+        print(n)
+    }
+    show(x)
+}
+
+fn main() {
+    print(1)
+}
+"#).unwrap();
+
+    let map = pluto::compile_file_with_coverage(&source_path, &bin_path, None).unwrap();
+    let status = std::process::Command::new(&bin_path)
+        .current_dir(dir.path())
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    // The lifted closure is excluded along with the function that contains it
+    assert!(map.points.iter().all(|p| p.function_name == "main"), "{:?}", map.points);
+
+    let data = CoverageData::read_binary(&dir.path().join(".pluto-coverage/coverage-data.bin")).unwrap();
+    let report = generate_json_report(&map, &data);
+    assert_eq!(report.summary.line_percent, 100.0);
+    assert_eq!(report.summary.function_percent, 100.0);
+    assert_eq!(report.files[0].excluded_lines, (2..=7).collect::<Vec<_>>());
+
+    let html = generate_html_report(&map, &data, dir.path());
+    assert!(html.contains("\"excluded_lines\":[2,3,4,5,6,7]"));
+}
+
 #[test]
 fn coverage_lcov_with_branches() {
     use pluto::coverage::*;
@@ -946,7 +1240,7 @@ fn coverage_lcov_with_branches() {
                 branch_id: 1,
             },
         ],
-        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string() }],
+        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string(), excluded_lines: vec![] }],
     };
     let data = CoverageData { counters: vec![1, 2, 0] };
     let lcov = generate_lcov(&map, &data);
@@ -978,7 +1272,7 @@ fn coverage_json_basic_format() {
                 branch_id: 0,
             },
         ],
-        files: vec![CoverageFile { id: 0, path: "math.pluto".to_string() }],
+        files: vec![CoverageFile { id: 0, path: "math.pluto".to_string(), excluded_lines: vec![] }],
     };
     let data = CoverageData { counters: vec![5, 5] };
     let report = generate_json_report(&map, &data);
@@ -1008,7 +1302,7 @@ fn coverage_json_serializes() {
                 branch_id: 0,
             },
         ],
-        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string() }],
+        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string(), excluded_lines: vec![] }],
     };
     let data = CoverageData { counters: vec![1] };
     let report = generate_json_report(&map, &data);
@@ -1137,7 +1431,7 @@ fn coverage_html_contains_template_structure() {
                 branch_id: 0,
             },
         ],
-        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string() }],
+        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string(), excluded_lines: vec![] }],
     };
     let data = CoverageData { counters: vec![1, 3] };
 
@@ -1176,7 +1470,7 @@ fn coverage_html_embeds_coverage_data() {
                 branch_id: 0,
             },
         ],
-        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string() }],
+        files: vec![CoverageFile { id: 0, path: "test.pluto".to_string(), excluded_lines: vec![] }],
     };
     let data = CoverageData { counters: vec![1, 1] };
 
@@ -1209,7 +1503,7 @@ fn coverage_html_embeds_source_code() {
             function_name: "hello".to_string(),
             branch_id: 0,
         }],
-        files: vec![CoverageFile { id: 0, path: "hello.pluto".to_string() }],
+        files: vec![CoverageFile { id: 0, path: "hello.pluto".to_string(), excluded_lines: vec![] }],
     };
     let data = CoverageData { counters: vec![1] };
 
@@ -1264,6 +1558,62 @@ fn main() {
     assert!(file_size > 1000, "HTML report should be >1KB, got {} bytes", file_size);
 }
 
+#[test]
+fn coverage_html_includes_per_function_hit_counts() {
+    let dir = tempfile::tempdir().unwrap();
+    let source_path = dir.path().join("main.pluto");
+    let bin_path = dir.path().join("test_bin");
+
+    std::fs::write(&source_path, r#"
+fn add(a: int, b: int) int {
+    return a + b
+}
+fn unused() {
+    print(0)
+}
+fn main() {
+    let mut total = 0
+    let mut i = 0
+    while i < 3 {
+        total = add(total, i)
+        i = i + 1
+    }
+    print(total)
+}
+"#).unwrap();
+
+    let map = pluto::compile_file_with_coverage(
+        &source_path, &bin_path, None,
+    ).unwrap();
+
+    let status = std::process::Command::new(&bin_path)
+        .current_dir(dir.path())
+        .status().unwrap();
+    assert!(status.success());
+
+    let cov_dir = dir.path().join(".pluto-coverage");
+    let data = CoverageData::read_binary(&cov_dir.join("coverage-data.bin")).unwrap();
+
+    let report = pluto::coverage::generate_json_report(&map, &data);
+    let funcs = &report.files[0].function_details;
+    let add = funcs.iter().find(|f| f.name == "add").expect("add in report");
+    assert_eq!(add.hit_count, 3);
+    assert_eq!(add.statement_hits, 3, "one return per call");
+    let unused = funcs.iter().find(|f| f.name == "unused").expect("unused in report");
+    assert_eq!(unused.hit_count, 0);
+    assert_eq!(unused.statement_hits, 0);
+    let main = funcs.iter().find(|f| f.name == "main").expect("main in report");
+    assert_eq!(main.hit_count, 1);
+    assert!(main.statement_hits > add.statement_hits, "main runs the loop");
+
+    // The counts are embedded for the HTML function table and source overlay
+    let html = pluto::coverage::generate_html_report(&map, &data, dir.path());
+    assert!(html.contains(r#""name":"add","line":2,"hit_count":3,"statement_hits":3"#),
+        "HTML should embed add's call and statement counts");
+    assert!(html.contains("Stmts executed"), "function table should show executed statements");
+    assert!(html.contains("fn-heat"), "source view should overlay per-function counts");
+}
+
 #[test]
 fn coverage_html_missing_source_still_works() {
     use pluto::coverage::*;
@@ -1279,7 +1629,7 @@ fn coverage_html_missing_source_still_works() {
             function_name: "main".to_string(),
             branch_id: 0,
         }],
-        files: vec![CoverageFile { id: 0, path: "missing.pluto".to_string() }],
+        files: vec![CoverageFile { id: 0, path: "missing.pluto".to_string(), excluded_lines: vec![] }],
     };
     let data = CoverageData { counters: vec![1] };
 
@@ -185,6 +185,60 @@ test "empty channel deadlocks" {
     assert!(stderr.contains("deadlock"), "Expected deadlock message, got stderr: {stderr}");
 }
 
+#[test]
+fn sequential_deadlock_in_test_body_has_no_spawn_note() {
+    let (_stdout, stderr, code) = compile_test_and_run(r#"
+test "never sent" {
+    let (tx, rx) = chan<int>(1)
+    let v = rx.recv()!
+    expect(v).to_equal(0)
+}
+"#);
+    assert_ne!(code, 0, "Should have exited with non-zero");
+    assert!(stderr.contains("deadlock detected — channel recv on empty buffer"), "got stderr: {stderr}");
+    assert!(!stderr.contains("note:"), "unexpected note, got stderr: {stderr}");
+}
+
+#[test]
+fn sequential_deadlock_in_spawned_task_suggests_round_robin() {
+    let (_stdout, stderr, code) = compile_test_and_run(r#"
+fn consume(rx: Receiver<int>) int {
+    return rx.recv()!
+}
+
+test "consumer spawned before send" {
+    let (tx, rx) = chan<int>(1)
+    let t = spawn consume(rx)
+    tx.send(5)!
+    expect(t.get()!).to_equal(5)
+}
+"#);
+    assert_ne!(code, 0, "Should have exited with non-zero");
+    assert!(stderr.contains("deadlock"), "Expected deadlock message, got stderr: {stderr}");
+    assert!(stderr.contains("tests[scheduler: RoundRobin]"), "Expected scheduler hint, got stderr: {stderr}");
+}
+
+#[test]
+fn round_robin_consumer_spawned_before_send_completes() {
+    // The same program runs to completion once tasks can interleave
+    let (stdout, stderr, code) = compile_test_and_run(r#"
+fn consume(rx: Receiver<int>) int {
+    return rx.recv()!
+}
+
+tests[scheduler: RoundRobin] {
+    test "consumer spawned before send" {
+        let (tx, rx) = chan<int>(1)
+        let t = spawn consume(rx)
+        tx.send(5)!
+        expect(t.get()!).to_equal(5)
+    }
+}
+"#);
+    assert!(stdout.contains("1 tests passed"), "Expected 1 tests passed, got: {stdout}\n{stderr}");
+    assert_eq!(code, 0);
+}
+
 // ── Existing concurrency patterns through test mode ─────────────────────
 
 #[test]
@@ -5769,3 +5823,250 @@ tests[scheduler: Exhaustive] {
     assert_eq!(code, 0);
     assert!(stderr.contains("schedule"), "Expected schedule info: {stderr}");
 }
+
+// ── WaitGroup under the test runtime ─────────────────────────────────────
+
+#[test]
+fn sequential_waitgroup_tasks_already_done() {
+    let (stdout, stderr, code) = compile_test_and_run(r#"
+fn worker(wg: WaitGroup) int {
+    wg.done()
+    return 1
+}
+
+test "sequential wait group" {
+    let wg = wait_group()
+    wg.add(2)
+    let a = spawn worker(wg)
+    let b = spawn worker(wg)
+    wg.wait()
+    expect(a.get() + b.get()).to_equal(2)
+}
+"#);
+    assert!(stdout.contains("1 tests passed"), "Expected pass, got stdout: {stdout}\nstderr: {stderr}");
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn round_robin_waitgroup_blocks_until_done() {
+    let (stdout, stderr, code) = compile_test_and_run(r#"
+fn worker(id: int, wg: WaitGroup, tx: Sender<int>) {
+    tx.send(id)!
+    wg.done()
+}
+
+tests[scheduler: RoundRobin] {
+    test "wait blocks until every worker is done" {
+        let wg = wait_group()
+        let (tx, rx) = chan<int>(4)
+        wg.add(3)
+        spawn worker(1, wg, tx).detach()
+        spawn worker(2, wg, tx).detach()
+        spawn worker(3, wg, tx).detach()
+        wg.wait()
+        expect(rx.len()).to_equal(3)
+    }
+}
+"#);
+    assert!(stdout.contains("1 tests passed"), "Expected pass, got stdout: {stdout}\nstderr: {stderr}");
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn round_robin_waitgroup_missing_done_deadlocks() {
+    let (_stdout, stderr, code) = compile_test_and_run(r#"
+fn worker(wg: WaitGroup) int {
+    return 1
+}
+
+tests[scheduler: RoundRobin] {
+    test "nobody calls done" {
+        let wg = wait_group()
+        wg.add(1)
+        let t = spawn worker(wg)
+        wg.wait()
+        expect(t.get()).to_equal(1)
+    }
+}
+"#);
+    assert_ne!(code, 0);
+    assert!(stderr.contains("blocked on WaitGroup.wait()"), "stderr: {stderr}");
+}
+
+#[test]
+fn exhaustive_waitgroup_every_schedule_completes() {
+    let (stdout, stderr, code) = compile_test_and_run(r#"
+fn worker(wg: WaitGroup) int {
+    wg.done()
+    return 1
+}
+
+tests[scheduler: Exhaustive] {
+    test "wait group under all interleavings" {
+        let wg = wait_group()
+        wg.add(2)
+        let a = spawn worker(wg)
+        let b = spawn worker(wg)
+        wg.wait()
+        expect(a.get() + b.get()).to_equal(2)
+    }
+}
+"#);
+    assert!(stdout.contains("1 tests passed"), "Expected pass, got stdout: {stdout}\nstderr: {stderr}");
+    assert_eq!(code, 0);
+}
+
+// ── Atomic under the test runtime ────────────────────────────────────────
+
+#[test]
+fn round_robin_atomic_shared_between_fibers() {
+    let (stdout, stderr, code) = compile_test_and_run(r#"
+fn bump(counter: Atomic, n: int) int {
+    let mut i = 0
+    while i < n {
+        counter.add(1)
+        i = i + 1
+    }
+    return n
+}
+
+tests[scheduler: RoundRobin] {
+    test "fibers update one counter" {
+        let counter = atomic(0)
+        let a = spawn bump(counter, 10)
+        let b = spawn bump(counter, 5)
+        expect(a.get() + b.get()).to_equal(15)
+        expect(counter.load()).to_equal(15)
+    }
+}
+"#);
+    assert!(stdout.contains("1 tests passed"), "Expected pass, got stdout: {stdout}\nstderr: {stderr}");
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn exhaustive_atomic_compare_and_swap_claims_once() {
+    let (stdout, stderr, code) = compile_test_and_run(r#"
+fn claim(owner: Atomic, id: int) int {
+    if owner.compare_and_swap(0, id) {
+        return 1
+    }
+    return 0
+}
+
+tests[scheduler: Exhaustive] {
+    test "only one task wins the claim" {
+        let owner = atomic(0)
+        let a = spawn claim(owner, 1)
+        let b = spawn claim(owner, 2)
+        expect(a.get() + b.get()).to_equal(1)
+    }
+}
+"#);
+    assert!(stdout.contains("1 tests passed"), "Expected pass, got stdout: {stdout}\nstderr: {stderr}");
+    assert_eq!(code, 0);
+}
+
+// ── Mutex under the test runtime ─────────────────────────────────────────
+
+#[test]
+fn round_robin_mutex_blocks_until_unlocked() {
+    // The first task holds the lock across a channel recv, so the second
+    // task's lock() must block its fiber until the guard is released
+    let (stdout, stderr, code) = compile_test_and_run(r#"
+fn hold(m: Mutex<int>, rx: Receiver<int>) int {
+    let g = m.lock()
+    let v = rx.recv()!
+    g.set(g.get() + v)
+    return 0
+}
+
+fn bump(m: Mutex<int>) int {
+    let g = m.lock()
+    g.set(g.get() * 2)
+    return 0
+}
+
+fn feed(tx: Sender<int>) int {
+    tx.send(4)!
+    return 0
+}
+
+tests[scheduler: RoundRobin] {
+    test "lock waits for the holder" {
+        let m = mutex(1)
+        let (tx, rx) = chan<int>(1)
+        let a = spawn hold(m, rx)
+        let b = spawn bump(m)
+        let c = spawn feed(tx)
+        b.get()
+        a.get()!
+        c.get()!
+        let g = m.lock()
+        expect(g.get()).to_equal(10)
+    }
+}
+"#);
+    assert!(stdout.contains("1 tests passed"), "Expected pass, got stdout: {stdout}\nstderr: {stderr}");
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn sequential_mutex_held_across_spawn_deadlock() {
+    let (_, stderr, code) = compile_test_and_run(r#"
+fn bump(m: Mutex<int>) int {
+    let g = m.lock()
+    g.set(g.get() + 1)
+    return 0
+}
+
+test "spawn while holding the lock" {
+    let m = mutex(0)
+    let g = m.lock()
+    let t = spawn bump(m)
+    t.get()
+}
+"#);
+    assert_ne!(code, 0);
+    assert!(stderr.contains("deadlock detected — Mutex.lock() on a mutex that is already held"), "stderr: {stderr}");
+}
+
+// ── Sleep under the test runtime ─────────────────────────────────────────
+
+#[test]
+fn round_robin_sleep_yields_to_other_fibers() {
+    // Fibers aren't preempted, so the waiter's loop only ends because sleep
+    // yields; the hour of sleeping is virtual and returns at once
+    let (stdout, stderr, code) = compile_test_and_run(r#"
+extern fn __pluto_time_sleep_ns(ns: int)
+extern fn __pluto_time_ns() int
+
+fn wait_for(flag: Atomic) int {
+    let mut naps = 0
+    while flag.load() == 0 {
+        __pluto_time_sleep_ns(3600000000000)
+        naps = naps + 1
+    }
+    return naps
+}
+
+fn set_flag(flag: Atomic) int {
+    flag.store(1)
+    return 0
+}
+
+tests[scheduler: RoundRobin] {
+    test "sleeping fiber lets the other run" {
+        let flag = atomic(0)
+        let start = __pluto_time_ns()
+        let w = spawn wait_for(flag)
+        let r = spawn set_flag(flag)
+        expect(r.get()).to_equal(0)
+        expect(w.get() >= 1).to_be_true()
+        expect(__pluto_time_ns() - start >= 3600000000000).to_be_true()
+    }
+}
+"#);
+    assert!(stdout.contains("1 tests passed"), "Expected pass, got stdout: {stdout}\nstderr: {stderr}");
+    assert_eq!(code, 0);
+}
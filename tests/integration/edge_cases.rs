@@ -57,6 +57,32 @@ fn deeply_nested_generics() {
     assert_eq!(stdout.trim(), "42");
 }
 
+#[test]
+fn nesting_within_limit_compiles() {
+    let src = format!("fn main() {{\n    print({}42{})\n}}\n", "(".repeat(100), ")".repeat(100));
+    assert_eq!(compile_and_run_stdout(&src).trim(), "42");
+}
+
+#[test]
+fn excessive_paren_nesting_rejected() {
+    // Far past the limit: must be a clean error, not a stack overflow
+    let src = format!("fn main() {{\n    let x = {}1{}\n}}\n", "(".repeat(100_000), ")".repeat(100_000));
+    compile_should_fail_with(&src, "expression too deeply nested");
+}
+
+#[test]
+fn excessive_block_nesting_rejected() {
+    let src = format!("fn main() {{\n{}print(1)\n{}}}\n", "if true {\n".repeat(10_000), "}\n".repeat(10_000));
+    compile_should_fail_with(&src, "expression too deeply nested");
+}
+
+#[test]
+fn excessive_operator_chain_rejected() {
+    // A flat chain still builds a deeply nested AST
+    let src = format!("fn main() {{\n    let x = 1{}\n}}\n", " + 1".repeat(100_000));
+    compile_should_fail_with(&src, "expression too deeply nested");
+}
+
 #[test]
 fn newline_before_dot_method_call() {
     // obj\n.method() → newline before . should work (method chaining)
@@ -800,10 +800,10 @@ fn fail_match_on_class() {
 
 #[test]
 fn fail_enum_field_access() {
-    // Cannot access fields on enum directly (only via match)
+    // Cannot access fields on enum directly (only via match or an `is` guard)
     compile_should_fail_with(
         "enum Wrapper {\n    Val { value: int }\n    Empty\n}\n\nfn main() {\n    let w = Wrapper.Val { value: 42 }\n    print(w.value)\n}",
-        "field access on non-class type",
+        "cannot access field 'value' of enum 'Wrapper' without checking its variant",
     );
 }
 
@@ -1247,3 +1247,305 @@ fn match_expr_trailing_comma_accepted() {
     "#);
     assert_eq!(stdout.trim(), "1");
 }
+
+#[test]
+fn tuple_variant_construct_and_match_positionally() {
+    let stdout = compile_and_run_stdout(r#"
+        enum Shape {
+            Circle(float)
+            Rect(int, int)
+            Empty
+        }
+        fn area(s: Shape) int {
+            match s {
+                Shape.Circle(r) {
+                    return (r * 2.0) as int
+                }
+                Shape.Rect(w, h) {
+                    return w * h
+                }
+                Shape.Empty {
+                    return 0
+                }
+            }
+        }
+        fn main() {
+            print(area(Shape.Circle(1.5)))
+            print(area(Shape.Rect(3, 4)))
+            let label = match Shape.Rect(2, 7) {
+                Shape.Circle(r) => "circle",
+                Shape.Rect(w, h) => f"{w}x{h}",
+                Shape.Empty => "empty"
+            }
+            print(label)
+        }
+    "#);
+    assert_eq!(stdout, "3\n12\n2x7\n");
+}
+
+#[test]
+fn tuple_variant_of_generic_enum() {
+    let stdout = compile_and_run_stdout(r#"
+        enum Box<T> {
+            Full(T)
+            Empty
+        }
+        fn main() {
+            let b = Box<string>.Full("hi")
+            match b {
+                Box.Full(s) { print(s) }
+                Box.Empty { print("empty") }
+            }
+        }
+    "#);
+    assert_eq!(stdout, "hi\n");
+}
+
+#[test]
+fn tuple_variant_wrong_arity_rejected() {
+    compile_should_fail_with(r#"
+        enum Shape {
+            Circle(float)
+        }
+        fn main() {
+            let s = Shape.Circle(1.0, 2.0)
+        }
+    "#, "has 1 fields, but 2 were provided");
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// METHODS & ASSOCIATED FUNCTIONS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn enum_method_matches_on_self() {
+    let stdout = compile_and_run_stdout(r#"
+        enum Shape {
+            Circle(float)
+            Rect { w: float, h: float }
+            Empty
+
+            fn area(self) float {
+                return match self {
+                    Shape.Circle(r) => 3.0 * r * r,
+                    Shape.Rect { w, h } => w * h,
+                    Shape.Empty => 0.0
+                }
+            }
+
+            fn scaled_area(self, k: float) float {
+                return self.area() * k
+            }
+        }
+        fn main() {
+            print(Shape.Circle(2.0).area())
+            let shapes = [Shape.Rect { w: 2.0, h: 3.5 }, Shape.Empty]
+            for s in shapes {
+                print(s.scaled_area(2.0))
+            }
+        }
+    "#);
+    assert_eq!(stdout, "12\n14\n0\n");
+}
+
+#[test]
+fn enum_associated_function() {
+    let stdout = compile_and_run_stdout(r#"
+        enum Color {
+            Red
+            Green
+
+            fn fallback() Color {
+                return Color.Green
+            }
+
+            fn name(self) string {
+                match self {
+                    Color.Red { return "red" }
+                    Color.Green { return "green" }
+                }
+            }
+        }
+        fn main() {
+            print(Color.fallback().name())
+        }
+    "#);
+    assert_eq!(stdout, "green\n");
+}
+
+#[test]
+fn enum_unknown_method_rejected() {
+    compile_should_fail_with(r#"
+        enum Color {
+            Red
+        }
+        fn main() {
+            let c = Color.Red
+            c.name()
+        }
+    "#, "enum 'Color' has no method 'name'");
+}
+
+#[test]
+fn enum_method_named_like_variant_rejected() {
+    compile_should_fail_with(r#"
+        enum Color {
+            Red
+
+            fn Red(self) int {
+                return 1
+            }
+        }
+        fn main() { }
+    "#, "has the same name as a variant");
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// `is` VARIANT TESTS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn is_tests_variant_tag() {
+    let stdout = compile_and_run_stdout(r#"
+        enum Shape {
+            Circle { radius: float }
+            Empty
+        }
+        fn main() {
+            let c = Shape.Circle { radius: 1.0 }
+            print(c is Shape.Circle)
+            print(c is Shape.Empty)
+            print(Shape.Empty is Shape.Empty)
+        }
+    "#);
+    assert_eq!(stdout, "true\nfalse\ntrue\n");
+}
+
+#[test]
+fn is_guard_exposes_variant_fields() {
+    let stdout = compile_and_run_stdout(r#"
+        enum Shape {
+            Circle { radius: int }
+            Rect { w: float, label: string, big: bool }
+            Square(int)
+        }
+        fn describe(s: Shape) {
+            if s is Shape.Circle {
+                print(s.radius * 2)
+            } else if s is Shape.Rect {
+                print(s.w)
+                print(s.label)
+                print(s.big)
+            }
+            if s is Shape.Square && s._0 > 2 {
+                print(s._0)
+            }
+        }
+        fn main() {
+            describe(Shape.Circle { radius: 5 })
+            describe(Shape.Rect { w: 1.5, label: "r", big: true })
+            describe(Shape.Square(1))
+            describe(Shape.Square(3))
+        }
+    "#);
+    assert_eq!(stdout, "10\n1.5\nr\ntrue\n3\n");
+}
+
+#[test]
+fn is_guard_on_self_and_generic_enum() {
+    let stdout = compile_and_run_stdout(r#"
+        enum Maybe<T> {
+            Just { value: T }
+            Nothing
+        }
+        enum Token {
+            Number(int)
+            End
+
+            fn value_or(self, d: int) int {
+                if self is Token.Number {
+                    return self._0
+                }
+                return d
+            }
+        }
+        fn main() {
+            let m = Maybe<string>.Just { value: "hi" }
+            if m is Maybe.Just {
+                print(m.value)
+            }
+            print(Token.Number(4).value_or(0))
+            print(Token.End.value_or(9))
+        }
+    "#);
+    assert_eq!(stdout, "hi\n4\n9\n");
+}
+
+#[test]
+fn enum_field_access_without_guard_rejected() {
+    compile_should_fail_with(r#"
+        enum Shape {
+            Circle { radius: int }
+            Empty
+        }
+        fn main() {
+            let s = Shape.Circle { radius: 1 }
+            print(s.radius)
+        }
+    "#, "cannot access field 'radius' of enum 'Shape' without checking its variant");
+}
+
+#[test]
+fn enum_field_access_on_wrong_variant_rejected() {
+    compile_should_fail_with(r#"
+        enum Shape {
+            Circle { radius: int }
+            Rect { w: int, h: int }
+        }
+        fn main() {
+            let s = Shape.Circle { radius: 1 }
+            if s is Shape.Circle {
+                print(s.w)
+            }
+        }
+    "#, "variant 'Shape.Circle' has no field 'w'");
+}
+
+#[test]
+fn is_guard_dropped_when_variable_reassigned() {
+    compile_should_fail_with(r#"
+        enum Shape {
+            Circle { radius: int }
+            Rect { w: int, h: int }
+        }
+        fn main() {
+            let mut s = Shape.Circle { radius: 1 }
+            if s is Shape.Circle {
+                s = Shape.Rect { w: 1, h: 2 }
+                print(s.radius)
+            }
+        }
+    "#, "cannot access field 'radius' of enum 'Shape' without checking its variant");
+}
+
+#[test]
+fn is_rejects_unknown_variant_and_non_enum() {
+    compile_should_fail_with(r#"
+        enum Shape {
+            Circle { radius: int }
+        }
+        fn main() {
+            let s = Shape.Circle { radius: 1 }
+            print(s is Shape.Tri)
+        }
+    "#, "enum 'Shape' has no variant 'Tri'");
+    compile_should_fail_with(r#"
+        enum Shape {
+            Circle { radius: int }
+        }
+        fn main() {
+            let x = 1
+            print(x is Shape.Circle)
+        }
+    "#, "'is' requires an enum value, found int");
+}
@@ -572,3 +572,285 @@ fn main() { print(g(1)) }
         "no catch handler covers",
     );
 }
+
+// ── map_err: transform the error before propagating ──────────────────────────
+
+#[test]
+fn map_err_raises_mapped_error() {
+    // The caller's error set is the mapper's error only, so a typed catch of
+    // `Wrapped` alone covers it.
+    let out = compile_and_run_stdout(
+        "error NotFound {\n    code: int\n}\n\nerror Wrapped {\n    reason: string\n}\n\nfn find(id: int) int {\n    if id < 0 {\n        raise NotFound { code: id }\n    }\n    return id\n}\n\nfn lookup(id: int) int {\n    let v = find(id)! map_err((e: error) => Wrapped { reason: \"lookup failed\" })\n    return v * 2\n}\n\nfn main() {\n    let x = lookup(-1) catch err: Wrapped {\n        print(err.reason)\n        return\n    }\n    print(x)\n}",
+    );
+    assert_eq!(out, "lookup failed\n");
+}
+
+#[test]
+fn map_err_success_path_skips_mapper() {
+    let out = compile_and_run_stdout(
+        "error NotFound {\n    code: int\n}\n\nerror Wrapped {\n    code: int\n}\n\nfn find(id: int) int {\n    if id < 0 {\n        raise NotFound { code: id }\n    }\n    return id\n}\n\nfn lookup(id: int) int {\n    let v = find(id)! map_err((e: error) => {\n        print(\"mapping\")\n        return Wrapped { code: 1 }\n    })\n    return v * 2\n}\n\nfn main() {\n    print(lookup(21) catch -1)\n    print(lookup(-1) catch -1)\n}",
+    );
+    assert_eq!(out, "42\nmapping\n-1\n");
+}
+
+#[test]
+fn map_err_on_method_call() {
+    let out = compile_and_run_stdout(
+        "error Empty {}\n\nerror StoreError {\n    code: int\n}\n\nclass Store {\n    n: int\n\n    fn get(self) int {\n        if self.n == 0 {\n            raise Empty {}\n        }\n        return self.n\n    }\n}\n\nfn read(s: Store) int {\n    return s.get()! map_err((e: error) => StoreError { code: 404 })\n}\n\nfn main() {\n    let v = read(Store { n: 0 }) catch err: StoreError {\n        print(err.code)\n        return\n    }\n    print(v)\n}",
+    );
+    assert_eq!(out, "404\n");
+}
+
+#[test]
+fn map_err_mapper_must_return_error() {
+    compile_should_fail_with(
+        "error Fail {}\n\nfn f() int {\n    raise Fail {}\n    return 0\n}\n\nfn g() int {\n    return f()! map_err((e: error) => 5)\n}\n\nfn main() {\n    print(g() catch 0)\n}",
+        "map_err expects a closure of type fn(error) E",
+    );
+}
+
+#[test]
+fn map_err_on_infallible_rejected() {
+    compile_should_fail_with(
+        "error Wrapped {}\n\nfn f() int {\n    return 1\n}\n\nfn g() int {\n    return f()! map_err((e: error) => Wrapped {})\n}\n\nfn main() {\n    print(g())\n}",
+        "'!' applied to infallible function 'f'",
+    );
+}
+
+#[test]
+fn try_catches_propagated_error() {
+    let out = compile_and_run_stdout(
+        "error Fail {}\n\nfn f(n: int) int {\n    if n < 0 {\n        raise Fail {}\n    }\n    return n\n}\n\nfn main() {\n    try {\n        let a = f(1)!\n        print(a)\n        let b = f(-1)!\n        print(b)\n    } catch e {\n        print(\"caught\")\n    }\n    print(\"after\")\n}",
+    );
+    assert_eq!(out, "1\ncaught\nafter\n");
+}
+
+#[test]
+fn try_success_path_skips_handler() {
+    let out = compile_and_run_stdout(
+        "error Fail {}\n\nfn f(n: int) int {\n    if n < 0 {\n        raise Fail {}\n    }\n    return n\n}\n\nfn main() {\n    try {\n        print(f(2)! + f(3)!)\n    } catch e {\n        print(\"caught\")\n    }\n}",
+    );
+    assert_eq!(out, "5\n");
+}
+
+#[test]
+fn try_catches_raise_in_body() {
+    let out = compile_and_run_stdout(
+        "error Fail {}\n\nfn main() {\n    let mut x = 0\n    try {\n        x = 1\n        raise Fail {}\n    } catch e {\n        x = x + 10\n    }\n    print(x)\n}",
+    );
+    assert_eq!(out, "11\n");
+}
+
+#[test]
+fn try_nested_catches_at_innermost() {
+    let out = compile_and_run_stdout(
+        "error Fail {}\n\nfn f() int {\n    raise Fail {}\n    return 0\n}\n\nfn main() {\n    try {\n        try {\n            f()!\n        } catch e {\n            print(\"inner\")\n        }\n        print(\"between\")\n        f()!\n    } catch e {\n        print(\"outer\")\n    }\n}",
+    );
+    assert_eq!(out, "inner\nbetween\nouter\n");
+}
+
+#[test]
+fn try_handler_reraise_propagates_to_caller() {
+    let out = compile_and_run_stdout(
+        "error Fail {}\n\nerror Wrapped {\n    code: int\n}\n\nfn f() int {\n    raise Fail {}\n    return 0\n}\n\nfn g() int {\n    try {\n        return f()!\n    } catch e {\n        raise Wrapped { code: 7 }\n    }\n}\n\nfn main() {\n    let v = g() catch err: Wrapped {\n        print(err.code)\n        return\n    }\n    print(v)\n}",
+    );
+    assert_eq!(out, "7\n");
+}
+
+#[test]
+fn try_catching_everything_makes_fn_infallible() {
+    compile_should_fail_with(
+        "error Fail {}\n\nfn f() int {\n    raise Fail {}\n    return 0\n}\n\nfn g() {\n    try {\n        f()!\n    } catch e {\n        print(\"handled\")\n    }\n}\n\nfn main() {\n    g()!\n}",
+        "'!' applied to infallible function 'g'",
+    );
+}
+
+#[test]
+fn try_bare_fallible_calls_propagate_to_handler() {
+    let out = compile_and_run_stdout(
+        "error Fail {}\n\nclass Box {\n    n: int\n\n    fn get(self) int {\n        if self.n < 0 {\n            raise Fail {}\n        }\n        return self.n\n    }\n}\n\nfn f(n: int) int {\n    if n < 0 {\n        raise Fail {}\n    }\n    return n\n}\n\nfn main() {\n    try {\n        print(f(1) + f(2))\n        print(Box { n: -1 }.get())\n        print(\"unreachable\")\n    } catch e {\n        print(\"caught method\")\n    }\n    try {\n        let v = f(-1) catch 7\n        print(v)\n        if v > 0 {\n            f(-2)\n        }\n        print(\"unreachable\")\n    } catch e {\n        print(\"caught fn\")\n    }\n}",
+    );
+    assert_eq!(out, "3\ncaught method\n7\ncaught fn\n");
+}
+
+#[test]
+fn try_bare_fallible_call_in_closure_must_be_handled() {
+    compile_should_fail_with(
+        "error Fail {}\n\nfn f() int {\n    raise Fail {}\n    return 0\n}\n\nfn main() {\n    try {\n        let run = () => {\n            print(f())\n        }\n        run()\n    } catch e {\n        print(\"caught\")\n    }\n}",
+        "call to fallible function 'f' must be handled",
+    );
+}
+
+#[test]
+fn try_bare_fallible_call_in_handler_must_be_handled() {
+    compile_should_fail_with(
+        "error Fail {}\n\nfn f() int {\n    raise Fail {}\n    return 0\n}\n\nfn main() {\n    try {\n        f()\n    } catch e {\n        f()\n    }\n}",
+        "call to fallible function 'f' must be handled",
+    );
+}
+
+#[test]
+fn try_requires_catch_binding() {
+    compile_should_fail_with(
+        "error Fail {}\n\nfn main() {\n    try {\n        print(1)\n    } catch {\n        print(2)\n    }\n}",
+        "expected 'catch <name> { ... }' after a try block",
+    );
+}
+
+#[test]
+fn try_finally_runs_on_success_and_caught_error() {
+    let out = compile_and_run_stdout(
+        "error Fail {}\n\nfn f(n: int) int {\n    if n < 0 {\n        raise Fail {}\n    }\n    return n\n}\n\nfn main() {\n    try {\n        print(f(1)!)\n    } catch e {\n        print(\"caught\")\n    } finally {\n        print(\"finally\")\n    }\n    try {\n        print(f(-1)!)\n    } catch e {\n        print(\"caught\")\n    } finally {\n        print(\"finally\")\n    }\n}",
+    );
+    assert_eq!(out, "1\nfinally\ncaught\nfinally\n");
+}
+
+#[test]
+fn try_finally_runs_before_return() {
+    let out = compile_and_run_stdout(
+        "error Fail {}\n\nfn f(n: int) int {\n    if n < 0 {\n        raise Fail {}\n    }\n    return n\n}\n\nfn g(n: int) int {\n    try {\n        return f(n)!\n    } catch e {\n        return -1\n    } finally {\n        print(\"cleanup\")\n    }\n}\n\nfn main() {\n    print(g(4))\n    print(g(-4))\n}",
+    );
+    assert_eq!(out, "cleanup\n4\ncleanup\n-1\n");
+}
+
+#[test]
+fn try_finally_runs_on_break_and_continue() {
+    let out = compile_and_run_stdout(
+        "error Fail {}\n\nfn main() {\n    for i in 0..4 {\n        try {\n            if i == 1 {\n                continue\n            }\n            if i == 2 {\n                break\n            }\n            print(i)\n        } catch e {\n        } finally {\n            print(\"end\")\n        }\n    }\n}",
+    );
+    assert_eq!(out, "0\nend\nend\nend\n");
+}
+
+#[test]
+fn try_finally_runs_when_handler_raises() {
+    let out = compile_and_run_stdout(
+        "error Fail {}\n\nerror Wrapped {\n    code: int\n}\n\nfn f() int {\n    raise Fail {}\n    return 0\n}\n\nfn g() {\n    try {\n        f()!\n    } catch e {\n        raise Wrapped { code: 7 }\n    } finally {\n        let v = f() catch 3\n        print(v)\n    }\n}\n\nfn main() {\n    g() catch err: Wrapped {\n        print(err.code)\n    }\n}",
+    );
+    assert_eq!(out, "3\n7\n");
+}
+
+#[test]
+fn try_finally_error_supersedes_pending_error() {
+    let out = compile_and_run_stdout(
+        "error Fail {}\n\nerror Late {}\n\nfn g() {\n    try {\n        raise Fail {}\n    } catch e {\n        raise Fail {}\n    } finally {\n        raise Late {}\n    }\n}\n\nfn main() {\n    g() catch err: Late {\n        print(\"late\")\n    } catch err {\n        print(\"fail\")\n    }\n}",
+    );
+    assert_eq!(out, "late\n");
+}
+
+#[test]
+fn try_finally_errors_escape_to_caller() {
+    compile_should_fail_with(
+        "error Fail {}\n\nfn f() int {\n    raise Fail {}\n    return 0\n}\n\nfn g() {\n    try {\n        print(1)\n    } catch e {\n        print(2)\n    } finally {\n        f()!\n    }\n}\n\nfn main() {\n    g()\n}",
+        "call to fallible function 'g' must be handled",
+    );
+}
+
+// ── Debug stack traces ──────────────────────────────────────────────────────
+
+/// Compile `source` with `--debug` instrumentation and run it.
+fn run_debug(source: &str) -> (String, String, i32) {
+    let dir = tempfile::tempdir().unwrap();
+    let src_path = dir.path().join("main.pt");
+    std::fs::write(&src_path, source).unwrap();
+    let bin_path = dir.path().join("test_bin");
+    pluto::compile_file_with_debug(&src_path, &bin_path, None, pluto::GcBackend::MarkSweep, true)
+        .unwrap_or_else(|e| panic!("Compilation failed: {e}"));
+    let output = std::process::Command::new(&bin_path).output().unwrap();
+    (
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+        output.status.code().unwrap_or(-1),
+    )
+}
+
+const UNCAUGHT_SRC: &str = "error Boom {}\n\nclass Worker {\n    n: int\n\n    fn step(self) int {\n        if self.n > 1 {\n            raise Boom {}\n        }\n        return self.n\n    }\n}\n\nfn outer(w: Worker) int {\n    return w.step()! + 1\n}\n\nfn main() {\n    print(outer(Worker { n: 1 })!)\n    print(outer(Worker { n: 5 })!)\n    print(3)\n}";
+
+#[test]
+fn debug_uncaught_error_prints_stack_trace() {
+    let (stdout, stderr, code) = run_debug(UNCAUGHT_SRC);
+    assert_eq!(stdout, "2\n");
+    assert_eq!(code, 1);
+    assert!(
+        stderr.contains("error: uncaught error Boom\nstack trace (most recent call first):\n  at Worker$step\n  at outer\n  at main\n"),
+        "unexpected stderr: {stderr}"
+    );
+}
+
+#[test]
+fn debug_trace_is_taken_where_error_was_raised() {
+    // `helper` has returned by the time `fail` raises, so it must not appear
+    let (_, stderr, code) = run_debug(
+        "error Boom {}\n\nfn helper() int {\n    return 1\n}\n\nfn fail(x: int) {\n    raise Boom {}\n}\n\nfn main() {\n    fail(helper())!\n}",
+    );
+    assert_eq!(code, 1);
+    assert!(stderr.contains("  at fail\n  at main\n"), "unexpected stderr: {stderr}");
+    assert!(!stderr.contains("helper"), "unexpected stderr: {stderr}");
+}
+
+#[test]
+fn debug_caught_error_is_silent() {
+    let (stdout, stderr, code) = run_debug(
+        "error Boom {}\n\nfn fail() int {\n    raise Boom {}\n}\n\nfn main() {\n    let x = fail() catch 7\n    print(x)\n}",
+    );
+    assert_eq!(stdout, "7\n");
+    assert_eq!(stderr, "");
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn uncaught_error_without_debug_has_no_trace() {
+    let (stdout, stderr, code) = common::compile_and_run_output(UNCAUGHT_SRC);
+    assert_eq!(stdout, "2\n");
+    assert!(!stderr.contains("stack trace"), "unexpected stderr: {stderr}");
+    assert_eq!(code, 0);
+}
+
+// ── Declared error sets ──────────────────────────────────────────────────────
+
+#[test]
+fn declared_error_set_allows_listed_errors() {
+    let out = compile_and_run_stdout(
+        "error ParseError {\n    msg: string\n}\n\nerror Empty {}\n\nfn parse(s: string) int | ParseError | Empty {\n    if s.len() == 0 {\n        raise Empty {}\n    }\n    if s == \"x\" {\n        raise ParseError { msg: \"bad\" }\n    }\n    return s.len()\n}\n\nfn wrapper(s: string) int | ParseError | Empty {\n    return parse(s)!\n}\n\nfn main() {\n    print(wrapper(\"abc\") catch -1)\n    let m = wrapper(\"x\") catch err {\n        print(\"caught\")\n        return\n    }\n    print(m)\n}",
+    );
+    assert_eq!(out, "3\ncaught\n");
+}
+
+#[test]
+fn declared_error_set_on_void_function() {
+    let out = compile_and_run_stdout(
+        "error Boom {}\n\nfn check(x: int) | Boom {\n    if x < 0 {\n        raise Boom {}\n    }\n}\n\nfn main() {\n    check(-1) catch Boom {\n        print(\"caught\")\n    }\n}",
+    );
+    assert_eq!(out, "caught\n");
+}
+
+#[test]
+fn declared_error_set_rejects_undeclared_raise() {
+    compile_should_fail_with(
+        "error ParseError {}\n\nerror Empty {}\n\nfn parse(s: string) int | ParseError {\n    if s.len() == 0 {\n        raise Empty {}\n    }\n    return 1\n}\n\nfn main() {\n    print(parse(\"\") catch 0)\n}",
+        "'parse' can raise Empty, but its signature only declares ParseError",
+    );
+}
+
+#[test]
+fn declared_error_set_rejects_undeclared_propagation() {
+    compile_should_fail_with(
+        "error ParseError {}\n\nerror Empty {}\n\nfn parse(s: string) int {\n    if s.len() == 0 {\n        raise Empty {}\n    }\n    raise ParseError {}\n}\n\nfn strict(s: string) int | ParseError {\n    return parse(s)!\n}\n\nfn main() {\n    print(strict(\"\") catch 0)\n}",
+        "'strict' can raise Empty, but its signature only declares ParseError",
+    );
+}
+
+#[test]
+fn declared_error_set_rejects_unknown_error() {
+    compile_should_fail_with(
+        "fn f() | Nope {\n}\n\nfn main() {\n    f()\n}",
+        "unknown error type 'Nope'",
+    );
+}
+
+#[test]
+fn trait_declared_error_set_bounds_implementations() {
+    compile_should_fail_with(
+        "error ParseError {}\n\nerror Other {}\n\ntrait Parser {\n    fn parse(self, s: string) int | ParseError\n}\n\nclass P impl Parser {\n    fn parse(self, s: string) int {\n        raise Other {}\n    }\n}\n\nfn main() {\n    let p = P {}\n    print(p.parse(\"a\") catch 0)\n}",
+        "'P.parse' can raise Other, but trait 'Parser' only declares ParseError",
+    );
+}
@@ -0,0 +1,132 @@
+mod common;
+use common::{compile_and_run_stdout, pluto};
+
+use pluto::format_source;
+
+const MESSY: &str = r#"class   Point {
+  x: int
+        y: int
+    fn sum(self) int { return self.x+self.y }
+}
+
+fn   main( ) {
+let p = Point { x: 1, y: 2 }
+      if p.sum() > 2 { print(p.sum()) } else { print(0) }
+}
+"#;
+
+#[test]
+fn fmt_canonicalizes_layout() {
+    let formatted = format_source(MESSY).unwrap();
+    assert!(formatted.contains("class Point {\n    x: int\n    y: int\n"), "{formatted}");
+    assert!(formatted.contains("return self.x + self.y"), "{formatted}");
+    assert!(formatted.contains("fn main() {\n    let p = Point { x: 1, y: 2 }\n"), "{formatted}");
+}
+
+#[test]
+fn fmt_is_idempotent() {
+    let once = format_source(MESSY).unwrap();
+    let twice = format_source(&once).unwrap();
+    assert_eq!(once, twice);
+}
+
+#[test]
+fn fmt_preserves_semantics() {
+    let formatted = format_source(MESSY).unwrap();
+    assert_eq!(compile_and_run_stdout(MESSY), compile_and_run_stdout(&formatted));
+}
+
+#[test]
+fn fmt_preserves_uuid_hints() {
+    let program = pluto::parse_for_editing(MESSY).unwrap();
+    let hinted = pluto::pretty::pretty_print(&program, true);
+    let formatted = format_source(&hinted).unwrap();
+    assert_eq!(formatted, hinted);
+
+    let mut restored = pluto::parse_for_editing(&formatted).unwrap();
+    assert!(pluto::pretty::restore_uuid_hints(&mut restored, &formatted));
+    assert_eq!(restored.classes[0].node.id, program.classes[0].node.id);
+    assert_eq!(restored.functions[0].node.id, program.functions[0].node.id);
+}
+
+#[test]
+fn fmt_without_hints_adds_none() {
+    let formatted = format_source(MESSY).unwrap();
+    assert!(!formatted.contains("@uuid"));
+}
+
+const COMMENTED: &str = r#"// Points on a grid
+class   Point {
+  x: int    // across
+        /* down */
+        y: int
+    // more fields later
+}
+
+fn main() {
+    let p = Point { x: 1, y: 2 }   // origin-ish
+    // print it
+    print(p.x)
+}
+// end of file
+"#;
+
+#[test]
+fn fmt_keeps_comments() {
+    let formatted = format_source(COMMENTED).unwrap();
+    assert_eq!(
+        formatted,
+        "// Points on a grid\nclass Point {\n    x: int // across\n    /* down */\n    y: int\n    // more fields later\n}\n\n\
+         fn main() {\n    let p = Point { x: 1, y: 2 } // origin-ish\n    // print it\n    print(p.x)\n}\n\n// end of file\n"
+    );
+    assert_eq!(format_source(&formatted).unwrap(), formatted);
+}
+
+#[test]
+fn fmt_keeps_comments_next_to_uuid_hints() {
+    let program = pluto::parse_for_editing(COMMENTED).unwrap();
+    let hinted = pluto::pretty::pretty_print(&program, true);
+    assert!(hinted.starts_with("// Points on a grid\n// @uuid: "), "{hinted}");
+    assert_eq!(format_source(&hinted).unwrap(), hinted);
+}
+
+#[test]
+fn fmt_keeps_nullable_match() {
+    let src = "fn main() {\n    let m: int? = 1\n    match m {\n        none { print(0) }\n        some(v) { print(v) }\n    }\n}\n";
+    let formatted = format_source(src).unwrap();
+    assert!(
+        formatted.contains("match m {\n        some(v) {\n            print(v)\n        }\n        none {\n            print(0)\n        }\n    }"),
+        "{formatted}"
+    );
+    assert!(!formatted.contains("if let"), "{formatted}");
+    assert_eq!(format_source(&formatted).unwrap(), formatted);
+    assert_eq!(compile_and_run_stdout(src), compile_and_run_stdout(&formatted));
+}
+
+#[test]
+fn fmt_rejects_unparseable_source() {
+    assert!(format_source("fn main( {\n}\n").is_err());
+}
+
+#[test]
+fn fmt_cli_check_stdout_and_write() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.pt");
+    std::fs::write(&path, MESSY).unwrap();
+
+    let check = pluto().arg("fmt").arg(&path).arg("--check").output().unwrap();
+    assert!(!check.status.success());
+    assert!(String::from_utf8_lossy(&check.stderr).contains("is not formatted"));
+
+    let printed = pluto().arg("fmt").arg(&path).arg("--stdout").output().unwrap();
+    assert!(printed.status.success());
+    assert_eq!(String::from_utf8_lossy(&printed.stdout), format_source(MESSY).unwrap());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), MESSY);
+
+    let write = pluto().arg("fmt").arg(&path).output().unwrap();
+    assert!(write.status.success());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), format_source(MESSY).unwrap());
+
+    let recheck = pluto().arg("fmt").arg(&path).arg("--check").output().unwrap();
+    assert!(recheck.status.success());
+}
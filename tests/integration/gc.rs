@@ -1,5 +1,5 @@
 mod common;
-use common::compile_and_run_stdout;
+use common::{compile_and_run_stdout, pluto};
 
 #[test]
 #[ignore] // #229: needs mut enforcement fixes
@@ -383,3 +383,201 @@ fn main() {
 "#);
     assert_eq!(out.trim(), "bounded");
 }
+
+#[test]
+fn gc_collect_frees_garbage() {
+    // Stay under the initial threshold so only the explicit gc_collect() frees anything
+    let out = compile_and_run_stdout(r#"
+class Obj {
+    value: int
+}
+
+fn main() {
+    let mut i = 0
+    while i < 1000 {
+        let tmp = Obj { value: i }
+        i = i + 1
+    }
+    let before = gc_heap_size()
+    gc_collect()
+    let after = gc_heap_size()
+    if after < before {
+        print("collected")
+    } else {
+        print(f"not collected: {before} -> {after}")
+    }
+}
+"#);
+    assert_eq!(out.trim(), "collected");
+}
+
+/// Compile `source` with the CLI for the `gc` backend under `envs` and return
+/// the binary's stdout.
+fn run_with_gc(source: &str, gc: &str, envs: &[(&str, &str)]) -> String {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("main.pluto");
+    let bin = dir.path().join("main_bin");
+    std::fs::write(&src, source).unwrap();
+    let output = pluto().arg("--gc").arg(gc).arg("compile").arg(&src).arg("-o").arg(&bin)
+        .env_remove("PLUTO_GC_HEAP_MB").env_remove("PLUTO_GC_GROWTH").envs(envs.iter().copied()).output().unwrap();
+    assert!(output.status.success(), "CLI compile failed: {}", String::from_utf8_lossy(&output.stderr));
+    let run = std::process::Command::new(&bin).output().unwrap();
+    assert!(run.status.success(), "Binary exited with non-zero status");
+    String::from_utf8_lossy(&run.stdout).to_string()
+}
+
+const CHURN: &str = r#"
+class Obj {
+    value: int
+}
+
+fn main() {
+    let mut i = 0
+    while i < 100000 {
+        let tmp = Obj { value: i }
+        i = i + 1
+    }
+    if gc_heap_size() > 1048576 {
+        print("large")
+    } else {
+        print("small")
+    }
+}
+"#;
+
+#[test]
+fn gc_heap_mb_delays_collection() {
+    // 100K discarded objects never reach a 64 MB threshold, but do reach the 256 KB default
+    assert_eq!(run_with_gc(CHURN, "marksweep", &[]).trim(), "small");
+    assert_eq!(run_with_gc(CHURN, "marksweep", &[("PLUTO_GC_HEAP_MB", "64")]).trim(), "large");
+}
+
+#[test]
+fn gc_config_env_rejects_bad_values() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("main.pluto");
+    std::fs::write(&src, "fn main() {\n    print(1)\n}").unwrap();
+    for (key, val) in [("PLUTO_GC_HEAP_MB", "0"), ("PLUTO_GC_GROWTH", "0.5")] {
+        let output = pluto().arg("compile").arg(&src).arg("-o").arg(dir.path().join("bin")).env(key, val).output().unwrap();
+        assert!(!output.status.success(), "{key}={val} should be rejected");
+        assert!(String::from_utf8_lossy(&output.stderr).contains(key));
+    }
+}
+
+#[test]
+fn gc_stats_counts_allocations_and_collections() {
+    let out = compile_and_run_stdout(r#"
+class Obj {
+    value: int
+}
+
+fn main() {
+    let start = gc_stats()
+    let mut i = 0
+    while i < 1000 {
+        let tmp = Obj { value: i }
+        i = i + 1
+    }
+    gc_collect()
+    let s = gc_stats()
+    print(s.allocated_bytes - start.allocated_bytes >= 1000 * 8)
+    print(s.live_bytes > 0)
+    print(s.live_bytes < s.allocated_bytes)
+    print(s.collections > start.collections)
+    print(s.pause_ns > 0)
+}
+"#);
+    assert_eq!(out.trim(), "true\ntrue\ntrue\ntrue\ntrue");
+}
+
+#[test]
+fn gc_stats_noop_backend_never_collects() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("main.pluto");
+    std::fs::write(&src, r#"
+class Obj {
+    value: int
+}
+
+fn main() {
+    let mut i = 0
+    while i < 1000 {
+        let tmp = Obj { value: i }
+        i = i + 1
+    }
+    gc_collect()
+    let s = gc_stats()
+    print(s.allocated_bytes >= 1000 * 8)
+    print(s.live_bytes == s.allocated_bytes)
+    print(s.collections)
+    print(s.pause_ns)
+}
+"#).unwrap();
+    let output = pluto().arg("--gc").arg("noop").arg("run").arg(&src).output().unwrap();
+    assert!(output.status.success(), "run failed: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "true\ntrue\n0\n0");
+}
+
+#[test]
+fn gc_stats_flag_prints_summary_at_exit() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("main.pluto");
+    std::fs::write(&src, "fn main() {\n    gc_collect()\n    print(1)\n}").unwrap();
+
+    let output = pluto().arg("run").arg("--gc-stats").arg(&src).output().unwrap();
+    assert!(output.status.success(), "run failed: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "1");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("gc: ") && stderr.contains("1 collections"), "missing GC summary: {stderr}");
+
+    let output = pluto().arg("run").arg(&src).env_remove("PLUTO_GC_STATS").output().unwrap();
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("gc: "));
+}
+
+#[test]
+fn gc_bump_reclaims_dead_blocks() {
+    assert_eq!(run_with_gc(CHURN, "bump", &[]).trim(), "small");
+}
+
+#[test]
+fn gc_bump_keeps_reachable_objects_across_resets() {
+    // A list node survives every 100 iterations, so most blocks hold one live
+    // object among garbage, and many empty blocks are reset under the list
+    let out = run_with_gc(r#"
+class Node {
+    value: int
+    next: Node?
+}
+
+class Obj {
+    value: int
+}
+
+fn main() {
+    let mut keep: Node? = none
+    let mut junk = 0
+    let mut i = 0
+    while i < 200000 {
+        let tmp = Obj { value: i }
+        junk = junk + tmp.value % 3
+        if i % 100 == 0 {
+            keep = Node { value: i, next: keep }
+        }
+        let label = f"item {i}"
+        junk = junk + label.len() - label.len()
+        i = i + 1
+    }
+    let mut sum = 0
+    let mut n = keep
+    while n != none {
+        let node = n?
+        sum = sum + node.value
+        n = node.next
+    }
+    print(sum)
+    print(junk)
+    print(gc_stats().collections > 10)
+}
+"#, "bump", &[]);
+    assert_eq!(out.trim(), "199900000\n199999\ntrue");
+}
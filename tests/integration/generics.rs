@@ -372,6 +372,106 @@ fn main() {
     );
 }
 
+fn bound_error(source: &str) -> (String, String) {
+    match pluto::compile_to_object(source) {
+        Err(pluto::diagnostics::CompileError::Type { msg, span }) => {
+            (msg, source[span.start..span.end].to_string())
+        }
+        Err(other) => panic!("expected a type error, got: {other}"),
+        Ok(_) => panic!("expected a type error, but compilation succeeded"),
+    }
+}
+
+#[test]
+fn type_bound_violation_names_missing_method() {
+    let (msg, text) = bound_error(r#"
+trait Printable {
+    fn show(self) string
+    fn label(self) string {
+        return "item"
+    }
+}
+
+class Dog {
+    name: string
+}
+
+fn describe<T: Printable>(prefix: string, x: T) string {
+    return prefix + x.show()
+}
+
+fn main() {
+    let s = describe("pet: ", Dog { name: "rex" })
+}
+"#);
+    assert_eq!(
+        msg,
+        "type Dog does not satisfy bound 'T: Printable' required by 'describe': Dog is missing method 'show'"
+    );
+    assert_eq!(text, "Dog { name: \"rex\" }");
+}
+
+#[test]
+fn type_bound_violation_names_missing_impl() {
+    let (msg, text) = bound_error(r#"
+trait Printable {
+    fn show(self) string
+}
+
+class Dog {
+    name: string
+
+    fn show(self) string {
+        return self.name
+    }
+}
+
+fn describe<T: Printable>(x: T) string {
+    return x.show()
+}
+
+fn main() {
+    let d = Dog { name: "rex" }
+    let s = describe(d)
+}
+"#);
+    assert_eq!(
+        msg,
+        "type Dog does not satisfy bound 'T: Printable' required by 'describe': Dog does not declare 'impl Printable'"
+    );
+    assert_eq!(text, "d");
+}
+
+#[test]
+fn type_bound_satisfied_by_trait_object() {
+    let out = compile_and_run_stdout(r#"
+trait Printable {
+    fn show(self) string
+}
+
+class Dog impl Printable {
+    name: string
+
+    fn show(self) string {
+        return self.name
+    }
+}
+
+fn describe<T: Printable>(x: T) string {
+    return "<" + x.show() + ">"
+}
+
+fn via_trait(p: Printable) string {
+    return describe(p)
+}
+
+fn main() {
+    print(via_trait(Dog { name: "rex" }))
+}
+"#);
+    assert_eq!(out.trim(), "<rex>");
+}
+
 #[test]
 fn type_bound_multiple() {
     let out = compile_and_run_stdout(r#"
@@ -667,6 +767,106 @@ fn main() {
     );
 }
 
+// ── Where clauses (conditional conformance) ──────────────────────
+
+const WHERE_PRELUDE: &str = r#"
+trait Printable {
+    fn show(self) string
+}
+
+class Name impl Printable {
+    text: string
+
+    fn show(self) string {
+        return self.text
+    }
+}
+
+class Box<T> impl Printable where T: Printable {
+    value: T
+
+    fn show(self) string {
+        return "[" + self.value.show() + "]"
+    }
+
+    fn get(self) T {
+        return self.value
+    }
+}
+
+fn display(p: Printable) {
+    print(p.show())
+}
+"#;
+
+#[test]
+fn where_clause_satisfied_conforms() {
+    let out = compile_and_run_stdout(&format!("{WHERE_PRELUDE}
+fn main() {{
+    display(Box<Name> {{ value: Name {{ text: \"hi\" }} }})
+    display(Box<Box<Name>> {{ value: Box<Name> {{ value: Name {{ text: \"nested\" }} }} }})
+}}
+"));
+    assert_eq!(out, "[hi]\n[[nested]]\n");
+}
+
+#[test]
+fn where_clause_unsatisfied_still_instantiates() {
+    // Box<int> exists; it just does not implement Printable
+    let out = compile_and_run_stdout(&format!("{WHERE_PRELUDE}
+fn main() {{
+    let b = Box<int> {{ value: 3 }}
+    print(b.get())
+}}
+"));
+    assert_eq!(out, "3\n");
+}
+
+#[test]
+fn where_clause_unsatisfied_not_trait_object() {
+    compile_should_fail_with(&format!("{WHERE_PRELUDE}
+fn main() {{
+    display(Box<int> {{ value: 3 }})
+}}
+"),
+        "expected trait Printable",
+    );
+}
+
+#[test]
+fn where_clause_unsatisfied_drops_trait_methods() {
+    compile_should_fail_with(&format!("{WHERE_PRELUDE}
+fn main() {{
+    let b = Box<int> {{ value: 3 }}
+    print(b.show())
+}}
+"),
+        "has no method 'show'",
+    );
+}
+
+#[test]
+fn where_clause_unknown_type_param_rejected() {
+    compile_should_fail_with(r#"
+trait Printable {
+    fn show(self) string
+}
+
+class Box<T> impl Printable where U: Printable {
+    value: T
+
+    fn show(self) string {
+        return "box"
+    }
+}
+
+fn main() {
+}
+"#,
+        "'U' in where clause is not a type parameter of class 'Box'",
+    );
+}
+
 // ── Generic DI ─────────────────────────────────────────────────────
 
 #[test]
@@ -916,3 +1116,259 @@ fn if_expr_type_parameter_unification() {
     );
     assert_eq!(out.trim(), "42");
 }
+
+// ── Generics over function types ──────────────────────────────────────────────
+
+#[test]
+fn generic_compose_returns_closure() {
+    let out = compile_and_run_stdout(
+        r#"
+        fn compose<A, B, C>(f: fn(B) C, g: fn(A) B) fn(A) C {
+            return (x: A) => f(g(x))
+        }
+        fn main() {
+            let inc = (x: int) => x + 1
+            let double = (x: int) => x * 2
+            let h = compose(inc, double)
+            print(h(3))
+            let k = compose(double, inc)
+            print(k(3))
+        }
+        "#,
+    );
+    assert_eq!(out, "7\n8\n");
+}
+
+#[test]
+fn generic_compose_across_function_types() {
+    // Each concrete (A, B, C) gets its own instantiation
+    let out = compile_and_run_stdout(
+        r#"
+        fn compose<A, B, C>(f: fn(B) C, g: fn(A) B) fn(A) C {
+            return (x: A) => f(g(x))
+        }
+        fn main() {
+            let len = (s: string) => s.len()
+            let is_even = (n: int) => n % 2 == 0
+            let label = (b: bool) => if b { "even" } else { "odd" }
+            let even_len = compose(is_even, len)
+            let describe = compose(label, even_len)
+            print(even_len("abcd"))
+            print(describe("abc"))
+        }
+        "#,
+    );
+    assert_eq!(out, "true\nodd\n");
+}
+
+#[test]
+fn generic_apply_twice() {
+    let out = compile_and_run_stdout(
+        r#"
+        fn twice<T>(f: fn(T) T) fn(T) T {
+            return (x: T) => f(f(x))
+        }
+        fn main() {
+            let shout = (s: string) => s + "!"
+            let quad = twice(twice((n: int) => n + 1))
+            let shout_twice = twice(shout)
+            print(shout_twice("hi"))
+            print(quad(0))
+        }
+        "#,
+    );
+    assert_eq!(out, "hi!!\n4\n");
+}
+
+// ── Inference over collection element types ───────────────────────────────────
+
+#[test]
+fn generic_infers_element_type_from_collections() {
+    let out = compile_and_run_stdout(
+        r#"
+        fn first<T>(xs: [T]) T {
+            return xs[0]
+        }
+        fn count_keys<K, V>(m: Map<K, V>) int {
+            return m.keys().len()
+        }
+        fn main() {
+            print(first([3, 4]))
+            print(first(["a", "b"]))
+            print(count_keys(Map<string, int> { "x": 1, "y": 2 }))
+        }
+        "#,
+    );
+    assert_eq!(out, "3\na\n2\n");
+}
+
+#[test]
+fn generic_conflicting_type_param_rejected() {
+    compile_should_fail_with(
+        r#"
+        fn pair<T>(a: T, b: T) T {
+            return a
+        }
+        fn main() {
+            pair(1, "two")
+        }
+        "#,
+        "conflicting types for 'T', int from argument 1 but string from argument 2",
+    );
+}
+
+#[test]
+fn generic_argument_shape_mismatch_rejected() {
+    compile_should_fail_with(
+        r#"
+        fn first<T>(xs: [T]) T {
+            return xs[0]
+        }
+        fn main() {
+            first(5)
+        }
+        "#,
+        "argument 1 expected [T], found int",
+    );
+}
+
+#[test]
+fn generic_unbound_type_param_rejected() {
+    compile_should_fail_with(
+        r#"
+        fn make<T>(n: int) int {
+            return n
+        }
+        fn main() {
+            make(1)
+        }
+        "#,
+        "no argument determines 'T'",
+    );
+}
+
+// ── Generic Methods ──────────────────────────────────────────────
+
+#[test]
+fn generic_method_on_non_generic_class() {
+    let out = compile_and_run_stdout(
+        r#"
+        class Logger {
+            prefix: string
+
+            fn log<T>(self, value: T) T {
+                print(self.prefix)
+                return value
+            }
+        }
+        fn main() {
+            let l = Logger { prefix: "log:" }
+            print(l.log(42))
+            print(l.log("hello"))
+        }
+        "#,
+    );
+    assert_eq!(out, "log:\n42\nlog:\nhello\n");
+}
+
+#[test]
+fn generic_method_calls_generic_method() {
+    let out = compile_and_run_stdout(
+        r#"
+        class Pairer {
+            fn keep<T>(self, v: T) T {
+                return v
+            }
+            fn first<A, B>(self, a: A, b: B) A {
+                return self.keep(a)
+            }
+        }
+        fn main() {
+            let p = Pairer {}
+            print(p.first(3, "x"))
+            print(p.first("y", 1.5))
+        }
+        "#,
+    );
+    assert_eq!(out, "3\ny\n");
+}
+
+#[test]
+fn generic_method_mut_self() {
+    let out = compile_and_run_stdout(
+        r#"
+        class Counter {
+            n: int
+            fn add<T>(mut self, v: T) {
+                self.n = self.n + 1
+            }
+        }
+        fn main() {
+            let mut c = Counter { n: 0 }
+            c.add(1)
+            c.add("two")
+            c.add(3.0)
+            print(c.n)
+        }
+        "#,
+    );
+    assert_eq!(out, "3\n");
+}
+
+#[test]
+fn generic_method_on_enum() {
+    let out = compile_and_run_stdout(
+        r#"
+        enum Shape {
+            Dot
+            Line(int)
+
+            fn tag<T>(self, v: T) [T] {
+                return [v]
+            }
+        }
+        fn main() {
+            print(Shape.Dot.tag(7)[0])
+            print(Shape.Line(1).tag("e")[0])
+        }
+        "#,
+    );
+    assert_eq!(out, "7\ne\n");
+}
+
+#[test]
+fn generic_method_bound_enforced() {
+    compile_should_fail_with(
+        r#"
+        trait Show {
+            fn show(self) string
+        }
+        class Printer {
+            fn put<T: Show>(self, v: T) {
+                print(v.show())
+            }
+        }
+        fn main() {
+            let p = Printer {}
+            p.put(3)
+        }
+        "#,
+        "type int does not satisfy bound 'T: Show' required by 'put'",
+    );
+}
+
+#[test]
+fn generic_method_on_generic_class_rejected() {
+    compile_should_fail_with(
+        r#"
+        class Box<U> {
+            v: U
+            fn log<T>(self, value: T) {
+            }
+        }
+        fn main() {
+        }
+        "#,
+        "method 'log' of generic class 'Box' cannot declare type parameters",
+    );
+}
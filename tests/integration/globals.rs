@@ -0,0 +1,160 @@
+mod common;
+use common::{compile_and_run_stdout, compile_should_fail_with};
+
+// ── Positive tests ─────────────────────────────────────────────────────
+
+#[test]
+fn global_counter_shared_between_functions() {
+    let out = compile_and_run_stdout(r#"
+global mut counter: int = 0
+
+fn bump() {
+    counter = counter + 1
+}
+
+fn main() {
+    bump()
+    bump()
+    counter += 10
+    print(counter)
+}
+"#);
+    assert_eq!(out, "12\n");
+}
+
+#[test]
+fn globals_of_each_type_with_const_initializers() {
+    let out = compile_and_run_stdout(r#"
+const BASE = 40
+
+global mut total = BASE + 2
+global mut ratio: float = 0.25
+global mut enabled = false
+
+fn main() {
+    enabled = !enabled
+    ratio = ratio * 2.0
+    print(total)
+    print(ratio)
+    print(enabled)
+}
+"#);
+    assert_eq!(out, "42\n0.5\ntrue\n");
+}
+
+#[test]
+fn global_read_and_written_from_closure_and_task() {
+    let out = compile_and_run_stdout(r#"
+global mut hits = 0
+
+fn record() int {
+    hits = hits + 1
+    return hits
+}
+
+fn main() {
+    let add = (n: int) => {
+        hits = hits + n
+    }
+    add(5)
+    let t = spawn record()
+    print(t.get())
+    print(hits)
+}
+"#);
+    assert_eq!(out, "6\n6\n");
+}
+
+#[test]
+fn local_variable_shadows_global() {
+    let out = compile_and_run_stdout(r#"
+global mut n = 1
+
+fn main() {
+    let mut n = 100
+    n = n + 1
+    print(n)
+    show()
+}
+
+fn show() {
+    print(n)
+}
+"#);
+    assert_eq!(out, "101\n1\n");
+}
+
+// ── Negative tests ─────────────────────────────────────────────────────
+
+#[test]
+fn global_requires_mut() {
+    compile_should_fail_with(
+        "global counter = 0\n\nfn main() {\n}\n",
+        "globals must be declared 'global mut'",
+    );
+}
+
+#[test]
+fn global_initializer_must_be_constant() {
+    compile_should_fail_with(r#"
+fn start() int {
+    return 1
+}
+
+global mut counter = start()
+
+fn main() {
+}
+"#, "initializer of global 'counter' is not a compile-time constant");
+}
+
+#[test]
+fn global_must_be_scalar() {
+    compile_should_fail_with(r#"
+global mut name = "pluto"
+
+fn main() {
+}
+"#, "global 'name' must be an int, float or bool");
+}
+
+#[test]
+fn global_assignment_type_mismatch() {
+    compile_should_fail_with(r#"
+global mut counter = 0
+
+fn main() {
+    counter = "many"
+}
+"#, "type mismatch in assignment: expected int, found string");
+}
+
+#[test]
+fn global_declared_type_mismatch() {
+    compile_should_fail_with(r#"
+global mut counter: float = 0
+
+fn main() {
+}
+"#, "global 'counter' is declared as float but its value is int");
+}
+
+#[test]
+fn global_name_clashes_with_const() {
+    compile_should_fail_with(r#"
+const LIMIT = 1
+global mut LIMIT = 2
+
+fn main() {
+}
+"#, "global 'LIMIT' is already declared");
+}
+
+#[test]
+fn undeclared_global_is_undefined() {
+    compile_should_fail_with(r#"
+fn main() {
+    counter = 1
+}
+"#, "undefined variable 'counter'");
+}
@@ -0,0 +1,125 @@
+//! Integration tests for incremental type checking (`analyze_file_incremental`).
+
+use std::path::{Path, PathBuf};
+
+use pluto::incremental::AnalysisCache;
+use tempfile::TempDir;
+
+const MODULES: usize = 19;
+
+fn module_source(i: usize, body: &str) -> String {
+    format!("pub fn f{i}(n: int) int {{\n    {body}\n}}\n\nfn helper{i}() int {{\n    return {i}\n}}\n")
+}
+
+/// A 20-file project: `main.pt` and 19 imported modules of two functions each.
+fn project() -> (TempDir, PathBuf) {
+    let dir = tempfile::tempdir().unwrap();
+    let mut main = String::new();
+    for i in 0..MODULES {
+        main.push_str(&format!("import m{i}\n"));
+        write(&dir.path().join(format!("m{i}.pt")), &module_source(i, &format!("return n + helper{i}()")));
+    }
+    main.push_str("\nfn main() {\n    let mut total = 0\n");
+    for i in 0..MODULES {
+        main.push_str(&format!("    total = total + m{i}.f{i}({i})\n"));
+    }
+    main.push_str("    print(total)\n}\n");
+    let entry = dir.path().join("main.pt");
+    write(&entry, &main);
+    (dir, entry)
+}
+
+fn write(path: &Path, text: &str) {
+    std::fs::write(path, text).unwrap();
+}
+
+fn analyze(entry: &Path, cache: &mut AnalysisCache) -> Result<(), pluto::diagnostics::CompileError> {
+    pluto::analyze_file_incremental(entry, None, cache).map(|_| ())
+}
+
+#[test]
+fn unchanged_project_reuses_every_body_check() {
+    let (_dir, entry) = project();
+    let mut cache = AnalysisCache::new();
+    analyze(&entry, &mut cache).unwrap();
+    let total = cache.last_checked();
+    assert!(total > 2 * MODULES, "checked {total}");
+    assert_eq!(cache.last_reused(), 0);
+
+    analyze(&entry, &mut cache).unwrap();
+    assert_eq!(cache.last_checked(), 0);
+    assert_eq!(cache.last_reused(), total);
+}
+
+#[test]
+fn editing_a_body_rechecks_only_that_file() {
+    let (dir, entry) = project();
+    let mut cache = AnalysisCache::new();
+    analyze(&entry, &mut cache).unwrap();
+    let total = cache.last_checked();
+
+    write(&dir.path().join("m7.pt"), &module_source(7, "return n * helper7()"));
+    analyze(&entry, &mut cache).unwrap();
+    assert_eq!(cache.last_checked(), 2);
+    assert_eq!(cache.last_reused(), total - 2);
+}
+
+#[test]
+fn signature_change_rechecks_dependents() {
+    let (dir, entry) = project();
+    let mut cache = AnalysisCache::new();
+    analyze(&entry, &mut cache).unwrap();
+
+    // main.pt is unchanged, but its call to m7.f7 no longer type-checks
+    let m7 = dir.path().join("m7.pt");
+    write(&m7, "pub fn f7(n: int) string {\n    return \"seven\"\n}\n");
+    let err = analyze(&entry, &mut cache).unwrap_err();
+    assert!(err.to_string().contains("string"), "{err}");
+
+    write(&m7, &module_source(7, "return n + helper7()"));
+    analyze(&entry, &mut cache).unwrap();
+}
+
+#[test]
+fn incremental_warnings_match_a_full_analysis() {
+    let (dir, entry) = project();
+    let mut cache = AnalysisCache::new();
+    analyze(&entry, &mut cache).unwrap();
+
+    write(&dir.path().join("m3.pt"), &module_source(3, "let unused = 1\n    return n"));
+    let main = std::fs::read_to_string(&entry).unwrap().replace("print(total)", "let spare = 2\n    print(total)");
+    write(&entry, &main);
+    let (_, _, _, incremental) = pluto::analyze_file_incremental(&entry, None, &mut cache).unwrap();
+    let (_, _, _, full) = pluto::analyze_file_with_warnings(&entry, None).unwrap();
+    let messages = |ws: &[pluto::diagnostics::CompileWarning]| ws.iter().map(|w| w.msg.clone()).collect::<Vec<_>>();
+    assert_eq!(messages(&incremental), messages(&full));
+    assert!(messages(&full).iter().any(|m| m.contains("spare")));
+}
+
+#[test]
+fn editing_session_reuses_body_checks() {
+    let (dir, entry) = project();
+    let mut cache = AnalysisCache::new();
+    pluto::parse_file_for_editing_incremental(&entry, None, &mut cache).unwrap();
+
+    write(&dir.path().join("m0.pt"), &module_source(0, "return n - helper0()"));
+    pluto::parse_file_for_editing_incremental(&entry, None, &mut cache).unwrap();
+    assert_eq!(cache.last_checked(), 2);
+}
+
+#[test]
+fn replayed_bodies_keep_their_generic_instances() {
+    let dir = tempfile::tempdir().unwrap();
+    let entry = dir.path().join("main.pt");
+    write(&dir.path().join("boxes.pt"), "pub class Box<T> {\n    value: T\n}\n\npub fn wrap<T>(value: T) Box<T> {\n    return Box<T> { value: value }\n}\n");
+    write(&dir.path().join("util.pt"), "pub fn one() int {\n    return 1\n}\n");
+    write(&entry, "import boxes\nimport util\n\nfn main() {\n    let b = boxes.wrap(util.one())\n    print(b.value)\n}\n");
+    let mut cache = AnalysisCache::new();
+    analyze(&entry, &mut cache).unwrap();
+
+    write(&dir.path().join("util.pt"), "pub fn one() int {\n    return 2 - 1\n}\n");
+    let (program, _, _, _) = pluto::analyze_file_incremental(&entry, None, &mut cache).unwrap();
+    assert!(program.classes.iter().any(|c| c.node.name.node.starts_with("boxes.Box$$")));
+    assert_eq!(cache.last_checked(), 1);
+    assert!(cache.last_reused() > 0);
+}
@@ -0,0 +1,230 @@
+mod common;
+use common::{compile_and_run_stdout, compile_should_fail_with};
+
+// ── Positive tests ─────────────────────────────────────────────────────
+
+#[test]
+fn int_match_with_wildcard() {
+    let out = compile_and_run_stdout(r#"
+fn describe(n: int) string {
+    match n {
+        0 {
+            return "zero"
+        }
+        1 | 2 | 3 {
+            return "small"
+        }
+        _ {
+            return "other"
+        }
+    }
+}
+
+fn main() {
+    print(describe(0))
+    print(describe(2))
+    print(describe(-7))
+    print(describe(40))
+}
+"#);
+    assert_eq!(out, "zero\nsmall\nother\nother\n");
+}
+
+#[test]
+fn int_match_ranges_cover_all_ints_without_wildcard() {
+    let out = compile_and_run_stdout(r#"
+fn main() {
+    let values = [-5, 0, 8, 9, 1000]
+    for v in values {
+        match v {
+            0..9 {
+                print("digit")
+            }
+            9.. {
+                print("big")
+            }
+            ..0 {
+                print("negative")
+            }
+        }
+    }
+}
+"#);
+    assert_eq!(out, "negative\ndigit\ndigit\nbig\nbig\n");
+}
+
+#[test]
+fn int_match_first_matching_arm_wins() {
+    let out = compile_and_run_stdout(r#"
+fn main() {
+    let n = 5
+    match n {
+        5 {
+            print("five")
+        }
+        0..=10 {
+            print("range")
+        }
+        _ {
+            print("other")
+        }
+    }
+}
+"#);
+    assert_eq!(out, "five\n");
+}
+
+#[test]
+fn int_match_bounds_may_be_consts() {
+    let out = compile_and_run_stdout(r#"
+const LOW = 10
+const HIGH = 20
+
+fn bucket(n: int) int {
+    match n {
+        ..LOW {
+            return 0
+        }
+        LOW..HIGH {
+            return 1
+        }
+        HIGH.. {
+            return 2
+        }
+    }
+}
+
+fn main() {
+    print(bucket(3))
+    print(bucket(10))
+    print(bucket(19))
+    print(bucket(20))
+}
+"#);
+    assert_eq!(out, "0\n1\n1\n2\n");
+}
+
+#[test]
+fn int_match_extreme_bounds() {
+    let out = compile_and_run_stdout(r#"
+fn main() {
+    let n = -9223372036854775808
+    match n {
+        -9223372036854775808 {
+            print("min")
+        }
+        -9223372036854775807..=9223372036854775807 {
+            print("rest")
+        }
+    }
+}
+"#);
+    assert_eq!(out, "min\n");
+}
+
+// ── Negative tests ─────────────────────────────────────────────────────
+
+#[test]
+fn int_match_missing_case_without_wildcard() {
+    compile_should_fail_with(r#"
+fn main() {
+    let n = 1
+    match n {
+        0 {
+            print("zero")
+        }
+        1 {
+            print("one")
+        }
+    }
+}
+"#, "non-exhaustive match: int values ..=-1 are not covered");
+}
+
+#[test]
+fn int_match_gap_between_ranges() {
+    compile_should_fail_with(r#"
+fn main() {
+    let n = 1
+    match n {
+        ..0 {
+            print("negative")
+        }
+        0..5 | 6..10 {
+            print("small")
+        }
+        10.. {
+            print("big")
+        }
+    }
+}
+"#, "non-exhaustive match: int values 5 are not covered");
+}
+
+#[test]
+fn int_match_empty_range() {
+    compile_should_fail_with(r#"
+fn main() {
+    match 1 {
+        5..5 {
+            print("never")
+        }
+        _ {
+            print("other")
+        }
+    }
+}
+"#, "range pattern matches no values");
+}
+
+#[test]
+fn int_match_requires_int_scrutinee() {
+    compile_should_fail_with(r#"
+fn main() {
+    match "a" {
+        0 {
+            print("zero")
+        }
+        _ {
+            print("other")
+        }
+    }
+}
+"#, "int patterns require an int value, found string");
+}
+
+#[test]
+fn int_match_bound_must_be_const() {
+    compile_should_fail_with(r#"
+fn main() {
+    let limit = 3
+    match 1 {
+        limit {
+            print("limit")
+        }
+        _ {
+            print("other")
+        }
+    }
+}
+"#, "int pattern bounds must be int literals or int consts");
+}
+
+#[test]
+fn int_match_bound_const_shadowed_by_local_is_rejected() {
+    compile_should_fail_with(r#"
+const LIMIT = 3
+
+fn main() {
+    let LIMIT = 4
+    match 1 {
+        LIMIT {
+            print("limit")
+        }
+        _ {
+            print("other")
+        }
+    }
+}
+"#, "int pattern bounds must be int literals or int consts");
+}
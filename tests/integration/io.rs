@@ -152,3 +152,103 @@ fn main() {
     );
     assert_eq!(out, "hello\n");
 }
+
+// ============================================================
+// read_line builtin echoes stdin line by line until EOF
+// ============================================================
+
+#[test]
+fn builtin_read_line_echo_until_eof() {
+    let out = run_project_with_stdin(
+        &[(
+            "main.pluto",
+            r#"fn main() int? {
+    while true {
+        let line = read_line()!?
+        print(f"> {line}")
+    }
+    return 0
+}
+"#,
+        )],
+        "one\ntwo\r\n\nthree",
+    );
+    assert_eq!(out, "> one\n> two\n> \n> three\n");
+}
+
+// ============================================================
+// read_line builtin returns none on empty stdin
+// ============================================================
+
+#[test]
+fn builtin_read_line_none_at_eof() {
+    let out = run_project_with_stdin(
+        &[(
+            "main.pluto",
+            r#"fn first_line() string? {
+    let line = read_line()!?
+    return line
+}
+
+fn main() {
+    let line = first_line() catch none
+    if line == none {
+        print("eof")
+    }
+}
+"#,
+        )],
+        "",
+    );
+    assert_eq!(out, "eof\n");
+}
+
+// ============================================================
+// read_all builtin returns the rest of stdin
+// ============================================================
+
+#[test]
+fn builtin_read_all_after_read_line() {
+    let out = run_project_with_stdin(
+        &[(
+            "main.pluto",
+            r#"fn main() int? {
+    let header = read_line()!?
+    let rest = read_all() catch err { "" }
+    print(header)
+    print(rest.len())
+    print(rest)
+    return 0
+}
+"#,
+        )],
+        "header\nbody 1\nbody 2\n",
+    );
+    assert_eq!(out, "header\n14\nbody 1\nbody 2\n\n");
+}
+
+// ============================================================
+// stdin builtins are fallible and must be handled
+// ============================================================
+
+#[test]
+fn builtin_read_line_must_be_handled() {
+    common::compile_should_fail_with(
+        r#"fn main() {
+    let line = read_line()
+}
+"#,
+        "call to fallible function 'read_line' must be handled with ! or catch",
+    );
+}
+
+#[test]
+fn builtin_read_all_wrong_arg_count() {
+    common::compile_should_fail_with(
+        r#"fn main() {
+    let text = read_all(1)!
+}
+"#,
+        "read_all() expects 0 arguments, got 1",
+    );
+}
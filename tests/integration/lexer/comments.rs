@@ -2,10 +2,11 @@
 //
 // Tests comment handling:
 // - Line comments
-// - Block comments (if supported)
+// - Block comments (nested)
 // - Edge cases
 
 use super::*;
+use pluto::diagnostics::CompileError;
 
 // ===== Line Comments =====
 
@@ -78,40 +79,40 @@ fn comment_entire_line() {
     assert!(tokens.iter().any(|(t, _)| matches!(t, Token::Let)));
 }
 
-// ===== Block Comments (Not Supported) =====
+// ===== Block Comments =====
 
 #[test]
-fn comment_block_not_supported() {
-    // /* ... */ style comments are not in current lexer
+fn comment_block_skipped() {
     let src = "let x = /* comment */ 1";
-    let result = lex(src);
-    // Will lex as: let x = / * comment * / 1
-    // May fail or produce unexpected tokens
-    if result.is_ok() {
-        let tokens = result.unwrap();
-        // Bug: block comments not supported
-        // Will be lexed as individual tokens
-        assert!(tokens.len() > 4, "Bug: block comments not supported, lexed as tokens");
-    }
+    assert_tokens(src, &[Token::Let, Token::Ident, Token::Eq, Token::IntLit(1)]);
 }
 
 #[test]
-fn comment_nested_block_not_supported() {
-    // /* outer /* inner */ */ - not supported
-    let src = "let x = /* outer /* inner */ */ 1";
-    let result = lex(src);
-    if result.is_ok() {
-        // Bug: nested block comments not supported
-    }
+fn comment_nested_block_skipped() {
+    let src = "let x = /* outer /* inner */ still comment */ 1";
+    assert_tokens(src, &[Token::Let, Token::Ident, Token::Eq, Token::IntLit(1)]);
 }
 
 #[test]
-fn comment_unterminated_block_not_supported() {
-    // /* comment without closing
+fn comment_unterminated_block_errors_at_opener() {
     let src = "let x = /* comment";
-    let result = lex(src);
-    // Should fail if block comments were supported
-    // Currently will lex as separate tokens
+    match lex(src) {
+        Err(CompileError::Syntax { msg, span }) => {
+            assert_eq!(msg, "unterminated block comment");
+            assert_eq!((span.start, span.end), (8, 10));
+        }
+        other => panic!("expected unterminated block comment error, got {other:?}"),
+    }
+}
+
+#[test]
+fn comment_unterminated_nested_block_errors_at_outer_opener() {
+    // The inner comment closes, the outer one never does
+    let src = "/* outer /* inner */ never closed";
+    match lex(src) {
+        Err(CompileError::Syntax { span, .. }) => assert_eq!((span.start, span.end), (0, 2)),
+        other => panic!("expected unterminated block comment error, got {other:?}"),
+    }
 }
 
 // ===== Comment-like Strings =====
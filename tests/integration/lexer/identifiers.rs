@@ -96,7 +96,7 @@ fn identifier_starting_with_number() {
     let tokens = lex_ok(src);
     assert_eq!(tokens.len(), 2);
     assert!(matches!(&tokens[0].0, Token::IntLit(1)));
-    assert!(matches!(&tokens[1].0, Token::Ident));
+    assert!(matches!(tokens[1].0, Token::Ident));
 }
 
 #[test]
@@ -119,9 +119,10 @@ fn identifier_dollar_sign_not_allowed() {
 
 #[test]
 fn identifier_at_sign_not_allowed() {
-    let src = "@foo";
-    // @ is not a valid token
-    lex_fails(src);
+    // @ is its own token, so `@foo` is an attribute, not an identifier
+    let tokens = lex_ok("@foo");
+    assert!(matches!(tokens[0].0, Token::At));
+    assert!(matches!(tokens[1].0, Token::Ident));
 }
 
 // ===== Reserved Keywords =====
@@ -150,7 +151,7 @@ fn keyword_all_reserved_words() {
     // Test that all keywords in is_keyword() are actually keywords
     let keywords = vec![
         "fn", "let", "mut", "return", "if", "else", "while", "true", "false",
-        "class", "trait", "app", "inject", "error", "raise", "catch", "spawn",
+        "class", "trait", "app", "inject", "error", "raise", "catch", "try", "finally", "is", "spawn",
         "enum", "impl", "self", "pub", "for", "in", "break", "continue",
         "match", "import", "as", "extern", "uses", "ambient", "tests", "test",
         "invariant", "requires", "assert", "select", "default",
@@ -140,14 +140,46 @@ fn integer_binary_trailing_underscore() {
 }
 
 #[test]
-fn integer_octal_not_supported() {
-    // Octal literals (0o777) are not in current lexer
-    let src = "0o777";
-    let result = lex(src);
-    if result.is_ok() {
-        let tokens = result.unwrap();
-        assert!(tokens.len() >= 2, "Bug: octal literals not supported");
-    }
+fn integer_octal_basic() {
+    assert_tokens("0o777", &[Token::IntLit(0o777)]);
+}
+
+#[test]
+fn integer_octal_uppercase_prefix() {
+    assert_tokens("0O17", &[Token::IntLit(15)]);
+}
+
+#[test]
+fn integer_octal_with_underscores() {
+    assert_tokens("0o7_5_5", &[Token::IntLit(493)]);
+}
+
+#[test]
+fn integer_octal_empty() {
+    lex_fails("0o");
+}
+
+#[test]
+fn integer_octal_invalid_digit() {
+    lex_fails("0o18");
+}
+
+#[test]
+fn integer_octal_leading_underscore() {
+    lex_fails("0o_17");
+}
+
+#[test]
+fn integer_octal_trailing_underscore() {
+    lex_fails("0o17_");
+}
+
+#[test]
+fn integer_radix_overflow() {
+    // Each radix reports out-of-range values instead of wrapping
+    lex_fails("0x1_0000_0000_0000_0000");
+    lex_fails("0b1_0000000000000000000000000000000000000000000000000000000000000000");
+    lex_fails("0o2_000_000_000_000_000_000_000");
 }
 
 #[test]
@@ -162,10 +162,12 @@ fn operator_slash_star_vs_division_and_multiply() {
 
 #[test]
 fn operator_slash_star_no_space() {
-    let tokens = lex_ok("/*");
+    // `/*` opens a block comment; `*/` is still two operators
+    lex_fails("/*");
+    let tokens = lex_ok("*/");
     assert_eq!(tokens.len(), 2);
-    assert!(matches!(&tokens[0].0, Token::Slash));
-    assert!(matches!(&tokens[1].0, Token::Star));
+    assert!(matches!(&tokens[0].0, Token::Star));
+    assert!(matches!(&tokens[1].0, Token::Slash));
 }
 
 #[test]
@@ -133,7 +133,6 @@ fn binary_literal_with_underscores() {
 }
 
 #[test]
-#[ignore] // Feature not implemented: octal literals (0o prefix) in lexer
 fn octal_literal() {
     // 0o755 = 493
     let stdout = compile_and_run_stdout(r#"
@@ -145,6 +144,47 @@ fn octal_literal() {
     assert_eq!(stdout.trim(), "493");
 }
 
+#[test]
+fn octal_literal_with_underscores() {
+    let stdout = compile_and_run_stdout(r#"
+        fn main() {
+            let x = 0o7_5_5
+            print(x)
+        }
+    "#);
+    assert_eq!(stdout.trim(), "493");
+}
+
+#[test]
+fn radix_literals_compare_equal_to_decimal() {
+    let stdout = compile_and_run_stdout(r#"
+        fn main() {
+            print(0xFF == 255)
+            print(1_000 == 1000)
+            print(0b1010 + 0o17 + 0x1F)
+        }
+    "#);
+    assert_eq!(stdout.trim(), "true\ntrue\n56");
+}
+
+#[test]
+fn octal_literal_invalid_digit() {
+    compile_should_fail_with(r#"
+        fn main() {
+            let x = 0o19
+        }
+    "#, "invalid digit in octal literal");
+}
+
+#[test]
+fn integer_literal_overflow_is_an_error() {
+    compile_should_fail_with(r#"
+        fn main() {
+            let x = 0xFFFF_FFFF_FFFF_FFFF_F
+        }
+    "#, "integer literal out of range");
+}
+
 #[test]
 fn underscore_separator_in_integer() {
     // 1_000_000 = 1000000
@@ -192,6 +192,23 @@ fn dep_with_internal_imports() {
     assert_eq!(out, "7\n");
 }
 
+#[test]
+fn pub_module_visible_within_package() {
+    // `pub(module)` items are shared between modules of the same package,
+    // including the dep's own internal modules
+    let out = run_manifest_project(
+        &[
+            ("main.pluto", "import mylib\nimport helpers\n\nfn main() {\n    print(helpers.double(mylib.compute(3, 4)))\n}"),
+            ("helpers/double.pluto", "pub(module) fn double(x: int) int {\n    return x * 2\n}"),
+        ],
+        &[("mylib", "deps/mylib", &[
+            ("compute.pluto", "import internal\n\npub fn compute(a: int, b: int) int {\n    return internal.add(a, b)\n}"),
+            ("internal/add.pluto", "pub(module) fn add(a: int, b: int) int {\n    return a + b\n}"),
+        ])],
+    );
+    assert_eq!(out, "14\n");
+}
+
 #[test]
 fn transitive_package_deps() {
     // Root depends on A, A depends on B (via its own pluto.toml)
@@ -352,6 +369,20 @@ fn same_dep_imported_in_two_branches() {
 // Error cases
 // ============================================================
 
+#[test]
+fn pub_module_hidden_from_dependent_package() {
+    let err = compile_manifest_should_fail(
+        &[("main.pluto", "import mylib\n\nfn main() {\n    print(mylib.helper())\n}")],
+        &[("mylib", "deps/mylib", &[
+            ("lib.pluto", "pub(module) fn helper() int {\n    return 1\n}\n\npub fn api() int {\n    return helper()\n}"),
+        ])],
+    );
+    assert!(
+        err.contains("'helper' is pub(module) in package 'mylib'"),
+        "Expected pub(module) visibility error, got: {}", err
+    );
+}
+
 #[test]
 fn transitive_dep_not_visible_to_root() {
     // Root depends on A, A depends on B. Root tries to import B directly — should fail.
@@ -909,6 +940,56 @@ fn git_dep_with_tag() {
     assert_eq!(out, "42\n");
 }
 
+/// Commit `val()` returning `value` to the dep repo and tag the commit.
+fn commit_tagged_val(dep_dir: &std::path::Path, value: i64, tag: &str) {
+    std::fs::write(dep_dir.join("val.pluto"), format!("pub fn val() int {{\n    return {value}\n}}")).unwrap();
+    git_cmd(dep_dir, &["add", "."]);
+    git_cmd(dep_dir, &["commit", "-m", tag]);
+    git_cmd(dep_dir, &["tag", tag]);
+}
+
+#[test]
+fn git_dep_with_version_picks_highest_match() {
+    let cache = tempfile::tempdir().unwrap();
+    let (dep_dir, dep_url) = create_git_dep(&[
+        ("val.pluto", "pub fn val() int {\n    return 0\n}"),
+    ]);
+    commit_tagged_val(dep_dir.path(), 100, "v1.0.0");
+    commit_tagged_val(dep_dir.path(), 120, "v1.2.0");
+    commit_tagged_val(dep_dir.path(), 200, "v2.0.0");
+
+    let toml = format!(
+        "[package]\nname = \"test\"\n\n[dependencies]\nmylib = {{ git = \"{}\", version = \"^1.0\" }}\n",
+        dep_url
+    );
+    let out = run_git_dep_project(
+        &toml,
+        &[("main.pluto", "import mylib\n\nfn main() {\n    print(mylib.val())\n}")],
+        cache.path(),
+    );
+    assert_eq!(out, "120\n");
+}
+
+#[test]
+fn git_dep_version_without_matching_tag() {
+    let cache = tempfile::tempdir().unwrap();
+    let (dep_dir, dep_url) = create_git_dep(&[
+        ("val.pluto", "pub fn val() int {\n    return 0\n}"),
+    ]);
+    commit_tagged_val(dep_dir.path(), 100, "v1.0.0");
+
+    let toml = format!(
+        "[package]\nname = \"test\"\n\n[dependencies]\nmylib = {{ git = \"{}\", version = \"^2\" }}\n",
+        dep_url
+    );
+    let err = compile_git_dep_should_fail(
+        &toml,
+        &[("main.pluto", "import mylib\n\nfn main() {\n    print(mylib.val())\n}")],
+        cache.path(),
+    );
+    assert!(err.contains("satisfies version '^2'"), "Expected no matching tag error, got: {}", err);
+}
+
 #[test]
 fn git_dep_with_rev() {
     let cache = tempfile::tempdir().unwrap();
@@ -1101,6 +1182,17 @@ fn git_dep_multiple_refs() {
     assert!(err.contains("specify at most one of 'rev', 'tag', 'branch'"), "Expected multiple refs error, got: {}", err);
 }
 
+#[test]
+fn git_dep_invalid_version() {
+    let cache = tempfile::tempdir().unwrap();
+    let err = compile_git_dep_should_fail(
+        "[package]\nname = \"test\"\n\n[dependencies]\nmylib = { git = \"file:///foo\", version = \"one point two\" }\n",
+        &[("main.pluto", "fn main() {\n    print(1)\n}")],
+        cache.path(),
+    );
+    assert!(err.contains("invalid version requirement 'one point two'"), "Expected invalid version error, got: {}", err);
+}
+
 #[test]
 fn git_dep_ref_with_path() {
     let cache = tempfile::tempdir().unwrap();
@@ -1126,3 +1218,246 @@ fn git_dep_neither() {
     );
     assert!(err.contains("must specify 'path' or 'git'"), "Expected neither error, got: {}", err);
 }
+
+// ============================================================
+// Lockfile (pluto.lock)
+// ============================================================
+
+const VAL_MAIN: &str = "import mylib\n\nfn main() {\n    print(mylib.val())\n}";
+
+/// Point the project's pluto.toml at `mylib` in `dep_url`, with `extra` dep keys.
+fn write_git_dep_manifest(project: &std::path::Path, dep_url: &str, extra: &str) {
+    std::fs::write(
+        project.join("pluto.toml"),
+        format!("[package]\nname = \"test\"\n\n[dependencies]\nmylib = {{ git = \"{dep_url}\"{extra} }}\n"),
+    ).unwrap();
+}
+
+/// Write a project whose main prints `mylib.val()` from the git dep at `dep_url`.
+fn git_dep_project(dep_url: &str) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    write_git_dep_manifest(dir.path(), dep_url, "");
+    std::fs::write(dir.path().join("main.pluto"), VAL_MAIN).unwrap();
+    dir
+}
+
+/// Compile and run the project in `project` against `cache_dir`, returning stdout.
+fn build_in(project: &std::path::Path, cache_dir: &std::path::Path) -> String {
+    let bin_path = project.join("test_bin");
+    unsafe { std::env::set_var("PLUTO_CACHE_DIR", cache_dir); }
+    let result = pluto::compile_file(&project.join("main.pluto"), &bin_path);
+    unsafe { std::env::remove_var("PLUTO_CACHE_DIR"); }
+    result.unwrap_or_else(|e| panic!("Compilation failed: {e}"));
+
+    let output = Command::new(&bin_path).output().unwrap();
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn read_lock(project: &std::path::Path) -> String {
+    std::fs::read_to_string(project.join("pluto.lock")).unwrap()
+}
+
+#[test]
+fn lockfile_records_resolved_commit() {
+    let cache = tempfile::tempdir().unwrap();
+    let (dep_dir, dep_url) = create_git_dep(&[("val.pluto", "pub fn val() int {\n    return 1\n}")]);
+    let project = git_dep_project(&dep_url);
+
+    assert_eq!(build_in(project.path(), cache.path()), "1\n");
+    let lock = read_lock(project.path());
+    assert!(lock.contains(&format!("url = \"{dep_url}\"")), "{lock}");
+    assert!(lock.contains("reference = \"default branch\""), "{lock}");
+    assert!(lock.contains(&format!("commit = \"{}\"", git_head_sha(dep_dir.path()))), "{lock}");
+}
+
+#[test]
+fn lockfile_not_written_without_git_deps() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("pluto.toml"), "[package]\nname = \"test\"\n").unwrap();
+    std::fs::write(dir.path().join("main.pluto"), "fn main() {\n    print(1)\n}").unwrap();
+    let cache = tempfile::tempdir().unwrap();
+    assert_eq!(build_in(dir.path(), cache.path()), "1\n");
+    assert!(!dir.path().join("pluto.lock").exists());
+}
+
+#[test]
+fn lockfile_builds_resolve_identical_deps() {
+    let (dep_dir, dep_url) = create_git_dep(&[("val.pluto", "pub fn val() int {\n    return 1\n}")]);
+    let project = git_dep_project(&dep_url);
+    let first_cache = tempfile::tempdir().unwrap();
+    assert_eq!(build_in(project.path(), first_cache.path()), "1\n");
+    let lock = read_lock(project.path());
+
+    // The dep moves on; a build with a fresh cache still uses the locked commit
+    std::fs::write(dep_dir.path().join("val.pluto"), "pub fn val() int {\n    return 2\n}").unwrap();
+    git_cmd(dep_dir.path(), &["add", "."]);
+    git_cmd(dep_dir.path(), &["commit", "-m", "newer"]);
+
+    let second_cache = tempfile::tempdir().unwrap();
+    assert_eq!(build_in(project.path(), second_cache.path()), "1\n");
+    assert_eq!(read_lock(project.path()), lock);
+}
+
+#[test]
+fn lockfile_entry_replaced_when_ref_changes() {
+    let cache = tempfile::tempdir().unwrap();
+    let (dep_dir, dep_url) = create_git_dep(&[("val.pluto", "pub fn val() int {\n    return 1\n}")]);
+    git_cmd(dep_dir.path(), &["tag", "v1"]);
+    std::fs::write(dep_dir.path().join("val.pluto"), "pub fn val() int {\n    return 2\n}").unwrap();
+    git_cmd(dep_dir.path(), &["add", "."]);
+    git_cmd(dep_dir.path(), &["commit", "-m", "newer"]);
+
+    let project = git_dep_project(&dep_url);
+    assert_eq!(build_in(project.path(), cache.path()), "2\n");
+
+    write_git_dep_manifest(project.path(), &dep_url, ", tag = \"v1\"");
+    assert_eq!(build_in(project.path(), cache.path()), "1\n");
+    let lock = read_lock(project.path());
+    assert!(lock.contains("reference = \"tag v1\""), "{lock}");
+    assert!(!lock.contains("default branch"), "{lock}");
+}
+
+#[test]
+fn update_moves_lockfile_forward() {
+    let cache = tempfile::tempdir().unwrap();
+    let (dep_dir, dep_url) = create_git_dep(&[("val.pluto", "pub fn val() int {\n    return 1\n}")]);
+    let project = git_dep_project(&dep_url);
+    assert_eq!(build_in(project.path(), cache.path()), "1\n");
+
+    std::fs::write(dep_dir.path().join("val.pluto"), "pub fn val() int {\n    return 2\n}").unwrap();
+    git_cmd(dep_dir.path(), &["add", "."]);
+    git_cmd(dep_dir.path(), &["commit", "-m", "newer"]);
+
+    unsafe { std::env::set_var("PLUTO_CACHE_DIR", cache.path()); }
+    let result = pluto::update_git_deps(project.path());
+    unsafe { std::env::remove_var("PLUTO_CACHE_DIR"); }
+    result.unwrap();
+
+    assert!(read_lock(project.path()).contains(&git_head_sha(dep_dir.path())));
+    assert_eq!(build_in(project.path(), cache.path()), "2\n");
+}
+
+#[test]
+fn frozen_rejects_lockfile_changes() {
+    let cache = tempfile::tempdir().unwrap();
+    let (dep_dir, dep_url) = create_git_dep(&[("val.pluto", "pub fn val() int {\n    return 1\n}")]);
+    git_cmd(dep_dir.path(), &["tag", "v1"]);
+    let project = git_dep_project(&dep_url);
+    let compile = |frozen: bool| {
+        let mut cmd = common::pluto();
+        cmd.arg("compile").arg(project.path().join("main.pluto"))
+            .arg("-o").arg(project.path().join("test_bin"))
+            .env("PLUTO_CACHE_DIR", cache.path());
+        if frozen {
+            cmd.arg("--frozen");
+        }
+        cmd.output().unwrap()
+    };
+
+    let missing = compile(true);
+    assert!(!missing.status.success());
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("pluto.lock is missing"));
+    assert!(!project.path().join("pluto.lock").exists());
+
+    assert!(compile(false).status.success());
+    assert!(compile(true).status.success());
+
+    write_git_dep_manifest(project.path(), &dep_url, ", tag = \"v1\"");
+    let stale = compile(true);
+    assert!(!stale.status.success());
+    assert!(String::from_utf8_lossy(&stale.stderr).contains("pluto.lock is out of date"));
+}
+
+// ============================================================
+// Native libraries ([link] + extern "C")
+// ============================================================
+
+/// Build `native/libshapes.a` under `dir`, exporting `triple`, `half`, and `is_even`.
+fn build_shapes_lib(dir: &std::path::Path) {
+    let native = dir.join("native");
+    std::fs::create_dir_all(&native).unwrap();
+    std::fs::write(native.join("shapes.c"), r#"#include <stdbool.h>
+#include <stdint.h>
+int64_t triple(int64_t x) { return x * 3; }
+double half(double x) { return x / 2.0; }
+bool is_even(int64_t x) { return x % 2 == 0; }
+"#).unwrap();
+    let cc = Command::new("cc").args(["-c", "shapes.c", "-o", "shapes.o"]).current_dir(&native).status().unwrap();
+    assert!(cc.success());
+    let ar = Command::new("ar").args(["rcs", "libshapes.a", "shapes.o"]).current_dir(&native).status().unwrap();
+    assert!(ar.success());
+}
+
+#[test]
+fn link_section_links_static_c_library() {
+    let dir = tempfile::tempdir().unwrap();
+    build_shapes_lib(dir.path());
+
+    std::fs::write(dir.path().join("pluto.toml"),
+        "[package]\nname = \"root\"\n\n[link]\nlibs = [\"shapes\"]\nsearch = [\"native\"]\n").unwrap();
+    std::fs::write(dir.path().join("main.pluto"), r#"extern "C" fn triple(x: int) int
+extern "C" fn half(x: float) float
+extern "C" fn is_even(x: int) bool
+
+fn main() {
+    print(triple(14))
+    print(half(5.0))
+    print(is_even(14))
+    print(is_even(7))
+}
+"#).unwrap();
+
+    let entry = dir.path().join("main.pluto");
+    let bin_path = dir.path().join("test_bin");
+    pluto::compile_file(&entry, &bin_path).unwrap_or_else(|e| panic!("Compilation failed: {e}"));
+    let output = Command::new(&bin_path).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "42\n2.5\ntrue\nfalse\n");
+}
+
+#[test]
+fn link_section_rejects_linker_options() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("pluto.toml"),
+        "[package]\nname = \"root\"\n\n[link]\nlibs = [\"-shared\"]\n").unwrap();
+    std::fs::write(dir.path().join("main.pluto"), "fn main() {\n}\n").unwrap();
+
+    let err = pluto::compile_file(&dir.path().join("main.pluto"), &dir.path().join("test_bin"))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("invalid library name '-shared'"), "got: {err}");
+}
+
+#[test]
+fn build_section_passes_linker_flags() {
+    let dir = tempfile::tempdir().unwrap();
+    build_shapes_lib(dir.path());
+    let search = dir.path().join("native");
+
+    // Absolute search paths are used as-is
+    std::fs::write(dir.path().join("pluto.toml"), format!(
+        "[package]\nname = \"root\"\n\n[build]\nlink_flags = [\"-lshapes\"]\nlink_search = [\"{}\"]\n",
+        search.display()
+    )).unwrap();
+    std::fs::write(dir.path().join("main.pluto"),
+        "extern \"C\" fn triple(x: int) int\n\nfn main() {\n    print(triple(5))\n}\n").unwrap();
+
+    let bin_path = dir.path().join("test_bin");
+    pluto::compile_file(&dir.path().join("main.pluto"), &bin_path).unwrap_or_else(|e| panic!("Compilation failed: {e}"));
+    let output = Command::new(&bin_path).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "15\n");
+}
+
+#[test]
+fn build_section_rejects_missing_search_path() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("pluto.toml"),
+        "[package]\nname = \"root\"\n\n[build]\nlink_search = [\"no/such/dir\"]\n").unwrap();
+    std::fs::write(dir.path().join("main.pluto"), "fn main() {\n}\n").unwrap();
+
+    let err = pluto::compile_file(&dir.path().join("main.pluto"), &dir.path().join("test_bin"))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("library search path") && err.contains("does not exist"), "got: {err}");
+}
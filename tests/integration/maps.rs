@@ -263,3 +263,71 @@ fn main() int {
 "#);
     assert_eq!(out, "100\n0\n100\n198\n");
 }
+
+// ── Hash seed ───────────────────────────────────────────────────────────────
+
+const HASH_ORDER_SRC: &str = r#"
+fn main() {
+    let m = Map<string, int> {}
+    let s = Set<int> {}
+    let mut i = 0
+    while i < 40 {
+        m.insert(f"key{i}", i)
+        s.insert(i * 7919)
+        i = i + 1
+    }
+    for k in m.keys() {
+        print(k)
+    }
+    for v in s.to_array() {
+        print(v)
+    }
+}
+"#;
+
+fn run_with_hash_seed(bin: &CompiledBinary, seed: Option<&str>) -> String {
+    let mut cmd = std::process::Command::new(&bin.path);
+    if let Some(seed) = seed {
+        cmd.env("PLUTO_HASH_SEED", seed);
+    } else {
+        cmd.env_remove("PLUTO_HASH_SEED");
+    }
+    let output = cmd.output().unwrap();
+    assert!(output.status.success(), "Binary exited with non-zero status");
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn sorted_lines(s: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = s.lines().collect();
+    lines.sort();
+    lines
+}
+
+#[test]
+fn map_iteration_order_is_reproducible_by_default() {
+    let bin = CompiledBinary::compile(HASH_ORDER_SRC);
+    let first = run_with_hash_seed(&bin, None);
+    let second = run_with_hash_seed(&bin, None);
+    assert_eq!(first.lines().count(), 80);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn map_iteration_order_is_reproducible_under_fixed_seed() {
+    let bin = CompiledBinary::compile(HASH_ORDER_SRC);
+    let first = run_with_hash_seed(&bin, Some("12345"));
+    let second = run_with_hash_seed(&bin, Some("12345"));
+    assert_eq!(first, second);
+}
+
+#[test]
+fn map_hash_seed_changes_order_not_contents() {
+    let bin = CompiledBinary::compile(HASH_ORDER_SRC);
+    let default = run_with_hash_seed(&bin, None);
+    let zero = run_with_hash_seed(&bin, Some("0"));
+    let seeded = run_with_hash_seed(&bin, Some("0xdeadbeef"));
+    // Seed 0 is the default
+    assert_eq!(default, zero);
+    assert_ne!(default, seeded);
+    assert_eq!(sorted_lines(&default), sorted_lines(&seeded));
+}
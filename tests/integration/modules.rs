@@ -60,6 +60,32 @@ fn import_single_file_module() {
     assert_eq!(out, "3\n");
 }
 
+#[test]
+fn import_module_const() {
+    let out = run_project(&[
+        ("main.pluto", "import limits\n\nfn main() {\n    print(limits.MAX)\n    print(limits.headroom(90))\n}"),
+        ("limits.pluto", "pub const MAX: int = 100\nconst STEP = 5\n\npub fn headroom(n: int) int {\n    return (MAX - n) / STEP\n}"),
+    ]);
+    assert_eq!(out, "100\n2\n");
+}
+
+#[test]
+fn import_module_global() {
+    let out = run_project(&[
+        ("main.pluto", "import stats\n\nfn main() {\n    stats.record(3)\n    stats.record(4)\n    print(stats.total)\n}"),
+        ("stats.pluto", "pub global mut total: int = 0\n\npub fn record(n: int) {\n    total = total + n\n}"),
+    ]);
+    assert_eq!(out, "7\n");
+}
+
+#[test]
+fn private_module_const_rejected() {
+    compile_project_should_fail(&[
+        ("main.pluto", "import limits\n\nfn main() {\n    print(limits.STEP)\n}"),
+        ("limits.pluto", "const STEP = 5\n"),
+    ]);
+}
+
 // ============================================================
 // Import directory module
 // ============================================================
@@ -100,6 +126,36 @@ fn call_imported_function() {
     assert_eq!(out, "42\n");
 }
 
+#[test]
+fn imported_function_named_error() {
+    // `error` is a keyword, but it can name a function or method and be called after a `.`
+    let out = run_project(&[
+        ("main.pluto", "import report\n\nfn main() {\n    report.error(\"disk full\")\n    let r = report.Reporter { count: 0 }\n    print(r.error())\n}"),
+        ("report.pluto", "pub fn error(msg: string) {\n    print(f\"error: {msg}\")\n}\n\npub class Reporter {\n    count: int\n\n    fn error(self) int {\n        return self.count + 1\n    }\n}"),
+    ]);
+    assert_eq!(out, "error: disk full\n1\n");
+}
+
+#[test]
+fn variant_payload_reads_at_same_offsets_in_two_files() {
+    // Both guarded `s.x` reads sit at the same byte offsets of their files
+    let out = run_project(&[
+        ("main.pluto", "import a\npub fn fb(s: Sh) int { if s is Sh.B { return s.x } return 0 }\n\nenum Sh {\n    A { x: int }\n    B { y: int, x: int }\n}\n\nfn main() {\n    print(fb(Sh.B { y: 1, x: 9 }))\n    print(a.fa(a.Sh.A { x: 5 }))\n}"),
+        ("a.pluto", "// mod a\npub fn fa(s: Sh) int { if s is Sh.A { return s.x } return 0 }\n\npub enum Sh {\n    A { x: int }\n    B { y: int, x: int }\n}"),
+    ]);
+    assert_eq!(out, "9\n5\n");
+}
+
+#[test]
+fn method_refs_at_same_offsets_in_two_files() {
+    // `p.val` and `p.get` sit at the same byte offsets of their files
+    let out = run_project(&[
+        ("main.pluto", "import a\npub fn f2(p: P) int { let g = p.val\n    return g() }\n\nclass P {\n    n: int\n\n    fn val(self) int {\n        return self.n * 10\n    }\n}\n\nfn main() {\n    print(f2(P { n: 4 }))\n    print(a.f1(a.P { n: 3 }))\n}"),
+        ("a.pluto", "// mod a\npub fn f1(p: P) int { let g = p.get\n    return g() }\n\npub class P {\n    n: int\n\n    fn get(self) int {\n        return self.n\n    }\n}"),
+    ]);
+    assert_eq!(out, "40\n3\n");
+}
+
 // ============================================================
 // Qualified type in parameter
 // ============================================================
@@ -674,6 +730,43 @@ pub fn other() int {
     ]);
 }
 
+#[test]
+fn circular_import_reports_full_cycle() {
+    // A→B→C→A: the error lists every file in the loop, in import order
+    let (dir, entry) = write_project(&[
+        ("main.pluto", r#"import a
+
+fn main() {
+    print(a.value())
+}
+"#),
+        ("a.pluto", r#"import b
+
+pub fn value() int {
+    return b.value()
+}
+"#),
+        ("b.pluto", r#"import c
+
+pub fn value() int {
+    return c.value()
+}
+"#),
+        ("c.pluto", r#"import a
+
+pub fn value() int {
+    return a.value()
+}
+"#),
+    ]);
+    let err = pluto::compile_file(&entry, &dir.path().join("test_bin")).unwrap_err().to_string();
+    let root = dir.path().canonicalize().unwrap();
+    let file = |name: &str| root.join(name).display().to_string();
+    let expected = format!("{} -> {} -> {} -> {}", file("a.pluto"), file("b.pluto"), file("c.pluto"), file("a.pluto"));
+    assert!(err.contains("circular import detected"), "unexpected error: {err}");
+    assert!(err.contains(&expected), "expected cycle '{expected}' in: {err}");
+}
+
 #[test]
 fn transitive_import_with_classes() {
     // Module imports another and uses its classes
@@ -1199,3 +1292,353 @@ fn imported_module_using_multifile_stdlib_compiles() {
     ]);
     assert_eq!(out, "42\n");
 }
+
+// ============================================================
+// Glob imports (import dir.*)
+// ============================================================
+
+#[test]
+fn glob_import_binds_each_file_as_submodule() {
+    let out = run_project(&[
+        ("main.pluto", r#"import handlers.*
+
+fn main() {
+    print(handlers.auth.login("ada"))
+    let u: handlers.users.User = handlers.users.User { id: handlers.users.ADMIN }
+    print(u.id)
+    print(handlers.users.count())
+    match handlers.users.role(u) {
+        handlers.users.Role.Admin {
+            print("admin")
+        }
+        handlers.users.Role.Member {
+            print("member")
+        }
+    }
+}
+"#),
+        ("handlers/auth.pluto", r#"pub fn login(name: string) string {
+    return f"welcome {name}"
+}
+"#),
+        ("handlers/users.pluto", r#"pub const ADMIN = 1
+
+pub class User {
+    id: int
+}
+
+pub enum Role {
+    Admin
+    Member
+}
+
+pub fn count() int {
+    return helper() + 1
+}
+
+pub fn role(u: User) Role {
+    if u.id == ADMIN {
+        return Role.Admin
+    }
+    return Role.Member
+}
+
+fn helper() int {
+    return 41
+}
+"#),
+    ]);
+    assert_eq!(out, "welcome ada\n1\n42\nadmin\n");
+}
+
+#[test]
+fn glob_import_with_alias_and_sub_imports() {
+    let out = run_project(&[
+        ("main.pluto", r#"import api.handlers.* as h
+
+fn main() {
+    print(h.ping.reply())
+}
+"#),
+        ("api/handlers/ping.pluto", r#"import util
+
+pub fn reply() string {
+    return util.shout("pong")
+}
+"#),
+        ("api/handlers/util.pluto", r#"pub fn shout(s: string) string {
+    return f"{s}!"
+}
+"#),
+    ]);
+    assert_eq!(out, "pong!\n");
+}
+
+#[test]
+fn glob_import_private_item_rejected() {
+    compile_project_should_fail(&[
+        ("main.pluto", r#"import handlers.*
+
+fn main() {
+    print(handlers.auth.secret())
+}
+"#),
+        ("handlers/auth.pluto", r#"fn secret() int {
+    return 1
+}
+"#),
+    ]);
+}
+
+#[test]
+fn glob_import_conflicting_module_names() {
+    let (dir, entry) = write_project(&[
+        ("main.pluto", r#"import handlers.*
+
+fn main() {
+    print(handlers.auth.id())
+}
+"#),
+        ("handlers/auth.pluto", "pub fn id() int {\n    return 1\n}\n"),
+        ("handlers/auth.pt", "pub fn id() int {\n    return 2\n}\n"),
+    ]);
+    let err = pluto::compile_file(&entry, &dir.path().join("test_bin")).unwrap_err().to_string();
+    assert!(err.contains("conflicting modules"), "unexpected error: {err}");
+    assert!(err.contains("handlers.auth"), "unexpected error: {err}");
+}
+
+// ============================================================
+// Selective imports (import m.{a, b as c})
+// ============================================================
+
+const SELECTIVE_MATH: &str = r#"pub const LIMIT = 100
+
+pub class Point {
+    x: int
+    y: int
+}
+
+pub enum Color {
+    Red
+    Green
+}
+
+pub error Overflow {}
+
+pub fn square(n: int) int {
+    return n * n
+}
+
+pub fn pow(base: int, exp: int) int {
+    let mut result = 1
+    for i in 0..exp {
+        result = result * base
+    }
+    if result > LIMIT {
+        raise Overflow {}
+    }
+    return result
+}
+
+pub fn other() int {
+    return 0
+}
+
+fn secret() int {
+    return 7
+}
+"#;
+
+#[test]
+fn selective_import_binds_items_and_aliases() {
+    let out = run_project(&[
+        ("main.pluto", r#"import math.{square, pow as power, Point, Color as Hue, Overflow, LIMIT}
+
+fn describe(c: Hue) string {
+    match c {
+        Hue.Red {
+            return "red"
+        }
+        Hue.Green {
+            return "green"
+        }
+    }
+}
+
+fn checked_power(base: int, exp: int) int {
+    return power(base, exp) catch err: Overflow {
+        return -1
+    }
+}
+
+fn main() {
+    print(square(7))
+    print(checked_power(2, 5))
+    let p: Point = Point { x: 1, y: 2 }
+    print(p.x + p.y)
+    print(describe(Hue.Green))
+    print(LIMIT)
+    print(checked_power(10, 3))
+}
+"#),
+        ("math.pluto", SELECTIVE_MATH),
+    ]);
+    assert_eq!(out, "49\n32\n3\ngreen\n100\n-1\n");
+}
+
+#[test]
+fn selective_import_inside_imported_module() {
+    let out = run_project(&[
+        ("main.pluto", r#"import shapes.geom
+
+fn main() {
+    print(geom.area(6))
+}
+"#),
+        ("shapes/geom.pluto", r#"import math.{square as sq}
+
+pub fn area(side: int) int {
+    return sq(side)
+}
+"#),
+        ("shapes/math.pluto", SELECTIVE_MATH),
+    ]);
+    assert_eq!(out, "36\n");
+}
+
+#[test]
+fn selective_import_does_not_bind_module() {
+    compile_project_should_fail(&[
+        ("main.pluto", r#"import math.{square}
+
+fn main() {
+    print(math.other())
+}
+"#),
+        ("math.pluto", SELECTIVE_MATH),
+    ]);
+}
+
+#[test]
+fn selective_import_errors() {
+    let compile_err = |main: &str| {
+        let (dir, entry) = write_project(&[("main.pluto", main), ("math.pluto", SELECTIVE_MATH)]);
+        pluto::compile_file(&entry, &dir.path().join("test_bin")).unwrap_err().to_string()
+    };
+
+    let err = compile_err("import math.{cube}\n\nfn main() {\n    print(cube(2))\n}\n");
+    assert!(err.contains("module 'math' has no item 'cube'"), "unexpected error: {err}");
+
+    let err = compile_err("import math.{square as twice}\n\nfn twice(n: int) int {\n    return n * 2\n}\n\nfn main() {\n    print(twice(2))\n}\n");
+    assert!(err.contains("collides with an existing name 'twice'"), "unexpected error: {err}");
+
+    let err = compile_err("import math.{secret}\n\nfn main() {\n    print(secret())\n}\n");
+    assert!(err.contains("'secret' is private to module 'math'"), "unexpected error: {err}");
+}
+
+// ============================================================
+// Dependency graphs (pluto graph)
+// ============================================================
+
+/// Write files to a temp directory and return it with the entry path.
+fn write_project(files: &[(&str, &str)]) -> (tempfile::TempDir, std::path::PathBuf) {
+    let dir = tempfile::tempdir().unwrap();
+    for (name, content) in files {
+        let path = dir.path().join(name);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(&path, content).unwrap();
+    }
+    let entry = dir.path().join("main.pluto");
+    (dir, entry)
+}
+
+/// Edges of a graph as (from label, to label) pairs.
+fn graph_edges(graph: &pluto::graph::Graph) -> Vec<(&str, &str)> {
+    graph.edges.iter().map(|&(from, to)| (graph.nodes[from].as_str(), graph.nodes[to].as_str())).collect()
+}
+
+#[test]
+fn import_graph_follows_files_and_directories() {
+    let (_dir, entry) = write_project(&[
+        ("main.pluto", "import geo\nimport util\n\nfn main() {\n    print(geo.area(2))\n}"),
+        ("geo/shapes.pluto", "import inner\n\npub fn area(x: int) int {\n    return inner.sq(x)\n}"),
+        ("geo/inner.pluto", "pub fn sq(x: int) int {\n    return x * x\n}"),
+        ("util.pluto", "import geo\n\npub fn f() int {\n    return geo.area(1)\n}"),
+    ]);
+    let graph = pluto::graph::import_graph(&entry, None).unwrap();
+    assert_eq!(graph.nodes, vec!["main.pluto", "geo", "inner", "util"]);
+    assert_eq!(graph_edges(&graph), vec![("main.pluto", "geo"), ("geo", "inner"), ("main.pluto", "util"), ("util", "geo")]);
+    assert!(graph.cycle_edges().is_empty());
+}
+
+#[test]
+fn import_graph_highlights_cycles() {
+    let (_dir, entry) = write_project(&[
+        ("main.pluto", "import a\n\nfn main() {\n    print(a.value())\n}"),
+        ("a.pluto", "import b\n\npub fn value() int {\n    return b.other()\n}"),
+        ("b.pluto", "import a\n\npub fn other() int {\n    return a.value()\n}"),
+    ]);
+    let graph = pluto::graph::import_graph(&entry, None).unwrap();
+    assert_eq!(graph_edges(&graph), vec![("main.pluto", "a"), ("a", "b"), ("b", "a")]);
+    assert_eq!(graph.cycle_edges().len(), 2);
+    let dot = graph.to_dot("imports");
+    assert!(dot.contains("n1 -> n2 [color=red];"), "{dot}");
+    assert!(dot.contains("n0 -> n1;"), "{dot}");
+}
+
+#[test]
+fn import_graph_expands_glob_imports() {
+    let (_dir, entry) = write_project(&[
+        ("main.pluto", "import handlers.*\n\nfn main() {\n    print(handlers.foo.f())\n}"),
+        ("handlers/foo.pluto", "import baz\n\npub fn f() int {\n    return baz.g()\n}"),
+        ("handlers/baz.pluto", "pub fn g() int {\n    return 2\n}"),
+    ]);
+    let graph = pluto::graph::import_graph(&entry, None).unwrap();
+    assert_eq!(graph.nodes, vec!["main.pluto", "handlers.baz", "handlers.foo"]);
+    assert_eq!(
+        graph_edges(&graph),
+        vec![("main.pluto", "handlers.baz"), ("main.pluto", "handlers.foo"), ("handlers.foo", "handlers.baz")]
+    );
+}
+
+#[test]
+fn call_graph_uses_resolved_call_targets() {
+    let (_dir, entry) = write_project(&[
+        ("main.pluto", "import lib\n\nfn twice(x: int) int {\n    return lib.double(x) * 2\n}\n\nclass C {\n    v: int\n\n    fn go(self) int {\n        return twice(self.v)\n    }\n}\n\nfn main() {\n    let c = C { v: 1 }\n    print(c.go())\n}"),
+        ("lib.pluto", "pub fn double(x: int) int {\n    return x + x\n}\n\npub fn unused() int {\n    return 0\n}"),
+    ]);
+    let graph = pluto::graph::call_graph(&entry, None).unwrap();
+    assert_eq!(graph_edges(&graph), vec![("twice", "lib.double"), ("C.go", "twice")]);
+    assert!(!graph.nodes.iter().any(|n| n == "lib.unused"));
+}
+
+#[test]
+fn graph_cli_writes_dot_file() {
+    let (dir, entry) = write_project(&[
+        ("main.pluto", "import a\n\nfn main() {\n    print(a.value())\n}"),
+        ("a.pluto", "pub fn value() int {\n    return 1\n}"),
+    ]);
+    let out = dir.path().join("deps.dot");
+    let output = common::pluto().arg("graph").arg(&entry).arg("-o").arg(&out).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        std::fs::read_to_string(&out).unwrap(),
+        "digraph imports {\n    rankdir=LR;\n    node [shape=box];\n    n0 [label=\"main.pluto\"];\n    n1 [label=\"a\"];\n    n0 -> n1;\n}\n"
+    );
+}
+
+#[test]
+fn module_fn_named_unreachable_is_not_the_builtin() {
+    let out = run_project(&[
+        ("main.pluto", "import util\n\nfn main() {\n    print(util.run())\n}"),
+        ("util.pluto", "pub fn unreachable() {\n    print(5)\n}\n\npub fn run() int {\n    unreachable()\n    return 1\n}"),
+    ]);
+    assert_eq!(out, "5\n1\n");
+    // Calling it doesn't end a path, so run() still needs a return
+    compile_project_should_fail(&[
+        ("main.pluto", "import util\n\nfn main() {\n    print(util.run())\n}"),
+        ("util.pluto", "pub fn unreachable() {\n    print(5)\n}\n\npub fn run() int {\n    unreachable()\n}"),
+    ]);
+}
@@ -491,3 +491,244 @@ fn if_expr_nullable_class() {
     assert_eq!(out.trim(), "10");
 }
 
+
+// ============================================================
+// if let / match on nullable values
+// ============================================================
+
+#[test]
+fn if_let_binds_unwrapped_int() {
+    let out = compile_and_run_stdout(r#"
+fn find(n: int) int? {
+    if n > 0 {
+        return n * 2
+    }
+    return none
+}
+
+fn main() {
+    if let x = find(3) {
+        print(x + 1)
+    } else {
+        print("none")
+    }
+    if let x = find(0) {
+        print(x)
+    } else {
+        print("none")
+    }
+}
+"#);
+    assert_eq!(out.trim(), "7\nnone");
+}
+
+#[test]
+fn if_let_unboxes_float_bool_and_heap_types() {
+    let out = compile_and_run_stdout(r#"
+class Point {
+    x: int
+}
+
+fn main() {
+    let f: float? = 2.5
+    let b: bool? = true
+    let s: string? = "hi"
+    let p: Point? = Point { x: 9 }
+    if let v = f { print(v * 2.0) }
+    if let v = b { print(!v) }
+    if let v = s { print(v + "!") }
+    if let v = p { print(v.x) }
+}
+"#);
+    assert_eq!(out.trim(), "5\nfalse\nhi!\n9");
+}
+
+#[test]
+fn if_let_without_else_skips_none() {
+    let out = compile_and_run_stdout(r#"
+fn main() {
+    let m: int? = none
+    if let x = m {
+        print(x)
+    }
+    print("done")
+}
+"#);
+    assert_eq!(out.trim(), "done");
+}
+
+#[test]
+fn if_let_else_if_let_chain() {
+    let out = compile_and_run_stdout(r#"
+fn main() {
+    let a: int? = none
+    let b: int? = 5
+    if let x = a {
+        print(x)
+    } else if let y = b {
+        print(y)
+    } else {
+        print("neither")
+    }
+}
+"#);
+    assert_eq!(out.trim(), "5");
+}
+
+#[test]
+fn match_nullable_runs_matching_arm() {
+    let out = compile_and_run_stdout(r#"
+fn describe(m: int?) string {
+    match m {
+        some(x) {
+            return f"got {x}"
+        }
+        none {
+            return "nothing"
+        }
+    }
+}
+
+fn main() {
+    let four: int? = 4
+    print(describe(four))
+    print(describe(none))
+}
+"#);
+    assert_eq!(out.trim(), "got 4\nnothing");
+}
+
+#[test]
+fn match_nullable_arms_in_any_order() {
+    let out = compile_and_run_stdout(r#"
+fn main() {
+    let name: string? = "pluto"
+    match name {
+        none { print("anonymous") }
+        some(n) { print(n) }
+    }
+}
+"#);
+    assert_eq!(out.trim(), "pluto");
+}
+
+#[test]
+fn if_let_binding_is_scoped_to_then_block() {
+    compile_should_fail_with(r#"
+fn main() {
+    let m: int? = 1
+    if let x = m {
+        print(x)
+    }
+    print(x)
+}
+"#, "undefined variable 'x'");
+}
+
+#[test]
+fn if_let_binding_is_immutable() {
+    compile_should_fail_with(r#"
+fn main() {
+    let m: int? = 1
+    if let x = m {
+        x = 2
+    }
+}
+"#, "cannot assign to immutable variable 'x'");
+}
+
+#[test]
+fn if_let_rejects_non_nullable_value() {
+    compile_should_fail_with(r#"
+fn main() {
+    let n = 3
+    if let x = n {
+        print(x)
+    }
+}
+"#, "require a nullable value, found int");
+}
+
+#[test]
+fn if_let_rejects_bare_none() {
+    compile_should_fail_with(r#"
+fn main() {
+    if let x = none {
+        print(1)
+    }
+}
+"#, "cannot unwrap `none`");
+}
+
+#[test]
+fn if_let_rejects_nested_nullable() {
+    compile_should_fail_with(r#"
+fn wrap<T>(x: T) T? {
+    return x
+}
+
+fn main() {
+    let m: int? = 1
+    if let v = wrap(m) {
+        print(1)
+    }
+}
+"#, "nested nullable types (T??) are not allowed");
+}
+
+#[test]
+fn match_nullable_missing_none_arm() {
+    compile_should_fail_with(r#"
+fn main() {
+    let m: int? = 1
+    match m {
+        some(x) { print(x) }
+    }
+}
+"#, "missing 'none' arm");
+}
+
+#[test]
+fn match_nullable_missing_some_arm() {
+    compile_should_fail_with(r#"
+fn main() {
+    let m: int? = 1
+    match m {
+        none { print(0) }
+    }
+}
+"#, "missing 'some(..)' arm");
+}
+
+#[test]
+fn match_nullable_duplicate_arm() {
+    compile_should_fail_with(r#"
+fn main() {
+    let m: int? = 1
+    match m {
+        some(x) { print(x) }
+        none { print(0) }
+        none { print(1) }
+    }
+}
+"#, "duplicate 'none' arm");
+}
+
+#[test]
+fn match_nullable_both_arms_return() {
+    let out = compile_and_run_stdout(r#"
+fn pick(m: int?) int {
+    match m {
+        some(x) { return x }
+        none { return -1 }
+    }
+}
+
+fn main() {
+    let three: int? = 3
+    print(pick(three))
+    print(pick(none))
+}
+"#);
+    assert_eq!(out.trim(), "3\n-1");
+}
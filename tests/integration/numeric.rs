@@ -78,6 +78,24 @@ fn cast_invalid_bool_to_float() {
     );
 }
 
+// ── Float literals ────────────────────────────────────────────────────────────
+
+#[test]
+fn float_scientific_notation() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let x = 1e3\n    print(x == 1000.0)\n    print(x)\n    print(2.5E-3 * 1000.0)\n}",
+    );
+    assert_eq!(out, "true\n1000\n2.5\n");
+}
+
+#[test]
+fn float_missing_exponent_digits() {
+    compile_should_fail_with(
+        "fn main() {\n    let x = 1e\n}",
+        "expected digits after exponent in float literal: 1e",
+    );
+}
+
 // ── Math builtins ─────────────────────────────────────────────────────────────
 
 #[test]
@@ -347,3 +347,181 @@ fn increment_field() {
     );
     assert_eq!(out, "3\n");
 }
+
+// ── Operator overloading via prelude traits ─────────────────────────────────
+
+const VEC2: &str = r#"
+class Vec2 impl Add, Sub, Mul, Eq {
+    x: int
+    y: int
+
+    fn add(self, other: Vec2) Vec2 { return Vec2 { x: self.x + other.x, y: self.y + other.y } }
+    fn sub(self, other: Vec2) Vec2 { return Vec2 { x: self.x - other.x, y: self.y - other.y } }
+    fn mul(self, other: Vec2) Vec2 { return Vec2 { x: self.x * other.x, y: self.y * other.y } }
+    fn eq(self, other: Vec2) bool { return self.x == other.x && self.y == other.y }
+}
+"#;
+
+#[test]
+fn overloaded_arithmetic_operators() {
+    let out = compile_and_run_stdout(&format!("{VEC2}
+fn main() {{
+    let a = Vec2 {{ x: 1, y: 2 }}
+    let b = Vec2 {{ x: 3, y: 4 }}
+    let c = a + b * b - a
+    print(c.x)
+    print(c.y)
+}}
+"));
+    assert_eq!(out, "9\n16\n");
+}
+
+#[test]
+fn overloaded_equality_operators() {
+    let out = compile_and_run_stdout(&format!("{VEC2}
+fn main() {{
+    let a = Vec2 {{ x: 1, y: 2 }}
+    print(a == Vec2 {{ x: 1, y: 2 }})
+    print(a != Vec2 {{ x: 1, y: 2 }})
+    print(a == Vec2 {{ x: 2, y: 1 }})
+}}
+"));
+    assert_eq!(out, "true\nfalse\nfalse\n");
+}
+
+#[test]
+fn equality_without_eq_compares_identity() {
+    let out = compile_and_run_stdout(r#"
+class P { x: int }
+fn main() {
+    let a = P { x: 1 }
+    let b = a
+    print(a == b)
+    print(a == P { x: 1 })
+}
+"#);
+    assert_eq!(out, "true\nfalse\n");
+}
+
+#[test]
+fn overloaded_comparison_operators() {
+    let out = compile_and_run_stdout(r#"
+class Money impl Ord {
+    cents: int
+
+    fn compare(self, other: Money) Ordering {
+        if self.cents < other.cents { return Ordering.Less }
+        if self.cents > other.cents { return Ordering.Greater }
+        return Ordering.Equal
+    }
+}
+fn main() {
+    let a = Money { cents: 5 }
+    let b = Money { cents: 9 }
+    print(a < b)
+    print(a > b)
+    print(a <= Money { cents: 5 })
+    print(a >= b)
+}
+"#);
+    assert_eq!(out, "true\nfalse\ntrue\nfalse\n");
+}
+
+#[test]
+fn enum_implements_ord_and_eq() {
+    let out = compile_and_run_stdout(r#"
+enum Level impl Eq, Ord {
+    Low
+    High
+
+    fn rank(self) int {
+        match self {
+            Level.Low { return 0 }
+            Level.High { return 1 }
+        }
+    }
+    fn eq(self, other: Level) bool { return self.rank() == other.rank() }
+    fn compare(self, other: Level) Ordering {
+        if self.rank() < other.rank() { return Ordering.Less }
+        if self.rank() > other.rank() { return Ordering.Greater }
+        return Ordering.Equal
+    }
+}
+fn main() {
+    print(Level.Low < Level.High)
+    print(Level.High == Level.High)
+    print(Level.High != Level.Low)
+}
+"#);
+    assert_eq!(out, "true\ntrue\ntrue\n");
+}
+
+#[test]
+fn operator_in_generic_function_with_bound() {
+    let out = compile_and_run_stdout(&format!("{VEC2}
+fn sum<T: Add>(a: T, b: T) T {{
+    return a + b
+}}
+fn main() {{
+    print(sum(Vec2 {{ x: 1, y: 2 }}, Vec2 {{ x: 3, y: 4 }}).y)
+}}
+"));
+    assert_eq!(out, "6\n");
+}
+
+#[test]
+fn operator_without_trait_rejected() {
+    compile_should_fail_with(r#"
+class P { x: int }
+fn main() {
+    let a = P { x: 1 }
+    let b = a + a
+}
+"#, "operator not supported for type P; implement trait 'Add' (method 'add')");
+}
+
+#[test]
+fn overloaded_operator_operand_mismatch_rejected() {
+    compile_should_fail_with(&format!("{VEC2}
+fn main() {{
+    let a = Vec2 {{ x: 1, y: 2 }}
+    let b = a + 1
+}}
+"), "operand type mismatch: Vec2 vs int");
+}
+
+#[test]
+fn self_method_on_trait_object_rejected() {
+    compile_should_fail_with(&format!("{VEC2}
+fn double(v: Add) {{
+    let w = v.add(v)
+}}
+fn main() {{ }}
+"), "uses 'Self', so it cannot be called on a trait object");
+}
+
+#[test]
+fn fallible_operator_method_rejected() {
+    compile_should_fail_with(r#"
+error Overflow {}
+class P impl Add {
+    x: int
+    fn add(self, other: P) P {
+        if other.x > 100 { raise Overflow {} }
+        return P { x: self.x + other.x }
+    }
+}
+fn main() { }
+"#, "operator trait 'Add' methods must not");
+}
+
+#[test]
+fn enum_missing_trait_method_rejected() {
+    compile_should_fail_with(r#"
+enum Color impl Eq {
+    Red
+    Blue
+}
+fn main() { }
+"#, "enum 'Color' does not implement required method 'eq' from trait 'Eq'");
+}
@@ -1,5 +1,5 @@
 mod common;
-use common::compile_and_run_stdout;
+use common::{compile_and_run_stdout, compile_should_fail_with};
 
 // ── Prelude is empty (Option<T> removed in favor of T? nullable types) ──
 // These tests verify the prelude infrastructure still works even when empty.
@@ -20,3 +20,79 @@ fn prelude_user_can_define_option_enum() {
     );
     assert_eq!(out, "42\n");
 }
+
+#[test]
+fn prelude_user_can_define_ordering_enum() {
+    // A user `Ordering` replaces the prelude's
+    let out = compile_and_run_stdout(r#"
+enum Ordering {
+    Lt
+    Gt
+}
+
+fn name(o: Ordering) string {
+    return match o {
+        Ordering.Lt => "lt",
+        Ordering.Gt => "gt"
+    }
+}
+
+fn main() {
+    print(name(Ordering.Gt))
+}
+"#);
+    assert_eq!(out, "gt\n");
+}
+
+#[test]
+fn prelude_user_trait_named_eq_does_not_overload_operators() {
+    // A user `Eq` replaces the prelude trait, so `==` keeps comparing identity
+    let out = compile_and_run_stdout(r#"
+trait Eq {
+    fn same(self, n: int) bool
+}
+
+class Id impl Eq {
+    n: int
+
+    fn same(self, n: int) bool {
+        return self.n == n
+    }
+}
+
+fn check(e: Eq) bool {
+    return e.same(4)
+}
+
+fn main() {
+    let a = Id { n: 4 }
+    let b = Id { n: 4 }
+    print(check(a))
+    print(a == b)
+    print(a == a)
+}
+"#);
+    assert_eq!(out, "true\nfalse\ntrue\n");
+}
+
+#[test]
+fn prelude_user_trait_named_add_does_not_overload_operators() {
+    compile_should_fail_with(r#"
+trait Add {
+    fn plus(self, n: int) int
+}
+
+class Id impl Add {
+    n: int
+
+    fn plus(self, n: int) int {
+        return self.n + n
+    }
+}
+
+fn main() {
+    let a = Id { n: 4 }
+    print(a + a)
+}
+"#, "operator not supported for type Id");
+}
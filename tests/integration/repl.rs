@@ -0,0 +1,101 @@
+mod common;
+use common::pluto;
+
+use std::io::Write;
+use std::process::Stdio;
+
+use pluto::repl::{is_incomplete, Repl, ReplOutcome};
+use pluto::GcBackend;
+
+fn ran_stdout(outcome: ReplOutcome) -> String {
+    match outcome {
+        ReplOutcome::Ran { stdout, exit_code, .. } => {
+            assert_eq!(exit_code, 0);
+            stdout
+        }
+        other => panic!("expected the input to run, got {other:?}"),
+    }
+}
+
+#[test]
+fn repl_echoes_expression_values() {
+    let mut repl = Repl::new(GcBackend::default()).unwrap();
+    assert_eq!(ran_stdout(repl.eval("1 + 2").unwrap()), "3\n");
+    assert_eq!(ran_stdout(repl.eval("\"hi\"").unwrap()), "hi\n");
+}
+
+#[test]
+fn repl_runs_statements() {
+    let mut repl = Repl::new(GcBackend::default()).unwrap();
+    let out = ran_stdout(repl.eval("let x = 4\nprint(x * x)\n").unwrap());
+    assert_eq!(out, "16\n");
+}
+
+#[test]
+fn repl_declarations_persist() {
+    let mut repl = Repl::new(GcBackend::default()).unwrap();
+    match repl.eval("fn double(n: int) int {\n    return n * 2\n}\n").unwrap() {
+        ReplOutcome::Declared(names) => assert_eq!(names, vec!["double".to_string()]),
+        other => panic!("expected a declaration, got {other:?}"),
+    }
+    repl.eval("class Box {\n    v: int\n}\n").unwrap();
+    assert_eq!(ran_stdout(repl.eval("double(Box { v: 21 }.v)").unwrap()), "42\n");
+}
+
+#[test]
+fn repl_redeclaration_replaces_previous() {
+    let mut repl = Repl::new(GcBackend::default()).unwrap();
+    repl.eval("fn f() int {\n    return 1\n}\n").unwrap();
+    repl.eval("fn f() int {\n    return 2\n}\n").unwrap();
+    assert_eq!(ran_stdout(repl.eval("f()").unwrap()), "2\n");
+}
+
+#[test]
+fn repl_errors_keep_session() {
+    let mut repl = Repl::new(GcBackend::default()).unwrap();
+    repl.eval("fn one() int {\n    return 1\n}\n").unwrap();
+    assert!(repl.eval("let = 3").is_err());
+    assert!(repl.eval("fn bad() int {\n    return \"no\"\n}\n").is_err());
+    assert!(repl.eval("bad()").is_err());
+    assert_eq!(ran_stdout(repl.eval("one()").unwrap()), "1\n");
+}
+
+#[test]
+fn repl_rejects_main() {
+    let mut repl = Repl::new(GcBackend::default()).unwrap();
+    let err = repl.eval("fn main() {\n}\n").unwrap_err();
+    assert!(err.to_string().contains("cannot declare 'main'"), "{err}");
+}
+
+#[test]
+fn repl_incomplete_input() {
+    assert!(is_incomplete("fn f() {\n"));
+    assert!(is_incomplete("print(1,\n"));
+    assert!(!is_incomplete("print(\"{\")\n"));
+    assert!(!is_incomplete("fn f() {\n}\n"));
+}
+
+#[test]
+fn repl_cli_session() {
+    let mut child = pluto()
+        .arg("repl")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"fn sq(n: int) int {\n    return n * n\n}\nsq(7)\nnope(\n)\nsq(3)\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success());
+    assert!(stdout.contains("declared sq"), "{stdout}");
+    assert!(stdout.contains("49\n"), "{stdout}");
+    assert!(stdout.contains("9\n"), "{stdout}");
+    assert!(stderr.contains("error:"), "{stderr}");
+}
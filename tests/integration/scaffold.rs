@@ -0,0 +1,71 @@
+mod common;
+use common::pluto;
+
+use pluto::scaffold::{new_project, ProjectKind};
+use std::process::Command;
+
+#[test]
+fn new_app_compiles_and_runs() {
+    let parent = tempfile::tempdir().unwrap();
+    let dir = parent.path().join("hello");
+    new_project(&dir, ProjectKind::App).unwrap();
+
+    let manifest = std::fs::read_to_string(dir.join("pluto.toml")).unwrap();
+    assert!(manifest.contains("name = \"hello\""), "{manifest}");
+    assert!(std::fs::read_to_string(dir.join(".gitignore")).unwrap().contains(".pluto-cache/"));
+
+    let graph = pluto::manifest::find_and_resolve(&dir).unwrap();
+    assert!(graph.root_dir.is_some());
+    assert!(graph.root_deps().is_empty());
+
+    let bin = dir.join("a.out");
+    pluto::compile_file(&dir.join("main.pluto"), &bin).unwrap();
+    let output = Command::new(&bin).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Hello, world!\n");
+}
+
+#[test]
+fn new_lib_is_importable_and_tested() {
+    let parent = tempfile::tempdir().unwrap();
+    let lib_dir = parent.path().join("greeter");
+    new_project(&lib_dir, ProjectKind::Lib).unwrap();
+    assert!(!lib_dir.join("main.pluto").exists());
+
+    let tests = pluto().arg("test").arg("lib.pluto").current_dir(&lib_dir).output().unwrap();
+    assert!(tests.status.success(), "{}", String::from_utf8_lossy(&tests.stderr));
+
+    let app_dir = parent.path().join("app");
+    new_project(&app_dir, ProjectKind::App).unwrap();
+    std::fs::write(
+        app_dir.join("pluto.toml"),
+        "[package]\nname = \"app\"\n\n[dependencies]\ngreeter = { path = \"../greeter\" }\n",
+    ).unwrap();
+    std::fs::write(app_dir.join("main.pluto"), "import greeter\n\nfn main() {\n    print(greeter.greet(\"lib\"))\n}\n").unwrap();
+
+    let bin = app_dir.join("a.out");
+    pluto::compile_file(&app_dir.join("main.pluto"), &bin).unwrap();
+    let output = Command::new(&bin).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Hello, lib!\n");
+}
+
+#[test]
+fn new_refuses_non_empty_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "keep me").unwrap();
+
+    let err = new_project(dir.path(), ProjectKind::App).unwrap_err();
+    assert!(err.to_string().contains("already exists and is not empty"), "{err}");
+    assert!(!dir.path().join("pluto.toml").exists());
+}
+
+#[test]
+fn new_cli_fills_empty_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = pluto().arg("new").arg(dir.path()).arg("--lib").output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(dir.path().join("lib.pluto").exists());
+
+    let again = pluto().arg("new").arg(dir.path()).output().unwrap();
+    assert!(!again.status.success());
+    assert!(String::from_utf8_lossy(&again.stderr).contains("not empty"));
+}
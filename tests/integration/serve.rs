@@ -0,0 +1,265 @@
+//! Integration tests for `pluto serve` (JSON-RPC analysis server).
+
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use tempfile::TempDir;
+
+mod common;
+
+/// A `pluto serve` process talking JSON-RPC over stdin/stdout.
+struct Client {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+}
+
+impl Client {
+    fn start() -> Self {
+        Self::start_with_env(&[])
+    }
+
+    fn start_with_env(envs: &[(&str, &str)]) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_pluto"))
+            .arg("serve")
+            .arg("--stdlib")
+            .arg("stdlib")
+            .envs(envs.iter().copied())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Self { child, stdin, stdout, next_id: 1 }
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+        let req = serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        writeln!(self.stdin, "{req}").unwrap();
+        self.stdin.flush().unwrap();
+
+        let mut line = String::new();
+        self.stdout.read_line(&mut line).unwrap();
+        let resp: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(resp["id"], id);
+        resp
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn serve_diagnostics_reports_type_errors() {
+    let temp = TempDir::new().unwrap();
+    let file = temp.path().join("bad.pluto");
+    std::fs::write(&file, "fn main() {\n    let x: int = \"hello\"\n}\n").unwrap();
+
+    let mut client = Client::start();
+    let resp = client.request("diagnostics", serde_json::json!({ "path": file }));
+    let result = &resp["result"];
+    assert_eq!(result["success"], false);
+    let errors = result["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(
+        errors[0]["message"].as_str().unwrap().contains("type mismatch"),
+        "unexpected diagnostic: {}",
+        errors[0]
+    );
+    assert_eq!(errors[0]["severity"], "error");
+    assert_eq!(errors[0]["span"]["line"], 2);
+
+    // The same process picks up the fix once the file changes on disk
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    std::fs::write(&file, "fn main() {\n    let x: int = 1\n    print(x)\n}\n").unwrap();
+    let resp = client.request("diagnostics", serde_json::json!({ "path": file }));
+    assert_eq!(resp["result"]["success"], true);
+    assert_eq!(resp["result"]["errors"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn serve_diagnostics_track_imported_files() {
+    let temp = TempDir::new().unwrap();
+    let file = temp.path().join("main.pluto");
+    std::fs::write(&file, "import util\n\nfn main() {\n    print(util.f())\n}\n").unwrap();
+    let util = temp.path().join("util.pluto");
+    std::fs::write(&util, "pub fn f() int {\n    return 1\n}\n\npub fn g() {\n    let x: int = \"no\"\n}\n").unwrap();
+
+    let mut client = Client::start();
+    let resp = client.request("diagnostics", serde_json::json!({ "path": file }));
+    assert_eq!(resp["result"]["success"], false, "{resp}");
+    // The position is located in util.pluto, not in the entry file
+    let span = &resp["result"]["errors"][0]["span"];
+    assert_eq!(span["line"], 6, "{resp}");
+    assert_eq!(span["column"], 18, "{resp}");
+
+    // Fixing only the imported file invalidates the cached result
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    std::fs::write(&util, "pub fn f() int {\n    return 1\n}\n").unwrap();
+    let resp = client.request("diagnostics", serde_json::json!({ "path": file }));
+    assert_eq!(resp["result"]["success"], true, "{resp}");
+}
+
+#[test]
+fn serve_definition_and_references() {
+    let temp = TempDir::new().unwrap();
+    let file = temp.path().join("main.pluto");
+    std::fs::write(
+        &file,
+        "fn add(a: int, b: int) int {\n    return a + b\n}\n\nfn main() {\n    print(add(1, 2))\n    print(add(3, 4))\n}\n",
+    )
+    .unwrap();
+
+    let mut client = Client::start();
+    let resp = client.request("analyze", serde_json::json!({ "path": file }));
+    assert_eq!(resp["result"]["name"], "main");
+
+    // Cursor on the first `add(` call in main (line 6, column 11)
+    let resp = client.request(
+        "definition",
+        serde_json::json!({ "path": file, "line": 6, "column": 11 }),
+    );
+    let def = &resp["result"];
+    assert_eq!(def["context"], "add");
+    assert_eq!(def["span"]["line"], 1);
+    assert_eq!(def["span"]["column"], 4);
+
+    // References from the declaration name find both calls
+    let resp = client.request(
+        "references",
+        serde_json::json!({ "path": file, "line": 1, "column": 5 }),
+    );
+    let refs = resp["result"].as_array().unwrap();
+    let lines: Vec<_> = refs.iter().map(|r| r["span"]["line"].as_u64().unwrap()).collect();
+    assert_eq!(lines, vec![6, 7]);
+
+    // Nothing resolvable under the cursor
+    let resp = client.request(
+        "definition",
+        serde_json::json!({ "path": file, "line": 4, "column": 1 }),
+    );
+    assert!(resp["result"].is_null());
+}
+
+#[test]
+fn serve_reports_rpc_errors() {
+    let mut client = Client::start();
+    let resp = client.request("frobnicate", serde_json::json!({ "path": "x.pluto" }));
+    assert_eq!(resp["error"]["code"], -32601);
+
+    let resp = client.request("analyze", serde_json::json!({}));
+    assert_eq!(resp["error"]["code"], -32602);
+
+    let resp = client.request("analyze", serde_json::json!({ "path": "/nonexistent/x.pluto" }));
+    assert_eq!(resp["error"]["code"], -32000);
+    assert!(resp["error"]["message"].as_str().unwrap().contains("Module not found"));
+}
+
+#[test]
+fn serve_second_compile_reuses_warm_runtime() {
+    let temp = TempDir::new().unwrap();
+    let file = temp.path().join("main.pluto");
+    std::fs::write(&file, "fn main() {\n    print(\"hello from the daemon\")\n}\n").unwrap();
+    let output = temp.path().join("main");
+
+    // Bypass the on-disk runtime cache so the first request has to build the
+    // runtime; the second must reuse the copy held by the server process.
+    let mut client = Client::start_with_env(&[("PLUTO_RUNTIME_NO_CACHE", "1")]);
+
+    let start = std::time::Instant::now();
+    let resp = client.request("compile", serde_json::json!({ "path": file, "output": output }));
+    let cold = start.elapsed();
+    assert_eq!(resp["result"]["success"], true, "{resp}");
+
+    let start = std::time::Instant::now();
+    let resp = client.request("compile", serde_json::json!({ "path": file, "output": output }));
+    let warm = start.elapsed();
+    assert_eq!(resp["result"]["success"], true, "{resp}");
+
+    assert!(warm < cold / 2, "warm compile ({warm:?}) was not faster than cold ({cold:?})");
+    let out = Command::new(&output).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "hello from the daemon\n");
+}
+
+#[test]
+fn serve_run_and_test() {
+    let temp = TempDir::new().unwrap();
+    let file = temp.path().join("main.pluto");
+    std::fs::write(
+        &file,
+        "fn double(x: int) int {\n    return x * 2\n}\n\nfn main() {\n    print(double(21))\n}\n",
+    )
+    .unwrap();
+    // Sibling files merge into one module, so the tests live in their own directory
+    std::fs::create_dir(temp.path().join("tests")).unwrap();
+    let tests = temp.path().join("tests").join("main.pluto");
+    std::fs::write(
+        &tests,
+        "fn double(x: int) int {\n    return x * 2\n}\n\ntest \"double\" {\n    expect(double(2)).to_equal(4)\n}\n",
+    )
+    .unwrap();
+
+    let mut client = Client::start();
+    let resp = client.request("run", serde_json::json!({ "path": file }));
+    assert_eq!(resp["result"]["success"], true, "{resp}");
+    assert_eq!(resp["result"]["stdout"], "42\n");
+
+    let resp = client.request("test", serde_json::json!({ "path": tests }));
+    assert_eq!(resp["result"]["success"], true, "{resp}");
+    assert!(resp["result"]["stdout"].as_str().unwrap().contains("double"));
+
+    std::fs::create_dir(temp.path().join("bad")).unwrap();
+    let bad = temp.path().join("bad").join("main.pluto");
+    std::fs::write(&bad, "fn main() {\n    print(nope)\n}\n").unwrap();
+    let resp = client.request("run", serde_json::json!({ "path": bad }));
+    assert_eq!(resp["result"]["success"], false, "{resp}");
+    assert_eq!(resp["result"]["compile_errors"][0]["span"]["line"], 2, "{resp}");
+}
+
+#[cfg(unix)]
+#[test]
+fn serve_over_unix_socket() {
+    use std::os::unix::net::UnixStream;
+
+    let temp = TempDir::new().unwrap();
+    let file = temp.path().join("bad.pluto");
+    std::fs::write(&file, "fn main() {\n    undefined_fn()\n}\n").unwrap();
+    let socket = temp.path().join("pluto.sock");
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pluto"))
+        .arg("serve")
+        .arg("--socket")
+        .arg(&socket)
+        .spawn()
+        .unwrap();
+
+    let mut stream = None;
+    for _ in 0..100 {
+        if let Ok(s) = UnixStream::connect(&socket) {
+            stream = Some(s);
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    let mut stream = stream.expect("server did not start listening");
+
+    let req = serde_json::json!({ "jsonrpc": "2.0", "id": 7, "method": "diagnostics", "params": { "path": file } });
+    writeln!(stream, "{req}").unwrap();
+    let mut line = String::new();
+    BufReader::new(stream.try_clone().unwrap()).read_line(&mut line).unwrap();
+    let _ = child.kill();
+    let _ = child.wait();
+
+    let resp: Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(resp["id"], 7);
+    assert_eq!(resp["result"]["success"], false);
+    assert!(resp["result"]["errors"][0]["message"].as_str().unwrap().contains("undefined_fn"));
+}
@@ -31,6 +31,10 @@ fn run_pluto_test_file(name: &str) -> (String, String, i32) {
 /// Compile and run a Pluto file in normal mode (not test mode).
 /// The binary runs with current_dir set to the project root.
 fn run_pluto_file(name: &str) -> (String, String, i32) {
+    run_pluto_file_with_env(name, &[])
+}
+
+fn run_pluto_file_with_env(name: &str, envs: &[(&str, &str)]) -> (String, String, i32) {
     let test_file = Path::new(env!("CARGO_MANIFEST_DIR"))
         .join("tests/stdlib")
         .join(name)
@@ -44,6 +48,7 @@ fn run_pluto_file(name: &str) -> (String, String, i32) {
 
     let output = Command::new(&bin_path)
         .current_dir(project_root)
+        .envs(envs.iter().copied())
         .output()
         .unwrap();
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
@@ -135,6 +140,54 @@ fn stdlib_path() {
     assert!(stdout.contains("tests passed"), "Expected test summary in output:\n{stdout}");
 }
 
+/// The `[LEVEL] message` part of each log line, after checking the timestamp
+/// in front of it looks like 2026-01-02T03:04:05.678Z.
+fn log_lines(stderr: &str) -> Vec<&str> {
+    stderr
+        .lines()
+        .map(|line| {
+            let (when, rest) = line.split_once(' ').unwrap_or_else(|| panic!("bad log line: {line}"));
+            let b = when.as_bytes();
+            assert!(
+                when.len() == 24 && b[4] == b'-' && b[10] == b'T' && b[19] == b'.' && b[23] == b'Z',
+                "bad timestamp in: {line}"
+            );
+            rest
+        })
+        .collect()
+}
+
+#[test]
+fn stdlib_log_default_level() {
+    let (stdout, stderr, code) = run_pluto_file_with_env("log", &[]);
+    assert_eq!(code, 0, "stderr: {stderr}");
+    assert_eq!(stdout, "done\n");
+    assert_eq!(
+        log_lines(&stderr),
+        ["[INFO] listening on 8080", "[WARN] disk space low", "[ERROR] connection failed"]
+    );
+}
+
+#[test]
+fn stdlib_log_level_from_env() {
+    let (_, stderr, code) = run_pluto_file_with_env("log", &[("PLUTO_LOG", "debug")]);
+    assert_eq!(code, 0, "stderr: {stderr}");
+    assert_eq!(log_lines(&stderr).len(), 4);
+    assert_eq!(log_lines(&stderr)[0], "[DEBUG] cache warmed");
+
+    let (_, stderr, code) = run_pluto_file_with_env("log", &[("PLUTO_LOG", "ERROR")]);
+    assert_eq!(code, 0, "stderr: {stderr}");
+    assert_eq!(log_lines(&stderr), ["[ERROR] connection failed"]);
+}
+
+#[test]
+fn stdlib_log_rejects_unknown_level() {
+    let (stdout, stderr, code) = run_pluto_file_with_env("log", &[("PLUTO_LOG", "verbose")]);
+    assert_eq!(code, 1);
+    assert_eq!(stdout, "");
+    assert!(stderr.contains("PLUTO_LOG must be debug, info, warn or error"), "stderr: {stderr}");
+}
+
 // TODO: Full regex escape sequence support (\d, \w, \s) - Phase 2 work
 // #[test]
 // fn stdlib_regex() {
@@ -168,6 +168,58 @@ fn string_interp_class_rejected() {
     );
 }
 
+/// Compile `source`, expecting typeck to reject an interpolation. Returns the
+/// error message and the source text its span covers.
+fn interp_type_error(source: &str) -> (String, String) {
+    match pluto::compile_to_object(source) {
+        Err(pluto::diagnostics::CompileError::Type { msg, span }) => {
+            (msg, source[span.start..span.end].to_string())
+        }
+        Err(other) => panic!("expected a type error, got: {other}"),
+        Ok(_) => panic!("expected a type error, but compilation succeeded"),
+    }
+}
+
+#[test]
+fn string_interp_error_points_at_expression() {
+    let (msg, text) = interp_type_error(
+        "fn main() {\n    let xs = [1, 2]\n    print(f\"xs = {xs} done\")\n}",
+    );
+    assert_eq!(msg, "cannot interpolate [int] into string");
+    assert_eq!(text, "xs");
+}
+
+#[test]
+fn string_interp_error_spans_whole_expression() {
+    let (msg, text) = interp_type_error(
+        "fn pair() Map<string, int> {\n    return Map<string, int> {}\n}\n\nfn main() {\n    let s = f\"a {1 + 2} b {pair()}\"\n}",
+    );
+    assert_eq!(msg, "cannot interpolate Map<string, int> into string");
+    assert_eq!(text, "pair()");
+}
+
+#[test]
+fn string_interp_error_span_skips_escapes() {
+    let (msg, text) = interp_type_error(
+        "fn main() {\n    let xs = [1]\n    print(f\"\\t\\u{41}\\\"{{ {xs}\")\n}",
+    );
+    assert_eq!(msg, "cannot interpolate [int] into string");
+    assert_eq!(text, "xs");
+}
+
+#[test]
+fn string_interp_error_in_generic_body_is_type_error() {
+    match pluto::compile_to_object(
+        "class Foo {\n    x: int\n}\n\nfn show<T>(v: T) string {\n    return f\"<{v}>\"\n}\n\nfn main() {\n    print(show(Foo { x: 1 }))\n}",
+    ) {
+        Err(pluto::diagnostics::CompileError::Type { msg, .. }) => {
+            assert_eq!(msg, "cannot interpolate Foo into string");
+        }
+        Err(other) => panic!("expected a type error, got: {other}"),
+        Ok(_) => panic!("expected a type error, but compilation succeeded"),
+    }
+}
+
 #[test]
 fn string_interp_trailing_tokens_rejected() {
     compile_should_fail_with(
@@ -290,6 +342,30 @@ fn string_split_empty_delim() {
     assert_eq!(out, "3\na\nb\nc\n");
 }
 
+#[test]
+fn string_chars() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let cs = \"h\u{e9}y\".chars()\n    print(cs.len())\n    for c in cs {\n        print(c)\n    }\n    print(\"\".chars().len())\n}",
+    );
+    assert_eq!(out, "3\nh\n\u{e9}\ny\n0\n");
+}
+
+#[test]
+fn string_lines_lf_and_crlf() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let ls = \"a\\r\\nb\\n\\nc\\n\".lines()\n    print(ls.len())\n    for l in ls {\n        print(f\"[{l}]\")\n    }\n    print(\"\".lines().len())\n}",
+    );
+    assert_eq!(out, "4\n[a]\n[b]\n[]\n[c]\n0\n");
+}
+
+#[test]
+fn string_bytes() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let bs = \"AB\".bytes()\n    print(bs.len())\n    let b: byte = bs[1]\n    print(b as int)\n    print(\"\".bytes().len())\n}",
+    );
+    assert_eq!(out, "2\n66\n0\n");
+}
+
 #[test]
 fn string_char_at() {
     let out = compile_and_run_stdout(
@@ -298,6 +374,30 @@ fn string_char_at() {
     assert_eq!(out, "h\no\n");
 }
 
+#[test]
+fn string_char_at_and_char_count_are_utf8_aware() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let s = \"hi \u{1F44B}!\"\n    print(s.len())\n    print(s.char_count())\n    print(s.char_at(3))\n    print(s.char_at(4))\n    print(s.char_at(3).len())\n}",
+    );
+    assert_eq!(out, "8\n5\n\u{1F44B}\n!\n4\n");
+}
+
+#[test]
+fn triple_quoted_string_dedents_to_closing_quotes() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let s = \"\"\"\n        one\n          two \"quoted\"\n        three\n        \"\"\"\n    print(s.len())\n    print(s)\n}",
+    );
+    assert_eq!(out, "24\none\n  two \"quoted\"\nthree\n");
+}
+
+#[test]
+fn triple_quoted_template_has_two_newlines() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let name = \"pluto\"\n    let s = f\"\"\"\n        Dear {name},\n        total: {1 + 2}\n        bye\n        \"\"\"\n    print(s.split(\"\\n\").len() - 1)\n    print(s)\n}",
+    );
+    assert_eq!(out, "2\nDear pluto,\ntotal: 3\nbye\n");
+}
+
 // ── String indexing ──
 
 #[test]
@@ -324,6 +424,14 @@ fn string_index_let_binding() {
     assert_eq!(out, "e\n");
 }
 
+#[test]
+fn string_range_slice() {
+    let out = compile_and_run_stdout(
+        "fn main() {\n    let s = \"hello world\"\n    print(s[2..5])\n    print(s[0..=4])\n    print(s[6..s.len()])\n    print(s[3..3].len())\n}",
+    );
+    assert_eq!(out, "llo\nhello\nworld\n0\n");
+}
+
 // ── String iteration ──
 
 #[test]
@@ -421,6 +529,24 @@ fn string_char_at_oob_aborts() {
     assert_ne!(code, 0, "OOB char_at should abort");
 }
 
+#[test]
+fn string_char_at_counts_chars_for_bounds() {
+    let (_, stderr, code) = compile_and_run_output(
+        "fn main() {\n    print(\"\u{e9}t\u{e9}\".char_at(3))\n}",
+    );
+    assert_ne!(code, 0);
+    assert!(stderr.contains("index 3, char count 3"), "stderr: {stderr}");
+}
+
+#[test]
+fn string_range_slice_oob_aborts() {
+    let (_, stderr, code) = compile_and_run_output(
+        "fn main() {\n    let s = \"abc\"\n    print(s[1..5])\n}",
+    );
+    assert_ne!(code, 0, "OOB slice should abort");
+    assert!(stderr.contains("string slice out of bounds: range 1..5, length 3"), "stderr: {stderr}");
+}
+
 // ── Compile error tests ──
 
 #[test]
@@ -262,6 +262,37 @@ fn main() {
     assert_eq!(synced_enum.variants.iter().find(|v| v.name.node == "Blue").unwrap().id, orig_blue_id);
 }
 
+#[test]
+fn class_member_changes_are_reported() {
+    let source = "class Foo {\n    x: int\n    y: string\n\n    fn get_x(self) int {\n        return self.x\n    }\n\n    fn old(self) {\n    }\n}\n";
+    let (dir, original) = emit_ast(source);
+    let pluto_path = dir.path().join("test.pluto");
+    let orig_class = &original.classes[0].node;
+    let orig_x_id = orig_class.fields[0].id;
+    let orig_y_id = orig_class.fields[1].id;
+    let orig_get_x_id = orig_class.methods[0].node.id;
+
+    // Rename y, add a field and a method, drop `old`
+    let edited = "class Foo {\n    x: int\n    label: string\n    z: float\n\n    fn get_x(self) int {\n        return self.x\n    }\n\n    fn reset(self) {\n    }\n}\n";
+    let pt_dir = tempfile::tempdir().unwrap();
+    let pt_path = pt_dir.path().join("test.pt");
+    std::fs::write(&pt_path, edited).unwrap();
+    let result = sync_pt_to_pluto(&pt_path, &pluto_path).unwrap();
+
+    assert_eq!(result.added, vec!["field Foo.z", "fn Foo.reset"]);
+    assert_eq!(result.removed, vec!["fn Foo.old"]);
+    assert_eq!(result.modified, vec!["field Foo.label"]);
+    assert_eq!(result.unchanged, 1);
+
+    let data = std::fs::read(&pluto_path).unwrap();
+    let (synced, _, _) = deserialize_program(&data).unwrap();
+    let synced_class = &synced.classes[0].node;
+    assert_eq!(synced_class.fields[0].id, orig_x_id);
+    assert_eq!(synced_class.fields[1].id, orig_y_id);
+    assert_ne!(synced_class.fields[2].id, orig_y_id);
+    assert_eq!(synced_class.methods[0].node.id, orig_get_x_id);
+}
+
 #[test]
 fn no_pluto_file_creates_fresh() {
     let dir = tempfile::tempdir().unwrap();
@@ -460,3 +491,18 @@ fn empty_derived_data() {
     assert!(derived.fn_error_sets.is_empty());
     assert!(derived.fn_signatures.is_empty());
 }
+
+#[test]
+fn comments_survive_round_trip() {
+    let source = "// greeting\nfn hello() {\n    print(1) // one\n    // done\n}\n";
+    let dir = tempfile::tempdir().unwrap();
+    let pluto_path = dir.path().join("test.pluto");
+    let pt_path = dir.path().join("test.pt");
+    std::fs::write(&pt_path, source).unwrap();
+
+    sync_pt_to_pluto(&pt_path, &pluto_path).unwrap();
+
+    let data = std::fs::read(&pluto_path).unwrap();
+    let (program, _source, _derived) = deserialize_program(&data).unwrap();
+    assert_eq!(pretty_print(&program, false), source);
+}
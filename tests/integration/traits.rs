@@ -15186,3 +15186,105 @@ fn main() {
 }
 "#, "expected trait Worker");
 }
+
+// ===== Trait objects as fields and return types =====
+
+#[test]
+fn trait_field_dispatches_after_read_back() {
+    let out = compile_and_run_stdout(r#"
+trait Printable {
+    fn display(self) string
+}
+class Cat impl Printable {
+    name: string
+    fn display(self) string { return "cat " + self.name }
+}
+class Widget {
+    renderer: Printable
+}
+fn main() {
+    let w = Widget { renderer: Cat { name: "felix" } }
+    let r = w.renderer
+    print(r.display())
+    print(w.renderer.display())
+}
+"#);
+    assert_eq!(out, "cat felix\ncat felix\n");
+}
+
+#[test]
+fn trait_field_reassigned_with_other_class() {
+    let out = compile_and_run_stdout(r#"
+trait Printable {
+    fn display(self) string
+}
+class Cat impl Printable {
+    fn display(self) string { return "cat" }
+}
+class Dog impl Printable {
+    fn display(self) string { return "dog" }
+}
+class Widget {
+    renderer: Printable
+}
+fn main() {
+    let mut w = Widget { renderer: Cat {} }
+    print(w.renderer.display())
+    w.renderer = Dog {}
+    print(w.renderer.display())
+}
+"#);
+    assert_eq!(out, "cat\ndog\n");
+}
+
+#[test]
+fn trait_field_reassign_rejects_non_impl_class() {
+    compile_should_fail_with(r#"
+trait Printable {
+    fn display(self) string
+}
+class Cat impl Printable {
+    fn display(self) string { return "cat" }
+}
+class Rock {
+    weight: int
+}
+class Widget {
+    renderer: Printable
+}
+fn main() {
+    let mut w = Widget { renderer: Cat {} }
+    w.renderer = Rock { weight: 1 }
+}
+"#, "field 'renderer': expected trait Printable, found Rock");
+}
+
+#[test]
+fn function_returns_trait_object_from_different_classes() {
+    let out = compile_and_run_stdout(r#"
+trait Printable {
+    fn display(self) string
+}
+class Cat impl Printable {
+    fn display(self) string { return "cat" }
+}
+class Dog impl Printable {
+    fn display(self) string { return "dog" }
+}
+class Widget {
+    renderer: Printable
+}
+fn pick(cat: bool) Printable {
+    if cat {
+        return Cat {}
+    }
+    return Dog {}
+}
+fn main() {
+    print(pick(true).display())
+    let w = Widget { renderer: pick(false) }
+    print(w.renderer.display())
+}
+"#);
+    assert_eq!(out, "cat\ndog\n");
+}
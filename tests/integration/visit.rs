@@ -148,9 +148,11 @@ fn create_simple_program() -> Program {
             },
             dummy_span(),
         ),
-        is_pub: false,
+        visibility: Visibility::Private,
         is_override: false,
         is_generator: false,
+        declared_errors: None,
+        no_coverage: false,
     };
 
     Program {
@@ -160,6 +162,8 @@ fn create_simple_program() -> Program {
         classes: vec![],
         traits: vec![],
         enums: vec![],
+        consts: vec![],
+        globals: vec![],
         errors: vec![],
         app: None,
         stages: vec![],
@@ -167,6 +171,7 @@ fn create_simple_program() -> Program {
         test_info: vec![],
         tests: None,
         fallible_extern_fns: vec![],
+        comments: vec![],
     }
 }
 
@@ -191,9 +196,11 @@ fn create_nested_program() -> Program {
             },
             dummy_span(),
         ),
-        is_pub: false,
+        visibility: Visibility::Private,
         is_override: false,
         is_generator: false,
+        declared_errors: None,
+        no_coverage: false,
     };
 
     Program {
@@ -203,6 +210,8 @@ fn create_nested_program() -> Program {
         classes: vec![],
         traits: vec![],
         enums: vec![],
+        consts: vec![],
+        globals: vec![],
         errors: vec![],
         app: None,
         stages: vec![],
@@ -210,6 +219,7 @@ fn create_nested_program() -> Program {
         test_info: vec![],
         tests: None,
         fallible_extern_fns: vec![],
+        comments: vec![],
     }
 }
 
@@ -241,9 +251,11 @@ fn create_program_with_types() -> Program {
             },
             dummy_span(),
         ),
-        is_pub: false,
+        visibility: Visibility::Private,
         is_override: false,
         is_generator: false,
+        declared_errors: None,
+        no_coverage: false,
     };
 
     Program {
@@ -253,6 +265,8 @@ fn create_program_with_types() -> Program {
         classes: vec![],
         traits: vec![],
         enums: vec![],
+        consts: vec![],
+        globals: vec![],
         errors: vec![],
         app: None,
         stages: vec![],
@@ -260,6 +274,7 @@ fn create_program_with_types() -> Program {
         test_info: vec![],
         tests: None,
         fallible_extern_fns: vec![],
+        comments: vec![],
     }
 }
 
@@ -110,3 +110,128 @@ fn method_param_not_warned() {
     // 'x' is a method param, 'f' is used — no warnings
     assert!(warnings.is_empty(), "expected no warnings, got: {:?}", warnings);
 }
+
+#[test]
+fn discarded_fallible_result_warns() {
+    let warnings = compile_and_get_warnings(
+        "error Bad {}\n\nfn parse(s: string) int {\n    if s == \"\" {\n        raise Bad {}\n    }\n    return 1\n}\n\nfn run() {\n    parse(\"a\")!\n}\n\nfn main() {\n    parse(\"b\") catch 0\n    run() catch Bad {\n        print(0)\n    }\n}",
+    );
+    assert_eq!(warnings.len(), 1, "got: {:?}", warnings);
+    assert!(warnings[0].contains("unused result of 'parse'"));
+}
+
+#[test]
+fn discarded_fallible_method_result_warns() {
+    let warnings = compile_and_get_warnings(
+        "error Bad {}\n\nclass Store {\n    fn get(self) int {\n        raise Bad {}\n    }\n}\n\nfn run() {\n    let s = Store {}\n    s.get()!\n}\n\nfn main() {\n    run() catch Bad {\n        print(0)\n    }\n}",
+    );
+    assert_eq!(warnings.len(), 1, "got: {:?}", warnings);
+    assert!(warnings[0].contains("unused result of 'get'"));
+}
+
+#[test]
+fn discarded_map_err_result_warns() {
+    let warnings = compile_and_get_warnings(
+        "error Bad {}\n\nerror Wrapped {}\n\nfn parse(s: string) int {\n    if s == \"\" {\n        raise Bad {}\n    }\n    return 1\n}\n\nfn run() {\n    parse(\"a\")! map_err((e: error) => Wrapped {})\n}\n\nfn main() {\n    run() catch Wrapped {\n        print(0)\n    }\n}",
+    );
+    assert_eq!(warnings.len(), 1, "got: {:?}", warnings);
+    assert!(warnings[0].contains("unused result of 'parse'"));
+}
+
+#[test]
+fn fallible_void_call_and_infallible_result_not_warned() {
+    let warnings = compile_and_get_warnings(
+        "error Bad {}\n\nfn save(s: string) {\n    if s == \"\" {\n        raise Bad {}\n    }\n}\n\nfn pure() int {\n    return 1\n}\n\nfn main() {\n    save(\"a\") catch Bad {\n        print(0)\n    }\n    pure()\n}",
+    );
+    assert!(warnings.is_empty(), "expected no warnings, got: {:?}", warnings);
+}
+
+#[test]
+fn caught_fallible_result_not_warned() {
+    let warnings = compile_and_get_warnings(
+        "error Bad {}\n\nclass Store {\n    fn get(self) int {\n        raise Bad {}\n    }\n}\n\nfn parse(s: string) int {\n    if s == \"\" {\n        raise Bad {}\n    }\n    return 1\n}\n\nfn main() {\n    parse(\"a\") catch 0\n    let s = Store {}\n    s.get() catch 0\n}",
+    );
+    assert!(warnings.is_empty(), "expected no warnings, got: {:?}", warnings);
+}
+
+#[test]
+fn underscore_binding_silences_unused_result() {
+    let warnings = compile_and_get_warnings(
+        "error Bad {}\n\nfn parse(s: string) int {\n    if s == \"\" {\n        raise Bad {}\n    }\n    return 1\n}\n\nfn main() {\n    let _discarded = parse(\"a\") catch 0\n}",
+    );
+    assert!(warnings.is_empty(), "expected no warnings, got: {:?}", warnings);
+}
+
+#[test]
+fn let_underscore_discard_not_warned() {
+    let warnings = compile_and_get_warnings(
+        "error Bad {}\n\nfn parse(s: string) int {\n    if s == \"\" {\n        raise Bad {}\n    }\n    return 1\n}\n\nfn main() {\n    let _ = parse(\"a\") catch 0\n    let _ = 42\n}",
+    );
+    assert!(warnings.is_empty(), "expected no warnings, got: {:?}", warnings);
+}
+
+#[test]
+fn statement_after_return_is_unreachable() {
+    let warnings = compile_and_get_warnings(
+        "fn f() int {\n    return 1\n    print(2)\n    print(3)\n}\n\nfn main() {\n    print(f())\n}",
+    );
+    // One warning per block, at the first dead statement
+    assert_eq!(warnings, vec!["unreachable code".to_string()]);
+}
+
+#[test]
+fn statement_after_raise_is_unreachable() {
+    let result = pluto::compile_to_object_with_warnings(
+        "error Bad {}\n\nfn f() int {\n    raise Bad {}\n    return 0\n}\n\nfn main() {\n    print(f() catch 1)\n}",
+    );
+    let (_, warnings) = result.unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].msg, "unreachable code");
+    assert!(matches!(warnings[0].kind, pluto::diagnostics::WarningKind::UnreachableCode));
+}
+
+#[test]
+fn unreachable_inside_loop_and_if_arms() {
+    let warnings = compile_and_get_warnings(
+        "fn main() {\n    let items = [1, 2, 3]\n    for i in items {\n        if i == 2 {\n            break\n            print(i)\n        } else {\n            continue\n            print(0)\n        }\n    }\n}",
+    );
+    assert_eq!(warnings.len(), 2, "got: {:?}", warnings);
+    assert!(warnings.iter().all(|w| w == "unreachable code"));
+}
+
+#[test]
+fn statement_after_exhaustive_terminating_branches_is_unreachable() {
+    let warnings = compile_and_get_warnings(
+        "enum Color {\n    Red\n    Blue\n}\n\nfn pick(x: int) int {\n    if x > 0 {\n        return 1\n    } else {\n        return 2\n    }\n    print(x)\n}\n\nfn name(c: Color) string {\n    match c {\n        Color.Red {\n            return \"red\"\n        }\n        Color.Blue {\n            return \"blue\"\n        }\n    }\n    print(0)\n}\n\nfn main() {\n    print(pick(1))\n    print(name(Color.Red))\n}",
+    );
+    assert_eq!(warnings.len(), 2, "got: {:?}", warnings);
+    assert!(warnings.iter().all(|w| w == "unreachable code"));
+}
+
+#[test]
+fn code_after_partial_return_is_reachable() {
+    let warnings = compile_and_get_warnings(
+        "fn f(x: int) int {\n    if x > 0 {\n        return 1\n    }\n    while x < 0 {\n        return 2\n    }\n    return 3\n}\n\nfn main() {\n    print(f(1))\n}",
+    );
+    assert!(warnings.is_empty(), "expected no warnings, got: {:?}", warnings);
+}
+
+#[test]
+fn spawn_capturing_scoped_self_warns() {
+    let warnings = compile_and_get_warnings(
+        "scoped class Job {\n    n: int\n\n    fn work(self) int {\n        return self.n * 2\n    }\n\n    fn start(self) int {\n        let t = spawn self.work()\n        return t.get()\n    }\n}\n\nfn main() {\n    let j = Job { n: 21 }\n    print(j.start())\n}",
+    );
+    assert_eq!(warnings.len(), 1, "got: {:?}", warnings);
+    assert_eq!(
+        warnings[0],
+        "spawned task captures 'self' of scoped class 'Job'; the task may outlive the instance's scope"
+    );
+}
+
+#[test]
+fn spawn_capturing_singleton_self_no_warning() {
+    let warnings = compile_and_get_warnings(
+        "class Counter {\n    n: int\n\n    fn work(self) int {\n        return self.n + 1\n    }\n\n    fn start(self) int {\n        let t = spawn self.work()\n        return t.get()\n    }\n}\n\nfn main() {\n    let c = Counter { n: 1 }\n    print(c.start())\n}",
+    );
+    assert!(warnings.is_empty(), "expected no warnings, got: {:?}", warnings);
+}
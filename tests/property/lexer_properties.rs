@@ -291,7 +291,7 @@ fn valid_identifiers() -> impl Strategy<Value = String> {
 fn is_pluto_keyword(s: &str) -> bool {
     matches!(s,
         "fn" | "let" | "mut" | "return" | "if" | "else" | "while" | "true" | "false" |
-        "class" | "trait" | "app" | "error" | "raise" | "catch" | "spawn" | "enum" |
+        "class" | "trait" | "app" | "error" | "raise" | "catch" | "try" | "finally" | "is" | "spawn" | "enum" |
         "impl" | "self" | "pub" | "for" | "in" | "break" | "continue" | "match" |
         "import" | "as" | "extern" | "uses" | "ambient" | "test" | "invariant" |
         "requires" | "assert" | "select" | "default" | "scope" | "scoped" |
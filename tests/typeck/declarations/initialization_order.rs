@@ -17,9 +17,9 @@ fn field_init_order() { compile_should_fail_with(r#"class C{x:int y:int} fn main
 #[test]
 fn static_init_order() { compile_should_fail_with(r#"static x:int=y static y:int=1 fn main(){}"#, "Syntax error: expected 'fn'"); }
 
-// Global const init order - Pluto doesn't have const keyword
+// Global const init order - a const may only use consts declared before it
 #[test]
-fn const_init_order() { compile_should_fail_with(r#"const X:int=Y const Y:int=1 fn main(){}"#, "Syntax error: expected 'fn'"); }
+fn const_init_order() { compile_should_fail_with("const X:int=Y\nconst Y:int=1\nfn main(){}", "initializer of const 'X' is not a compile-time constant"); }
 
 // DI init order violation - correctly detected
 #[test]
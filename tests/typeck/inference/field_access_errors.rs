@@ -235,7 +235,7 @@ fn field_access_on_unit_enum_variant() {
             let v = c.value
         }
         "#,
-        "field access on non-class type",
+        "cannot access field 'value' of enum 'Color' without checking its variant",
     );
 }
 
@@ -253,7 +253,7 @@ fn unknown_field_on_enum_data_variant() {
             let v = s.diameter
         }
         "#,
-        "field access on non-class type",
+        "cannot access field 'diameter' of enum 'Shape' without checking its variant",
     );
 }
 